@@ -8,6 +8,7 @@ use crate::avm1::scope::Scope;
 use crate::avm1::value::Value;
 use crate::avm1::{ArrayObject, Object, ObjectPtr, ScriptObject, TObject};
 use crate::display_object::{DisplayObject, TDisplayObject};
+use crate::profiler::{FunctionKey, ScriptVm};
 use crate::string::AvmString;
 use crate::tag_utils::SwfSlice;
 use gc_arena::{Collect, Gc, GcCell, MutationContext};
@@ -314,11 +315,21 @@ impl fmt::Debug for Executable<'_> {
 }
 
 /// Indicates the default name to use for this execution in debug builds.
+#[derive(Clone, Copy)]
 pub enum ExecutionName<'gc> {
     Static(&'static str),
     Dynamic(AvmString<'gc>),
 }
 
+impl fmt::Display for ExecutionName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExecutionName::Static(n) => f.write_str(n),
+            ExecutionName::Dynamic(n) => write!(f, "{}", n),
+        }
+    }
+}
+
 impl<'gc> Executable<'gc> {
     /// Execute the given code.
     ///
@@ -341,7 +352,16 @@ impl<'gc> Executable<'gc> {
             Executable::Native(nf) => {
                 // TODO: Change NativeFunction to accept `this: Value`.
                 let this = this.coerce_to_object(activation);
-                return nf(activation, this, args);
+                if let Some(profiler) = activation.context.profiler.as_mut() {
+                    profiler.enter_keyed(ScriptVm::Avm1, FunctionKey::Native(*nf as usize), || {
+                        name.to_string()
+                    });
+                }
+                let result = nf(activation, this, args);
+                if let Some(profiler) = activation.context.profiler.as_mut() {
+                    profiler.exit();
+                }
+                return result;
             }
             Executable::Action(af) => af,
         };
@@ -398,6 +418,7 @@ impl<'gc> Executable<'gc> {
         // The caller is the previous callee.
         let arguments_caller = activation.callee;
 
+        let profile_name = af.name.map(ExecutionName::Dynamic).unwrap_or(name);
         let name = if cfg!(feature = "avm_debug") {
             Cow::Owned(af.debug_string_for_call(name, args))
         } else {
@@ -451,7 +472,18 @@ impl<'gc> Executable<'gc> {
             }
         }
 
-        Ok(frame.run_actions(af.data.clone())?.value())
+        if let Some(profiler) = frame.context.profiler.as_mut() {
+            profiler.enter_keyed(
+                ScriptVm::Avm1,
+                FunctionKey::Actions(af.data.clone()),
+                || profile_name.to_string(),
+            );
+        }
+        let result = frame.run_actions(af.data.clone());
+        if let Some(profiler) = frame.context.profiler.as_mut() {
+            profiler.exit();
+        }
+        Ok(result?.value())
     }
 }
 
@@ -2,6 +2,7 @@
 
 use super::matrix::object_to_matrix;
 use crate::avm1::function::{Executable, FunctionObject};
+use crate::avm1::globals::bitmap_filter::avm1_to_filter;
 use crate::avm1::globals::color_transform::ColorTransformObject;
 use crate::avm1::object::bitmap_data::BitmapDataObject;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
@@ -14,6 +15,7 @@ use crate::character::Character;
 use crate::display_object::TDisplayObject;
 use crate::swf::BlendMode;
 use gc_arena::{GcCell, MutationContext};
+use ruffle_render::filters::Filter;
 use ruffle_render::transform::Transform;
 
 const PROTO_DECLS: &[Declaration] = declare_properties! {
@@ -554,24 +556,125 @@ pub fn draw<'gc>(
     Ok((-1).into())
 }
 
+fn object_to_rectangle<'gc>(
+    rectangle: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<(i32, i32, i32, i32), Error<'gc>> {
+    let x = rectangle.get("x", activation)?.coerce_to_f64(activation)? as i32;
+    let y = rectangle.get("y", activation)?.coerce_to_f64(activation)? as i32;
+    let width = rectangle
+        .get("width", activation)?
+        .coerce_to_f64(activation)? as i32;
+    let height = rectangle
+        .get("height", activation)?
+        .coerce_to_f64(activation)? as i32;
+    Ok((x, y, width, height))
+}
+
 pub fn apply_filter<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("BitmapData.applyFilter - not yet implemented");
+    if let Some(bitmap_data) = this.as_bitmap_data_object() {
+        if !bitmap_data.disposed() {
+            let source_bitmap = args
+                .get(0)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+            let source_rect = args
+                .get(1)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+            let source_rect = object_to_rectangle(source_rect, activation)?;
+            let dest_point = args
+                .get(2)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+            let dest_x = dest_point.get("x", activation)?.coerce_to_f64(activation)? as i32;
+            let dest_y = dest_point.get("y", activation)?.coerce_to_f64(activation)? as i32;
+            let filter_object = args
+                .get(3)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+            let filter = match avm1_to_filter(filter_object, activation)? {
+                Some(filter) => filter,
+                None => return Ok((-1).into()),
+            };
+
+            let map_bitmap = if let Filter::DisplacementMapFilter(_) = filter {
+                match filter_object.get("mapBitmap", activation)? {
+                    Value::Object(map) => map.as_bitmap_data_object(),
+                    _ => None,
+                }
+            } else {
+                None
+            };
+
+            if let Some(src_bitmap) = source_bitmap.as_bitmap_data_object() {
+                if !src_bitmap.disposed() {
+                    // dealing with object aliasing...
+                    let src_bitmap_clone: BitmapData; // only initialized if source is the same object as self
+                    let src_bitmap_data_cell = src_bitmap.bitmap_data();
+                    let src_bitmap_gc_ref; // only initialized if source is a different object than self
+                    let source_bitmap_ref = // holds the reference to either of the ones above
+                        if GcCell::ptr_eq(src_bitmap.bitmap_data(), bitmap_data.bitmap_data()) {
+                            src_bitmap_clone = src_bitmap_data_cell.read().clone();
+                            &src_bitmap_clone
+                        } else {
+                            src_bitmap_gc_ref = src_bitmap_data_cell.read();
+                            &src_bitmap_gc_ref
+                        };
+                    // The map may also be the same object as self, so it is copied.
+                    let map = map_bitmap.map(|map| map.bitmap_data().read().clone());
+
+                    bitmap_data
+                        .bitmap_data()
+                        .write(activation.context.gc_context)
+                        .apply_filter(
+                            source_bitmap_ref,
+                            source_rect,
+                            (dest_x, dest_y),
+                            &filter,
+                            map.as_ref(),
+                        );
+                    return Ok(0.into());
+                }
+            }
+        }
+    }
+
     Ok((-1).into())
 }
 
 pub fn generate_filter_rect<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(bitmap_data) = this.as_bitmap_data_object() {
         if !bitmap_data.disposed() {
-            log::warn!("BitmapData.generateFilterRect - not yet implemented");
-            return Ok(Value::Undefined);
+            let source_rect = args
+                .get(0)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+            let source_rect = object_to_rectangle(source_rect, activation)?;
+            let filter = args
+                .get(1)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+            let filter = match avm1_to_filter(filter, activation)? {
+                Some(filter) => filter,
+                None => return Ok((-1).into()),
+            };
+
+            let (x, y, width, height) = BitmapData::generate_filter_rect(source_rect, &filter);
+            let proto = activation.context.avm1.prototypes().rectangle_constructor;
+            let rect = proto.construct(
+                activation,
+                &[x.into(), y.into(), width.into(), height.into()],
+            )?;
+            return Ok(rect);
         }
     }
 
@@ -4,8 +4,14 @@ use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::object::NativeObject;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
-use crate::avm1::{Attribute, Object, ScriptObject, TObject, Value};
+use crate::avm1::{ArrayObject, Attribute, Object, ScriptObject, TObject, Value};
 use gc_arena::{GcCell, MutationContext};
+use ruffle_render::filters::{
+    BevelFilter, BevelFilterType, BlurFilter, ColorMatrixFilter, ConvolutionFilter,
+    DisplacementMapFilter, DisplacementMapFilterMode, DropShadowFilter, Filter, GlowFilter,
+    GradientFilter,
+};
+use swf::Color;
 
 const PROTO_DECLS: &[Declaration] = declare_properties! {
     "clone" => method(clone);
@@ -254,6 +260,406 @@ pub fn clone<'gc>(
     Ok(Value::Undefined)
 }
 
+fn get_f32<'gc>(
+    object: Object<'gc>,
+    name: &'static str,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<f32, Error<'gc>> {
+    Ok(object.get(name, activation)?.coerce_to_f64(activation)? as f32)
+}
+
+fn get_bool<'gc>(
+    object: Object<'gc>,
+    name: &'static str,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<bool, Error<'gc>> {
+    Ok(object
+        .get(name, activation)?
+        .as_bool(activation.swf_version()))
+}
+
+fn get_quality<'gc>(
+    object: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<u8, Error<'gc>> {
+    Ok(object
+        .get("quality", activation)?
+        .coerce_to_i32(activation)?
+        .clamp(0, 15) as u8)
+}
+
+fn get_color<'gc>(
+    object: Object<'gc>,
+    color_name: &'static str,
+    alpha_name: &'static str,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Color, Error<'gc>> {
+    let rgb = object
+        .get(color_name, activation)?
+        .coerce_to_u32(activation)?;
+    let alpha = object
+        .get(alpha_name, activation)?
+        .coerce_to_f64(activation)?;
+    Ok(Color::from_rgb(rgb, (alpha.clamp(0.0, 1.0) * 255.0) as u8))
+}
+
+fn get_bevel_type<'gc>(
+    object: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<BevelFilterType, Error<'gc>> {
+    let bevel_type = object
+        .get("type", activation)?
+        .coerce_to_string(activation)?;
+    Ok(if &bevel_type == b"outer" {
+        BevelFilterType::Outer
+    } else if &bevel_type == b"full" {
+        BevelFilterType::Full
+    } else {
+        BevelFilterType::Inner
+    })
+}
+
+fn get_array<'gc>(
+    object: Object<'gc>,
+    name: &'static str,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Vec<Value<'gc>>, Error<'gc>> {
+    match object.get(name, activation)? {
+        Value::Object(array) => {
+            let length = array.length(activation)?;
+            Ok((0..length)
+                .map(|i| array.get_element(activation, i))
+                .collect())
+        }
+        _ => Ok(Vec::new()),
+    }
+}
+
+fn get_gradient<'gc>(
+    object: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<GradientFilter, Error<'gc>> {
+    let colors = get_array(object, "colors", activation)?;
+    let alphas = get_array(object, "alphas", activation)?;
+    let ratios = get_array(object, "ratios", activation)?;
+    let mut records = Vec::with_capacity(colors.len());
+    for ((color, alpha), ratio) in colors.iter().zip(alphas.iter()).zip(ratios.iter()) {
+        let color = color.coerce_to_u32(activation)?;
+        let alpha = alpha.coerce_to_f64(activation)?;
+        let ratio = ratio.coerce_to_i32(activation)?;
+        records.push((
+            ratio.clamp(0, 255) as u8,
+            Color::from_rgb(color, (alpha.clamp(0.0, 1.0) * 255.0) as u8),
+        ));
+    }
+    Ok(GradientFilter {
+        colors: records,
+        blur_x: get_f32(object, "blurX", activation)?,
+        blur_y: get_f32(object, "blurY", activation)?,
+        angle: get_f32(object, "angle", activation)?.to_radians(),
+        distance: get_f32(object, "distance", activation)?,
+        strength: get_f32(object, "strength", activation)?,
+        quality: get_quality(object, activation)?,
+        bevel_type: get_bevel_type(object, activation)?,
+        knockout: get_bool(object, "knockout", activation)?,
+    })
+}
+
+/// Converts a `flash.filters` object into a filter.
+///
+/// Returns `None` if the object is not a filter.
+pub fn avm1_to_filter<'gc>(
+    object: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Option<Filter>, Error<'gc>> {
+    let filter = match object.native() {
+        NativeObject::BlurFilter(_) => Filter::BlurFilter(BlurFilter {
+            blur_x: get_f32(object, "blurX", activation)?,
+            blur_y: get_f32(object, "blurY", activation)?,
+            quality: get_quality(object, activation)?,
+        }),
+        NativeObject::BevelFilter(_) => Filter::BevelFilter(BevelFilter {
+            shadow_color: get_color(object, "shadowColor", "shadowAlpha", activation)?,
+            highlight_color: get_color(object, "highlightColor", "highlightAlpha", activation)?,
+            blur_x: get_f32(object, "blurX", activation)?,
+            blur_y: get_f32(object, "blurY", activation)?,
+            angle: get_f32(object, "angle", activation)?.to_radians(),
+            distance: get_f32(object, "distance", activation)?,
+            strength: get_f32(object, "strength", activation)?,
+            quality: get_quality(object, activation)?,
+            bevel_type: get_bevel_type(object, activation)?,
+            knockout: get_bool(object, "knockout", activation)?,
+        }),
+        _ if object.as_glow_filter_object().is_some() => Filter::GlowFilter(GlowFilter {
+            color: get_color(object, "color", "alpha", activation)?,
+            blur_x: get_f32(object, "blurX", activation)?,
+            blur_y: get_f32(object, "blurY", activation)?,
+            strength: get_f32(object, "strength", activation)?,
+            quality: get_quality(object, activation)?,
+            inner: get_bool(object, "inner", activation)?,
+            knockout: get_bool(object, "knockout", activation)?,
+        }),
+        _ if object.as_drop_shadow_filter_object().is_some() => {
+            Filter::DropShadowFilter(DropShadowFilter {
+                color: get_color(object, "color", "alpha", activation)?,
+                blur_x: get_f32(object, "blurX", activation)?,
+                blur_y: get_f32(object, "blurY", activation)?,
+                angle: get_f32(object, "angle", activation)?.to_radians(),
+                distance: get_f32(object, "distance", activation)?,
+                strength: get_f32(object, "strength", activation)?,
+                quality: get_quality(object, activation)?,
+                inner: get_bool(object, "inner", activation)?,
+                knockout: get_bool(object, "knockout", activation)?,
+                hide_object: get_bool(object, "hideObject", activation)?,
+            })
+        }
+        _ if object.as_gradient_glow_filter_object().is_some() => {
+            Filter::GradientGlowFilter(get_gradient(object, activation)?)
+        }
+        _ if object.as_gradient_bevel_filter_object().is_some() => {
+            Filter::GradientBevelFilter(get_gradient(object, activation)?)
+        }
+        _ if object.as_color_matrix_filter_object().is_some() => {
+            let values = get_array(object, "matrix", activation)?;
+            let mut matrix = [0.0; 20];
+            for (dst, src) in matrix.iter_mut().zip(values.iter()) {
+                *dst = src.coerce_to_f64(activation)? as f32;
+            }
+            Filter::ColorMatrixFilter(ColorMatrixFilter { matrix })
+        }
+        _ if object.as_convolution_filter_object().is_some() => {
+            let values = get_array(object, "matrix", activation)?;
+            let mut matrix = Vec::with_capacity(values.len());
+            for value in values {
+                matrix.push(value.coerce_to_f64(activation)? as f32);
+            }
+            Filter::ConvolutionFilter(ConvolutionFilter {
+                matrix_x: get_f32(object, "matrixX", activation)?.clamp(0.0, 15.0) as u8,
+                matrix_y: get_f32(object, "matrixY", activation)?.clamp(0.0, 15.0) as u8,
+                matrix,
+                divisor: get_f32(object, "divisor", activation)?,
+                bias: get_f32(object, "bias", activation)?,
+                preserve_alpha: get_bool(object, "preserveAlpha", activation)?,
+                clamp: get_bool(object, "clamp", activation)?,
+                color: get_color(object, "color", "alpha", activation)?,
+            })
+        }
+        _ if object.as_displacement_map_filter_object().is_some() => {
+            let map_bitmap = match object.get("mapBitmap", activation)? {
+                Value::Object(map) => map.as_bitmap_data_object().and_then(|map| {
+                    map.bitmap_data()
+                        .write(activation.context.gc_context)
+                        .bitmap_handle(activation.context.renderer)
+                }),
+                _ => None,
+            };
+            let map_point = match object.get("mapPoint", activation)? {
+                Value::Object(point) => (
+                    get_f32(point, "x", activation)? as i32,
+                    get_f32(point, "y", activation)? as i32,
+                ),
+                _ => (0, 0),
+            };
+            let mode = object
+                .get("mode", activation)?
+                .coerce_to_string(activation)?;
+            let mode = if &mode == b"clamp" {
+                DisplacementMapFilterMode::Clamp
+            } else if &mode == b"ignore" {
+                DisplacementMapFilterMode::Ignore
+            } else if &mode == b"color" {
+                DisplacementMapFilterMode::Color
+            } else {
+                DisplacementMapFilterMode::Wrap
+            };
+            Filter::DisplacementMapFilter(DisplacementMapFilter {
+                map_bitmap,
+                map_point,
+                component_x: object
+                    .get("componentX", activation)?
+                    .coerce_to_u8(activation)?,
+                component_y: object
+                    .get("componentY", activation)?
+                    .coerce_to_u8(activation)?,
+                scale_x: get_f32(object, "scaleX", activation)?,
+                scale_y: get_f32(object, "scaleY", activation)?,
+                mode,
+                color: get_color(object, "color", "alpha", activation)?,
+            })
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(filter))
+}
+
+fn bevel_type_to_value<'gc>(bevel_type: BevelFilterType) -> Value<'gc> {
+    match bevel_type {
+        BevelFilterType::Inner => "inner".into(),
+        BevelFilterType::Outer => "outer".into(),
+        BevelFilterType::Full => "full".into(),
+    }
+}
+
+fn gradient_to_args<'gc>(
+    filter: &GradientFilter,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Vec<Value<'gc>> {
+    let array_proto = activation.context.avm1.prototypes().array;
+    let gc_context = activation.context.gc_context;
+    let colors = filter.colors.iter().map(|(_, color)| color.to_rgb().into());
+    let alphas = filter
+        .colors
+        .iter()
+        .map(|(_, color)| (f64::from(color.a) / 255.0).into());
+    let ratios = filter.colors.iter().map(|(ratio, _)| (*ratio).into());
+    vec![
+        filter.distance.into(),
+        f64::from(filter.angle).to_degrees().into(),
+        ArrayObject::new(gc_context, array_proto, colors).into(),
+        ArrayObject::new(gc_context, array_proto, alphas).into(),
+        ArrayObject::new(gc_context, array_proto, ratios).into(),
+        filter.blur_x.into(),
+        filter.blur_y.into(),
+        filter.strength.into(),
+        filter.quality.into(),
+        bevel_type_to_value(filter.bevel_type),
+        filter.knockout.into(),
+    ]
+}
+
+/// Creates a new `flash.filters` object from a filter.
+pub fn filter_to_avm1<'gc>(
+    filter: &Filter,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let alpha = |color: &Color| f64::from(color.a) / 255.0;
+    let prototypes = activation.context.avm1.prototypes().clone();
+    let (constructor, args): (Object<'gc>, Vec<Value<'gc>>) = match filter {
+        Filter::BlurFilter(filter) => (
+            prototypes.blur_filter_constructor,
+            vec![
+                filter.blur_x.into(),
+                filter.blur_y.into(),
+                filter.quality.into(),
+            ],
+        ),
+        Filter::GlowFilter(filter) => (
+            prototypes.glow_filter_constructor,
+            vec![
+                filter.color.to_rgb().into(),
+                alpha(&filter.color).into(),
+                filter.blur_x.into(),
+                filter.blur_y.into(),
+                filter.strength.into(),
+                filter.quality.into(),
+                filter.inner.into(),
+                filter.knockout.into(),
+            ],
+        ),
+        Filter::DropShadowFilter(filter) => (
+            prototypes.drop_shadow_filter_constructor,
+            vec![
+                filter.distance.into(),
+                f64::from(filter.angle).to_degrees().into(),
+                filter.color.to_rgb().into(),
+                alpha(&filter.color).into(),
+                filter.blur_x.into(),
+                filter.blur_y.into(),
+                filter.strength.into(),
+                filter.quality.into(),
+                filter.inner.into(),
+                filter.knockout.into(),
+                filter.hide_object.into(),
+            ],
+        ),
+        Filter::BevelFilter(filter) => (
+            prototypes.bevel_filter_constructor,
+            vec![
+                filter.distance.into(),
+                f64::from(filter.angle).to_degrees().into(),
+                filter.highlight_color.to_rgb().into(),
+                alpha(&filter.highlight_color).into(),
+                filter.shadow_color.to_rgb().into(),
+                alpha(&filter.shadow_color).into(),
+                filter.blur_x.into(),
+                filter.blur_y.into(),
+                filter.strength.into(),
+                filter.quality.into(),
+                bevel_type_to_value(filter.bevel_type),
+                filter.knockout.into(),
+            ],
+        ),
+        Filter::GradientGlowFilter(filter) => {
+            let constructor = prototypes.gradient_glow_filter_constructor;
+            (constructor, gradient_to_args(filter, activation))
+        }
+        Filter::GradientBevelFilter(filter) => {
+            let constructor = prototypes.gradient_bevel_filter_constructor;
+            (constructor, gradient_to_args(filter, activation))
+        }
+        Filter::ColorMatrixFilter(filter) => {
+            let constructor = prototypes.color_matrix_filter_constructor;
+            let matrix = ArrayObject::new(
+                activation.context.gc_context,
+                prototypes.array,
+                filter.matrix.iter().map(|n| (*n).into()),
+            );
+            (constructor, vec![matrix.into()])
+        }
+        Filter::ConvolutionFilter(filter) => {
+            let matrix = ArrayObject::new(
+                activation.context.gc_context,
+                prototypes.array,
+                filter.matrix.iter().map(|n| (*n).into()),
+            );
+            (
+                prototypes.convolution_filter_constructor,
+                vec![
+                    filter.matrix_x.into(),
+                    filter.matrix_y.into(),
+                    matrix.into(),
+                    filter.divisor.into(),
+                    filter.bias.into(),
+                    filter.preserve_alpha.into(),
+                    filter.clamp.into(),
+                    filter.color.to_rgb().into(),
+                    alpha(&filter.color).into(),
+                ],
+            )
+        }
+        Filter::DisplacementMapFilter(filter) => {
+            let constructor = prototypes.displacement_map_filter_constructor;
+            let point_constructor = prototypes.point_constructor;
+            let map_point = point_constructor.construct(
+                activation,
+                &[filter.map_point.0.into(), filter.map_point.1.into()],
+            )?;
+            let mode = match filter.mode {
+                DisplacementMapFilterMode::Wrap => "wrap",
+                DisplacementMapFilterMode::Clamp => "clamp",
+                DisplacementMapFilterMode::Ignore => "ignore",
+                DisplacementMapFilterMode::Color => "color",
+            };
+            (
+                constructor,
+                vec![
+                    // TODO: We only keep the texture of the map, not its `BitmapData`.
+                    Value::Null,
+                    map_point,
+                    filter.component_x.into(),
+                    filter.component_y.into(),
+                    filter.scale_x.into(),
+                    filter.scale_y.into(),
+                    mode.into(),
+                    filter.color.to_rgb().into(),
+                    alpha(&filter.color).into(),
+                ],
+            )
+        }
+    };
+    constructor.construct(activation, &args)
+}
+
 pub fn create_proto<'gc>(
     gc_context: MutationContext<'gc, '_>,
     proto: Object<'gc>,
@@ -0,0 +1,94 @@
+//! AVM1 NetConnection object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::function::ExecutionReason;
+use crate::avm1::property_decl::{define_properties_on, Declaration};
+use crate::avm1::{Object, ScriptObject, TObject, Value};
+use gc_arena::MutationContext;
+
+const PROTO_DECLS: &[Declaration] = declare_properties! {
+    "connect" => method(connect; DONT_ENUM | DONT_DELETE);
+    "close" => method(close; DONT_ENUM | DONT_DELETE);
+};
+
+/// Implements `NetConnection`
+pub fn constructor<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    this.set("isConnected", false.into(), activation)?;
+    Ok(this.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let object = ScriptObject::new(gc_context, Some(proto));
+    define_properties_on(PROTO_DECLS, gc_context, object, fn_proto);
+    object.into()
+}
+
+/// Call `onStatus` on a `NetConnection` with an info object.
+fn on_status<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    code: &'static str,
+    level: &'static str,
+) -> Result<(), Error<'gc>> {
+    let info = ScriptObject::new(
+        activation.context.gc_context,
+        Some(activation.context.avm1.prototypes().object),
+    );
+    info.set("code", code.into(), activation)?;
+    info.set("level", level.into(), activation)?;
+
+    this.call_method(
+        "onStatus".into(),
+        &[info.into()],
+        activation,
+        ExecutionReason::FunctionCall,
+    )?;
+    Ok(())
+}
+
+fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // Only progressive downloads are supported, which don't connect to a server.
+    match args.get(0).unwrap_or(&Value::Undefined) {
+        Value::Undefined | Value::Null => {
+            this.set("isConnected", true.into(), activation)?;
+            this.set("uri", "null".into(), activation)?;
+            on_status(activation, this, "NetConnection.Connect.Success", "status")?;
+            Ok(true.into())
+        }
+        uri => {
+            this.set("isConnected", false.into(), activation)?;
+            this.set("uri", *uri, activation)?;
+            on_status(activation, this, "NetConnection.Connect.Failed", "error")?;
+            Ok(false.into())
+        }
+    }
+}
+
+fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if this
+        .get("isConnected", activation)?
+        .as_bool(activation.swf_version())
+    {
+        this.set("isConnected", false.into(), activation)?;
+        on_status(activation, this, "NetConnection.Connect.Closed", "status")?;
+    }
+
+    Ok(Value::Undefined)
+}
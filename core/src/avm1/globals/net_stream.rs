@@ -0,0 +1,159 @@
+//! AVM1 NetStream object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::property_decl::{define_properties_on, Declaration};
+use crate::avm1::{Object, ScriptObject, Value};
+use crate::streams::NetStream;
+use gc_arena::MutationContext;
+
+const PROTO_DECLS: &[Declaration] = declare_properties! {
+    "play" => method(play; DONT_ENUM | DONT_DELETE);
+    "pause" => method(pause; DONT_ENUM | DONT_DELETE);
+    "seek" => method(seek; DONT_ENUM | DONT_DELETE);
+    "close" => method(close; DONT_ENUM | DONT_DELETE);
+    "setBufferTime" => method(set_buffer_time; DONT_ENUM | DONT_DELETE);
+    "time" => property(get_time; DONT_ENUM | DONT_DELETE);
+    "bytesLoaded" => property(get_bytes_loaded; DONT_ENUM | DONT_DELETE);
+    "bytesTotal" => property(get_bytes_total; DONT_ENUM | DONT_DELETE);
+    "bufferLength" => property(get_buffer_length; DONT_ENUM | DONT_DELETE);
+    "bufferTime" => property(get_buffer_time; DONT_ENUM | DONT_DELETE);
+};
+
+/// Implements `NetStream`
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let object = ScriptObject::new(gc_context, Some(proto));
+    define_properties_on(PROTO_DECLS, gc_context, object, fn_proto);
+    object.into()
+}
+
+fn stream<'gc>(activation: &mut Activation<'_, 'gc, '_>, this: Object<'gc>) -> NetStream<'gc> {
+    activation
+        .context
+        .stream_manager
+        .stream_for(activation.context.gc_context, this.into())
+}
+
+fn play<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    match args.get(0).unwrap_or(&Value::Undefined) {
+        Value::Undefined | Value::Null => stream(activation, this).close(&mut activation.context),
+        url => {
+            let url = url.coerce_to_string(activation)?;
+            stream(activation, this).play(&mut activation.context, &url.to_utf8_lossy());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn pause<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // Without an argument, `pause` toggles between pausing and resuming.
+    let stream = stream(activation, this);
+    match args.get(0) {
+        None | Some(Value::Undefined) => stream.toggle_pause(&mut activation.context),
+        Some(flag) if flag.as_bool(activation.swf_version()) => {
+            stream.pause(&mut activation.context)
+        }
+        Some(_) => stream.resume(&mut activation.context),
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn seek<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let offset = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_f64(activation)?;
+    stream(activation, this).seek(&mut activation.context, offset);
+
+    Ok(Value::Undefined)
+}
+
+fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    stream(activation, this).close(&mut activation.context);
+
+    Ok(Value::Undefined)
+}
+
+fn set_buffer_time<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let buffer_time = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_f64(activation)?;
+    stream(activation, this).set_buffer_time(activation.context.gc_context, buffer_time);
+
+    Ok(Value::Undefined)
+}
+
+fn get_time<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(stream(activation, this).time().into())
+}
+
+fn get_bytes_loaded<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok((stream(activation, this).bytes_loaded() as f64).into())
+}
+
+fn get_bytes_total<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok((stream(activation, this).bytes_total() as f64).into())
+}
+
+fn get_buffer_length<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(stream(activation, this).buffer_length().into())
+}
+
+fn get_buffer_time<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(stream(activation, this).buffer_time().into())
+}
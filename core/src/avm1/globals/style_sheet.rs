@@ -0,0 +1,235 @@
+//! AVM1 TextField.StyleSheet object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::globals::text_field::new_text_format;
+use crate::avm1::property::Attribute;
+use crate::avm1::property_decl::{define_properties_on, Declaration};
+use crate::avm1::{ArrayObject, Object, ScriptObject, TObject, Value};
+use crate::html::{parse_css, CssStyle, StyleSheet};
+use crate::string::{AvmString, WString};
+use gc_arena::MutationContext;
+
+const PROTO_DECLS: &[Declaration] = declare_properties! {
+    "clear" => method(clear; DONT_ENUM | DONT_DELETE);
+    "getStyle" => method(get_style; DONT_ENUM | DONT_DELETE);
+    "getStyleNames" => method(get_style_names; DONT_ENUM | DONT_DELETE);
+    "parseCSS" => method(parse_css_method; DONT_ENUM | DONT_DELETE);
+    "setStyle" => method(set_style; DONT_ENUM | DONT_DELETE);
+    "transform" => method(transform; DONT_ENUM | DONT_DELETE);
+};
+
+/// Implements `TextField.StyleSheet`
+pub fn constructor<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    reset_styles(activation, this);
+    Ok(this.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let object = ScriptObject::new(gc_context, Some(proto));
+    define_properties_on(PROTO_DECLS, gc_context, object, fn_proto);
+    object.into()
+}
+
+/// Replace the styles of a style sheet with an empty set.
+fn reset_styles<'gc>(activation: &mut Activation<'_, 'gc, '_>, this: Object<'gc>) {
+    let styles = ScriptObject::new(
+        activation.context.gc_context,
+        Some(activation.context.avm1.prototypes().object),
+    );
+    this.define_value(
+        activation.context.gc_context,
+        "_css",
+        styles.into(),
+        Attribute::DONT_ENUM,
+    );
+}
+
+/// The object holding the styles of a style sheet, by lowercase name.
+fn styles<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<Option<Object<'gc>>, Error<'gc>> {
+    match this.get("_css", activation)? {
+        Value::Object(styles) => Ok(Some(styles)),
+        _ => Ok(None),
+    }
+}
+
+/// Copy the properties of a style object into a new object.
+fn copy_style<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    style: Object<'gc>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    let copy = ScriptObject::new(
+        activation.context.gc_context,
+        Some(activation.context.avm1.prototypes().object),
+    );
+    for name in style.get_keys(activation) {
+        let value = style.get(name, activation)?;
+        copy.set(name, value, activation)?;
+    }
+    Ok(copy.into())
+}
+
+fn lowercase_name<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    name: &Value<'gc>,
+) -> Result<AvmString<'gc>, Error<'gc>> {
+    let name = name.coerce_to_string(activation)?;
+    Ok(AvmString::new(
+        activation.context.gc_context,
+        name.to_ascii_lowercase(),
+    ))
+}
+
+/// Build the style described by the properties of a style object.
+fn object_to_style<'gc>(activation: &mut Activation<'_, 'gc, '_>, style: Object<'gc>) -> CssStyle {
+    CssStyle::from_properties(|name| match style.get(name, activation) {
+        Ok(Value::Undefined | Value::Null) | Err(_) => None,
+        Ok(value) => value
+            .coerce_to_string(activation)
+            .ok()
+            .map(|value| WString::from(value.as_wstr())),
+    })
+}
+
+/// Read the styles of a `TextField.StyleSheet` object, as they are at this point.
+pub fn object_to_style_sheet<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<StyleSheet, Error<'gc>> {
+    let mut style_sheet = StyleSheet::new();
+    if let Some(styles) = styles(activation, this)? {
+        for name in styles.get_keys(activation) {
+            if let Value::Object(style) = styles.get(name, activation)? {
+                let style = object_to_style(activation, style);
+                style_sheet.insert(&name, style);
+            }
+        }
+    }
+    Ok(style_sheet)
+}
+
+fn clear<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    reset_styles(activation, this);
+    Ok(Value::Undefined)
+}
+
+fn get_style<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = lowercase_name(activation, args.get(0).unwrap_or(&Value::Undefined))?;
+    if let Some(styles) = styles(activation, this)? {
+        if let Value::Object(style) = styles.get(name, activation)? {
+            return Ok(copy_style(activation, style)?.into());
+        }
+    }
+
+    Ok(Value::Null)
+}
+
+fn get_style_names<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let names = match styles(activation, this)? {
+        Some(styles) => styles.get_keys(activation),
+        None => Vec::new(),
+    };
+
+    Ok(ArrayObject::new(
+        activation.context.gc_context,
+        activation.context.avm1.prototypes().array,
+        names.into_iter().map(Value::from),
+    )
+    .into())
+}
+
+fn parse_css_method<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let css = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?;
+    let (rules, styles) = match (parse_css(&css), styles(activation, this)?) {
+        (Some(rules), Some(styles)) => (rules, styles),
+        _ => return Ok(false.into()),
+    };
+
+    for (selector, declarations) in rules {
+        let name = AvmString::new(activation.context.gc_context, selector.to_ascii_lowercase());
+        let style = match styles.get(name, activation)? {
+            Value::Object(style) => style,
+            _ => {
+                let style = ScriptObject::new(
+                    activation.context.gc_context,
+                    Some(activation.context.avm1.prototypes().object),
+                );
+                styles.set(name, style.into(), activation)?;
+                style.into()
+            }
+        };
+
+        for (property, value) in declarations {
+            let property = AvmString::new(activation.context.gc_context, property);
+            let value = AvmString::new(activation.context.gc_context, value);
+            style.set(property, value.into(), activation)?;
+        }
+    }
+
+    Ok(true.into())
+}
+
+fn set_style<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = lowercase_name(activation, args.get(0).unwrap_or(&Value::Undefined))?;
+    if let Some(styles) = styles(activation, this)? {
+        match args.get(1) {
+            Some(Value::Object(style)) => {
+                let style = copy_style(activation, *style)?;
+                styles.set(name, style.into(), activation)?;
+            }
+            _ => {
+                styles.delete(activation, name);
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn transform<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    match args.get(0) {
+        Some(Value::Object(style)) => {
+            let format = object_to_style(activation, *style).format;
+            Ok(new_text_format(activation, format).into())
+        }
+        _ => Ok(Value::Null),
+    }
+}
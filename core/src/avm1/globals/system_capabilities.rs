@@ -1,10 +1,10 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
-use crate::avm1::globals::system::SystemCapabilities;
 use crate::avm1::object::Object;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{ScriptObject, Value};
 use crate::string::AvmString;
+use crate::system_properties::SystemCapabilities;
 use gc_arena::MutationContext;
 
 const OBJECT_DECLS: &[Declaration] = declare_properties! {
@@ -208,7 +208,7 @@ pub fn get_version<'gc>(
         activation
             .context
             .system
-            .get_version_string(activation.context.avm1),
+            .get_version_string(activation.context.avm1.player_version()),
     )
     .into())
 }
@@ -221,7 +221,7 @@ pub fn get_server_string<'gc>(
     let server_string = activation
         .context
         .system
-        .get_server_string(activation.context.avm1);
+        .get_server_string(activation.context.avm1.player_version());
     Ok(AvmString::new_utf8(activation.context.gc_context, server_string).into())
 }
 
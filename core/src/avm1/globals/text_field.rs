@@ -1,14 +1,18 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
+use crate::avm1::globals::style_sheet;
 use crate::avm1::object::NativeObject;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{globals, Object, ScriptObject, TObject, Value};
-use crate::display_object::{AutoSizeMode, EditText, TDisplayObject, TextSelection};
+use crate::display_object::{
+    AutoSizeMode, EditText, EditTextStyleSheet, TDisplayObject, TextSelection,
+};
 use crate::font::round_down_to_pixel;
 use crate::html::TextFormat;
 use crate::string::{AvmString, WStr};
+use crate::vminterface::AvmObject;
 use gc_arena::{GcCell, MutationContext};
-use swf::Color;
+use swf::{Color, Twips};
 
 macro_rules! tf_method {
     ($fn:expr) => {
@@ -58,23 +62,36 @@ const PROTO_DECLS: &[Declaration] = declare_properties! {
     "replaceSel" => method(tf_method!(replace_sel); DONT_ENUM | DONT_DELETE);
     "replaceText" => method(tf_method!(replace_text); DONT_ENUM | DONT_DELETE);
     "removeTextField" => method(tf_method!(remove_text_field); DONT_ENUM | DONT_DELETE);
+    "getCharBoundaries" => method(tf_method!(get_char_boundaries); DONT_ENUM | DONT_DELETE);
+    "getCharIndexAtPoint" => method(tf_method!(get_char_index_at_point); DONT_ENUM | DONT_DELETE);
+    "getLineIndexAtPoint" => method(tf_method!(get_line_index_at_point); DONT_ENUM | DONT_DELETE);
+    "getLineIndexOfChar" => method(tf_method!(get_line_index_of_char); DONT_ENUM | DONT_DELETE);
+    "getLineLength" => method(tf_method!(get_line_length); DONT_ENUM | DONT_DELETE);
+    "getLineOffset" => method(tf_method!(get_line_offset); DONT_ENUM | DONT_DELETE);
+    "getLineText" => method(tf_method!(get_line_text); DONT_ENUM | DONT_DELETE);
+    "getFirstCharInParagraph" => method(tf_method!(get_first_char_in_paragraph); DONT_ENUM | DONT_DELETE);
+    "getParagraphLength" => method(tf_method!(get_paragraph_length); DONT_ENUM | DONT_DELETE);
     "autoSize" => property(tf_getter!(auto_size), tf_setter!(set_auto_size));
     "background" => property(tf_getter!(background), tf_setter!(set_background));
     "backgroundColor" => property(tf_getter!(background_color), tf_setter!(set_background_color));
     "border" => property(tf_getter!(border), tf_setter!(set_border));
     "borderColor" => property(tf_getter!(border_color), tf_setter!(set_border_color));
     "bottomScroll" => property(tf_getter!(bottom_scroll));
+    "condenseWhite" => property(tf_getter!(condense_white), tf_setter!(set_condense_white));
     "embedFonts" => property(tf_getter!(embed_fonts), tf_setter!(set_embed_fonts));
     "getDepth" => method(globals::get_depth; DONT_ENUM | DONT_DELETE | READ_ONLY | VERSION_6);
     "hscroll" => property(tf_getter!(hscroll), tf_setter!(set_hscroll));
     "html" => property(tf_getter!(html), tf_setter!(set_html));
     "htmlText" => property(tf_getter!(html_text), tf_setter!(set_html_text));
+    "styleSheet" => property(tf_getter!(style_sheet), tf_setter!(set_style_sheet));
     "length" => property(tf_getter!(length));
     "maxhscroll" => property(tf_getter!(maxhscroll));
     "maxscroll" => property(tf_getter!(maxscroll));
+    "maxChars" => property(tf_getter!(max_chars), tf_setter!(set_max_chars));
     "multiline" => property(tf_getter!(multiline), tf_setter!(set_multiline));
     "password" => property(tf_getter!(password), tf_setter!(set_password));
     "scroll" => property(tf_getter!(scroll), tf_setter!(set_scroll));
+    "restrict" => property(tf_getter!(restrict), tf_setter!(set_restrict));
     "selectable" => property(tf_getter!(selectable), tf_setter!(set_selectable));
     "text" => property(tf_getter!(text), tf_setter!(set_text));
     "textColor" => property(tf_getter!(text_color), tf_setter!(set_text_color));
@@ -107,6 +124,73 @@ pub fn create_proto<'gc>(
     define_properties_on(PROTO_DECLS, gc_context, object, fn_proto);
     object.into()
 }
+pub fn condense_white<'gc>(
+    this: EditText<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.condense_white().into())
+}
+
+pub fn set_condense_white<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    this.set_condense_white(
+        value.as_bool(activation.swf_version()),
+        &mut activation.context,
+    );
+    Ok(())
+}
+
+pub fn max_chars<'gc>(
+    this: EditText<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    // A text field without a limit has a `maxChars` of `null`.
+    match this.max_chars() {
+        0 => Ok(Value::Null),
+        max_chars => Ok(max_chars.into()),
+    }
+}
+
+pub fn set_max_chars<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let max_chars = value.coerce_to_i32(activation)?;
+    this.set_max_chars(max_chars, &mut activation.context);
+    Ok(())
+}
+
+pub fn restrict<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    match this.restrict() {
+        Some(restrict) => Ok(AvmString::new(activation.context.gc_context, restrict).into()),
+        None => Ok(Value::Null),
+    }
+}
+
+pub fn set_restrict<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    match value {
+        Value::Undefined | Value::Null => {
+            this.set_restrict(None, &mut activation.context);
+        }
+        value => {
+            let restrict = value.coerce_to_string(activation)?;
+            this.set_restrict(Some(&restrict), &mut activation.context);
+        }
+    }
+    Ok(())
+}
+
 pub fn password<'gc>(
     this: EditText<'gc>,
     _activation: &mut Activation<'_, 'gc, '_>,
@@ -126,7 +210,7 @@ pub fn set_password<'gc>(
     Ok(())
 }
 
-fn new_text_format<'gc>(
+pub(crate) fn new_text_format<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     text_format: TextFormat,
 ) -> ScriptObject<'gc> {
@@ -285,6 +369,153 @@ fn replace_text<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Coerce an index argument, returning `None` for negative indices.
+fn index_arg<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Option<usize>, Error<'gc>> {
+    let index = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_i32(activation)?;
+
+    Ok(usize::try_from(index).ok())
+}
+
+/// Coerce the point arguments of a point method to twips.
+fn point_args<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<(Twips, Twips), Error<'gc>> {
+    let x = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_f64(activation)?;
+    let y = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_f64(activation)?;
+
+    Ok((Twips::from_pixels(x), Twips::from_pixels(y)))
+}
+
+fn get_char_boundaries<'gc>(
+    text_field: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let bounds = index_arg(activation, args)?.and_then(|index| text_field.char_bounds(index));
+
+    if let Some(bounds) = bounds {
+        let constructor = activation.context.avm1.prototypes().rectangle_constructor;
+        return constructor.construct(
+            activation,
+            &[
+                bounds.offset_x().to_pixels().into(),
+                bounds.offset_y().to_pixels().into(),
+                bounds.width().to_pixels().into(),
+                bounds.height().to_pixels().into(),
+            ],
+        );
+    }
+
+    Ok(Value::Null)
+}
+
+fn get_char_index_at_point<'gc>(
+    text_field: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let point = point_args(activation, args)?;
+    Ok(text_field
+        .char_index_at_point(point)
+        .map_or(-1.0, |index| index as f64)
+        .into())
+}
+
+fn get_line_index_at_point<'gc>(
+    text_field: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let point = point_args(activation, args)?;
+    Ok(text_field
+        .line_index_at_point(point)
+        .map_or(-1.0, |line| line as f64)
+        .into())
+}
+
+fn get_line_index_of_char<'gc>(
+    text_field: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(index_arg(activation, args)?
+        .and_then(|index| text_field.line_index_of_char(index))
+        .map_or(-1.0, |line| line as f64)
+        .into())
+}
+
+fn get_line_length<'gc>(
+    text_field: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(index_arg(activation, args)?
+        .and_then(|line| text_field.line_length(line))
+        .map_or(-1.0, |length| length as f64)
+        .into())
+}
+
+fn get_line_offset<'gc>(
+    text_field: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(index_arg(activation, args)?
+        .and_then(|line| text_field.line_offset(line))
+        .map_or(-1.0, |offset| offset as f64)
+        .into())
+}
+
+fn get_line_text<'gc>(
+    text_field: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(text) = index_arg(activation, args)?.and_then(|line| text_field.line_text(line)) {
+        return Ok(AvmString::new(activation.context.gc_context, text).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn get_first_char_in_paragraph<'gc>(
+    text_field: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(index_arg(activation, args)?
+        .and_then(|index| text_field.paragraph_start(index))
+        .map_or(-1.0, |index| index as f64)
+        .into())
+}
+
+fn get_paragraph_length<'gc>(
+    text_field: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(index_arg(activation, args)?
+        .and_then(|index| text_field.paragraph_length(index))
+        .map_or(-1.0, |length| length as f64)
+        .into())
+}
+
 pub fn remove_text_field<'gc>(
     text_field: EditText<'gc>,
     activation: &mut Activation<'_, 'gc, '_>,
@@ -375,11 +606,51 @@ pub fn set_html_text<'gc>(
     value: Value<'gc>,
 ) -> Result<(), Error<'gc>> {
     let text = value.coerce_to_string(activation)?;
+
+    // Changes made to the style sheet since it was attached apply to new text.
+    if let Some(AvmObject::Avm1(style_sheet)) = this.style_sheet() {
+        let styles = style_sheet::object_to_style_sheet(activation, style_sheet)?;
+        this.set_style_sheet(
+            Some(EditTextStyleSheet {
+                object: style_sheet.into(),
+                styles,
+            }),
+            &mut activation.context,
+        );
+    }
+
     this.set_html_text(&text, &mut activation.context);
     // Changing the htmlText does NOT update variable bindings (does not call EditText::propagate_text_binding).
     Ok(())
 }
 
+pub fn style_sheet<'gc>(
+    this: EditText<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    match this.style_sheet() {
+        Some(AvmObject::Avm1(style_sheet)) => Ok(style_sheet.into()),
+        _ => Ok(Value::Undefined),
+    }
+}
+
+pub fn set_style_sheet<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let style_sheet = match value {
+        Value::Object(object) => Some(EditTextStyleSheet {
+            object: object.into(),
+            styles: style_sheet::object_to_style_sheet(activation, object)?,
+        }),
+        _ => None,
+    };
+
+    this.set_style_sheet(style_sheet, &mut activation.context);
+    Ok(())
+}
+
 pub fn background<'gc>(
     this: EditText<'gc>,
     _activation: &mut Activation<'_, 'gc, '_>,
@@ -599,7 +870,7 @@ pub fn get_type<'gc>(
     this: EditText<'gc>,
     _activation: &mut Activation<'_, 'gc, '_>,
 ) -> Result<Value<'gc>, Error<'gc>> {
-    let tf_type = match this.is_editable() {
+    let tf_type = match this.is_input() {
         true => "input",
         false => "dynamic",
     };
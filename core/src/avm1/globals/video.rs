@@ -3,10 +3,16 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::object::Object;
+use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::value::Value;
-use crate::avm1::ScriptObject;
+use crate::avm1::{ScriptObject, TObject};
+use crate::display_object::TDisplayObject;
 use gc_arena::MutationContext;
 
+const PROTO_DECLS: &[Declaration] = declare_properties! {
+    "attachVideo" => method(attach_video; DONT_ENUM | DONT_DELETE);
+};
+
 /// Implements `Video`
 pub fn constructor<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
@@ -19,8 +25,31 @@ pub fn constructor<'gc>(
 pub fn create_proto<'gc>(
     gc_context: MutationContext<'gc, '_>,
     proto: Object<'gc>,
-    _fn_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
 ) -> Object<'gc> {
     let object = ScriptObject::new(gc_context, Some(proto));
+    define_properties_on(PROTO_DECLS, gc_context, object, fn_proto);
     object.into()
 }
+
+fn attach_video<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(video) = this.as_display_object().and_then(|this| this.as_video()) {
+        let stream = match args.get(0).unwrap_or(&Value::Undefined) {
+            Value::Object(stream) => Some(
+                activation
+                    .context
+                    .stream_manager
+                    .stream_for(activation.context.gc_context, (*stream).into()),
+            ),
+            _ => None,
+        };
+
+        video.attach_net_stream(&mut activation.context, stream);
+    }
+
+    Ok(Value::Undefined)
+}
@@ -0,0 +1,127 @@
+//! AVM1 XMLSocket object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::function::ExecutionReason;
+use crate::avm1::property_decl::{define_properties_on, Declaration};
+use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::socket::{SocketTarget, Sockets};
+use gc_arena::MutationContext;
+use std::time::Duration;
+
+/// How long a connection attempt may take. Unlike the AVM2 socket classes,
+/// `XMLSocket` has no way to configure this.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(20);
+
+const PROTO_DECLS: &[Declaration] = declare_properties! {
+    "connect" => method(connect; DONT_ENUM | DONT_DELETE);
+    "close" => method(close; DONT_ENUM | DONT_DELETE);
+    "send" => method(send; DONT_ENUM | DONT_DELETE);
+    "onData" => method(on_data; DONT_ENUM | DONT_DELETE);
+};
+
+/// Implements `XMLSocket`
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let object = ScriptObject::new(gc_context, Some(proto));
+    define_properties_on(PROTO_DECLS, gc_context, object, fn_proto);
+    object.into()
+}
+
+fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // A null host connects to the host the movie was loaded from.
+    let host = match args.get(0).unwrap_or(&Value::Undefined) {
+        Value::Undefined | Value::Null => Sockets::default_host(&activation.context),
+        host => host.coerce_to_string(activation)?.to_string(),
+    };
+
+    let port = match args.get(1) {
+        Some(port) => port.coerce_to_i32(activation)?,
+        None => return Ok(false.into()),
+    };
+    let port = match u16::try_from(port) {
+        Ok(port) if port != 0 => port,
+        _ => return Ok(false.into()),
+    };
+
+    Sockets::connect(
+        &mut activation.context,
+        SocketTarget::Avm1XmlSocket(this),
+        host,
+        port,
+        CONNECT_TIMEOUT,
+    );
+
+    Ok(true.into())
+}
+
+fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    activation
+        .context
+        .sockets
+        .close(SocketTarget::Avm1XmlSocket(this));
+
+    Ok(Value::Undefined)
+}
+
+fn send<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let message = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?;
+
+    // Messages are terminated by a null byte.
+    let mut data = message.to_utf8_lossy().into_owned().into_bytes();
+    data.push(0);
+
+    activation
+        .context
+        .sockets
+        .send(SocketTarget::Avm1XmlSocket(this), data);
+
+    Ok(Value::Undefined)
+}
+
+fn on_data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // The default implementation parses the message as XML, and passes it on
+    // to `onXML`.
+    let src = args.get(0).copied().unwrap_or(Value::Undefined);
+    let xml_constructor = activation.context.avm1.prototypes().xml_constructor;
+    let xml = xml_constructor.construct(activation, &[src])?;
+
+    this.call_method(
+        "onXML".into(),
+        &[xml],
+        activation,
+        ExecutionReason::FunctionCall,
+    )?;
+
+    Ok(Value::Undefined)
+}
@@ -9,6 +9,7 @@ use crate::avm1::{scope, Activation, ActivationIdentifier, Error, Object, Value}
 use crate::context::UpdateContext;
 use crate::frame_lifecycle::FramePhase;
 use crate::prelude::*;
+use crate::profiler::ScriptVm;
 use crate::string::AvmString;
 use crate::tag_utils::SwfSlice;
 use crate::{avm1, avm_debug};
@@ -133,6 +134,10 @@ impl<'gc> Avm1<'gc> {
             ),
         );
         let constant_pool = parent_activation.context.avm1.constant_pool;
+        let name = name.into();
+        if let Some(profiler) = parent_activation.context.profiler.as_mut() {
+            profiler.enter_named(ScriptVm::Avm1, &name);
+        }
         let child_name = parent_activation.id.child(name);
         let mut child_activation = Activation::from_action(
             parent_activation.context.reborrow(),
@@ -147,6 +152,9 @@ impl<'gc> Avm1<'gc> {
         if let Err(e) = child_activation.run_actions(code) {
             root_error_handler(&mut child_activation, e);
         }
+        if let Some(profiler) = child_activation.context.profiler.as_mut() {
+            profiler.exit();
+        }
     }
 
     /// Add a stack frame that executes code in initializer scope.
@@ -218,6 +226,9 @@ impl<'gc> Avm1<'gc> {
         );
         parent_activation.context.avm1.push(Value::Undefined);
         let constant_pool = parent_activation.context.avm1.constant_pool;
+        if let Some(profiler) = parent_activation.context.profiler.as_mut() {
+            profiler.enter_named(ScriptVm::Avm1, "[Init]");
+        }
         let child_name = parent_activation.id.child("[Init]");
         let mut child_activation = Activation::from_action(
             parent_activation.context.reborrow(),
@@ -232,6 +243,9 @@ impl<'gc> Avm1<'gc> {
         if let Err(e) = child_activation.run_actions(code) {
             root_error_handler(&mut child_activation, e);
         }
+        if let Some(profiler) = child_activation.context.profiler.as_mut() {
+            profiler.exit();
+        }
     }
 
     /// Add a stack frame that executes code in timeline scope for an object
@@ -6,6 +6,8 @@ use crate::avm2::globals::SystemClasses;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::script::{Script, TranslationUnit};
 use crate::context::UpdateContext;
+use crate::display_object::{DisplayObject, TDisplayObject};
+use crate::profiler::ScriptVm;
 use crate::string::AvmString;
 use fnv::FnvHashMap;
 use gc_arena::{Collect, GcCell, MutationContext};
@@ -28,10 +30,13 @@ pub mod bytearray;
 mod call_stack;
 mod class;
 mod domain;
+pub mod e4x;
 pub mod error;
 mod events;
 mod function;
 pub mod globals;
+mod inline_cache;
+mod metadata;
 mod method;
 mod multiname;
 mod namespace;
@@ -46,6 +51,7 @@ mod string;
 mod traits;
 mod value;
 mod vector;
+mod verify;
 mod vtable;
 
 pub use crate::avm2::activation::Activation;
@@ -105,6 +111,21 @@ pub struct Avm2<'gc> {
     /// collector does not support weak references.
     broadcast_list: FnvHashMap<AvmString<'gc>, Vec<Object<'gc>>>,
 
+    /// Classes registered with `flash.net.registerClassAlias`, keyed by alias.
+    ///
+    /// AMF serialization uses these aliases to name typed objects, so that
+    /// they can be deserialized back into instances of the same class.
+    alias_to_class_map: FnvHashMap<AvmString<'gc>, ClassObject<'gc>>,
+
+    /// The reverse of `alias_to_class_map`.
+    class_to_alias_map: FnvHashMap<ClassObject<'gc>, AvmString<'gc>>,
+
+    /// Whether an `uncaughtError` event is currently being dispatched.
+    ///
+    /// Errors thrown by `uncaughtError` listeners are never redispatched, so
+    /// that a faulty listener can't recurse forever.
+    dispatching_uncaught_error: bool,
+
     #[cfg(feature = "avm_debug")]
     pub debug_output: bool,
 }
@@ -124,6 +145,9 @@ impl<'gc> Avm2<'gc> {
             native_instance_allocator_table: Default::default(),
             native_instance_init_table: Default::default(),
             broadcast_list: Default::default(),
+            alias_to_class_map: Default::default(),
+            class_to_alias_map: Default::default(),
+            dispatching_uncaught_error: false,
 
             #[cfg(feature = "avm_debug")]
             debug_output: false,
@@ -143,6 +167,29 @@ impl<'gc> Avm2<'gc> {
         self.system_classes.as_ref().unwrap()
     }
 
+    /// Register a class alias.
+    ///
+    /// A class may be registered under several aliases, all of which can be
+    /// deserialized; the most recent one is used when serializing.
+    pub fn register_class_alias(&mut self, name: AvmString<'gc>, class_object: ClassObject<'gc>) {
+        if let Some(old_class) = self.alias_to_class_map.insert(name, class_object) {
+            if self.class_to_alias_map.get(&old_class) == Some(&name) {
+                self.class_to_alias_map.remove(&old_class);
+            }
+        }
+        self.class_to_alias_map.insert(class_object, name);
+    }
+
+    /// Look up the class registered under a given alias.
+    pub fn get_class_by_alias(&self, name: AvmString<'gc>) -> Option<ClassObject<'gc>> {
+        self.alias_to_class_map.get(&name).copied()
+    }
+
+    /// Look up the alias a class was registered under.
+    pub fn get_alias_by_class(&self, class_object: ClassObject<'gc>) -> Option<AvmString<'gc>> {
+        self.class_to_alias_map.get(&class_object).copied()
+    }
+
     /// Run a script's initializer method.
     pub fn run_script_initializer(
         script: Script<'gc>,
@@ -160,7 +207,13 @@ impl<'gc> Avm2<'gc> {
                     .context
                     .avm2
                     .push_global_init(init_activation.context.gc_context);
+                if let Some(profiler) = init_activation.context.profiler.as_mut() {
+                    profiler.enter_named(ScriptVm::Avm2, "global$init");
+                }
                 let r = (method.method)(&mut init_activation, Some(scope), &[]);
+                if let Some(profiler) = init_activation.context.profiler.as_mut() {
+                    profiler.exit();
+                }
                 init_activation
                     .context
                     .avm2
@@ -172,7 +225,13 @@ impl<'gc> Avm2<'gc> {
                     .context
                     .avm2
                     .push_global_init(init_activation.context.gc_context);
+                if let Some(profiler) = init_activation.context.profiler.as_mut() {
+                    profiler.enter_named(ScriptVm::Avm2, "global$init");
+                }
                 let r = init_activation.run_actions(method);
+                if let Some(profiler) = init_activation.context.profiler.as_mut() {
+                    profiler.exit();
+                }
                 init_activation
                     .context
                     .avm2
@@ -197,6 +256,103 @@ impl<'gc> Avm2<'gc> {
         dispatch_event(&mut activation, target, event)
     }
 
+    /// Report an error that escaped every script handler.
+    ///
+    /// Thrown values are first dispatched as an `uncaughtError` event on the
+    /// `uncaughtErrorEvents` of the `LoaderInfo` that owns `source`, falling
+    /// back to the root movie's. If no listener cancels that event, the error
+    /// is logged with `description` and forwarded to the `LogBackend`.
+    pub fn uncaught_error(
+        activation: &mut Activation<'_, 'gc, '_>,
+        source: Option<DisplayObject<'gc>>,
+        error: Error<'gc>,
+        description: &str,
+    ) {
+        if let Error::AvmError(value) = &error {
+            if !activation.context.avm2.dispatching_uncaught_error {
+                activation.context.avm2.dispatching_uncaught_error = true;
+                let result = Self::dispatch_uncaught_error_event(activation, source, *value);
+                activation.context.avm2.dispatching_uncaught_error = false;
+
+                match result {
+                    Ok(true) => return,
+                    Ok(false) => {}
+                    Err(e) => log::error!("Error dispatching uncaughtError event: {}", e),
+                }
+            }
+        }
+
+        let error_object = match &error {
+            Error::AvmError(Value::Object(object)) => object.as_error_object(),
+            _ => None,
+        };
+        let (message, stack_trace) = if let Some(error_object) = error_object {
+            let message = match error_object.display(activation) {
+                Ok(message) => message.to_string(),
+                Err(_) => error.to_string(),
+            };
+            let stack_trace = match error_object.call_stack() {
+                Some(call_stack) => call_stack.to_string(),
+                None => activation.avm2().call_stack().read().to_string(),
+            };
+            (message, stack_trace)
+        } else {
+            let message = match &error {
+                Error::AvmError(value) => match value.coerce_to_string(activation) {
+                    Ok(message) => message.to_string(),
+                    Err(_) => error.to_string(),
+                },
+                Error::RustError(_) => error.to_string(),
+            };
+            let stack_trace = activation.avm2().call_stack().read().to_string();
+            (message, stack_trace)
+        };
+
+        log::error!("{}: {}{}", description, message, stack_trace);
+        activation
+            .context
+            .log
+            .avm_uncaught_error(&message, stack_trace.trim_start());
+    }
+
+    /// Dispatch an `uncaughtError` event for a thrown value.
+    ///
+    /// The `bool` reads true if a listener cancelled the event.
+    fn dispatch_uncaught_error_event(
+        activation: &mut Activation<'_, 'gc, '_>,
+        source: Option<DisplayObject<'gc>>,
+        value: Value<'gc>,
+    ) -> Result<bool, Error<'gc>> {
+        let mut loader_info = None;
+        let mut node = source;
+        while let Some(display_object) = node {
+            loader_info = display_object.loader_info();
+            if loader_info.is_some() {
+                break;
+            }
+            node = display_object.parent();
+        }
+
+        let loader_info =
+            loader_info.or_else(|| activation.context.stage.root_clip().loader_info());
+        let target = match loader_info
+            .as_ref()
+            .and_then(|loader_info| loader_info.as_loader_info_object())
+        {
+            Some(loader_info) => loader_info.uncaught_error_events(),
+            None => return Ok(false),
+        };
+
+        let event = activation.avm2().classes().uncaughterrorevent.construct(
+            activation,
+            &["uncaughtError".into(), true.into(), true.into(), value],
+        )?;
+
+        let was_not_cancelled = crate::avm2::events::dispatch_event(activation, target, event)?;
+
+        Ok(!was_not_cancelled)
+    }
+
     /// Add an object to the broadcast list.
     ///
     /// Each broadcastable event contains it's own broadcast list. You must
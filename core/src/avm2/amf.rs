@@ -1,241 +1,1377 @@
-use crate::avm2::bytearray::ByteArrayStorage;
-use crate::avm2::object::{ByteArrayObject, TObject};
-use crate::avm2::ArrayObject;
-use crate::avm2::ArrayStorage;
-use crate::avm2::Multiname;
-use crate::avm2::{Activation, Error, Object, Value};
+//! AMF0 and AMF3 serialization of AVM2 values
+//!
+//! Values are always read from and written to a `ByteArray` object, rather
+//! than a plain buffer, so that `IExternalizable` objects can read and write
+//! their own data at the right place in the same stream.
+
+use crate::avm2::bytearray::{ByteArrayStorage, ObjectEncoding};
+use crate::avm2::e4x;
+use crate::avm2::object::{ByteArrayObject, ClassObject, TObject, VectorObject};
+use crate::avm2::traits::TraitKind;
+use crate::avm2::vector::VectorStorage;
+use crate::avm2::{Activation, ArrayObject, Error, Multiname, Object, QName, Value};
 use crate::string::AvmString;
-use enumset::EnumSet;
-use flash_lso::types::{AMFVersion, Element, Lso};
-use flash_lso::types::{Attribute, ClassDefinition, Value as AmfValue};
+use fnv::FnvHashMap;
 
-/// Serialize a Value to an AmfValue
-pub fn serialize_value<'gc>(
-    activation: &mut Activation<'_, 'gc, '_>,
-    elem: Value<'gc>,
-    amf_version: AMFVersion,
-) -> Option<AmfValue> {
-    match elem {
-        Value::Undefined => Some(AmfValue::Undefined),
-        Value::Null => Some(AmfValue::Null),
-        Value::Bool(b) => Some(AmfValue::Bool(b)),
-        Value::Number(f) => Some(AmfValue::Number(f)),
-        Value::Integer(num) => {
-            // NOTE - we should really be converting `Value::Integer` to `Value::Number`
-            // whenever it's outside this range, instead of performing this during AMF serialization.
-            // Integers are unsupported in AMF0, and must be converted to Number regardless of whether
-            // it can be represented as an integer.
-            if amf_version == AMFVersion::AMF0 || num >= (1 << 28) || num < -(1 << 28) {
-                Some(AmfValue::Number(num as f64))
+/// AMF0 type markers.
+mod amf0 {
+    pub const NUMBER: u8 = 0x00;
+    pub const BOOLEAN: u8 = 0x01;
+    pub const STRING: u8 = 0x02;
+    pub const OBJECT: u8 = 0x03;
+    pub const NULL: u8 = 0x05;
+    pub const UNDEFINED: u8 = 0x06;
+    pub const REFERENCE: u8 = 0x07;
+    pub const ECMA_ARRAY: u8 = 0x08;
+    pub const OBJECT_END: u8 = 0x09;
+    pub const STRICT_ARRAY: u8 = 0x0A;
+    pub const DATE: u8 = 0x0B;
+    pub const LONG_STRING: u8 = 0x0C;
+    pub const UNSUPPORTED: u8 = 0x0D;
+    pub const XML_DOCUMENT: u8 = 0x0F;
+    pub const TYPED_OBJECT: u8 = 0x10;
+    pub const AVMPLUS: u8 = 0x11;
+}
+
+/// AMF3 type markers.
+mod amf3 {
+    pub const UNDEFINED: u8 = 0x00;
+    pub const NULL: u8 = 0x01;
+    pub const FALSE: u8 = 0x02;
+    pub const TRUE: u8 = 0x03;
+    pub const INTEGER: u8 = 0x04;
+    pub const DOUBLE: u8 = 0x05;
+    pub const STRING: u8 = 0x06;
+    pub const XML_DOCUMENT: u8 = 0x07;
+    pub const DATE: u8 = 0x08;
+    pub const ARRAY: u8 = 0x09;
+    pub const OBJECT: u8 = 0x0A;
+    pub const XML: u8 = 0x0B;
+    pub const BYTE_ARRAY: u8 = 0x0C;
+    pub const VECTOR_INT: u8 = 0x0D;
+    pub const VECTOR_UINT: u8 = 0x0E;
+    pub const VECTOR_DOUBLE: u8 = 0x0F;
+    pub const VECTOR_OBJECT: u8 = 0x10;
+    pub const DICTIONARY: u8 = 0x11;
+}
+
+/// The smallest integer that AMF3 can store as an `integer`.
+const AMF3_INTEGER_MIN: i32 = -(1 << 28);
+
+/// The largest integer that AMF3 can store as an `integer`.
+const AMF3_INTEGER_MAX: i32 = (1 << 28) - 1;
+
+/// The header of a `.sol` file, up to the name of the shared object.
+const LSO_SIGNATURE: &[u8] = b"TCSO\x00\x04\x00\x00\x00\x00";
+
+/// The class information written alongside an object.
+///
+/// AMF3 calls this the object's "traits", and only writes it out once per
+/// class; later objects of the same class refer back to the first copy.
+#[derive(Clone, Debug)]
+struct AmfTraits<'gc> {
+    /// The alias of the class, or an empty string for anonymous objects.
+    name: AvmString<'gc>,
+
+    /// Whether the object writes its own data through `IExternalizable`.
+    externalizable: bool,
+
+    /// Whether dynamic properties follow the sealed ones.
+    dynamic: bool,
+
+    /// The names of the public variables and read-write accessors of the
+    /// class, in the order their values are written.
+    sealed: Vec<AvmString<'gc>>,
+}
+
+impl<'gc> AmfTraits<'gc> {
+    /// Describe how instances of a class are serialized.
+    fn for_class(activation: &mut Activation<'_, 'gc, '_>, class: ClassObject<'gc>) -> Self {
+        let object_class = activation.avm2().classes().object;
+        if Object::ptr_eq(class, object_class) {
+            return Self {
+                name: "".into(),
+                externalizable: false,
+                dynamic: true,
+                sealed: Vec::new(),
+            };
+        }
+
+        let name = activation
+            .avm2()
+            .get_alias_by_class(class)
+            .unwrap_or_else(|| "".into());
+        let externalizable = class.has_class_in_chain(activation.avm2().classes().iexternalizable);
+        let dynamic = !class.inner_class_definition().read().is_sealed();
+
+        // Superclass properties are written before subclass properties.
+        let mut chain = Vec::new();
+        let mut current = Some(class);
+        while let Some(cls) = current {
+            if !Object::ptr_eq(cls, object_class) {
+                chain.push(cls);
+            }
+            current = cls.superclass_object();
+        }
+
+        let mut sealed = Vec::new();
+        let mut getters = Vec::new();
+        let mut setters = Vec::new();
+        for cls in chain.into_iter().rev() {
+            let class_def = cls.inner_class_definition();
+            for instance_trait in class_def.read().instance_traits() {
+                let name = instance_trait.name();
+                if !name.namespace().is_public() {
+                    continue;
+                }
+
+                let local_name = name.local_name();
+                match instance_trait.kind() {
+                    TraitKind::Slot { .. } if !sealed.contains(&local_name) => {
+                        sealed.push(local_name)
+                    }
+                    TraitKind::Getter { .. } => getters.push(local_name),
+                    TraitKind::Setter { .. } => setters.push(local_name),
+                    _ => {}
+                }
+            }
+        }
+
+        // Read-only and write-only accessors can't be round-tripped, so only
+        // properties with both a getter and a setter are serialized.
+        for getter in getters {
+            if setters.contains(&getter) && !sealed.contains(&getter) {
+                sealed.push(getter);
+            }
+        }
+
+        Self {
+            name,
+            externalizable,
+            dynamic,
+            sealed,
+        }
+    }
+}
+
+/// The kind of element a `Vector` is written as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum VectorKind {
+    Int,
+    UInt,
+    Double,
+    Object,
+}
+
+/// How an object is written out.
+enum ObjectKind<'gc> {
+    /// Functions and display objects, which are written as `undefined`.
+    Unserializable,
+    Array,
+    Date(f64),
+    Xml(AvmString<'gc>),
+    ByteArray,
+    Vector(VectorKind),
+    Dictionary,
+    Object(AmfTraits<'gc>),
+}
+
+impl<'gc> ObjectKind<'gc> {
+    fn of(
+        activation: &mut Activation<'_, 'gc, '_>,
+        object: Object<'gc>,
+    ) -> Result<Self, Error<'gc>> {
+        // TODO: Find a more general rule for which object types should be skipped,
+        // and which turn into undefined.
+        if object.as_executable().is_some() || object.as_display_object().is_some() {
+            return Ok(Self::Unserializable);
+        }
+
+        if object.as_array_storage().is_some() {
+            return Ok(Self::Array);
+        }
+
+        if let Some(date) = object.as_date_object() {
+            let time = date
+                .date_time()
+                .map_or(f64::NAN, |date_time| date_time.timestamp_millis() as f64);
+            return Ok(Self::Date(time));
+        }
+
+        if let Some(xml) = object.as_xml() {
+            let settings = e4x::xml_settings(activation)?;
+            let mc = activation.context.gc_context;
+            return Ok(Self::Xml(xml.node().xml_to_xml_string(mc, &settings)));
+        }
+
+        if let Some(list) = object.as_xml_list() {
+            let settings = e4x::xml_settings(activation)?;
+            let mc = activation.context.gc_context;
+            return Ok(Self::Xml(list.list_to_xml_string(mc, &settings)));
+        }
+
+        if object.as_bytearray().is_some() {
+            return Ok(Self::ByteArray);
+        }
+
+        if let Some(vector) = object.as_vector_storage() {
+            let value_type = vector.value_type();
+            let classes = activation.avm2().classes();
+            let kind = if Object::ptr_eq(value_type, classes.int) {
+                VectorKind::Int
+            } else if Object::ptr_eq(value_type, classes.uint) {
+                VectorKind::UInt
+            } else if Object::ptr_eq(value_type, classes.number) {
+                VectorKind::Double
             } else {
-                Some(AmfValue::Integer(num))
-            }
-        }
-        Value::String(s) => Some(AmfValue::String(s.to_string())),
-        Value::Object(o) => {
-            // TODO: Find a more general rule for which object types should be skipped,
-            // and which turn into undefined.
-            if o.as_executable().is_some() {
-                None
-            } else if o.as_display_object().is_some() {
-                Some(AmfValue::Undefined)
-            } else if let Some(array) = o.as_array_storage() {
-                let mut values = Vec::new();
-                recursive_serialize(activation, o, &mut values, amf_version).unwrap();
-
-                let mut dense = vec![];
-                let mut sparse = vec![];
-                for (i, elem) in (0..array.length()).zip(values.into_iter()) {
-                    if elem.name == i.to_string() {
-                        dense.push(elem.value.clone());
-                    } else {
-                        sparse.push(elem);
+                VectorKind::Object
+            };
+            return Ok(Self::Vector(kind));
+        }
+
+        if object.as_dictionary_object().is_some() {
+            return Ok(Self::Dictionary);
+        }
+
+        let class = object
+            .instance_of()
+            .unwrap_or_else(|| activation.avm2().classes().object);
+        Ok(Self::Object(AmfTraits::for_class(activation, class)))
+    }
+}
+
+/// The name a class is written under when used as the type of a `Vector`.
+fn vector_type_name<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    class: ClassObject<'gc>,
+) -> AvmString<'gc> {
+    if Object::ptr_eq(class, activation.avm2().classes().object) {
+        return "".into();
+    }
+
+    activation
+        .avm2()
+        .get_alias_by_class(class)
+        .unwrap_or_else(|| {
+            class
+                .inner_class_definition()
+                .read()
+                .name()
+                .to_qualified_name(activation.context.gc_context)
+        })
+}
+
+/// Resolve the type of a `Vector`, as written by `vector_type_name`.
+fn vector_type_class<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    name: AvmString<'gc>,
+) -> ClassObject<'gc> {
+    let object_class = activation.avm2().classes().object;
+    if name.is_empty() {
+        return object_class;
+    }
+
+    if let Some(class) = activation.avm2().get_class_by_alias(name) {
+        return class;
+    }
+
+    let qname = QName::from_qualified_name(name, activation.context.gc_context);
+    activation
+        .caller_domain()
+        .get_defined_value(activation, qname)
+        .ok()
+        .and_then(|value| value.as_object())
+        .and_then(|object| object.as_class_object())
+        .unwrap_or(object_class)
+}
+
+/// Collect the enumerable properties of an object, skipping functions.
+fn dynamic_properties<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<Vec<(AvmString<'gc>, Value<'gc>)>, Error<'gc>> {
+    let mut properties = Vec::new();
+    let mut last_index = object.get_next_enumerant(0, activation)?;
+    while let Some(index) = last_index {
+        let name = object
+            .get_enumerant_name(index, activation)?
+            .coerce_to_string(activation)?;
+        let value = object.get_property(&Multiname::public(name), activation)?;
+
+        if !matches!(value.as_object(), Some(o) if o.as_executable().is_some()) {
+            properties.push((name, value));
+        }
+        last_index = object.get_next_enumerant(index, activation)?;
+    }
+    Ok(properties)
+}
+
+/// The elements of an array, split into its dense portion starting at index
+/// 0 and every other property.
+type SplitArray<'gc> = (Vec<Value<'gc>>, Vec<(AvmString<'gc>, Value<'gc>)>);
+
+fn split_array<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    array: Object<'gc>,
+) -> Result<SplitArray<'gc>, Error<'gc>> {
+    let mut dense = Vec::new();
+    let mut associative = Vec::new();
+    for (name, value) in dynamic_properties(activation, array)? {
+        if associative.is_empty() && name.to_string() == dense.len().to_string() {
+            dense.push(value);
+        } else {
+            associative.push((name, value));
+        }
+    }
+    Ok((dense, associative))
+}
+
+/// Collect the entries of a `Dictionary`, including those with object keys.
+fn dictionary_entries<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<Vec<(Value<'gc>, Value<'gc>)>, Error<'gc>> {
+    let dictionary = object.as_dictionary_object().unwrap();
+    let mut entries = Vec::new();
+    let mut last_index = object.get_next_enumerant(0, activation)?;
+    while let Some(index) = last_index {
+        let key = object.get_enumerant_name(index, activation)?;
+        let value = match key {
+            Value::Object(key) => dictionary.get_property_by_object(key),
+            _ => {
+                let name = key.coerce_to_string(activation)?;
+                object.get_property(&Multiname::public(name), activation)?
+            }
+        };
+        entries.push((key, value));
+        last_index = object.get_next_enumerant(index, activation)?;
+    }
+    Ok(entries)
+}
+
+/// Writes values in AMF0 or AMF3 into a `ByteArray`.
+///
+/// A writer holds the reference tables for a single top-level value, so that
+/// strings, objects and traits that are written more than once are only
+/// stored the first time.
+pub struct AmfWriter<'gc> {
+    /// The `ByteArray` being written to.
+    output: Object<'gc>,
+
+    encoding: ObjectEncoding,
+
+    strings: FnvHashMap<AvmString<'gc>, u32>,
+
+    objects: FnvHashMap<Object<'gc>, u32>,
+
+    traits: FnvHashMap<ClassObject<'gc>, u32>,
+}
+
+impl<'gc> AmfWriter<'gc> {
+    pub fn new(output: Object<'gc>, encoding: ObjectEncoding) -> Self {
+        Self {
+            output,
+            encoding,
+            strings: Default::default(),
+            objects: Default::default(),
+            traits: Default::default(),
+        }
+    }
+
+    fn write_bytes(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        bytes: &[u8],
+    ) -> Result<(), Error<'gc>> {
+        self.output
+            .as_bytearray_mut(activation.context.gc_context)
+            .ok_or("AMF output must be a ByteArray")?
+            .write_bytes(bytes)
+    }
+
+    fn write_u8(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        value: u8,
+    ) -> Result<(), Error<'gc>> {
+        self.write_bytes(activation, &[value])
+    }
+
+    fn write_u16(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        value: u16,
+    ) -> Result<(), Error<'gc>> {
+        self.write_bytes(activation, &value.to_be_bytes())
+    }
+
+    fn write_u32(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        value: u32,
+    ) -> Result<(), Error<'gc>> {
+        self.write_bytes(activation, &value.to_be_bytes())
+    }
+
+    fn write_f64(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        value: f64,
+    ) -> Result<(), Error<'gc>> {
+        self.write_bytes(activation, &value.to_be_bytes())
+    }
+
+    /// Write a variable-length AMF3 `U29` integer.
+    fn write_u29(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        value: u32,
+    ) -> Result<(), Error<'gc>> {
+        let value = value & 0x1FFF_FFFF;
+        if value < 0x80 {
+            self.write_bytes(activation, &[value as u8])
+        } else if value < 0x4000 {
+            self.write_bytes(activation, &[(value >> 7) as u8 | 0x80, value as u8 & 0x7F])
+        } else if value < 0x20_0000 {
+            self.write_bytes(
+                activation,
+                &[
+                    (value >> 14) as u8 | 0x80,
+                    (value >> 7) as u8 | 0x80,
+                    value as u8 & 0x7F,
+                ],
+            )
+        } else {
+            self.write_bytes(
+                activation,
+                &[
+                    (value >> 22) as u8 | 0x80,
+                    (value >> 15) as u8 | 0x80,
+                    (value >> 8) as u8 | 0x80,
+                    value as u8,
+                ],
+            )
+        }
+    }
+
+    /// Write the length of an inline AMF3 value, which shares its `U29` with
+    /// the flag distinguishing it from a reference.
+    fn write_inline_length(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        length: usize,
+    ) -> Result<(), Error<'gc>> {
+        let length = u32::try_from(length)
+            .ok()
+            .filter(|length| *length < (1 << 28))
+            .ok_or("RangeError: Value is too large to be serialized")?;
+        self.write_u29(activation, (length << 1) | 1)
+    }
+
+    /// Write a string without a type marker, as used for property names.
+    pub fn write_string(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        string: AvmString<'gc>,
+    ) -> Result<(), Error<'gc>> {
+        match self.encoding {
+            ObjectEncoding::Amf0 => {
+                let bytes = string.to_utf8_lossy();
+                let length = u16::try_from(bytes.len())
+                    .map_err(|_| "RangeError: UTF String length must fit into a short")?;
+                self.write_u16(activation, length)?;
+                self.write_bytes(activation, bytes.as_bytes())
+            }
+            ObjectEncoding::Amf3 => {
+                // The empty string is never sent by reference.
+                if string.is_empty() {
+                    return self.write_u29(activation, 1);
+                }
+
+                if let Some(index) = self.strings.get(&string) {
+                    return self.write_u29(activation, index << 1);
+                }
+                self.strings.insert(string, self.strings.len() as u32);
+
+                let bytes = string.to_utf8_lossy();
+                self.write_inline_length(activation, bytes.len())?;
+                self.write_bytes(activation, bytes.as_bytes())
+            }
+        }
+    }
+
+    /// Write a value, including its type marker.
+    pub fn write_value(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        value: Value<'gc>,
+    ) -> Result<(), Error<'gc>> {
+        match self.encoding {
+            ObjectEncoding::Amf0 => self.write_amf0_value(activation, value),
+            ObjectEncoding::Amf3 => self.write_amf3_value(activation, value),
+        }
+    }
+
+    fn write_amf0_value(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        value: Value<'gc>,
+    ) -> Result<(), Error<'gc>> {
+        match value {
+            Value::Undefined => self.write_u8(activation, amf0::UNDEFINED),
+            Value::Null => self.write_u8(activation, amf0::NULL),
+            Value::Bool(b) => {
+                self.write_u8(activation, amf0::BOOLEAN)?;
+                self.write_u8(activation, b as u8)
+            }
+            // Integers are unsupported in AMF0, and must be converted to Number
+            // regardless of whether it can be represented as an integer.
+            Value::Integer(i) => {
+                self.write_u8(activation, amf0::NUMBER)?;
+                self.write_f64(activation, i as f64)
+            }
+            Value::Number(n) => {
+                self.write_u8(activation, amf0::NUMBER)?;
+                self.write_f64(activation, n)
+            }
+            Value::String(s) => {
+                let bytes = s.to_utf8_lossy();
+                if let Ok(length) = u16::try_from(bytes.len()) {
+                    self.write_u8(activation, amf0::STRING)?;
+                    self.write_u16(activation, length)?;
+                } else {
+                    let length = u32::try_from(bytes.len())
+                        .map_err(|_| "RangeError: Value is too large to be serialized")?;
+                    self.write_u8(activation, amf0::LONG_STRING)?;
+                    self.write_u32(activation, length)?;
+                }
+                self.write_bytes(activation, bytes.as_bytes())
+            }
+            Value::Object(o) => self.write_amf0_object(activation, o),
+        }
+    }
+
+    fn write_amf0_object(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        object: Object<'gc>,
+    ) -> Result<(), Error<'gc>> {
+        let traits = match ObjectKind::of(activation, object)? {
+            ObjectKind::Unserializable => return self.write_u8(activation, amf0::UNDEFINED),
+            ObjectKind::Date(time) => {
+                self.write_u8(activation, amf0::DATE)?;
+                self.write_f64(activation, time)?;
+                // Time zone, which is unused.
+                return self.write_u16(activation, 0);
+            }
+            ObjectKind::Xml(xml) => {
+                let bytes = xml.to_utf8_lossy();
+                let length = u32::try_from(bytes.len())
+                    .map_err(|_| "RangeError: Value is too large to be serialized")?;
+                self.write_u8(activation, amf0::XML_DOCUMENT)?;
+                self.write_u32(activation, length)?;
+                return self.write_bytes(activation, bytes.as_bytes());
+            }
+            // Types that only exist in AMF3 are written by switching encodings,
+            // which starts over with fresh reference tables.
+            ObjectKind::ByteArray | ObjectKind::Vector(_) | ObjectKind::Dictionary => None,
+            ObjectKind::Object(traits) if traits.externalizable => None,
+            ObjectKind::Array => Some(None),
+            ObjectKind::Object(traits) => Some(Some(traits)),
+        };
+
+        let traits = match traits {
+            Some(traits) => traits,
+            None => {
+                self.write_u8(activation, amf0::AVMPLUS)?;
+                let mut writer = AmfWriter::new(self.output, ObjectEncoding::Amf3);
+                return writer.write_amf3_object(activation, object);
+            }
+        };
+
+        if let Some(index) = self.objects.get(&object) {
+            if let Ok(index) = u16::try_from(*index) {
+                self.write_u8(activation, amf0::REFERENCE)?;
+                return self.write_u16(activation, index);
+            }
+        }
+        self.objects.insert(object, self.objects.len() as u32);
+
+        let properties = match traits {
+            None => {
+                let length = object.as_array_storage().unwrap().length();
+                let (dense, associative) = split_array(activation, object)?;
+                if associative.is_empty() && dense.len() == length {
+                    self.write_u8(activation, amf0::STRICT_ARRAY)?;
+                    self.write_u32(activation, dense.len() as u32)?;
+                    for value in dense {
+                        self.write_amf0_value(activation, value)?;
                     }
+                    return Ok(());
                 }
 
-                if sparse.is_empty() {
-                    Some(AmfValue::StrictArray(dense))
+                self.write_u8(activation, amf0::ECMA_ARRAY)?;
+                self.write_u32(activation, length as u32)?;
+                dynamic_properties(activation, object)?
+            }
+            Some(traits) => {
+                if traits.name.is_empty() {
+                    self.write_u8(activation, amf0::OBJECT)?;
                 } else {
-                    let len = sparse.len() as u32;
-                    Some(AmfValue::ECMAArray(dense, sparse, len))
+                    self.write_u8(activation, amf0::TYPED_OBJECT)?;
+                    self.write_string(activation, traits.name)?;
                 }
-            } else if let Some(date) = o.as_date_object() {
-                date.date_time()
-                    .map(|date_time| AmfValue::Date(date_time.timestamp_millis() as f64, None))
-            } else {
-                let is_object = o
+
+                let mut properties = Vec::with_capacity(traits.sealed.len());
+                for name in traits.sealed {
+                    let value = object.get_property(&Multiname::public(name), activation)?;
+                    properties.push((name, value));
+                }
+                if traits.dynamic {
+                    properties.extend(dynamic_properties(activation, object)?);
+                }
+                properties
+            }
+        };
+
+        for (name, value) in properties {
+            self.write_string(activation, name)?;
+            self.write_amf0_value(activation, value)?;
+        }
+        self.write_string(activation, "".into())?;
+        self.write_u8(activation, amf0::OBJECT_END)
+    }
+
+    fn write_amf3_value(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        value: Value<'gc>,
+    ) -> Result<(), Error<'gc>> {
+        match value {
+            Value::Undefined => self.write_u8(activation, amf3::UNDEFINED),
+            Value::Null => self.write_u8(activation, amf3::NULL),
+            Value::Bool(false) => self.write_u8(activation, amf3::FALSE),
+            Value::Bool(true) => self.write_u8(activation, amf3::TRUE),
+            // NOTE - we should really be converting `Value::Integer` to `Value::Number`
+            // whenever it's outside this range, instead of performing this during AMF serialization.
+            Value::Integer(i) if (AMF3_INTEGER_MIN..=AMF3_INTEGER_MAX).contains(&i) => {
+                self.write_u8(activation, amf3::INTEGER)?;
+                self.write_u29(activation, i as u32)
+            }
+            Value::Integer(i) => {
+                self.write_u8(activation, amf3::DOUBLE)?;
+                self.write_f64(activation, i as f64)
+            }
+            Value::Number(n) => {
+                self.write_u8(activation, amf3::DOUBLE)?;
+                self.write_f64(activation, n)
+            }
+            Value::String(s) => {
+                self.write_u8(activation, amf3::STRING)?;
+                self.write_string(activation, s)
+            }
+            Value::Object(o) => self.write_amf3_object(activation, o),
+        }
+    }
+
+    fn write_amf3_object(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        object: Object<'gc>,
+    ) -> Result<(), Error<'gc>> {
+        let kind = ObjectKind::of(activation, object)?;
+        let marker = match &kind {
+            ObjectKind::Unserializable => return self.write_u8(activation, amf3::UNDEFINED),
+            ObjectKind::Array => amf3::ARRAY,
+            ObjectKind::Date(_) => amf3::DATE,
+            ObjectKind::Xml(_) => amf3::XML,
+            ObjectKind::ByteArray => amf3::BYTE_ARRAY,
+            ObjectKind::Vector(VectorKind::Int) => amf3::VECTOR_INT,
+            ObjectKind::Vector(VectorKind::UInt) => amf3::VECTOR_UINT,
+            ObjectKind::Vector(VectorKind::Double) => amf3::VECTOR_DOUBLE,
+            ObjectKind::Vector(VectorKind::Object) => amf3::VECTOR_OBJECT,
+            ObjectKind::Dictionary => amf3::DICTIONARY,
+            ObjectKind::Object(_) => amf3::OBJECT,
+        };
+        self.write_u8(activation, marker)?;
+
+        if let Some(index) = self.objects.get(&object) {
+            return self.write_u29(activation, index << 1);
+        }
+        self.objects.insert(object, self.objects.len() as u32);
+
+        match kind {
+            ObjectKind::Unserializable => unreachable!(),
+            ObjectKind::Array => {
+                let (dense, associative) = split_array(activation, object)?;
+                self.write_inline_length(activation, dense.len())?;
+                for (name, value) in associative {
+                    self.write_string(activation, name)?;
+                    self.write_amf3_value(activation, value)?;
+                }
+                self.write_string(activation, "".into())?;
+                for value in dense {
+                    self.write_amf3_value(activation, value)?;
+                }
+                Ok(())
+            }
+            ObjectKind::Date(time) => {
+                self.write_u29(activation, 1)?;
+                self.write_f64(activation, time)
+            }
+            ObjectKind::Xml(xml) => {
+                let bytes = xml.to_utf8_lossy();
+                self.write_inline_length(activation, bytes.len())?;
+                self.write_bytes(activation, bytes.as_bytes())
+            }
+            ObjectKind::ByteArray => {
+                let bytes = object.as_bytearray().unwrap().bytes().to_vec();
+                self.write_inline_length(activation, bytes.len())?;
+                self.write_bytes(activation, &bytes)
+            }
+            ObjectKind::Vector(kind) => {
+                let (values, is_fixed, value_type) = {
+                    let vector = object.as_vector_storage().unwrap();
+                    let values: Vec<_> = vector.iter().collect();
+                    (values, vector.is_fixed(), vector.value_type())
+                };
+                self.write_inline_length(activation, values.len())?;
+                self.write_u8(activation, is_fixed as u8)?;
+                match kind {
+                    VectorKind::Int => {
+                        for value in values {
+                            let value = value.coerce_to_i32(activation)?;
+                            self.write_bytes(activation, &value.to_be_bytes())?;
+                        }
+                    }
+                    VectorKind::UInt => {
+                        for value in values {
+                            let value = value.coerce_to_u32(activation)?;
+                            self.write_u32(activation, value)?;
+                        }
+                    }
+                    VectorKind::Double => {
+                        for value in values {
+                            let value = value.coerce_to_number(activation)?;
+                            self.write_f64(activation, value)?;
+                        }
+                    }
+                    VectorKind::Object => {
+                        let type_name = vector_type_name(activation, value_type);
+                        self.write_string(activation, type_name)?;
+                        for value in values {
+                            self.write_amf3_value(activation, value)?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+            ObjectKind::Dictionary => {
+                let entries = dictionary_entries(activation, object)?;
+                self.write_inline_length(activation, entries.len())?;
+                // Weak keys are not implemented, so they are never written.
+                self.write_u8(activation, 0)?;
+                for (key, value) in entries {
+                    self.write_amf3_value(activation, key)?;
+                    self.write_amf3_value(activation, value)?;
+                }
+                Ok(())
+            }
+            ObjectKind::Object(traits) => {
+                let class = object
                     .instance_of()
-                    .map_or(false, |c| c == activation.avm2().classes().object);
-                if is_object {
-                    let mut object_body = Vec::new();
-                    recursive_serialize(activation, o, &mut object_body, amf_version).unwrap();
-                    Some(AmfValue::Object(
-                        object_body,
-                        Some(ClassDefinition {
-                            name: "".to_string(),
-                            attributes: EnumSet::only(Attribute::Dynamic),
-                            static_properties: Vec::new(),
-                        }),
-                    ))
+                    .unwrap_or_else(|| activation.avm2().classes().object);
+                if let Some(index) = self.traits.get(&class) {
+                    self.write_u29(activation, (index << 2) | 1)?;
                 } else {
-                    log::warn!(
-                        "Serialization is not implemented for class other than Object: {:?}",
-                        o
-                    );
-                    None
+                    self.traits.insert(class, self.traits.len() as u32);
+
+                    let count = u32::try_from(traits.sealed.len())
+                        .ok()
+                        .filter(|count| *count < (1 << 25))
+                        .ok_or("RangeError: Value is too large to be serialized")?;
+                    let flags = 0b11
+                        | (traits.externalizable as u32) << 2
+                        | (traits.dynamic as u32) << 3
+                        | count << 4;
+                    self.write_u29(activation, flags)?;
+                    self.write_string(activation, traits.name)?;
+                    for name in &traits.sealed {
+                        self.write_string(activation, *name)?;
+                    }
                 }
+
+                if traits.externalizable {
+                    object.call_property(
+                        &Multiname::public("writeExternal"),
+                        &[self.output.into()],
+                        activation,
+                    )?;
+                    return Ok(());
+                }
+
+                for name in traits.sealed {
+                    let value = object.get_property(&Multiname::public(name), activation)?;
+                    self.write_amf3_value(activation, value)?;
+                }
+                if traits.dynamic {
+                    for (name, value) in dynamic_properties(activation, object)? {
+                        self.write_string(activation, name)?;
+                        self.write_amf3_value(activation, value)?;
+                    }
+                    self.write_string(activation, "".into())?;
+                }
+                Ok(())
             }
         }
     }
 }
 
-/// Serialize an Object and any children to a AMF object
-pub fn recursive_serialize<'gc>(
-    activation: &mut Activation<'_, 'gc, '_>,
-    obj: Object<'gc>,
-    elements: &mut Vec<Element>,
-    amf_version: AMFVersion,
-) -> Result<(), Error<'gc>> {
-    let mut last_index = obj.get_next_enumerant(0, activation)?;
-    while let Some(index) = last_index {
-        let name = obj
-            .get_enumerant_name(index, activation)?
-            .coerce_to_string(activation)?;
-        let value = obj.get_property(&Multiname::public(name), activation)?;
+/// Reads values in AMF0 or AMF3 from a `ByteArray`.
+///
+/// As with `AmfWriter`, a reader holds the reference tables for a single
+/// top-level value.
+pub struct AmfReader<'gc> {
+    /// The `ByteArray` being read from.
+    input: Object<'gc>,
+
+    encoding: ObjectEncoding,
+
+    strings: Vec<AvmString<'gc>>,
+
+    objects: Vec<Object<'gc>>,
+
+    traits: Vec<AmfTraits<'gc>>,
+}
+
+impl<'gc> AmfReader<'gc> {
+    pub fn new(input: Object<'gc>, encoding: ObjectEncoding) -> Self {
+        Self {
+            input,
+            encoding,
+            strings: Vec::new(),
+            objects: Vec::new(),
+            traits: Vec::new(),
+        }
+    }
+
+    fn read_bytes(&self, length: usize) -> Result<Vec<u8>, Error<'gc>> {
+        Ok(self
+            .input
+            .as_bytearray()
+            .ok_or("AMF input must be a ByteArray")?
+            .read_bytes(length)?
+            .to_vec())
+    }
+
+    fn read_u8(&self) -> Result<u8, Error<'gc>> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u16(&self) -> Result<u16, Error<'gc>> {
+        let bytes = self.read_bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
 
-        if let Some(value) = serialize_value(activation, value, amf_version) {
-            elements.push(Element::new(name.to_utf8_lossy(), value));
+    fn read_u32(&self) -> Result<u32, Error<'gc>> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_f64(&self) -> Result<f64, Error<'gc>> {
+        let bytes = self.read_bytes(8)?;
+        Ok(f64::from_be_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Read a variable-length AMF3 `U29` integer.
+    fn read_u29(&self) -> Result<u32, Error<'gc>> {
+        let mut value = 0;
+        for _ in 0..3 {
+            let byte = self.read_u8()?;
+            value = (value << 7) | (byte & 0x7F) as u32;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
         }
-        last_index = obj.get_next_enumerant(index, activation)?;
+        Ok((value << 8) | self.read_u8()? as u32)
+    }
+
+    fn read_utf8(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        length: usize,
+    ) -> Result<AvmString<'gc>, Error<'gc>> {
+        let bytes = self.read_bytes(length)?;
+        Ok(AvmString::new_utf8_bytes(
+            activation.context.gc_context,
+            &bytes,
+        ))
+    }
+
+    /// Look up a previously read object, for an AMF3 value sent by reference.
+    fn object_reference(&self, index: u32) -> Result<Value<'gc>, Error<'gc>> {
+        self.objects
+            .get(index as usize)
+            .map(|object| (*object).into())
+            .ok_or_else(|| "Error: Invalid AMF object reference".into())
+    }
+
+    /// Read a string without a type marker, as used for property names.
+    pub fn read_string(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<AvmString<'gc>, Error<'gc>> {
+        match self.encoding {
+            ObjectEncoding::Amf0 => {
+                let length = self.read_u16()?;
+                self.read_utf8(activation, length.into())
+            }
+            ObjectEncoding::Amf3 => {
+                let header = self.read_u29()?;
+                if header & 1 == 0 {
+                    return self
+                        .strings
+                        .get((header >> 1) as usize)
+                        .copied()
+                        .ok_or_else(|| "Error: Invalid AMF string reference".into());
+                }
+
+                let length = (header >> 1) as usize;
+                if length == 0 {
+                    return Ok("".into());
+                }
+
+                let string = self.read_utf8(activation, length)?;
+                self.strings.push(string);
+                Ok(string)
+            }
+        }
+    }
+
+    /// Read a value, including its type marker.
+    pub fn read_value(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        match self.encoding {
+            ObjectEncoding::Amf0 => self.read_amf0_value(activation),
+            ObjectEncoding::Amf3 => self.read_amf3_value(activation),
+        }
+    }
+
+    /// Read the properties of an AMF0 object, up to the end marker.
+    fn read_amf0_properties(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        mut object: Object<'gc>,
+    ) -> Result<(), Error<'gc>> {
+        loop {
+            let name = self.read_string(activation)?;
+            if name.is_empty() {
+                return match self.read_u8()? {
+                    amf0::OBJECT_END => Ok(()),
+                    _ => Err("Error: Invalid AMF0 object".into()),
+                };
+            }
+
+            let value = self.read_amf0_value(activation)?;
+            object.set_property(&Multiname::public(name), value, activation)?;
+        }
+    }
+
+    fn read_amf0_value(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        Ok(match self.read_u8()? {
+            amf0::NUMBER => self.read_f64()?.into(),
+            amf0::BOOLEAN => (self.read_u8()? != 0).into(),
+            amf0::STRING => {
+                let length = self.read_u16()?;
+                self.read_utf8(activation, length.into())?.into()
+            }
+            amf0::LONG_STRING => {
+                let length = self.read_u32()?;
+                self.read_utf8(activation, length as usize)?.into()
+            }
+            amf0::NULL => Value::Null,
+            amf0::UNDEFINED | amf0::UNSUPPORTED => Value::Undefined,
+            amf0::REFERENCE => {
+                let index = self.read_u16()?;
+                self.object_reference(index.into())?
+            }
+            amf0::OBJECT => {
+                let object = activation
+                    .avm2()
+                    .classes()
+                    .object
+                    .construct(activation, &[])?;
+                self.objects.push(object);
+                self.read_amf0_properties(activation, object)?;
+                object.into()
+            }
+            amf0::TYPED_OBJECT => {
+                let name = self.read_string(activation)?;
+                let class = activation
+                    .avm2()
+                    .get_class_by_alias(name)
+                    .unwrap_or_else(|| activation.avm2().classes().object);
+                let object = class.construct(activation, &[])?;
+                self.objects.push(object);
+                self.read_amf0_properties(activation, object)?;
+                object.into()
+            }
+            amf0::ECMA_ARRAY => {
+                // The length is implied by the elements that follow.
+                self.read_u32()?;
+                let array = ArrayObject::empty(activation)?;
+                self.objects.push(array);
+                self.read_amf0_properties(activation, array)?;
+                array.into()
+            }
+            amf0::STRICT_ARRAY => {
+                let length = self.read_u32()?;
+                let array = ArrayObject::empty(activation)?;
+                self.objects.push(array);
+                for index in 0..length as usize {
+                    let value = self.read_amf0_value(activation)?;
+                    array
+                        .as_array_storage_mut(activation.context.gc_context)
+                        .unwrap()
+                        .set(index, value);
+                }
+                array.into()
+            }
+            amf0::DATE => {
+                let time = self.read_f64()?;
+                // Time zone, which is unused.
+                self.read_u16()?;
+                activation
+                    .avm2()
+                    .classes()
+                    .date
+                    .construct(activation, &[time.into()])?
+                    .into()
+            }
+            amf0::XML_DOCUMENT => {
+                let length = self.read_u32()?;
+                let xml = self.read_utf8(activation, length as usize)?;
+                activation
+                    .avm2()
+                    .classes()
+                    .xml
+                    .construct(activation, &[xml.into()])?
+                    .into()
+            }
+            amf0::AVMPLUS => {
+                let mut reader = AmfReader::new(self.input, ObjectEncoding::Amf3);
+                reader.read_amf3_value(activation)?
+            }
+            marker => return Err(format!("Error: Unknown AMF0 type marker {marker}").into()),
+        })
+    }
+
+    fn read_amf3_value(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let marker = self.read_u8()?;
+        match marker {
+            amf3::UNDEFINED => return Ok(Value::Undefined),
+            amf3::NULL => return Ok(Value::Null),
+            amf3::FALSE => return Ok(false.into()),
+            amf3::TRUE => return Ok(true.into()),
+            amf3::INTEGER => {
+                // Sign-extend the 29-bit integer.
+                let value = ((self.read_u29()? << 3) as i32) >> 3;
+                return Ok(value.into());
+            }
+            amf3::DOUBLE => return Ok(self.read_f64()?.into()),
+            amf3::STRING => return Ok(self.read_string(activation)?.into()),
+            _ => {}
+        }
+
+        // Every other type can be sent by reference.
+        let header = self.read_u29()?;
+        if header & 1 == 0 {
+            return self.object_reference(header >> 1);
+        }
+        let length = (header >> 1) as usize;
+
+        let object = match marker {
+            amf3::XML | amf3::XML_DOCUMENT => {
+                let xml = self.read_utf8(activation, length)?;
+                let object = activation
+                    .avm2()
+                    .classes()
+                    .xml
+                    .construct(activation, &[xml.into()])?;
+                self.objects.push(object);
+                object
+            }
+            amf3::DATE => {
+                let time = self.read_f64()?;
+                let object = activation
+                    .avm2()
+                    .classes()
+                    .date
+                    .construct(activation, &[time.into()])?;
+                self.objects.push(object);
+                object
+            }
+            amf3::ARRAY => {
+                let mut array = ArrayObject::empty(activation)?;
+                self.objects.push(array);
+                loop {
+                    let name = self.read_string(activation)?;
+                    if name.is_empty() {
+                        break;
+                    }
+                    let value = self.read_amf3_value(activation)?;
+                    array.set_property(&Multiname::public(name), value, activation)?;
+                }
+                for index in 0..length {
+                    let value = self.read_amf3_value(activation)?;
+                    array
+                        .as_array_storage_mut(activation.context.gc_context)
+                        .unwrap()
+                        .set(index, value);
+                }
+                array
+            }
+            amf3::OBJECT => self.read_amf3_object(activation, header)?,
+            amf3::BYTE_ARRAY => {
+                let bytes = self.read_bytes(length)?;
+                let storage = ByteArrayStorage::from_vec(bytes);
+                let object = ByteArrayObject::from_storage(activation, storage)?;
+                self.objects.push(object);
+                object
+            }
+            amf3::VECTOR_INT | amf3::VECTOR_UINT | amf3::VECTOR_DOUBLE | amf3::VECTOR_OBJECT => {
+                let is_fixed = self.read_u8()? != 0;
+                let classes = activation.avm2().classes();
+                let value_type = match marker {
+                    amf3::VECTOR_INT => classes.int,
+                    amf3::VECTOR_UINT => classes.uint,
+                    amf3::VECTOR_DOUBLE => classes.number,
+                    _ => {
+                        let type_name = self.read_string(activation)?;
+                        vector_type_class(activation, type_name)
+                    }
+                };
+
+                // The vector is registered before its contents are read, so
+                // that it can contain references to itself.
+                let storage = VectorStorage::new(0, false, value_type, activation);
+                let vector = VectorObject::from_vector(storage, activation)?;
+                self.objects.push(vector);
+
+                let mut values = Vec::new();
+                for _ in 0..length {
+                    let value = match marker {
+                        amf3::VECTOR_INT => (self.read_u32()? as i32).into(),
+                        amf3::VECTOR_UINT => self.read_u32()?.into(),
+                        amf3::VECTOR_DOUBLE => self.read_f64()?.into(),
+                        _ => self
+                            .read_amf3_value(activation)?
+                            .coerce_to_type(activation, value_type)?,
+                    };
+                    values.push(value);
+                }
+
+                let mut storage = vector
+                    .as_vector_storage_mut(activation.context.gc_context)
+                    .unwrap();
+                storage.replace_storage(values);
+                storage.set_is_fixed(is_fixed);
+                drop(storage);
+                vector
+            }
+            amf3::DICTIONARY => {
+                // Weak keys are not implemented.
+                self.read_u8()?;
+                let mut dictionary = activation
+                    .avm2()
+                    .classes()
+                    .dictionary
+                    .construct(activation, &[])?;
+                self.objects.push(dictionary);
+                for _ in 0..length {
+                    let key = self.read_amf3_value(activation)?;
+                    let value = self.read_amf3_value(activation)?;
+                    match key {
+                        Value::Object(key) => dictionary
+                            .as_dictionary_object()
+                            .unwrap()
+                            .set_property_by_object(key, value, activation.context.gc_context),
+                        _ => {
+                            let name = key.coerce_to_string(activation)?;
+                            dictionary.set_property(&Multiname::public(name), value, activation)?;
+                        }
+                    }
+                }
+                dictionary
+            }
+            marker => return Err(format!("Error: Unknown AMF3 type marker {marker}").into()),
+        };
+
+        Ok(object.into())
+    }
+
+    fn read_amf3_object(
+        &mut self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        header: u32,
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        let traits = if header & 0b10 == 0 {
+            self.traits
+                .get((header >> 2) as usize)
+                .cloned()
+                .ok_or("Error: Invalid AMF traits reference")?
+        } else {
+            let name = self.read_string(activation)?;
+            let mut sealed = Vec::new();
+            for _ in 0..header >> 4 {
+                sealed.push(self.read_string(activation)?);
+            }
+            let traits = AmfTraits {
+                name,
+                externalizable: header & 0b100 != 0,
+                dynamic: header & 0b1000 != 0,
+                sealed,
+            };
+            self.traits.push(traits.clone());
+            traits
+        };
+
+        let class = if traits.name.is_empty() {
+            None
+        } else {
+            activation.avm2().get_class_by_alias(traits.name)
+        };
+        let class = match class {
+            Some(class) => class,
+            None if traits.externalizable => {
+                return Err(format!(
+                    "ReferenceError: Error #1065: Variable {} is not defined.",
+                    traits.name
+                )
+                .into())
+            }
+            None => activation.avm2().classes().object,
+        };
+
+        let mut object = class.construct(activation, &[])?;
+        self.objects.push(object);
+
+        if traits.externalizable {
+            if !class.has_class_in_chain(activation.avm2().classes().iexternalizable) {
+                return Err(format!(
+                    "ArgumentError: Error #2173: Unable to read object in stream. The class {} does not implement flash.utils.IExternalizable but is aliased to an externalizable class.",
+                    traits.name
+                )
+                .into());
+            }
+
+            object.call_property(
+                &Multiname::public("readExternal"),
+                &[self.input.into()],
+                activation,
+            )?;
+            return Ok(object);
+        }
+
+        for name in traits.sealed {
+            let value = self.read_amf3_value(activation)?;
+            object.set_property(&Multiname::public(name), value, activation)?;
+        }
+
+        if traits.dynamic {
+            loop {
+                let name = self.read_string(activation)?;
+                if name.is_empty() {
+                    break;
+                }
+                let value = self.read_amf3_value(activation)?;
+                object.set_property(&Multiname::public(name), value, activation)?;
+            }
+        }
+
+        Ok(object)
     }
-    Ok(())
 }
 
-/// Deserialize a AmfValue to a Value
-pub fn deserialize_value<'gc>(
+/// Serialize the enumerable properties of an object into the `.sol` format
+/// used by shared objects.
+pub fn serialize_lso<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
-    val: &AmfValue,
-) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(match val {
-        AmfValue::Null => Value::Null,
-        AmfValue::Undefined => Value::Undefined,
-        AmfValue::Number(f) => (*f).into(),
-        AmfValue::Integer(num) => (*num).into(),
-        AmfValue::String(s) => Value::String(AvmString::new_utf8(activation.context.gc_context, s)),
-        AmfValue::Bool(b) => (*b).into(),
-        AmfValue::ByteArray(bytes) => {
-            let storage = ByteArrayStorage::from_vec(bytes.clone());
-            let bytearray = ByteArrayObject::from_storage(activation, storage)?;
-            bytearray.into()
-        }
-        AmfValue::ECMAArray(values, elements, _) => {
-            // First let's create an array out of `values` (dense portion), then we add the elements onto it.
-            let mut arr: Vec<Option<Value<'gc>>> = Vec::with_capacity(values.len());
-            for value in values {
-                arr.push(Some(deserialize_value(activation, value)?));
-            }
-            let storage = ArrayStorage::from_storage(arr);
-            let mut array = ArrayObject::from_storage(activation, storage)?;
-            // Now let's add each element as a property
-            for element in elements {
-                array.set_property(
-                    &Multiname::public(AvmString::new_utf8(
-                        activation.context.gc_context,
-                        element.name(),
-                    )),
-                    deserialize_value(activation, element.value())?,
-                    activation,
-                )?;
-            }
-            array.into()
-        }
-        AmfValue::StrictArray(values) => {
-            let mut arr: Vec<Option<Value<'gc>>> = Vec::with_capacity(values.len());
-            for value in values {
-                arr.push(Some(deserialize_value(activation, value)?));
-            }
-            let storage = ArrayStorage::from_storage(arr);
-            let array = ArrayObject::from_storage(activation, storage)?;
-            array.into()
-        }
-        AmfValue::Object(elements, class) => {
-            if let Some(class) = class {
-                if !class.name.is_empty() && class.name != "Object" {
-                    log::warn!("Deserializing class {:?} is not supported!", class);
-                }
-            }
-
-            let mut obj = activation
-                .avm2()
-                .classes()
-                .object
-                .construct(activation, &[])?;
-            for entry in elements {
-                let value = deserialize_value(activation, entry.value())?;
-                obj.set_property(
-                    &Multiname::public(AvmString::new_utf8(
-                        activation.context.gc_context,
-                        entry.name(),
-                    )),
-                    value,
-                    activation,
-                )?;
-            }
-            obj.into()
-        }
-        AmfValue::Date(time, _) => activation
-            .avm2()
-            .classes()
-            .date
-            .construct(activation, &[(*time).into()])?
-            .into(),
-        AmfValue::XML(content, _) => activation
-            .avm2()
-            .classes()
-            .xml
-            .construct(
-                activation,
-                &[Value::String(AvmString::new_utf8(
-                    activation.context.gc_context,
-                    content,
-                ))],
-            )?
-            .into(),
-        AmfValue::VectorDouble(..)
-        | AmfValue::VectorUInt(..)
-        | AmfValue::VectorInt(..)
-        | AmfValue::VectorObject(..)
-        | AmfValue::Dictionary(..)
-        | AmfValue::Custom(..) => {
-            log::error!("Deserialization not yet implemented: {:?}", val);
-            Value::Undefined
-        }
-        AmfValue::AMF3(val) => deserialize_value(activation, val)?,
-        AmfValue::Unsupported => Value::Undefined,
-    })
-}
-
-/// Deserializes a Lso into an object containing the properties stored
+    name: &str,
+    data: Object<'gc>,
+) -> Result<Vec<u8>, Error<'gc>> {
+    let body = ByteArrayObject::from_storage(activation, ByteArrayStorage::new())?;
+    let mut writer = AmfWriter::new(body, ObjectEncoding::Amf3);
+    for (name, value) in dynamic_properties(activation, data)? {
+        writer.write_string(activation, name)?;
+        writer.write_value(activation, value)?;
+        writer.write_u8(activation, 0)?;
+    }
+    let body = body.as_bytearray().unwrap().bytes().to_vec();
+
+    let name = name.as_bytes();
+    let name_length =
+        u16::try_from(name.len()).map_err(|_| "Error: Shared object name is too long")?;
+    let length = LSO_SIGNATURE.len() + 2 + name.len() + 4 + body.len();
+
+    let mut bytes = Vec::with_capacity(length + 6);
+    bytes.extend_from_slice(&[0x00, 0xBF]);
+    bytes.extend_from_slice(&(length as u32).to_be_bytes());
+    bytes.extend_from_slice(LSO_SIGNATURE);
+    bytes.extend_from_slice(&name_length.to_be_bytes());
+    bytes.extend_from_slice(name);
+    bytes.extend_from_slice(&(ObjectEncoding::Amf3 as u32).to_be_bytes());
+    bytes.extend_from_slice(&body);
+    Ok(bytes)
+}
+
+/// Deserializes a `.sol` file into an object containing the properties stored.
+///
+/// Returns `None` if the data is not a shared object.
 pub fn deserialize_lso<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
-    lso: &Lso,
-) -> Result<Object<'gc>, Error<'gc>> {
-    let mut obj = activation
+    bytes: &[u8],
+) -> Result<Option<Object<'gc>>, Error<'gc>> {
+    let header_length = 6 + LSO_SIGNATURE.len();
+    if bytes.len() < header_length + 2
+        || bytes[..2] != [0x00, 0xBF]
+        || &bytes[6..header_length] != LSO_SIGNATURE
+    {
+        return Ok(None);
+    }
+
+    let name_length = u16::from_be_bytes([bytes[header_length], bytes[header_length + 1]]);
+    let version_offset = header_length + 2 + name_length as usize;
+    let encoding = match bytes.get(version_offset..version_offset + 4) {
+        Some([0, 0, 0, 0]) => ObjectEncoding::Amf0,
+        Some([0, 0, 0, 3]) => ObjectEncoding::Amf3,
+        _ => return Ok(None),
+    };
+
+    let body = ByteArrayStorage::from_vec(bytes[version_offset + 4..].to_vec());
+    let body = ByteArrayObject::from_storage(activation, body)?;
+    let mut reader = AmfReader::new(body, encoding);
+
+    let mut data = activation
         .avm2()
         .classes()
         .object
         .construct(activation, &[])?;
-
-    for child in &lso.body {
-        obj.set_property(
-            &Multiname::public(AvmString::new_utf8(
-                activation.context.gc_context,
-                &child.name,
-            )),
-            deserialize_value(activation, child.value())?,
-            activation,
-        )?;
+    while body.as_bytearray().unwrap().bytes_available() > 0 {
+        let name = reader.read_string(activation)?;
+        let value = reader.read_value(activation)?;
+        // Every property is followed by a padding byte.
+        reader.read_u8()?;
+        data.set_property(&Multiname::public(name), value, activation)?;
     }
 
-    Ok(obj)
+    Ok(Some(data))
 }
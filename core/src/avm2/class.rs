@@ -1,6 +1,7 @@
 //! AVM2 classes
 
 use crate::avm2::activation::Activation;
+use crate::avm2::metadata::Metadata;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::object::{ClassObject, Object};
 use crate::avm2::script::TranslationUnit;
@@ -144,6 +145,9 @@ pub struct Class<'gc> {
     /// These are accessed as class object properties.
     class_traits: Vec<Trait<'gc>>,
 
+    /// The metadata tags attached to this class, if any.
+    metadata: Option<Box<[Metadata<'gc>]>>,
+
     /// Whether or not this `Class` has loaded its traits or not.
     traits_loaded: bool,
 
@@ -194,6 +198,7 @@ impl<'gc> Class<'gc> {
                     "<Null specialization constructor>",
                     mc,
                 ),
+                metadata: None,
                 traits_loaded: true,
                 is_system: true,
             },
@@ -332,6 +337,7 @@ impl<'gc> Class<'gc> {
                     "<Null specialization constructor>",
                     activation.context.gc_context,
                 ),
+                metadata: None,
                 traits_loaded: false,
                 is_system: false,
             },
@@ -503,6 +509,7 @@ impl<'gc> Class<'gc> {
                 class_initializer_called: false,
                 call_handler: None,
                 class_traits: Vec::new(),
+                metadata: None,
                 traits_loaded: true,
                 is_system: false,
             },
@@ -824,6 +831,14 @@ impl<'gc> Class<'gc> {
         self.specialized_class_init = specialized_init;
     }
 
+    pub fn metadata(&self) -> &[Metadata<'gc>] {
+        self.metadata.as_deref().unwrap_or_default()
+    }
+
+    pub fn set_metadata(&mut self, metadata: Box<[Metadata<'gc>]>) {
+        self.metadata = Some(metadata);
+    }
+
     pub fn interfaces(&self) -> &[Multiname<'gc>] {
         &self.interfaces
     }
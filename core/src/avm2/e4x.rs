@@ -0,0 +1,1068 @@
+//! The ECMAScript for XML (E4X) object model, shared by the `XML` and `XMLList` classes.
+
+use crate::avm2::activation::Activation;
+use crate::avm2::error::type_error;
+use crate::avm2::object::TObject;
+use crate::avm2::value::Value;
+use crate::avm2::{Error, Multiname, Namespace};
+use crate::string::{AvmString, WStr, WString};
+use gc_arena::{Collect, GcCell, MutationContext};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::fmt::{self, Debug};
+
+/// A node in an E4X tree: an element, attribute, text, comment or
+/// processing instruction.
+///
+/// `XML` objects wrap a single node, and `XMLList` objects wrap a list of nodes.
+/// Nodes are shared between all objects that refer to them, so modifying a node
+/// through one object is visible from all others.
+#[derive(Copy, Clone, Collect)]
+#[collect(no_drop)]
+pub struct E4XNode<'gc>(GcCell<'gc, E4XNodeData<'gc>>);
+
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct E4XNodeData<'gc> {
+    parent: Option<E4XNode<'gc>>,
+
+    /// The namespace of this node's name. `None` if the node is not in a namespace.
+    namespace: Option<E4XNamespace<'gc>>,
+
+    /// The local name of this node. Only elements, attributes and processing
+    /// instructions have a name.
+    local_name: Option<AvmString<'gc>>,
+
+    kind: E4XNodeKind<'gc>,
+}
+
+/// A namespace declared in an XML document.
+#[derive(Copy, Clone, Collect, Debug, PartialEq, Eq)]
+#[collect(no_drop)]
+pub struct E4XNamespace<'gc> {
+    /// The prefix used for this namespace, or `None` for the default namespace.
+    pub prefix: Option<AvmString<'gc>>,
+    pub uri: AvmString<'gc>,
+}
+
+#[derive(Collect, Debug)]
+#[collect(no_drop)]
+pub enum E4XNodeKind<'gc> {
+    Text(AvmString<'gc>),
+    CData(AvmString<'gc>),
+    Comment(AvmString<'gc>),
+    ProcessingInstruction(AvmString<'gc>),
+    Attribute(AvmString<'gc>),
+    Element {
+        /// The namespaces declared on this element with `xmlns` attributes.
+        namespaces: Vec<E4XNamespace<'gc>>,
+        attributes: Vec<E4XNode<'gc>>,
+        children: Vec<E4XNode<'gc>>,
+    },
+}
+
+/// The settings of the `XML` class which affect parsing and serialization.
+#[derive(Copy, Clone, Debug)]
+pub struct E4XSettings {
+    pub ignore_comments: bool,
+    pub ignore_processing_instructions: bool,
+    pub ignore_whitespace: bool,
+    pub pretty_printing: bool,
+    pub pretty_indent: u32,
+}
+
+impl Default for E4XSettings {
+    fn default() -> Self {
+        Self {
+            ignore_comments: true,
+            ignore_processing_instructions: true,
+            ignore_whitespace: true,
+            pretty_printing: true,
+            pretty_indent: 2,
+        }
+    }
+}
+
+/// Read the current settings from the static properties of the `XML` class.
+pub fn xml_settings<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<E4XSettings, Error<'gc>> {
+    let xml_class = activation.avm2().classes().xml;
+    let mut get_flag = |name: &'static str| -> Result<bool, Error<'gc>> {
+        Ok(xml_class
+            .get_property(&Multiname::public(name), activation)?
+            .coerce_to_boolean())
+    };
+    let ignore_comments = get_flag("ignoreComments")?;
+    let ignore_processing_instructions = get_flag("ignoreProcessingInstructions")?;
+    let ignore_whitespace = get_flag("ignoreWhitespace")?;
+    let pretty_printing = get_flag("prettyPrinting")?;
+    let pretty_indent = xml_class
+        .get_property(&Multiname::public("prettyIndent"), activation)?
+        .coerce_to_i32(activation)?;
+
+    Ok(E4XSettings {
+        ignore_comments,
+        ignore_processing_instructions,
+        ignore_whitespace,
+        pretty_printing,
+        pretty_indent: pretty_indent.max(0) as u32,
+    })
+}
+
+impl<'gc> Debug for E4XNode<'gc> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let node = self.0.read();
+        f.debug_struct("E4XNode")
+            .field("namespace", &node.namespace)
+            .field("local_name", &node.local_name)
+            .field("kind", &node.kind)
+            .finish()
+    }
+}
+
+fn is_whitespace(c: u16) -> bool {
+    matches!(u8::try_from(c), Ok(b' ' | b'\t' | b'\r' | b'\n'))
+}
+
+fn malformed_error<'gc>(activation: &mut Activation<'_, 'gc, '_>, message: &str) -> Error<'gc> {
+    match type_error(activation, message, 1090) {
+        Ok(error) => Error::AvmError(error),
+        Err(error) => error,
+    }
+}
+
+fn parser_error<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    error: quick_xml::Error,
+) -> Error<'gc> {
+    log::warn!("XML parser failure: {}", error);
+    malformed_error(
+        activation,
+        "Error #1090: XML parser failure: element is malformed.",
+    )
+}
+
+impl<'gc> E4XNode<'gc> {
+    fn new(
+        mc: MutationContext<'gc, '_>,
+        namespace: Option<E4XNamespace<'gc>>,
+        local_name: Option<AvmString<'gc>>,
+        kind: E4XNodeKind<'gc>,
+    ) -> Self {
+        Self(GcCell::allocate(
+            mc,
+            E4XNodeData {
+                parent: None,
+                namespace,
+                local_name,
+                kind,
+            },
+        ))
+    }
+
+    /// Create a text node.
+    pub fn text(mc: MutationContext<'gc, '_>, text: AvmString<'gc>) -> Self {
+        Self::new(mc, None, None, E4XNodeKind::Text(text))
+    }
+
+    /// Create an element without attributes or children.
+    pub fn element(
+        mc: MutationContext<'gc, '_>,
+        namespace: Option<E4XNamespace<'gc>>,
+        local_name: AvmString<'gc>,
+    ) -> Self {
+        Self::new(
+            mc,
+            namespace,
+            Some(local_name),
+            E4XNodeKind::Element {
+                namespaces: Vec::new(),
+                attributes: Vec::new(),
+                children: Vec::new(),
+            },
+        )
+    }
+
+    /// Create an attribute, which is not yet attached to an element.
+    pub fn attribute(
+        mc: MutationContext<'gc, '_>,
+        namespace: Option<E4XNamespace<'gc>>,
+        local_name: AvmString<'gc>,
+        value: AvmString<'gc>,
+    ) -> Self {
+        Self::new(
+            mc,
+            namespace,
+            Some(local_name),
+            E4XNodeKind::Attribute(value),
+        )
+    }
+
+    /// Parse a string of XML markup into a list of nodes.
+    ///
+    /// The string may contain any number of top-level nodes, which is how both
+    /// `XML` and `XMLList` objects are constructed from strings.
+    pub fn parse(
+        activation: &mut Activation<'_, 'gc, '_>,
+        data: &WStr,
+        settings: &E4XSettings,
+    ) -> Result<Vec<Self>, Error<'gc>> {
+        let data_utf8 = data.to_utf8_lossy();
+        let mut parser = Reader::from_str(&data_utf8);
+        let mut buf = Vec::new();
+        let mut top_level = Vec::new();
+        let mut open_tags: Vec<E4XNode<'gc>> = Vec::new();
+
+        fn push_node<'gc>(
+            mc: MutationContext<'gc, '_>,
+            node: E4XNode<'gc>,
+            open_tags: &[E4XNode<'gc>],
+            top_level: &mut Vec<E4XNode<'gc>>,
+        ) {
+            if let Some(parent) = open_tags.last() {
+                parent.append_child(mc, node);
+            } else {
+                top_level.push(node);
+            }
+        }
+
+        loop {
+            buf.clear();
+            let event = match parser.read_event(&mut buf) {
+                Ok(event) => event,
+                Err(error) => return Err(parser_error(activation, error)),
+            };
+            let mc = activation.context.gc_context;
+
+            match event {
+                Event::Start(bs) => {
+                    let node = Self::from_start_event(activation, &bs, &open_tags)?;
+                    push_node(mc, node, &open_tags, &mut top_level);
+                    open_tags.push(node);
+                }
+                Event::Empty(bs) => {
+                    let node = Self::from_start_event(activation, &bs, &open_tags)?;
+                    push_node(mc, node, &open_tags, &mut top_level);
+                }
+                Event::End(_) => {
+                    open_tags.pop();
+                }
+                Event::Text(bt) => {
+                    let text = match bt.unescaped() {
+                        Ok(text) => AvmString::new_utf8_bytes(mc, &text),
+                        Err(error) => return Err(parser_error(activation, error)),
+                    };
+                    let text = if settings.ignore_whitespace {
+                        let trimmed = text.trim_matches(is_whitespace);
+                        if trimmed.is_empty() {
+                            continue;
+                        }
+                        AvmString::new(mc, trimmed)
+                    } else {
+                        text
+                    };
+                    if !text.is_empty() {
+                        push_node(mc, Self::text(mc, text), &open_tags, &mut top_level);
+                    }
+                }
+                Event::CData(bt) => {
+                    let text = AvmString::new_utf8_bytes(mc, bt.escaped());
+                    let node = Self::new(mc, None, None, E4XNodeKind::CData(text));
+                    push_node(mc, node, &open_tags, &mut top_level);
+                }
+                Event::Comment(bt) if !settings.ignore_comments => {
+                    let text = AvmString::new_utf8_bytes(mc, bt.escaped());
+                    let node = Self::new(mc, None, None, E4XNodeKind::Comment(text));
+                    push_node(mc, node, &open_tags, &mut top_level);
+                }
+                Event::PI(bt) if !settings.ignore_processing_instructions => {
+                    let text = WString::from_utf8_bytes(bt.escaped().to_vec());
+                    let (name, value) = match text.find(is_whitespace) {
+                        Some(index) => {
+                            let (name, value) = text.split_at(index);
+                            (name, value.trim_start_matches(is_whitespace))
+                        }
+                        None => (text.as_wstr(), WStr::empty()),
+                    };
+                    let node = Self::new(
+                        mc,
+                        None,
+                        Some(AvmString::new(mc, name)),
+                        E4XNodeKind::ProcessingInstruction(AvmString::new(mc, value)),
+                    );
+                    push_node(mc, node, &open_tags, &mut top_level);
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        if let Some(unclosed) = open_tags.last() {
+            let name = unclosed.qualified_name();
+            return Err(malformed_error(
+                activation,
+                &format!(
+                    "Error #1085: The element type \"{name}\" must be terminated by the matching end-tag \"</{name}>\"."
+                ),
+            ));
+        }
+
+        Ok(top_level)
+    }
+
+    /// Construct an element (with its attributes and namespace declarations)
+    /// from a `quick_xml` start tag.
+    fn from_start_event(
+        activation: &mut Activation<'_, 'gc, '_>,
+        bs: &BytesStart<'_>,
+        open_tags: &[E4XNode<'gc>],
+    ) -> Result<Self, Error<'gc>> {
+        let mc = activation.context.gc_context;
+
+        let mut namespaces = Vec::new();
+        let mut raw_attributes = Vec::new();
+        for attribute in bs.attributes() {
+            let attribute = match attribute {
+                Ok(attribute) => attribute,
+                Err(error) => return Err(parser_error(activation, error)),
+            };
+            let value = match attribute.unescaped_value() {
+                Ok(value) => AvmString::new_utf8_bytes(mc, &value),
+                Err(error) => return Err(parser_error(activation, error)),
+            };
+
+            if attribute.key == b"xmlns" {
+                namespaces.push(E4XNamespace {
+                    prefix: None,
+                    uri: value,
+                });
+            } else if let Some(prefix) = attribute.key.strip_prefix(b"xmlns:") {
+                namespaces.push(E4XNamespace {
+                    prefix: Some(AvmString::new_utf8_bytes(mc, prefix)),
+                    uri: value,
+                });
+            } else {
+                raw_attributes.push((AvmString::new_utf8_bytes(mc, attribute.key), value));
+            }
+        }
+
+        // Prefixes are resolved with the namespaces declared on this element,
+        // and then with the ones declared on its ancestors.
+        let resolve_prefix = |prefix: Option<&WStr>| -> Option<E4XNamespace<'gc>> {
+            let matches = |ns: &&E4XNamespace<'gc>| ns.prefix.as_deref() == prefix;
+            if let Some(ns) = namespaces.iter().find(matches) {
+                return Some(*ns);
+            }
+            open_tags
+                .iter()
+                .rev()
+                .find_map(|tag| match &tag.0.read().kind {
+                    E4XNodeKind::Element { namespaces, .. } => {
+                        namespaces.iter().find(matches).copied()
+                    }
+                    _ => None,
+                })
+        };
+        let split_name = |name: AvmString<'gc>| match name.find(b':') {
+            Some(index) => {
+                let (prefix, local_name) = name.split_at(index);
+                (
+                    Some(AvmString::new(mc, prefix)),
+                    AvmString::new(mc, &local_name[1..]),
+                )
+            }
+            None => (None, name),
+        };
+
+        let (prefix, local_name) = split_name(AvmString::new_utf8_bytes(mc, bs.name()));
+        let namespace = match prefix {
+            Some(prefix) => match resolve_prefix(Some(&prefix)) {
+                Some(ns) => Some(ns),
+                None => {
+                    return Err(malformed_error(
+                        activation,
+                        &format!(
+                            "Error #1083: The prefix \"{prefix}\" for element \"{local_name}\" is not bound."
+                        ),
+                    ))
+                }
+            },
+            // An empty default namespace means that the element is not in a namespace.
+            None => resolve_prefix(None).filter(|ns| !ns.uri.is_empty()),
+        };
+
+        let node = Self::element(mc, namespace, local_name);
+        for (name, value) in raw_attributes {
+            // Attributes without a prefix are never in a namespace.
+            let (prefix, local_name) = split_name(name);
+            let namespace = match prefix {
+                Some(prefix) => match resolve_prefix(Some(&prefix)) {
+                    Some(ns) => Some(ns),
+                    None => {
+                        return Err(malformed_error(
+                            activation,
+                            &format!(
+                                "Error #1083: The prefix \"{prefix}\" for element \"{local_name}\" is not bound."
+                            ),
+                        ))
+                    }
+                },
+                None => None,
+            };
+            node.set_attribute(mc, Self::attribute(mc, namespace, local_name, value));
+        }
+        if let E4XNodeKind::Element {
+            namespaces: node_namespaces,
+            ..
+        } = &mut node.0.write(mc).kind
+        {
+            *node_namespaces = namespaces;
+        }
+
+        Ok(node)
+    }
+
+    pub fn ptr_eq(self, other: Self) -> bool {
+        GcCell::ptr_eq(self.0, other.0)
+    }
+
+    pub fn kind(&self) -> std::cell::Ref<'_, E4XNodeKind<'gc>> {
+        std::cell::Ref::map(self.0.read(), |node| &node.kind)
+    }
+
+    pub fn parent(self) -> Option<Self> {
+        self.0.read().parent
+    }
+
+    pub fn local_name(self) -> Option<AvmString<'gc>> {
+        self.0.read().local_name
+    }
+
+    pub fn namespace(self) -> Option<E4XNamespace<'gc>> {
+        self.0.read().namespace
+    }
+
+    pub fn set_name(
+        self,
+        mc: MutationContext<'gc, '_>,
+        namespace: Option<E4XNamespace<'gc>>,
+        local_name: AvmString<'gc>,
+    ) {
+        let mut node = self.0.write(mc);
+        node.namespace = namespace;
+        node.local_name = Some(local_name);
+    }
+
+    /// The name of this node, including its namespace prefix.
+    pub fn qualified_name(self) -> WString {
+        let node = self.0.read();
+        let mut name = WString::new();
+        if let Some(prefix) = node.namespace.and_then(|ns| ns.prefix) {
+            name.push_str(&prefix);
+            name.push_byte(b':');
+        }
+        if let Some(local_name) = node.local_name {
+            name.push_str(&local_name);
+        }
+        name
+    }
+
+    pub fn is_element(self) -> bool {
+        matches!(self.0.read().kind, E4XNodeKind::Element { .. })
+    }
+
+    pub fn is_attribute(self) -> bool {
+        matches!(self.0.read().kind, E4XNodeKind::Attribute(_))
+    }
+
+    /// The name of the kind of this node, as returned by `XML.nodeKind`.
+    pub fn node_kind(self) -> &'static str {
+        match self.0.read().kind {
+            E4XNodeKind::Text(_) | E4XNodeKind::CData(_) => "text",
+            E4XNodeKind::Comment(_) => "comment",
+            E4XNodeKind::ProcessingInstruction(_) => "processing-instruction",
+            E4XNodeKind::Attribute(_) => "attribute",
+            E4XNodeKind::Element { .. } => "element",
+        }
+    }
+
+    pub fn children(self) -> Vec<Self> {
+        match &self.0.read().kind {
+            E4XNodeKind::Element { children, .. } => children.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn attributes(self) -> Vec<Self> {
+        match &self.0.read().kind {
+            E4XNodeKind::Element { attributes, .. } => attributes.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The namespaces declared on this element.
+    pub fn namespace_declarations(self) -> Vec<E4XNamespace<'gc>> {
+        match &self.0.read().kind {
+            E4XNodeKind::Element { namespaces, .. } => namespaces.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Find the namespace with the given prefix that is in scope for this node.
+    pub fn resolve_prefix(self, prefix: Option<&WStr>) -> Option<E4XNamespace<'gc>> {
+        let mut node = Some(self);
+        while let Some(current) = node {
+            if let Some(ns) = current
+                .namespace_declarations()
+                .into_iter()
+                .find(|ns| ns.prefix.as_deref() == prefix)
+            {
+                return Some(ns);
+            }
+            node = current.parent();
+        }
+        None
+    }
+
+    /// Find a namespace with the given URI that is in scope for this node.
+    pub fn namespace_for_uri(self, uri: AvmString<'gc>) -> Option<E4XNamespace<'gc>> {
+        let mut node = Some(self);
+        while let Some(current) = node {
+            if let Some(ns) = current
+                .namespace_declarations()
+                .into_iter()
+                .find(|ns| ns.uri == uri)
+            {
+                return Some(ns);
+            }
+            node = current.parent();
+        }
+        None
+    }
+
+    /// The index of this node in its parent's children.
+    pub fn child_index(self) -> Option<usize> {
+        let parent = self.parent()?;
+        let index = parent
+            .children()
+            .iter()
+            .position(|child| child.ptr_eq(self));
+        index
+    }
+
+    /// Detach this node from its parent.
+    pub fn remove(self, mc: MutationContext<'gc, '_>) {
+        if let Some(parent) = self.parent() {
+            if let E4XNodeKind::Element {
+                children,
+                attributes,
+                ..
+            } = &mut parent.0.write(mc).kind
+            {
+                children.retain(|child| !child.ptr_eq(self));
+                attributes.retain(|attribute| !attribute.ptr_eq(self));
+            }
+            self.0.write(mc).parent = None;
+        }
+    }
+
+    /// Insert a node as a child of this element, at the given index.
+    /// The node is first removed from its previous parent, if any.
+    pub fn insert_child(self, mc: MutationContext<'gc, '_>, index: usize, child: Self) {
+        if !self.is_element() {
+            return;
+        }
+        child.remove(mc);
+        if let E4XNodeKind::Element { children, .. } = &mut self.0.write(mc).kind {
+            children.insert(index.min(children.len()), child);
+        }
+        child.0.write(mc).parent = Some(self);
+    }
+
+    pub fn append_child(self, mc: MutationContext<'gc, '_>, child: Self) {
+        let len = self.children().len();
+        self.insert_child(mc, len, child);
+    }
+
+    /// Replace the child at the given index with a node.
+    pub fn replace_child(self, mc: MutationContext<'gc, '_>, index: usize, child: Self) {
+        if let Some(old) = self.children().get(index) {
+            if old.ptr_eq(child) {
+                return;
+            }
+            old.remove(mc);
+        }
+        self.insert_child(mc, index, child);
+    }
+
+    /// Remove all the children of this element.
+    pub fn clear_children(self, mc: MutationContext<'gc, '_>) {
+        for child in self.children() {
+            child.remove(mc);
+        }
+    }
+
+    /// Add an attribute to this element, replacing any attribute with the same name.
+    pub fn set_attribute(self, mc: MutationContext<'gc, '_>, attribute: Self) {
+        let name = attribute.local_name();
+        let uri = attribute.namespace().map(|ns| ns.uri);
+        if let E4XNodeKind::Element { attributes, .. } = &mut self.0.write(mc).kind {
+            attributes.retain(|existing| {
+                existing.local_name() != name || existing.namespace().map(|ns| ns.uri) != uri
+            });
+            attributes.push(attribute);
+        } else {
+            return;
+        }
+        attribute.0.write(mc).parent = Some(self);
+    }
+
+    /// Declare a namespace on this element, unless it is already declared.
+    pub fn add_namespace(self, mc: MutationContext<'gc, '_>, namespace: E4XNamespace<'gc>) {
+        if let E4XNodeKind::Element { namespaces, .. } = &mut self.0.write(mc).kind {
+            if !namespaces.contains(&namespace) {
+                namespaces.push(namespace);
+            }
+        }
+    }
+
+    /// Replace the value of a text, comment, processing instruction or attribute node.
+    pub fn set_value(self, mc: MutationContext<'gc, '_>, value: AvmString<'gc>) {
+        match &mut self.0.write(mc).kind {
+            E4XNodeKind::Text(text)
+            | E4XNodeKind::CData(text)
+            | E4XNodeKind::Comment(text)
+            | E4XNodeKind::ProcessingInstruction(text)
+            | E4XNodeKind::Attribute(text) => *text = value,
+            E4XNodeKind::Element { .. } => {}
+        }
+    }
+
+    /// Make a deep copy of this node, which has no parent.
+    pub fn deep_copy(self, mc: MutationContext<'gc, '_>) -> Self {
+        let node = self.0.read();
+        let kind = match &node.kind {
+            E4XNodeKind::Text(text) => E4XNodeKind::Text(*text),
+            E4XNodeKind::CData(text) => E4XNodeKind::CData(*text),
+            E4XNodeKind::Comment(text) => E4XNodeKind::Comment(*text),
+            E4XNodeKind::ProcessingInstruction(text) => E4XNodeKind::ProcessingInstruction(*text),
+            E4XNodeKind::Attribute(text) => E4XNodeKind::Attribute(*text),
+            E4XNodeKind::Element { namespaces, .. } => E4XNodeKind::Element {
+                namespaces: namespaces.clone(),
+                attributes: Vec::new(),
+                children: Vec::new(),
+            },
+        };
+        let copy = Self::new(mc, node.namespace, node.local_name, kind);
+        drop(node);
+
+        for attribute in self.attributes() {
+            copy.set_attribute(mc, attribute.deep_copy(mc));
+        }
+        for child in self.children() {
+            copy.append_child(mc, child.deep_copy(mc));
+        }
+        copy
+    }
+
+    /// Whether this node's name matches the given multiname.
+    ///
+    /// Attribute multinames only match attributes, and other multinames only
+    /// match elements (or any child, for the `*` wildcard).
+    pub fn matches_name(self, name: &Multiname<'gc>) -> bool {
+        let node = self.0.read();
+        if name.is_attribute() != matches!(node.kind, E4XNodeKind::Attribute(_)) {
+            return false;
+        }
+
+        let is_any_name = match name.local_name() {
+            None => true,
+            Some(local_name) => &local_name == b"*",
+        };
+        if is_any_name {
+            // A wildcard matches nodes in any namespace, unless a specific one was requested.
+            return match name.namespace_set() {
+                [ns] if ns.is_namespace() && !ns.is_public() => {
+                    node.namespace.map(|ns| ns.uri) == Some(ns.as_uri())
+                }
+                _ => true,
+            };
+        }
+
+        if node.local_name != name.local_name() {
+            return false;
+        }
+
+        let uri = node.namespace.map(|ns| ns.uri).unwrap_or_else(|| "".into());
+        name.namespace_set()
+            .iter()
+            .any(|ns| ns.is_any() || (ns.is_namespace() && ns.as_uri() == uri))
+    }
+
+    /// The children (or attributes, for an attribute multiname) of this node
+    /// that match the given name.
+    pub fn matching_children(self, name: &Multiname<'gc>) -> Vec<Self> {
+        let candidates = if name.is_attribute() {
+            self.attributes()
+        } else {
+            self.children()
+        };
+        candidates
+            .into_iter()
+            .filter(|child| child.matches_name(name))
+            .collect()
+    }
+
+    /// Append the descendants of this node that match the given name to `out`,
+    /// in document order.
+    pub fn descendants(self, name: &Multiname<'gc>, out: &mut Vec<Self>) {
+        if name.is_attribute() {
+            out.extend(
+                self.attributes()
+                    .into_iter()
+                    .filter(|attribute| attribute.matches_name(name)),
+            );
+        }
+        for child in self.children() {
+            if child.matches_name(name) {
+                out.push(child);
+            }
+            child.descendants(name, out);
+        }
+    }
+
+    /// Whether this node has simple content, as defined by `XML.hasSimpleContent`.
+    pub fn has_simple_content(self) -> bool {
+        match &self.0.read().kind {
+            E4XNodeKind::Comment(_) | E4XNodeKind::ProcessingInstruction(_) => false,
+            E4XNodeKind::Element { children, .. } => {
+                children.iter().all(|child| !child.is_element())
+            }
+            _ => true,
+        }
+    }
+
+    /// Convert this node to a string, as done by `XML.toString`.
+    ///
+    /// Nodes with simple content are converted to their text content,
+    /// while other nodes are converted to XML markup.
+    pub fn xml_to_string(
+        self,
+        mc: MutationContext<'gc, '_>,
+        settings: &E4XSettings,
+    ) -> AvmString<'gc> {
+        if !self.has_simple_content() {
+            return self.xml_to_xml_string(mc, settings);
+        }
+
+        match &self.0.read().kind {
+            E4XNodeKind::Text(text) | E4XNodeKind::CData(text) | E4XNodeKind::Attribute(text) => {
+                *text
+            }
+            E4XNodeKind::Element { children, .. } => {
+                let mut out = WString::new();
+                for child in children {
+                    if let E4XNodeKind::Text(text) | E4XNodeKind::CData(text) = &*child.kind() {
+                        out.push_str(text);
+                    }
+                }
+                AvmString::new(mc, out)
+            }
+            _ => "".into(),
+        }
+    }
+
+    /// Convert this node to XML markup, as done by `XML.toXMLString`.
+    pub fn xml_to_xml_string(
+        self,
+        mc: MutationContext<'gc, '_>,
+        settings: &E4XSettings,
+    ) -> AvmString<'gc> {
+        let mut out = WString::new();
+        self.write_xml_string(&mut out, &[], 0, settings);
+        AvmString::new(mc, out)
+    }
+
+    fn write_xml_string(
+        self,
+        out: &mut WString,
+        ancestor_namespaces: &[E4XNamespace<'gc>],
+        indent_level: u32,
+        settings: &E4XSettings,
+    ) {
+        if settings.pretty_printing {
+            for _ in 0..indent_level {
+                out.push_byte(b' ');
+            }
+        }
+
+        let node = self.0.read();
+        match &node.kind {
+            E4XNodeKind::Text(text) => {
+                let text = if settings.pretty_printing {
+                    text.trim_matches(is_whitespace)
+                } else {
+                    text.as_wstr()
+                };
+                out.push_str(&escape_element_value(text));
+            }
+            E4XNodeKind::CData(text) => {
+                out.push_str(WStr::from_units(b"<![CDATA["));
+                out.push_str(text);
+                out.push_str(WStr::from_units(b"]]>"));
+            }
+            E4XNodeKind::Attribute(value) => out.push_str(&escape_attribute_value(value)),
+            E4XNodeKind::Comment(text) => {
+                out.push_str(WStr::from_units(b"<!--"));
+                out.push_str(text);
+                out.push_str(WStr::from_units(b"-->"));
+            }
+            E4XNodeKind::ProcessingInstruction(text) => {
+                out.push_str(WStr::from_units(b"<?"));
+                if let Some(name) = node.local_name {
+                    out.push_str(&name);
+                }
+                if !text.is_empty() {
+                    out.push_byte(b' ');
+                    out.push_str(text);
+                }
+                out.push_str(WStr::from_units(b"?>"));
+            }
+            E4XNodeKind::Element {
+                namespaces,
+                attributes,
+                children,
+            } => {
+                // Declare the namespaces of this element, as well as the ones used by
+                // its name and attributes, unless an ancestor already declared them.
+                let mut declarations: Vec<E4XNamespace<'gc>> = Vec::new();
+                let used = node.namespace.into_iter().chain(
+                    attributes
+                        .iter()
+                        .filter_map(|attribute| attribute.namespace()),
+                );
+                for ns in namespaces.iter().copied().chain(used) {
+                    if !ancestor_namespaces.contains(&ns) && !declarations.contains(&ns) {
+                        declarations.push(ns);
+                    }
+                }
+
+                let name = self.qualified_name();
+                out.push_byte(b'<');
+                out.push_str(&name);
+                for ns in &declarations {
+                    out.push_str(WStr::from_units(b" xmlns"));
+                    if let Some(prefix) = ns.prefix {
+                        out.push_byte(b':');
+                        out.push_str(&prefix);
+                    }
+                    out.push_str(WStr::from_units(b"=\""));
+                    out.push_str(&escape_attribute_value(&ns.uri));
+                    out.push_byte(b'"');
+                }
+                for attribute in attributes {
+                    out.push_byte(b' ');
+                    out.push_str(&attribute.qualified_name());
+                    out.push_str(WStr::from_units(b"=\""));
+                    if let E4XNodeKind::Attribute(value) = &*attribute.kind() {
+                        out.push_str(&escape_attribute_value(value));
+                    }
+                    out.push_byte(b'"');
+                }
+
+                if children.is_empty() {
+                    out.push_str(WStr::from_units(b"/>"));
+                    return;
+                }
+                out.push_byte(b'>');
+
+                let indent_children = children.len() > 1
+                    || !matches!(
+                        &*children[0].kind(),
+                        E4XNodeKind::Text(_) | E4XNodeKind::CData(_)
+                    );
+                let indent_children = settings.pretty_printing && indent_children;
+                let child_indent_level = if indent_children {
+                    indent_level + settings.pretty_indent
+                } else {
+                    0
+                };
+
+                let mut in_scope = ancestor_namespaces.to_vec();
+                in_scope.extend(declarations);
+                for child in children {
+                    if indent_children {
+                        out.push_byte(b'\n');
+                    }
+                    child.write_xml_string(out, &in_scope, child_indent_level, settings);
+                }
+
+                if indent_children {
+                    out.push_byte(b'\n');
+                    for _ in 0..indent_level {
+                        out.push_byte(b' ');
+                    }
+                }
+                out.push_str(WStr::from_units(b"</"));
+                out.push_str(&name);
+                out.push_byte(b'>');
+            }
+        }
+    }
+}
+
+/// Implementation of `EscapeElementValue` from ECMA-357 (10.2.1.1).
+pub fn escape_element_value(s: &WStr) -> WString {
+    let mut r = WString::with_capacity(s.len(), s.is_wide());
+    for c in s {
+        let escape: &[u8] = match u8::try_from(c) {
+            Ok(b'<') => b"&lt;",
+            Ok(b'>') => b"&gt;",
+            Ok(b'&') => b"&amp;",
+            _ => {
+                r.push(c);
+                continue;
+            }
+        };
+
+        r.push_str(WStr::from_units(escape));
+    }
+    r
+}
+
+/// Implementation of `EscapeAttributeValue` from ECMA-357 (10.2.1.2).
+pub fn escape_attribute_value(s: &WStr) -> WString {
+    let mut r = WString::with_capacity(s.len(), s.is_wide());
+    for c in s {
+        let escape: &[u8] = match u8::try_from(c) {
+            Ok(b'"') => b"&quot;",
+            Ok(b'<') => b"&lt;",
+            Ok(b'&') => b"&amp;",
+            Ok(b'\x0A') => b"&#xA;",
+            Ok(b'\x0D') => b"&#xD;",
+            Ok(b'\x09') => b"&#x9;",
+            _ => {
+                r.push(c);
+                continue;
+            }
+        };
+
+        r.push_str(WStr::from_units(escape));
+    }
+    r
+}
+
+/// Returns the name that XML properties should be looked up with: unqualified
+/// names are resolved in the default XML namespace, if one was set with
+/// `default xml namespace = ...`.
+pub fn resolve_name<'gc>(
+    name: &Multiname<'gc>,
+    activation: &Activation<'_, 'gc, '_>,
+) -> Multiname<'gc> {
+    match activation.default_xml_namespace() {
+        Some(uri)
+            if !name.is_attribute()
+                && name.local_name().is_some()
+                && name.contains_public_namespace() =>
+        {
+            name.with_namespace(Namespace::Namespace(uri))
+        }
+        _ => name.clone(),
+    }
+}
+
+/// The namespace that a node created from the given name should be in.
+pub fn name_namespace<'gc>(name: &Multiname<'gc>) -> Option<E4XNamespace<'gc>> {
+    match name.namespace_set() {
+        [ns] if ns.is_namespace() && !ns.is_public() => Some(E4XNamespace {
+            prefix: None,
+            uri: ns.as_uri(),
+        }),
+        _ => None,
+    }
+}
+
+/// The nodes of an `XML` or `XMLList` value, or `None` for any other value.
+pub fn value_nodes<'gc>(value: Value<'gc>) -> Option<Vec<E4XNode<'gc>>> {
+    let object = value.as_object()?;
+    if let Some(xml) = object.as_xml() {
+        Some(vec![xml.node()])
+    } else {
+        object.as_xml_list().map(|list| list.children())
+    }
+}
+
+/// Compare two lists of nodes, as done by the `==` operator on `XML` and `XMLList` values.
+///
+/// Nodes with simple content are compared by their text, and other nodes by their markup.
+pub fn nodes_equal<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    a: &[E4XNode<'gc>],
+    b: &[E4XNode<'gc>],
+) -> Result<bool, Error<'gc>> {
+    if a.len() != b.len() {
+        return Ok(false);
+    }
+
+    let settings = xml_settings(activation)?;
+    let mc = activation.context.gc_context;
+    Ok(a.iter().zip(b).all(|(a, b)| {
+        if a.ptr_eq(*b) {
+            true
+        } else if a.has_simple_content() && b.has_simple_content() {
+            a.xml_to_string(mc, &settings) == b.xml_to_string(mc, &settings)
+        } else {
+            a.xml_to_xml_string(mc, &settings) == b.xml_to_xml_string(mc, &settings)
+        }
+    }))
+}
+
+/// Convert a value that is assigned to an XML property into a list of nodes.
+///
+/// `XML` and `XMLList` values are copied, while other values (including text
+/// and attribute nodes) return `None` and should be assigned as strings.
+pub fn value_to_nodes<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<Option<Vec<E4XNode<'gc>>>, Error<'gc>> {
+    let nodes = match value_nodes(value) {
+        Some(nodes) => nodes,
+        None => return Ok(None),
+    };
+
+    if nodes.iter().all(|node| {
+        matches!(
+            &*node.kind(),
+            E4XNodeKind::Text(_) | E4XNodeKind::Attribute(_)
+        )
+    }) {
+        return Ok(None);
+    }
+
+    let mc = activation.context.gc_context;
+    Ok(Some(
+        nodes.into_iter().map(|node| node.deep_copy(mc)).collect(),
+    ))
+}
+
+/// Convert a value that is assigned to an XML attribute into a string.
+/// The items of an `XMLList` are separated with spaces.
+pub fn value_to_attribute_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<AvmString<'gc>, Error<'gc>> {
+    if let Some(list) = value.as_object().and_then(|object| object.as_xml_list()) {
+        let settings = xml_settings(activation)?;
+        let mut out = WString::new();
+        for (i, child) in list.children().into_iter().enumerate() {
+            if i > 0 {
+                out.push_byte(b' ');
+            }
+            out.push_str(&child.xml_to_string(activation.context.gc_context, &settings));
+        }
+        return Ok(AvmString::new(activation.context.gc_context, out));
+    }
+
+    value.coerce_to_string(activation)
+}
@@ -3,6 +3,7 @@
 use crate::avm2::activation::Activation;
 use crate::avm2::object::{Object, TObject};
 use crate::avm2::value::Value;
+use crate::avm2::Avm2;
 use crate::avm2::Error;
 use crate::avm2::Multiname;
 use crate::avm2::Namespace;
@@ -419,11 +420,11 @@ pub fn dispatch_event_to_target<'gc>(
         let object = activation.global_scope();
 
         if let Err(err) = handler.call(object, &[event.into()], activation) {
-            log::error!(
-                "Error dispatching event {:?} to handler {:?} : {:?}",
-                event,
-                handler,
-                err
+            Avm2::uncaught_error(
+                activation,
+                target.as_display_object(),
+                err,
+                "Error dispatching event to handler",
             );
         }
     }
@@ -2,11 +2,12 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::method::{BytecodeMethod, Method, NativeMethod};
-use crate::avm2::object::{ClassObject, Object};
+use crate::avm2::object::{ClassObject, Object, TObject};
 use crate::avm2::scope::ScopeChain;
 use crate::avm2::traits::TraitKind;
 use crate::avm2::value::Value;
 use crate::avm2::Error;
+use crate::profiler::{FunctionKey, ScriptVm};
 use crate::string::WString;
 use gc_arena::{Collect, Gc};
 use std::fmt;
@@ -114,6 +115,7 @@ impl<'gc> Executable<'gc> {
                 let method = bm.method.method;
                 let receiver = bm.bound_receiver.or(unbound_receiver);
                 let caller_domain = activation.caller_domain();
+                let default_xml_namespace = activation.default_xml_namespace();
                 let subclass_object = bm.bound_superclass;
                 let mut activation = Activation::from_builtin(
                     activation.context.reborrow(),
@@ -122,6 +124,7 @@ impl<'gc> Executable<'gc> {
                     bm.scope,
                     caller_domain,
                 )?;
+                activation.set_default_xml_namespace(default_xml_namespace);
 
                 if arguments.len() > bm.method.signature.len() && !bm.method.is_variadic {
                     return Err(format!(
@@ -142,6 +145,7 @@ impl<'gc> Executable<'gc> {
                     .context
                     .avm2
                     .push_call(activation.context.gc_context, self.clone());
+                self.profiler_enter(&mut activation);
                 method(&mut activation, receiver, &arguments)
             }
             Executable::Action(bm) => {
@@ -154,6 +158,7 @@ impl<'gc> Executable<'gc> {
 
                 let receiver = bm.receiver.or(unbound_receiver);
                 let subclass_object = bm.bound_superclass;
+                let default_xml_namespace = activation.default_xml_namespace();
 
                 let mut activation = Activation::from_method(
                     activation.context.reborrow(),
@@ -164,13 +169,18 @@ impl<'gc> Executable<'gc> {
                     subclass_object,
                     callee,
                 )?;
+                activation.set_default_xml_namespace(default_xml_namespace);
                 activation
                     .context
                     .avm2
                     .push_call(activation.context.gc_context, self.clone());
+                self.profiler_enter(&mut activation);
                 activation.run_actions(bm.method)
             }
         };
+        if let Some(profiler) = activation.context.profiler.as_mut() {
+            profiler.exit();
+        }
         activation
             .context
             .avm2
@@ -178,6 +188,29 @@ impl<'gc> Executable<'gc> {
         ret
     }
 
+    /// Tell the script profiler, if it is running, that this executable was
+    /// entered.
+    fn profiler_enter(&self, activation: &mut Activation<'_, 'gc, '_>) {
+        if let Some(profiler) = activation.context.profiler.as_mut() {
+            let full_name = || {
+                let mut name = WString::new();
+                self.write_full_name(&mut name);
+                name.to_utf8_lossy().into_owned()
+            };
+
+            match self {
+                // Native methods are shared between classes, so only their
+                // names tell them apart.
+                Executable::Native(_) => profiler.enter_named(ScriptVm::Avm2, &full_name()),
+                Executable::Action(BytecodeExecutable { method, .. }) => profiler.enter_keyed(
+                    ScriptVm::Avm2,
+                    FunctionKey::AbcMethod(method.abc.clone(), method.abc_method),
+                    full_name,
+                ),
+            }
+        }
+    }
+
     pub fn bound_superclass(&self) -> Option<ClassObject<'gc>> {
         match self {
             Executable::Native(NativeExecutable {
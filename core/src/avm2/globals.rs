@@ -17,6 +17,7 @@ use std::sync::Arc;
 use swf::TagCode;
 
 mod array;
+mod avmplus;
 mod boolean;
 mod class;
 mod date;
@@ -89,6 +90,8 @@ pub struct SystemClasses<'gc> {
     pub mouseevent: ClassObject<'gc>,
     pub progressevent: ClassObject<'gc>,
     pub textevent: ClassObject<'gc>,
+    pub dataevent: ClassObject<'gc>,
+    pub netstatusevent: ClassObject<'gc>,
     pub errorevent: ClassObject<'gc>,
     pub ioerrorevent: ClassObject<'gc>,
     pub securityerrorevent: ClassObject<'gc>,
@@ -100,13 +103,16 @@ pub struct SystemClasses<'gc> {
     pub rectangle: ClassObject<'gc>,
     pub keyboardevent: ClassObject<'gc>,
     pub point: ClassObject<'gc>,
+    pub error: ClassObject<'gc>,
     pub rangeerror: ClassObject<'gc>,
     pub referenceerror: ClassObject<'gc>,
     pub argumenterror: ClassObject<'gc>,
     pub typeerror: ClassObject<'gc>,
     pub verifyerror: ClassObject<'gc>,
+    pub securityerror: ClassObject<'gc>,
     pub ioerror: ClassObject<'gc>,
     pub eoferror: ClassObject<'gc>,
+    pub uncaughterrorevent: ClassObject<'gc>,
     pub uncaughterrorevents: ClassObject<'gc>,
     pub statictext: ClassObject<'gc>,
     pub textlinemetrics: ClassObject<'gc>,
@@ -115,6 +121,11 @@ pub struct SystemClasses<'gc> {
     pub indexbuffer3d: ClassObject<'gc>,
     pub vertexbuffer3d: ClassObject<'gc>,
     pub program3d: ClassObject<'gc>,
+    pub texture: ClassObject<'gc>,
+    pub rectangletexture: ClassObject<'gc>,
+    pub cubetexture: ClassObject<'gc>,
+    pub dictionary: ClassObject<'gc>,
+    pub iexternalizable: ClassObject<'gc>,
 }
 
 impl<'gc> SystemClasses<'gc> {
@@ -174,6 +185,8 @@ impl<'gc> SystemClasses<'gc> {
             mouseevent: object,
             progressevent: object,
             textevent: object,
+            dataevent: object,
+            netstatusevent: object,
             errorevent: object,
             ioerrorevent: object,
             securityerrorevent: object,
@@ -185,13 +198,16 @@ impl<'gc> SystemClasses<'gc> {
             rectangle: object,
             keyboardevent: object,
             point: object,
+            error: object,
             rangeerror: object,
             referenceerror: object,
             argumenterror: object,
             typeerror: object,
             verifyerror: object,
+            securityerror: object,
             ioerror: object,
             eoferror: object,
+            uncaughterrorevent: object,
             uncaughterrorevents: object,
             statictext: object,
             textlinemetrics: object,
@@ -200,6 +216,11 @@ impl<'gc> SystemClasses<'gc> {
             indexbuffer3d: object,
             vertexbuffer3d: object,
             program3d: object,
+            texture: object,
+            rectangletexture: object,
+            cubetexture: object,
+            dictionary: object,
+            iexternalizable: object,
         }
     }
 }
@@ -410,6 +431,8 @@ pub fn load_player_globals<'gc>(
     avm2_system_class!(uint, activation, uint::create_class(mc), script);
     avm2_system_class!(namespace, activation, namespace::create_class(mc), script);
     avm2_system_class!(qname, activation, qname::create_class(mc), script);
+    avm2_system_class!(xml, activation, xml::create_class(mc), script);
+    avm2_system_class!(xml_list, activation, xml_list::create_class(mc), script);
     avm2_system_class!(array, activation, array::create_class(mc), script);
 
     function(activation, "", "trace", toplevel::trace, script)?;
@@ -638,12 +661,12 @@ fn load_playerglobal<'gc>(
         script,
         [
             ("", "ArgumentError", argumenterror),
+            ("", "Error", error),
             ("", "RangeError", rangeerror),
             ("", "ReferenceError", referenceerror),
             ("", "TypeError", typeerror),
+            ("", "SecurityError", securityerror),
             ("", "VerifyError", verifyerror),
-            ("", "XML", xml),
-            ("", "XMLList", xml_list),
             ("flash.display", "Scene", scene),
             ("flash.display", "FrameLabel", framelabel),
             ("flash.display", "Stage3D", stage3d),
@@ -651,6 +674,13 @@ fn load_playerglobal<'gc>(
             ("flash.display3D", "IndexBuffer3D", indexbuffer3d),
             ("flash.display3D", "Program3D", program3d),
             ("flash.display3D", "VertexBuffer3D", vertexbuffer3d),
+            ("flash.display3D.textures", "Texture", texture),
+            (
+                "flash.display3D.textures",
+                "RectangleTexture",
+                rectangletexture
+            ),
+            ("flash.display3D.textures", "CubeTexture", cubetexture),
             (
                 "flash.errors",
                 "IllegalOperationError",
@@ -660,6 +690,8 @@ fn load_playerglobal<'gc>(
             ("flash.errors", "EOFError", eoferror),
             ("flash.events", "Event", event),
             ("flash.events", "TextEvent", textevent),
+            ("flash.events", "DataEvent", dataevent),
+            ("flash.events", "NetStatusEvent", netstatusevent),
             ("flash.events", "ErrorEvent", errorevent),
             ("flash.events", "KeyboardEvent", keyboardevent),
             ("flash.events", "ProgressEvent", progressevent),
@@ -667,6 +699,7 @@ fn load_playerglobal<'gc>(
             ("flash.events", "IOErrorEvent", ioerrorevent),
             ("flash.events", "MouseEvent", mouseevent),
             ("flash.events", "FullScreenEvent", fullscreenevent),
+            ("flash.events", "UncaughtErrorEvent", uncaughterrorevent),
             ("flash.events", "UncaughtErrorEvents", uncaughterrorevents),
             ("flash.geom", "Matrix", matrix),
             ("flash.geom", "Point", point),
@@ -674,6 +707,8 @@ fn load_playerglobal<'gc>(
             ("flash.geom", "Transform", transform),
             ("flash.geom", "ColorTransform", colortransform),
             ("flash.utils", "ByteArray", bytearray),
+            ("flash.utils", "Dictionary", dictionary),
+            ("flash.utils", "IExternalizable", iexternalizable),
             ("flash.text", "StaticText", statictext),
             ("flash.text", "TextLineMetrics", textlinemetrics),
         ]
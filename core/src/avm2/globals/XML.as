@@ -1,4 +1,5 @@
+// This is a stub - the actual class is defined in `xml.rs`
 package {
-    [Ruffle(InstanceAllocator)]
-    public final dynamic class XML {}
-}
\ No newline at end of file
+	public final dynamic class XML {
+	}
+}
@@ -0,0 +1,17 @@
+package avmplus {
+	public const HIDE_NSURI_METHODS:uint = 0x0001;
+	public const INCLUDE_BASES:uint = 0x0002;
+	public const INCLUDE_INTERFACES:uint = 0x0004;
+	public const INCLUDE_VARIABLES:uint = 0x0008;
+	public const INCLUDE_ACCESSORS:uint = 0x0010;
+	public const INCLUDE_METHODS:uint = 0x0020;
+	public const INCLUDE_METADATA:uint = 0x0040;
+	public const INCLUDE_CONSTRUCTOR:uint = 0x0080;
+	public const INCLUDE_TRAITS:uint = 0x0100;
+	public const USE_ITRAITS:uint = 0x0200;
+	public const HIDE_OBJECT:uint = 0x0400;
+	public const FLASH10_FLAGS:uint = 0x05FF;
+
+	public native function describeType(value:*, flags:uint):XML;
+	public native function describeTypeJSON(value:*, flags:uint):Object;
+}
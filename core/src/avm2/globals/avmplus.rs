@@ -0,0 +1,808 @@
+//! `avmplus` namespace
+//!
+//! This implements the reflection functions that `flash.utils.describeType`
+//! is built upon in Flash Player.
+
+use crate::avm2::activation::Activation;
+use crate::avm2::array::ArrayStorage;
+use crate::avm2::e4x::E4XNode;
+use crate::avm2::metadata::Metadata;
+use crate::avm2::method::{Method, ParamConfig};
+use crate::avm2::object::{ArrayObject, ClassObject, Object, TObject, XmlObject};
+use crate::avm2::traits::{Trait, TraitKind};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::avm2::Multiname;
+use crate::avm2::Namespace;
+use crate::avm2::QName;
+use crate::string::{AvmString, WString};
+use bitflags::bitflags;
+use gc_arena::MutationContext;
+
+bitflags! {
+    /// Flags that select which parts of a type `describeTypeJSON` describes.
+    pub struct DescribeTypeFlags: u32 {
+        /// Leave out methods in a namespace other than `public`.
+        const HIDE_NSURI_METHODS = 1 << 0;
+        const INCLUDE_BASES = 1 << 1;
+        const INCLUDE_INTERFACES = 1 << 2;
+        const INCLUDE_VARIABLES = 1 << 3;
+        const INCLUDE_ACCESSORS = 1 << 4;
+        const INCLUDE_METHODS = 1 << 5;
+        const INCLUDE_METADATA = 1 << 6;
+        const INCLUDE_CONSTRUCTOR = 1 << 7;
+        const INCLUDE_TRAITS = 1 << 8;
+
+        /// Describe the instance traits of a class, rather than its statics.
+        const USE_ITRAITS = 1 << 9;
+
+        /// Leave out traits declared by `Object`.
+        const HIDE_OBJECT = 1 << 10;
+
+        /// The flags used by `flash.utils.describeType`.
+        const FLASH10_FLAGS = Self::HIDE_NSURI_METHODS.bits
+            | Self::INCLUDE_BASES.bits
+            | Self::INCLUDE_INTERFACES.bits
+            | Self::INCLUDE_VARIABLES.bits
+            | Self::INCLUDE_ACCESSORS.bits
+            | Self::INCLUDE_METHODS.bits
+            | Self::INCLUDE_METADATA.bits
+            | Self::INCLUDE_CONSTRUCTOR.bits
+            | Self::INCLUDE_TRAITS.bits
+            | Self::HIDE_OBJECT.bits;
+    }
+}
+
+/// The description of a type, as produced by `describeTypeJSON`.
+///
+/// This is converted into either an object or an XML tree, depending on which
+/// reflection function was called.
+struct TypeDescription<'gc> {
+    name: AvmString<'gc>,
+    is_dynamic: bool,
+    is_final: bool,
+    is_static: bool,
+    traits: Option<TraitsDescription<'gc>>,
+}
+
+/// The traits of a type. Parts that weren't requested are `None`.
+#[derive(Default)]
+struct TraitsDescription<'gc> {
+    bases: Option<Vec<AvmString<'gc>>>,
+    interfaces: Option<Vec<AvmString<'gc>>>,
+    constructor: Option<Vec<ParameterDescription<'gc>>>,
+    variables: Option<Vec<VariableDescription<'gc>>>,
+    accessors: Option<Vec<AccessorDescription<'gc>>>,
+    methods: Option<Vec<MethodDescription<'gc>>>,
+    metadata: Option<Vec<Metadata<'gc>>>,
+}
+
+struct ParameterDescription<'gc> {
+    type_name: AvmString<'gc>,
+    optional: bool,
+}
+
+struct VariableDescription<'gc> {
+    name: AvmString<'gc>,
+    uri: Option<AvmString<'gc>>,
+    type_name: AvmString<'gc>,
+    is_const: bool,
+    metadata: Option<Vec<Metadata<'gc>>>,
+}
+
+struct AccessorDescription<'gc> {
+    name: QName<'gc>,
+    uri: Option<AvmString<'gc>>,
+    type_name: AvmString<'gc>,
+    readable: bool,
+    writable: bool,
+    declared_by: AvmString<'gc>,
+    metadata: Option<Vec<Metadata<'gc>>>,
+}
+
+impl<'gc> AccessorDescription<'gc> {
+    fn access(&self) -> &'static str {
+        match (self.readable, self.writable) {
+            (true, true) => "readwrite",
+            (false, true) => "writeonly",
+            _ => "readonly",
+        }
+    }
+}
+
+struct MethodDescription<'gc> {
+    name: QName<'gc>,
+    uri: Option<AvmString<'gc>>,
+    return_type: AvmString<'gc>,
+    declared_by: AvmString<'gc>,
+    parameters: Vec<ParameterDescription<'gc>>,
+    metadata: Option<Vec<Metadata<'gc>>>,
+}
+
+/// The fully qualified name of a class, as used throughout type descriptions.
+fn class_name<'gc>(class: ClassObject<'gc>, mc: MutationContext<'gc, '_>) -> AvmString<'gc> {
+    class
+        .inner_class_definition()
+        .read()
+        .name()
+        .to_qualified_name(mc)
+}
+
+/// The fully qualified name of a trait, parameter or return type.
+fn type_name<'gc>(name: &Multiname<'gc>, mc: MutationContext<'gc, '_>) -> AvmString<'gc> {
+    let local_name = match name.local_name() {
+        Some(local_name) if !name.is_any() => local_name,
+        _ => return "*".into(),
+    };
+    let ns = name.namespace_set().first().copied();
+    let qname = QName::new(ns.unwrap_or_else(Namespace::public), local_name);
+    if name.params().is_empty() {
+        return qname.to_qualified_name(mc);
+    }
+
+    let mut out = WString::from(qname.to_qualified_name(mc).as_wstr());
+    out.push_utf8(".<");
+    for (i, param) in name.params().iter().enumerate() {
+        if i > 0 {
+            out.push_char(',');
+        }
+        out.push_str(&type_name(param, mc));
+    }
+    out.push_char('>');
+    AvmString::new(mc, out)
+}
+
+fn describe_parameters<'gc>(
+    signature: &[ParamConfig<'gc>],
+    mc: MutationContext<'gc, '_>,
+) -> Vec<ParameterDescription<'gc>> {
+    signature
+        .iter()
+        .map(|param| ParameterDescription {
+            type_name: type_name(&param.param_type_name, mc),
+            optional: param.default_value.is_some(),
+        })
+        .collect()
+}
+
+fn return_type<'gc>(method: &Method<'gc>, mc: MutationContext<'gc, '_>) -> AvmString<'gc> {
+    match method {
+        Method::Bytecode(bm) => type_name(&bm.return_type, mc),
+        // Native methods don't declare their return type.
+        Method::Native(_) => "*".into(),
+    }
+}
+
+/// Collect every interface implemented by a class, including those
+/// implemented by its superclasses or extended by other interfaces.
+fn collect_interfaces<'gc>(class: ClassObject<'gc>, out: &mut Vec<ClassObject<'gc>>) {
+    for interface in class.interfaces() {
+        if !out
+            .iter()
+            .any(|existing| Object::ptr_eq(*existing, interface))
+        {
+            out.push(interface);
+            collect_interfaces(interface, out);
+        }
+    }
+}
+
+/// Describe a set of traits, declared by the given classes.
+///
+/// The classes must be listed from most to least derived, so that overriding
+/// methods and accessors take precedence over the ones they override.
+fn describe_traits<'gc>(
+    sources: &[(ClassObject<'gc>, Vec<Trait<'gc>>)],
+    flags: DescribeTypeFlags,
+    out: &mut TraitsDescription<'gc>,
+    mc: MutationContext<'gc, '_>,
+) {
+    if flags.contains(DescribeTypeFlags::INCLUDE_VARIABLES) {
+        out.variables = Some(Vec::new());
+    }
+    if flags.contains(DescribeTypeFlags::INCLUDE_ACCESSORS) {
+        out.accessors = Some(Vec::new());
+    }
+    if flags.contains(DescribeTypeFlags::INCLUDE_METHODS) {
+        out.methods = Some(Vec::new());
+    }
+
+    for (class, traits) in sources {
+        let declared_by = class_name(*class, mc);
+        for my_trait in traits {
+            let name = my_trait.name();
+            let uri = match name.namespace() {
+                Namespace::Namespace(uri) if uri.is_empty() => None,
+                Namespace::Namespace(uri) => Some(uri),
+                // Private, protected and internal traits are never described.
+                _ => continue,
+            };
+            let metadata = flags
+                .contains(DescribeTypeFlags::INCLUDE_METADATA)
+                .then(|| my_trait.metadata().to_vec());
+
+            match my_trait.kind() {
+                TraitKind::Slot { type_name: ty, .. } | TraitKind::Const { type_name: ty, .. } => {
+                    if let Some(variables) = &mut out.variables {
+                        variables.push(VariableDescription {
+                            name: name.local_name(),
+                            uri,
+                            type_name: type_name(ty, mc),
+                            is_const: matches!(my_trait.kind(), TraitKind::Const { .. }),
+                            metadata,
+                        });
+                    }
+                }
+                TraitKind::Getter { method, .. } | TraitKind::Setter { method, .. } => {
+                    let accessors = match &mut out.accessors {
+                        Some(accessors) => accessors,
+                        None => continue,
+                    };
+                    let is_getter = matches!(my_trait.kind(), TraitKind::Getter { .. });
+                    let existing = accessors.iter_mut().find(|a| a.name == name);
+                    let accessor = match existing {
+                        Some(accessor) => accessor,
+                        None => {
+                            accessors.push(AccessorDescription {
+                                name,
+                                uri,
+                                type_name: "*".into(),
+                                readable: false,
+                                writable: false,
+                                declared_by,
+                                metadata,
+                            });
+                            accessors.last_mut().unwrap()
+                        }
+                    };
+
+                    if is_getter && !accessor.readable {
+                        accessor.readable = true;
+                        accessor.type_name = return_type(method, mc);
+                    } else if !is_getter && !accessor.writable {
+                        accessor.writable = true;
+                        if !accessor.readable {
+                            if let Some(param) = method.signature().first() {
+                                accessor.type_name = type_name(&param.param_type_name, mc);
+                            }
+                        }
+                    }
+                }
+                TraitKind::Method { method, .. } => {
+                    if uri.is_some() && flags.contains(DescribeTypeFlags::HIDE_NSURI_METHODS) {
+                        continue;
+                    }
+                    let methods = match &mut out.methods {
+                        Some(methods) => methods,
+                        None => continue,
+                    };
+                    if methods.iter().any(|m| m.name == name) {
+                        continue;
+                    }
+                    methods.push(MethodDescription {
+                        name,
+                        uri,
+                        return_type: return_type(method, mc),
+                        declared_by,
+                        parameters: describe_parameters(method.signature(), mc),
+                        metadata,
+                    });
+                }
+                TraitKind::Class { .. } | TraitKind::Function { .. } => {}
+            }
+        }
+    }
+}
+
+/// Describe the type of a value.
+fn describe_value<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+    flags: DescribeTypeFlags,
+) -> Result<TypeDescription<'gc>, Error<'gc>> {
+    let mc = activation.context.gc_context;
+    let untyped = |name: &'static str| TypeDescription {
+        name: name.into(),
+        is_dynamic: false,
+        is_final: true,
+        is_static: false,
+        traits: None,
+    };
+
+    let object = match value {
+        Value::Null => return Ok(untyped("null")),
+        Value::Undefined => return Ok(untyped("void")),
+        value => value.coerce_to_object(activation)?,
+    };
+
+    let class_class = activation.avm2().classes().class;
+    let object_class = activation.avm2().classes().object;
+    let hide_object = flags.contains(DescribeTypeFlags::HIDE_OBJECT);
+
+    let (class, is_static) = match (object.as_class_object(), object.instance_of()) {
+        (Some(class), _) => (class, !flags.contains(DescribeTypeFlags::USE_ITRAITS)),
+        // Only classes have instance traits to describe.
+        (None, Some(class)) if !flags.contains(DescribeTypeFlags::USE_ITRAITS) => (class, false),
+        (None, Some(class)) => {
+            return Ok(TypeDescription {
+                name: class_name(class, mc),
+                ..untyped("Object")
+            })
+        }
+        (None, None) => return Ok(untyped("Object")),
+    };
+
+    let class_def = class.inner_class_definition();
+    let mut description = if is_static {
+        TypeDescription {
+            name: class_name(class, mc),
+            is_dynamic: true,
+            is_final: true,
+            is_static: true,
+            traits: None,
+        }
+    } else {
+        let read = class_def.read();
+        TypeDescription {
+            name: class_name(class, mc),
+            is_dynamic: !read.is_sealed(),
+            is_final: read.is_final(),
+            is_static: false,
+            traits: None,
+        }
+    };
+
+    if !flags.contains(DescribeTypeFlags::INCLUDE_TRAITS) {
+        return Ok(description);
+    }
+
+    // Statics are described as the class traits of the class, followed by the
+    // instance traits of `Class` itself.
+    let mut sources = Vec::new();
+    let mut base = if is_static {
+        sources.push((class, class_def.read().class_traits().to_vec()));
+        Some(class_class)
+    } else {
+        Some(class)
+    };
+    let mut bases = Vec::new();
+    while let Some(current) = base {
+        if !Object::ptr_eq(current, class) {
+            bases.push(class_name(current, mc));
+        }
+        if !hide_object || !Object::ptr_eq(current, object_class) {
+            let traits = current
+                .inner_class_definition()
+                .read()
+                .instance_traits()
+                .to_vec();
+            sources.push((current, traits));
+        }
+        base = current.superclass_object();
+    }
+
+    let mut traits = TraitsDescription::default();
+    if flags.contains(DescribeTypeFlags::INCLUDE_BASES) {
+        traits.bases = Some(bases);
+    }
+    if flags.contains(DescribeTypeFlags::INCLUDE_INTERFACES) {
+        let mut interfaces = Vec::new();
+        if !is_static {
+            let mut current = Some(class);
+            while let Some(cls) = current {
+                collect_interfaces(cls, &mut interfaces);
+                current = cls.superclass_object();
+            }
+        }
+        traits.interfaces = Some(
+            interfaces
+                .into_iter()
+                .map(|interface| class_name(interface, mc))
+                .collect(),
+        );
+    }
+    if flags.contains(DescribeTypeFlags::INCLUDE_CONSTRUCTOR) && !is_static {
+        let instance_init = class_def.read().instance_init();
+        if !instance_init.signature().is_empty() {
+            traits.constructor = Some(describe_parameters(instance_init.signature(), mc));
+        }
+    }
+    if flags.contains(DescribeTypeFlags::INCLUDE_METADATA) {
+        traits.metadata = Some(if is_static {
+            Vec::new()
+        } else {
+            class_def.read().metadata().to_vec()
+        });
+    }
+    describe_traits(&sources, flags, &mut traits, mc);
+
+    description.traits = Some(traits);
+    Ok(description)
+}
+
+/// Set a public property of a newly created object.
+fn set_field<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    mut object: Object<'gc>,
+    name: &'static str,
+    value: impl Into<Value<'gc>>,
+) -> Result<(), Error<'gc>> {
+    object.set_property(&Multiname::public(name), value.into(), activation)
+}
+
+fn new_object<'gc>(activation: &mut Activation<'_, 'gc, '_>) -> Result<Object<'gc>, Error<'gc>> {
+    activation
+        .avm2()
+        .classes()
+        .object
+        .construct(activation, &[])
+}
+
+fn new_array<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    values: Vec<Value<'gc>>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(ArrayObject::from_storage(activation, ArrayStorage::from_args(&values))?.into())
+}
+
+fn strings_to_json<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    strings: Option<Vec<AvmString<'gc>>>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    match strings {
+        Some(strings) => new_array(activation, strings.into_iter().map(Value::from).collect()),
+        None => Ok(Value::Null),
+    }
+}
+
+fn parameters_to_json<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    parameters: Vec<ParameterDescription<'gc>>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut values = Vec::with_capacity(parameters.len());
+    for parameter in parameters {
+        let object = new_object(activation)?;
+        set_field(activation, object, "type", parameter.type_name)?;
+        set_field(activation, object, "optional", parameter.optional)?;
+        values.push(object.into());
+    }
+    new_array(activation, values)
+}
+
+fn metadata_to_json<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    metadata: Option<Vec<Metadata<'gc>>>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let metadata = match metadata {
+        Some(metadata) => metadata,
+        None => return Ok(Value::Null),
+    };
+
+    let mut values = Vec::with_capacity(metadata.len());
+    for tag in metadata {
+        let mut items = Vec::with_capacity(tag.items().len());
+        for item in tag.items() {
+            let object = new_object(activation)?;
+            set_field(activation, object, "key", item.key)?;
+            set_field(activation, object, "value", item.value)?;
+            items.push(object.into());
+        }
+
+        let object = new_object(activation)?;
+        set_field(activation, object, "name", tag.name())?;
+        let items = new_array(activation, items)?;
+        set_field(activation, object, "value", items)?;
+        values.push(object.into());
+    }
+    new_array(activation, values)
+}
+
+fn traits_to_json<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    traits: TraitsDescription<'gc>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let object = new_object(activation)?;
+
+    let bases = strings_to_json(activation, traits.bases)?;
+    set_field(activation, object, "bases", bases)?;
+    let interfaces = strings_to_json(activation, traits.interfaces)?;
+    set_field(activation, object, "interfaces", interfaces)?;
+    let constructor = match traits.constructor {
+        Some(parameters) => parameters_to_json(activation, parameters)?,
+        None => Value::Null,
+    };
+    set_field(activation, object, "constructor", constructor)?;
+
+    let variables = match traits.variables {
+        Some(variables) => {
+            let mut values = Vec::with_capacity(variables.len());
+            for variable in variables {
+                let entry = new_object(activation)?;
+                set_field(activation, entry, "name", variable.name)?;
+                if let Some(uri) = variable.uri {
+                    set_field(activation, entry, "uri", uri)?;
+                }
+                let access = if variable.is_const {
+                    "readonly"
+                } else {
+                    "readwrite"
+                };
+                set_field(activation, entry, "access", access)?;
+                set_field(activation, entry, "type", variable.type_name)?;
+                let metadata = metadata_to_json(activation, variable.metadata)?;
+                set_field(activation, entry, "metadata", metadata)?;
+                values.push(entry.into());
+            }
+            new_array(activation, values)?
+        }
+        None => Value::Null,
+    };
+    set_field(activation, object, "variables", variables)?;
+
+    let accessors = match traits.accessors {
+        Some(accessors) => {
+            let mut values = Vec::with_capacity(accessors.len());
+            for accessor in accessors {
+                let entry = new_object(activation)?;
+                set_field(activation, entry, "name", accessor.name.local_name())?;
+                if let Some(uri) = accessor.uri {
+                    set_field(activation, entry, "uri", uri)?;
+                }
+                set_field(activation, entry, "access", accessor.access())?;
+                set_field(activation, entry, "type", accessor.type_name)?;
+                set_field(activation, entry, "declaredBy", accessor.declared_by)?;
+                let metadata = metadata_to_json(activation, accessor.metadata)?;
+                set_field(activation, entry, "metadata", metadata)?;
+                values.push(entry.into());
+            }
+            new_array(activation, values)?
+        }
+        None => Value::Null,
+    };
+    set_field(activation, object, "accessors", accessors)?;
+
+    let methods = match traits.methods {
+        Some(methods) => {
+            let mut values = Vec::with_capacity(methods.len());
+            for method in methods {
+                let entry = new_object(activation)?;
+                set_field(activation, entry, "name", method.name.local_name())?;
+                if let Some(uri) = method.uri {
+                    set_field(activation, entry, "uri", uri)?;
+                }
+                set_field(activation, entry, "returnType", method.return_type)?;
+                set_field(activation, entry, "declaredBy", method.declared_by)?;
+                let parameters = parameters_to_json(activation, method.parameters)?;
+                set_field(activation, entry, "parameters", parameters)?;
+                let metadata = metadata_to_json(activation, method.metadata)?;
+                set_field(activation, entry, "metadata", metadata)?;
+                values.push(entry.into());
+            }
+            new_array(activation, values)?
+        }
+        None => Value::Null,
+    };
+    set_field(activation, object, "methods", methods)?;
+
+    let metadata = metadata_to_json(activation, traits.metadata)?;
+    set_field(activation, object, "metadata", metadata)?;
+
+    Ok(object.into())
+}
+
+/// Implements `avmplus.describeTypeJSON`
+pub fn describe_type_json<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+    let flags = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_u32(activation)?;
+    let description = describe_value(
+        activation,
+        value,
+        DescribeTypeFlags::from_bits_truncate(flags),
+    )?;
+
+    let object = new_object(activation)?;
+    set_field(activation, object, "name", description.name)?;
+    set_field(activation, object, "isDynamic", description.is_dynamic)?;
+    set_field(activation, object, "isFinal", description.is_final)?;
+    set_field(activation, object, "isStatic", description.is_static)?;
+    let traits = match description.traits {
+        Some(traits) => traits_to_json(activation, traits)?,
+        None => Value::Null,
+    };
+    set_field(activation, object, "traits", traits)?;
+
+    Ok(object.into())
+}
+
+/// Append a new, empty element to `parent`.
+fn append_element<'gc>(
+    mc: MutationContext<'gc, '_>,
+    parent: E4XNode<'gc>,
+    name: &'static str,
+) -> E4XNode<'gc> {
+    let element = E4XNode::element(mc, None, name.into());
+    parent.append_child(mc, element);
+    element
+}
+
+fn set_attribute<'gc>(
+    mc: MutationContext<'gc, '_>,
+    element: E4XNode<'gc>,
+    name: &'static str,
+    value: AvmString<'gc>,
+) {
+    element.set_attribute(mc, E4XNode::attribute(mc, None, name.into(), value));
+}
+
+fn bool_string<'gc>(value: bool) -> AvmString<'gc> {
+    if value {
+        "true".into()
+    } else {
+        "false".into()
+    }
+}
+
+fn parameters_to_xml<'gc>(
+    mc: MutationContext<'gc, '_>,
+    parent: E4XNode<'gc>,
+    parameters: &[ParameterDescription<'gc>],
+) {
+    for (i, parameter) in parameters.iter().enumerate() {
+        let element = append_element(mc, parent, "parameter");
+        set_attribute(
+            mc,
+            element,
+            "index",
+            AvmString::new_utf8(mc, (i + 1).to_string()),
+        );
+        set_attribute(mc, element, "type", parameter.type_name);
+        set_attribute(mc, element, "optional", bool_string(parameter.optional));
+    }
+}
+
+fn metadata_to_xml<'gc>(
+    mc: MutationContext<'gc, '_>,
+    parent: E4XNode<'gc>,
+    metadata: &Option<Vec<Metadata<'gc>>>,
+) {
+    for tag in metadata.iter().flatten() {
+        let element = append_element(mc, parent, "metadata");
+        set_attribute(mc, element, "name", tag.name());
+        for item in tag.items() {
+            let arg = append_element(mc, element, "arg");
+            set_attribute(mc, arg, "key", item.key);
+            set_attribute(mc, arg, "value", item.value);
+        }
+    }
+}
+
+/// Append the XML description of a set of traits to `parent`.
+fn traits_to_xml<'gc>(
+    mc: MutationContext<'gc, '_>,
+    parent: E4XNode<'gc>,
+    traits: &TraitsDescription<'gc>,
+) {
+    for base in traits.bases.iter().flatten() {
+        let element = append_element(mc, parent, "extendsClass");
+        set_attribute(mc, element, "type", *base);
+    }
+    for interface in traits.interfaces.iter().flatten() {
+        let element = append_element(mc, parent, "implementsInterface");
+        set_attribute(mc, element, "type", *interface);
+    }
+    if let Some(constructor) = &traits.constructor {
+        let element = append_element(mc, parent, "constructor");
+        parameters_to_xml(mc, element, constructor);
+    }
+    for variable in traits.variables.iter().flatten() {
+        let kind = if variable.is_const {
+            "constant"
+        } else {
+            "variable"
+        };
+        let element = append_element(mc, parent, kind);
+        set_attribute(mc, element, "name", variable.name);
+        if let Some(uri) = variable.uri {
+            set_attribute(mc, element, "uri", uri);
+        }
+        set_attribute(mc, element, "type", variable.type_name);
+        metadata_to_xml(mc, element, &variable.metadata);
+    }
+    for accessor in traits.accessors.iter().flatten() {
+        let element = append_element(mc, parent, "accessor");
+        set_attribute(mc, element, "name", accessor.name.local_name());
+        if let Some(uri) = accessor.uri {
+            set_attribute(mc, element, "uri", uri);
+        }
+        set_attribute(mc, element, "access", accessor.access().into());
+        set_attribute(mc, element, "type", accessor.type_name);
+        set_attribute(mc, element, "declaredBy", accessor.declared_by);
+        metadata_to_xml(mc, element, &accessor.metadata);
+    }
+    for method in traits.methods.iter().flatten() {
+        let element = append_element(mc, parent, "method");
+        set_attribute(mc, element, "name", method.name.local_name());
+        if let Some(uri) = method.uri {
+            set_attribute(mc, element, "uri", uri);
+        }
+        set_attribute(mc, element, "declaredBy", method.declared_by);
+        set_attribute(mc, element, "returnType", method.return_type);
+        parameters_to_xml(mc, element, &method.parameters);
+        metadata_to_xml(mc, element, &method.metadata);
+    }
+    metadata_to_xml(mc, parent, &traits.metadata);
+}
+
+/// Describe the type of a value as XML, in the format of `flash.utils.describeType`.
+///
+/// Classes are described by their statics, followed by a `<factory>` element
+/// describing their instances.
+pub fn describe_type_xml<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+    flags: DescribeTypeFlags,
+) -> Result<E4XNode<'gc>, Error<'gc>> {
+    let description = describe_value(activation, value, flags)?;
+    let mc = activation.context.gc_context;
+
+    let root = E4XNode::element(mc, None, "type".into());
+    set_attribute(mc, root, "name", description.name);
+    let base = description
+        .traits
+        .as_ref()
+        .and_then(|traits| traits.bases.as_ref())
+        .and_then(|bases| bases.first());
+    if let Some(base) = base {
+        set_attribute(mc, root, "base", *base);
+    }
+    set_attribute(mc, root, "isDynamic", bool_string(description.is_dynamic));
+    set_attribute(mc, root, "isFinal", bool_string(description.is_final));
+    set_attribute(mc, root, "isStatic", bool_string(description.is_static));
+    if let Some(traits) = &description.traits {
+        traits_to_xml(mc, root, traits);
+    }
+
+    let is_class = value
+        .as_object()
+        .and_then(|object| object.as_class_object())
+        .is_some();
+    if is_class && !flags.contains(DescribeTypeFlags::USE_ITRAITS) {
+        let instance = describe_value(activation, value, flags | DescribeTypeFlags::USE_ITRAITS)?;
+        if let Some(traits) = &instance.traits {
+            let factory = append_element(mc, root, "factory");
+            set_attribute(mc, factory, "type", instance.name);
+            traits_to_xml(mc, factory, traits);
+        }
+    }
+
+    Ok(root)
+}
+
+/// Implements `avmplus.describeType`
+pub fn describe_type<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+    let flags = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_u32(activation)?;
+    let node = describe_type_xml(
+        activation,
+        value,
+        DescribeTypeFlags::from_bits_truncate(flags),
+    )?;
+
+    Ok(XmlObject::from_node(activation, node)?.into())
+}
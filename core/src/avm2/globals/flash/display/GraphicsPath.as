@@ -0,0 +1,52 @@
+package flash.display {
+    public final class GraphicsPath implements IGraphicsPath, IGraphicsData {
+        public var commands:Vector.<int>;
+        public var data:Vector.<Number>;
+        public var winding:String;
+
+        public function GraphicsPath(commands:Vector.<int> = null, data:Vector.<Number> = null, winding:String = "evenOdd") {
+            this.commands = commands;
+            this.data = data;
+            this.winding = winding;
+        }
+
+        public function moveTo(x:Number, y:Number):void {
+            this.addCommand(GraphicsPathCommand.MOVE_TO, x, y);
+        }
+
+        public function lineTo(x:Number, y:Number):void {
+            this.addCommand(GraphicsPathCommand.LINE_TO, x, y);
+        }
+
+        public function curveTo(controlX:Number, controlY:Number, anchorX:Number, anchorY:Number):void {
+            this.addCommand(GraphicsPathCommand.CURVE_TO, controlX, controlY, anchorX, anchorY);
+        }
+
+        public function cubicCurveTo(
+            controlX1:Number, controlY1:Number, controlX2:Number, controlY2:Number, anchorX:Number, anchorY:Number
+        ):void {
+            this.addCommand(GraphicsPathCommand.CUBIC_CURVE_TO, controlX1, controlY1, controlX2, controlY2, anchorX, anchorY);
+        }
+
+        public function wideMoveTo(x:Number, y:Number):void {
+            this.addCommand(GraphicsPathCommand.WIDE_MOVE_TO, 0, 0, x, y);
+        }
+
+        public function wideLineTo(x:Number, y:Number):void {
+            this.addCommand(GraphicsPathCommand.WIDE_LINE_TO, 0, 0, x, y);
+        }
+
+        private function addCommand(command:int, ...data):void {
+            if (this.commands == null) {
+                this.commands = new Vector.<int>();
+            }
+            if (this.data == null) {
+                this.data = new Vector.<Number>();
+            }
+            this.commands.push(command);
+            for each (var value:Number in data) {
+                this.data.push(value);
+            }
+        }
+    }
+}
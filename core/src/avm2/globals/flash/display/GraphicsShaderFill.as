@@ -0,0 +1,14 @@
+package flash.display {
+    import flash.geom.Matrix;
+
+    public final class GraphicsShaderFill implements IGraphicsFill, IGraphicsData {
+        // FIXME - this should be a `flash.display.Shader` once we implement that class.
+        public var shader:Object;
+        public var matrix:Matrix;
+
+        public function GraphicsShaderFill(shader:Object = null, matrix:Matrix = null) {
+            this.shader = shader;
+            this.matrix = matrix;
+        }
+    }
+}
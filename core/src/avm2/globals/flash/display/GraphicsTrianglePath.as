@@ -0,0 +1,17 @@
+package flash.display {
+    public final class GraphicsTrianglePath implements IGraphicsPath, IGraphicsData {
+        public var vertices:Vector.<Number>;
+        public var indices:Vector.<int>;
+        public var uvtData:Vector.<Number>;
+        public var culling:String;
+
+        public function GraphicsTrianglePath(
+            vertices:Vector.<Number> = null, indices:Vector.<int> = null, uvtData:Vector.<Number> = null, culling:String = "none"
+        ) {
+            this.vertices = vertices;
+            this.indices = indices;
+            this.uvtData = uvtData;
+            this.culling = culling;
+        }
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::filters::object_to_filter;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::object::{bitmapdata_allocator, BitmapDataObject, Object, TObject};
 use crate::avm2::value::Value;
@@ -16,6 +17,7 @@ use crate::character::Character;
 use crate::display_object::Bitmap;
 use crate::swf::BlendMode;
 use gc_arena::{GcCell, MutationContext};
+use ruffle_render::filters::Filter;
 use ruffle_render::transform::Transform;
 use std::str::FromStr;
 
@@ -843,13 +845,140 @@ pub fn rect<'gc>(
     Ok(Value::Undefined)
 }
 
+fn get_rectangle<'gc>(
+    rectangle: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<(i32, i32, i32, i32), Error<'gc>> {
+    let x = rectangle
+        .get_property(&Multiname::public("x"), activation)?
+        .coerce_to_i32(activation)?;
+    let y = rectangle
+        .get_property(&Multiname::public("y"), activation)?
+        .coerce_to_i32(activation)?;
+    let width = rectangle
+        .get_property(&Multiname::public("width"), activation)?
+        .coerce_to_i32(activation)?;
+    let height = rectangle
+        .get_property(&Multiname::public("height"), activation)?
+        .coerce_to_i32(activation)?;
+    Ok((x, y, width, height))
+}
+
+fn get_filter<'gc>(
+    filter: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Filter, Error<'gc>> {
+    match object_to_filter(filter, activation)? {
+        Some(filter) => Ok(filter),
+        None => Err(Error::AvmError(crate::avm2::error::argument_error(
+            activation,
+            "Error #2005: Parameter 3 is of the incorrect type. Should be type Filter.",
+            2005,
+        )?)),
+    }
+}
+
 /// Implement `BitmapData.applyFilter`
 pub fn apply_filter<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
-    _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("BitmapData.applyFilter: Not yet implemented");
+    if let Some(bitmap_data) = this.and_then(|this| this.as_bitmap_data()) {
+        bitmap_data.read().check_valid(activation)?;
+        let source_bitmap = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_object(activation)?;
+        let source_rect = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_object(activation)?;
+        let source_rect = get_rectangle(source_rect, activation)?;
+        let dest_point = args
+            .get(2)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_object(activation)?;
+        let dest_x = dest_point
+            .get_property(&Multiname::public("x"), activation)?
+            .coerce_to_i32(activation)?;
+        let dest_y = dest_point
+            .get_property(&Multiname::public("y"), activation)?
+            .coerce_to_i32(activation)?;
+        let filter_object = args
+            .get(3)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_object(activation)?;
+        let filter = get_filter(filter_object, activation)?;
+
+        let map_bitmap = if let Filter::DisplacementMapFilter(_) = filter {
+            filter_object
+                .get_property(&Multiname::public("mapBitmap"), activation)?
+                .as_object()
+                .and_then(|map| map.as_bitmap_data())
+        } else {
+            None
+        };
+
+        if let Some(src_bitmap) = source_bitmap.as_bitmap_data() {
+            src_bitmap.read().check_valid(activation)?;
+            // dealing with object aliasing...
+            let src_bitmap_clone: BitmapData; // only initialized if source is the same object as self
+            let src_bitmap_gc_ref; // only initialized if source is a different object than self
+            let source_bitmap_ref = // holds the reference to either of the ones above
+                if GcCell::ptr_eq(src_bitmap, bitmap_data) {
+                    src_bitmap_clone = src_bitmap.read().clone();
+                    &src_bitmap_clone
+                } else {
+                    src_bitmap_gc_ref = src_bitmap.read();
+                    &src_bitmap_gc_ref
+                };
+            // The map may also be the same object as self, so it is copied.
+            let map = map_bitmap.map(|map| map.read().clone());
+            bitmap_data
+                .write(activation.context.gc_context)
+                .apply_filter(
+                    source_bitmap_ref,
+                    source_rect,
+                    (dest_x, dest_y),
+                    &filter,
+                    map.as_ref(),
+                );
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implement `BitmapData.generateFilterRect`
+pub fn generate_filter_rect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(bitmap_data) = this.and_then(|this| this.as_bitmap_data()) {
+        bitmap_data.read().check_valid(activation)?;
+        let source_rect = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_object(activation)?;
+        let source_rect = get_rectangle(source_rect, activation)?;
+        let filter = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_object(activation)?;
+        let filter = get_filter(filter, activation)?;
+
+        let (x, y, width, height) = BitmapData::generate_filter_rect(source_rect, &filter);
+        return Ok(activation
+            .avm2()
+            .classes()
+            .rectangle
+            .construct(
+                activation,
+                &[x.into(), y.into(), width.into(), height.into()],
+            )?
+            .into());
+    }
     Ok(Value::Undefined)
 }
 
@@ -1013,6 +1142,7 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("fillRect", fill_rect),
         ("dispose", dispose),
         ("applyFilter", apply_filter),
+        ("generateFilterRect", generate_filter_rect),
         ("clone", clone),
         ("perlinNoise", perlin_noise),
     ];
@@ -2,6 +2,7 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::Class;
+use crate::avm2::globals::flash::filters::{filter_to_object, object_to_filter};
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::object::{stage_allocator, Object, TObject};
 use crate::avm2::value::Value;
@@ -245,7 +246,13 @@ pub fn filters<'gc>(
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
-        return Ok(ArrayObject::from_storage(activation, dobj.filters())?.into());
+        let mut filters = Vec::new();
+        for filter in dobj.filters() {
+            filters.push(filter_to_object(&filter, activation)?);
+        }
+        return Ok(
+            ArrayObject::from_storage(activation, ArrayStorage::from_args(&filters))?.into(),
+        );
     }
     Ok(ArrayObject::empty(activation)?.into())
 }
@@ -269,8 +276,7 @@ pub fn set_filters<'gc>(
         let new_filters = args.get(0).cloned().unwrap_or(Value::Undefined);
 
         if matches!(new_filters, Value::Undefined | Value::Null) {
-            let new_storage = ArrayStorage::new(0);
-            dobj.set_filters(activation.context.gc_context, new_storage);
+            dobj.set_filters(activation.context.gc_context, Vec::new());
         } else {
             let new_filters = new_filters.coerce_to_object(activation)?;
 
@@ -281,7 +287,11 @@ pub fn set_filters<'gc>(
 
                     let filter_class_object = activation.resolve_class(&filter_class)?;
 
-                    for filter in filters_storage.iter().flatten() {
+                    let filter_values: Vec<_> = filters_storage.iter().flatten().collect();
+                    drop(filters_storage);
+
+                    let mut new_filters = Vec::with_capacity(filter_values.len());
+                    for filter in filter_values {
                         if matches!(filter, Value::Undefined | Value::Null) {
                             return build_argument_type_error(activation);
                         } else {
@@ -290,11 +300,14 @@ pub fn set_filters<'gc>(
                             if !filter_object.is_of_type(filter_class_object, activation) {
                                 return build_argument_type_error(activation);
                             }
+
+                            if let Some(filter) = object_to_filter(filter_object, activation)? {
+                                new_filters.push(filter);
+                            }
                         }
                     }
-                    let new_storage = ArrayStorage::from_storage(filters_storage.iter().collect());
 
-                    dobj.set_filters(activation.context.gc_context, new_storage);
+                    dobj.set_filters(activation.context.gc_context, new_filters);
                 }
             }
         }
@@ -899,6 +912,36 @@ fn set_scroll_rect<'gc>(
     Ok(Value::Undefined)
 }
 
+fn scale_nine_grid<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        if let Some(grid) = dobj.scaling_grid() {
+            return Ok(new_rectangle(activation, grid.into())?.into());
+        } else {
+            return Ok(Value::Null);
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+fn set_scale_nine_grid<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        let grid = match args.get(0).and_then(|value| value.as_object()) {
+            Some(rectangle) => Some(object_to_rectangle(activation, rectangle)?),
+            None => None,
+        };
+        dobj.set_scaling_grid(activation.context.gc_context, grid);
+    }
+    Ok(Value::Undefined)
+}
+
 fn local_to_global<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
@@ -1066,6 +1109,39 @@ fn set_cache_as_bitmap<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `opaqueBackground`'s getter.
+pub fn opaque_background<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        return Ok(match dobj.opaque_background() {
+            Some(color) => color.to_rgb().into(),
+            None => Value::Null,
+        });
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `opaqueBackground`'s setter.
+pub fn set_opaque_background<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        let color = match args.get(0).unwrap_or(&Value::Null) {
+            Value::Undefined | Value::Null => None,
+            value => Some(Color::from_rgb(value.coerce_to_u32(activation)?, 255)),
+        };
+        dobj.set_opaque_background(activation.context.gc_context, color);
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Construct `DisplayObject`'s class.
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
@@ -1123,12 +1199,22 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("filters", Some(filters), Some(set_filters)),
         ("transform", Some(transform), Some(set_transform)),
         ("scrollRect", Some(scroll_rect), Some(set_scroll_rect)),
+        (
+            "scale9Grid",
+            Some(scale_nine_grid),
+            Some(set_scale_nine_grid),
+        ),
         ("mask", Some(mask), Some(set_mask)),
         (
             "cacheAsBitmap",
             Some(cache_as_bitmap),
             Some(set_cache_as_bitmap),
         ),
+        (
+            "opaqueBackground",
+            Some(opaque_background),
+            Some(set_opaque_background),
+        ),
     ];
     write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
 
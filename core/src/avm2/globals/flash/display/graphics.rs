@@ -2,20 +2,39 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::flash::geom::transform::{matrix_to_object, object_to_matrix};
 use crate::avm2::method::{Method, NativeMethodImpl};
-use crate::avm2::object::{stage_allocator, Object, TObject};
+use crate::avm2::object::{
+    stage_allocator, ArrayObject, ClassObject, Object, TObject, VectorObject,
+};
 use crate::avm2::value::Value;
+use crate::avm2::vector::VectorStorage;
+use crate::avm2::ArrayStorage;
 use crate::avm2::Error;
 use crate::avm2::Multiname;
 use crate::avm2::Namespace;
 use crate::avm2::QName;
-use crate::display_object::TDisplayObject;
+use crate::display_object::{DisplayObject, TDisplayObject, TDisplayObjectContainer};
 use crate::drawing::Drawing;
 use crate::string::WStr;
 use gc_arena::{GcCell, MutationContext};
-use ruffle_render::shape_utils::DrawCommand;
+use ruffle_render::bitmap::{BitmapInfo, BitmapSource};
+use ruffle_render::matrix::Matrix;
+use ruffle_render::shape_utils::{DrawCommand, DrawPath, FillRule};
 use std::f64::consts::FRAC_1_SQRT_2;
-use swf::{Color, FillStyle, Fixed8, LineCapStyle, LineJoinStyle, LineStyle, Twips};
+use swf::{
+    Color, FillStyle, Fixed8, Gradient, GradientInterpolation, GradientRecord, GradientSpread,
+    LineCapStyle, LineJoinStyle, LineStyle, Twips,
+};
+
+// The values of `flash.display.GraphicsPathCommand`.
+const PATH_NO_OP: i32 = 0;
+const PATH_MOVE_TO: i32 = 1;
+const PATH_LINE_TO: i32 = 2;
+const PATH_CURVE_TO: i32 = 3;
+const PATH_WIDE_MOVE_TO: i32 = 4;
+const PATH_WIDE_LINE_TO: i32 = 5;
+const PATH_CUBIC_CURVE_TO: i32 = 6;
 
 /// Implements `flash.display.Graphics`'s instance constructor.
 fn instance_init<'gc>(
@@ -80,23 +99,226 @@ fn begin_fill<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Reads every element of a `Vector` or `Array` argument, or an empty list if it's `null`.
+fn get_list<'gc>(value: Option<&Value<'gc>>) -> Vec<Value<'gc>> {
+    if let Some(object) = value.and_then(|value| value.as_object()) {
+        if let Some(vector) = object.as_vector_storage() {
+            return vector.iter().collect();
+        }
+        if let Some(array) = object.as_array_storage() {
+            return array
+                .iter()
+                .map(|v| v.unwrap_or(Value::Undefined))
+                .collect();
+        }
+    }
+    Vec::new()
+}
+
+fn get_numbers<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Option<&Value<'gc>>,
+) -> Result<Vec<f64>, Error<'gc>> {
+    get_list(value)
+        .into_iter()
+        .map(|v| v.coerce_to_number(activation))
+        .collect()
+}
+
+fn get_ints<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Option<&Value<'gc>>,
+) -> Result<Vec<i32>, Error<'gc>> {
+    get_list(value)
+        .into_iter()
+        .map(|v| v.coerce_to_i32(activation))
+        .collect()
+}
+
+/// A bitmap fill that hasn't been registered with a `Drawing` yet.
+struct BitmapFill {
+    bitmap: BitmapInfo,
+    matrix: Matrix,
+    is_repeating: bool,
+    is_smoothed: bool,
+}
+
+impl BitmapFill {
+    /// Reads the `bitmap`, `matrix`, `repeat` and `smooth` arguments of
+    /// `beginBitmapFill` and `lineBitmapStyle`.
+    fn from_args<'gc>(
+        activation: &mut Activation<'_, 'gc, '_>,
+        args: &[Value<'gc>],
+    ) -> Result<Option<Self>, Error<'gc>> {
+        let bitmap_data = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .as_object()
+            .and_then(|bitmap| bitmap.as_bitmap_data())
+            .ok_or_else(|| {
+                Error::from("TypeError: Error #2007: Parameter bitmap must be non-null.")
+            })?;
+
+        let mut matrix = match args.get(1).unwrap_or(&Value::Null) {
+            Value::Null | Value::Undefined => Matrix::IDENTITY,
+            matrix => object_to_matrix(matrix.coerce_to_object(activation)?, activation)?,
+        };
+        // Flash matrix is in pixels. Scale from pixels to twips.
+        matrix *= Matrix::scale(Twips::TWIPS_PER_PIXEL as f32, Twips::TWIPS_PER_PIXEL as f32);
+
+        let is_repeating = args.get(2).map_or(true, |v| v.coerce_to_boolean());
+        let is_smoothed = args.get(3).map_or(false, |v| v.coerce_to_boolean());
+
+        let mut bitmap_data = bitmap_data.write(activation.context.gc_context);
+        let handle = match bitmap_data.bitmap_handle(activation.context.renderer) {
+            Some(handle) => handle,
+            None => return Ok(None),
+        };
+
+        Ok(Some(Self {
+            bitmap: BitmapInfo {
+                handle,
+                width: bitmap_data.width() as u16,
+                height: bitmap_data.height() as u16,
+            },
+            matrix,
+            is_repeating,
+            is_smoothed,
+        }))
+    }
+
+    /// Registers the bitmap with `drawing`, and returns the fill style using it.
+    fn into_fill_style(self, drawing: &mut Drawing) -> FillStyle {
+        FillStyle::Bitmap {
+            id: drawing.add_bitmap(self.bitmap),
+            matrix: self.matrix.into(),
+            is_smoothed: self.is_smoothed,
+            is_repeating: self.is_repeating,
+        }
+    }
+}
+
+/// Reads the arguments of `beginGradientFill` and `lineGradientStyle`.
+fn gradient_fill_from_args<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<FillStyle, Error<'gc>> {
+    let gradient_type = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?;
+    let colors = get_list(args.get(1));
+    let alphas = get_list(args.get(2));
+    let ratios = get_list(args.get(3));
+
+    let mut records = Vec::with_capacity(colors.len());
+    // Gradients are limited to 15 colors.
+    for ((color, alpha), ratio) in colors.iter().zip(alphas.iter()).zip(ratios.iter()).take(15) {
+        let color = color.coerce_to_u32(activation)?;
+        let alpha = alpha.coerce_to_number(activation)?;
+        let ratio = ratio.coerce_to_number(activation)?;
+        records.push(GradientRecord {
+            ratio: ratio.clamp(0.0, 255.0) as u8,
+            color: color_from_args(color, alpha.clamp(0.0, 1.0)),
+        });
+    }
+
+    let matrix = match args.get(4).unwrap_or(&Value::Null) {
+        Value::Null | Value::Undefined => Matrix::IDENTITY,
+        matrix => object_to_matrix(matrix.coerce_to_object(activation)?, activation)?,
+    };
+    let spread = args
+        .get(5)
+        .cloned()
+        .unwrap_or_else(|| "pad".into())
+        .coerce_to_string(activation)?;
+    let spread = if &spread == b"reflect" {
+        GradientSpread::Reflect
+    } else if &spread == b"repeat" {
+        GradientSpread::Repeat
+    } else {
+        GradientSpread::Pad
+    };
+    let interpolation = args
+        .get(6)
+        .cloned()
+        .unwrap_or_else(|| "rgb".into())
+        .coerce_to_string(activation)?;
+    let interpolation = if &interpolation == b"linearRGB" {
+        GradientInterpolation::LinearRgb
+    } else {
+        GradientInterpolation::Rgb
+    };
+    let focal_point = args
+        .get(7)
+        .cloned()
+        .unwrap_or_else(|| 0.0.into())
+        .coerce_to_number(activation)?;
+
+    let gradient = Gradient {
+        matrix: matrix.into(),
+        spread,
+        interpolation,
+        records,
+    };
+    if &gradient_type == b"linear" {
+        Ok(FillStyle::LinearGradient(gradient))
+    } else if &gradient_type == b"radial" {
+        if focal_point == 0.0 {
+            Ok(FillStyle::RadialGradient(gradient))
+        } else {
+            Ok(FillStyle::FocalGradient {
+                gradient,
+                focal_point: Fixed8::from_f64(focal_point.clamp(-1.0, 1.0)),
+            })
+        }
+    } else {
+        Err("ArgumentError: Error #2008: Parameter type must be one of the accepted values.".into())
+    }
+}
+
 /// Implements `Graphics.beginBitmapFill`.
 fn begin_bitmap_fill<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
-    _this: Option<Object<'gc>>,
-    _args: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Graphics.beginBitmapFill: not yet implemented");
+    if let Some(this) = this.and_then(|t| t.as_display_object()) {
+        let bitmap_fill = BitmapFill::from_args(activation, args)?;
+
+        if let Some(mut draw) = this.as_drawing(activation.context.gc_context) {
+            let style = bitmap_fill.map(|bitmap_fill| bitmap_fill.into_fill_style(&mut draw));
+            draw.set_fill_style(style);
+        }
+    }
+
     Ok(Value::Undefined)
 }
 
 /// Implements `Graphics.beginGradientFill`.
 fn begin_gradient_fill<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this.and_then(|t| t.as_display_object()) {
+        let style = gradient_fill_from_args(activation, args)?;
+
+        if let Some(mut draw) = this.as_drawing(activation.context.gc_context) {
+            draw.set_fill_style(Some(style));
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.beginShaderFill`.
+fn begin_shader_fill<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
     _this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Graphics.beginGradientFill: not yet implemented");
+    log::warn!("Graphics.beginShaderFill: not yet implemented");
     Ok(Value::Undefined)
 }
 
@@ -786,6 +1008,761 @@ fn draw_ellipse<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `Graphics.lineGradientStyle`.
+fn line_gradient_style<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this.and_then(|t| t.as_display_object()) {
+        let style = gradient_fill_from_args(activation, args)?;
+
+        if let Some(mut draw) = this.as_drawing(activation.context.gc_context) {
+            draw.set_line_fill_style(style);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.lineBitmapStyle`.
+fn line_bitmap_style<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this.and_then(|t| t.as_display_object()) {
+        let bitmap_fill = BitmapFill::from_args(activation, args)?;
+
+        if let (Some(bitmap_fill), Some(mut draw)) =
+            (bitmap_fill, this.as_drawing(activation.context.gc_context))
+        {
+            let style = bitmap_fill.into_fill_style(&mut draw);
+            draw.set_line_fill_style(style);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.cubicCurveTo`.
+fn cubic_curve_to<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this.and_then(|t| t.as_display_object()) {
+        let mut points = [Twips::ZERO; 6];
+        for (i, point) in points.iter_mut().enumerate() {
+            *point = Twips::from_pixels(
+                args.get(i)
+                    .cloned()
+                    .unwrap_or(Value::Undefined)
+                    .coerce_to_number(activation)?,
+            );
+        }
+        let [control_x1, control_y1, control_x2, control_y2, anchor_x, anchor_y] = points;
+
+        if let Some(mut draw) = this.as_drawing(activation.context.gc_context) {
+            draw.draw_cubic_curve(
+                (control_x1, control_y1),
+                (control_x2, control_y2),
+                (anchor_x, anchor_y),
+            );
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Draws the commands of a `GraphicsPath`, reading their coordinates from `data`.
+fn draw_path_commands(drawing: &mut Drawing, commands: &[i32], mut data: &[f64]) {
+    for &command in commands {
+        let num_values = match command {
+            PATH_MOVE_TO | PATH_LINE_TO => 2,
+            PATH_CURVE_TO | PATH_WIDE_MOVE_TO | PATH_WIDE_LINE_TO => 4,
+            PATH_CUBIC_CURVE_TO => 6,
+            _ => 0,
+        };
+        // Drawing stops at the first command without enough data.
+        if data.len() < num_values {
+            break;
+        }
+        let (values, rest) = data.split_at(num_values);
+        data = rest;
+        let point = |i: usize| {
+            (
+                Twips::from_pixels(values[i]),
+                Twips::from_pixels(values[i + 1]),
+            )
+        };
+
+        match command {
+            PATH_MOVE_TO | PATH_WIDE_MOVE_TO => {
+                let (x, y) = point(num_values - 2);
+                drawing.draw_command(DrawCommand::MoveTo { x, y });
+            }
+            PATH_LINE_TO | PATH_WIDE_LINE_TO => {
+                let (x, y) = point(num_values - 2);
+                drawing.draw_command(DrawCommand::LineTo { x, y });
+            }
+            PATH_CURVE_TO => {
+                let (x1, y1) = point(0);
+                let (x2, y2) = point(2);
+                drawing.draw_command(DrawCommand::CurveTo { x1, y1, x2, y2 });
+            }
+            PATH_CUBIC_CURVE_TO => {
+                drawing.draw_cubic_curve(point(0), point(2), point(4));
+            }
+            PATH_NO_OP => {}
+            _ => log::warn!("Graphics.drawPath: Unknown command {}", command),
+        }
+    }
+}
+
+/// Implements `Graphics.drawPath`.
+fn draw_path<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this.and_then(|t| t.as_display_object()) {
+        let commands = get_ints(activation, args.get(0))?;
+        let data = get_numbers(activation, args.get(1))?;
+        let winding = args
+            .get(2)
+            .cloned()
+            .unwrap_or_else(|| "evenOdd".into())
+            .coerce_to_string(activation)?;
+        let winding_rule = if &winding == b"evenOdd" {
+            FillRule::EvenOdd
+        } else if &winding == b"nonZero" {
+            FillRule::NonZero
+        } else {
+            return Err(
+                "ArgumentError: Error #2008: Parameter winding must be one of the accepted values."
+                    .into(),
+            );
+        };
+
+        if let Some(mut draw) = this.as_drawing(activation.context.gc_context) {
+            draw.draw_with_winding_rule(winding_rule, |draw| {
+                draw_path_commands(draw, &commands, &data)
+            });
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Returns the matrix of a bitmap fill that maps the bitmap coordinates `uvs` (in pixels)
+/// to the triangle `points`, or `None` if the bitmap coordinates don't form a triangle.
+fn triangle_bitmap_matrix(points: [(f64, f64); 3], uvs: [(f64, f64); 3]) -> Option<Matrix> {
+    let [p0, p1, p2] = points;
+    let [t0, t1, t2] = uvs;
+    let (dp1, dp2) = ((p1.0 - p0.0, p1.1 - p0.1), (p2.0 - p0.0, p2.1 - p0.1));
+    let (dt1, dt2) = ((t1.0 - t0.0, t1.1 - t0.1), (t2.0 - t0.0, t2.1 - t0.1));
+
+    let det = dt1.0 * dt2.1 - dt2.0 * dt1.1;
+    if det.abs() < f64::EPSILON {
+        return None;
+    }
+    let a = (dp1.0 * dt2.1 - dp2.0 * dt1.1) / det;
+    let b = (dp1.1 * dt2.1 - dp2.1 * dt1.1) / det;
+    let c = (dp2.0 * dt1.0 - dp1.0 * dt2.0) / det;
+    let d = (dp2.1 * dt1.0 - dp1.1 * dt2.0) / det;
+    let tx = p0.0 - (a * t0.0 + c * t0.1);
+    let ty = p0.1 - (b * t0.0 + d * t0.1);
+
+    let matrix = Matrix {
+        a: a as f32,
+        b: b as f32,
+        c: c as f32,
+        d: d as f32,
+        tx: Twips::from_pixels(tx),
+        ty: Twips::from_pixels(ty),
+    };
+    // Bitmap fill matrices map from twips.
+    Some(matrix * Matrix::scale(Twips::TWIPS_PER_PIXEL as f32, Twips::TWIPS_PER_PIXEL as f32))
+}
+
+/// Implements `Graphics.drawTriangles`.
+fn draw_triangles<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this.and_then(|t| t.as_display_object()) {
+        let vertices: Vec<(f64, f64)> = get_numbers(activation, args.get(0))?
+            .chunks_exact(2)
+            .map(|vertex| (vertex[0], vertex[1]))
+            .collect();
+        let indices: Vec<usize> = match args.get(1).unwrap_or(&Value::Null) {
+            Value::Null | Value::Undefined => (0..vertices.len()).collect(),
+            indices => get_ints(activation, Some(indices))?
+                .into_iter()
+                .map(|index| index as usize)
+                .collect(),
+        };
+        let uvt_data = get_numbers(activation, args.get(2))?;
+        // Each vertex has either (u, v) or (u, v, t) coordinates. We ignore the `t` (perspective) coordinate.
+        let uvt_size = if uvt_data.len() >= vertices.len() * 3 {
+            3
+        } else {
+            2
+        };
+        let uvs: Vec<(f64, f64)> = uvt_data
+            .chunks_exact(uvt_size)
+            .map(|uvt| (uvt[0], uvt[1]))
+            .collect();
+        let culling = args
+            .get(3)
+            .cloned()
+            .unwrap_or_else(|| "none".into())
+            .coerce_to_string(activation)?;
+        let culling = if &culling == b"none" {
+            0.0
+        } else if &culling == b"positive" {
+            1.0
+        } else if &culling == b"negative" {
+            -1.0
+        } else {
+            return Err(
+                "ArgumentError: Error #2008: Parameter culling must be one of the accepted values."
+                    .into(),
+            );
+        };
+
+        if let Some(mut draw) = this.as_drawing(activation.context.gc_context) {
+            let fill_style = draw.fill_style().cloned();
+            // A bitmap fill is mapped onto each triangle using its UV coordinates.
+            let bitmap_size = match &fill_style {
+                Some(FillStyle::Bitmap { id, .. }) if uvs.len() >= vertices.len() => draw
+                    .bitmap_size(*id)
+                    .map(|size| (size.width as f64, size.height as f64)),
+                _ => None,
+            };
+
+            for triangle in indices.chunks_exact(3) {
+                let points = match (
+                    vertices.get(triangle[0]),
+                    vertices.get(triangle[1]),
+                    vertices.get(triangle[2]),
+                ) {
+                    (Some(&a), Some(&b), Some(&c)) => [a, b, c],
+                    _ => continue,
+                };
+
+                let [a, b, c] = points;
+                let cross = (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0);
+                if cross * culling > 0.0 {
+                    continue;
+                }
+
+                // Each triangle is filled separately, so that overlapping triangles don't cancel out.
+                match (&fill_style, bitmap_size) {
+                    (
+                        Some(FillStyle::Bitmap {
+                            id,
+                            is_smoothed,
+                            is_repeating,
+                            ..
+                        }),
+                        Some((width, height)),
+                    ) => {
+                        let uv =
+                            |i: usize| (uvs[triangle[i]].0 * width, uvs[triangle[i]].1 * height);
+                        if let Some(matrix) = triangle_bitmap_matrix(points, [uv(0), uv(1), uv(2)])
+                        {
+                            draw.set_fill_style(Some(FillStyle::Bitmap {
+                                id: *id,
+                                matrix: matrix.into(),
+                                is_smoothed: *is_smoothed,
+                                is_repeating: *is_repeating,
+                            }));
+                        }
+                    }
+                    (Some(fill_style), _) => draw.set_fill_style(Some(fill_style.clone())),
+                    (None, _) => {}
+                }
+
+                let [a, b, c] = points.map(|(x, y)| (Twips::from_pixels(x), Twips::from_pixels(y)));
+                draw.draw_command(DrawCommand::MoveTo { x: a.0, y: a.1 });
+                draw.draw_command(DrawCommand::LineTo { x: b.0, y: b.1 });
+                draw.draw_command(DrawCommand::LineTo { x: c.0, y: c.1 });
+                draw.draw_command(DrawCommand::LineTo { x: a.0, y: a.1 });
+            }
+
+            if bitmap_size.is_some() {
+                // Restore the original matrix of the bitmap fill.
+                draw.set_fill_style(fill_style);
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn graphics_class<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    name: &'static str,
+) -> Result<ClassObject<'gc>, Error<'gc>> {
+    activation.resolve_class(&Multiname::new(Namespace::package("flash.display"), name))
+}
+
+/// Reads the given properties of `object`, to be passed as arguments to a `Graphics` method.
+fn get_properties<'gc>(
+    object: Object<'gc>,
+    names: &[&'static str],
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Vec<Value<'gc>>, Error<'gc>> {
+    names
+        .iter()
+        .map(|name| object.get_property(&Multiname::public(*name), activation))
+        .collect()
+}
+
+const GRADIENT_FILL_PROPERTIES: &[&str] = &[
+    "type",
+    "colors",
+    "alphas",
+    "ratios",
+    "matrix",
+    "spreadMethod",
+    "interpolationMethod",
+    "focalPointRatio",
+];
+const BITMAP_FILL_PROPERTIES: &[&str] = &["bitmapData", "matrix", "repeat", "smooth"];
+
+/// Draws a single `IGraphicsData` object, by calling the corresponding `Graphics` method.
+fn draw_graphics_data_item<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    item: Object<'gc>,
+) -> Result<(), Error<'gc>> {
+    let class = graphics_class(activation, "GraphicsSolidFill")?;
+    if item.is_of_type(class, activation) {
+        let args = get_properties(item, &["color", "alpha"], activation)?;
+        begin_fill(activation, this, &args)?;
+        return Ok(());
+    }
+
+    let class = graphics_class(activation, "GraphicsGradientFill")?;
+    if item.is_of_type(class, activation) {
+        let args = get_properties(item, GRADIENT_FILL_PROPERTIES, activation)?;
+        begin_gradient_fill(activation, this, &args)?;
+        return Ok(());
+    }
+
+    let class = graphics_class(activation, "GraphicsBitmapFill")?;
+    if item.is_of_type(class, activation) {
+        let args = get_properties(item, BITMAP_FILL_PROPERTIES, activation)?;
+        begin_bitmap_fill(activation, this, &args)?;
+        return Ok(());
+    }
+
+    let class = graphics_class(activation, "GraphicsShaderFill")?;
+    if item.is_of_type(class, activation) {
+        let args = get_properties(item, &["shader", "matrix"], activation)?;
+        begin_shader_fill(activation, this, &args)?;
+        return Ok(());
+    }
+
+    let class = graphics_class(activation, "GraphicsEndFill")?;
+    if item.is_of_type(class, activation) {
+        end_fill(activation, this, &[])?;
+        return Ok(());
+    }
+
+    let class = graphics_class(activation, "GraphicsStroke")?;
+    if item.is_of_type(class, activation) {
+        let fill = item
+            .get_property(&Multiname::public("fill"), activation)?
+            .as_object();
+        let solid_fill_class = graphics_class(activation, "GraphicsSolidFill")?;
+        let (color, alpha) = match fill {
+            Some(fill) if fill.is_of_type(solid_fill_class, activation) => {
+                let color = fill.get_property(&Multiname::public("color"), activation)?;
+                let alpha = fill.get_property(&Multiname::public("alpha"), activation)?;
+                (color, alpha)
+            }
+            _ => (0.into(), 1.0.into()),
+        };
+
+        let mut args = get_properties(
+            item,
+            &[
+                "thickness",
+                "pixelHinting",
+                "scaleMode",
+                "caps",
+                "joints",
+                "miterLimit",
+            ],
+            activation,
+        )?;
+        args.splice(1..1, [color, alpha]);
+        line_style(activation, this, &args)?;
+
+        if let Some(fill) = fill {
+            let class = graphics_class(activation, "GraphicsGradientFill")?;
+            if fill.is_of_type(class, activation) {
+                let args = get_properties(fill, GRADIENT_FILL_PROPERTIES, activation)?;
+                line_gradient_style(activation, this, &args)?;
+            }
+
+            let class = graphics_class(activation, "GraphicsBitmapFill")?;
+            if fill.is_of_type(class, activation) {
+                let args = get_properties(fill, BITMAP_FILL_PROPERTIES, activation)?;
+                line_bitmap_style(activation, this, &args)?;
+            }
+        }
+        return Ok(());
+    }
+
+    let class = graphics_class(activation, "GraphicsPath")?;
+    if item.is_of_type(class, activation) {
+        let args = get_properties(item, &["commands", "data", "winding"], activation)?;
+        draw_path(activation, this, &args)?;
+        return Ok(());
+    }
+
+    let class = graphics_class(activation, "GraphicsTrianglePath")?;
+    if item.is_of_type(class, activation) {
+        let args = get_properties(
+            item,
+            &["vertices", "indices", "uvtData", "culling"],
+            activation,
+        )?;
+        draw_triangles(activation, this, &args)?;
+        return Ok(());
+    }
+
+    Ok(())
+}
+
+/// Implements `Graphics.drawGraphicsData`.
+fn draw_graphics_data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    for item in get_list(args.get(0)) {
+        if let Some(item) = item.as_object() {
+            draw_graphics_data_item(activation, this, item)?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn spread_to_value<'gc>(spread: GradientSpread) -> Value<'gc> {
+    match spread {
+        GradientSpread::Pad => "pad".into(),
+        GradientSpread::Reflect => "reflect".into(),
+        GradientSpread::Repeat => "repeat".into(),
+    }
+}
+
+fn interpolation_to_value<'gc>(interpolation: GradientInterpolation) -> Value<'gc> {
+    match interpolation {
+        GradientInterpolation::Rgb => "rgb".into(),
+        GradientInterpolation::LinearRgb => "linearRGB".into(),
+    }
+}
+
+/// Converts a fill style to a `GraphicsSolidFill`, `GraphicsGradientFill` or `GraphicsBitmapFill`.
+fn fill_style_to_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    style: &FillStyle,
+    transform: Matrix,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (class_name, args): (&'static str, Vec<Value<'gc>>) = match style {
+        FillStyle::Color(color) => (
+            "GraphicsSolidFill",
+            vec![color.to_rgb().into(), (f64::from(color.a) / 255.0).into()],
+        ),
+        FillStyle::LinearGradient(gradient)
+        | FillStyle::RadialGradient(gradient)
+        | FillStyle::FocalGradient { gradient, .. } => {
+            let (gradient_type, focal_point) = match style {
+                FillStyle::LinearGradient(_) => ("linear", 0.0),
+                FillStyle::FocalGradient { focal_point, .. } => ("radial", focal_point.to_f64()),
+                _ => ("radial", 0.0),
+            };
+            let colors: Vec<Value<'gc>> = gradient
+                .records
+                .iter()
+                .map(|record| record.color.to_rgb().into())
+                .collect();
+            let alphas: Vec<Value<'gc>> = gradient
+                .records
+                .iter()
+                .map(|record| (f64::from(record.color.a) / 255.0).into())
+                .collect();
+            let ratios: Vec<Value<'gc>> = gradient
+                .records
+                .iter()
+                .map(|record| record.ratio.into())
+                .collect();
+            let matrix = transform * Matrix::from(gradient.matrix);
+            (
+                "GraphicsGradientFill",
+                vec![
+                    gradient_type.into(),
+                    ArrayObject::from_storage(activation, ArrayStorage::from_args(&colors))?.into(),
+                    ArrayObject::from_storage(activation, ArrayStorage::from_args(&alphas))?.into(),
+                    ArrayObject::from_storage(activation, ArrayStorage::from_args(&ratios))?.into(),
+                    matrix_to_object(matrix, activation)?,
+                    spread_to_value(gradient.spread),
+                    interpolation_to_value(gradient.interpolation),
+                    focal_point.into(),
+                ],
+            )
+        }
+        FillStyle::Bitmap {
+            matrix,
+            is_smoothed,
+            is_repeating,
+            ..
+        } => {
+            // FIXME - drawings don't keep track of the `BitmapData` used by their bitmap fills.
+            log::warn!("Graphics.readGraphicsData: bitmapData of bitmap fills is not implemented");
+            let matrix = transform
+                * Matrix::from(*matrix)
+                * Matrix::scale(
+                    1.0 / Twips::TWIPS_PER_PIXEL as f32,
+                    1.0 / Twips::TWIPS_PER_PIXEL as f32,
+                );
+            (
+                "GraphicsBitmapFill",
+                vec![
+                    Value::Null,
+                    matrix_to_object(matrix, activation)?,
+                    (*is_repeating).into(),
+                    (*is_smoothed).into(),
+                ],
+            )
+        }
+    };
+
+    let class = graphics_class(activation, class_name)?;
+    Ok(class.construct(activation, &args)?.into())
+}
+
+/// Converts a line style to a `GraphicsStroke`.
+fn line_style_to_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    style: &LineStyle,
+    transform: Matrix,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let scale_mode = match (style.allow_scale_x(), style.allow_scale_y()) {
+        (true, true) => "normal",
+        (true, false) => "horizontal",
+        (false, true) => "vertical",
+        (false, false) => "none",
+    };
+    let caps = match style.start_cap() {
+        LineCapStyle::None => "none",
+        LineCapStyle::Round => "round",
+        LineCapStyle::Square => "square",
+    };
+    let (joints, miter_limit) = match style.join_style() {
+        LineJoinStyle::Round => ("round", 3.0),
+        LineJoinStyle::Bevel => ("bevel", 3.0),
+        LineJoinStyle::Miter(limit) => ("miter", limit.to_f64()),
+    };
+    let fill = fill_style_to_object(activation, style.fill_style(), transform)?;
+
+    let args = [
+        style.width().to_pixels().into(),
+        style.is_pixel_hinted().into(),
+        scale_mode.into(),
+        caps.into(),
+        joints.into(),
+        miter_limit.into(),
+        fill,
+    ];
+    let class = graphics_class(activation, "GraphicsStroke")?;
+    Ok(class.construct(activation, &args)?.into())
+}
+
+/// Converts a list of draw commands to a `GraphicsPath`.
+fn draw_commands_to_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    commands: &[DrawCommand],
+    winding_rule: FillRule,
+    transform: Matrix,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut path_commands = Vec::with_capacity(commands.len());
+    let mut data = Vec::with_capacity(commands.len() * 2);
+    let mut push_point = |x: Twips, y: Twips| {
+        let (x, y) = transform * (x, y);
+        data.push(x.to_pixels().into());
+        data.push(y.to_pixels().into());
+    };
+    for (i, command) in commands.iter().enumerate() {
+        match *command {
+            DrawCommand::MoveTo { .. }
+                if matches!(commands.get(i + 1), Some(DrawCommand::MoveTo { .. })) =>
+            {
+                // Only the last of several consecutive moves has any effect.
+            }
+            DrawCommand::MoveTo { x, y } => {
+                path_commands.push(PATH_MOVE_TO.into());
+                push_point(x, y);
+            }
+            DrawCommand::LineTo { x, y } => {
+                path_commands.push(PATH_LINE_TO.into());
+                push_point(x, y);
+            }
+            DrawCommand::CurveTo { x1, y1, x2, y2 } => {
+                path_commands.push(PATH_CURVE_TO.into());
+                push_point(x1, y1);
+                push_point(x2, y2);
+            }
+        }
+    }
+
+    let int_class = activation.avm2().classes().int;
+    let path_commands = VectorStorage::from_values(path_commands, false, int_class);
+    let number_class = activation.avm2().classes().number;
+    let data = VectorStorage::from_values(data, false, number_class);
+    let winding = match winding_rule {
+        FillRule::EvenOdd => "evenOdd",
+        FillRule::NonZero => "nonZero",
+    };
+
+    let args = [
+        VectorObject::from_vector(path_commands, activation)?.into(),
+        VectorObject::from_vector(data, activation)?.into(),
+        winding.into(),
+    ];
+    let class = graphics_class(activation, "GraphicsPath")?;
+    Ok(class.construct(activation, &args)?.into())
+}
+
+/// Appends the `IGraphicsData` objects describing the drawing of `dobj` (and its children,
+/// if `recurse` is set) to `items`. `transform` maps from `dobj` to the reading object.
+fn read_graphics_data_of<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    dobj: DisplayObject<'gc>,
+    transform: Matrix,
+    recurse: bool,
+    items: &mut Vec<Value<'gc>>,
+) -> Result<(), Error<'gc>> {
+    let drawing = dobj
+        .as_drawing(activation.context.gc_context)
+        .map(|drawing| drawing.clone());
+
+    if let Some(drawing) = drawing {
+        for path in drawing.draw_paths() {
+            let commands = match &path {
+                DrawPath::Fill { commands, .. } | DrawPath::Stroke { commands, .. } => commands,
+            };
+            // Paths that only move the pen don't draw anything.
+            if commands
+                .iter()
+                .all(|command| matches!(command, DrawCommand::MoveTo { .. }))
+            {
+                continue;
+            }
+
+            match path {
+                DrawPath::Fill {
+                    style,
+                    commands,
+                    winding_rule,
+                } => {
+                    items.push(fill_style_to_object(activation, style, transform)?);
+                    items.push(draw_commands_to_object(
+                        activation,
+                        &commands,
+                        winding_rule,
+                        transform,
+                    )?);
+                    let class = graphics_class(activation, "GraphicsEndFill")?;
+                    items.push(class.construct(activation, &[])?.into());
+                }
+                DrawPath::Stroke {
+                    style, commands, ..
+                } => {
+                    items.push(line_style_to_object(activation, style, transform)?);
+                    items.push(draw_commands_to_object(
+                        activation,
+                        &commands,
+                        FillRule::EvenOdd,
+                        transform,
+                    )?);
+                    // A stroke with a `NaN` thickness ends the line.
+                    let class = graphics_class(activation, "GraphicsStroke")?;
+                    items.push(class.construct(activation, &[])?.into());
+                }
+            }
+        }
+    }
+
+    if recurse {
+        if let Some(container) = dobj.as_container() {
+            for child in container.iter_render_list() {
+                let child_transform = transform * *child.base().matrix();
+                read_graphics_data_of(activation, child, child_transform, recurse, items)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Implements `Graphics.readGraphicsData`.
+fn read_graphics_data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this.and_then(|t| t.as_display_object()) {
+        let recurse = args.get(0).map_or(true, |v| v.coerce_to_boolean());
+
+        let mut items = Vec::new();
+        read_graphics_data_of(activation, this, Matrix::IDENTITY, recurse, &mut items)?;
+
+        let class = graphics_class(activation, "IGraphicsData")?;
+        let storage = VectorStorage::from_values(items, false, class);
+        return Ok(VectorObject::from_vector(storage, activation)?.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.copyFrom`.
+fn copy_from<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this.and_then(|t| t.as_display_object()) {
+        let source = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .as_object()
+            .and_then(|source| source.as_display_object())
+            .ok_or_else(|| {
+                Error::from("TypeError: Error #2007: Parameter sourceGraphics must be non-null.")
+            })?;
+
+        let source = source
+            .as_drawing(activation.context.gc_context)
+            .map(|drawing| drawing.clone());
+        if let (Some(source), Some(mut draw)) =
+            (source, this.as_drawing(activation.context.gc_context))
+        {
+            draw.copy_from(&source);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Construct `Graphics`'s class.
 pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
     let class = Class::new(
@@ -820,6 +1797,15 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("drawRoundRect", draw_round_rect),
         ("drawCircle", draw_circle),
         ("drawEllipse", draw_ellipse),
+        ("beginShaderFill", begin_shader_fill),
+        ("lineGradientStyle", line_gradient_style),
+        ("lineBitmapStyle", line_bitmap_style),
+        ("cubicCurveTo", cubic_curve_to),
+        ("drawPath", draw_path),
+        ("drawTriangles", draw_triangles),
+        ("drawGraphicsData", draw_graphics_data),
+        ("readGraphicsData", read_graphics_data),
+        ("copyFrom", copy_from),
     ];
     write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
 
@@ -3,4 +3,5 @@
 pub mod context_3d;
 pub mod index_buffer_3d;
 pub mod program_3d;
+pub mod textures;
 pub mod vertex_buffer_3d;
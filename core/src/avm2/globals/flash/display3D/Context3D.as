@@ -1,6 +1,12 @@
 package flash.display3D {
+    import flash.display.BitmapData;
     import flash.events.EventDispatcher;
     import flash.geom.Matrix3D;
+    import flash.geom.Rectangle;
+    import flash.display3D.textures.CubeTexture;
+    import flash.display3D.textures.RectangleTexture;
+    import flash.display3D.textures.Texture;
+    import flash.display3D.textures.TextureBase;
 
     public final class Context3D extends EventDispatcher {
         public native function clear(red:Number = 0.0, green:Number = 0.0, blue:Number = 0.0, alpha:Number = 1.0, depth:Number = 1.0, stencil:uint = 0, mask:uint = 0xffffffff):void;
@@ -17,6 +23,28 @@ package flash.display3D {
         public native function present():void;
         public native function setCulling(triangleFaceToCull:String):void;
 
+        public native function createTexture(width:int, height:int, format:String, optimizeForRenderToTexture:Boolean, streamingLevels:int = 0):Texture;
+        public native function createRectangleTexture(width:int, height:int, format:String, optimizeForRenderToTexture:Boolean):RectangleTexture;
+        public native function createCubeTexture(size:int, format:String, optimizeForRenderToTexture:Boolean, streamingLevels:int = 0):CubeTexture;
+        public native function setTextureAt(sampler:int, texture:TextureBase):void;
+        public native function setSamplerStateAt(sampler:int, wrap:String, filter:String, mipfilter:String):void;
+
+        public native function setBlendFactors(sourceFactor:String, destinationFactor:String):void;
+        public native function setDepthTest(depthMask:Boolean, passCompareMode:String):void;
+        public native function setStencilActions(
+            triangleFace:String = "frontAndBack", compareMode:String = "always", actionOnBothPass:String = "keep",
+            actionOnDepthFail:String = "keep", actionOnDepthPassStencilFail:String = "keep"
+        ):void;
+        public native function setStencilReferenceValue(referenceValue:uint, readMask:uint = 255, writeMask:uint = 255):void;
+        public native function setScissorRectangle(rectangle:Rectangle):void;
+        public native function setColorMask(red:Boolean, green:Boolean, blue:Boolean, alpha:Boolean):void;
+
+        public native function setRenderToTexture(
+            texture:TextureBase, enableDepthAndStencil:Boolean = false, antiAlias:int = 0, surfaceSelector:int = 0, colorOutputIndex:int = 0
+        ):void;
+        public native function setRenderToBackBuffer():void;
+        public native function drawToBitmapData(destination:BitmapData):void;
+
         // FIXME - implement this
         public function get driverInfo():String {
             return "Dummy Ruffle driver";
@@ -1,9 +1,18 @@
 use ruffle_render::backend::BufferUsage;
+use ruffle_render::backend::Context3DBlendFactor;
+use ruffle_render::backend::Context3DCompareMode;
+use ruffle_render::backend::Context3DMipFilter;
+use ruffle_render::backend::Context3DRectangle;
+use ruffle_render::backend::Context3DStencilAction;
+use ruffle_render::backend::Context3DTextureFilter;
+use ruffle_render::backend::Context3DTextureFormat;
 use ruffle_render::backend::Context3DTriangleFace;
 use ruffle_render::backend::Context3DVertexBufferFormat;
+use ruffle_render::backend::Context3DWrapMode;
 use ruffle_render::backend::ProgramType;
 
 use crate::avm2::Activation;
+use crate::avm2::AvmString;
 use crate::avm2::Multiname;
 use crate::avm2::TObject;
 use crate::avm2::Value;
@@ -197,18 +206,10 @@ pub fn set_culling<'gc>(
             .unwrap_or(&Value::Undefined)
             .coerce_to_string(activation)?;
 
-        let culling = if &*culling == b"none" {
-            Context3DTriangleFace::None
-        } else if &*culling == b"back" {
-            Context3DTriangleFace::Back
-        } else if &*culling == b"front" {
-            Context3DTriangleFace::Front
-        } else if &*culling == b"front_and_back" {
-            Context3DTriangleFace::FrontAndBack
-        } else {
+        let culling = parse_triangle_face(culling).unwrap_or_else(|| {
             log::error!("Unknown culling {:?}", culling);
             Context3DTriangleFace::None
-        };
+        });
 
         context.set_culling(activation, culling);
     }
@@ -299,3 +300,566 @@ pub fn clear<'gc>(
     }
     Ok(Value::Undefined)
 }
+
+fn texture_format<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    format: &Value<'gc>,
+) -> Result<Context3DTextureFormat, Error<'gc>> {
+    let format = format.coerce_to_string(activation)?;
+    if &*format == b"bgra" {
+        Ok(Context3DTextureFormat::Bgra)
+    } else if &*format == b"bgraPacked4444" {
+        Ok(Context3DTextureFormat::BgraPacked)
+    } else if &*format == b"bgrPacked565" {
+        Ok(Context3DTextureFormat::BgrPacked)
+    } else if &*format == b"compressed" {
+        Ok(Context3DTextureFormat::Compressed)
+    } else if &*format == b"compressedAlpha" {
+        Ok(Context3DTextureFormat::CompressedAlpha)
+    } else if &*format == b"rgbaHalfFloat" {
+        Ok(Context3DTextureFormat::RgbaHalfFloat)
+    } else {
+        Err(
+            "ArgumentError: Error #2008: Parameter format must be one of the accepted values."
+                .into(),
+        )
+    }
+}
+
+pub fn create_texture<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(context) = this.and_then(|this| this.as_context_3d()) {
+        let width = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+        let height = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+        let format = texture_format(activation, args.get(2).unwrap_or(&Value::Undefined))?;
+        let optimize_for_render_to_texture =
+            args.get(3).unwrap_or(&Value::Undefined).coerce_to_boolean();
+        let streaming_levels = args
+            .get(4)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+        return context.create_texture(
+            activation,
+            width,
+            height,
+            format,
+            optimize_for_render_to_texture,
+            streaming_levels,
+        );
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn create_rectangle_texture<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(context) = this.and_then(|this| this.as_context_3d()) {
+        let width = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+        let height = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+        let format = texture_format(activation, args.get(2).unwrap_or(&Value::Undefined))?;
+        let optimize_for_render_to_texture =
+            args.get(3).unwrap_or(&Value::Undefined).coerce_to_boolean();
+        return context.create_rectangle_texture(
+            activation,
+            width,
+            height,
+            format,
+            optimize_for_render_to_texture,
+        );
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn create_cube_texture<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(context) = this.and_then(|this| this.as_context_3d()) {
+        let size = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+        let format = texture_format(activation, args.get(1).unwrap_or(&Value::Undefined))?;
+        let optimize_for_render_to_texture =
+            args.get(2).unwrap_or(&Value::Undefined).coerce_to_boolean();
+        let streaming_levels = args
+            .get(3)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+        return context.create_cube_texture(
+            activation,
+            size,
+            format,
+            optimize_for_render_to_texture,
+            streaming_levels,
+        );
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn set_texture_at<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(context) = this.and_then(|this| this.as_context_3d()) {
+        let sampler = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+        if sampler >= 8 {
+            return Err("RangeError: Error #3670: Sampler index is out of range.".into());
+        }
+
+        // Passing `null` unbinds the texture.
+        let texture = match args.get(1).unwrap_or(&Value::Null) {
+            Value::Null | Value::Undefined => None,
+            texture => Some(
+                texture
+                    .coerce_to_object(activation)?
+                    .as_texture()
+                    .ok_or_else(|| Error::from("TypeError: Parameter must be a TextureBase"))?,
+            ),
+        };
+
+        context.set_texture_at(activation, sampler, texture);
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn set_sampler_state_at<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(context) = this.and_then(|this| this.as_context_3d()) {
+        let sampler = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+        if sampler >= 8 {
+            return Err("RangeError: Error #3670: Sampler index is out of range.".into());
+        }
+
+        let wrap = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation)?;
+        let wrap = if &*wrap == b"clamp" {
+            Context3DWrapMode::Clamp
+        } else if &*wrap == b"clamp_u_repeat_v" {
+            Context3DWrapMode::ClampURepeatV
+        } else if &*wrap == b"repeat" {
+            Context3DWrapMode::Repeat
+        } else if &*wrap == b"repeat_u_clamp_v" {
+            Context3DWrapMode::RepeatUClampV
+        } else {
+            return Err(
+                "ArgumentError: Error #2008: Parameter wrap must be one of the accepted values."
+                    .into(),
+            );
+        };
+
+        let filter = args
+            .get(2)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation)?;
+        let filter = if &*filter == b"nearest" {
+            Context3DTextureFilter::Nearest
+        } else if &*filter == b"linear" {
+            Context3DTextureFilter::Linear
+        } else if &*filter == b"anisotropic2x" {
+            Context3DTextureFilter::Anisotropic2X
+        } else if &*filter == b"anisotropic4x" {
+            Context3DTextureFilter::Anisotropic4X
+        } else if &*filter == b"anisotropic8x" {
+            Context3DTextureFilter::Anisotropic8X
+        } else if &*filter == b"anisotropic16x" {
+            Context3DTextureFilter::Anisotropic16X
+        } else {
+            return Err(
+                "ArgumentError: Error #2008: Parameter filter must be one of the accepted values."
+                    .into(),
+            );
+        };
+
+        let mip_filter = args
+            .get(3)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation)?;
+        let mip_filter = if &*mip_filter == b"mipnone" {
+            Context3DMipFilter::MipNone
+        } else if &*mip_filter == b"mipnearest" {
+            Context3DMipFilter::MipNearest
+        } else if &*mip_filter == b"miplinear" {
+            Context3DMipFilter::MipLinear
+        } else {
+            return Err(
+                "ArgumentError: Error #2008: Parameter mipfilter must be one of the accepted values."
+                    .into(),
+            );
+        };
+
+        context.set_sampler_state_at(activation, sampler, wrap, filter, mip_filter);
+    }
+    Ok(Value::Undefined)
+}
+
+fn parse_triangle_face(face: AvmString) -> Option<Context3DTriangleFace> {
+    if &*face == b"none" {
+        Some(Context3DTriangleFace::None)
+    } else if &*face == b"back" {
+        Some(Context3DTriangleFace::Back)
+    } else if &*face == b"front" {
+        Some(Context3DTriangleFace::Front)
+    } else if &*face == b"frontAndBack" {
+        Some(Context3DTriangleFace::FrontAndBack)
+    } else {
+        None
+    }
+}
+
+fn parse_blend_factor<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: &Value<'gc>,
+    param_name: &str,
+) -> Result<Context3DBlendFactor, Error<'gc>> {
+    let factor = value.coerce_to_string(activation)?;
+    Ok(if &*factor == b"destinationAlpha" {
+        Context3DBlendFactor::DestinationAlpha
+    } else if &*factor == b"destinationColor" {
+        Context3DBlendFactor::DestinationColor
+    } else if &*factor == b"one" {
+        Context3DBlendFactor::One
+    } else if &*factor == b"oneMinusDestinationAlpha" {
+        Context3DBlendFactor::OneMinusDestinationAlpha
+    } else if &*factor == b"oneMinusDestinationColor" {
+        Context3DBlendFactor::OneMinusDestinationColor
+    } else if &*factor == b"oneMinusSourceAlpha" {
+        Context3DBlendFactor::OneMinusSourceAlpha
+    } else if &*factor == b"oneMinusSourceColor" {
+        Context3DBlendFactor::OneMinusSourceColor
+    } else if &*factor == b"sourceAlpha" {
+        Context3DBlendFactor::SourceAlpha
+    } else if &*factor == b"sourceColor" {
+        Context3DBlendFactor::SourceColor
+    } else if &*factor == b"zero" {
+        Context3DBlendFactor::Zero
+    } else {
+        return Err(format!(
+            "ArgumentError: Error #2008: Parameter {param_name} must be one of the accepted values."
+        )
+        .into());
+    })
+}
+
+fn parse_compare_mode<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: &Value<'gc>,
+    param_name: &str,
+) -> Result<Context3DCompareMode, Error<'gc>> {
+    let mode = value.coerce_to_string(activation)?;
+    Ok(if &*mode == b"always" {
+        Context3DCompareMode::Always
+    } else if &*mode == b"equal" {
+        Context3DCompareMode::Equal
+    } else if &*mode == b"greater" {
+        Context3DCompareMode::Greater
+    } else if &*mode == b"greaterEqual" {
+        Context3DCompareMode::GreaterEqual
+    } else if &*mode == b"less" {
+        Context3DCompareMode::Less
+    } else if &*mode == b"lessEqual" {
+        Context3DCompareMode::LessEqual
+    } else if &*mode == b"never" {
+        Context3DCompareMode::Never
+    } else if &*mode == b"notEqual" {
+        Context3DCompareMode::NotEqual
+    } else {
+        return Err(format!(
+            "ArgumentError: Error #2008: Parameter {param_name} must be one of the accepted values."
+        )
+        .into());
+    })
+}
+
+fn parse_stencil_action<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: &Value<'gc>,
+    param_name: &str,
+) -> Result<Context3DStencilAction, Error<'gc>> {
+    let action = value.coerce_to_string(activation)?;
+    Ok(if &*action == b"decrementSaturate" {
+        Context3DStencilAction::DecrementSaturate
+    } else if &*action == b"decrementWrap" {
+        Context3DStencilAction::DecrementWrap
+    } else if &*action == b"incrementSaturate" {
+        Context3DStencilAction::IncrementSaturate
+    } else if &*action == b"incrementWrap" {
+        Context3DStencilAction::IncrementWrap
+    } else if &*action == b"invert" {
+        Context3DStencilAction::Invert
+    } else if &*action == b"keep" {
+        Context3DStencilAction::Keep
+    } else if &*action == b"set" {
+        Context3DStencilAction::Set
+    } else if &*action == b"zero" {
+        Context3DStencilAction::Zero
+    } else {
+        return Err(format!(
+            "ArgumentError: Error #2008: Parameter {param_name} must be one of the accepted values."
+        )
+        .into());
+    })
+}
+
+pub fn set_blend_factors<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(context) = this.and_then(|this| this.as_context_3d()) {
+        let source_factor = parse_blend_factor(
+            activation,
+            args.get(0).unwrap_or(&Value::Undefined),
+            "sourceFactor",
+        )?;
+        let destination_factor = parse_blend_factor(
+            activation,
+            args.get(1).unwrap_or(&Value::Undefined),
+            "destinationFactor",
+        )?;
+
+        context.set_blend_factors(activation, source_factor, destination_factor);
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn set_depth_test<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(context) = this.and_then(|this| this.as_context_3d()) {
+        let depth_mask = args.get(0).unwrap_or(&Value::Undefined).coerce_to_boolean();
+        let pass_compare_mode = parse_compare_mode(
+            activation,
+            args.get(1).unwrap_or(&Value::Undefined),
+            "passCompareMode",
+        )?;
+
+        context.set_depth_test(activation, depth_mask, pass_compare_mode);
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn set_stencil_actions<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(context) = this.and_then(|this| this.as_context_3d()) {
+        let triangle_face = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation)?;
+        let triangle_face = parse_triangle_face(triangle_face).ok_or_else(|| {
+            Error::from(
+                "ArgumentError: Error #2008: Parameter triangleFace must be one of the accepted values.",
+            )
+        })?;
+        let compare_mode = parse_compare_mode(
+            activation,
+            args.get(1).unwrap_or(&Value::Undefined),
+            "compareMode",
+        )?;
+        let action_on_both_pass = parse_stencil_action(
+            activation,
+            args.get(2).unwrap_or(&Value::Undefined),
+            "actionOnBothPass",
+        )?;
+        let action_on_depth_fail = parse_stencil_action(
+            activation,
+            args.get(3).unwrap_or(&Value::Undefined),
+            "actionOnDepthFail",
+        )?;
+        let action_on_depth_pass_stencil_fail = parse_stencil_action(
+            activation,
+            args.get(4).unwrap_or(&Value::Undefined),
+            "actionOnDepthPassStencilFail",
+        )?;
+
+        context.set_stencil_actions(
+            activation,
+            triangle_face,
+            compare_mode,
+            action_on_both_pass,
+            action_on_depth_fail,
+            action_on_depth_pass_stencil_fail,
+        );
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn set_stencil_reference_value<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(context) = this.and_then(|this| this.as_context_3d()) {
+        let reference_value = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+        let read_mask = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+        let write_mask = args
+            .get(2)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+
+        context.set_stencil_reference_value(activation, reference_value, read_mask, write_mask);
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn set_scissor_rectangle<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(context) = this.and_then(|this| this.as_context_3d()) {
+        // Passing `null` disables scissoring.
+        let rectangle = match args.get(0).unwrap_or(&Value::Null) {
+            Value::Null | Value::Undefined => None,
+            rectangle => {
+                let rectangle = rectangle.coerce_to_object(activation)?;
+                const NAMES: &[&str] = &["x", "y", "width", "height"];
+                let mut values = [0; 4];
+                for (&name, value) in NAMES.iter().zip(&mut values) {
+                    *value = rectangle
+                        .get_property(&Multiname::public(name), activation)?
+                        .coerce_to_number(activation)?
+                        .max(0.0) as u32;
+                }
+                let [x, y, width, height] = values;
+                Some(Context3DRectangle {
+                    x,
+                    y,
+                    width,
+                    height,
+                })
+            }
+        };
+
+        context.set_scissor_rectangle(activation, rectangle);
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn set_color_mask<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(context) = this.and_then(|this| this.as_context_3d()) {
+        let red = args.get(0).unwrap_or(&Value::Undefined).coerce_to_boolean();
+        let green = args.get(1).unwrap_or(&Value::Undefined).coerce_to_boolean();
+        let blue = args.get(2).unwrap_or(&Value::Undefined).coerce_to_boolean();
+        let alpha = args.get(3).unwrap_or(&Value::Undefined).coerce_to_boolean();
+
+        context.set_color_mask(activation, red, green, blue, alpha);
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn set_render_to_texture<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(context) = this.and_then(|this| this.as_context_3d()) {
+        let texture = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .as_object()
+            .and_then(|texture| texture.as_texture())
+            .ok_or_else(|| {
+                Error::from("TypeError: Error #2007: Parameter texture must be non-null.")
+            })?;
+        let enable_depth_and_stencil = args.get(1).unwrap_or(&Value::Undefined).coerce_to_boolean();
+        let anti_alias = args
+            .get(2)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+        let surface_selector = args
+            .get(3)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+        let color_output_index = args
+            .get(4)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+
+        context.set_render_to_texture(
+            activation,
+            texture,
+            enable_depth_and_stencil,
+            anti_alias,
+            surface_selector,
+            color_output_index,
+        );
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn set_render_to_back_buffer<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(context) = this.and_then(|this| this.as_context_3d()) {
+        context.set_render_to_back_buffer(activation);
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn draw_to_bitmap_data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(context) = this.and_then(|this| this.as_context_3d()) {
+        let bitmap_data = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .as_object()
+            .and_then(|destination| destination.as_bitmap_data())
+            .ok_or_else(|| {
+                Error::from("TypeError: Error #2007: Parameter destination must be non-null.")
+            })?;
+
+        context.draw_to_bitmap_data(activation, bitmap_data)?;
+    }
+    Ok(Value::Undefined)
+}
@@ -0,0 +1,6 @@
+//! `flash.display3D.textures` namespace
+
+pub mod cube_texture;
+pub mod rectangle_texture;
+pub mod texture;
+pub mod texture_base;
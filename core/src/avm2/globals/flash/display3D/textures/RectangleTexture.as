@@ -0,0 +1,10 @@
+package flash.display3D.textures {
+    import flash.display.BitmapData;
+    import flash.utils.ByteArray;
+
+    [Ruffle(InstanceAllocator)]
+    public final class RectangleTexture extends TextureBase {
+        public native function uploadFromBitmapData(source:BitmapData):void;
+        public native function uploadFromByteArray(data:ByteArray, byteArrayOffset:uint):void;
+    }
+}
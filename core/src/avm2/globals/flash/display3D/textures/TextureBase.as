@@ -0,0 +1,26 @@
+package flash.display3D.textures {
+    import flash.events.Event;
+    import flash.events.EventDispatcher;
+    import flash.utils.ByteArray;
+    import flash.utils.setTimeout;
+
+    public class TextureBase extends EventDispatcher {
+        // FIXME - actually free the texture
+        public function dispose():void {}
+
+        // Shared by `Texture` and `CubeTexture`.
+        internal function uploadCompressed(data:ByteArray, byteArrayOffset:uint, async:Boolean):void {
+            this.uploadCompressedTextureFromByteArrayInternal(data, byteArrayOffset);
+            if (async) {
+                // The texture is uploaded synchronously, but the event
+                // must still be dispatched after this method returns.
+                var texture = this;
+                setTimeout(function() {
+                    texture.dispatchEvent(new Event("textureReady"));
+                }, 0);
+            }
+        }
+
+        private native function uploadCompressedTextureFromByteArrayInternal(data:ByteArray, byteArrayOffset:uint):void;
+    }
+}
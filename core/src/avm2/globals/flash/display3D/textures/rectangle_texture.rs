@@ -0,0 +1,53 @@
+use crate::avm2::Activation;
+use crate::avm2::ClassObject;
+use crate::avm2::TObject;
+use crate::avm2::Value;
+use crate::avm2::{Error, Object};
+
+use super::texture_base::{upload_bitmap_data, upload_byte_array};
+
+pub fn rectangle_texture_allocator<'gc>(
+    _class: ClassObject<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    panic!("This allocator should not be called!")
+}
+
+pub fn upload_from_bitmap_data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(texture) = this.and_then(|this| this.as_texture()) {
+        upload_bitmap_data(
+            activation,
+            texture,
+            args.get(0).unwrap_or(&Value::Undefined),
+            0,
+            0,
+        )?;
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn upload_from_byte_array<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(texture) = this.and_then(|this| this.as_texture()) {
+        let byte_array_offset = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)?;
+        upload_byte_array(
+            activation,
+            texture,
+            args.get(0).unwrap_or(&Value::Undefined),
+            byte_array_offset,
+            0,
+            0,
+        )?;
+    }
+    Ok(Value::Undefined)
+}
@@ -0,0 +1,142 @@
+use ruffle_render::atf::decode_atf;
+use ruffle_render::backend::Context3DTextureFormat;
+
+use crate::avm2::object::TextureObject;
+use crate::avm2::Activation;
+use crate::avm2::TObject;
+use crate::avm2::Value;
+use crate::avm2::{Error, Object};
+
+pub fn upload_compressed_texture_from_byte_array_internal<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(texture) = this.and_then(|this| this.as_texture()) {
+        let byte_array = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_object(activation)?;
+        let byte_array = byte_array
+            .as_bytearray()
+            .ok_or_else(|| Error::from("ArgumentError: Parameter must be a ByteArray"))?;
+        let byte_array_offset = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation)? as usize;
+
+        let data = byte_array.read_at(
+            byte_array.len().saturating_sub(byte_array_offset),
+            byte_array_offset,
+        )?;
+        let atf = match decode_atf(data) {
+            Ok(atf) => atf,
+            Err(e) => {
+                log::warn!("uploadCompressedTextureFromByteArray: {e}");
+                return Ok(Value::Undefined);
+            }
+        };
+        drop(byte_array);
+
+        if atf.width != texture.width() || atf.height != texture.height() {
+            log::warn!(
+                "uploadCompressedTextureFromByteArray: ATF data is {}x{}, but the texture is {}x{}",
+                atf.width,
+                atf.height,
+                texture.width(),
+                texture.height()
+            );
+        }
+
+        for (side, levels) in atf.faces.into_iter().enumerate() {
+            for (mip_level, data) in levels.into_iter().enumerate() {
+                texture.context3d().upload_texture_data(
+                    activation,
+                    texture,
+                    side as u32,
+                    mip_level as u32,
+                    data,
+                );
+            }
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+/// Uploads the contents of a `BitmapData` to a mip level of `texture`.
+pub fn upload_bitmap_data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    texture: TextureObject<'gc>,
+    source: &Value<'gc>,
+    side: u32,
+    mip_level: u32,
+) -> Result<(), Error<'gc>> {
+    let source = source
+        .coerce_to_object(activation)?
+        .as_bitmap_data()
+        .ok_or_else(|| Error::from("ArgumentError: Parameter must be a BitmapData"))?;
+    let source = source.read();
+
+    let (width, height) = mip_level_size(texture, mip_level);
+    if source.width() != width || source.height() != height {
+        log::warn!(
+            "uploadFromBitmapData: BitmapData is {}x{}, but mip level {mip_level} is {width}x{height}",
+            source.width(),
+            source.height()
+        );
+        return Ok(());
+    }
+
+    // Like Flash, we upload premultiplied pixels.
+    let data = source.pixels_rgba();
+    drop(source);
+
+    texture
+        .context3d()
+        .upload_texture_data(activation, texture, side, mip_level, data);
+    Ok(())
+}
+
+/// Uploads pixels stored in the texture's format from a `ByteArray`
+/// to a mip level of `texture`.
+pub fn upload_byte_array<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    texture: TextureObject<'gc>,
+    data: &Value<'gc>,
+    byte_array_offset: u32,
+    side: u32,
+    mip_level: u32,
+) -> Result<(), Error<'gc>> {
+    let byte_array = data.coerce_to_object(activation)?;
+    let byte_array = byte_array
+        .as_bytearray()
+        .ok_or_else(|| Error::from("ArgumentError: Parameter must be a ByteArray"))?;
+
+    if texture.format() != Context3DTextureFormat::Bgra {
+        log::warn!(
+            "uploadFromByteArray: Texture format {:?} is not yet implemented",
+            texture.format()
+        );
+        return Ok(());
+    }
+
+    let (width, height) = mip_level_size(texture, mip_level);
+    let bgra = byte_array.read_at((width * height * 4) as usize, byte_array_offset as usize)?;
+    let data = bgra
+        .chunks_exact(4)
+        .flat_map(|bgra| [bgra[2], bgra[1], bgra[0], bgra[3]])
+        .collect();
+    drop(byte_array);
+
+    texture
+        .context3d()
+        .upload_texture_data(activation, texture, side, mip_level, data);
+    Ok(())
+}
+
+fn mip_level_size(texture: TextureObject<'_>, mip_level: u32) -> (u32, u32) {
+    (
+        (texture.width() >> mip_level).max(1),
+        (texture.height() >> mip_level).max(1),
+    )
+}
@@ -0,0 +1,460 @@
+//! `flash.filters` namespace
+//!
+//! The filter classes themselves are implemented in ActionScript. This module converts
+//! between instances of those classes and the filters understood by the renderer.
+
+use crate::avm2::object::{ClassObject, Object, TObject};
+use crate::avm2::{Activation, ArrayObject, ArrayStorage, Error, Multiname, Namespace, Value};
+use ruffle_render::filters::{
+    BevelFilter, BevelFilterType, BlurFilter, ColorMatrixFilter, ConvolutionFilter,
+    DisplacementMapFilter, DisplacementMapFilterMode, DropShadowFilter, Filter, GlowFilter,
+    GradientFilter,
+};
+use swf::Color;
+
+fn filter_class<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    name: &'static str,
+) -> Result<ClassObject<'gc>, Error<'gc>> {
+    activation.resolve_class(&Multiname::new(Namespace::package("flash.filters"), name))
+}
+
+fn get_number<'gc>(
+    object: Object<'gc>,
+    name: &'static str,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<f64, Error<'gc>> {
+    object
+        .get_property(&Multiname::public(name), activation)?
+        .coerce_to_number(activation)
+}
+
+fn get_u32<'gc>(
+    object: Object<'gc>,
+    name: &'static str,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<u32, Error<'gc>> {
+    object
+        .get_property(&Multiname::public(name), activation)?
+        .coerce_to_u32(activation)
+}
+
+fn get_bool<'gc>(
+    object: Object<'gc>,
+    name: &'static str,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<bool, Error<'gc>> {
+    Ok(object
+        .get_property(&Multiname::public(name), activation)?
+        .coerce_to_boolean())
+}
+
+fn get_quality<'gc>(
+    object: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<u8, Error<'gc>> {
+    Ok(object
+        .get_property(&Multiname::public("quality"), activation)?
+        .coerce_to_i32(activation)?
+        .clamp(0, 15) as u8)
+}
+
+fn get_color<'gc>(
+    object: Object<'gc>,
+    color_name: &'static str,
+    alpha_name: &'static str,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Color, Error<'gc>> {
+    let rgb = get_u32(object, color_name, activation)?;
+    let alpha = get_number(object, alpha_name, activation)?;
+    Ok(Color::from_rgb(rgb, (alpha.clamp(0.0, 1.0) * 255.0) as u8))
+}
+
+fn get_bevel_type<'gc>(
+    object: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<BevelFilterType, Error<'gc>> {
+    let bevel_type = object
+        .get_property(&Multiname::public("type"), activation)?
+        .coerce_to_string(activation)?;
+    Ok(if &bevel_type == b"outer" {
+        BevelFilterType::Outer
+    } else if &bevel_type == b"full" {
+        BevelFilterType::Full
+    } else {
+        BevelFilterType::Inner
+    })
+}
+
+/// Reads every element of an array property, or an empty list if it isn't an array.
+fn get_array<'gc>(
+    object: Object<'gc>,
+    name: &'static str,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Vec<Value<'gc>>, Error<'gc>> {
+    let value = object.get_property(&Multiname::public(name), activation)?;
+    let array = value.as_object().and_then(|array| {
+        array.as_array_storage().map(|storage| {
+            storage
+                .iter()
+                .map(|v| v.unwrap_or(Value::Undefined))
+                .collect()
+        })
+    });
+    Ok(array.unwrap_or_default())
+}
+
+fn get_gradient<'gc>(
+    object: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<GradientFilter, Error<'gc>> {
+    let colors = get_array(object, "colors", activation)?;
+    let alphas = get_array(object, "alphas", activation)?;
+    let ratios = get_array(object, "ratios", activation)?;
+    let mut records = Vec::with_capacity(colors.len());
+    for ((color, alpha), ratio) in colors.iter().zip(alphas.iter()).zip(ratios.iter()).take(16) {
+        let color = color.coerce_to_u32(activation)?;
+        let alpha = alpha.coerce_to_number(activation)?;
+        let ratio = ratio.coerce_to_i32(activation)?;
+        records.push((
+            ratio.clamp(0, 255) as u8,
+            Color::from_rgb(color, (alpha.clamp(0.0, 1.0) * 255.0) as u8),
+        ));
+    }
+    Ok(GradientFilter {
+        colors: records,
+        blur_x: get_number(object, "blurX", activation)? as f32,
+        blur_y: get_number(object, "blurY", activation)? as f32,
+        angle: get_number(object, "angle", activation)?.to_radians() as f32,
+        distance: get_number(object, "distance", activation)? as f32,
+        strength: get_number(object, "strength", activation)? as f32,
+        quality: get_quality(object, activation)?,
+        bevel_type: get_bevel_type(object, activation)?,
+        knockout: get_bool(object, "knockout", activation)?,
+    })
+}
+
+/// Converts an instance of a `flash.filters` class into a filter.
+///
+/// Returns `None` if the object is not a filter that we know how to render.
+pub fn object_to_filter<'gc>(
+    object: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Option<Filter>, Error<'gc>> {
+    let class = filter_class(activation, "BlurFilter")?;
+    if object.is_of_type(class, activation) {
+        return Ok(Some(Filter::BlurFilter(BlurFilter {
+            blur_x: get_number(object, "blurX", activation)? as f32,
+            blur_y: get_number(object, "blurY", activation)? as f32,
+            quality: get_quality(object, activation)?,
+        })));
+    }
+
+    let class = filter_class(activation, "GlowFilter")?;
+    if object.is_of_type(class, activation) {
+        return Ok(Some(Filter::GlowFilter(GlowFilter {
+            color: get_color(object, "color", "alpha", activation)?,
+            blur_x: get_number(object, "blurX", activation)? as f32,
+            blur_y: get_number(object, "blurY", activation)? as f32,
+            strength: get_number(object, "strength", activation)? as f32,
+            quality: get_quality(object, activation)?,
+            inner: get_bool(object, "inner", activation)?,
+            knockout: get_bool(object, "knockout", activation)?,
+        })));
+    }
+
+    let class = filter_class(activation, "DropShadowFilter")?;
+    if object.is_of_type(class, activation) {
+        return Ok(Some(Filter::DropShadowFilter(DropShadowFilter {
+            color: get_color(object, "color", "alpha", activation)?,
+            blur_x: get_number(object, "blurX", activation)? as f32,
+            blur_y: get_number(object, "blurY", activation)? as f32,
+            angle: get_number(object, "angle", activation)?.to_radians() as f32,
+            distance: get_number(object, "distance", activation)? as f32,
+            strength: get_number(object, "strength", activation)? as f32,
+            quality: get_quality(object, activation)?,
+            inner: get_bool(object, "inner", activation)?,
+            knockout: get_bool(object, "knockout", activation)?,
+            hide_object: get_bool(object, "hideObject", activation)?,
+        })));
+    }
+
+    let class = filter_class(activation, "BevelFilter")?;
+    if object.is_of_type(class, activation) {
+        return Ok(Some(Filter::BevelFilter(BevelFilter {
+            shadow_color: get_color(object, "shadowColor", "shadowAlpha", activation)?,
+            highlight_color: get_color(object, "highlightColor", "highlightAlpha", activation)?,
+            blur_x: get_number(object, "blurX", activation)? as f32,
+            blur_y: get_number(object, "blurY", activation)? as f32,
+            angle: get_number(object, "angle", activation)?.to_radians() as f32,
+            distance: get_number(object, "distance", activation)? as f32,
+            strength: get_number(object, "strength", activation)? as f32,
+            quality: get_quality(object, activation)?,
+            bevel_type: get_bevel_type(object, activation)?,
+            knockout: get_bool(object, "knockout", activation)?,
+        })));
+    }
+
+    let class = filter_class(activation, "GradientGlowFilter")?;
+    if object.is_of_type(class, activation) {
+        return Ok(Some(Filter::GradientGlowFilter(get_gradient(
+            object, activation,
+        )?)));
+    }
+
+    let class = filter_class(activation, "GradientBevelFilter")?;
+    if object.is_of_type(class, activation) {
+        return Ok(Some(Filter::GradientBevelFilter(get_gradient(
+            object, activation,
+        )?)));
+    }
+
+    let class = filter_class(activation, "ColorMatrixFilter")?;
+    if object.is_of_type(class, activation) {
+        let values = get_array(object, "matrix", activation)?;
+        let mut matrix = [0.0; 20];
+        for (dst, src) in matrix.iter_mut().zip(values.iter()) {
+            *dst = src.coerce_to_number(activation)? as f32;
+        }
+        return Ok(Some(Filter::ColorMatrixFilter(ColorMatrixFilter {
+            matrix,
+        })));
+    }
+
+    let class = filter_class(activation, "ConvolutionFilter")?;
+    if object.is_of_type(class, activation) {
+        let values = get_array(object, "matrix", activation)?;
+        let mut matrix = Vec::with_capacity(values.len());
+        for value in values {
+            matrix.push(value.coerce_to_number(activation)? as f32);
+        }
+        return Ok(Some(Filter::ConvolutionFilter(ConvolutionFilter {
+            matrix_x: get_number(object, "matrixX", activation)?.clamp(0.0, 15.0) as u8,
+            matrix_y: get_number(object, "matrixY", activation)?.clamp(0.0, 15.0) as u8,
+            matrix,
+            divisor: get_number(object, "divisor", activation)? as f32,
+            bias: get_number(object, "bias", activation)? as f32,
+            preserve_alpha: get_bool(object, "preserveAlpha", activation)?,
+            clamp: get_bool(object, "clamp", activation)?,
+            color: get_color(object, "color", "alpha", activation)?,
+        })));
+    }
+
+    let class = filter_class(activation, "DisplacementMapFilter")?;
+    if object.is_of_type(class, activation) {
+        let map_bitmap = object
+            .get_property(&Multiname::public("mapBitmap"), activation)?
+            .as_object()
+            .and_then(|map| map.as_bitmap_data())
+            .and_then(|map| {
+                map.write(activation.context.gc_context)
+                    .bitmap_handle(activation.context.renderer)
+            });
+        let map_point = match object
+            .get_property(&Multiname::public("mapPoint"), activation)?
+            .as_object()
+        {
+            Some(point) => (
+                get_number(point, "x", activation)? as i32,
+                get_number(point, "y", activation)? as i32,
+            ),
+            None => (0, 0),
+        };
+        let mode = object
+            .get_property(&Multiname::public("mode"), activation)?
+            .coerce_to_string(activation)?;
+        let mode = if &mode == b"clamp" {
+            DisplacementMapFilterMode::Clamp
+        } else if &mode == b"ignore" {
+            DisplacementMapFilterMode::Ignore
+        } else if &mode == b"color" {
+            DisplacementMapFilterMode::Color
+        } else {
+            DisplacementMapFilterMode::Wrap
+        };
+        return Ok(Some(Filter::DisplacementMapFilter(DisplacementMapFilter {
+            map_bitmap,
+            map_point,
+            component_x: get_u32(object, "componentX", activation)? as u8,
+            component_y: get_u32(object, "componentY", activation)? as u8,
+            scale_x: get_number(object, "scaleX", activation)? as f32,
+            scale_y: get_number(object, "scaleY", activation)? as f32,
+            mode,
+            color: get_color(object, "color", "alpha", activation)?,
+        })));
+    }
+
+    Ok(None)
+}
+
+fn bevel_type_to_value<'gc>(bevel_type: BevelFilterType) -> Value<'gc> {
+    match bevel_type {
+        BevelFilterType::Inner => "inner".into(),
+        BevelFilterType::Outer => "outer".into(),
+        BevelFilterType::Full => "full".into(),
+    }
+}
+
+fn gradient_to_args<'gc>(
+    filter: &GradientFilter,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Vec<Value<'gc>>, Error<'gc>> {
+    let colors: Vec<Value<'gc>> = filter
+        .colors
+        .iter()
+        .map(|(_, color)| color.to_rgb().into())
+        .collect();
+    let alphas: Vec<Value<'gc>> = filter
+        .colors
+        .iter()
+        .map(|(_, color)| (f64::from(color.a) / 255.0).into())
+        .collect();
+    let ratios: Vec<Value<'gc>> = filter
+        .colors
+        .iter()
+        .map(|(ratio, _)| (*ratio).into())
+        .collect();
+    Ok(vec![
+        filter.distance.into(),
+        f64::from(filter.angle).to_degrees().into(),
+        ArrayObject::from_storage(activation, ArrayStorage::from_args(&colors))?.into(),
+        ArrayObject::from_storage(activation, ArrayStorage::from_args(&alphas))?.into(),
+        ArrayObject::from_storage(activation, ArrayStorage::from_args(&ratios))?.into(),
+        filter.blur_x.into(),
+        filter.blur_y.into(),
+        filter.strength.into(),
+        filter.quality.into(),
+        bevel_type_to_value(filter.bevel_type),
+        filter.knockout.into(),
+    ])
+}
+
+/// Creates a new instance of the `flash.filters` class corresponding to a filter.
+pub fn filter_to_object<'gc>(
+    filter: &Filter,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let alpha = |color: &Color| f64::from(color.a) / 255.0;
+    let (class_name, args): (&'static str, Vec<Value<'gc>>) = match filter {
+        Filter::BlurFilter(filter) => (
+            "BlurFilter",
+            vec![
+                filter.blur_x.into(),
+                filter.blur_y.into(),
+                filter.quality.into(),
+            ],
+        ),
+        Filter::GlowFilter(filter) => (
+            "GlowFilter",
+            vec![
+                filter.color.to_rgb().into(),
+                alpha(&filter.color).into(),
+                filter.blur_x.into(),
+                filter.blur_y.into(),
+                filter.strength.into(),
+                filter.quality.into(),
+                filter.inner.into(),
+                filter.knockout.into(),
+            ],
+        ),
+        Filter::DropShadowFilter(filter) => (
+            "DropShadowFilter",
+            vec![
+                filter.distance.into(),
+                f64::from(filter.angle).to_degrees().into(),
+                filter.color.to_rgb().into(),
+                alpha(&filter.color).into(),
+                filter.blur_x.into(),
+                filter.blur_y.into(),
+                filter.strength.into(),
+                filter.quality.into(),
+                filter.inner.into(),
+                filter.knockout.into(),
+                filter.hide_object.into(),
+            ],
+        ),
+        Filter::BevelFilter(filter) => (
+            "BevelFilter",
+            vec![
+                filter.distance.into(),
+                f64::from(filter.angle).to_degrees().into(),
+                filter.highlight_color.to_rgb().into(),
+                alpha(&filter.highlight_color).into(),
+                filter.shadow_color.to_rgb().into(),
+                alpha(&filter.shadow_color).into(),
+                filter.blur_x.into(),
+                filter.blur_y.into(),
+                filter.strength.into(),
+                filter.quality.into(),
+                bevel_type_to_value(filter.bevel_type),
+                filter.knockout.into(),
+            ],
+        ),
+        Filter::GradientGlowFilter(filter) => {
+            ("GradientGlowFilter", gradient_to_args(filter, activation)?)
+        }
+        Filter::GradientBevelFilter(filter) => {
+            ("GradientBevelFilter", gradient_to_args(filter, activation)?)
+        }
+        Filter::ColorMatrixFilter(filter) => {
+            let matrix: Vec<Value<'gc>> = filter.matrix.iter().map(|n| (*n).into()).collect();
+            (
+                "ColorMatrixFilter",
+                vec![
+                    ArrayObject::from_storage(activation, ArrayStorage::from_args(&matrix))?.into(),
+                ],
+            )
+        }
+        Filter::ConvolutionFilter(filter) => {
+            let matrix: Vec<Value<'gc>> = filter.matrix.iter().map(|n| (*n).into()).collect();
+            (
+                "ConvolutionFilter",
+                vec![
+                    filter.matrix_x.into(),
+                    filter.matrix_y.into(),
+                    ArrayObject::from_storage(activation, ArrayStorage::from_args(&matrix))?.into(),
+                    filter.divisor.into(),
+                    filter.bias.into(),
+                    filter.preserve_alpha.into(),
+                    filter.clamp.into(),
+                    filter.color.to_rgb().into(),
+                    alpha(&filter.color).into(),
+                ],
+            )
+        }
+        Filter::DisplacementMapFilter(filter) => {
+            let map_point = activation
+                .avm2()
+                .classes()
+                .point
+                .construct(
+                    activation,
+                    &[filter.map_point.0.into(), filter.map_point.1.into()],
+                )?
+                .into();
+            let mode = match filter.mode {
+                DisplacementMapFilterMode::Wrap => "wrap",
+                DisplacementMapFilterMode::Clamp => "clamp",
+                DisplacementMapFilterMode::Ignore => "ignore",
+                DisplacementMapFilterMode::Color => "color",
+            };
+            (
+                "DisplacementMapFilter",
+                vec![
+                    // TODO: We only keep the texture of the map, not its `BitmapData`.
+                    Value::Null,
+                    map_point,
+                    filter.component_x.into(),
+                    filter.component_y.into(),
+                    filter.scale_x.into(),
+                    filter.scale_y.into(),
+                    mode.into(),
+                    filter.color.to_rgb().into(),
+                    alpha(&filter.color).into(),
+                ],
+            )
+        }
+    };
+    let class = filter_class(activation, class_name)?;
+    Ok(class.construct(activation, &args)?.into())
+}
@@ -1,11 +1,10 @@
 package flash.net {
 
     import flash.net.URLRequest;
-    import __ruffle__.log_warn;
 
     public native function navigateToURL(request:URLRequest, window:String = null):void;
 
-    public function registerClassAlias(a:String, b:Object):void {
-        log_warn("flash.net.registerClassAlias - is not implemented");
-    }
-}
\ No newline at end of file
+    public native function registerClassAlias(aliasName:String, classObject:Object):void;
+
+    public native function getClassByAlias(aliasName:String):Object;
+}
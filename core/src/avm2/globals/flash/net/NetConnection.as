@@ -0,0 +1,57 @@
+package flash.net {
+	import flash.events.EventDispatcher;
+	import flash.events.NetStatusEvent;
+
+	public class NetConnection extends EventDispatcher {
+		private var _connected:Boolean = false;
+		private var _uri:String = null;
+		private var _client:Object = this;
+
+		public function NetConnection() {
+		}
+
+		public function get connected():Boolean {
+			return this._connected;
+		}
+
+		public function get uri():String {
+			return this._uri;
+		}
+
+		public function get client():Object {
+			return this._client;
+		}
+
+		public function set client(value:Object):void {
+			if (value == null) {
+				throw new TypeError("Error #2004: One of the parameters is invalid.", 2004);
+			}
+			this._client = value;
+		}
+
+		public function connect(command:String, ... args):void {
+			this.close();
+
+			// Only progressive downloads are supported, which don't connect to a server.
+			if (command == null || command == "null") {
+				this._connected = true;
+				this._uri = "null";
+				this.dispatchStatus("NetConnection.Connect.Success", "status");
+			} else {
+				this._uri = command;
+				this.dispatchStatus("NetConnection.Connect.Failed", "error");
+			}
+		}
+
+		public function close():void {
+			if (this._connected) {
+				this._connected = false;
+				this.dispatchStatus("NetConnection.Connect.Closed", "status");
+			}
+		}
+
+		private function dispatchStatus(code:String, level:String):void {
+			this.dispatchEvent(new NetStatusEvent(NetStatusEvent.NET_STATUS, false, false, {"code": code, "level": level}));
+		}
+	}
+}
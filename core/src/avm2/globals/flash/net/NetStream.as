@@ -0,0 +1,56 @@
+package flash.net {
+	import flash.events.EventDispatcher;
+
+	public class NetStream extends EventDispatcher {
+		private var _client:Object = this;
+
+		public function NetStream(connection:NetConnection, peerID:String = "connectToFMS") {
+			if (!connection.connected) {
+				throw new ArgumentError("Error #2126: NetConnection object must be connected.", 2126);
+			}
+		}
+
+		public function get client():Object {
+			return this._client;
+		}
+
+		public function set client(value:Object):void {
+			if (value == null) {
+				throw new TypeError("Error #2004: One of the parameters is invalid.", 2004);
+			}
+			this._client = value;
+		}
+
+		public native function get time():Number;
+
+		public native function get bytesLoaded():uint;
+
+		public native function get bytesTotal():uint;
+
+		public native function get bufferLength():Number;
+
+		public native function get bufferTime():Number;
+
+		public native function set bufferTime(value:Number):void;
+
+		public function play(... args):void {
+			if (args.length == 0 || args[0] == null) {
+				this.close();
+				return;
+			}
+			this.playFile(String(args[0]));
+		}
+
+		private native function playFile(url:String):void;
+
+		public native function pause():void;
+
+		public native function resume():void;
+
+		public native function togglePause():void;
+
+		public native function seek(offset:Number):void;
+
+		public native function close():void;
+	}
+}
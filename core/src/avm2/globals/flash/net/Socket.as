@@ -0,0 +1,196 @@
+package flash.net {
+	import flash.errors.IOError;
+	import flash.events.EventDispatcher;
+	import flash.utils.ByteArray;
+	import flash.utils.IDataInput;
+	import flash.utils.IDataOutput;
+
+	// The `ruffle` namespace is declared in `SharedObject.as`.
+
+	public class Socket extends EventDispatcher implements IDataInput, IDataOutput {
+		// Data received from the server which has not been read yet.
+		// This is filled in by Ruffle as data arrives.
+		ruffle var _input:ByteArray = new ByteArray();
+
+		// Data written by the movie which has not been flushed yet.
+		ruffle var _output:ByteArray = new ByteArray();
+
+		private var _timeout:uint = 20000;
+
+		public function Socket(host:String = null, port:int = 0) {
+			if (host != null) {
+				this.connect(host, port);
+			}
+		}
+
+		public function get bytesAvailable():uint {
+			return ruffle::_input.bytesAvailable;
+		}
+
+		public function get bytesPending():uint {
+			return ruffle::_output.length;
+		}
+
+		public native function get connected():Boolean;
+
+		public function get endian():String {
+			return ruffle::_output.endian;
+		}
+
+		public function set endian(value:String):void {
+			ruffle::_input.endian = value;
+			ruffle::_output.endian = value;
+		}
+
+		public function get objectEncoding():uint {
+			return ruffle::_output.objectEncoding;
+		}
+
+		public function set objectEncoding(value:uint):void {
+			ruffle::_input.objectEncoding = value;
+			ruffle::_output.objectEncoding = value;
+		}
+
+		public function get timeout():uint {
+			return this._timeout;
+		}
+
+		public function set timeout(value:uint):void {
+			this._timeout = value;
+		}
+
+		public function connect(host:String, port:int):void {
+			ruffle::_input.clear();
+			ruffle::_output.clear();
+			this.connectSocket(host, port);
+		}
+
+		private native function connectSocket(host:String, port:int):void;
+
+		public native function close():void;
+
+		public native function flush():void;
+
+		private function checkConnected():void {
+			if (!this.connected) {
+				throw new IOError("Error #2002: Operation attempted on invalid socket.", 2002);
+			}
+		}
+
+		public function readBoolean():Boolean {
+			return ruffle::_input.readBoolean();
+		}
+
+		public function readByte():int {
+			return ruffle::_input.readByte();
+		}
+
+		public function readBytes(bytes:ByteArray, offset:uint = 0, length:uint = 0):void {
+			ruffle::_input.readBytes(bytes, offset, length);
+		}
+
+		public function readDouble():Number {
+			return ruffle::_input.readDouble();
+		}
+
+		public function readFloat():Number {
+			return ruffle::_input.readFloat();
+		}
+
+		public function readInt():int {
+			return ruffle::_input.readInt();
+		}
+
+		public function readMultiByte(length:uint, charSet:String):String {
+			return ruffle::_input.readMultiByte(length, charSet);
+		}
+
+		public function readObject():* {
+			return ruffle::_input.readObject();
+		}
+
+		public function readShort():int {
+			return ruffle::_input.readShort();
+		}
+
+		public function readUnsignedByte():uint {
+			return ruffle::_input.readUnsignedByte();
+		}
+
+		public function readUnsignedInt():uint {
+			return ruffle::_input.readUnsignedInt();
+		}
+
+		public function readUnsignedShort():uint {
+			return ruffle::_input.readUnsignedShort();
+		}
+
+		public function readUTF():String {
+			return ruffle::_input.readUTF();
+		}
+
+		public function readUTFBytes(length:uint):String {
+			return ruffle::_input.readUTFBytes(length);
+		}
+
+		public function writeBoolean(value:Boolean):void {
+			this.checkConnected();
+			ruffle::_output.writeBoolean(value);
+		}
+
+		public function writeByte(value:int):void {
+			this.checkConnected();
+			ruffle::_output.writeByte(value);
+		}
+
+		public function writeBytes(bytes:ByteArray, offset:uint = 0, length:uint = 0):void {
+			this.checkConnected();
+			ruffle::_output.writeBytes(bytes, offset, length);
+		}
+
+		public function writeDouble(value:Number):void {
+			this.checkConnected();
+			ruffle::_output.writeDouble(value);
+		}
+
+		public function writeFloat(value:Number):void {
+			this.checkConnected();
+			ruffle::_output.writeFloat(value);
+		}
+
+		public function writeInt(value:int):void {
+			this.checkConnected();
+			ruffle::_output.writeInt(value);
+		}
+
+		public function writeMultiByte(value:String, charSet:String):void {
+			this.checkConnected();
+			ruffle::_output.writeMultiByte(value, charSet);
+		}
+
+		public function writeObject(object:*):void {
+			this.checkConnected();
+			ruffle::_output.writeObject(object);
+		}
+
+		public function writeShort(value:int):void {
+			this.checkConnected();
+			ruffle::_output.writeShort(value);
+		}
+
+		public function writeUnsignedInt(value:uint):void {
+			this.checkConnected();
+			ruffle::_output.writeUnsignedInt(value);
+		}
+
+		public function writeUTF(value:String):void {
+			this.checkConnected();
+			ruffle::_output.writeUTF(value);
+		}
+
+		public function writeUTFBytes(value:String):void {
+			this.checkConnected();
+			ruffle::_output.writeUTFBytes(value);
+		}
+	}
+}
@@ -0,0 +1,29 @@
+package flash.net {
+	import flash.events.EventDispatcher;
+
+	public class XMLSocket extends EventDispatcher {
+		private var _timeout:int = 20000;
+
+		public function XMLSocket(host:String = null, port:int = 0) {
+			if (host != null) {
+				this.connect(host, port);
+			}
+		}
+
+		public native function get connected():Boolean;
+
+		public function get timeout():int {
+			return this._timeout;
+		}
+
+		public function set timeout(value:int):void {
+			this._timeout = value;
+		}
+
+		public native function connect(host:String, port:int):void;
+
+		public native function close():void;
+
+		public native function send(object:*):void;
+	}
+}
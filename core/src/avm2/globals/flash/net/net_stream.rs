@@ -0,0 +1,180 @@
+//! `flash.net.NetStream` native function definitions
+
+use crate::avm2::value::Value;
+use crate::avm2::{Activation, Error, Object};
+use crate::streams::NetStream;
+
+fn stream<'gc>(activation: &mut Activation<'_, 'gc, '_>, this: Object<'gc>) -> NetStream<'gc> {
+    activation
+        .context
+        .stream_manager
+        .stream_for(activation.context.gc_context, this.into())
+}
+
+/// Implements `NetStream.time`
+pub fn get_time<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        return Ok(stream(activation, this).time().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `NetStream.bytesLoaded`
+pub fn get_bytes_loaded<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        return Ok((stream(activation, this).bytes_loaded() as u32).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `NetStream.bytesTotal`
+pub fn get_bytes_total<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        return Ok((stream(activation, this).bytes_total() as u32).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `NetStream.bufferLength`
+pub fn get_buffer_length<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        return Ok(stream(activation, this).buffer_length().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `NetStream.bufferTime`'s getter
+pub fn get_buffer_time<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        return Ok(stream(activation, this).buffer_time().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `NetStream.bufferTime`'s setter
+pub fn set_buffer_time<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        let buffer_time = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_number(activation)?;
+        stream(activation, this).set_buffer_time(activation.context.gc_context, buffer_time);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements the loading part of `NetStream.play`
+pub fn play_file<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        let url = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation)?;
+        stream(activation, this).play(&mut activation.context, &url.to_utf8_lossy());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `NetStream.pause`
+pub fn pause<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        stream(activation, this).pause(&mut activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `NetStream.resume`
+pub fn resume<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        stream(activation, this).resume(&mut activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `NetStream.togglePause`
+pub fn toggle_pause<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        stream(activation, this).toggle_pause(&mut activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `NetStream.seek`
+pub fn seek<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        let offset = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_number(activation)?;
+        stream(activation, this).seek(&mut activation.context, offset);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `NetStream.close`
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        stream(activation, this).close(&mut activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
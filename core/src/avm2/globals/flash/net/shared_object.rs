@@ -6,7 +6,6 @@ use crate::avm2::{Activation, Error, Namespace, Object, Value};
 use crate::display_object::DisplayObject;
 use crate::display_object::TDisplayObject;
 use crate::string::AvmString;
-use flash_lso::types::{AMFVersion, Lso};
 use std::borrow::Cow;
 
 pub fn get_local<'gc>(
@@ -152,8 +151,8 @@ pub fn get_local<'gc>(
 
     // Load the data object from storage if it existed prior
     if let Some(saved) = activation.context.storage.get(&full_name) {
-        if let Ok(lso) = flash_lso::read::Reader::default().parse(&saved) {
-            data = crate::avm2::amf::deserialize_lso(activation, &lso)?.into();
+        if let Ok(Some(lso)) = crate::avm2::amf::deserialize_lso(activation, &saved) {
+            data = lso.into();
         }
     }
 
@@ -192,19 +191,8 @@ pub fn flush<'gc>(
             .coerce_to_string(activation)?;
         let name = name.to_utf8_lossy();
 
-        let mut elements = Vec::new();
-        crate::avm2::amf::recursive_serialize(activation, data, &mut elements, AMFVersion::AMF3)?;
-        let mut lso = Lso::new(
-            elements,
-            &name
-                .split('/')
-                .last()
-                .map(|e| e.to_string())
-                .unwrap_or_else(|| "<unknown>".to_string()),
-            AMFVersion::AMF3,
-        );
-
-        let bytes = flash_lso::write::write_to_bytes(&mut lso).unwrap_or_default();
+        let lso_name = name.split('/').last().unwrap_or("<unknown>");
+        let bytes = crate::avm2::amf::serialize_lso(activation, lso_name, data)?;
 
         return Ok(activation.context.storage.put(&name, &bytes).into());
     }
@@ -0,0 +1,141 @@
+//! `flash.net.Socket` native function definitions
+
+use crate::avm2::error::{io_error, security_error};
+use crate::avm2::object::TObject;
+use crate::avm2::value::Value;
+use crate::avm2::{Activation, Error, Multiname, Namespace, Object};
+use crate::socket::{SocketTarget, Sockets};
+use std::time::Duration;
+
+/// Read the host and port arguments of `Socket.connect` and
+/// `XMLSocket.connect`.
+pub fn connect_args<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<(String, u16), Error<'gc>> {
+    let host = match args.get(0).unwrap_or(&Value::Null) {
+        Value::Undefined | Value::Null => Sockets::default_host(&activation.context),
+        host => host.coerce_to_string(activation)?.to_string(),
+    };
+
+    let port = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(activation)?;
+    let port = match u16::try_from(port) {
+        Ok(port) if port != 0 => port,
+        _ => {
+            return Err(Error::AvmError(security_error(
+                activation,
+                "Error #2003: Invalid socket port number specified.",
+                2003,
+            )?))
+        }
+    };
+
+    Ok((host, port))
+}
+
+/// Read the `timeout` property of a `Socket` or `XMLSocket`.
+pub fn timeout<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<Duration, Error<'gc>> {
+    let timeout = this
+        .get_property(&Multiname::public("timeout"), activation)?
+        .coerce_to_u32(activation)?;
+    Ok(Duration::from_millis(timeout.into()))
+}
+
+pub fn invalid_socket_error<'gc>(activation: &mut Activation<'_, 'gc, '_>) -> Error<'gc> {
+    match io_error(
+        activation,
+        "Error #2002: Operation attempted on invalid socket.",
+        2002,
+    ) {
+        Ok(error) => Error::AvmError(error),
+        Err(error) => error,
+    }
+}
+
+/// Implements `Socket.connected`
+pub fn get_connected<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        let target = SocketTarget::Avm2Socket(this);
+        return Ok(activation.context.sockets.is_connected(target).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements the connection part of `Socket.connect`
+pub fn connect_socket<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        let (host, port) = connect_args(activation, args)?;
+        let timeout = timeout(activation, this)?;
+        Sockets::connect(
+            &mut activation.context,
+            SocketTarget::Avm2Socket(this),
+            host,
+            port,
+            timeout,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Socket.close`
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        if !activation
+            .context
+            .sockets
+            .close(SocketTarget::Avm2Socket(this))
+        {
+            return Err(invalid_socket_error(activation));
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Socket.flush`
+pub fn flush<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        let target = SocketTarget::Avm2Socket(this);
+        if !activation.context.sockets.is_connected(target) {
+            return Err(invalid_socket_error(activation));
+        }
+
+        let output_name = Multiname::new(Namespace::Namespace("__ruffle__".into()), "_output");
+        let output = this.get_property(&output_name, activation)?;
+        if let Some(output) = output.as_object() {
+            if let Some(mut bytes) = output.as_bytearray_mut(activation.context.gc_context) {
+                let data = bytes.bytes().to_vec();
+                bytes.clear();
+                if !data.is_empty() {
+                    activation.context.sockets.send(target, data);
+                }
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
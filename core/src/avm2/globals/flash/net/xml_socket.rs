@@ -0,0 +1,82 @@
+//! `flash.net.XMLSocket` native function definitions
+
+use crate::avm2::globals::flash::net::socket::{connect_args, invalid_socket_error, timeout};
+use crate::avm2::value::Value;
+use crate::avm2::{Activation, Error, Object};
+use crate::socket::{SocketTarget, Sockets};
+
+/// Implements `XMLSocket.connected`
+pub fn get_connected<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        let target = SocketTarget::Avm2XmlSocket(this);
+        return Ok(activation.context.sockets.is_connected(target).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XMLSocket.connect`
+pub fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        let (host, port) = connect_args(activation, args)?;
+        let timeout = timeout(activation, this)?;
+        Sockets::connect(
+            &mut activation.context,
+            SocketTarget::Avm2XmlSocket(this),
+            host,
+            port,
+            timeout,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XMLSocket.close`
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        activation
+            .context
+            .sockets
+            .close(SocketTarget::Avm2XmlSocket(this));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XMLSocket.send`
+pub fn send<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        let message = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation)?;
+
+        // Messages are terminated by a null byte.
+        let mut data = message.to_utf8_lossy().into_owned().into_bytes();
+        data.push(0);
+
+        let target = SocketTarget::Avm2XmlSocket(this);
+        if !activation.context.sockets.send(target, data) {
+            return Err(invalid_socket_error(activation));
+        }
+    }
+
+    Ok(Value::Undefined)
+}
@@ -2,5 +2,6 @@
 #![allow(clippy::module_inception)]
 
 pub mod application_domain;
+pub mod capabilities;
 pub mod security;
 pub mod system;
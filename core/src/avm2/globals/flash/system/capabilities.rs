@@ -0,0 +1,252 @@
+//! `flash.system.Capabilities` native methods
+
+use crate::avm2::activation::Activation;
+use crate::avm2::object::{ArrayObject, Object};
+use crate::avm2::value::Value;
+use crate::avm2::{ArrayStorage, Error};
+use crate::string::AvmString;
+use crate::system_properties::SystemCapabilities;
+
+macro_rules! capabilities_func {
+    ($func_name: ident, $capability: expr) => {
+        pub fn $func_name<'gc>(
+            activation: &mut Activation<'_, 'gc, '_>,
+            _this: Option<Object<'gc>>,
+            _args: &[Value<'gc>],
+        ) -> Result<Value<'gc>, Error<'gc>> {
+            Ok(activation.context.system.has_capability($capability).into())
+        }
+    };
+}
+
+macro_rules! inverse_capabilities_func {
+    ($func_name: ident, $capability: expr) => {
+        pub fn $func_name<'gc>(
+            activation: &mut Activation<'_, 'gc, '_>,
+            _this: Option<Object<'gc>>,
+            _args: &[Value<'gc>],
+        ) -> Result<Value<'gc>, Error<'gc>> {
+            Ok((!activation.context.system.has_capability($capability)).into())
+        }
+    };
+}
+
+capabilities_func!(get_has_accessibility, SystemCapabilities::ACCESSIBILITY);
+capabilities_func!(get_has_audio, SystemCapabilities::AUDIO);
+capabilities_func!(get_has_audio_encoder, SystemCapabilities::AUDIO_ENCODER);
+capabilities_func!(get_has_embedded_video, SystemCapabilities::EMBEDDED_VIDEO);
+capabilities_func!(get_has_ime, SystemCapabilities::IME);
+capabilities_func!(get_has_mp3, SystemCapabilities::MP3);
+capabilities_func!(get_has_printing, SystemCapabilities::PRINTING);
+capabilities_func!(
+    get_has_screen_broadcast,
+    SystemCapabilities::SCREEN_BROADCAST
+);
+capabilities_func!(get_has_screen_playback, SystemCapabilities::SCREEN_PLAYBACK);
+capabilities_func!(get_has_streaming_audio, SystemCapabilities::STREAMING_AUDIO);
+capabilities_func!(get_has_streaming_video, SystemCapabilities::STREAMING_VIDEO);
+capabilities_func!(get_has_tls, SystemCapabilities::TLS);
+capabilities_func!(get_has_video_encoder, SystemCapabilities::VIDEO_ENCODER);
+capabilities_func!(get_is_debugger, SystemCapabilities::DEBUGGER);
+capabilities_func!(
+    get_is_embedded_in_acrobat,
+    SystemCapabilities::ACROBAT_EMBEDDED
+);
+capabilities_func!(
+    get_supports32bit_processes,
+    SystemCapabilities::PROCESS_32_BIT
+);
+capabilities_func!(
+    get_supports64bit_processes,
+    SystemCapabilities::PROCESS_64_BIT
+);
+inverse_capabilities_func!(get_av_hardware_disable, SystemCapabilities::AV_HARDWARE);
+inverse_capabilities_func!(
+    get_local_file_read_disable,
+    SystemCapabilities::LOCAL_FILE_READ
+);
+
+/// Implements `Capabilities.cpuAddressSize`
+pub fn get_cpu_address_size<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let is_64_bit = activation
+        .context
+        .system
+        .has_capability(SystemCapabilities::PROCESS_64_BIT);
+    Ok(if is_64_bit { 64 } else { 32 }.into())
+}
+
+/// Implements `Capabilities.cpuArchitecture`
+pub fn get_cpu_architecture<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(AvmString::new_utf8(
+        activation.context.gc_context,
+        activation.context.system.cpu_architecture.to_string(),
+    )
+    .into())
+}
+
+/// Implements `Capabilities.language`
+pub fn get_language<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(AvmString::new_utf8(
+        activation.context.gc_context,
+        activation
+            .context
+            .system
+            .language
+            .get_language_code(activation.context.player_version),
+    )
+    .into())
+}
+
+/// Implements `Capabilities.languages`
+pub fn get_languages<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let language = get_language(activation, this, args)?;
+    let storage = ArrayStorage::from_args(&[language]);
+    Ok(ArrayObject::from_storage(activation, storage)?.into())
+}
+
+/// Implements `Capabilities.manufacturer`
+pub fn get_manufacturer<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(AvmString::new_utf8(
+        activation.context.gc_context,
+        activation
+            .context
+            .system
+            .manufacturer
+            .get_manufacturer_string(activation.context.player_version),
+    )
+    .into())
+}
+
+/// Implements `Capabilities.maxLevelIDC`
+pub fn get_max_level_idc<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(AvmString::new_utf8(
+        activation.context.gc_context,
+        &activation.context.system.idc_level,
+    )
+    .into())
+}
+
+/// Implements `Capabilities.os`
+pub fn get_os<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(AvmString::new_utf8(
+        activation.context.gc_context,
+        activation.context.system.os.to_string(),
+    )
+    .into())
+}
+
+/// Implements `Capabilities.pixelAspectRatio`
+pub fn get_pixel_aspect_ratio<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(activation.context.system.aspect_ratio.into())
+}
+
+/// Implements `Capabilities.playerType`
+pub fn get_player_type<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(AvmString::new_utf8(
+        activation.context.gc_context,
+        activation.context.system.player_type.to_string(),
+    )
+    .into())
+}
+
+/// Implements `Capabilities.screenColor`
+pub fn get_screen_color<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(AvmString::new_utf8(
+        activation.context.gc_context,
+        activation.context.system.screen_color.to_string(),
+    )
+    .into())
+}
+
+/// Implements `Capabilities.screenDPI`
+pub fn get_screen_dpi<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(activation.context.system.dpi.into())
+}
+
+/// Implements `Capabilities.screenResolutionX`
+pub fn get_screen_resolution_x<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(activation.context.system.screen_resolution.0.into())
+}
+
+/// Implements `Capabilities.screenResolutionY`
+pub fn get_screen_resolution_y<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(activation.context.system.screen_resolution.1.into())
+}
+
+/// Implements `Capabilities.serverString`
+pub fn get_server_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let server_string = activation
+        .context
+        .system
+        .get_server_string(activation.context.player_version);
+    Ok(AvmString::new_utf8(activation.context.gc_context, server_string).into())
+}
+
+/// Implements `Capabilities.version`
+pub fn get_version<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let version = activation
+        .context
+        .system
+        .get_version_string(activation.context.player_version);
+    Ok(AvmString::new_utf8(activation.context.gc_context, version).into())
+}
@@ -0,0 +1,66 @@
+package flash.text {
+	import flash.events.EventDispatcher;
+
+	public dynamic class StyleSheet extends EventDispatcher {
+		use namespace AS3;
+
+		private var _styles:Object = {};
+
+		public function StyleSheet() {
+		}
+
+		public function get styleNames():Array {
+			var names:Array = [];
+			for (var name:String in this._styles) {
+				names.push(name);
+			}
+			return names;
+		}
+
+		public function clear():void {
+			this._styles = {};
+		}
+
+		public function getStyle(styleName:String):Object {
+			return copyStyle(this._styles[styleName.toLowerCase()]);
+		}
+
+		public function setStyle(styleName:String, styleObject:Object):void {
+			if (styleObject == null) {
+				delete this._styles[styleName.toLowerCase()];
+			} else {
+				this._styles[styleName.toLowerCase()] = copyStyle(styleObject);
+			}
+		}
+
+		public function parseCSS(CSSText:String):void {
+			var rules:Array = parseRules(CSSText);
+			if (rules == null) {
+				return;
+			}
+			for each (var rule:Array in rules) {
+				var name:String = String(rule[0]).toLowerCase();
+				var style:Object = this._styles[name] || {};
+				for (var property:String in rule[1]) {
+					style[property] = rule[1][property];
+				}
+				this._styles[name] = style;
+			}
+		}
+
+		public native function transform(formatObject:Object):Object;
+
+		private native function parseRules(css:String):Array;
+
+		private static function copyStyle(style:Object):Object {
+			if (style == null) {
+				return null;
+			}
+			var copy:Object = {};
+			for (var property:String in style) {
+				copy[property] = style[property];
+			}
+			return copy;
+		}
+	}
+}
@@ -0,0 +1,96 @@
+//! `flash.text.StyleSheet` native function definitions
+
+use crate::avm2::array::ArrayStorage;
+use crate::avm2::object::{ArrayObject, Object, TObject, TextFormatObject};
+use crate::avm2::value::Value;
+use crate::avm2::{Activation, Error, Multiname};
+use crate::html::{parse_css, CssStyle, StyleSheet};
+use crate::string::{AvmString, WString};
+
+/// Build the style described by the properties of a style object.
+fn object_to_style<'gc>(activation: &mut Activation<'_, 'gc, '_>, object: Object<'gc>) -> CssStyle {
+    CssStyle::from_properties(|name| {
+        match object.get_property(&Multiname::public(name), activation) {
+            Ok(Value::Undefined | Value::Null) | Err(_) => None,
+            Ok(value) => value
+                .coerce_to_string(activation)
+                .ok()
+                .map(|value| WString::from(value.as_wstr())),
+        }
+    })
+}
+
+/// Read the styles of a `StyleSheet` object, as they are at this point.
+pub fn object_to_style_sheet<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<StyleSheet, Error<'gc>> {
+    let mut style_sheet = StyleSheet::new();
+    let names = object
+        .get_property(&Multiname::public("styleNames"), activation)?
+        .coerce_to_object(activation)?;
+    let names: Vec<_> = names
+        .as_array_storage()
+        .map(|storage| storage.iter().flatten().collect())
+        .unwrap_or_default();
+
+    for name in names {
+        let name = name.coerce_to_string(activation)?;
+        let style =
+            object.call_property(&Multiname::public("getStyle"), &[name.into()], activation)?;
+        if let Value::Object(style) = style {
+            let style = object_to_style(activation, style);
+            style_sheet.insert(&name, style);
+        }
+    }
+
+    Ok(style_sheet)
+}
+
+/// Implements `StyleSheet.parseRules`
+pub fn parse_rules<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let css = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?;
+
+    let rules = match parse_css(&css) {
+        Some(rules) => rules,
+        None => return Ok(Value::Null),
+    };
+
+    let mut storage = ArrayStorage::new(0);
+    for (selector, declarations) in rules {
+        let object_class = activation.avm2().classes().object;
+        let mut style = object_class.construct(activation, &[])?;
+        for (name, value) in declarations {
+            let name = AvmString::new(activation.context.gc_context, name);
+            let value = AvmString::new(activation.context.gc_context, value);
+            style.set_property(&Multiname::public(name), value.into(), activation)?;
+        }
+
+        let selector = AvmString::new(activation.context.gc_context, selector);
+        let rule = ArrayStorage::from_args(&[selector.into(), style.into()]);
+        storage.push(ArrayObject::from_storage(activation, rule)?.into());
+    }
+
+    Ok(ArrayObject::from_storage(activation, storage)?.into())
+}
+
+/// Implements `StyleSheet.transform`
+pub fn transform<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let format = match args.get(0).unwrap_or(&Value::Undefined) {
+        Value::Object(object) => object_to_style(activation, *object).format,
+        _ => return Ok(Value::Null),
+    };
+
+    Ok(TextFormatObject::from_text_format(activation, format)?.into())
+}
@@ -2,6 +2,8 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::error::{error, range_error};
+use crate::avm2::globals::flash::text::style_sheet::object_to_style_sheet;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::object::{Object, TObject, TextFormatObject};
 use crate::avm2::value::Value;
@@ -9,13 +11,16 @@ use crate::avm2::Error;
 use crate::avm2::Multiname;
 use crate::avm2::Namespace;
 use crate::avm2::QName;
-use crate::display_object::{AutoSizeMode, EditText, TDisplayObject, TextSelection};
+use crate::display_object::{
+    AutoSizeMode, EditText, EditTextStyleSheet, TDisplayObject, TextSelection,
+};
 use crate::html::TextFormat;
 use crate::string::AvmString;
 use crate::tag_utils::SwfMovie;
+use crate::vminterface::AvmObject;
 use gc_arena::{GcCell, MutationContext};
 use std::sync::Arc;
-use swf::Color;
+use swf::{Color, Twips};
 
 /// Implements `flash.text.TextField`'s instance constructor.
 pub fn instance_init<'gc>(
@@ -375,12 +380,67 @@ pub fn set_html_text<'gc>(
             .coerce_to_string(activation)?;
 
         this.set_is_html(&mut activation.context, true);
+
+        // Changes made to the style sheet since it was attached apply to new text.
+        if let Some(AvmObject::Avm2(style_sheet)) = this.style_sheet() {
+            let styles = object_to_style_sheet(activation, style_sheet)?;
+            this.set_style_sheet(
+                Some(EditTextStyleSheet {
+                    object: style_sheet.into(),
+                    styles,
+                }),
+                &mut activation.context,
+            );
+        }
+
         this.set_html_text(&html_text, &mut activation.context);
     }
 
     Ok(Value::Undefined)
 }
 
+pub fn style_sheet<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        if let Some(AvmObject::Avm2(style_sheet)) = this.style_sheet() {
+            return Ok(style_sheet.into());
+        }
+
+        return Ok(Value::Null);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_style_sheet<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let style_sheet = match args.get(0).unwrap_or(&Value::Undefined) {
+            Value::Object(object) => Some(EditTextStyleSheet {
+                object: (*object).into(),
+                styles: object_to_style_sheet(activation, *object)?,
+            }),
+            _ => None,
+        };
+
+        this.set_style_sheet(style_sheet, &mut activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
+
 pub fn length<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
@@ -396,6 +456,119 @@ pub fn length<'gc>(
     Ok(Value::Undefined)
 }
 
+pub fn condense_white<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this.condense_white().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_condense_white<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let condense_white = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_boolean();
+
+        this.set_condense_white(condense_white, &mut activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn max_chars<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this.max_chars().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_max_chars<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let max_chars = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+
+        this.set_max_chars(max_chars, &mut activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn restrict<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(match this.restrict() {
+            Some(restrict) => AvmString::new(activation.context.gc_context, restrict).into(),
+            None => Value::Null,
+        });
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_restrict<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        match args.get(0).unwrap_or(&Value::Undefined) {
+            Value::Undefined | Value::Null => {
+                this.set_restrict(None, &mut activation.context);
+            }
+            value => {
+                let restrict = value.coerce_to_string(activation)?;
+                this.set_restrict(Some(&restrict), &mut activation.context);
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
 pub fn multiline<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
@@ -595,7 +768,7 @@ pub fn get_type<'gc>(
         .and_then(|this| this.as_display_object())
         .and_then(|this| this.as_edit_text())
     {
-        match this.is_editable() {
+        match this.is_input() {
             true => return Ok("input".into()),
             false => return Ok("dynamic".into()),
         }
@@ -738,6 +911,13 @@ pub fn replace_selected_text<'gc>(
         .and_then(|this| this.as_display_object())
         .and_then(|this| this.as_edit_text())
     {
+        if this.style_sheet().is_some() {
+            return Err(Error::AvmError(error(
+                activation,
+                "Error #2009: This method cannot be used on a text field with a style sheet.",
+                2009,
+            )?));
+        }
         let value = args
             .get(0)
             .cloned()
@@ -767,6 +947,13 @@ pub fn replace_text<'gc>(
         .and_then(|this| this.as_display_object())
         .and_then(|this| this.as_edit_text())
     {
+        if this.style_sheet().is_some() {
+            return Err(Error::AvmError(error(
+                activation,
+                "Error #2009: This method cannot be used on a text field with a style sheet.",
+                2009,
+            )?));
+        }
         let begin_index = args
             .get(0)
             .cloned()
@@ -835,6 +1022,13 @@ pub fn set_text_format<'gc>(
         .and_then(|this| this.as_display_object())
         .and_then(|this| this.as_edit_text())
     {
+        if this.style_sheet().is_some() {
+            return Err(Error::AvmError(error(
+                activation,
+                "Error #2009: This method cannot be used on a text field with a style sheet.",
+                2009,
+            )?));
+        }
         let tf = args.get(0).unwrap_or(&Value::Undefined).as_object();
         if let Some(tf) = tf {
             if let Some(tf) = tf.as_text_format() {
@@ -1122,6 +1316,317 @@ pub fn get_line_metrics<'gc>(
     Ok(Value::Undefined)
 }
 
+pub fn get_image_reference<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let id = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?;
+
+        return Ok(this
+            .image_reference(&id)
+            .map(|image| image.object2())
+            .filter(|object| !matches!(object, Value::Undefined))
+            .unwrap_or(Value::Null));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Coerce the line index argument of a line method, throwing a `RangeError`
+/// if the line doesn't exist.
+fn line_index_arg<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: EditText<'gc>,
+    args: &[Value<'gc>],
+) -> Result<usize, Error<'gc>> {
+    let line = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_i32(activation)?;
+
+    if line < 0 || line as usize >= this.layout_lines() {
+        return Err(Error::AvmError(range_error(
+            activation,
+            "Error #2006: The supplied index is out of bounds.",
+            2006,
+        )?));
+    }
+
+    Ok(line as usize)
+}
+
+/// Coerce the character index argument of a character method.
+///
+/// Negative indices are returned as `None`.
+fn char_index_arg<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Option<usize>, Error<'gc>> {
+    let index = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_i32(activation)?;
+
+    Ok(usize::try_from(index).ok())
+}
+
+/// Coerce the point arguments of a point method to twips.
+fn point_args<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<(Twips, Twips), Error<'gc>> {
+    let x = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_number(activation)?;
+    let y = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_number(activation)?;
+
+    Ok((Twips::from_pixels(x), Twips::from_pixels(y)))
+}
+
+pub fn get_char_boundaries<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let bounds = char_index_arg(activation, args)?.and_then(|index| this.char_bounds(index));
+
+        if let Some(bounds) = bounds {
+            let args = &[
+                bounds.offset_x().to_pixels().into(),
+                bounds.offset_y().to_pixels().into(),
+                bounds.width().to_pixels().into(),
+                bounds.height().to_pixels().into(),
+            ];
+            return Ok(activation
+                .avm2()
+                .classes()
+                .rectangle
+                .construct(activation, args)?
+                .into());
+        }
+
+        return Ok(Value::Null);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_char_index_at_point<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let point = point_args(activation, args)?;
+        return Ok(this
+            .char_index_at_point(point)
+            .map_or(-1, |index| index as i32)
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_line_index_at_point<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let point = point_args(activation, args)?;
+        return Ok(this
+            .line_index_at_point(point)
+            .map_or(-1, |line| line as i32)
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_line_index_of_char<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(char_index_arg(activation, args)?
+            .and_then(|index| this.line_index_of_char(index))
+            .map_or(-1, |line| line as i32)
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_line_length<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let line = line_index_arg(activation, this, args)?;
+        return Ok(this.line_length(line).unwrap_or_default().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_line_offset<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let line = line_index_arg(activation, this, args)?;
+        return Ok(this.line_offset(line).unwrap_or_default().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_line_text<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        let line = line_index_arg(activation, this, args)?;
+        let text = this.line_text(line).unwrap_or_default();
+        return Ok(AvmString::new(activation.context.gc_context, text).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_first_char_in_paragraph<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(char_index_arg(activation, args)?
+            .and_then(|index| this.paragraph_start(index))
+            .map_or(-1, |index| index as i32)
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_paragraph_length<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(char_index_arg(activation, args)?
+            .and_then(|index| this.paragraph_length(index))
+            .map_or(-1, |length| length as i32)
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn selection_begin_index<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this
+            .selection()
+            .map_or(0, |selection| selection.start())
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn selection_end_index<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this
+            .selection()
+            .map_or(0, |selection| selection.end())
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn caret_index<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|this| this.as_edit_text())
+    {
+        return Ok(this
+            .selection()
+            .map_or(0, |selection| selection.to())
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
 pub fn bottom_scroll_v<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
@@ -1273,6 +1778,11 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("border", Some(border), Some(set_border)),
         ("borderColor", Some(border_color), Some(set_border_color)),
         ("bottomScrollV", Some(bottom_scroll_v), None),
+        (
+            "condenseWhite",
+            Some(condense_white),
+            Some(set_condense_white),
+        ),
         (
             "defaultTextFormat",
             Some(default_text_format),
@@ -1286,12 +1796,15 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("embedFonts", Some(embed_fonts), Some(set_embed_fonts)),
         ("htmlText", Some(html_text), Some(set_html_text)),
         ("length", Some(length), None),
+        ("maxChars", Some(max_chars), Some(set_max_chars)),
         ("maxScrollH", Some(max_scroll_h), None),
         ("maxScrollV", Some(max_scroll_v), None),
         ("multiline", Some(multiline), Some(set_multiline)),
+        ("restrict", Some(restrict), Some(set_restrict)),
         ("scrollH", Some(scroll_h), Some(set_scroll_h)),
         ("scrollV", Some(scroll_v), Some(set_scroll_v)),
         ("selectable", Some(selectable), Some(set_selectable)),
+        ("styleSheet", Some(style_sheet), Some(set_style_sheet)),
         ("text", Some(text), Some(set_text)),
         ("textColor", Some(text_color), Some(set_text_color)),
         ("textHeight", Some(text_height), None),
@@ -1307,6 +1820,9 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("thickness", Some(thickness), Some(set_thickness)),
         ("sharpness", Some(sharpness), Some(set_sharpness)),
         ("numLines", Some(num_lines), None),
+        ("selectionBeginIndex", Some(selection_begin_index), None),
+        ("selectionEndIndex", Some(selection_end_index), None),
+        ("caretIndex", Some(caret_index), None),
     ];
     write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
 
@@ -1318,6 +1834,16 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         ("setSelection", set_selection),
         ("setTextFormat", set_text_format),
         ("getLineMetrics", get_line_metrics),
+        ("getImageReference", get_image_reference),
+        ("getCharBoundaries", get_char_boundaries),
+        ("getCharIndexAtPoint", get_char_index_at_point),
+        ("getLineIndexAtPoint", get_line_index_at_point),
+        ("getLineIndexOfChar", get_line_index_of_char),
+        ("getLineLength", get_line_length),
+        ("getLineOffset", get_line_offset),
+        ("getLineText", get_line_text),
+        ("getFirstCharInParagraph", get_first_char_in_paragraph),
+        ("getParagraphLength", get_paragraph_length),
     ];
     write.define_public_builtin_instance_methods(mc, PUBLIC_INSTANCE_METHODS);
 
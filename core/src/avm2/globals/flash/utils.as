@@ -1,4 +1,5 @@
 package flash.utils {
+	public native function describeType(value:*):XML;
 	public native function getDefinitionByName(name:String):Object;
 	public native function getQualifiedClassName(value:*):String;
 	public native function getQualifiedSuperclassName(value:*):String;
@@ -1,6 +1,7 @@
 //! `flash.utils` namespace
 
-use crate::avm2::object::TObject;
+use crate::avm2::globals::avmplus::{self, DescribeTypeFlags};
+use crate::avm2::object::{TObject, XmlObject};
 use crate::avm2::QName;
 use crate::avm2::{Activation, Error, Object, Value};
 use crate::string::AvmString;
@@ -263,3 +264,15 @@ pub fn get_definition_by_name<'gc>(
     let qname = QName::from_qualified_name(name, activation.context.gc_context);
     appdomain.get_defined_value(activation, qname)
 }
+
+/// Implements `flash.utils.describeType`
+pub fn describe_type<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+    let node = avmplus::describe_type_xml(activation, value, DescribeTypeFlags::FLASH10_FLAGS)?;
+
+    Ok(XmlObject::from_node(activation, node)?.into())
+}
@@ -16,10 +16,13 @@ include "SyntaxError.as"
 include "URIError.as"
 include "VerifyError.as"
 
+include "avmplus.as"
+
 include "flash/accessibility/AccessibilityProperties.as"
 include "flash/crypto.as"
 include "flash/utils/IDataInput.as"
 include "flash/utils/IDataOutput.as"
+include "flash/utils/IExternalizable.as"
 include "flash/utils/ByteArray.as"
 include "flash/utils/Dictionary.as"
 include "flash/desktop/ClipboardFormats.as"
@@ -34,8 +37,20 @@ include "flash/display/ColorCorrectionSupport.as"
 include "flash/display/FocusDirection.as"
 include "flash/display/FrameLabel.as"
 include "flash/display/GradientType.as"
+include "flash/display/IGraphicsData.as"
+include "flash/display/IGraphicsFill.as"
+include "flash/display/IGraphicsPath.as"
+include "flash/display/IGraphicsStroke.as"
+include "flash/display/GraphicsBitmapFill.as"
+include "flash/display/GraphicsEndFill.as"
+include "flash/display/GraphicsGradientFill.as"
+include "flash/display/GraphicsPath.as"
 include "flash/display/GraphicsPathCommand.as"
 include "flash/display/GraphicsPathWinding.as"
+include "flash/display/GraphicsShaderFill.as"
+include "flash/display/GraphicsSolidFill.as"
+include "flash/display/GraphicsStroke.as"
+include "flash/display/GraphicsTrianglePath.as"
 include "flash/display/InterpolationMethod.as"
 include "flash/display/JointStyle.as"
 include "flash/display/JPEGEncoderOptions.as"
@@ -75,6 +90,10 @@ include "flash/display3D/Context3DWrapMode.as"
 include "flash/display3D/IndexBuffer3D.as"
 include "flash/display3D/Program3D.as"
 include "flash/display3D/VertexBuffer3D.as"
+include "flash/display3D/textures/TextureBase.as"
+include "flash/display3D/textures/CubeTexture.as"
+include "flash/display3D/textures/RectangleTexture.as"
+include "flash/display3D/textures/Texture.as"
 
 // Event needs to come before its subclasses
 include "flash/events/Event.as"
@@ -133,12 +152,16 @@ include "flash/errors/StackOverflowError.as"
 include "flash/filters/BitmapFilter.as"
 include "flash/filters/BitmapFilterQuality.as"
 include "flash/filters/BitmapFilterType.as"
+include "flash/filters/BevelFilter.as"
 include "flash/filters/BlurFilter.as"
 include "flash/filters/ColorMatrixFilter.as"
+include "flash/filters/ConvolutionFilter.as"
 include "flash/filters/DisplacementMapFilter.as"
 include "flash/filters/DisplacementMapFilterMode.as"
 include "flash/filters/DropShadowFilter.as"
 include "flash/filters/GlowFilter.as"
+include "flash/filters/GradientBevelFilter.as"
+include "flash/filters/GradientGlowFilter.as"
 
 include "flash/geom/ColorTransform.as"
 include "flash/geom/Matrix.as"
@@ -175,19 +198,23 @@ include "flash/net/FileReferenceList.as"
 include "flash/net/IDynamicPropertyOutput.as"
 include "flash/net/IDynamicPropertyWriter.as"
 include "flash/net/LocalConnection.as"
+include "flash/net/NetConnection.as"
 include "flash/net/NetGroupReceiveMode.as"
 include "flash/net/NetGroupReplicationStrategy.as"
 include "flash/net/NetGroupSendMode.as"
 include "flash/net/NetGroupSendResult.as"
+include "flash/net/NetStream.as"
 include "flash/net/ObjectEncoding.as"
 include "flash/net/SharedObject.as"
 include "flash/net/SharedObjectFlushStatus.as"
+include "flash/net/Socket.as"
 include "flash/net/URLLoader.as"
 include "flash/net/URLLoaderDataFormat.as"
 include "flash/net/URLRequest.as"
 include "flash/net/URLRequestHeader.as"
 include "flash/net/URLRequestMethod.as"
 include "flash/net/URLVariables.as"
+include "flash/net/XMLSocket.as"
 
 include "flash/printing/PrintJobOrientation.as"
 include "flash/security/CertificateStatus.as"
@@ -207,6 +234,7 @@ include "flash/text/FontStyle.as"
 include "flash/text/FontType.as"
 include "flash/text/GridFitType.as"
 include "flash/text/StaticText.as"
+include "flash/text/StyleSheet.as"
 include "flash/text/TextColorType.as"
 include "flash/text/TextDisplayMode.as"
 include "flash/text/TextExtent.as"
@@ -235,5 +263,3 @@ include "flash/utils/Proxy.as"
 include "flash/utils/CompressionAlgorithm.as"
 include "flash/utils/Endian.as"
 include "flash/utils/Timer.as"
-include "XML.as"
-include "XMLList.as"
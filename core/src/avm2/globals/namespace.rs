@@ -2,30 +2,124 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::Class;
-use crate::avm2::method::Method;
-use crate::avm2::object::{namespace_allocator, Object};
+use crate::avm2::method::{Method, NativeMethodImpl};
+use crate::avm2::object::{namespace_allocator, Object, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 use crate::avm2::Multiname;
 use crate::avm2::Namespace;
 use crate::avm2::QName;
+use crate::string::AvmString;
 use gc_arena::{GcCell, MutationContext};
 
+/// Get the URI of a value that is used as a namespace.
+fn value_to_uri<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<AvmString<'gc>, Error<'gc>> {
+    match value {
+        Value::Object(o) if o.as_namespace().is_some() => Ok(o.as_namespace().unwrap().as_uri()),
+        Value::Object(o) if o.as_qname_object().is_some() => {
+            let qname = o.as_qname_object().unwrap();
+            let uri = qname.qname().map(|qname| qname.namespace().as_uri());
+            Ok(uri.unwrap_or_else(|| "".into()))
+        }
+        value => value.coerce_to_string(activation),
+    }
+}
+
 /// Implements `Namespace`'s instance initializer.
+///
+/// This accepts either a URI, or a prefix and a URI.
 pub fn instance_init<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this.and_then(|this| this.as_namespace_object()) {
+        let (uri, prefix) = match args {
+            [] => ("".into(), Some("".into())),
+            [uri] => {
+                let prefix = match uri {
+                    Value::Object(o) if o.as_namespace_object().is_some() => {
+                        o.as_namespace_object().unwrap().prefix()
+                    }
+                    _ => None,
+                };
+                let uri = value_to_uri(activation, *uri)?;
+                let prefix = if uri.is_empty() {
+                    Some("".into())
+                } else {
+                    prefix
+                };
+                (uri, prefix)
+            }
+            [prefix, uri, ..] => {
+                let uri = value_to_uri(activation, *uri)?;
+                let prefix = match prefix {
+                    Value::Undefined => None,
+                    prefix => Some(prefix.coerce_to_string(activation)?),
+                };
+                (uri, prefix)
+            }
+        };
+
+        this.init_namespace(
+            activation.context.gc_context,
+            Namespace::Namespace(uri),
+            prefix,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Namespace(value)`, which returns namespaces unchanged.
+fn class_call<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
     _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let [value] = args {
+        if value
+            .as_object()
+            .and_then(|o| o.as_namespace_object())
+            .is_some()
+        {
+            return Ok(*value);
+        }
+    }
+
+    let this_class = activation.avm2().classes().namespace;
+    this_class.construct(activation, args).map(|o| o.into())
+}
+
+/// Implements `Namespace.prefix`'s getter
+pub fn prefix<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Err("Namespace constructor is a stub.".into())
+    if let Some(this) = this.and_then(|this| this.as_namespace_object()) {
+        return Ok(this.prefix().map_or(Value::Undefined, Value::from));
+    }
+
+    Ok(Value::Undefined)
 }
 
-fn class_call<'gc>(
+/// Implements `Namespace.uri`'s getter
+pub fn uri<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
-    _this: Option<Object<'gc>>,
+    this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Err("Namespace constructor is a stub.".into())
+    if let Some(this) = this {
+        if let Some(namespace) = this.as_namespace() {
+            return Ok(namespace.as_uri().into());
+        }
+    }
+
+    Ok(Value::Undefined)
 }
 
 /// Implements `Namespace`'s native instance initializer.
@@ -73,5 +167,12 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         mc,
     ));
 
+    const PUBLIC_INSTANCE_PROPERTIES: &[(
+        &str,
+        Option<NativeMethodImpl>,
+        Option<NativeMethodImpl>,
+    )] = &[("prefix", Some(prefix), None), ("uri", Some(uri), None)];
+    write.define_public_builtin_instance_properties(mc, PUBLIC_INSTANCE_PROPERTIES);
+
     class
 }
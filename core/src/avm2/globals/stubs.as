@@ -22,4 +22,6 @@ include "String.as"
 include "int.as"
 include "uint.as"
 include "Vector.as"
+include "XML.as"
+include "XMLList.as"
 include "__ruffle__/logging.as"
\ No newline at end of file
@@ -1,3 +1,985 @@
 //! XML builtin and prototype
 
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::e4x::{self, E4XNamespace, E4XNode, E4XNodeKind};
+use crate::avm2::error::type_error;
+use crate::avm2::method::{Method, NativeMethodImpl};
 pub use crate::avm2::object::xml_allocator;
+use crate::avm2::object::{
+    ClassObject, FunctionObject, NamespaceObject, Object, QNameObject, TObject, XmlListObject,
+    XmlObject,
+};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::avm2::Multiname;
+use crate::avm2::Namespace;
+use crate::avm2::QName;
+use gc_arena::{GcCell, MutationContext};
+
+/// The names and default values of the static settings of the `XML` class.
+const SETTINGS: &[(&str, bool)] = &[
+    ("ignoreComments", true),
+    ("ignoreProcessingInstructions", true),
+    ("ignoreWhitespace", true),
+    ("prettyPrinting", true),
+];
+const DEFAULT_PRETTY_INDENT: i32 = 2;
+
+/// Convert a value into a single XML node, as done by `new XML(value)`.
+fn value_to_node<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<E4XNode<'gc>, Error<'gc>> {
+    let mc = activation.context.gc_context;
+    let text = match value {
+        Value::Undefined | Value::Null => return Ok(E4XNode::text(mc, "".into())),
+        Value::Object(object) => {
+            if let Some(xml) = object.as_xml() {
+                return Ok(xml.node().deep_copy(mc));
+            }
+            if let Some(list) = object.as_xml_list() {
+                if let [child] = list.children().as_slice() {
+                    return Ok(child.deep_copy(mc));
+                }
+                return Err(Error::AvmError(type_error(
+                    activation,
+                    "Error #1088: The markup in the document following the root element must be well-formed.",
+                    1088,
+                )?));
+            }
+            value.coerce_to_string(activation)?
+        }
+        value => value.coerce_to_string(activation)?,
+    };
+
+    let settings = e4x::xml_settings(activation)?;
+    let mut nodes = E4XNode::parse(activation, &text, &settings)?;
+    match nodes.len() {
+        0 => Ok(E4XNode::text(mc, "".into())),
+        1 => Ok(nodes.remove(0)),
+        _ => Err(Error::AvmError(type_error(
+            activation,
+            "Error #1088: The markup in the document following the root element must be well-formed.",
+            1088,
+        )?)),
+    }
+}
+
+/// Implements `XML`'s instance initializer.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+
+        if let Some(xml) = this.as_xml() {
+            let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+            let node = value_to_node(activation, value)?;
+            xml.set_node(activation.context.gc_context, node);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML`'s native instance initializer.
+pub fn native_instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        activation.super_init(this, args)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML(value)`, which returns XML values unchanged.
+fn class_call<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+    if value.as_object().and_then(|o| o.as_xml()).is_some() {
+        return Ok(value);
+    }
+
+    let node = value_to_node(activation, value)?;
+    Ok(XmlObject::from_node(activation, node)?.into())
+}
+
+/// Implements `XML`'s class initializer.
+pub fn class_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.unwrap();
+    let this_class = this.as_class_object().unwrap();
+    install_prototype_methods(activation, this_class, INSTANCE_METHODS)?;
+
+    Ok(Value::Undefined)
+}
+
+/// Install the given methods on the prototype of an E4X class, so that they
+/// can also be called from code that doesn't use the `AS3` namespace.
+pub fn install_prototype_methods<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this_class: ClassObject<'gc>,
+    methods: &[(&'static str, NativeMethodImpl)],
+) -> Result<(), Error<'gc>> {
+    let scope = activation.create_scopechain();
+    let proto = this_class.prototype();
+
+    for (name, method) in methods {
+        let function = FunctionObject::from_method(
+            activation,
+            Method::from_builtin(*method, name, activation.context.gc_context),
+            scope,
+            None,
+            Some(this_class),
+        );
+        proto.set_property_local(&Multiname::public(*name), function.into(), activation)?;
+        proto.set_local_property_is_enumerable(
+            activation.context.gc_context,
+            (*name).into(),
+            false,
+        );
+    }
+
+    Ok(())
+}
+
+/// Get the XML object that a method was called on.
+///
+/// Most `XML` methods can also be called on an `XMLList` that contains
+/// exactly one item.
+fn xml_this<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    method: &str,
+) -> Result<Option<E4XNode<'gc>>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(None),
+    };
+
+    if let Some(xml) = this.as_xml() {
+        return Ok(Some(xml.node()));
+    }
+    if let Some(list) = this.as_xml_list() {
+        if let [child] = list.children().as_slice() {
+            return Ok(Some(*child));
+        }
+        return Err(Error::AvmError(type_error(
+            activation,
+            &format!(
+                "Error #1086: The {} method only works on lists containing one item.",
+                method
+            ),
+            1086,
+        )?));
+    }
+
+    Ok(None)
+}
+
+/// Returns the name held by a `QName` object.
+fn qname_of<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<QName<'gc>, Error<'gc>> {
+    match object
+        .as_qname_object()
+        .and_then(|qname| qname.qname().map(|qname| *qname))
+    {
+        Some(qname) => Ok(qname),
+        None => Err(Error::AvmError(type_error(
+            activation,
+            "Error #1009: Cannot access a property or method of a null object reference.",
+            1009,
+        )?)),
+    }
+}
+
+/// Convert the argument of a method such as `XML.child` into a multiname
+/// that can be used to match nodes.
+pub fn name_to_multiname<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    name: Value<'gc>,
+    is_attribute: bool,
+) -> Result<Multiname<'gc>, Error<'gc>> {
+    let mut multiname = match name {
+        Value::Object(object) if object.as_qname_object().is_some() => {
+            let qname = qname_of(activation, object)?;
+            Multiname::new(qname.namespace(), qname.local_name())
+        }
+        Value::Undefined => Multiname::new(Namespace::Any, "*"),
+        name => {
+            let local_name = name.coerce_to_string(activation)?;
+            let multiname = Multiname::public(local_name);
+            e4x::resolve_name(&multiname, activation)
+        }
+    };
+    multiname.set_is_attribute(is_attribute);
+    Ok(multiname)
+}
+
+/// Convert a value that is added as a child of an element into nodes.
+///
+/// Strings which contain markup are parsed, while other values are added as text.
+pub fn value_to_child_nodes<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<Vec<E4XNode<'gc>>, Error<'gc>> {
+    let mc = activation.context.gc_context;
+    if let Some(nodes) = e4x::value_nodes(value) {
+        // Nodes that are already part of another tree are copied, rather than moved.
+        return Ok(nodes
+            .into_iter()
+            .map(|node| {
+                if node.parent().is_some() {
+                    node.deep_copy(mc)
+                } else {
+                    node
+                }
+            })
+            .collect());
+    }
+
+    let text = value.coerce_to_string(activation)?;
+    if text.trim_start().starts_with(b'<') {
+        let settings = e4x::xml_settings(activation)?;
+        return E4XNode::parse(activation, &text, &settings);
+    }
+    Ok(vec![E4XNode::text(mc, text)])
+}
+
+fn xml_list<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    nodes: Vec<E4XNode<'gc>>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(XmlListObject::from_nodes(activation, nodes)?.into())
+}
+
+/// Implements `XML.name`
+pub fn name<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(node) = xml_this(activation, this, "name")? {
+        if let Some(local_name) = node.local_name() {
+            let uri = node
+                .namespace()
+                .map(|ns| ns.uri)
+                .unwrap_or_else(|| "".into());
+            let qname = QName::new(Namespace::Namespace(uri), local_name);
+            return Ok(QNameObject::from_qname(activation, qname)?.into());
+        }
+        return Ok(Value::Null);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.localName`
+pub fn local_name<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(node) = xml_this(activation, this, "localName")? {
+        return Ok(node.local_name().map_or(Value::Null, Value::from));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.namespace`
+pub fn namespace<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(node) = xml_this(activation, this, "namespace")? {
+        let namespace = match args.get(0) {
+            Some(prefix) => {
+                let prefix = prefix.coerce_to_string(activation)?;
+                let prefix = if prefix.is_empty() {
+                    None
+                } else {
+                    Some(prefix)
+                };
+                match node.resolve_prefix(prefix.as_deref()) {
+                    Some(ns) => ns.uri,
+                    None => return Ok(Value::Undefined),
+                }
+            }
+            None => node
+                .namespace()
+                .map(|ns| ns.uri)
+                .unwrap_or_else(|| "".into()),
+        };
+        return Ok(
+            NamespaceObject::from_namespace(activation, Namespace::Namespace(namespace))?.into(),
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.nodeKind`
+pub fn node_kind<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(node) = xml_this(activation, this, "nodeKind")? {
+        return Ok(node.node_kind().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.attributes`
+pub fn attributes<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let nodes = this_nodes(this)
+        .into_iter()
+        .flat_map(|node| node.attributes())
+        .collect();
+    xml_list(activation, nodes)
+}
+
+/// Implements `XML.attribute`
+pub fn attribute<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = args.get(0).cloned().unwrap_or(Value::Undefined);
+    let name = name_to_multiname(activation, name, true)?;
+    let nodes = this_nodes(this)
+        .into_iter()
+        .flat_map(|node| node.matching_children(&name))
+        .collect();
+    xml_list(activation, nodes)
+}
+
+/// Implements `XML.child`
+pub fn child<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = args.get(0).cloned().unwrap_or(Value::Undefined);
+    let nodes = if let Value::Integer(_) | Value::Number(_) = name {
+        let index = name.coerce_to_number(activation)?;
+        this_nodes(this)
+            .into_iter()
+            .filter_map(|node| {
+                if index >= 0.0 {
+                    node.children().get(index as usize).copied()
+                } else {
+                    None
+                }
+            })
+            .collect()
+    } else {
+        let name = name_to_multiname(activation, name, false)?;
+        this_nodes(this)
+            .into_iter()
+            .flat_map(|node| node.matching_children(&name))
+            .collect()
+    };
+    xml_list(activation, nodes)
+}
+
+/// Implements `XML.children`
+pub fn children<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let nodes = this_nodes(this)
+        .into_iter()
+        .flat_map(|node| node.children())
+        .collect();
+    xml_list(activation, nodes)
+}
+
+/// Implements `XML.elements`
+pub fn elements<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = args.get(0).cloned().unwrap_or(Value::Undefined);
+    let name = name_to_multiname(activation, name, false)?;
+    let nodes = this_nodes(this)
+        .into_iter()
+        .flat_map(|node| node.children())
+        .filter(|child| child.is_element() && child.matches_name(&name))
+        .collect();
+    xml_list(activation, nodes)
+}
+
+/// Implements `XML.descendants`
+pub fn descendants<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = args.get(0).cloned().unwrap_or(Value::Undefined);
+    let name = name_to_multiname(activation, name, false)?;
+    let mut nodes = Vec::new();
+    for node in this_nodes(this) {
+        node.descendants(&name, &mut nodes);
+    }
+    xml_list(activation, nodes)
+}
+
+/// Implements `XML.text`
+pub fn text<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let nodes = this_nodes(this)
+        .into_iter()
+        .flat_map(|node| node.children())
+        .filter(|child| matches!(&*child.kind(), E4XNodeKind::Text(_) | E4XNodeKind::CData(_)))
+        .collect();
+    xml_list(activation, nodes)
+}
+
+/// Implements `XML.comments`
+pub fn comments<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let nodes = this_nodes(this)
+        .into_iter()
+        .flat_map(|node| node.children())
+        .filter(|child| matches!(&*child.kind(), E4XNodeKind::Comment(_)))
+        .collect();
+    xml_list(activation, nodes)
+}
+
+/// Implements `XML.processingInstructions`
+pub fn processing_instructions<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = match args.get(0).cloned().unwrap_or(Value::Undefined) {
+        Value::Undefined => None,
+        name => Some(name.coerce_to_string(activation)?).filter(|name| name != b"*"),
+    };
+    let nodes = this_nodes(this)
+        .into_iter()
+        .flat_map(|node| node.children())
+        .filter(|child| {
+            matches!(&*child.kind(), E4XNodeKind::ProcessingInstruction(_))
+                && (name.is_none() || child.local_name() == name)
+        })
+        .collect();
+    xml_list(activation, nodes)
+}
+
+/// Implements `XML.parent`
+pub fn parent<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // An `XMLList` only has a parent if all of its items share the same parent.
+    let mut parent: Option<E4XNode<'gc>> = None;
+    for node in this_nodes(this) {
+        match (parent, node.parent()) {
+            (_, None) => return Ok(Value::Undefined),
+            (None, Some(node_parent)) => parent = Some(node_parent),
+            (Some(parent), Some(node_parent)) if !parent.ptr_eq(node_parent) => {
+                return Ok(Value::Undefined)
+            }
+            _ => {}
+        }
+    }
+
+    match parent {
+        Some(parent) => Ok(XmlObject::from_node(activation, parent)?.into()),
+        None => Ok(Value::Undefined),
+    }
+}
+
+/// Implements `XML.appendChild`
+pub fn append_child<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(node) = xml_this(activation, this, "appendChild")? {
+        let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+        for child in value_to_child_nodes(activation, value)? {
+            node.append_child(activation.context.gc_context, child);
+        }
+    }
+
+    Ok(this.map_or(Value::Undefined, Value::from))
+}
+
+/// Implements `XML.prependChild`
+pub fn prepend_child<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(node) = xml_this(activation, this, "prependChild")? {
+        let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+        let children = value_to_child_nodes(activation, value)?;
+        for (index, child) in children.into_iter().enumerate() {
+            node.insert_child(activation.context.gc_context, index, child);
+        }
+    }
+
+    Ok(this.map_or(Value::Undefined, Value::from))
+}
+
+/// Find the index of a child node given to `insertChildAfter` or `insertChildBefore`.
+///
+/// Returns `Some(None)` if the reference child is `null`.
+fn reference_child_index<'gc>(node: E4XNode<'gc>, child: Value<'gc>) -> Option<Option<usize>> {
+    let child = match child {
+        Value::Null | Value::Undefined => return Some(None),
+        Value::Object(object) => {
+            if let Some(xml) = object.as_xml() {
+                xml.node()
+            } else if let Some(list) = object.as_xml_list() {
+                *list.children().first()?
+            } else {
+                return None;
+            }
+        }
+        _ => return None,
+    };
+
+    node.children()
+        .iter()
+        .position(|existing| existing.ptr_eq(child))
+        .map(Some)
+}
+
+/// Implements `XML.insertChildAfter`
+pub fn insert_child_after<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(node) = xml_this(activation, this, "insertChildAfter")? {
+        let reference = args.get(0).cloned().unwrap_or(Value::Undefined);
+        let index = match reference_child_index(node, reference) {
+            Some(Some(index)) => index + 1,
+            Some(None) => 0,
+            None => return Ok(Value::Undefined),
+        };
+        let value = args.get(1).cloned().unwrap_or(Value::Undefined);
+        for (offset, child) in value_to_child_nodes(activation, value)?
+            .into_iter()
+            .enumerate()
+        {
+            node.insert_child(activation.context.gc_context, index + offset, child);
+        }
+    }
+
+    Ok(this.map_or(Value::Undefined, Value::from))
+}
+
+/// Implements `XML.insertChildBefore`
+pub fn insert_child_before<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(node) = xml_this(activation, this, "insertChildBefore")? {
+        let reference = args.get(0).cloned().unwrap_or(Value::Undefined);
+        let index = match reference_child_index(node, reference) {
+            Some(Some(index)) => index,
+            Some(None) => node.children().len(),
+            None => return Ok(Value::Undefined),
+        };
+        let value = args.get(1).cloned().unwrap_or(Value::Undefined);
+        for (offset, child) in value_to_child_nodes(activation, value)?
+            .into_iter()
+            .enumerate()
+        {
+            node.insert_child(activation.context.gc_context, index + offset, child);
+        }
+    }
+
+    Ok(this.map_or(Value::Undefined, Value::from))
+}
+
+/// Implements `XML.childIndex`
+pub fn child_index<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(node) = xml_this(activation, this, "childIndex")? {
+        if node.is_attribute() {
+            return Ok((-1).into());
+        }
+        return Ok(node
+            .child_index()
+            .map_or(Value::Integer(-1), |index| (index as i32).into()));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.setName`
+pub fn set_name<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(node) = xml_this(activation, this, "setName")? {
+        let name = args.get(0).cloned().unwrap_or(Value::Undefined);
+        let (namespace, local_name) = match name {
+            Value::Object(object) if object.as_qname_object().is_some() => {
+                let qname = qname_of(activation, object)?;
+                let namespace = match qname.namespace() {
+                    Namespace::Namespace(uri) if !uri.is_empty() => {
+                        // Keep the prefix that the namespace was declared with, if any.
+                        let prefix = node.namespace_for_uri(uri).and_then(|ns| ns.prefix);
+                        Some(E4XNamespace { prefix, uri })
+                    }
+                    _ => None,
+                };
+                (namespace, qname.local_name())
+            }
+            name => (node.namespace(), name.coerce_to_string(activation)?),
+        };
+        node.set_name(activation.context.gc_context, namespace, local_name);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.setChildren`
+pub fn set_children<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(node) = xml_this(activation, this, "setChildren")? {
+        let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+        let children = value_to_child_nodes(activation, value)?;
+        node.clear_children(activation.context.gc_context);
+        for child in children {
+            node.append_child(activation.context.gc_context, child);
+        }
+    }
+
+    Ok(this.map_or(Value::Undefined, Value::from))
+}
+
+/// Implements `XML.copy`
+pub fn copy<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mc = activation.context.gc_context;
+    if let Some(xml) = this.and_then(|this| this.as_xml()) {
+        return Ok(XmlObject::from_node(activation, xml.node().deep_copy(mc))?.into());
+    }
+
+    let nodes = this_nodes(this)
+        .into_iter()
+        .map(|node| node.deep_copy(mc))
+        .collect();
+    xml_list(activation, nodes)
+}
+
+/// Implements `XML.hasSimpleContent`
+pub fn has_simple_content<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(list) = this.and_then(|this| this.as_xml_list()) {
+        return Ok(list.has_simple_content().into());
+    }
+
+    Ok(this_nodes(this)
+        .into_iter()
+        .all(|node| node.has_simple_content())
+        .into())
+}
+
+/// Implements `XML.hasComplexContent`
+pub fn has_complex_content<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let nodes = this_nodes(this);
+    if this.and_then(|this| this.as_xml_list()).is_some() && nodes.len() != 1 {
+        return Ok(nodes.iter().any(|node| node.is_element()).into());
+    }
+
+    Ok(nodes
+        .into_iter()
+        .any(|node| node.children().iter().any(|child| child.is_element()))
+        .into())
+}
+
+/// Implements `XML.length`
+pub fn length<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok((this_nodes(this).len() as i32).into())
+}
+
+/// Implements `XML.contains`
+pub fn contains<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+    for node in this_nodes(this) {
+        let item: Value<'gc> = XmlObject::from_node(activation, node)?.into();
+        if item.abstract_eq(&value, activation)? {
+            return Ok(true.into());
+        }
+    }
+
+    Ok(false.into())
+}
+
+/// Implements `XML.toString`
+pub fn to_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let settings = e4x::xml_settings(activation)?;
+    let mc = activation.context.gc_context;
+    if let Some(xml) = this.and_then(|this| this.as_xml()) {
+        return Ok(xml.node().xml_to_string(mc, &settings).into());
+    }
+    if let Some(list) = this.and_then(|this| this.as_xml_list()) {
+        return Ok(list.list_to_string(mc, &settings).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.toXMLString`
+pub fn to_xml_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let settings = e4x::xml_settings(activation)?;
+    let mc = activation.context.gc_context;
+    if let Some(xml) = this.and_then(|this| this.as_xml()) {
+        return Ok(xml.node().xml_to_xml_string(mc, &settings).into());
+    }
+    if let Some(list) = this.and_then(|this| this.as_xml_list()) {
+        return Ok(list.list_to_xml_string(mc, &settings).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.valueOf`
+pub fn value_of<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.map_or(Value::Undefined, Value::from))
+}
+
+/// The nodes of the `XML` or `XMLList` object that a method was called on.
+pub fn this_nodes<'gc>(this: Option<Object<'gc>>) -> Vec<E4XNode<'gc>> {
+    this.and_then(|this| e4x::value_nodes(this.into()))
+        .unwrap_or_default()
+}
+
+/// Implements `XML.settings`
+pub fn settings<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut settings = activation
+        .avm2()
+        .classes()
+        .object
+        .construct(activation, &[])?;
+    if let Some(this) = this {
+        for (name, _) in SETTINGS {
+            let value = this.get_property(&Multiname::public(*name), activation)?;
+            settings.set_property(&Multiname::public(*name), value, activation)?;
+        }
+        let indent = this.get_property(&Multiname::public("prettyIndent"), activation)?;
+        settings.set_property(&Multiname::public("prettyIndent"), indent, activation)?;
+    }
+
+    Ok(settings.into())
+}
+
+/// Implements `XML.setSettings`
+pub fn set_settings<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut this) = this {
+        let settings = match args.get(0) {
+            Some(Value::Object(settings)) => Some(*settings),
+            _ => None,
+        };
+
+        for (name, default) in SETTINGS {
+            let name = Multiname::public(*name);
+            let value = match settings {
+                Some(settings) => match settings.get_property(&name, activation)? {
+                    Value::Bool(value) => value,
+                    _ => continue,
+                },
+                None => *default,
+            };
+            this.set_property(&name, value.into(), activation)?;
+        }
+
+        let name = Multiname::public("prettyIndent");
+        let indent = match settings {
+            Some(settings) => settings.get_property(&name, activation)?,
+            None => DEFAULT_PRETTY_INDENT.into(),
+        };
+        if let Value::Integer(_) | Value::Number(_) = indent {
+            this.set_property(&name, indent, activation)?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.defaultSettings`
+pub fn default_settings<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut settings = activation
+        .avm2()
+        .classes()
+        .object
+        .construct(activation, &[])?;
+    for (name, default) in SETTINGS {
+        settings.set_property(&Multiname::public(*name), (*default).into(), activation)?;
+    }
+    settings.set_property(
+        &Multiname::public("prettyIndent"),
+        DEFAULT_PRETTY_INDENT.into(),
+        activation,
+    )?;
+
+    Ok(settings.into())
+}
+
+const INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+    ("appendChild", append_child),
+    ("attribute", attribute),
+    ("attributes", attributes),
+    ("child", child),
+    ("childIndex", child_index),
+    ("children", children),
+    ("comments", comments),
+    ("contains", contains),
+    ("copy", copy),
+    ("descendants", descendants),
+    ("elements", elements),
+    ("hasComplexContent", has_complex_content),
+    ("hasSimpleContent", has_simple_content),
+    ("insertChildAfter", insert_child_after),
+    ("insertChildBefore", insert_child_before),
+    ("length", length),
+    ("localName", local_name),
+    ("name", name),
+    ("namespace", namespace),
+    ("nodeKind", node_kind),
+    ("parent", parent),
+    ("prependChild", prepend_child),
+    ("processingInstructions", processing_instructions),
+    ("setChildren", set_children),
+    ("setName", set_name),
+    ("text", text),
+    ("toString", to_string),
+    ("toXMLString", to_xml_string),
+    ("valueOf", value_of),
+];
+
+/// Construct `XML`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::public(), "XML"),
+        Some(Multiname::public("Object")),
+        Method::from_builtin(instance_init, "<XML instance initializer>", mc),
+        Method::from_builtin(class_init, "<XML class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+    write.set_attributes(ClassAttributes::FINAL);
+    write.set_instance_allocator(xml_allocator);
+    write.set_native_instance_init(Method::from_builtin(
+        native_instance_init,
+        "<XML native instance initializer>",
+        mc,
+    ));
+    write.set_call_handler(Method::from_builtin(class_call, "<XML call handler>", mc));
+
+    write.define_as3_builtin_instance_methods(mc, INSTANCE_METHODS);
+
+    for (name, default) in SETTINGS {
+        write.define_class_trait(Trait::from_slot(
+            QName::new(Namespace::public(), *name),
+            Multiname::public("Boolean"),
+            Some((*default).into()),
+        ));
+    }
+    write.define_class_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "prettyIndent"),
+        Multiname::public("int"),
+        Some(DEFAULT_PRETTY_INDENT.into()),
+    ));
+
+    const CLASS_METHODS: &[(&str, NativeMethodImpl)] = &[
+        ("settings", settings),
+        ("setSettings", set_settings),
+        ("defaultSettings", default_settings),
+    ];
+    write.define_as3_builtin_class_methods(mc, CLASS_METHODS);
+    write.define_public_builtin_class_methods(mc, CLASS_METHODS);
+
+    class
+}
@@ -1,4 +1,161 @@
 //! XMLList builtin and prototype
 
-// XMLList currently uses the same instance allocator as XML
-pub use crate::avm2::object::xml_allocator as xml_list_allocator;
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::e4x::{self, E4XNode};
+use crate::avm2::globals::xml;
+use crate::avm2::method::{Method, NativeMethodImpl};
+pub use crate::avm2::object::xml_list_allocator;
+use crate::avm2::object::{Object, TObject, XmlListObject};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::avm2::Multiname;
+use crate::avm2::Namespace;
+use crate::avm2::QName;
+use gc_arena::{GcCell, MutationContext};
+
+/// Convert a value into a list of nodes, as done by `new XMLList(value)`.
+fn value_to_nodes<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<Vec<E4XNode<'gc>>, Error<'gc>> {
+    let text = match value {
+        Value::Undefined | Value::Null => return Ok(Vec::new()),
+        Value::Object(object) => {
+            if let Some(xml) = object.as_xml() {
+                return Ok(vec![xml.node()]);
+            }
+            if let Some(list) = object.as_xml_list() {
+                return Ok(list.children());
+            }
+            value.coerce_to_string(activation)?
+        }
+        value => value.coerce_to_string(activation)?,
+    };
+
+    let settings = e4x::xml_settings(activation)?;
+    E4XNode::parse(activation, &text, &settings)
+}
+
+/// Implements `XMLList`'s instance initializer.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+
+        if let Some(list) = this.as_xml_list() {
+            let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+            let children = value_to_nodes(activation, value)?;
+            list.set_children(activation.context.gc_context, children);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XMLList`'s native instance initializer.
+pub fn native_instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        activation.super_init(this, args)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XMLList(value)`, which returns XMLList values unchanged.
+fn class_call<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+    if value.as_object().and_then(|o| o.as_xml_list()).is_some() {
+        return Ok(value);
+    }
+
+    let children = value_to_nodes(activation, value)?;
+    Ok(XmlListObject::from_nodes(activation, children)?.into())
+}
+
+/// Implements `XMLList`'s class initializer.
+pub fn class_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.unwrap();
+    let this_class = this.as_class_object().unwrap();
+    xml::install_prototype_methods(activation, this_class, INSTANCE_METHODS)?;
+
+    Ok(Value::Undefined)
+}
+
+/// The methods of `XMLList`. Most of these are shared with `XML`, and either
+/// apply to every item in the list or require the list to contain exactly one item.
+const INSTANCE_METHODS: &[(&str, NativeMethodImpl)] = &[
+    ("appendChild", xml::append_child),
+    ("attribute", xml::attribute),
+    ("attributes", xml::attributes),
+    ("child", xml::child),
+    ("childIndex", xml::child_index),
+    ("children", xml::children),
+    ("comments", xml::comments),
+    ("contains", xml::contains),
+    ("copy", xml::copy),
+    ("descendants", xml::descendants),
+    ("elements", xml::elements),
+    ("hasComplexContent", xml::has_complex_content),
+    ("hasSimpleContent", xml::has_simple_content),
+    ("insertChildAfter", xml::insert_child_after),
+    ("insertChildBefore", xml::insert_child_before),
+    ("length", xml::length),
+    ("localName", xml::local_name),
+    ("name", xml::name),
+    ("namespace", xml::namespace),
+    ("nodeKind", xml::node_kind),
+    ("parent", xml::parent),
+    ("prependChild", xml::prepend_child),
+    ("processingInstructions", xml::processing_instructions),
+    ("setChildren", xml::set_children),
+    ("setName", xml::set_name),
+    ("text", xml::text),
+    ("toString", xml::to_string),
+    ("toXMLString", xml::to_xml_string),
+    ("valueOf", xml::value_of),
+];
+
+/// Construct `XMLList`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::public(), "XMLList"),
+        Some(Multiname::public("Object")),
+        Method::from_builtin(instance_init, "<XMLList instance initializer>", mc),
+        Method::from_builtin(class_init, "<XMLList class initializer>", mc),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+    write.set_attributes(ClassAttributes::FINAL);
+    write.set_instance_allocator(xml_list_allocator);
+    write.set_native_instance_init(Method::from_builtin(
+        native_instance_init,
+        "<XMLList native instance initializer>",
+        mc,
+    ));
+    write.set_call_handler(Method::from_builtin(
+        class_call,
+        "<XMLList call handler>",
+        mc,
+    ));
+
+    write.define_as3_builtin_instance_methods(mc, INSTANCE_METHODS);
+
+    class
+}
@@ -0,0 +1,60 @@
+//! Per-op caches of resolved traits
+
+use crate::avm2::property::Property;
+use crate::avm2::vtable::VTable;
+use crate::avm2::Multiname;
+use gc_arena::{Collect, GcCell, MutationContext};
+
+/// The trait lookup caches of a single method body.
+///
+/// Each op of the verified method body gets one cache entry, which remembers
+/// the vtable of the last receiver the op resolved a trait on, and the trait
+/// it found there. Property accesses on receivers of the same class can then
+/// skip multiname resolution entirely.
+///
+/// Only hits are cached: names which resolve to dynamic or prototype
+/// properties are always looked up again, as those can change at any time.
+/// A class's traits are fixed once the class has been initialized, so a
+/// cached hit stays valid for as long as its vtable is the receiver's.
+#[derive(Clone, Collect, Copy, Debug)]
+#[collect(no_drop)]
+pub struct InlineCaches<'gc>(GcCell<'gc, Vec<Option<(VTable<'gc>, Property)>>>);
+
+impl<'gc> InlineCaches<'gc> {
+    pub fn new(mc: MutationContext<'gc, '_>) -> Self {
+        Self(GcCell::allocate(mc, Vec::new()))
+    }
+
+    /// Ensure there is a cache entry for every op in a method body with
+    /// `len` ops.
+    pub fn reserve(self, mc: MutationContext<'gc, '_>, len: usize) {
+        if self.0.read().len() < len {
+            self.0.write(mc).resize(len, None);
+        }
+    }
+
+    /// Resolve a trait in `vtable` through the cache entry at `index`.
+    ///
+    /// `multiname` must be the same for every lookup through a given entry,
+    /// which means it cannot have any runtime components.
+    pub fn get_trait(
+        self,
+        mc: MutationContext<'gc, '_>,
+        index: usize,
+        vtable: VTable<'gc>,
+        multiname: &Multiname<'gc>,
+    ) -> Option<Property> {
+        if let Some(Some((cached_vtable, property))) = self.0.read().get(index) {
+            if VTable::ptr_eq(*cached_vtable, vtable) {
+                return Some(*property);
+            }
+        }
+
+        let property = vtable.get_trait(multiname)?;
+        if let Some(entry) = self.0.write(mc).get_mut(index) {
+            *entry = Some((vtable, property));
+        }
+
+        Some(property)
+    }
+}
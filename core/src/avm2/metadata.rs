@@ -0,0 +1,89 @@
+//! Trait and class metadata
+
+use crate::avm2::script::TranslationUnit;
+use crate::avm2::Error;
+use crate::string::AvmString;
+use gc_arena::{Collect, MutationContext};
+use swf::avm2::types::{Index, Metadata as AbcMetadata};
+
+/// A single `[Name(key="value")]` metadata tag, as attached to a trait or class.
+///
+/// Flash Player keeps these around at runtime solely so that they can be
+/// reported by `describeType` and `describeTypeJSON`.
+#[derive(Clone, Debug, Collect)]
+#[collect(no_drop)]
+pub struct Metadata<'gc> {
+    /// The name of the metadata tag.
+    name: AvmString<'gc>,
+
+    /// The arguments of the metadata tag, in declaration order.
+    items: Vec<MetadataItem<'gc>>,
+}
+
+/// A single argument of a metadata tag.
+#[derive(Clone, Debug, Collect)]
+#[collect(no_drop)]
+pub struct MetadataItem<'gc> {
+    /// The key of this argument. Positional arguments have an empty key.
+    pub key: AvmString<'gc>,
+
+    /// The value of this argument.
+    pub value: AvmString<'gc>,
+}
+
+impl<'gc> Metadata<'gc> {
+    /// Load a metadata tag from the given translation unit.
+    pub fn from_abc_index(
+        unit: TranslationUnit<'gc>,
+        index: Index<AbcMetadata>,
+        mc: MutationContext<'gc, '_>,
+    ) -> Result<Self, Error<'gc>> {
+        let abc = unit.abc();
+        let abc_metadata = abc
+            .metadata
+            .get(index.0 as usize)
+            .ok_or_else(|| format!("Unknown metadata {}", index.0))?;
+
+        let name = unit.pool_string(abc_metadata.name.0, mc)?;
+        let items = abc_metadata
+            .items
+            .iter()
+            .map(|item| {
+                Ok(MetadataItem {
+                    key: unit.pool_string(item.key.0, mc)?,
+                    value: unit.pool_string(item.value.0, mc)?,
+                })
+            })
+            .collect::<Result<_, Error<'gc>>>()?;
+
+        Ok(Self { name, items })
+    }
+
+    /// Load every metadata tag in a list of indices.
+    ///
+    /// Returns `None` if there are no tags, so that traits without metadata
+    /// don't need to allocate.
+    pub fn from_abc_indices(
+        unit: TranslationUnit<'gc>,
+        indices: &[Index<AbcMetadata>],
+        mc: MutationContext<'gc, '_>,
+    ) -> Result<Option<Box<[Self]>>, Error<'gc>> {
+        if indices.is_empty() {
+            return Ok(None);
+        }
+
+        indices
+            .iter()
+            .map(|index| Self::from_abc_index(unit, *index, mc))
+            .collect::<Result<_, _>>()
+            .map(Some)
+    }
+
+    pub fn name(&self) -> AvmString<'gc> {
+        self.name
+    }
+
+    pub fn items(&self) -> &[MetadataItem<'gc>] {
+        &self.items
+    }
+}
@@ -1,13 +1,16 @@
 //! AVM2 methods
 
 use crate::avm2::activation::Activation;
+use crate::avm2::inline_cache::InlineCaches;
 use crate::avm2::object::Object;
 use crate::avm2::script::TranslationUnit;
 use crate::avm2::value::{abc_default_value, Value};
+use crate::avm2::verify::{verify_method, VerifiedMethodInfo};
 use crate::avm2::Error;
 use crate::avm2::Multiname;
 use crate::string::AvmString;
 use gc_arena::{Collect, Gc, MutationContext};
+use std::cell::RefCell;
 use std::fmt;
 use std::ops::Deref;
 use std::rc::Rc;
@@ -128,6 +131,13 @@ pub struct BytecodeMethod<'gc> {
     /// A free-standing function corresponds to the `Function` trait type, and
     /// is instantiated with the `newfunction` opcode.
     pub is_function: bool,
+
+    /// The verified and optimized method body, once this method has been run.
+    #[collect(require_static)]
+    verified_info: RefCell<Option<Rc<VerifiedMethodInfo>>>,
+
+    /// The trait lookup caches of the ops in the verified method body.
+    inline_caches: InlineCaches<'gc>,
 }
 
 impl<'gc> BytecodeMethod<'gc> {
@@ -162,6 +172,8 @@ impl<'gc> BytecodeMethod<'gc> {
                         signature,
                         return_type,
                         is_function,
+                        verified_info: RefCell::new(None),
+                        inline_caches: InlineCaches::new(activation.context.gc_context),
                     });
                 }
             }
@@ -175,6 +187,8 @@ impl<'gc> BytecodeMethod<'gc> {
             signature,
             return_type: Multiname::any(),
             is_function,
+            verified_info: RefCell::new(None),
+            inline_caches: InlineCaches::new(activation.context.gc_context),
         })
     }
 
@@ -204,6 +218,31 @@ impl<'gc> BytecodeMethod<'gc> {
         }
     }
 
+    /// Get the verified body of this method, verifying it first if this
+    /// has not been done yet.
+    ///
+    /// Verification failures throw a `VerifyError`, and are not cached.
+    pub fn verify(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Rc<VerifiedMethodInfo>, Error<'gc>> {
+        if let Some(info) = &*self.verified_info.borrow() {
+            return Ok(info.clone());
+        }
+
+        let info = Rc::new(verify_method(activation, self)?);
+        self.inline_caches
+            .reserve(activation.context.gc_context, info.parsed_code.len());
+        *self.verified_info.borrow_mut() = Some(info.clone());
+
+        Ok(info)
+    }
+
+    /// Get the trait lookup caches of this method, which are indexed by op.
+    pub fn inline_caches(&self) -> InlineCaches<'gc> {
+        self.inline_caches
+    }
+
     /// Get the list of method params for this method.
     pub fn signature(&self) -> &[ParamConfig<'gc>] {
         &self.signature
@@ -58,6 +58,8 @@ bitflags! {
         /// Whether the name needs to be read at runtime before use
         /// This should only be set when lazy-initialized in Activation.
         const HAS_LAZY_NAME = 1 << 1;
+        /// Whether this was an attribute multiname, used to access XML attributes.
+        const ATTRIBUTE = 1 << 2;
     }
 }
 
@@ -101,6 +103,15 @@ impl<'gc> Multiname<'gc> {
         self.has_lazy_ns() || self.has_lazy_name()
     }
 
+    #[inline(always)]
+    pub fn is_attribute(&self) -> bool {
+        self.flags.contains(MultinameFlags::ATTRIBUTE)
+    }
+
+    pub fn set_is_attribute(&mut self, is_attribute: bool) {
+        self.flags.set(MultinameFlags::ATTRIBUTE, is_attribute);
+    }
+
     /// Read a namespace set from the ABC constant pool, and return a list of
     /// copied namespaces.
     fn abc_namespace_set(
@@ -148,7 +159,7 @@ impl<'gc> Multiname<'gc> {
         let abc = translation_unit.abc();
         let abc_multiname = Self::resolve_multiname_index(&abc, multiname_index)?;
 
-        Ok(match abc_multiname {
+        let mut multiname = match abc_multiname {
             AbcMultiname::QName { namespace, name } | AbcMultiname::QNameA { namespace, name } => {
                 Self {
                     ns: NamespaceSet::single(Namespace::from_abc_namespace(
@@ -218,7 +229,17 @@ impl<'gc> Multiname<'gc> {
                 }
                 base
             }
-        })
+        };
+
+        multiname.set_is_attribute(matches!(
+            abc_multiname,
+            AbcMultiname::QNameA { .. }
+                | AbcMultiname::RTQNameA { .. }
+                | AbcMultiname::RTQNameLA
+                | AbcMultiname::MultinameA { .. }
+                | AbcMultiname::MultinameLA { .. }
+        ));
+        Ok(multiname)
     }
 
     #[inline(never)]
@@ -268,7 +289,7 @@ impl<'gc> Multiname<'gc> {
             ns,
             name,
             params: self.params.clone(),
-            flags: Default::default(),
+            flags: self.flags & MultinameFlags::ATTRIBUTE,
         })
     }
 
@@ -317,6 +338,16 @@ impl<'gc> Multiname<'gc> {
         }
     }
 
+    /// Create a copy of this multiname which is only satisfied by the given namespace.
+    pub fn with_namespace(&self, ns: Namespace<'gc>) -> Self {
+        Self {
+            ns: NamespaceSet::single(ns),
+            name: self.name,
+            params: self.params.clone(),
+            flags: self.flags,
+        }
+    }
+
     pub fn namespace_set(&self) -> &[Namespace<'gc>] {
         match &self.ns {
             NamespaceSet::Single(ns) => std::slice::from_ref(ns),
@@ -53,8 +53,10 @@ mod soundchannel_object;
 mod stage3d_object;
 mod stage_object;
 mod textformat_object;
+mod texture_object;
 mod vector_object;
 mod vertex_buffer_3d_object;
+mod xml_list_object;
 mod xml_object;
 
 pub use crate::avm2::object::array_object::{array_allocator, ArrayObject};
@@ -85,8 +87,10 @@ pub use crate::avm2::object::soundchannel_object::{soundchannel_allocator, Sound
 pub use crate::avm2::object::stage3d_object::{stage_3d_allocator, Stage3DObject};
 pub use crate::avm2::object::stage_object::{stage_allocator, StageObject};
 pub use crate::avm2::object::textformat_object::{textformat_allocator, TextFormatObject};
+pub use crate::avm2::object::texture_object::TextureObject;
 pub use crate::avm2::object::vector_object::{vector_allocator, VectorObject};
 pub use crate::avm2::object::vertex_buffer_3d_object::VertexBuffer3DObject;
+pub use crate::avm2::object::xml_list_object::{xml_list_allocator, XmlListObject};
 pub use crate::avm2::object::xml_object::{xml_allocator, XmlObject};
 
 /// Represents an object that can be directly interacted with by the AVM2
@@ -106,6 +110,7 @@ pub use crate::avm2::object::xml_object::{xml_allocator, XmlObject};
         EventObject(EventObject<'gc>),
         DispatchObject(DispatchObject<'gc>),
         XmlObject(XmlObject<'gc>),
+        XmlListObject(XmlListObject<'gc>),
         RegExpObject(RegExpObject<'gc>),
         ByteArrayObject(ByteArrayObject<'gc>),
         LoaderInfoObject(LoaderInfoObject<'gc>),
@@ -125,6 +130,7 @@ pub use crate::avm2::object::xml_object::{xml_allocator, XmlObject};
         IndexBuffer3DObject(IndexBuffer3DObject<'gc>),
         VertexBuffer3DObject(VertexBuffer3DObject<'gc>),
         Program3DObject(Program3DObject<'gc>),
+        TextureObject(TextureObject<'gc>),
     }
 )]
 pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy {
@@ -154,13 +160,28 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     /// This corresponds directly to the AVM2 operation `getproperty`, with the
     /// exception that it does not special-case object lookups on dictionary
     /// structured objects.
-    #[allow(unused_mut)] //Not unused.
     fn get_property(
+        self,
+        multiname: &Multiname<'gc>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let property = self.vtable().and_then(|vtable| vtable.get_trait(multiname));
+        self.get_property_with_trait(multiname, property, activation)
+    }
+
+    /// Retrieve a property, given the trait that `multiname` resolves to in
+    /// this object's vtable.
+    ///
+    /// This allows callers to cache trait lookups; `get_property` should be
+    /// preferred otherwise.
+    #[allow(unused_mut)] //Not unused.
+    fn get_property_with_trait(
         mut self,
         multiname: &Multiname<'gc>,
+        property: Option<Property>,
         activation: &mut Activation<'_, 'gc, '_>,
     ) -> Result<Value<'gc>, Error<'gc>> {
-        match self.vtable().and_then(|vtable| vtable.get_trait(multiname)) {
+        match property {
             Some(Property::Slot { slot_id }) | Some(Property::ConstSlot { slot_id }) => {
                 self.base().get_slot(slot_id)
             }
@@ -216,7 +237,23 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         value: Value<'gc>,
         activation: &mut Activation<'_, 'gc, '_>,
     ) -> Result<(), Error<'gc>> {
-        match self.vtable().and_then(|vtable| vtable.get_trait(multiname)) {
+        let property = self.vtable().and_then(|vtable| vtable.get_trait(multiname));
+        self.set_property_with_trait(multiname, property, value, activation)
+    }
+
+    /// Set a property, given the trait that `multiname` resolves to in this
+    /// object's vtable.
+    ///
+    /// This allows callers to cache trait lookups; `set_property` should be
+    /// preferred otherwise.
+    fn set_property_with_trait(
+        &mut self,
+        multiname: &Multiname<'gc>,
+        property: Option<Property>,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        match property {
             Some(Property::Slot { slot_id }) => {
                 let value = self
                     .vtable()
@@ -320,14 +357,30 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     /// This method should not be overridden.
     ///
     /// This corresponds directly to the `callproperty` operation in AVM2.
-    #[allow(unused_mut)]
     fn call_property(
+        self,
+        multiname: &Multiname<'gc>,
+        arguments: &[Value<'gc>],
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let property = self.vtable().and_then(|vtable| vtable.get_trait(multiname));
+        self.call_property_with_trait(multiname, property, arguments, activation)
+    }
+
+    /// Call a named property, given the trait that `multiname` resolves to in
+    /// this object's vtable.
+    ///
+    /// This allows callers to cache trait lookups; `call_property` should be
+    /// preferred otherwise.
+    #[allow(unused_mut)]
+    fn call_property_with_trait(
         mut self,
         multiname: &Multiname<'gc>,
+        property: Option<Property>,
         arguments: &[Value<'gc>],
         activation: &mut Activation<'_, 'gc, '_>,
     ) -> Result<Value<'gc>, Error<'gc>> {
-        match self.vtable().and_then(|vtable| vtable.get_trait(multiname)) {
+        match property {
             Some(Property::Slot { slot_id }) | Some(Property::ConstSlot { slot_id }) => {
                 let obj = self.base().get_slot(slot_id)?.as_callable(
                     activation,
@@ -929,6 +982,10 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         None
     }
 
+    fn as_namespace_object(&self) -> Option<NamespaceObject<'gc>> {
+        None
+    }
+
     /// Unwrap this object as a `QNameObject`
     fn as_qname_object(self) -> Option<QNameObject<'gc>> {
         None
@@ -1113,6 +1170,10 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         None
     }
 
+    fn as_xml_list(&self) -> Option<XmlListObject<'gc>> {
+        None
+    }
+
     fn as_context_3d(&self) -> Option<Context3DObject<'gc>> {
         None
     }
@@ -1132,6 +1193,10 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     fn as_stage_3d(&self) -> Option<Stage3DObject<'gc>> {
         None
     }
+
+    fn as_texture(&self) -> Option<TextureObject<'gc>> {
+        None
+    }
 }
 
 pub enum ObjectPtr {}
@@ -5,10 +5,13 @@ use crate::avm2::object::script_object::ScriptObjectData;
 use crate::avm2::object::{Object, ObjectPtr, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
+use crate::bitmap::bitmap_data::BitmapData;
 use crate::context::RenderContext;
 use gc_arena::{Collect, GcCell, MutationContext};
 use ruffle_render::backend::{
-    BufferUsage, Context3D, Context3DCommand, Context3DTriangleFace, Context3DVertexBufferFormat,
+    BufferUsage, Context3D, Context3DBlendFactor, Context3DCommand, Context3DCompareMode,
+    Context3DMipFilter, Context3DRectangle, Context3DStencilAction, Context3DTextureFilter,
+    Context3DTextureFormat, Context3DTriangleFace, Context3DVertexBufferFormat, Context3DWrapMode,
     ProgramType,
 };
 use ruffle_render::commands::CommandHandler;
@@ -16,7 +19,7 @@ use ruffle_render::transform::Transform;
 use std::cell::{Ref, RefMut};
 
 use super::program_3d_object::Program3DObject;
-use super::{IndexBuffer3DObject, VertexBuffer3DObject};
+use super::{IndexBuffer3DObject, TextureObject, VertexBuffer3DObject};
 
 #[derive(Clone, Collect, Copy)]
 #[collect(no_drop)]
@@ -251,6 +254,279 @@ impl<'gc> Context3DObject<'gc> {
             .push(Context3DCommand::SetCulling { face });
     }
 
+    pub fn create_texture(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        width: u32,
+        height: u32,
+        format: Context3DTextureFormat,
+        optimize_for_render_to_texture: bool,
+        streaming_levels: u32,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let handle = self
+            .0
+            .write(activation.context.gc_context)
+            .render_context
+            .as_mut()
+            .unwrap()
+            .create_texture(
+                width,
+                height,
+                format,
+                optimize_for_render_to_texture,
+                streaming_levels,
+            );
+        let class = activation.avm2().classes().texture;
+        Ok(Value::Object(TextureObject::from_handle(
+            activation, class, *self, handle, format, width, height,
+        )?))
+    }
+
+    pub fn create_rectangle_texture(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        width: u32,
+        height: u32,
+        format: Context3DTextureFormat,
+        optimize_for_render_to_texture: bool,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let handle = self
+            .0
+            .write(activation.context.gc_context)
+            .render_context
+            .as_mut()
+            .unwrap()
+            .create_rectangle_texture(width, height, format, optimize_for_render_to_texture);
+        let class = activation.avm2().classes().rectangletexture;
+        Ok(Value::Object(TextureObject::from_handle(
+            activation, class, *self, handle, format, width, height,
+        )?))
+    }
+
+    pub fn create_cube_texture(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        size: u32,
+        format: Context3DTextureFormat,
+        optimize_for_render_to_texture: bool,
+        streaming_levels: u32,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let handle = self
+            .0
+            .write(activation.context.gc_context)
+            .render_context
+            .as_mut()
+            .unwrap()
+            .create_cube_texture(
+                size,
+                format,
+                optimize_for_render_to_texture,
+                streaming_levels,
+            );
+        let class = activation.avm2().classes().cubetexture;
+        Ok(Value::Object(TextureObject::from_handle(
+            activation, class, *self, handle, format, size, size,
+        )?))
+    }
+
+    pub fn upload_texture_data(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        texture: TextureObject<'gc>,
+        side: u32,
+        mip_level: u32,
+        data: Vec<u8>,
+    ) {
+        self.0.write(activation.context.gc_context).commands.push(
+            Context3DCommand::UploadToTexture {
+                texture: texture.handle(),
+                side,
+                mip_level,
+                data,
+            },
+        );
+    }
+
+    pub fn set_texture_at(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        sampler: u32,
+        texture: Option<TextureObject<'gc>>,
+    ) {
+        self.0
+            .write(activation.context.gc_context)
+            .commands
+            .push(Context3DCommand::SetTextureAt {
+                sampler,
+                texture: texture.map(|texture| texture.handle()),
+            });
+    }
+
+    pub fn set_sampler_state_at(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        sampler: u32,
+        wrap: Context3DWrapMode,
+        filter: Context3DTextureFilter,
+        mip_filter: Context3DMipFilter,
+    ) {
+        self.0.write(activation.context.gc_context).commands.push(
+            Context3DCommand::SetSamplerStateAt {
+                sampler,
+                wrap,
+                filter,
+                mip_filter,
+            },
+        );
+    }
+
+    pub fn set_blend_factors(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        source_factor: Context3DBlendFactor,
+        destination_factor: Context3DBlendFactor,
+    ) {
+        self.0.write(activation.context.gc_context).commands.push(
+            Context3DCommand::SetBlendFactors {
+                source_factor,
+                destination_factor,
+            },
+        );
+    }
+
+    pub fn set_depth_test(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        depth_mask: bool,
+        pass_compare_mode: Context3DCompareMode,
+    ) {
+        self.0
+            .write(activation.context.gc_context)
+            .commands
+            .push(Context3DCommand::SetDepthTest {
+                depth_mask,
+                pass_compare_mode,
+            });
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_stencil_actions(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        triangle_face: Context3DTriangleFace,
+        compare_mode: Context3DCompareMode,
+        action_on_both_pass: Context3DStencilAction,
+        action_on_depth_fail: Context3DStencilAction,
+        action_on_depth_pass_stencil_fail: Context3DStencilAction,
+    ) {
+        self.0.write(activation.context.gc_context).commands.push(
+            Context3DCommand::SetStencilActions {
+                triangle_face,
+                compare_mode,
+                action_on_both_pass,
+                action_on_depth_fail,
+                action_on_depth_pass_stencil_fail,
+            },
+        );
+    }
+
+    pub fn set_stencil_reference_value(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        reference_value: u32,
+        read_mask: u32,
+        write_mask: u32,
+    ) {
+        self.0.write(activation.context.gc_context).commands.push(
+            Context3DCommand::SetStencilReferenceValue {
+                reference_value,
+                read_mask,
+                write_mask,
+            },
+        );
+    }
+
+    pub fn set_scissor_rectangle(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        rectangle: Option<Context3DRectangle>,
+    ) {
+        self.0
+            .write(activation.context.gc_context)
+            .commands
+            .push(Context3DCommand::SetScissorRectangle { rectangle });
+    }
+
+    pub fn set_color_mask(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        red: bool,
+        green: bool,
+        blue: bool,
+        alpha: bool,
+    ) {
+        self.0
+            .write(activation.context.gc_context)
+            .commands
+            .push(Context3DCommand::SetColorMask {
+                red,
+                green,
+                blue,
+                alpha,
+            });
+    }
+
+    pub fn set_render_to_texture(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        texture: TextureObject<'gc>,
+        enable_depth_and_stencil: bool,
+        anti_alias: u32,
+        surface_selector: u32,
+        color_output_index: u32,
+    ) {
+        self.0.write(activation.context.gc_context).commands.push(
+            Context3DCommand::SetRenderToTexture {
+                texture: texture.handle(),
+                enable_depth_and_stencil,
+                anti_alias,
+                surface_selector,
+                color_output_index,
+            },
+        );
+    }
+
+    pub fn set_render_to_back_buffer(&self, activation: &mut Activation<'_, 'gc, '_>) {
+        self.0
+            .write(activation.context.gc_context)
+            .commands
+            .push(Context3DCommand::SetRenderToBackBuffer);
+    }
+
+    /// Executes all pending commands, and then copies the back buffer to `bitmap_data`.
+    pub fn draw_to_bitmap_data(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        bitmap_data: GcCell<'gc, BitmapData<'gc>>,
+    ) -> Result<(), Error<'gc>> {
+        self.present(activation)?;
+
+        let handle = {
+            let context3d = self.0.read();
+            let context3d = context3d.render_context.as_ref().unwrap();
+            if !context3d.should_render() {
+                // There's no back buffer before `configureBackBuffer` is called.
+                return Ok(());
+            }
+            context3d.bitmap_handle()
+        };
+
+        bitmap_data
+            .write(activation.context.gc_context)
+            .draw_bitmap_handle(&handle, activation.context.renderer);
+        Ok(())
+    }
+
     pub fn present(&self, activation: &mut Activation<'_, 'gc, '_>) -> Result<(), Error<'gc>> {
         let mut write = self.0.write(activation.context.gc_context);
         let commands = std::mem::take(&mut write.commands);
@@ -1,7 +1,6 @@
 //! Object representation for Error objects
 
 use crate::avm2::activation::Activation;
-#[cfg(feature = "avm_debug")]
 use crate::avm2::call_stack::CallStack;
 use crate::avm2::object::script_object::ScriptObjectData;
 use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject};
@@ -12,6 +11,7 @@ use crate::avm2::Multiname;
 use crate::string::WString;
 use gc_arena::{Collect, GcCell, MutationContext};
 use std::cell::{Ref, RefMut};
+use std::fmt::Debug;
 
 /// A class instance allocator that allocates Error objects.
 pub fn error_allocator<'gc>(
@@ -24,25 +24,31 @@ pub fn error_allocator<'gc>(
         activation.context.gc_context,
         ErrorObjectData {
             base,
-            #[cfg(feature = "avm_debug")]
-            call_stack: activation.avm2().call_stack().read().clone(),
+            // Copying the call stack is costly, so unless `getStackTrace` is
+            // available it's only captured when the error is thrown out of a method.
+            call_stack: if cfg!(feature = "avm_debug") {
+                Some(activation.avm2().call_stack().read().clone())
+            } else {
+                None
+            },
         },
     ))
     .into())
 }
 
-#[derive(Clone, Collect, Debug, Copy)]
+#[derive(Clone, Collect, Copy)]
 #[collect(no_drop)]
 pub struct ErrorObject<'gc>(GcCell<'gc, ErrorObjectData<'gc>>);
 
-#[derive(Clone, Collect, Debug)]
+#[derive(Clone, Collect)]
 #[collect(no_drop)]
 pub struct ErrorObjectData<'gc> {
     /// Base script object
     base: ScriptObjectData<'gc>,
 
-    #[cfg(feature = "avm_debug")]
-    call_stack: CallStack<'gc>,
+    /// The call stack at the time this error was created or first thrown,
+    /// if it has been captured yet.
+    call_stack: Option<CallStack<'gc>>,
 }
 
 impl<'gc> ErrorObject<'gc> {
@@ -73,7 +79,9 @@ impl<'gc> ErrorObject<'gc> {
     ) -> Result<AvmString<'gc>, Error<'gc>> {
         let mut output = WString::new();
         output.push_str(&self.display(activation)?);
-        self.call_stack().display(&mut output);
+        if let Some(call_stack) = self.call_stack() {
+            call_stack.display(&mut output);
+        }
         Ok(AvmString::new(activation.context.gc_context, output))
     }
 
@@ -85,9 +93,37 @@ impl<'gc> ErrorObject<'gc> {
         self.display(activation)
     }
 
-    #[cfg(feature = "avm_debug")]
-    fn call_stack(&self) -> Ref<CallStack<'gc>> {
-        Ref::map(self.0.read(), |r| &r.call_stack)
+    pub fn call_stack(&self) -> Option<Ref<CallStack<'gc>>> {
+        let read = self.0.read();
+        read.call_stack.as_ref()?;
+
+        Some(Ref::map(read, |r| r.call_stack.as_ref().unwrap()))
+    }
+
+    /// Capture the current call stack, unless one was already captured.
+    pub fn capture_call_stack(&self, activation: &mut Activation<'_, 'gc, '_>) {
+        let call_stack = activation.avm2().call_stack();
+        let mut write = self.0.write(activation.context.gc_context);
+        if write.call_stack.is_none() {
+            write.call_stack = Some(call_stack.read().clone());
+        }
+    }
+}
+
+impl<'gc> Debug for ErrorObject<'gc> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self.0.try_read() {
+            Ok(obj) => f
+                .debug_struct("ErrorObject")
+                .field("class", &obj.base.debug_class_name())
+                .field("ptr", &self.0.as_ptr())
+                .finish(),
+            Err(err) => f
+                .debug_struct("ErrorObject")
+                .field("class", &err)
+                .field("ptr", &self.0.as_ptr())
+                .finish(),
+        }
     }
 }
 
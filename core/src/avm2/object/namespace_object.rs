@@ -6,6 +6,7 @@ use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 use crate::avm2::Namespace;
+use crate::string::AvmString;
 use gc_arena::{Collect, GcCell, MutationContext};
 use std::cell::{Ref, RefMut};
 
@@ -21,6 +22,7 @@ pub fn namespace_allocator<'gc>(
         NamespaceObjectData {
             base,
             namespace: Namespace::public(),
+            prefix: None,
         },
     ))
     .into())
@@ -39,6 +41,9 @@ pub struct NamespaceObjectData<'gc> {
 
     /// The namespace name this object is associated with.
     namespace: Namespace<'gc>,
+
+    /// The prefix of this namespace, if it was constructed with one.
+    prefix: Option<AvmString<'gc>>,
 }
 
 impl<'gc> NamespaceObject<'gc> {
@@ -52,7 +57,11 @@ impl<'gc> NamespaceObject<'gc> {
 
         let mut this: Object<'gc> = NamespaceObject(GcCell::allocate(
             activation.context.gc_context,
-            NamespaceObjectData { base, namespace },
+            NamespaceObjectData {
+                base,
+                namespace,
+                prefix: None,
+            },
         ))
         .into();
         this.install_instance_slots(activation);
@@ -61,6 +70,21 @@ impl<'gc> NamespaceObject<'gc> {
 
         Ok(this)
     }
+
+    pub fn prefix(&self) -> Option<AvmString<'gc>> {
+        self.0.read().prefix
+    }
+
+    pub fn init_namespace(
+        &self,
+        mc: MutationContext<'gc, '_>,
+        namespace: Namespace<'gc>,
+        prefix: Option<AvmString<'gc>>,
+    ) {
+        let mut write = self.0.write(mc);
+        write.namespace = namespace;
+        write.prefix = prefix;
+    }
 }
 
 impl<'gc> TObject<'gc> for NamespaceObject<'gc> {
@@ -90,4 +114,8 @@ impl<'gc> TObject<'gc> for NamespaceObject<'gc> {
     fn as_namespace(&self) -> Option<Ref<Namespace<'gc>>> {
         Some(Ref::map(self.0.read(), |s| &s.namespace))
     }
+
+    fn as_namespace_object(&self) -> Option<Self> {
+        Some(*self)
+    }
 }
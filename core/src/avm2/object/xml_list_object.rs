@@ -0,0 +1,348 @@
+//! Object representation for XMLList objects
+
+use crate::avm2::activation::Activation;
+use crate::avm2::e4x::{self, E4XNode, E4XNodeKind};
+use crate::avm2::error::type_error;
+use crate::avm2::object::script_object::ScriptObjectData;
+use crate::avm2::object::xml_object::xml_index;
+use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject, XmlObject};
+use crate::avm2::property::Property;
+use crate::avm2::value::{Hint, Value};
+use crate::avm2::Error;
+use crate::avm2::Multiname;
+use crate::string::{AvmString, WString};
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::cell::{Ref, RefMut};
+
+/// A class instance allocator that allocates XMLList objects.
+pub fn xml_list_allocator<'gc>(
+    class: ClassObject<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    let base = ScriptObjectData::new(class);
+
+    Ok(XmlListObject(GcCell::allocate(
+        activation.context.gc_context,
+        XmlListObjectData {
+            base,
+            children: Vec::new(),
+        },
+    ))
+    .into())
+}
+
+#[derive(Clone, Collect, Debug, Copy)]
+#[collect(no_drop)]
+pub struct XmlListObject<'gc>(GcCell<'gc, XmlListObjectData<'gc>>);
+
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub struct XmlListObjectData<'gc> {
+    /// Base script object
+    base: ScriptObjectData<'gc>,
+
+    /// The nodes in this list.
+    children: Vec<E4XNode<'gc>>,
+}
+
+impl<'gc> XmlListObject<'gc> {
+    /// Create a list containing the given nodes.
+    pub fn from_nodes(
+        activation: &mut Activation<'_, 'gc, '_>,
+        children: Vec<E4XNode<'gc>>,
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        let class = activation.avm2().classes().xml_list;
+        let base = ScriptObjectData::new(class);
+
+        let mut this: Object<'gc> = XmlListObject(GcCell::allocate(
+            activation.context.gc_context,
+            XmlListObjectData { base, children },
+        ))
+        .into();
+        this.install_instance_slots(activation);
+
+        class.call_native_init(Some(this), &[], activation)?;
+
+        Ok(this)
+    }
+
+    pub fn children(&self) -> Vec<E4XNode<'gc>> {
+        self.0.read().children.clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.read().children.len()
+    }
+
+    pub fn set_children(&self, mc: MutationContext<'gc, '_>, children: Vec<E4XNode<'gc>>) {
+        self.0.write(mc).children = children;
+    }
+
+    /// Returns the matching attributes or children of every element in this list.
+    fn matching_children(&self, name: &Multiname<'gc>) -> Vec<E4XNode<'gc>> {
+        self.children()
+            .into_iter()
+            .flat_map(|child| child.matching_children(name))
+            .collect()
+    }
+}
+
+impl<'gc> TObject<'gc> for XmlListObject<'gc> {
+    fn base(&self) -> Ref<ScriptObjectData<'gc>> {
+        Ref::map(self.0.read(), |read| &read.base)
+    }
+
+    fn base_mut(&self, mc: MutationContext<'gc, '_>) -> RefMut<ScriptObjectData<'gc>> {
+        RefMut::map(self.0.write(mc), |write| &mut write.base)
+    }
+
+    fn as_ptr(&self) -> *const ObjectPtr {
+        self.0.as_ptr() as *const ObjectPtr
+    }
+
+    // As with `XML`, properties always refer to the attributes and children
+    // of the nodes in this list.
+    fn get_property_with_trait(
+        self,
+        multiname: &Multiname<'gc>,
+        _property: Option<Property>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        self.get_property_local(multiname, activation)
+    }
+
+    fn get_property_local(
+        self,
+        name: &Multiname<'gc>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        if let Some(index) = xml_index(name) {
+            return match self.0.read().children.get(index).copied() {
+                Some(child) => Ok(XmlObject::from_node(activation, child)?.into()),
+                None => Ok(Value::Undefined),
+            };
+        }
+
+        let name = e4x::resolve_name(name, activation);
+        let children = self.matching_children(&name);
+        Ok(XmlListObject::from_nodes(activation, children)?.into())
+    }
+
+    fn set_property_with_trait(
+        &mut self,
+        multiname: &Multiname<'gc>,
+        _property: Option<Property>,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        self.set_property_local(multiname, value, activation)
+    }
+
+    fn set_property_local(
+        self,
+        name: &Multiname<'gc>,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        let mc = activation.context.gc_context;
+
+        if let Some(index) = xml_index(name) {
+            let existing = self.0.read().children.get(index).copied();
+            let existing = if let Some(existing) = existing {
+                existing
+            } else {
+                // Assigning past the end of the list appends to it, which is
+                // how filter expressions collect their results.
+                let new_nodes = match value.as_object() {
+                    Some(object) if object.as_xml().is_some() => {
+                        vec![object.as_xml().unwrap().node()]
+                    }
+                    Some(object) if object.as_xml_list().is_some() => {
+                        object.as_xml_list().unwrap().children()
+                    }
+                    _ => vec![E4XNode::text(mc, value.coerce_to_string(activation)?)],
+                };
+                self.0.write(mc).children.extend(new_nodes);
+                return Ok(());
+            };
+
+            match e4x::value_to_nodes(activation, value)? {
+                Some(new_nodes) => {
+                    if let (Some(parent), Some(child_index)) =
+                        (existing.parent(), existing.child_index())
+                    {
+                        existing.remove(mc);
+                        for (offset, new_child) in new_nodes.iter().enumerate() {
+                            parent.insert_child(mc, child_index + offset, *new_child);
+                        }
+                    }
+                    self.0.write(mc).children.splice(index..=index, new_nodes);
+                }
+                None => {
+                    let text = e4x::value_to_attribute_string(activation, value)?;
+                    if existing.is_element() {
+                        existing.clear_children(mc);
+                        if !text.is_empty() {
+                            existing.append_child(mc, E4XNode::text(mc, text));
+                        }
+                    } else {
+                        existing.set_value(mc, text);
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        let children = self.children();
+        match children.as_slice() {
+            [] => Ok(()),
+            [child] => {
+                let child = XmlObject::from_node(activation, *child)?;
+                child.set_property_local(name, value, activation)
+            }
+            _ => Err(Error::AvmError(type_error(
+                activation,
+                "Error #1089: Assignment to lists with more than one item is not supported.",
+                1089,
+            )?)),
+        }
+    }
+
+    fn delete_property(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        multiname: &Multiname<'gc>,
+    ) -> Result<bool, Error<'gc>> {
+        self.delete_property_local(activation, multiname)
+    }
+
+    fn delete_property_local(
+        self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        name: &Multiname<'gc>,
+    ) -> Result<bool, Error<'gc>> {
+        let mc = activation.context.gc_context;
+
+        if let Some(index) = xml_index(name) {
+            if index < self.len() {
+                let child = self.0.write(mc).children.remove(index);
+                child.remove(mc);
+            }
+            return Ok(true);
+        }
+
+        let name = e4x::resolve_name(name, activation);
+        for child in self.matching_children(&name) {
+            child.remove(mc);
+        }
+        Ok(true)
+    }
+
+    fn has_own_property(self, name: &Multiname<'gc>) -> bool {
+        if let Some(index) = xml_index(name) {
+            return index < self.len();
+        }
+
+        self.children().into_iter().any(|child| {
+            (name.is_attribute() && child.is_element()) || !child.matching_children(name).is_empty()
+        })
+    }
+
+    fn get_next_enumerant(
+        self,
+        last_index: u32,
+        _activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Option<u32>, Error<'gc>> {
+        Ok(if (last_index as usize) < self.len() {
+            Some(last_index + 1)
+        } else {
+            None
+        })
+    }
+
+    fn get_enumerant_name(
+        self,
+        index: u32,
+        _activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        Ok(match index.checked_sub(1) {
+            Some(index) if (index as usize) < self.len() => index.into(),
+            _ => Value::Undefined,
+        })
+    }
+
+    fn get_enumerant_value(
+        self,
+        index: u32,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let child = index
+            .checked_sub(1)
+            .and_then(|index| self.0.read().children.get(index as usize).copied());
+        match child {
+            Some(child) => Ok(XmlObject::from_node(activation, child)?.into()),
+            None => Ok(Value::Undefined),
+        }
+    }
+
+    fn default_hint(&self) -> Hint {
+        Hint::String
+    }
+
+    fn value_of(&self, _mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error<'gc>> {
+        Ok(Value::Object(Object::from(*self)))
+    }
+
+    fn as_xml_list(&self) -> Option<Self> {
+        Some(*self)
+    }
+}
+
+impl<'gc> XmlListObject<'gc> {
+    /// Whether this list has simple content, as defined by `XMLList.hasSimpleContent`.
+    pub fn has_simple_content(&self) -> bool {
+        match self.0.read().children.as_slice() {
+            [] => true,
+            [child] => child.has_simple_content(),
+            children => children.iter().all(|child| !child.is_element()),
+        }
+    }
+
+    /// Convert this list to a string, as done by `XMLList.toString`.
+    pub fn list_to_string(
+        &self,
+        mc: MutationContext<'gc, '_>,
+        settings: &e4x::E4XSettings,
+    ) -> AvmString<'gc> {
+        if !self.has_simple_content() {
+            return self.list_to_xml_string(mc, settings);
+        }
+
+        let mut out = WString::new();
+        for child in self.children() {
+            if !matches!(
+                &*child.kind(),
+                E4XNodeKind::Comment(_) | E4XNodeKind::ProcessingInstruction(_)
+            ) {
+                out.push_str(&child.xml_to_string(mc, settings));
+            }
+        }
+        AvmString::new(mc, out)
+    }
+
+    /// Convert this list to XML markup, as done by `XMLList.toXMLString`.
+    pub fn list_to_xml_string(
+        &self,
+        mc: MutationContext<'gc, '_>,
+        settings: &e4x::E4XSettings,
+    ) -> AvmString<'gc> {
+        let mut out = WString::new();
+        for (i, child) in self.children().into_iter().enumerate() {
+            if i > 0 && settings.pretty_printing {
+                out.push_byte(b'\n');
+            }
+            out.push_str(&child.xml_to_xml_string(mc, settings));
+        }
+        AvmString::new(mc, out)
+    }
+}
@@ -1,10 +1,14 @@
 //! Object representation for XML objects
 
 use crate::avm2::activation::Activation;
+use crate::avm2::e4x::{self, E4XNode};
+use crate::avm2::error::type_error;
 use crate::avm2::object::script_object::ScriptObjectData;
-use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject};
-use crate::avm2::value::Value;
+use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject, XmlListObject};
+use crate::avm2::property::Property;
+use crate::avm2::value::{Hint, Value};
 use crate::avm2::Error;
+use crate::avm2::Multiname;
 use gc_arena::{Collect, GcCell, MutationContext};
 use std::cell::{Ref, RefMut};
 
@@ -14,10 +18,11 @@ pub fn xml_allocator<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
 ) -> Result<Object<'gc>, Error<'gc>> {
     let base = ScriptObjectData::new(class);
+    let node = E4XNode::text(activation.context.gc_context, "".into());
 
     Ok(XmlObject(GcCell::allocate(
         activation.context.gc_context,
-        XmlObjectData { base },
+        XmlObjectData { base, node },
     ))
     .into())
 }
@@ -31,6 +36,47 @@ pub struct XmlObject<'gc>(GcCell<'gc, XmlObjectData<'gc>>);
 pub struct XmlObjectData<'gc> {
     /// Base script object
     base: ScriptObjectData<'gc>,
+
+    /// The node of the XML tree that this object represents.
+    node: E4XNode<'gc>,
+}
+
+impl<'gc> XmlObject<'gc> {
+    /// Wrap an XML node into an object.
+    pub fn from_node(
+        activation: &mut Activation<'_, 'gc, '_>,
+        node: E4XNode<'gc>,
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        let class = activation.avm2().classes().xml;
+        let base = ScriptObjectData::new(class);
+
+        let mut this: Object<'gc> = XmlObject(GcCell::allocate(
+            activation.context.gc_context,
+            XmlObjectData { base, node },
+        ))
+        .into();
+        this.install_instance_slots(activation);
+
+        class.call_native_init(Some(this), &[], activation)?;
+
+        Ok(this)
+    }
+
+    pub fn node(&self) -> E4XNode<'gc> {
+        self.0.read().node
+    }
+
+    pub fn set_node(&self, mc: MutationContext<'gc, '_>, node: E4XNode<'gc>) {
+        self.0.write(mc).node = node;
+    }
+}
+
+/// Parse a property name as an index into an XML or XMLList object.
+pub(super) fn xml_index(name: &Multiname<'_>) -> Option<usize> {
+    if name.is_attribute() {
+        return None;
+    }
+    name.local_name()?.parse::<usize>().ok()
 }
 
 impl<'gc> TObject<'gc> for XmlObject<'gc> {
@@ -46,6 +92,201 @@ impl<'gc> TObject<'gc> for XmlObject<'gc> {
         self.0.as_ptr() as *const ObjectPtr
     }
 
+    // XML properties always refer to attributes and children, even if they
+    // share their name with one of the methods of the `XML` class.
+    fn get_property_with_trait(
+        self,
+        multiname: &Multiname<'gc>,
+        _property: Option<Property>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        self.get_property_local(multiname, activation)
+    }
+
+    fn get_property_local(
+        self,
+        name: &Multiname<'gc>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        if let Some(index) = xml_index(name) {
+            return Ok(if index == 0 {
+                self.into()
+            } else {
+                Value::Undefined
+            });
+        }
+
+        let name = e4x::resolve_name(name, activation);
+        let children = self.node().matching_children(&name);
+        Ok(XmlListObject::from_nodes(activation, children)?.into())
+    }
+
+    fn set_property_with_trait(
+        &mut self,
+        multiname: &Multiname<'gc>,
+        _property: Option<Property>,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        self.set_property_local(multiname, value, activation)
+    }
+
+    fn set_property_local(
+        self,
+        name: &Multiname<'gc>,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        if xml_index(name).is_some() {
+            return Err(Error::AvmError(type_error(
+                activation,
+                "Error #1087: Assignment to indexed XML is not allowed.",
+                1087,
+            )?));
+        }
+
+        let node = self.node();
+        if !node.is_element() {
+            return Ok(());
+        }
+
+        let name = e4x::resolve_name(name, activation);
+        let mc = activation.context.gc_context;
+        let matching = node.matching_children(&name);
+
+        if name.is_attribute() {
+            let value = e4x::value_to_attribute_string(activation, value)?;
+            if let Some((first, rest)) = matching.split_first() {
+                first.set_value(mc, value);
+                for attribute in rest {
+                    attribute.remove(mc);
+                }
+            } else if let Some(local_name) = name.local_name() {
+                let attribute =
+                    E4XNode::attribute(mc, e4x::name_namespace(&name), local_name, value);
+                node.set_attribute(mc, attribute);
+            }
+            return Ok(());
+        }
+
+        let replacement = e4x::value_to_nodes(activation, value)?;
+        match matching.split_first() {
+            Some((first, rest)) => {
+                for child in rest {
+                    child.remove(mc);
+                }
+                match replacement {
+                    Some(nodes) => {
+                        let index = first.child_index().unwrap_or_default();
+                        first.remove(mc);
+                        for (offset, new_child) in nodes.into_iter().enumerate() {
+                            node.insert_child(mc, index + offset, new_child);
+                        }
+                    }
+                    None => {
+                        let text = value.coerce_to_string(activation)?;
+                        first.clear_children(mc);
+                        if !text.is_empty() {
+                            first.append_child(mc, E4XNode::text(mc, text));
+                        }
+                    }
+                }
+            }
+            None => match replacement {
+                Some(nodes) => {
+                    for new_child in nodes {
+                        node.append_child(mc, new_child);
+                    }
+                }
+                None => {
+                    let local_name = match name.local_name() {
+                        Some(local_name) if &local_name != b"*" => local_name,
+                        _ => return Ok(()),
+                    };
+                    let text = value.coerce_to_string(activation)?;
+                    let element = E4XNode::element(mc, e4x::name_namespace(&name), local_name);
+                    if !text.is_empty() {
+                        element.append_child(mc, E4XNode::text(mc, text));
+                    }
+                    node.append_child(mc, element);
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    fn delete_property(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        multiname: &Multiname<'gc>,
+    ) -> Result<bool, Error<'gc>> {
+        self.delete_property_local(activation, multiname)
+    }
+
+    fn delete_property_local(
+        self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        name: &Multiname<'gc>,
+    ) -> Result<bool, Error<'gc>> {
+        if xml_index(name).is_some() {
+            return Ok(false);
+        }
+
+        let name = e4x::resolve_name(name, activation);
+        for child in self.node().matching_children(&name) {
+            child.remove(activation.context.gc_context);
+        }
+        Ok(true)
+    }
+
+    fn has_own_property(self, name: &Multiname<'gc>) -> bool {
+        if let Some(index) = xml_index(name) {
+            return index == 0;
+        }
+
+        // Attributes always resolve to the current node inside filter predicates,
+        // so that `list.(@missing == "x")` doesn't look for `@missing` elsewhere.
+        let node = self.node();
+        (name.is_attribute() && node.is_element()) || !node.matching_children(name).is_empty()
+    }
+
+    fn get_next_enumerant(
+        self,
+        last_index: u32,
+        _activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Option<u32>, Error<'gc>> {
+        Ok(if last_index == 0 { Some(1) } else { None })
+    }
+
+    fn get_enumerant_name(
+        self,
+        index: u32,
+        _activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        Ok(if index == 1 {
+            0.into()
+        } else {
+            Value::Undefined
+        })
+    }
+
+    fn get_enumerant_value(
+        self,
+        index: u32,
+        _activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        Ok(if index == 1 {
+            self.into()
+        } else {
+            Value::Undefined
+        })
+    }
+
+    fn default_hint(&self) -> Hint {
+        Hint::String
+    }
+
     fn value_of(&self, _mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error<'gc>> {
         Ok(Value::Object(Object::from(*self)))
     }
@@ -3,6 +3,7 @@
 use crate::avm2::activation::Activation;
 use crate::avm2::domain::Domain;
 use crate::avm2::object::{Object, TObject};
+use crate::avm2::property::Property;
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 use crate::avm2::Multiname;
@@ -160,6 +161,27 @@ impl<'gc> ScopeChain<'gc> {
             Ok(None)
         }
     }
+
+    /// Find the scope that `find` will always return for a multiname, along
+    /// with the trait it has for that name.
+    ///
+    /// Returns `None` if the name isn't a trait of any of our scopes, or if
+    /// a `with` scope could shadow it with a dynamic property.
+    pub fn find_trait(&self, multiname: &Multiname<'gc>) -> Option<(usize, Property)> {
+        let scopes = self.scopes?;
+        for (depth, scope) in scopes.iter().enumerate().rev() {
+            let property = scope
+                .values()
+                .vtable()
+                .and_then(|vtable| vtable.get_trait(multiname));
+            if let Some(property) = property {
+                return Some((depth, property));
+            } else if scope.with() {
+                return None;
+            }
+        }
+        None
+    }
 }
 
 /// Searches for a scope in the scope stack by a multiname.
@@ -2,6 +2,7 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::class::Class;
+use crate::avm2::metadata::Metadata;
 use crate::avm2::method::Method;
 use crate::avm2::script::TranslationUnit;
 use crate::avm2::value::{abc_default_value, Value};
@@ -50,6 +51,9 @@ pub struct Trait<'gc> {
 
     /// The kind of trait in use.
     kind: TraitKind<'gc>,
+
+    /// The metadata tags attached to this trait, if any.
+    metadata: Option<Box<[Metadata<'gc>]>>,
 }
 
 fn trait_attribs_from_abc_traits(abc_trait: &AbcTrait) -> TraitAttributes {
@@ -111,6 +115,7 @@ impl<'gc> Trait<'gc> {
         Trait {
             name,
             attributes: TraitAttributes::empty(),
+            metadata: None,
             kind: TraitKind::Class { slot_id: 0, class },
         }
     }
@@ -119,6 +124,7 @@ impl<'gc> Trait<'gc> {
         Trait {
             name,
             attributes: TraitAttributes::empty(),
+            metadata: None,
             kind: TraitKind::Method { disp_id: 0, method },
         }
     }
@@ -127,6 +133,7 @@ impl<'gc> Trait<'gc> {
         Trait {
             name,
             attributes: TraitAttributes::empty(),
+            metadata: None,
             kind: TraitKind::Getter { disp_id: 0, method },
         }
     }
@@ -135,6 +142,7 @@ impl<'gc> Trait<'gc> {
         Trait {
             name,
             attributes: TraitAttributes::empty(),
+            metadata: None,
             kind: TraitKind::Setter { disp_id: 0, method },
         }
     }
@@ -143,6 +151,7 @@ impl<'gc> Trait<'gc> {
         Trait {
             name,
             attributes: TraitAttributes::empty(),
+            metadata: None,
             kind: TraitKind::Function {
                 slot_id: 0,
                 function,
@@ -158,6 +167,7 @@ impl<'gc> Trait<'gc> {
         Trait {
             name,
             attributes: TraitAttributes::empty(),
+            metadata: None,
             kind: TraitKind::Slot {
                 slot_id: 0,
                 default_value: default_value.unwrap_or_else(|| default_value_for_type(&type_name)),
@@ -175,6 +185,7 @@ impl<'gc> Trait<'gc> {
         Trait {
             name,
             attributes: TraitAttributes::empty(),
+            metadata: None,
             kind: TraitKind::Slot {
                 slot_id: 0,
                 default_value: default_value.unwrap_or_else(|| default_value_for_type(&type_name)),
@@ -193,7 +204,7 @@ impl<'gc> Trait<'gc> {
         let mc = activation.context.gc_context;
         let name = QName::from_abc_multiname(unit, abc_trait.name, mc)?;
 
-        Ok(match &abc_trait.kind {
+        let kind = match &abc_trait.kind {
             AbcTraitKind::Slot {
                 slot_id,
                 type_name,
@@ -204,56 +215,32 @@ impl<'gc> Trait<'gc> {
                     .deref()
                     .clone();
                 let default_value = slot_default_value(unit, value, &type_name, activation)?;
-                Trait {
-                    name,
-                    attributes: trait_attribs_from_abc_traits(abc_trait),
-                    kind: TraitKind::Slot {
-                        slot_id: *slot_id,
-                        type_name,
-                        default_value,
-                        unit: Some(unit),
-                    },
+                TraitKind::Slot {
+                    slot_id: *slot_id,
+                    type_name,
+                    default_value,
+                    unit: Some(unit),
                 }
             }
-            AbcTraitKind::Method { disp_id, method } => Trait {
-                name,
-                attributes: trait_attribs_from_abc_traits(abc_trait),
-                kind: TraitKind::Method {
-                    disp_id: *disp_id,
-                    method: unit.load_method(*method, false, activation)?,
-                },
+            AbcTraitKind::Method { disp_id, method } => TraitKind::Method {
+                disp_id: *disp_id,
+                method: unit.load_method(*method, false, activation)?,
             },
-            AbcTraitKind::Getter { disp_id, method } => Trait {
-                name,
-                attributes: trait_attribs_from_abc_traits(abc_trait),
-                kind: TraitKind::Getter {
-                    disp_id: *disp_id,
-                    method: unit.load_method(*method, false, activation)?,
-                },
+            AbcTraitKind::Getter { disp_id, method } => TraitKind::Getter {
+                disp_id: *disp_id,
+                method: unit.load_method(*method, false, activation)?,
             },
-            AbcTraitKind::Setter { disp_id, method } => Trait {
-                name,
-                attributes: trait_attribs_from_abc_traits(abc_trait),
-                kind: TraitKind::Setter {
-                    disp_id: *disp_id,
-                    method: unit.load_method(*method, false, activation)?,
-                },
+            AbcTraitKind::Setter { disp_id, method } => TraitKind::Setter {
+                disp_id: *disp_id,
+                method: unit.load_method(*method, false, activation)?,
             },
-            AbcTraitKind::Class { slot_id, class } => Trait {
-                name,
-                attributes: trait_attribs_from_abc_traits(abc_trait),
-                kind: TraitKind::Class {
-                    slot_id: *slot_id,
-                    class: unit.load_class(class.0, activation)?,
-                },
+            AbcTraitKind::Class { slot_id, class } => TraitKind::Class {
+                slot_id: *slot_id,
+                class: unit.load_class(class.0, activation)?,
             },
-            AbcTraitKind::Function { slot_id, function } => Trait {
-                name,
-                attributes: trait_attribs_from_abc_traits(abc_trait),
-                kind: TraitKind::Function {
-                    slot_id: *slot_id,
-                    function: unit.load_method(*function, true, activation)?,
-                },
+            AbcTraitKind::Function { slot_id, function } => TraitKind::Function {
+                slot_id: *slot_id,
+                function: unit.load_method(*function, true, activation)?,
             },
             AbcTraitKind::Const {
                 slot_id,
@@ -265,17 +252,30 @@ impl<'gc> Trait<'gc> {
                     .deref()
                     .clone();
                 let default_value = slot_default_value(unit, value, &type_name, activation)?;
-                Trait {
-                    name,
-                    attributes: trait_attribs_from_abc_traits(abc_trait),
-                    kind: TraitKind::Const {
-                        slot_id: *slot_id,
-                        type_name,
-                        default_value,
-                        unit: Some(unit),
-                    },
+                TraitKind::Const {
+                    slot_id: *slot_id,
+                    type_name,
+                    default_value,
+                    unit: Some(unit),
                 }
             }
+        };
+
+        let metadata = Metadata::from_abc_indices(unit, &abc_trait.metadata, mc)?;
+
+        // Class-level metadata is declared on the trait that defines the
+        // class, but is reported as part of the class itself.
+        if let TraitKind::Class { class, .. } = &kind {
+            if let Some(metadata) = &metadata {
+                class.write(mc).set_metadata(metadata.clone());
+            }
+        }
+
+        Ok(Trait {
+            name,
+            attributes: trait_attribs_from_abc_traits(abc_trait),
+            kind,
+            metadata,
         })
     }
 
@@ -287,6 +287,10 @@ impl<'gc> Trait<'gc> {
         &self.kind
     }
 
+    pub fn metadata(&self) -> &[Metadata<'gc>] {
+        self.metadata.as_deref().unwrap_or_default()
+    }
+
     pub fn is_final(&self) -> bool {
         self.attributes.contains(TraitAttributes::FINAL)
     }
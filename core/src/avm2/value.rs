@@ -1,8 +1,11 @@
 //! AVM2 values
 
 use crate::avm2::activation::Activation;
+use crate::avm2::e4x;
 use crate::avm2::globals::NS_VECTOR;
-use crate::avm2::object::{ClassObject, NamespaceObject, Object, PrimitiveObject, TObject};
+use crate::avm2::object::{
+    ClassObject, NamespaceObject, Object, PrimitiveObject, TObject, XmlObject,
+};
 use crate::avm2::script::TranslationUnit;
 use crate::avm2::Error;
 use crate::avm2::Multiname;
@@ -996,6 +999,15 @@ impl<'gc> Value<'gc> {
                     return Ok(*self);
                 }
             }
+
+            // An `XMLList` with a single item can be used as `XML`.
+            if Object::ptr_eq(class, activation.avm2().classes().xml) {
+                if let Some(list) = object.as_xml_list() {
+                    if let [node] = list.children().as_slice() {
+                        return Ok(XmlObject::from_node(activation, *node)?.into());
+                    }
+                }
+            }
         }
 
         let name = class.inner_class_definition().read().name();
@@ -1099,7 +1111,15 @@ impl<'gc> Value<'gc> {
             }
             (Value::String(a), Value::String(b)) => Ok(a == b),
             (Value::Bool(a), Value::Bool(b)) => Ok(a == b),
-            (Value::Object(a), Value::Object(b)) => Ok(Object::ptr_eq(*a, *b)),
+            (Value::Object(a), Value::Object(b)) => {
+                if let (Some(a), Some(b)) =
+                    (e4x::value_nodes((*a).into()), e4x::value_nodes((*b).into()))
+                {
+                    return e4x::nodes_equal(activation, &a, &b);
+                }
+
+                Ok(Object::ptr_eq(*a, *b))
+            }
             (Value::Undefined, Value::Null) => Ok(true),
             (Value::Null, Value::Undefined) => Ok(true),
             (Value::Number(_) | Value::Integer(_), Value::String(_)) => {
@@ -0,0 +1,937 @@
+//! AVM2 bytecode verification and optimization
+//!
+//! Before a method body is run for the first time, its bytecode is decoded
+//! into a list of ops and checked in the same way as Flash Player's verifier
+//! would: every branch must land on an instruction, the operand and scope
+//! stacks must stay within the limits declared by the method body and agree
+//! wherever control flow merges, and registers must exist. Methods that fail
+//! these checks throw a `VerifyError` instead of running.
+//!
+//! The same analysis also infers the primitive types of stack values and
+//! local registers, which is used to remove coercions that can never change
+//! their input. It also tracks which scopes are the method's receiver, so
+//! that name lookups which always end up on the same outer scope can be
+//! resolved once, rather than every time they run.
+
+use crate::avm2::activation::Activation;
+use crate::avm2::error::verify_error;
+use crate::avm2::method::BytecodeMethod;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::property::Property;
+use crate::avm2::Error;
+use crate::swf::extensions::ReadSwfExt;
+use swf::avm2::read::Reader;
+use swf::avm2::types::{
+    AbcFile, Exception, Index, MethodBody, Multiname as AbcMultiname, Namespace as AbcNamespace, Op,
+};
+
+/// A method body which has passed verification.
+#[derive(Debug)]
+pub struct VerifiedMethodInfo {
+    /// The decoded and optimized ops of the method body.
+    ///
+    /// The offsets of all branching ops, including `LookupSwitch`, have been
+    /// rewritten to absolute indices into this list.
+    pub parsed_code: Vec<Op>,
+
+    /// The exception handlers of the method body, in their original order.
+    ///
+    /// All offsets have been rewritten to indices into `parsed_code`.
+    pub exceptions: Vec<Exception>,
+}
+
+/// Verify and optimize the body of a bytecode method.
+pub fn verify_method<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    method: &BytecodeMethod<'gc>,
+) -> Result<VerifiedMethodInfo, Error<'gc>> {
+    let body = method
+        .body()
+        .ok_or("Cannot verify non-native method without body")?;
+
+    let verifier = Verifier {
+        abc: &method.abc,
+        body,
+        method_name: method.method_name(),
+    };
+
+    match verifier.verify() {
+        Ok((mut info, lookups)) => {
+            if !method.is_function && !lookups.is_empty() {
+                resolve_scope_lookups(activation, method, &mut info.parsed_code, &lookups);
+            }
+            Ok(info)
+        }
+        Err(VerifyFailure { message, code }) => Err(Error::AvmError(verify_error(
+            activation,
+            &format!("Error #{code}: {message}"),
+            code,
+        )?)),
+    }
+}
+
+/// Rewrite name lookups that will always find the same trait on the outer
+/// scope into direct accesses of that scope.
+///
+/// A `FindPropStrict` becomes a `GetOuterScope`, and a `GetLex` of a slot on
+/// the global scope becomes a `GetGlobalSlot`. Lookups that would search the
+/// receiver first are only resolved when every receiver of the method has
+/// the same traits: in static methods, and in instance methods of final
+/// classes.
+fn resolve_scope_lookups<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    method: &BytecodeMethod<'gc>,
+    code: &mut [Op],
+    lookups: &[ScopeLookup],
+) {
+    let outer = activation.outer();
+    if outer.is_empty() {
+        return;
+    }
+
+    let this = activation
+        .local_register(0)
+        .ok()
+        .and_then(|this| this.as_object());
+    let receiver_vtable = activation.subclass_object().and_then(|class| {
+        if Object::ptr_eq(this?, class.into()) {
+            class.vtable()
+        } else if class.inner_class_definition().read().is_final() {
+            Some(class.instance_vtable())
+        } else {
+            None
+        }
+    });
+
+    let translation_unit = method.translation_unit();
+    for lookup in lookups {
+        let op = &mut code[lookup.op_index];
+        let index = match op {
+            Op::GetLex { index } | Op::FindPropStrict { index } => *index,
+            _ => continue,
+        };
+        let multiname =
+            match translation_unit.pool_multiname_static(index, activation.context.gc_context) {
+                Ok(multiname) => multiname,
+                Err(_) => continue,
+            };
+
+        if lookup.searches_this {
+            match receiver_vtable {
+                Some(vtable) if vtable.get_trait(&multiname).is_none() => {}
+                _ => continue,
+            }
+        }
+
+        *op = match (&op, outer.find_trait(&multiname)) {
+            (Op::FindPropStrict { .. }, Some((depth, _))) => Op::GetOuterScope {
+                index: depth as u32,
+            },
+            (
+                Op::GetLex { .. },
+                Some((0, Property::Slot { slot_id } | Property::ConstSlot { slot_id })),
+            ) => Op::GetGlobalSlot { index: slot_id },
+            _ => continue,
+        };
+    }
+}
+
+/// The reason a method body failed verification.
+struct VerifyFailure {
+    message: String,
+    code: u32,
+}
+
+impl VerifyFailure {
+    fn new(message: impl Into<String>, code: u32) -> Self {
+        Self {
+            message: message.into(),
+            code,
+        }
+    }
+}
+
+/// What is statically known about a value on the stack or in a register.
+///
+/// Coercing a value of any primitive type to that same type can never change
+/// the value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ValueType {
+    Any,
+    /// The receiver of the method, as passed in register 0.
+    This,
+    Int,
+    Uint,
+    Number,
+    Boolean,
+    String,
+}
+
+impl ValueType {
+    fn merge(self, other: Self) -> Self {
+        if self == other {
+            self
+        } else {
+            ValueType::Any
+        }
+    }
+}
+
+/// The abstract state of a frame just before an op is run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct FrameState {
+    stack: Vec<ValueType>,
+    /// The values on the local scope stack. `with` scopes are always `Any`.
+    scope: Vec<ValueType>,
+    locals: Vec<ValueType>,
+}
+
+impl FrameState {
+    /// Merge another incoming state into this one.
+    ///
+    /// Returns whether this state changed.
+    fn merge(&mut self, other: &FrameState) -> Result<bool, VerifyFailure> {
+        if self.stack.len() != other.stack.len() {
+            return Err(VerifyFailure::new(
+                format!(
+                    "Stack depth is unbalanced. {} != {}.",
+                    self.stack.len(),
+                    other.stack.len()
+                ),
+                1030,
+            ));
+        }
+
+        if self.scope.len() != other.scope.len() {
+            return Err(VerifyFailure::new(
+                format!(
+                    "Scope depth is unbalanced. {} != {}.",
+                    self.scope.len(),
+                    other.scope.len()
+                ),
+                1031,
+            ));
+        }
+
+        let mut changed = false;
+        for (mine, theirs) in self
+            .stack
+            .iter_mut()
+            .chain(self.scope.iter_mut())
+            .chain(self.locals.iter_mut())
+            .zip(
+                other
+                    .stack
+                    .iter()
+                    .chain(other.scope.iter())
+                    .chain(other.locals.iter()),
+            )
+        {
+            let merged = mine.merge(*theirs);
+            changed |= merged != *mine;
+            *mine = merged;
+        }
+
+        Ok(changed)
+    }
+}
+
+/// A `GetLex` or `FindPropStrict` whose name is looked up on the receiver
+/// (if anything) before the outer scope.
+#[derive(Debug)]
+struct ScopeLookup {
+    op_index: usize,
+    /// Whether the receiver is on the local scope stack when the op runs.
+    searches_this: bool,
+}
+
+struct Verifier<'a> {
+    abc: &'a AbcFile,
+    body: &'a MethodBody,
+    method_name: &'a str,
+}
+
+impl<'a> Verifier<'a> {
+    fn verify(&self) -> Result<(VerifiedMethodInfo, Vec<ScopeLookup>), VerifyFailure> {
+        let (mut parsed_code, byte_offsets, illegal_opcode) = self.decode();
+        let index_of = |byte_offset: i64| -> Option<usize> {
+            usize::try_from(byte_offset)
+                .ok()
+                .and_then(|byte_offset| byte_offsets.binary_search(&byte_offset).ok())
+        };
+
+        // Rewrite branch offsets, which are relative to the end of the
+        // instruction (or its start, for `LookupSwitch`), into op indices.
+        //
+        // Invalid targets are only an error if the branch is reachable, so
+        // they are just recorded here.
+        let mut invalid_branches = vec![false; parsed_code.len()];
+        for (index, op) in parsed_code.iter_mut().enumerate() {
+            let start = byte_offsets[index] as i64;
+            let end = byte_offsets
+                .get(index + 1)
+                .map(|end| *end as i64)
+                .unwrap_or(self.body.code.len() as i64);
+            let mut resolve = |base: i64, offset: &mut i32| match index_of(base + *offset as i64) {
+                Some(target) => *offset = target as i32,
+                None => {
+                    invalid_branches[index] = true;
+                    *offset = 0;
+                }
+            };
+
+            match op {
+                Op::Jump { offset }
+                | Op::IfTrue { offset }
+                | Op::IfFalse { offset }
+                | Op::IfEq { offset }
+                | Op::IfNe { offset }
+                | Op::IfGe { offset }
+                | Op::IfGt { offset }
+                | Op::IfLe { offset }
+                | Op::IfLt { offset }
+                | Op::IfNge { offset }
+                | Op::IfNgt { offset }
+                | Op::IfNle { offset }
+                | Op::IfNlt { offset }
+                | Op::IfStrictEq { offset }
+                | Op::IfStrictNe { offset } => resolve(end, offset),
+                Op::LookupSwitch {
+                    default_offset,
+                    case_offsets,
+                } => {
+                    resolve(start, default_offset);
+                    for case_offset in case_offsets.iter_mut() {
+                        resolve(start, case_offset);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut exceptions = Vec::with_capacity(self.body.exceptions.len());
+        for exception in self.body.exceptions.iter() {
+            let code_len = self.body.code.len() as u32;
+            let target = index_of(exception.target_offset as i64);
+            if exception.from_offset > exception.to_offset
+                || exception.to_offset > code_len
+                || target.is_none()
+            {
+                return Err(VerifyFailure::new(
+                    "Illegal range or target offsets in exception handler.",
+                    1054,
+                ));
+            }
+
+            let first_at_or_after = |byte_offset: u32| {
+                byte_offsets.partition_point(|offset| *offset < byte_offset as usize)
+            };
+            exceptions.push(Exception {
+                from_offset: first_at_or_after(exception.from_offset) as u32,
+                to_offset: first_at_or_after(exception.to_offset) as u32,
+                target_offset: target.unwrap() as u32,
+                variable_name: exception.variable_name,
+                type_name: exception.type_name,
+            });
+        }
+
+        let states = self.analyze(&parsed_code, &exceptions, &invalid_branches, illegal_opcode)?;
+        let lookups = self.optimize(&mut parsed_code, &states);
+
+        Ok((
+            VerifiedMethodInfo {
+                parsed_code,
+                exceptions,
+            },
+            lookups,
+        ))
+    }
+
+    /// Decode the instructions of the method body, returning the ops along
+    /// with the byte offset each of them starts at.
+    ///
+    /// Decoding stops at the first illegal opcode, whose offset is returned
+    /// as well. This is only an error if that opcode is actually reachable.
+    fn decode(&self) -> (Vec<Op>, Vec<usize>, Option<usize>) {
+        let code = &self.body.code[..];
+        let mut reader = Reader::new(code);
+        let mut parsed_code = Vec::new();
+        let mut byte_offsets = Vec::new();
+
+        loop {
+            let offset = reader.pos(code);
+            if offset >= code.len() {
+                return (parsed_code, byte_offsets, None);
+            }
+
+            match reader.read_op() {
+                Ok(op) => {
+                    parsed_code.push(op);
+                    byte_offsets.push(offset);
+                }
+                Err(_) => return (parsed_code, byte_offsets, Some(offset)),
+            }
+        }
+    }
+
+    /// The error raised when control reaches the end of the decoded ops.
+    fn end_of_code(&self, illegal_opcode: Option<usize>) -> VerifyFailure {
+        match illegal_opcode {
+            Some(offset) => VerifyFailure::new(
+                format!(
+                    "Method {} contained illegal opcode {} at offset {}.",
+                    self.method_name, self.body.code[offset], offset
+                ),
+                1011,
+            ),
+            None => VerifyFailure::new("Code cannot fall off the end of a method.", 1020),
+        }
+    }
+
+    /// Compute the abstract state of the frame before each reachable op.
+    fn analyze(
+        &self,
+        code: &[Op],
+        exceptions: &[Exception],
+        invalid_branches: &[bool],
+        illegal_opcode: Option<usize>,
+    ) -> Result<Vec<Option<FrameState>>, VerifyFailure> {
+        let mut states: Vec<Option<FrameState>> = vec![None; code.len()];
+        let mut worklist = Vec::new();
+
+        if code.is_empty() {
+            return Err(self.end_of_code(illegal_opcode));
+        }
+
+        let mut locals = vec![ValueType::Any; self.body.num_locals as usize];
+        if let Some(this) = locals.first_mut() {
+            *this = ValueType::This;
+        }
+        states[0] = Some(FrameState {
+            stack: Vec::new(),
+            scope: Vec::new(),
+            locals,
+        });
+        worklist.push(0);
+
+        while let Some(index) = worklist.pop() {
+            let mut state = states[index].clone().unwrap();
+
+            // Any op inside a `try` block can transfer control to its handler,
+            // which starts with just the thrown value on the stack.
+            for exception in exceptions.iter() {
+                if (exception.from_offset as usize..exception.to_offset as usize).contains(&index) {
+                    let mut handler_state = FrameState {
+                        stack: Vec::new(),
+                        scope: Vec::new(),
+                        locals: vec![ValueType::Any; self.body.num_locals as usize],
+                    };
+                    self.push(&mut handler_state, ValueType::Any)?;
+                    Self::flow_to(
+                        &mut states,
+                        &mut worklist,
+                        exception.target_offset as usize,
+                        &handler_state,
+                    )?;
+                }
+            }
+
+            if invalid_branches[index] {
+                return Err(VerifyFailure::new(
+                    "At least one branch target was not on a valid instruction in the method.",
+                    1021,
+                ));
+            }
+
+            let op = &code[index];
+            self.step(op, &mut state)?;
+
+            let falls_through = !matches!(
+                op,
+                Op::Jump { .. }
+                    | Op::LookupSwitch { .. }
+                    | Op::ReturnValue
+                    | Op::ReturnVoid
+                    | Op::Throw
+            );
+            if falls_through {
+                if index + 1 >= code.len() {
+                    return Err(self.end_of_code(illegal_opcode));
+                }
+                Self::flow_to(&mut states, &mut worklist, index + 1, &state)?;
+            }
+
+            match op {
+                Op::Jump { offset }
+                | Op::IfTrue { offset }
+                | Op::IfFalse { offset }
+                | Op::IfEq { offset }
+                | Op::IfNe { offset }
+                | Op::IfGe { offset }
+                | Op::IfGt { offset }
+                | Op::IfLe { offset }
+                | Op::IfLt { offset }
+                | Op::IfNge { offset }
+                | Op::IfNgt { offset }
+                | Op::IfNle { offset }
+                | Op::IfNlt { offset }
+                | Op::IfStrictEq { offset }
+                | Op::IfStrictNe { offset } => {
+                    Self::flow_to(&mut states, &mut worklist, *offset as usize, &state)?;
+                }
+                Op::LookupSwitch {
+                    default_offset,
+                    case_offsets,
+                } => {
+                    Self::flow_to(&mut states, &mut worklist, *default_offset as usize, &state)?;
+                    for case_offset in case_offsets.iter() {
+                        Self::flow_to(&mut states, &mut worklist, *case_offset as usize, &state)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(states)
+    }
+
+    /// Merge a state into the state before the op at `target`, scheduling the
+    /// op to be analyzed again if anything changed.
+    fn flow_to(
+        states: &mut [Option<FrameState>],
+        worklist: &mut Vec<usize>,
+        target: usize,
+        state: &FrameState,
+    ) -> Result<(), VerifyFailure> {
+        let changed = match &mut states[target] {
+            Some(existing) => existing.merge(state)?,
+            slot @ None => {
+                *slot = Some(state.clone());
+                true
+            }
+        };
+
+        if changed && !worklist.contains(&target) {
+            worklist.push(target);
+        }
+
+        Ok(())
+    }
+
+    /// Apply the effects of an op to the abstract state of the frame.
+    fn step(&self, op: &Op, state: &mut FrameState) -> Result<(), VerifyFailure> {
+        match op {
+            Op::PushByte { .. } | Op::PushShort { .. } | Op::PushInt { .. } => {
+                self.push(state, ValueType::Int)?
+            }
+            Op::PushUint { .. } => self.push(state, ValueType::Uint)?,
+            Op::PushDouble { .. } | Op::PushNaN => self.push(state, ValueType::Number)?,
+            Op::PushTrue | Op::PushFalse => self.push(state, ValueType::Boolean)?,
+            Op::PushString { .. } => self.push(state, ValueType::String)?,
+            Op::PushNull
+            | Op::PushUndefined
+            | Op::PushNamespace { .. }
+            | Op::PushConstant { .. }
+            | Op::NewActivation
+            | Op::NewFunction { .. }
+            | Op::GetGlobalScope
+            | Op::GetGlobalSlot { .. }
+            | Op::FindDef { .. } => self.push(state, ValueType::Any)?,
+            Op::NewCatch { index } => {
+                if index.0 as usize >= self.body.exceptions.len() {
+                    return Err(self.cpool_out_of_range(index.0, self.body.exceptions.len()));
+                }
+                self.push(state, ValueType::Any)?;
+            }
+            Op::Pop => {
+                self.pop(state, 1)?;
+            }
+            Op::Dup => {
+                let value = self.pop(state, 1)?[0];
+                self.push(state, value)?;
+                self.push(state, value)?;
+            }
+            Op::Swap => {
+                let values = self.pop(state, 2)?;
+                self.push(state, values[1])?;
+                self.push(state, values[0])?;
+            }
+            Op::GetLocal { index } => {
+                let value = state.locals[self.register(*index)?];
+                self.push(state, value)?;
+            }
+            Op::SetLocal { index } => {
+                let register = self.register(*index)?;
+                let value = self.pop(state, 1)?[0];
+                state.locals[register] = value;
+            }
+            Op::Kill { index } => {
+                let register = self.register(*index)?;
+                state.locals[register] = ValueType::Any;
+            }
+            Op::IncLocal { index } | Op::DecLocal { index } => {
+                let register = self.register(*index)?;
+                state.locals[register] = ValueType::Number;
+            }
+            Op::IncLocalI { index } | Op::DecLocalI { index } => {
+                let register = self.register(*index)?;
+                state.locals[register] = ValueType::Int;
+            }
+            Op::HasNext2 {
+                object_register,
+                index_register,
+            } => {
+                let object_register = self.register(*object_register)?;
+                let index_register = self.register(*index_register)?;
+                state.locals[object_register] = ValueType::Any;
+                state.locals[index_register] = ValueType::Any;
+                self.push(state, ValueType::Boolean)?;
+            }
+            Op::Call { num_args } => {
+                self.pop_push(state, *num_args as usize + 2, ValueType::Any)?
+            }
+            Op::Construct { num_args }
+            | Op::CallMethod { num_args, .. }
+            | Op::CallStatic { num_args, .. } => {
+                self.pop_push(state, *num_args as usize + 1, ValueType::Any)?
+            }
+            Op::CallProperty { index, num_args }
+            | Op::CallPropLex { index, num_args }
+            | Op::CallSuper { index, num_args }
+            | Op::ConstructProp { index, num_args } => {
+                let runtime_parts = self.runtime_parts(*index)?;
+                self.pop_push(
+                    state,
+                    *num_args as usize + 1 + runtime_parts,
+                    ValueType::Any,
+                )?
+            }
+            Op::CallPropVoid { index, num_args } | Op::CallSuperVoid { index, num_args } => {
+                let runtime_parts = self.runtime_parts(*index)?;
+                self.pop(state, *num_args as usize + 1 + runtime_parts)?;
+            }
+            Op::ConstructSuper { num_args } => {
+                self.pop(state, *num_args as usize + 1)?;
+            }
+            Op::ApplyType { num_types } => {
+                self.pop_push(state, *num_types as usize + 1, ValueType::Any)?
+            }
+            Op::NewArray { num_args } => {
+                self.pop_push(state, *num_args as usize, ValueType::Any)?
+            }
+            Op::NewObject { num_args } => {
+                self.pop_push(state, *num_args as usize * 2, ValueType::Any)?
+            }
+            Op::NewClass { index } => {
+                if index.0 as usize >= self.abc.classes.len() {
+                    return Err(self.cpool_out_of_range(index.0, self.abc.classes.len()));
+                }
+                self.pop_push(state, 1, ValueType::Any)?
+            }
+            Op::GetProperty { index }
+            | Op::GetSuper { index }
+            | Op::DeleteProperty { index }
+            | Op::GetDescendants { index } => {
+                let runtime_parts = self.runtime_parts(*index)?;
+                self.pop_push(state, 1 + runtime_parts, ValueType::Any)?
+            }
+            Op::SetProperty { index } | Op::InitProperty { index } | Op::SetSuper { index } => {
+                let runtime_parts = self.runtime_parts(*index)?;
+                self.pop(state, 2 + runtime_parts)?;
+            }
+            Op::FindProperty { index } | Op::FindPropStrict { index } | Op::GetLex { index } => {
+                let runtime_parts = self.runtime_parts(*index)?;
+                self.pop_push(state, runtime_parts, ValueType::Any)?
+            }
+            Op::GetSlot { .. } => self.pop_push(state, 1, ValueType::Any)?,
+            Op::SetSlot { .. } => {
+                self.pop(state, 2)?;
+            }
+            Op::SetGlobalSlot { .. } => {
+                self.pop(state, 1)?;
+            }
+            Op::PushScope | Op::PushWith => {
+                let value = match op {
+                    Op::PushScope => self.pop(state, 1)?[0],
+                    _ => self.pop(state, 1).map(|_| ValueType::Any)?,
+                };
+                if state.scope.len() + 1 > self.max_scope_depth() {
+                    return Err(VerifyFailure::new("Scope stack overflow occurred.", 1017));
+                }
+                state.scope.push(value);
+            }
+            Op::PopScope => {
+                if state.scope.pop().is_none() {
+                    return Err(VerifyFailure::new("Scope stack underflow occurred.", 1018));
+                }
+            }
+            Op::GetScopeObject { index } => {
+                let value = match state.scope.get(*index as usize) {
+                    Some(value) => *value,
+                    None => {
+                        return Err(VerifyFailure::new(
+                            format!("Getscopeobject {index} is out of bounds."),
+                            1019,
+                        ))
+                    }
+                };
+                self.push(state, value)?;
+            }
+            Op::GetOuterScope { .. } => self.push(state, ValueType::Any)?,
+            Op::Coerce { index } => {
+                self.multiname(*index)?;
+                let value_type = match self.primitive_type_name(*index) {
+                    Some(ValueType::String) | None => ValueType::Any,
+                    Some(value_type) => value_type,
+                };
+                self.pop_push(state, 1, value_type)?
+            }
+            Op::AsType { type_name: index } | Op::IsType { index } => {
+                self.multiname(*index)?;
+                let value_type = if matches!(op, Op::IsType { .. }) {
+                    ValueType::Boolean
+                } else {
+                    ValueType::Any
+                };
+                self.pop_push(state, 1, value_type)?
+            }
+            Op::CoerceA | Op::CoerceO | Op::CoerceS | Op::ConvertO | Op::CheckFilter => {
+                self.pop_push(state, 1, ValueType::Any)?
+            }
+            Op::EscXAttr | Op::EscXElem | Op::ConvertS | Op::TypeOf => {
+                self.pop_push(state, 1, ValueType::String)?
+            }
+            Op::CoerceB | Op::ConvertB | Op::Not => self.pop_push(state, 1, ValueType::Boolean)?,
+            Op::CoerceD
+            | Op::ConvertD
+            | Op::Negate
+            | Op::Increment
+            | Op::Decrement
+            | Op::Lf32
+            | Op::Lf64 => self.pop_push(state, 1, ValueType::Number)?,
+            Op::CoerceI
+            | Op::ConvertI
+            | Op::NegateI
+            | Op::IncrementI
+            | Op::DecrementI
+            | Op::BitNot
+            | Op::Sxi1
+            | Op::Sxi8
+            | Op::Sxi16
+            | Op::Li8
+            | Op::Li16
+            | Op::Li32 => self.pop_push(state, 1, ValueType::Int)?,
+            Op::CoerceU | Op::ConvertU => self.pop_push(state, 1, ValueType::Uint)?,
+            Op::Add | Op::AsTypeLate | Op::NextName | Op::NextValue | Op::HasNext => {
+                self.pop_push(state, 2, ValueType::Any)?
+            }
+            Op::Subtract | Op::Multiply | Op::Divide | Op::Modulo => {
+                self.pop_push(state, 2, ValueType::Number)?
+            }
+            Op::AddI
+            | Op::SubtractI
+            | Op::MultiplyI
+            | Op::BitAnd
+            | Op::BitOr
+            | Op::BitXor
+            | Op::LShift
+            | Op::RShift => self.pop_push(state, 2, ValueType::Int)?,
+            Op::URShift => self.pop_push(state, 2, ValueType::Uint)?,
+            Op::Equals
+            | Op::StrictEquals
+            | Op::LessThan
+            | Op::LessEquals
+            | Op::GreaterThan
+            | Op::GreaterEquals
+            | Op::In
+            | Op::InstanceOf
+            | Op::IsTypeLate => self.pop_push(state, 2, ValueType::Boolean)?,
+            Op::Si8 | Op::Si16 | Op::Si32 | Op::Sf32 | Op::Sf64 => {
+                self.pop(state, 2)?;
+            }
+            Op::IfTrue { .. } | Op::IfFalse { .. } | Op::LookupSwitch { .. } => {
+                self.pop(state, 1)?;
+            }
+            Op::IfEq { .. }
+            | Op::IfNe { .. }
+            | Op::IfGe { .. }
+            | Op::IfGt { .. }
+            | Op::IfLe { .. }
+            | Op::IfLt { .. }
+            | Op::IfNge { .. }
+            | Op::IfNgt { .. }
+            | Op::IfNle { .. }
+            | Op::IfNlt { .. }
+            | Op::IfStrictEq { .. }
+            | Op::IfStrictNe { .. } => {
+                self.pop(state, 2)?;
+            }
+            Op::ReturnValue | Op::Throw | Op::DxnsLate => {
+                self.pop(state, 1)?;
+            }
+            Op::ReturnVoid
+            | Op::Debug { .. }
+            | Op::Jump { .. }
+            | Op::Nop
+            | Op::Label
+            | Op::Bkpt
+            | Op::BkptLine { .. }
+            | Op::DebugFile { .. }
+            | Op::DebugLine { .. }
+            | Op::Dxns { .. }
+            | Op::Timestamp => {}
+        }
+
+        Ok(())
+    }
+
+    /// Remove coercions which the analysis proved to be no-ops, and replace
+    /// coercions to primitive classes with their dedicated opcodes.
+    ///
+    /// Returns the name lookups that can be resolved against the outer scope
+    /// once the receiver is known.
+    fn optimize(&self, code: &mut [Op], states: &[Option<FrameState>]) -> Vec<ScopeLookup> {
+        let mut lookups = Vec::new();
+        for (op_index, (op, state)) in code.iter_mut().zip(states.iter()).enumerate() {
+            let state = match state {
+                Some(state) => state,
+                None => continue,
+            };
+
+            if let Op::GetLex { index } | Op::FindPropStrict { index } = op {
+                let only_this = state.scope.iter().all(|value| *value == ValueType::This);
+                if only_this && matches!(self.runtime_parts(*index), Ok(0)) {
+                    lookups.push(ScopeLookup {
+                        op_index,
+                        searches_this: !state.scope.is_empty(),
+                    });
+                }
+                continue;
+            }
+
+            if let Op::Coerce { index } = op {
+                *op = match self.primitive_type_name(*index) {
+                    Some(ValueType::Int) => Op::CoerceI,
+                    Some(ValueType::Uint) => Op::CoerceU,
+                    Some(ValueType::Number) => Op::CoerceD,
+                    Some(ValueType::Boolean) => Op::CoerceB,
+                    Some(ValueType::String) => Op::CoerceS,
+                    _ => continue,
+                };
+            }
+
+            let top = state.stack.last().copied().unwrap_or(ValueType::Any);
+            let is_no_op = match op {
+                Op::CoerceA => true,
+                Op::CoerceI | Op::ConvertI => top == ValueType::Int,
+                Op::CoerceU | Op::ConvertU => top == ValueType::Uint,
+                Op::CoerceD | Op::ConvertD => top == ValueType::Number,
+                Op::CoerceB | Op::ConvertB => top == ValueType::Boolean,
+                Op::CoerceS | Op::ConvertS => top == ValueType::String,
+                _ => false,
+            };
+
+            if is_no_op {
+                *op = Op::Nop;
+            }
+        }
+
+        lookups
+    }
+
+    fn max_scope_depth(&self) -> usize {
+        self.body
+            .max_scope_depth
+            .saturating_sub(self.body.init_scope_depth) as usize
+    }
+
+    fn push(&self, state: &mut FrameState, value: ValueType) -> Result<(), VerifyFailure> {
+        if state.stack.len() >= self.body.max_stack as usize {
+            return Err(VerifyFailure::new("Stack overflow occurred.", 1023));
+        }
+
+        state.stack.push(value);
+        Ok(())
+    }
+
+    fn pop(&self, state: &mut FrameState, count: usize) -> Result<Vec<ValueType>, VerifyFailure> {
+        if count > state.stack.len() {
+            return Err(VerifyFailure::new("Stack underflow occurred.", 1024));
+        }
+
+        Ok(state.stack.split_off(state.stack.len() - count))
+    }
+
+    fn pop_push(
+        &self,
+        state: &mut FrameState,
+        count: usize,
+        value: ValueType,
+    ) -> Result<(), VerifyFailure> {
+        self.pop(state, count)?;
+        self.push(state, value)
+    }
+
+    fn register(&self, index: u32) -> Result<usize, VerifyFailure> {
+        if index >= self.body.num_locals {
+            return Err(VerifyFailure::new(
+                format!("An invalid register {index} was accessed."),
+                1025,
+            ));
+        }
+
+        Ok(index as usize)
+    }
+
+    fn cpool_out_of_range(&self, index: u32, len: usize) -> VerifyFailure {
+        VerifyFailure::new(format!("Cpool index {index} is out of range {len}."), 1032)
+    }
+
+    fn multiname(&self, index: Index<AbcMultiname>) -> Result<&'a AbcMultiname, VerifyFailure> {
+        let multinames = &self.abc.constant_pool.multinames;
+        index
+            .0
+            .checked_sub(1)
+            .and_then(|index| multinames.get(index as usize))
+            .ok_or_else(|| self.cpool_out_of_range(index.0, multinames.len() + 1))
+    }
+
+    /// The number of stack values a multiname reads at runtime.
+    fn runtime_parts(&self, index: Index<AbcMultiname>) -> Result<usize, VerifyFailure> {
+        Ok(match self.multiname(index)? {
+            AbcMultiname::RTQName { .. } | AbcMultiname::RTQNameA { .. } => 1,
+            AbcMultiname::MultinameL { .. } | AbcMultiname::MultinameLA { .. } => 1,
+            AbcMultiname::RTQNameL | AbcMultiname::RTQNameLA => 2,
+            _ => 0,
+        })
+    }
+
+    /// If a multiname refers to `int`, `uint`, `Number`, `Boolean` or
+    /// `String`, return the matching value type.
+    fn primitive_type_name(&self, index: Index<AbcMultiname>) -> Option<ValueType> {
+        let pool = &self.abc.constant_pool;
+        let (namespace, name) = match self.multiname(index).ok()? {
+            AbcMultiname::QName { namespace, name } => (namespace, name),
+            _ => return None,
+        };
+
+        match pool.namespaces.get(namespace.0.checked_sub(1)? as usize)? {
+            AbcNamespace::Package(package) if package.0 == 0 => {}
+            AbcNamespace::Package(package) => {
+                if !pool.strings.get(package.0 as usize - 1)?.is_empty() {
+                    return None;
+                }
+            }
+            _ => return None,
+        }
+
+        match pool.strings.get(name.0.checked_sub(1)? as usize)?.as_str() {
+            "int" => Some(ValueType::Int),
+            "uint" => Some(ValueType::Uint),
+            "Number" => Some(ValueType::Number),
+            "Boolean" => Some(ValueType::Boolean),
+            "String" => Some(ValueType::String),
+            _ => None,
+        }
+    }
+}
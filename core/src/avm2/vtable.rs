@@ -97,6 +97,10 @@ impl<'gc> VTable<'gc> {
         VTable(GcCell::allocate(mc, self.0.read().clone()))
     }
 
+    pub fn ptr_eq(a: Self, b: Self) -> bool {
+        GcCell::ptr_eq(a.0, b.0)
+    }
+
     pub fn get_trait(self, name: &Multiname<'gc>) -> Option<Property> {
         self.0
             .read()
@@ -1,5 +1,12 @@
 pub trait LogBackend {
     fn avm_trace(&self, message: &str);
+
+    /// Called when an AVM2 error escapes a script without being caught,
+    /// and no `uncaughtError` listener prevented its default behavior.
+    ///
+    /// `stack_trace` holds one `at` line per active call when the error was
+    /// thrown, in the same format as `Error.getStackTrace`.
+    fn avm_uncaught_error(&self, _message: &str, _stack_trace: &str) {}
 }
 
 /// Logging backend that just reroutes traces to the log crate
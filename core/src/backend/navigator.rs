@@ -1,11 +1,14 @@
 //! Browser-related platform functions
 
 use crate::loader::Error;
+use crate::socket::{ConnectionState, SocketAction, SocketHandle};
 use crate::string::WStr;
 use indexmap::IndexMap;
 use std::future::Future;
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
+use std::sync::mpsc::{Receiver, Sender};
+use std::time::Duration;
 use swf::avm1::types::SendVarsMethod;
 use url::Url;
 
@@ -158,6 +161,28 @@ pub trait NavigatorBackend {
     /// Changing http -> https for example. This function may alter any part of the
     /// URL (generally only if configured to do so by the user).
     fn pre_process_url(&self, url: Url) -> Url;
+
+    /// Open a TCP connection to `host` on `port`, as used by `XMLSocket` and
+    /// `flash.net.Socket`.
+    ///
+    /// The backend reports back by sending `SocketAction`s for `handle` to
+    /// `sender`: first whether the connection could be made within `timeout`,
+    /// then any data received from the peer, and finally a `Close` if the peer
+    /// closes the connection.
+    ///
+    /// Everything received through `receiver` must be written to the
+    /// connection in order, including anything sent before it has been made.
+    /// Once `receiver` is disconnected, the backend should close the
+    /// connection without reporting anything further.
+    fn connect_socket(
+        &mut self,
+        host: String,
+        port: u16,
+        timeout: Duration,
+        handle: SocketHandle,
+        receiver: Receiver<Vec<u8>>,
+        sender: Sender<SocketAction>,
+    );
 }
 
 #[cfg(not(target_family = "wasm"))]
@@ -304,4 +329,16 @@ impl NavigatorBackend for NullNavigatorBackend {
     fn pre_process_url(&self, url: Url) -> Url {
         url
     }
+
+    fn connect_socket(
+        &mut self,
+        _host: String,
+        _port: u16,
+        _timeout: Duration,
+        handle: SocketHandle,
+        _receiver: Receiver<Vec<u8>>,
+        sender: Sender<SocketAction>,
+    ) {
+        let _ = sender.send(SocketAction::Connect(handle, ConnectionState::Failed));
+    }
 }
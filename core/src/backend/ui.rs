@@ -15,6 +15,10 @@ pub trait UiBackend {
     /// Sets the clipboard to the given content.
     fn set_clipboard_content(&mut self, content: String);
 
+    /// Gets the text content of the clipboard, or an empty string if it has
+    /// none.
+    fn clipboard_content(&mut self) -> String;
+
     fn set_fullscreen(&mut self, is_full: bool) -> Result<(), FullscreenError>;
 
     /// Displays a warning about unsupported content in Ruffle.
@@ -139,6 +143,10 @@ impl UiBackend for NullUiBackend {
 
     fn set_clipboard_content(&mut self, _content: String) {}
 
+    fn clipboard_content(&mut self) -> String {
+        String::new()
+    }
+
     fn set_fullscreen(&mut self, _is_full: bool) -> Result<(), FullscreenError> {
         Ok(())
     }
@@ -8,11 +8,14 @@ use bitflags::bitflags;
 use gc_arena::{Collect, GcCell};
 use ruffle_render::backend::RenderBackend;
 use ruffle_render::bitmap::{Bitmap, BitmapFormat, BitmapHandle};
+use ruffle_render::bounding_box::BoundingBox;
 use ruffle_render::color_transform::ColorTransform;
 use ruffle_render::commands::{CommandHandler, CommandList};
+use ruffle_render::filters::cpu::FilterBuffer;
+use ruffle_render::filters::Filter;
 use ruffle_render::transform::Transform;
 use std::ops::Range;
-use swf::BlendMode;
+use swf::{BlendMode, Twips};
 
 /// An implementation of the Lehmer/Park-Miller random number generator
 /// Uses the fixed parameters m = 2,147,483,647 and a = 16,807
@@ -162,6 +165,11 @@ pub struct BitmapData<'gc> {
     /// The pixels in the bitmap, stored as a array of pre-multiplied ARGB colour values
     pixels: Vec<Color>,
     dirty: bool,
+
+    /// Incremented whenever the pixels shown by the bitmap handle change, so that cached
+    /// bitmaps containing this `BitmapData` can tell when they are out of date.
+    version: u64,
+
     width: u32,
     height: u32,
     transparency: bool,
@@ -224,6 +232,7 @@ impl<'gc> BitmapData<'gc> {
         self.bitmap_handle = None;
         // There's no longer a handle to update
         self.dirty = false;
+        self.version += 1;
         self.disposed = true;
     }
 
@@ -261,6 +270,10 @@ impl<'gc> BitmapData<'gc> {
         self.dirty = dirty;
     }
 
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
     pub fn pixels(&self) -> &[Color] {
         &self.pixels
     }
@@ -905,20 +918,141 @@ impl<'gc> BitmapData<'gc> {
         }
     }
 
+    /// Applies `filter` to a rectangle of `source_bitmap`, replacing the pixels of this bitmap
+    /// at `dest_point` with the result.
+    ///
+    /// Pixels outside of the source rectangle are treated as transparent. The map of a
+    /// displacement map filter is read from `map_bitmap`, rather than from the filter's texture.
+    pub fn apply_filter(
+        &mut self,
+        source_bitmap: &Self,
+        src_rect: (i32, i32, i32, i32),
+        dest_point: (i32, i32),
+        filter: &Filter,
+        map_bitmap: Option<&Self>,
+    ) {
+        // Only the part of the source rectangle that lies inside the source bitmap is filtered,
+        // and the destination moves along with any clipped edge.
+        let (src_x, src_y, src_width, src_height) = src_rect;
+        let min_x = src_x.max(0);
+        let min_y = src_y.max(0);
+        let max_x =
+            (i64::from(src_x) + i64::from(src_width)).min(i64::from(source_bitmap.width())) as i32;
+        let max_y = (i64::from(src_y) + i64::from(src_height))
+            .min(i64::from(source_bitmap.height())) as i32;
+        if max_x <= min_x || max_y <= min_y {
+            return;
+        }
+        let (src_width, src_height) = (max_x - min_x, max_y - min_y);
+        let dest_point = (
+            dest_point.0.saturating_add(min_x - src_x),
+            dest_point.1.saturating_add(min_y - src_y),
+        );
+
+        let source = source_bitmap.to_filter_buffer((min_x, min_y, src_width, src_height));
+        let map = map_bitmap
+            .map(|map| map.to_filter_buffer((0, 0, map.width() as i32, map.height() as i32)));
+        let result = source.apply(filter, map.as_ref());
+
+        let (dest_min_x, dest_min_y) = dest_point;
+        for y in 0..src_height {
+            for x in 0..src_width {
+                let dest_x = dest_min_x.saturating_add(x);
+                let dest_y = dest_min_y.saturating_add(y);
+                if !self.is_point_in_bounds(dest_x, dest_y) {
+                    continue;
+                }
+                let [r, g, b, a] = result.get(x, y);
+                let to_u8 = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+                let color = if self.transparency {
+                    Color::argb(to_u8(a), to_u8(r), to_u8(g), to_u8(b))
+                } else {
+                    Color::argb(255, to_u8(r), to_u8(g), to_u8(b))
+                };
+                self.set_pixel32_raw(dest_x as u32, dest_y as u32, color);
+            }
+        }
+    }
+
+    /// Copies a rectangle of this bitmap into a buffer for filtering.
+    /// Pixels outside of the bitmap are transparent.
+    fn to_filter_buffer(
+        &self,
+        (min_x, min_y, width, height): (i32, i32, i32, i32),
+    ) -> FilterBuffer {
+        let mut buffer = FilterBuffer::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                if !self.is_point_in_bounds(min_x + x, min_y + y) {
+                    continue;
+                }
+                let color = self
+                    .get_pixel_raw((min_x + x) as u32, (min_y + y) as u32)
+                    .unwrap();
+                let a = if self.transparency {
+                    color.alpha()
+                } else {
+                    255
+                };
+                buffer.set(
+                    x,
+                    y,
+                    [
+                        f32::from(color.red()) / 255.0,
+                        f32::from(color.green()) / 255.0,
+                        f32::from(color.blue()) / 255.0,
+                        f32::from(a) / 255.0,
+                    ],
+                );
+            }
+        }
+        buffer
+    }
+
+    /// Returns the area affected by applying `filter` to the given rectangle.
+    pub fn generate_filter_rect(
+        src_rect: (i32, i32, i32, i32),
+        filter: &Filter,
+    ) -> (i32, i32, i32, i32) {
+        // The filter grows every rectangle by the same margins, so they are measured on an empty
+        // rectangle to keep huge source rectangles from overflowing.
+        let (x, y, width, height) = src_rect;
+        let margins = filter.calculate_dest_rect(&BoundingBox {
+            x_min: Twips::ZERO,
+            y_min: Twips::ZERO,
+            x_max: Twips::ZERO,
+            y_max: Twips::ZERO,
+            valid: true,
+        });
+        let to_i32 = |value: f64| value.clamp(f64::from(i32::MIN), f64::from(i32::MAX)) as i32;
+        let x_min = (f64::from(x) + margins.x_min.to_pixels()).floor();
+        let y_min = (f64::from(y) + margins.y_min.to_pixels()).floor();
+        let x_max = (f64::from(x) + f64::from(width) + margins.x_max.to_pixels()).ceil();
+        let y_max = (f64::from(y) + f64::from(height) + margins.y_max.to_pixels()).ceil();
+        (
+            to_i32(x_min),
+            to_i32(y_min),
+            to_i32(x_max - x_min),
+            to_i32(y_max - y_min),
+        )
+    }
+
     // Updates the data stored with our `BitmapHandle` if this `BitmapData`
     // is dirty
     pub fn update_dirty_texture(&mut self, context: &mut RenderContext) {
-        let handle = self.bitmap_handle(context.renderer).unwrap();
+        self.upload_dirty_pixels(context.renderer);
+    }
+
+    fn upload_dirty_pixels(&mut self, renderer: &mut dyn RenderBackend) {
+        let handle = self.bitmap_handle(renderer).unwrap();
         if self.dirty() {
-            if let Err(e) = context.renderer.update_texture(
-                &handle,
-                self.width(),
-                self.height(),
-                self.pixels_rgba(),
-            ) {
+            if let Err(e) =
+                renderer.update_texture(&handle, self.width(), self.height(), self.pixels_rgba())
+            {
                 log::error!("Failed to update dirty bitmap {:?}: {:?}", handle, e);
             }
             self.set_dirty(false);
+            self.version += 1;
         }
     }
 
@@ -964,6 +1098,7 @@ impl<'gc> BitmapData<'gc> {
             Some(Self {
                 pixels,
                 dirty: false,
+                version: 0,
                 width: bitmap.width,
                 height: bitmap.height,
                 transparency: true,
@@ -1013,10 +1148,12 @@ impl<'gc> BitmapData<'gc> {
             stage: context.stage,
             clip_depth_stack: vec![],
             allow_mask: true,
+            bitmap_cache_dependencies: None,
         };
 
-        // Make the screen opacity match the opacity of this bitmap
-        render_context.commands.push_blend_mode(blend_mode);
+        // The blend mode is applied to the drawn content as a whole,
+        // so it's recorded separately and rendered as a single group.
+        let unblended_commands = std::mem::take(render_context.commands);
         match &mut source {
             IBitmapDrawable::BitmapData(data) => {
                 // if try_write fails,
@@ -1037,7 +1174,10 @@ impl<'gc> BitmapData<'gc> {
                 object.render_self(&mut render_context);
             }
         }
-        render_context.commands.pop_blend_mode();
+        let blended_commands = std::mem::replace(render_context.commands, unblended_commands);
+        render_context
+            .commands
+            .render_blend(&blended_commands, blend_mode);
 
         self.update_dirty_texture(&mut render_context);
 
@@ -1056,6 +1196,24 @@ impl<'gc> BitmapData<'gc> {
             Err(e) => panic!("BitmapData.draw failed: {e:?}"),
         }
     }
+
+    /// Draws `bitmap` on top of this `BitmapData` at its original size.
+    /// This is used by `Context3D.drawToBitmapData` to copy the back buffer.
+    pub fn draw_bitmap_handle(&mut self, bitmap: &BitmapHandle, renderer: &mut dyn RenderBackend) {
+        self.upload_dirty_pixels(renderer);
+        let handle = self.bitmap_handle(renderer).unwrap();
+
+        let mut commands = CommandList::new();
+        commands.render_bitmap(bitmap, &Transform::default(), false);
+
+        match renderer.render_offscreen(handle, self.width(), self.height(), commands) {
+            Ok(image) => copy_pixels_to_bitmapdata(self, image.data()),
+            Err(ruffle_render::error::Error::Unimplemented) => {
+                log::warn!("Context3D.drawToBitmapData: Not yet implemented")
+            }
+            Err(e) => log::error!("Context3D.drawToBitmapData failed: {e:?}"),
+        }
+    }
 }
 
 pub enum IBitmapDrawable<'gc> {
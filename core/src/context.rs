@@ -1,7 +1,6 @@
 //! Contexts and helper types passed between functions.
 
 use crate::avm1::Avm1;
-use crate::avm1::SystemProperties;
 use crate::avm1::{Object as Avm1Object, Value as Avm1Value};
 use crate::avm2::{Avm2, Object as Avm2Object, SoundChannelObject, Value as Avm2Value};
 use crate::backend::{
@@ -12,7 +11,9 @@ use crate::backend::{
     ui::{InputManager, UiBackend},
 };
 use crate::context_menu::ContextMenuState;
-use crate::display_object::{EditText, InteractiveObject, MovieClip, SoundTransform, Stage};
+use crate::display_object::{
+    BitmapCacheDependency, EditText, InteractiveObject, MovieClip, SoundTransform, Stage,
+};
 use crate::external::ExternalInterface;
 use crate::focus_tracker::FocusTracker;
 use crate::frame_lifecycle::FramePhase;
@@ -20,6 +21,10 @@ use crate::library::Library;
 use crate::loader::LoadManager;
 use crate::player::Player;
 use crate::prelude::*;
+use crate::profiler::Profiler;
+use crate::socket::Sockets;
+use crate::streams::StreamManager;
+use crate::system_properties::SystemProperties;
 use crate::tag_utils::{SwfMovie, SwfSlice};
 use crate::timer::Timers;
 use core::fmt;
@@ -139,6 +144,12 @@ pub struct UpdateContext<'a, 'gc, 'gc_context> {
     /// Timed callbacks created with `setInterval`/`setTimeout`.
     pub timers: &'a mut Timers<'gc>,
 
+    /// Connections opened by `XMLSocket` and `flash.net.Socket`.
+    pub sockets: &'a mut Sockets<'gc>,
+
+    /// Media streams played by `NetStream`.
+    pub stream_manager: &'a mut StreamManager<'gc>,
+
     pub current_context_menu: &'a mut Option<ContextMenuState<'gc>>,
 
     /// The AVM1 global state.
@@ -179,6 +190,9 @@ pub struct UpdateContext<'a, 'gc, 'gc_context> {
     ///
     /// If we are not doing frame processing, then this is `FramePhase::Enter`.
     pub frame_phase: &'a mut FramePhase,
+
+    /// The script profiler, if script execution is being profiled.
+    pub profiler: &'a mut Option<Profiler>,
 }
 
 /// Convenience methods for controlling audio.
@@ -327,6 +341,8 @@ impl<'a, 'gc, 'gc_context> UpdateContext<'a, 'gc, 'gc_context> {
             avm2_shared_objects: self.avm2_shared_objects,
             unbound_text_fields: self.unbound_text_fields,
             timers: self.timers,
+            sockets: self.sockets,
+            stream_manager: self.stream_manager,
             current_context_menu: self.current_context_menu,
             avm1: self.avm1,
             avm2: self.avm2,
@@ -340,6 +356,7 @@ impl<'a, 'gc, 'gc_context> UpdateContext<'a, 'gc, 'gc_context> {
             frame_rate: self.frame_rate,
             actions_since_timeout_check: self.actions_since_timeout_check,
             frame_phase: self.frame_phase,
+            profiler: self.profiler,
         }
     }
 
@@ -453,6 +470,10 @@ pub struct RenderContext<'a, 'gc, 'gc_context> {
     /// Whether to allow pushing a new mask. A masker-inside-a-masker does not work in Flash, instead
     /// causing the inner mask to be included as part of the outer mask. Maskee-inside-a-maskee works as one expects.
     pub allow_mask: bool,
+
+    /// The contents that can change without invalidating the cached bitmap currently being
+    /// rendered, if any. See `BitmapCacheDependency`.
+    pub bitmap_cache_dependencies: Option<Vec<BitmapCacheDependency<'gc>>>,
 }
 
 /// The type of action being run.
@@ -1,21 +1,25 @@
 use crate::avm1::{Object as Avm1Object, TObject as Avm1TObject, Value as Avm1Value};
 use crate::avm2::{
-    Activation as Avm2Activation, ArrayStorage as Avm2ArrayStorage, Avm2, Error as Avm2Error,
-    EventObject as Avm2EventObject, Multiname as Avm2Multiname, Object as Avm2Object,
-    TObject as Avm2TObject, Value as Avm2Value,
+    Activation as Avm2Activation, Avm2, Error as Avm2Error, EventObject as Avm2EventObject,
+    Multiname as Avm2Multiname, Object as Avm2Object, TObject as Avm2TObject, Value as Avm2Value,
 };
+use crate::bitmap::bitmap_data::BitmapData;
 use crate::context::{RenderContext, UpdateContext};
 use crate::drawing::Drawing;
 use crate::player::NEWEST_PLAYER_VERSION;
 use crate::prelude::*;
+use crate::streams::NetStream;
 use crate::string::{AvmString, WString};
 use crate::tag_utils::SwfMovie;
 use crate::types::{Degrees, Percent};
 use crate::vminterface::Instantiator;
 use bitflags::bitflags;
-use gc_arena::{Collect, MutationContext};
+use gc_arena::{Collect, GcCell, MutationContext};
 use ruffle_macros::enum_trait_object;
-use ruffle_render::transform::Transform;
+use ruffle_render::bitmap::{BitmapFormat, BitmapHandle};
+use ruffle_render::commands::CommandList;
+use ruffle_render::filters::Filter;
+use ruffle_render::transform::{Transform, TransformStack};
 use std::cell::{Ref, RefMut};
 use std::fmt::Debug;
 use std::sync::Arc;
@@ -40,7 +44,7 @@ pub use crate::display_object::container::{DisplayObjectContainer, TDisplayObjec
 pub use avm1_button::{Avm1Button, ButtonState, ButtonTracking};
 pub use avm2_button::Avm2Button;
 pub use bitmap::Bitmap;
-pub use edit_text::{AutoSizeMode, EditText, TextSelection};
+pub use edit_text::{AutoSizeMode, EditText, EditTextStyleSheet, TextSelection};
 pub use graphic::Graphic;
 pub use interactive::{InteractiveObject, TInteractiveObject};
 pub use loader_display::LoaderDisplay;
@@ -51,6 +55,118 @@ pub use stage::{Stage, StageAlign, StageDisplayState, StageQuality, StageScaleMo
 pub use text::Text;
 pub use video::Video;
 
+/// The bitmap that a display object with `cacheAsBitmap` was last rendered into.
+#[derive(Clone, Debug, Collect)]
+#[collect(no_drop)]
+pub struct BitmapCache<'gc> {
+    /// The bitmap containing the rendered contents.
+    #[collect(require_static)]
+    handle: BitmapHandle,
+    width: u32,
+    height: u32,
+
+    /// The position of the bitmap relative to the origin of the object, in pixels.
+    x_min: f64,
+    y_min: f64,
+
+    /// The transform that the contents were rendered with, without its translation.
+    /// If this changes, the bitmap needs to be rendered again.
+    #[collect(require_static)]
+    matrix: Matrix,
+
+    /// The background color that the bitmap was filled with, from `opaqueBackground`.
+    #[collect(require_static)]
+    background: Option<Color>,
+
+    /// Contents of the bitmap that can change without the display objects knowing about it.
+    dependencies: Vec<BitmapCacheDependency<'gc>>,
+
+    /// Whether this object or its children have changed since the bitmap was rendered,
+    /// so that the bitmap must be rendered again.
+    dirty: bool,
+}
+
+/// Something drawn into a cached bitmap that can change without invalidating it.
+///
+/// These are collected in `RenderContext::bitmap_cache_dependencies` while a cached bitmap is
+/// rendered, and checked before the bitmap is reused.
+#[derive(Clone, Debug, Collect)]
+#[collect(no_drop)]
+pub enum BitmapCacheDependency<'gc> {
+    /// The pixels of a `BitmapData`, with the version that was drawn.
+    BitmapData(GcCell<'gc, BitmapData<'gc>>, u64),
+
+    /// The current frame of a `NetStream`, with the number of frames it had decoded.
+    NetStream(NetStream<'gc>, u64),
+
+    /// Contents that change over time by themselves, such as a blinking caret.
+    Animated,
+}
+
+impl<'gc> BitmapCacheDependency<'gc> {
+    /// Whether this has changed since it was drawn into the cached bitmap.
+    fn is_modified(&self) -> bool {
+        match self {
+            Self::BitmapData(bitmap_data, version) => bitmap_data
+                .try_read()
+                .map_or(true, |bd| bd.dirty() || bd.version() != *version),
+            Self::NetStream(stream, frames_decoded) => stream.frames_decoded() != *frames_decoded,
+            Self::Animated => true,
+        }
+    }
+}
+
+impl<'gc> BitmapCache<'gc> {
+    /// The largest width or height of a cached bitmap.
+    const MAX_SIZE: u32 = 8191;
+
+    /// The largest number of pixels in a cached bitmap.
+    const MAX_PIXELS: u32 = 16_777_215;
+
+    /// Renders `commands` into a bitmap of the given size, which is first filled with `background`.
+    /// The bitmap of the previous cache is reused if it has the same size.
+    fn render(
+        context: &mut RenderContext,
+        handle: Option<BitmapHandle>,
+        width: u32,
+        height: u32,
+        background: Option<Color>,
+        commands: CommandList,
+    ) -> Option<BitmapHandle> {
+        let pixels = match &background {
+            Some(color) => [color.r, color.g, color.b, 255].repeat((width * height) as usize),
+            None => vec![0; (width * height * 4) as usize],
+        };
+        let handle = match handle {
+            Some(handle) => context
+                .renderer
+                .update_texture(&handle, width, height, pixels)
+                .map(|_| handle),
+            None => context
+                .renderer
+                .register_bitmap(ruffle_render::bitmap::Bitmap::new(
+                    width,
+                    height,
+                    BitmapFormat::Rgba,
+                    pixels,
+                )),
+        };
+        let result = handle.and_then(|handle| {
+            context
+                .renderer
+                .render_offscreen(handle.clone(), width, height, commands)
+                .map(|_| handle)
+        });
+        match result {
+            Ok(handle) => Some(handle),
+            Err(e) => {
+                log::warn!("Failed to render cached bitmap: {}", e);
+                None
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug, Collect)]
 #[collect(no_drop)]
 pub struct DisplayObjectBase<'gc> {
@@ -60,7 +176,8 @@ pub struct DisplayObjectBase<'gc> {
     #[collect(require_static)]
     transform: Transform,
     name: AvmString<'gc>,
-    filters: Avm2ArrayStorage<'gc>,
+    #[collect(require_static)]
+    filters: Vec<Filter>,
     clip_depth: Depth,
 
     // Cached transform properties `_xscale`, `_yscale`, `_rotation`.
@@ -114,6 +231,15 @@ pub struct DisplayObjectBase<'gc> {
     /// changes immediately (without needing wait for a render)
     #[collect(require_static)]
     next_scroll_rect: Rectangle<Twips>,
+
+    /// The 9-slice scaling grid, in local coordinates.
+    /// When this object is scaled, its contents are split along this grid so that
+    /// the corners keep their original size. Set by `DefineScalingGrid` or `scale9Grid`.
+    #[collect(require_static)]
+    scaling_grid: Option<Rectangle<Twips>>,
+
+    /// The bitmap that this object was last rendered into, if it is cached as a bitmap.
+    bitmap_cache: Option<BitmapCache<'gc>>,
 }
 
 impl<'gc> Default for DisplayObjectBase<'gc> {
@@ -124,7 +250,7 @@ impl<'gc> Default for DisplayObjectBase<'gc> {
             depth: Default::default(),
             transform: Default::default(),
             name: Default::default(),
-            filters: Avm2ArrayStorage::new(0),
+            filters: Vec::new(),
             clip_depth: Default::default(),
             rotation: Degrees::from_radians(0.0),
             scale_x: Percent::from_unit(1.0),
@@ -139,6 +265,8 @@ impl<'gc> Default for DisplayObjectBase<'gc> {
             flags: DisplayObjectFlags::VISIBLE,
             scroll_rect: None,
             next_scroll_rect: Default::default(),
+            scaling_grid: None,
+            bitmap_cache: None,
         }
     }
 }
@@ -309,11 +437,11 @@ impl<'gc> DisplayObjectBase<'gc> {
         self.name = name;
     }
 
-    fn filters(&self) -> Avm2ArrayStorage<'gc> {
-        Avm2ArrayStorage::from_storage(self.filters.iter().collect())
+    fn filters(&self) -> Vec<Filter> {
+        self.filters.clone()
     }
 
-    fn set_filters(&mut self, filters: Avm2ArrayStorage<'gc>) {
+    fn set_filters(&mut self, filters: Vec<Filter>) {
         self.filters = filters;
     }
 
@@ -500,27 +628,17 @@ pub fn render_base<'gc>(this: DisplayObject<'gc>, context: &mut RenderContext<'_
         return;
     }
     context.transform_stack.push(this.base().transform());
+    // Blend modes are applied to the entire contents of this object (including its mask),
+    // so those are recorded into a separate command list that the backend renders as a group.
     let blend_mode = this.blend_mode();
-    if blend_mode != BlendMode::Normal {
-        context.commands.push_blend_mode(this.blend_mode());
-    }
-
-    let scroll_rect_matrix = if let Some(rect) = this.scroll_rect() {
-        let cur_transform = context.transform_stack.transform();
-        // The matrix we use for actually drawing a rectangle for cropping purposes
-        // Note that we do *not* apply the translation yet
-        Some(
-            cur_transform.matrix
-                * Matrix::scale(
-                    rect.width().to_pixels() as f32,
-                    rect.height().to_pixels() as f32,
-                ),
-        )
+    let unblended_commands = if blend_mode != BlendMode::Normal {
+        Some(std::mem::take(context.commands))
     } else {
         None
     };
 
-    if let Some(rect) = this.scroll_rect() {
+    let scroll_rect = this.scroll_rect();
+    if let Some(rect) = &scroll_rect {
         // Translate everything that we render (including DisplayObject.mask)
         context.transform_stack.push(&Transform {
             matrix: Matrix::translate(-rect.x_min, -rect.y_min),
@@ -542,10 +660,42 @@ pub fn render_base<'gc>(this: DisplayObject<'gc>, context: &mut RenderContext<'_
         context.commands.activate_mask();
     }
 
+    if context.allow_mask && !context.is_offscreen && this.uses_bitmap_cache() {
+        render_cached(this, context);
+    } else {
+        if this.base().bitmap_cache.is_some() {
+            this.base_mut(context.gc_context).bitmap_cache = None;
+        }
+        render_contents(this, context);
+    }
+
+    if let Some(m) = mask {
+        context.commands.deactivate_mask();
+        context.allow_mask = false;
+        context.transform_stack.push(&mask_transform);
+        m.render_self(context);
+        context.transform_stack.pop();
+        context.allow_mask = true;
+        context.commands.pop_mask();
+    }
+    if let Some(commands) = unblended_commands {
+        let blended_commands = std::mem::replace(context.commands, commands);
+        context.commands.render_blend(&blended_commands, blend_mode);
+    }
+
+    if scroll_rect.is_some() {
+        // Remove the translation that we pushed
+        context.transform_stack.pop();
+    }
+
+    context.transform_stack.pop();
+}
+
+/// Renders the (cropped and filtered) contents of `this`, in the current coordinate space.
+fn render_contents<'gc>(this: DisplayObject<'gc>, context: &mut RenderContext<'_, 'gc, '_>) {
     // There are two parts to 'DisplayObject.scrollRect':
     // a scroll effect (translation), and a crop effect.
-    // This scroll is implementing by appling a translation matrix
-    // when we defined 'scroll_rect_matrix'.
+    // The scroll is implemented by the translation matrix pushed in `render_base`.
     // The crop is implemented as a rectangular mask using the height
     // and width provided by 'scrollRect'.
 
@@ -553,6 +703,26 @@ pub fn render_base<'gc>(this: DisplayObject<'gc>, context: &mut RenderContext<'_
     // with 'DisplayObject.mask'. We will end up rendering content that
     // lies in the intersection of the scroll rect and DisplayObject.mask,
     // which is exactly the behavior that we want.
+    let scroll_rect_matrix = this.scroll_rect().map(|rect| {
+        // The matrix we use for actually drawing a rectangle for cropping purposes.
+        // This undoes the scroll translation, as the crop doesn't move with the contents.
+        context.transform_stack.transform().matrix
+            * Matrix::translate(rect.x_min, rect.y_min)
+            * Matrix::scale(
+                rect.width().to_pixels() as f32,
+                rect.height().to_pixels() as f32,
+            )
+    });
+
+    // Filters are applied to the (cropped) contents of this object, so those are recorded into
+    // a separate command list that the backend renders offscreen.
+    let filters = this.filters();
+    let unfiltered_commands = if filters.is_empty() {
+        None
+    } else {
+        Some(std::mem::take(context.commands))
+    };
+
     if let Some(rect_mat) = scroll_rect_matrix {
         context.commands.push_mask();
         // The color doesn't matter, as this is a mask.
@@ -570,25 +740,237 @@ pub fn render_base<'gc>(this: DisplayObject<'gc>, context: &mut RenderContext<'_
         context.commands.pop_mask();
     }
 
-    if let Some(m) = mask {
-        context.commands.deactivate_mask();
-        context.allow_mask = false;
-        context.transform_stack.push(&mask_transform);
-        m.render_self(context);
-        context.transform_stack.pop();
-        context.allow_mask = true;
-        context.commands.pop_mask();
+    if let Some(commands) = unfiltered_commands {
+        let filtered_commands = std::mem::replace(context.commands, commands);
+        let bounds = contents_bounds(this, &context.transform_stack.transform().matrix);
+        context
+            .commands
+            .render_filtered(&filtered_commands, &filters, &bounds);
     }
-    if blend_mode != BlendMode::Normal {
-        context.commands.pop_blend_mode();
+}
+
+/// The bounds of the (cropped and filtered) contents of `this`, transformed by `matrix`.
+fn contents_bounds<'gc>(this: DisplayObject<'gc>, matrix: &Matrix) -> BoundingBox {
+    let mut bounds = if let Some(rect) = this.scroll_rect() {
+        BoundingBox::from(rect).transform(matrix)
+    } else {
+        this.bounds_with_transform(matrix)
+    };
+    for filter in &this.filters() {
+        bounds = filter.calculate_dest_rect(&bounds);
     }
+    bounds
+}
 
-    if scroll_rect_matrix.is_some() {
-        // Remove the translation that we pushed
-        context.transform_stack.pop();
+/// Renders the contents of `this` through its bitmap cache.
+///
+/// The contents are rendered offscreen with the current transform, minus its translation,
+/// and the resulting bitmap is drawn at the current position. The bitmap is only re-rendered
+/// when the cache has been invalidated by a change to this object or its children, or when
+/// the object is scaled, rotated or skewed. Moving the object or changing its color transform
+/// reuses the same bitmap.
+fn render_cached<'gc>(this: DisplayObject<'gc>, context: &mut RenderContext<'_, 'gc, '_>) {
+    let transform = context.transform_stack.transform().clone();
+    let linear_matrix = Matrix {
+        tx: Twips::ZERO,
+        ty: Twips::ZERO,
+        ..transform.matrix
+    };
+    let background = this.opaque_background();
+    let cache = this.base_mut(context.gc_context).bitmap_cache.take();
+    let cache = match cache {
+        Some(cache)
+            if !cache.dirty
+                && cache.matrix == linear_matrix
+                && cache.background == background
+                && !cache
+                    .dependencies
+                    .iter()
+                    .any(BitmapCacheDependency::is_modified) =>
+        {
+            cache
+        }
+        cache => {
+            let bounds = contents_bounds(this, &linear_matrix);
+            if !bounds.valid {
+                return;
+            }
+
+            // The cached bitmap is aligned to whole pixels, like in Flash.
+            let x_min = bounds.x_min.to_pixels().floor();
+            let y_min = bounds.y_min.to_pixels().floor();
+            let width = (bounds.x_max.to_pixels().ceil() - x_min) as u32;
+            let height = (bounds.y_max.to_pixels().ceil() - y_min) as u32;
+            if width == 0 || height == 0 {
+                return;
+            }
+            if width > BitmapCache::MAX_SIZE
+                || height > BitmapCache::MAX_SIZE
+                || width * height > BitmapCache::MAX_PIXELS
+            {
+                // Flash doesn't cache objects this large, and draws them directly instead.
+                return render_contents(this, context);
+            }
+
+            // The contents are rendered as if offscreen, since parts that are culled now
+            // could be moved onscreen later while the bitmap is still in use.
+            let mut transform_stack = TransformStack::new();
+            transform_stack.push(&Transform {
+                matrix: Matrix {
+                    tx: Twips::from_pixels(-x_min),
+                    ty: Twips::from_pixels(-y_min),
+                    ..linear_matrix
+                },
+                color_transform: Default::default(),
+            });
+            std::mem::swap(context.transform_stack, &mut transform_stack);
+            let commands = std::mem::take(context.commands);
+            context.is_offscreen = true;
+            context.bitmap_cache_dependencies = Some(Vec::new());
+            render_contents(this, context);
+            let dependencies = context.bitmap_cache_dependencies.take();
+            context.is_offscreen = false;
+            let cached_commands = std::mem::replace(context.commands, commands);
+            std::mem::swap(context.transform_stack, &mut transform_stack);
+
+            let handle = BitmapCache::render(
+                context,
+                cache
+                    .filter(|cache| cache.width == width && cache.height == height)
+                    .map(|cache| cache.handle),
+                width,
+                height,
+                background.clone(),
+                cached_commands,
+            );
+            match handle {
+                Some(handle) => BitmapCache {
+                    handle,
+                    width,
+                    height,
+                    x_min,
+                    y_min,
+                    matrix: linear_matrix,
+                    background,
+                    dependencies: dependencies.unwrap_or_default(),
+                    dirty: false,
+                },
+                None => return render_contents(this, context),
+            }
+        }
+    };
+
+    let matrix = Matrix::translate(
+        Twips::from_pixels(transform.matrix.tx.to_pixels().round() + cache.x_min),
+        Twips::from_pixels(transform.matrix.ty.to_pixels().round() + cache.y_min),
+    );
+    context.commands.render_bitmap(
+        &cache.handle,
+        &Transform {
+            matrix,
+            color_transform: transform.color_transform,
+        },
+        false,
+    );
+    this.base_mut(context.gc_context).bitmap_cache = Some(cache);
+}
+
+/// Renders the contents of `this` using its 9-slice scaling grid, if it has one.
+///
+/// The contents are split into nine regions along the grid, and each one is drawn separately
+/// (masked to its region), so that the corners keep their size and only the edges and
+/// center are stretched. Like Flash, the grid is ignored when the object is rotated or skewed.
+pub fn render_with_scaling_grid<'gc>(
+    this: DisplayObject<'gc>,
+    context: &mut RenderContext<'_, 'gc, '_>,
+    render: impl Fn(&mut RenderContext<'_, 'gc, '_>),
+) {
+    let matrix = this.base().transform.matrix;
+    let grid = match this.scaling_grid() {
+        // Masks can't be nested while rendering a mask, so these are drawn unsliced.
+        Some(grid) if matrix.b == 0.0 && matrix.c == 0.0 && context.allow_mask => grid,
+        _ => return render(context),
+    };
+    let (scale_x, scale_y) = (f64::from(matrix.a.abs()), f64::from(matrix.d.abs()));
+    let bounds = this.bounds_with_transform(&Matrix::default());
+    if !bounds.valid || scale_x == 0.0 || scale_y == 0.0 || (scale_x == 1.0 && scale_y == 1.0) {
+        return render(context);
+    }
+
+    let columns = slice_scaling_grid(bounds.x_min, bounds.x_max, grid.x_min, grid.x_max, scale_x);
+    let rows = slice_scaling_grid(bounds.y_min, bounds.y_max, grid.y_min, grid.y_max, scale_y);
+    for &(src_y, dest_y) in &rows {
+        for &(src_x, dest_x) in &columns {
+            let (src_width, dest_width) = (src_x.1 - src_x.0, dest_x.1 - dest_x.0);
+            let (src_height, dest_height) = (src_y.1 - src_y.0, dest_y.1 - dest_y.0);
+            if src_width <= 0.0 || dest_width <= 0.0 || src_height <= 0.0 || dest_height <= 0.0 {
+                continue;
+            }
+
+            let region_matrix = context.transform_stack.transform().matrix
+                * Matrix::translate(Twips::new(dest_x.0 as i32), Twips::new(dest_y.0 as i32))
+                * Matrix::scale(
+                    Twips::new(dest_width as i32).to_pixels() as f32,
+                    Twips::new(dest_height as i32).to_pixels() as f32,
+                );
+            context.commands.push_mask();
+            // The color doesn't matter, as this is a mask.
+            context.commands.draw_rect(Color::BLACK, &region_matrix);
+            context.commands.activate_mask();
+
+            let scale_x = dest_width / src_width;
+            let scale_y = dest_height / src_height;
+            context.transform_stack.push(&Transform {
+                matrix: Matrix {
+                    a: scale_x as f32,
+                    d: scale_y as f32,
+                    tx: Twips::new((dest_x.0 - src_x.0 * scale_x) as i32),
+                    ty: Twips::new((dest_y.0 - src_y.0 * scale_y) as i32),
+                    ..Default::default()
+                },
+                color_transform: Default::default(),
+            });
+            render(context);
+            context.transform_stack.pop();
+
+            context.commands.deactivate_mask();
+            context.commands.draw_rect(Color::BLACK, &region_matrix);
+            context.commands.pop_mask();
+        }
     }
+}
 
-    context.transform_stack.pop();
+/// Splits the range `[min, max]` at `grid_min` and `grid_max`, in twips.
+///
+/// Returns the `(start, end)` of each of the three parts, along with the range that it is
+/// stretched into so that the outer parts keep their size after scaling by `scale`.
+/// If the outer parts don't fit, they are shrunk proportionally and the center disappears.
+fn slice_scaling_grid(
+    min: Twips,
+    max: Twips,
+    grid_min: Twips,
+    grid_max: Twips,
+    scale: f64,
+) -> [((f64, f64), (f64, f64)); 3] {
+    let min = f64::from(min.get());
+    let max = f64::from(max.get());
+    let grid_min = f64::from(grid_min.get()).clamp(min, max);
+    let grid_max = f64::from(grid_max.get()).clamp(grid_min, max);
+
+    let mut start_size = (grid_min - min) / scale;
+    let mut end_size = (max - grid_max) / scale;
+    let total_size = max - min;
+    if start_size + end_size > total_size {
+        let shrink = total_size / (start_size + end_size);
+        start_size *= shrink;
+        end_size *= shrink;
+    }
+
+    [
+        ((min, grid_min), (min, min + start_size)),
+        ((grid_min, grid_max), (min + start_size, max - end_size)),
+        ((grid_max, max), (max - end_size, max)),
+    ]
 }
 
 #[enum_trait_object(
@@ -693,7 +1075,11 @@ pub trait TDisplayObject<'gc>:
     }
 
     fn set_matrix(&self, gc_context: MutationContext<'gc, '_>, matrix: Matrix) {
+        let changed = *self.base().matrix() != matrix;
         self.base_mut(gc_context).set_matrix(matrix);
+        if changed {
+            self.invalidate_parent_cached_bitmap(gc_context);
+        }
     }
 
     fn set_color_transform(
@@ -701,8 +1087,12 @@ pub trait TDisplayObject<'gc>:
         gc_context: MutationContext<'gc, '_>,
         color_transform: ColorTransform,
     ) {
+        let changed = *self.base().color_transform() != color_transform;
         self.base_mut(gc_context)
-            .set_color_transform(color_transform)
+            .set_color_transform(color_transform);
+        if changed {
+            self.invalidate_parent_cached_bitmap(gc_context);
+        }
     }
 
     /// Should only be used to implement 'Transform.concatenatedMatrix'
@@ -764,6 +1154,7 @@ pub trait TDisplayObject<'gc>:
     /// Set by the `_x`/`x` ActionScript properties.
     fn set_x(&self, gc_context: MutationContext<'gc, '_>, value: f64) {
         self.base_mut(gc_context).set_x(value);
+        self.invalidate_parent_cached_bitmap(gc_context);
     }
 
     /// The `y` position in pixels of this display object in local space.
@@ -776,6 +1167,7 @@ pub trait TDisplayObject<'gc>:
     /// Set by the `_y`/`y` ActionScript properties.
     fn set_y(&self, gc_context: MutationContext<'gc, '_>, value: f64) {
         self.base_mut(gc_context).set_y(value);
+        self.invalidate_parent_cached_bitmap(gc_context);
     }
 
     /// The rotation in degrees this display object in local space.
@@ -791,6 +1183,7 @@ pub trait TDisplayObject<'gc>:
     fn set_rotation(&self, gc_context: MutationContext<'gc, '_>, radians: Degrees) {
         self.base_mut(gc_context).set_rotation(radians);
         self.set_scale_rotation_cached(gc_context);
+        self.invalidate_parent_cached_bitmap(gc_context);
     }
 
     /// The X axis scale for this display object in local space.
@@ -806,6 +1199,7 @@ pub trait TDisplayObject<'gc>:
     fn set_scale_x(&self, gc_context: MutationContext<'gc, '_>, value: Percent) {
         self.base_mut(gc_context).set_scale_x(value);
         self.set_scale_rotation_cached(gc_context);
+        self.invalidate_parent_cached_bitmap(gc_context);
     }
 
     /// The Y axis scale for this display object in local space.
@@ -821,6 +1215,7 @@ pub trait TDisplayObject<'gc>:
     fn set_scale_y(&self, gc_context: MutationContext<'gc, '_>, value: Percent) {
         self.base_mut(gc_context).set_scale_y(value);
         self.set_scale_rotation_cached(gc_context);
+        self.invalidate_parent_cached_bitmap(gc_context);
     }
 
     /// Gets the pixel width of the AABB containing this display object in local space.
@@ -929,7 +1324,8 @@ pub trait TDisplayObject<'gc>:
     /// 1 is fully opaque.
     /// Set by the `_alpha`/`alpha` ActionScript properties.
     fn set_alpha(&self, gc_context: MutationContext<'gc, '_>, value: f64) {
-        self.base_mut(gc_context).set_alpha(value)
+        self.base_mut(gc_context).set_alpha(value);
+        self.invalidate_parent_cached_bitmap(gc_context);
     }
 
     fn name(&self) -> AvmString<'gc> {
@@ -939,12 +1335,13 @@ pub trait TDisplayObject<'gc>:
         self.base_mut(gc_context).set_name(name)
     }
 
-    fn filters(&self) -> Avm2ArrayStorage<'gc> {
+    fn filters(&self) -> Vec<Filter> {
         self.base().filters()
     }
 
-    fn set_filters(&self, gc_context: MutationContext<'gc, '_>, filters: Avm2ArrayStorage<'gc>) {
-        self.base_mut(gc_context).set_filters(filters)
+    fn set_filters(&self, gc_context: MutationContext<'gc, '_>, filters: Vec<Filter>) {
+        self.base_mut(gc_context).set_filters(filters);
+        self.invalidate_cached_bitmap(gc_context);
     }
 
     /// Returns the dot-syntax path to this display object, e.g. `_level0.foo.clip`
@@ -993,6 +1390,7 @@ pub trait TDisplayObject<'gc>:
     }
     fn set_clip_depth(&self, gc_context: MutationContext<'gc, '_>, depth: Depth) {
         self.base_mut(gc_context).set_clip_depth(depth);
+        self.invalidate_parent_cached_bitmap(gc_context);
     }
 
     /// Retrieve the parent of this display object.
@@ -1050,6 +1448,7 @@ pub trait TDisplayObject<'gc>:
             }
         }
         self.base_mut(gc_context).set_masker(node);
+        self.invalidate_parent_cached_bitmap(gc_context);
     }
     fn maskee(&self) -> Option<DisplayObject<'gc>> {
         self.base().maskee()
@@ -1066,6 +1465,7 @@ pub trait TDisplayObject<'gc>:
             }
         }
         self.base_mut(gc_context).set_maskee(node);
+        self.invalidate_parent_cached_bitmap(gc_context);
     }
 
     fn scroll_rect(&self) -> Option<Rectangle<Twips>> {
@@ -1082,6 +1482,20 @@ pub trait TDisplayObject<'gc>:
         rectangle: Rectangle<Twips>,
     ) {
         self.base_mut(gc_context).next_scroll_rect = rectangle;
+        self.invalidate_cached_bitmap(gc_context);
+    }
+
+    fn scaling_grid(&self) -> Option<Rectangle<Twips>> {
+        self.base().scaling_grid.clone()
+    }
+
+    fn set_scaling_grid(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        rectangle: Option<Rectangle<Twips>>,
+    ) {
+        self.base_mut(gc_context).scaling_grid = rectangle;
+        self.invalidate_cached_bitmap(gc_context);
     }
 
     fn removed(&self) -> bool {
@@ -1103,6 +1517,7 @@ pub trait TDisplayObject<'gc>:
     /// Returned by the `_visible`/`visible` ActionScript properties.
     fn set_visible(&self, gc_context: MutationContext<'gc, '_>, value: bool) {
         self.base_mut(gc_context).set_visible(value);
+        self.invalidate_parent_cached_bitmap(gc_context);
     }
 
     /// The blend mode used when rendering this display object.
@@ -1115,6 +1530,7 @@ pub trait TDisplayObject<'gc>:
     /// Values other than the defualt `BlendMode::Normal` implicitly cause cache-as-bitmap behavior.
     fn set_blend_mode(&self, gc_context: MutationContext<'gc, '_>, value: BlendMode) {
         self.base_mut(gc_context).set_blend_mode(value);
+        self.invalidate_parent_cached_bitmap(gc_context);
     }
 
     /// The opaque background color of this display object.
@@ -1128,6 +1544,7 @@ pub trait TDisplayObject<'gc>:
     /// is ignored.
     fn set_opaque_background(&self, gc_context: MutationContext<'gc, '_>, value: Option<Color>) {
         self.base_mut(gc_context).set_opaque_background(value);
+        self.invalidate_cached_bitmap(gc_context);
     }
 
     /// Whether this display object represents the root of loaded content.
@@ -1185,7 +1602,44 @@ pub trait TDisplayObject<'gc>:
     /// Explicilty sets this display object to be cached into a bitmap rendering.
     /// Note that the object will still be bitmap cached if a filter or blend mode is active.
     fn set_is_bitmap_cached(&self, gc_context: MutationContext<'gc, '_>, value: bool) {
-        self.base_mut(gc_context).set_is_bitmap_cached(value)
+        self.base_mut(gc_context).set_is_bitmap_cached(value);
+        self.invalidate_cached_bitmap(gc_context);
+    }
+
+    /// Whether this display object is rendered through a cached bitmap.
+    /// This is the case when it is explicitly bitmap cached, or has filters or an opaque
+    /// background applied.
+    fn uses_bitmap_cache(&self) -> bool {
+        let base = self.base();
+        base.is_bitmap_cached() || !base.filters.is_empty() || base.opaque_background.is_some()
+    }
+
+    /// Forces the cached bitmaps of this object and its ancestors to be rendered again.
+    ///
+    /// This must be called whenever the contents of this object change, such as its drawing
+    /// or its children.
+    fn invalidate_cached_bitmap(&self, gc_context: MutationContext<'gc, '_>) {
+        let mut node = Some((*self).into());
+        while let Some(object) = node {
+            if let Some(cache) = &mut object.base_mut(gc_context).bitmap_cache {
+                cache.dirty = true;
+            }
+            node = object.parent();
+        }
+    }
+
+    /// Forces the cached bitmaps that contain this object to be rendered again.
+    ///
+    /// This must be called whenever the way this object is drawn into its parent changes, such
+    /// as its transform. The cached bitmap of this object itself stays valid.
+    fn invalidate_parent_cached_bitmap(&self, gc_context: MutationContext<'gc, '_>) {
+        if let Some(parent) = self.parent() {
+            parent.invalidate_cached_bitmap(gc_context);
+        }
+        // Masks are drawn along with the object that they mask.
+        if let Some(parent) = self.maskee().and_then(|maskee| maskee.parent()) {
+            parent.invalidate_cached_bitmap(gc_context);
+        }
     }
 
     /// Whether this display object has a scroll rectangle applied.
@@ -1195,7 +1649,8 @@ pub trait TDisplayObject<'gc>:
 
     /// Sets whether this display object has a scroll rectangle applied.
     fn set_has_scroll_rect(&self, gc_context: MutationContext<'gc, '_>, value: bool) {
-        self.base_mut(gc_context).set_has_scroll_rect(value)
+        self.base_mut(gc_context).set_has_scroll_rect(value);
+        self.invalidate_cached_bitmap(gc_context);
     }
 
     /// Called whenever the focus tracker has deemed this display object worthy, or no longer worthy,
@@ -1453,6 +1908,12 @@ pub trait TDisplayObject<'gc>:
             if let Some(blend_mode) = place_object.blend_mode {
                 self.set_blend_mode(context.gc_context, blend_mode);
             }
+            if let Some(filters) = &place_object.filters {
+                self.set_filters(
+                    context.gc_context,
+                    filters.iter().map(Filter::from).collect(),
+                );
+            }
             if self.swf_version() >= 11 {
                 if let Some(visible) = place_object.is_visible {
                     self.set_visible(context.gc_context, visible);
@@ -1472,7 +1933,6 @@ pub trait TDisplayObject<'gc>:
             // name, clip_depth, clip_actions
             // These properties are only set on initial placement in `MovieClip::instantiate_child`
             // and can not be modified by subsequent PlaceObject tags.
-            // TODO: Filters need to be applied here.
         }
     }
 
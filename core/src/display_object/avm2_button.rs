@@ -275,6 +275,8 @@ impl<'gc> Avm2Button<'gc> {
         if let Some(state) = self.get_state_child(state.into()) {
             state.set_parent(context.gc_context, Some(self.into()));
         }
+        drop(button);
+        self.invalidate_cached_bitmap(context.gc_context);
     }
 
     /// Get the display object that represents a particular button state.
@@ -306,6 +308,9 @@ impl<'gc> Avm2Button<'gc> {
             swf::ButtonState::HIT_TEST => self.0.write(context.gc_context).hit_area = child,
             _ => (),
         }
+        if is_cur_state {
+            self.invalidate_cached_bitmap(context.gc_context);
+        }
 
         if let Some(child) = child {
             if let Some(mut parent) = child.parent().and_then(|parent| parent.as_container()) {
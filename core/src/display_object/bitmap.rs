@@ -6,7 +6,9 @@ use crate::avm2::{
     StageObject as Avm2StageObject, Value as Avm2Value,
 };
 use crate::context::{RenderContext, UpdateContext};
-use crate::display_object::{DisplayObjectBase, DisplayObjectPtr, TDisplayObject};
+use crate::display_object::{
+    BitmapCacheDependency, DisplayObjectBase, DisplayObjectPtr, TDisplayObject,
+};
 use crate::prelude::*;
 use crate::vminterface::Instantiator;
 use gc_arena::{Collect, Gc, GcCell, MutationContext};
@@ -194,6 +196,8 @@ impl<'gc> Bitmap<'gc> {
             write.bitmap_data = None;
             write.bitmap_handle = None;
         }
+
+        self.invalidate_cached_bitmap(context.gc_context);
     }
 
     pub fn avm2_bitmapdata_class(self) -> Option<Avm2ClassObject<'gc>> {
@@ -232,6 +236,7 @@ impl<'gc> Bitmap<'gc> {
 
     pub fn set_smoothing(self, mc: MutationContext<'gc, '_>, smoothing: bool) {
         self.0.write(mc).smoothing = smoothing;
+        self.invalidate_cached_bitmap(mc);
     }
 }
 
@@ -310,6 +315,12 @@ impl<'gc> TDisplayObject<'gc> for Bitmap<'gc> {
             if let Some(inner_bitmap_data) = bitmap_data.bitmap_data {
                 if let Ok(mut bd) = inner_bitmap_data.try_write(context.gc_context) {
                     bd.update_dirty_texture(context);
+                    if let Some(dependencies) = &mut context.bitmap_cache_dependencies {
+                        dependencies.push(BitmapCacheDependency::BitmapData(
+                            inner_bitmap_data,
+                            bd.version(),
+                        ));
+                    }
                 } else {
                     return; // bail, this is caused by recursive render attempt. TODO: support this.
                 };
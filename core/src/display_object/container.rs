@@ -204,6 +204,8 @@ pub trait TDisplayObjectContainer<'gc>:
         let removed_child = self
             .raw_container_mut(context.gc_context)
             .replace_at_depth(child, depth);
+        let this: DisplayObject<'_> = self.into();
+        this.invalidate_cached_bitmap(context.gc_context);
 
         child.set_parent(context.gc_context, Some(self.into()));
         child.set_place_frame(context.gc_context, 0);
@@ -242,6 +244,7 @@ pub trait TDisplayObjectContainer<'gc>:
             child,
             depth,
         );
+        this.invalidate_cached_bitmap(context.gc_context);
     }
 
     /// Insert a child display object into the container at a specific position
@@ -279,6 +282,7 @@ pub trait TDisplayObjectContainer<'gc>:
 
         self.raw_container_mut(context.gc_context)
             .insert_at_id(child, index);
+        this.invalidate_cached_bitmap(context.gc_context);
 
         if parent_changed {
             dispatch_added_event(this, child, child_was_on_stage, context);
@@ -296,6 +300,8 @@ pub trait TDisplayObjectContainer<'gc>:
     ) {
         self.raw_container_mut(context.gc_context)
             .swap_at_id(index1, index2);
+        let this: DisplayObject<'_> = (*self).into();
+        this.invalidate_cached_bitmap(context.gc_context);
     }
 
     /// Remove (and unloads) a child display object from this container's render and depth lists.
@@ -315,6 +321,8 @@ pub trait TDisplayObjectContainer<'gc>:
         write.remove_child_from_depth_list(child);
         let removed_from_render_list = write.remove_child_from_render_list(child);
         drop(write);
+        let this: DisplayObject<'_> = (*self).into();
+        this.invalidate_cached_bitmap(context.gc_context);
 
         if removed_from_render_list {
             child.unload(context);
@@ -377,6 +385,10 @@ pub trait TDisplayObjectContainer<'gc>:
 
             write = self.raw_container_mut(context.gc_context);
         }
+        drop(write);
+
+        let this: DisplayObject<'_> = (*self).into();
+        this.invalidate_cached_bitmap(context.gc_context);
     }
 
     /// Determine if the container is empty.
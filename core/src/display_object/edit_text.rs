@@ -8,27 +8,38 @@ use crate::avm1::{
     Value as Avm1Value,
 };
 use crate::avm2::{
-    Activation as Avm2Activation, Object as Avm2Object, StageObject as Avm2StageObject,
+    Activation as Avm2Activation, Object as Avm2Object, QName as Avm2QName,
+    StageObject as Avm2StageObject, TObject as _,
 };
+use crate::backend::navigator::Request;
 use crate::backend::ui::MouseCursor;
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::interactive::{
     InteractiveObject, InteractiveObjectBase, TInteractiveObject,
 };
-use crate::display_object::{DisplayObjectBase, DisplayObjectPtr, TDisplayObject};
+use crate::display_object::{
+    BitmapCacheDependency, DisplayObject, DisplayObjectBase, DisplayObjectPtr, MovieClip,
+    TDisplayObject,
+};
 use crate::drawing::Drawing;
 use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult, KeyCode};
 use crate::font::{round_down_to_pixel, Glyph, TextRenderSettings};
-use crate::html::{BoxBounds, FormatSpans, LayoutBox, LayoutContent, LayoutMetrics, TextFormat};
+use crate::frame_lifecycle::catchup_display_object_to_frame;
+use crate::html::{
+    BoxBounds, FormatSpans, LayoutBox, LayoutContent, LayoutMetrics, StyleSheet, TextFormat,
+};
+use crate::loader::MovieLoaderEventHandler;
 use crate::prelude::*;
 use crate::string::{utils as string_utils, AvmString, WStr, WString};
 use crate::tag_utils::SwfMovie;
 use crate::vminterface::{AvmObject, Instantiator};
 use chrono::Utc;
 use gc_arena::{Collect, Gc, GcCell, MutationContext};
+use instant::Instant;
 use ruffle_render::commands::CommandHandler;
 use ruffle_render::shape_utils::DrawCommand;
 use ruffle_render::transform::Transform;
+use std::time::Duration;
 use std::{cell::Ref, cell::RefMut, sync::Arc};
 use swf::{Color, Twips};
 
@@ -126,26 +137,99 @@ pub struct EditTextData<'gc> {
 
     /// Flags indicating the text field's settings.
     flags: EditTextFlag,
+
+    /// The style sheet attached to this text field, if any.
+    style_sheet: Option<EditTextStyleSheet<'gc>>,
+
+    /// The HTML text last set on this text field while it has a style sheet.
+    ///
+    /// Text styled by a style sheet can't be raised back to HTML, so the
+    /// source is kept instead.
+    html_source: Option<WString>,
+
+    /// The text range of the link under the mouse, styled with `a:hover`.
+    #[collect(require_static)]
+    hovered_link: Option<(usize, usize)>,
+
+    /// The characters the user may type into this text field, or `None` if
+    /// there's no restriction.
+    restrict: Option<TextRestrict>,
+
+    /// The maximum number of characters the user may type into this text
+    /// field, or 0 if there's no limit.
+    max_chars: i32,
+
+    /// The display objects of the images embedded in the text, in the same
+    /// order as the images of `text_spans`.
+    images: Vec<DisplayObject<'gc>>,
+
+    /// The states of the text before the user's latest edits, for undo.
+    undo_stack: Vec<UndoState<'gc>>,
+
+    /// The states of the text before the latest undos, for redo.
+    redo_stack: Vec<UndoState<'gc>>,
+
+    /// Whether the latest edit was typing, which further typing is undone
+    /// along with.
+    is_typing: bool,
+
+    /// The time and text position of the last click, to detect double-clicks.
+    #[collect(require_static)]
+    last_click: Option<(Instant, usize)>,
+}
+
+/// A state of the text of an `EditText` that the user can return to.
+#[derive(Clone, Debug, Collect)]
+#[collect(no_drop)]
+struct UndoState<'gc> {
+    text_spans: FormatSpans,
+    images: Vec<DisplayObject<'gc>>,
+    selection: Option<TextSelection>,
+}
+
+/// A style sheet attached to a text field.
+#[derive(Clone, Debug, Collect)]
+#[collect(no_drop)]
+pub struct EditTextStyleSheet<'gc> {
+    /// The AVM `StyleSheet` object.
+    pub object: AvmObject<'gc>,
+
+    /// The styles of the object, at the time it was attached.
+    pub styles: StyleSheet,
 }
 
 // TODO: would be nicer to compute (and return) this during layout, instead of afterwards
-/// Compute line (index, offset, extent) from the layout data.
+/// Compute line (index, offset, extent, start) from the layout data.
 fn get_line_data(layout: &[LayoutBox]) -> Vec<LineData> {
+    // images are placed beside lines, not in them
+    let layout: Vec<&LayoutBox> = layout
+        .iter()
+        .filter(|layout_box| layout_box.as_image().is_none())
+        .collect();
+
     // if there are no boxes, there are no lines
     if layout.is_empty() {
         return Vec::new();
     }
 
-    let first_box = &layout[0];
+    let text_range = |layout_box: &LayoutBox| match layout_box.content() {
+        LayoutContent::Text { start, end, .. } => Some((*start, *end)),
+        _ => None,
+    };
+
+    let first_box = layout[0];
 
     let mut index = 1;
     let mut offset = first_box.bounds().offset_y();
     let mut extent = first_box.bounds().extent_y();
+    let mut start = text_range(first_box).map(|(start, _)| start);
+    let mut end = text_range(first_box).map_or(0, |(_, end)| end);
 
     let mut line_data = Vec::new();
 
     for layout_box in layout.get(1..).unwrap() {
         let bounds = layout_box.bounds();
+        let range = text_range(layout_box);
 
         // if the top of the new box is lower than the bottom of the old box, it's a new line
         if bounds.offset_y() > extent {
@@ -154,15 +238,26 @@ fn get_line_data(layout: &[LayoutBox]) -> Vec<LineData> {
                 index,
                 offset,
                 extent,
+                // a line without text starts where the previous text ended
+                start: start.unwrap_or(end),
             });
 
             index += 1;
             offset = bounds.offset_y();
             extent = bounds.extent_y();
+            start = range.map(|(start, _)| start);
         } else {
             // otherwise we continue from the previous box
             offset = offset.min(bounds.offset_y());
             extent = extent.max(bounds.extent_y());
+            start = match (start, range) {
+                (Some(start), Some((box_start, _))) => Some(start.min(box_start)),
+                (start, range) => start.or(range.map(|(start, _)| start)),
+            };
+        }
+
+        if let Some((_, box_end)) = range {
+            end = end.max(box_end);
         }
     }
 
@@ -171,6 +266,7 @@ fn get_line_data(layout: &[LayoutBox]) -> Vec<LineData> {
         index,
         offset,
         extent,
+        start: start.unwrap_or(end),
     });
 
     line_data
@@ -189,7 +285,7 @@ impl<'gc> EditText<'gc> {
 
         let text = WString::from_utf8(&text.to_str_lossy(encoding));
         let mut text_spans = if swf_tag.is_html() {
-            FormatSpans::from_html(&text, default_format, swf_tag.is_multiline())
+            FormatSpans::from_html(&text, default_format, None, swf_tag.is_multiline(), false)
         } else {
             FormatSpans::from_text(text, default_format)
         };
@@ -269,6 +365,16 @@ impl<'gc> EditText<'gc> {
                 hscroll: 0.0,
                 line_data,
                 scroll: 1,
+                style_sheet: None,
+                html_source: None,
+                hovered_link: None,
+                restrict: None,
+                max_chars: swf_tag.max_length().unwrap_or_default().into(),
+                images: Vec::new(),
+                undo_stack: Vec::new(),
+                redo_stack: Vec::new(),
+                is_typing: false,
+                last_click: None,
             },
         ));
 
@@ -323,13 +429,20 @@ impl<'gc> EditText<'gc> {
         let mut edit_text = self.0.write(context.gc_context);
         let default_format = edit_text.text_spans.default_format().clone();
         edit_text.text_spans = FormatSpans::from_text(text.into(), default_format);
+        edit_text.html_source = None;
+        edit_text.hovered_link = None;
+        edit_text.images.clear();
+        edit_text.undo_stack.clear();
+        edit_text.redo_stack.clear();
         drop(edit_text);
 
         self.relayout(context);
     }
 
     pub fn html_text(self) -> WString {
-        if self.is_html() {
+        if let Some(html_source) = &self.0.read().html_source {
+            html_source.clone()
+        } else if self.is_html() {
             self.0.read().text_spans.to_html()
         } else {
             // Non-HTML text fields always return plain text.
@@ -344,16 +457,56 @@ impl<'gc> EditText<'gc> {
             write.text_spans = FormatSpans::from_html(
                 text,
                 default_format,
+                write
+                    .style_sheet
+                    .as_ref()
+                    .map(|style_sheet| &style_sheet.styles),
                 write.flags.contains(EditTextFlag::MULTILINE),
+                write.flags.contains(EditTextFlag::CONDENSE_WHITE),
             );
+            write.html_source = write.style_sheet.is_some().then(|| text.into());
+            write.hovered_link = None;
+            write.images.clear();
+            write.undo_stack.clear();
+            write.redo_stack.clear();
             drop(write);
 
+            self.load_images(context);
             self.relayout(context);
         } else {
             self.set_text(text, context);
         }
     }
 
+    pub fn style_sheet(self) -> Option<AvmObject<'gc>> {
+        self.0
+            .read()
+            .style_sheet
+            .as_ref()
+            .map(|style_sheet| style_sheet.object)
+    }
+
+    /// Attach a style sheet to this text field, or detach the current one.
+    ///
+    /// Like in Flash Player, a text field with a style sheet can't be edited
+    /// by the user, whatever its `type`. The current HTML text is styled
+    /// again with the new sheet.
+    pub fn set_style_sheet(
+        self,
+        style_sheet: Option<EditTextStyleSheet<'gc>>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) {
+        let html = self.html_text();
+        let mut write = self.0.write(context.gc_context);
+        write.style_sheet = style_sheet;
+        let is_html = write.flags.contains(EditTextFlag::HTML);
+        drop(write);
+
+        if is_html {
+            self.set_html_text(&html, context);
+        }
+    }
+
     pub fn text_length(self) -> usize {
         self.0.read().text_spans.text().len()
     }
@@ -389,10 +542,19 @@ impl<'gc> EditText<'gc> {
         self.relayout(context);
     }
 
-    pub fn is_editable(self) -> bool {
+    /// Whether this is an input text field, as reported by its `type`.
+    pub fn is_input(self) -> bool {
         !self.0.read().flags.contains(EditTextFlag::READ_ONLY)
     }
 
+    /// Whether the user can edit the text of this field.
+    ///
+    /// Input text fields can't be edited while they have a style sheet.
+    pub fn is_editable(self) -> bool {
+        let read = self.0.read();
+        !read.flags.contains(EditTextFlag::READ_ONLY) && read.style_sheet.is_none()
+    }
+
     pub fn set_editable(self, is_editable: bool, context: &mut UpdateContext<'_, 'gc, '_>) {
         self.0
             .write(context.gc_context)
@@ -424,6 +586,44 @@ impl<'gc> EditText<'gc> {
         self.relayout(context);
     }
 
+    pub fn condense_white(self) -> bool {
+        self.0.read().flags.contains(EditTextFlag::CONDENSE_WHITE)
+    }
+
+    /// Set whether whitespace is condensed in HTML text.
+    ///
+    /// This only applies to HTML text that is set afterwards.
+    pub fn set_condense_white(
+        self,
+        condense_white: bool,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) {
+        self.0
+            .write(context.gc_context)
+            .flags
+            .set(EditTextFlag::CONDENSE_WHITE, condense_white);
+    }
+
+    pub fn restrict(self) -> Option<WString> {
+        self.0
+            .read()
+            .restrict
+            .as_ref()
+            .map(|restrict| restrict.source().into())
+    }
+
+    pub fn set_restrict(self, restrict: Option<&WStr>, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.0.write(context.gc_context).restrict = restrict.map(TextRestrict::from_wstr);
+    }
+
+    pub fn max_chars(self) -> i32 {
+        self.0.read().max_chars
+    }
+
+    pub fn set_max_chars(self, max_chars: i32, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.0.write(context.gc_context).max_chars = max_chars;
+    }
+
     pub fn is_selectable(self) -> bool {
         !self.0.read().flags.contains(EditTextFlag::NO_SELECT)
     }
@@ -536,13 +736,226 @@ impl<'gc> EditText<'gc> {
         text: &WStr,
         context: &mut UpdateContext<'_, 'gc, '_>,
     ) {
-        self.0
-            .write(context.gc_context)
+        let mut write = self.0.write(context.gc_context);
+        // Keep the display objects of the images that the text spans keep.
+        let kept: Vec<bool> = write
             .text_spans
-            .replace_text(from, to, text, None);
+            .images()
+            .iter()
+            .map(|image| image.position <= from || image.position >= to)
+            .collect();
+        let mut kept = kept.into_iter();
+        write.images.retain(|_| kept.next().unwrap_or(false));
+        write.text_spans.replace_text(from, to, text, None);
+        drop(write);
+
         self.relayout(context);
     }
 
+    /// Create the display objects of the images embedded in the text.
+    ///
+    /// An image is instantiated from the library symbol exported as its
+    /// `src`, or in AS3 movies from the display object class named by it,
+    /// and is otherwise loaded from `src` as a URL. Images without a size
+    /// take the size of their symbol, or of the loaded content once it
+    /// arrives.
+    fn load_images(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let movie = self.0.read().static_data.swf.clone();
+        let specs = self.0.read().text_spans.images().to_vec();
+        let mut images = Vec::with_capacity(specs.len());
+
+        for spec in specs.iter() {
+            let src = AvmString::new(context.gc_context, spec.src.clone());
+            let symbol = context
+                .library
+                .library_for_movie(movie.clone())
+                .filter(|library| library.character_by_export_name(src).is_some())
+                .and_then(|library| {
+                    library
+                        .instantiate_by_export_name(src, context.gc_context)
+                        .ok()
+                });
+
+            let class_instance = match symbol {
+                Some(_) => None,
+                None => self.construct_image_class(context, src),
+            };
+
+            let image = if let Some(image) = class_instance {
+                image.set_parent(context.gc_context, Some(self.into()));
+                image
+            } else {
+                let image = match symbol {
+                    Some(symbol) => symbol,
+                    None => {
+                        let clip: DisplayObject<'gc> =
+                            MovieClip::new(movie.clone(), context.gc_context).into();
+                        let future = context.load_manager.load_movie_into_clip(
+                            context.player.clone(),
+                            clip,
+                            Request::get(spec.src.to_string()),
+                            None,
+                            Some(MovieLoaderEventHandler::EditTextImage(self)),
+                        );
+                        context.navigator.spawn_future(future);
+                        clip
+                    }
+                };
+                image.set_parent(context.gc_context, Some(self.into()));
+                image.post_instantiation(context, None, Instantiator::Movie, false);
+                catchup_display_object_to_frame(context, image);
+                image
+            };
+
+            images.push(image);
+        }
+
+        self.0.write(context.gc_context).images = images;
+        self.size_images(context.gc_context);
+    }
+
+    /// Construct an instance of the AVM2 display object class named by the
+    /// `src` of an image.
+    fn construct_image_class(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        src: AvmString<'gc>,
+    ) -> Option<DisplayObject<'gc>> {
+        if !context.is_action_script_3() {
+            return None;
+        }
+
+        // Text fields created by ActionScript have no library of their own,
+        // so their images come from the root movie.
+        let movie = self.0.read().static_data.swf.clone();
+        let domain = match context.library.library_for_movie(movie.clone()) {
+            Some(library) if movie.is_action_script_3() => library.avm2_domain(),
+            _ => context
+                .library
+                .library_for_movie(context.swf.clone())?
+                .avm2_domain(),
+        };
+
+        let mut activation = Avm2Activation::from_nothing(context.reborrow());
+        let name = Avm2QName::from_qualified_name(src, activation.context.gc_context);
+        let class = domain
+            .get_defined_value(&mut activation, name)
+            .ok()?
+            .as_object()?
+            .as_class_object()?;
+        if !class.has_class_in_chain(activation.avm2().classes().display_object) {
+            return None;
+        }
+
+        class
+            .construct(&mut activation, &[])
+            .ok()?
+            .as_display_object()
+    }
+
+    /// Give the images without an explicit width or height the size of
+    /// their content, if it has any yet.
+    fn size_images(self, gc_context: MutationContext<'gc, '_>) {
+        let mut write = self.0.write(gc_context);
+        let write = &mut *write;
+        for (spec, image) in write
+            .text_spans
+            .images_mut()
+            .iter_mut()
+            .zip(write.images.iter())
+        {
+            let bounds = image.bounds();
+            if !bounds.valid {
+                continue;
+            }
+            if spec.width.is_none() {
+                spec.width = Some(bounds.width().to_pixels());
+            }
+            if spec.height.is_none() {
+                spec.height = Some(bounds.height().to_pixels());
+            }
+        }
+    }
+
+    /// Called when the content of an image loaded from a URL has arrived.
+    ///
+    /// Images without a size were laid out with an empty box, so the text
+    /// is laid out again around their real size.
+    pub fn image_loaded(self, context: &mut UpdateContext<'_, 'gc, '_>, image: DisplayObject<'gc>) {
+        let is_ours = self
+            .0
+            .read()
+            .images
+            .iter()
+            .any(|other| DisplayObject::ptr_eq(*other, image));
+        if is_ours {
+            self.size_images(context.gc_context);
+            self.relayout(context);
+        }
+    }
+
+    /// Get the display object of the image embedded in the text with the
+    /// given `id`.
+    pub fn image_reference(self, id: &WStr) -> Option<DisplayObject<'gc>> {
+        let read = self.0.read();
+        read.text_spans
+            .images()
+            .iter()
+            .zip(read.images.iter())
+            .find(|(spec, _)| !spec.id.is_empty() && spec.id == id)
+            .map(|(_, image)| *image)
+    }
+
+    /// Move the display objects of the embedded images to their place in the
+    /// layout.
+    fn place_images(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let read = self.0.read();
+        let padding = Twips::from_pixels(Self::INTERNAL_PADDING);
+        let mut placements = Vec::new();
+        for layout_box in read.layout.iter() {
+            if let Some(image) = layout_box.as_image().and_then(|i| read.images.get(i)) {
+                let origin = layout_box.bounds().origin();
+                placements.push((
+                    *image,
+                    read.bounds.x_min + origin.x() + padding,
+                    read.bounds.y_min + origin.y() + padding,
+                ));
+            }
+        }
+        drop(read);
+
+        for (image, x, y) in placements {
+            let mut matrix = *image.base().matrix();
+            matrix.tx = x;
+            matrix.ty = y;
+            image.set_matrix(context.gc_context, matrix);
+        }
+    }
+
+    /// Render the image in a layout box, scaled to the box.
+    fn render_image(
+        self,
+        context: &mut RenderContext<'_, 'gc, '_>,
+        image: DisplayObject<'gc>,
+        lbox: &LayoutBox<'gc>,
+    ) {
+        let bounds = image.bounds();
+        if !bounds.valid || bounds.width() == Twips::ZERO || bounds.height() == Twips::ZERO {
+            return;
+        }
+
+        let box_bounds = lbox.bounds();
+        let scale_x = box_bounds.width().get() as f32 / bounds.width().get() as f32;
+        let scale_y = box_bounds.height().get() as f32 / bounds.height().get() as f32;
+        context.transform_stack.push(&Transform {
+            matrix: Matrix::scale(scale_x, scale_y)
+                * Matrix::translate(-bounds.x_min, -bounds.y_min),
+            color_transform: *image.base().color_transform(),
+        });
+        image.render_self(context);
+        context.transform_stack.pop();
+    }
+
     /// Construct a base text transform for a particular `EditText` span.
     ///
     /// This `text_transform` is separate from and relative to the base
@@ -628,6 +1041,7 @@ impl<'gc> EditText<'gc> {
 
     /// Redraw the border of this `EditText`.
     fn redraw_border(self, gc_context: MutationContext<'gc, '_>) {
+        self.invalidate_cached_bitmap(gc_context);
         let mut write = self.0.write(gc_context);
 
         write.drawing.clear();
@@ -674,6 +1088,12 @@ impl<'gc> EditText<'gc> {
         }
     }
 
+    /// The most edits that the user can undo.
+    const MAX_UNDO_STATES: usize = 100;
+
+    /// The longest time between the clicks of a double-click.
+    const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+
     /// Internal padding between the bounds of the EditText and the text.
     /// Applies to each side.
     const INTERNAL_PADDING: f64 = 2.0;
@@ -685,6 +1105,7 @@ impl<'gc> EditText<'gc> {
     /// have already been calculated and applied to HTML trees lowered into the
     /// text-span representation.
     fn relayout(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.invalidate_cached_bitmap(context.gc_context);
         let mut edit_text = self.0.write(context.gc_context);
         let autosize = edit_text.autosize;
         let is_word_wrap = edit_text.flags.contains(EditTextFlag::WORD_WRAP);
@@ -699,8 +1120,21 @@ impl<'gc> EditText<'gc> {
             edit_text.text_spans.clear_displayed_text();
         }
 
+        // The link under the mouse is laid out with the style sheet's `a:hover` style.
+        let hovered_spans = edit_text.hovered_link.and_then(|(from, to)| {
+            let hover = edit_text
+                .style_sheet
+                .as_ref()?
+                .styles
+                .get(WStr::from_units(b"a:hover"))?;
+            let mut spans = edit_text.text_spans.clone();
+            let format = hover.apply_to(spans.get_text_format(from, to));
+            spans.set_text_format(from, to, &format);
+            Some(spans)
+        });
+
         let (new_layout, intrinsic_bounds) = LayoutBox::lower_from_text_spans(
-            &edit_text.text_spans,
+            hovered_spans.as_ref().unwrap_or(&edit_text.text_spans),
             context,
             movie,
             edit_text.bounds.width() - padding,
@@ -714,6 +1148,7 @@ impl<'gc> EditText<'gc> {
         // reset scroll
         edit_text.hscroll = 0.0;
         edit_text.scroll = 1;
+        let has_images = !edit_text.images.is_empty();
 
         if autosize != AutoSizeMode::None {
             if !is_word_wrap {
@@ -737,6 +1172,12 @@ impl<'gc> EditText<'gc> {
             edit_text.bounds.set_height(height);
             drop(edit_text);
             self.redraw_border(context.gc_context);
+        } else {
+            drop(edit_text);
+        }
+
+        if has_images {
+            self.place_images(context);
         }
     }
 
@@ -836,6 +1277,7 @@ impl<'gc> EditText<'gc> {
             if let Some(selection) = selection {
                 if selection.is_caret()
                     && !edit_text.flags.contains(EditTextFlag::READ_ONLY)
+                    && edit_text.style_sheet.is_none()
                     && selection.start() >= *start
                     && selection.end() <= *end
                     && Utc::now().timestamp_subsec_millis() / 500 == 0
@@ -857,6 +1299,10 @@ impl<'gc> EditText<'gc> {
             0
         };
 
+        if let Some(image) = lbox.as_image().and_then(|i| edit_text.images.get(i)) {
+            self.render_image(context, *image, lbox);
+        }
+
         // If the font can't be found or has no glyph information, use the "device font" instead.
         // We're cheating a bit and not actually rendering text using the OS/web.
         // Instead, we embed an SWF version of Noto Sans to use as the "device font", and render
@@ -1075,6 +1521,7 @@ impl<'gc> EditText<'gc> {
         selection: Option<TextSelection>,
         gc_context: MutationContext<'gc, '_>,
     ) {
+        self.invalidate_cached_bitmap(gc_context);
         let mut text = self.0.write(gc_context);
         if let Some(mut selection) = selection {
             selection.clamp(text.text_spans.text().len());
@@ -1093,7 +1540,8 @@ impl<'gc> EditText<'gc> {
         gc_context: MutationContext<'gc, '_>,
         settings: TextRenderSettings,
     ) {
-        self.0.write(gc_context).render_settings = settings
+        self.0.write(gc_context).render_settings = settings;
+        self.invalidate_cached_bitmap(gc_context);
     }
 
     pub fn hscroll(self) -> f64 {
@@ -1102,6 +1550,7 @@ impl<'gc> EditText<'gc> {
 
     pub fn set_hscroll(self, hscroll: f64, context: &mut UpdateContext<'_, 'gc, '_>) {
         self.0.write(context.gc_context).hscroll = hscroll;
+        self.invalidate_cached_bitmap(context.gc_context);
     }
 
     pub fn scroll(self) -> usize {
@@ -1119,188 +1568,519 @@ impl<'gc> EditText<'gc> {
         };
         let clamped = scroll_lines.clamp(1, self.maxscroll());
         self.0.write(context.gc_context).scroll = clamped;
+        self.invalidate_cached_bitmap(context.gc_context);
     }
 
+    /// The caret position nearest to a point on the stage.
+    ///
+    /// Points beside a line of text are placed at the end of that line.
     pub fn screen_position_to_index(self, position: (Twips, Twips)) -> Option<usize> {
-        let text = self.0.read();
         let position = self.global_to_local(position);
-        let position = (
-            position.0 + Twips::from_pixels(Self::INTERNAL_PADDING),
-            position.1 + Twips::from_pixels(Self::INTERNAL_PADDING),
-        );
+        let (origin_x, origin_y) = self.layout_origin();
+        let (x, y) = (position.0 - origin_x, position.1 - origin_y);
 
-        for layout_box in text.layout.iter() {
-            let origin = layout_box.bounds().origin();
-            let mut matrix = Matrix::translate(origin.x(), origin.y());
-            matrix.invert();
-            let local_position = matrix * position;
+        let glyph = self.find_glyph(|_position, bounds, glyph_x, advance| {
+            let glyph_x = bounds.offset_x() + glyph_x;
+            x >= glyph_x && x < glyph_x + advance && y >= bounds.offset_y() && y < bounds.extent_y()
+        });
 
-            if let Some((text, _tf, font, params, color)) =
-                layout_box.as_renderable_text(text.text_spans.text())
-            {
-                let mut result = None;
-                let baseline_adjustment =
-                    font.get_baseline_for_height(params.height()) - params.height();
-                font.evaluate(
-                    text,
-                    self.text_transform(color, baseline_adjustment),
-                    params,
-                    |pos, _transform, _glyph: &Glyph, advance, x| {
-                        if local_position.0 >= x
-                            && local_position.0 <= x + advance
-                            && local_position.1 >= Twips::ZERO
-                            && local_position.1 <= params.height()
-                        {
-                            if local_position.0 >= x + (advance / 2) {
-                                result = Some(string_utils::next_char_boundary(text, pos));
-                            } else {
-                                result = Some(pos);
-                            }
-                        }
-                    },
-                );
-                if result.is_some() {
-                    return result;
-                }
+        if let Some((index, bounds, glyph_x, advance)) = glyph {
+            if x >= bounds.offset_x() + glyph_x + advance / 2 {
+                let text = self.text();
+                return Some(string_utils::next_char_boundary(&text, index));
             }
+            return Some(index);
         }
 
-        None
+        self.line_index_at_point(position)
+            .map(|line| self.line_end(line))
+    }
+
+    /// Whether the style sheet of this text field has an `a:hover` style.
+    fn has_hover_style(self) -> bool {
+        self.0
+            .read()
+            .style_sheet
+            .as_ref()
+            .map_or(false, |style_sheet| {
+                style_sheet
+                    .styles
+                    .get(WStr::from_units(b"a:hover"))
+                    .is_some()
+            })
+    }
+
+    /// The text range of the link containing the given text index, if any.
+    ///
+    /// Neighbouring spans with the same URL make up a single link.
+    fn link_range_at(self, index: usize) -> Option<(usize, usize)> {
+        let text = self.0.read();
+        let spans: Vec<_> = text
+            .text_spans
+            .iter_spans()
+            .map(|(start, end, _text, span)| (start, end, &span.url))
+            .collect();
+        let position = spans
+            .iter()
+            .position(|(start, end, _url)| (*start..*end).contains(&index))?;
+        let url = spans[position].2;
+        if url.is_empty() {
+            return None;
+        }
+
+        let first = spans[..position]
+            .iter()
+            .rposition(|(_, _, other)| *other != url)
+            .map_or(0, |i| i + 1);
+        let last = spans[position..]
+            .iter()
+            .position(|(_, _, other)| *other != url)
+            .map_or(spans.len(), |i| position + i);
+        Some((spans[first].0, spans[last - 1].1))
+    }
+
+    /// Change the link styled with `a:hover`, keeping the current scroll.
+    fn set_hovered_link(
+        self,
+        link: Option<(usize, usize)>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) {
+        let read = self.0.read();
+        if read.hovered_link == link {
+            return;
+        }
+        let (scroll, hscroll) = (read.scroll, read.hscroll);
+        drop(read);
+
+        self.0.write(context.gc_context).hovered_link = link;
+        self.relayout(context);
+
+        let mut write = self.0.write(context.gc_context);
+        write.scroll = scroll.min(write.line_data.len().max(1));
+        write.hscroll = hscroll;
     }
 
     pub fn text_input(self, character: char, context: &mut UpdateContext<'_, 'gc, '_>) {
-        if self.0.read().flags.contains(EditTextFlag::READ_ONLY) {
+        if !self.is_editable() {
             return;
         }
 
-        if let Some(selection) = self.selection() {
-            let mut changed = false;
-            match character as u8 {
-                8 | 127 if !selection.is_caret() => {
-                    // Backspace or delete with multiple characters selected
-                    self.replace_text(selection.start(), selection.end(), WStr::empty(), context);
-                    self.set_selection(
-                        Some(TextSelection::for_position(selection.start())),
-                        context.gc_context,
-                    );
-                    changed = true;
-                }
-                8 => {
-                    // Backspace with caret
-                    if selection.start() > 0 {
-                        // Delete previous character
-                        let text = self.text();
-                        let start = string_utils::prev_char_boundary(&text, selection.start());
-                        self.replace_text(start, selection.start(), WStr::empty(), context);
-                        self.set_selection(
-                            Some(TextSelection::for_position(start)),
-                            context.gc_context,
-                        );
-                        changed = true;
-                    }
+        let selection = match self.selection() {
+            Some(selection) => selection,
+            None => return,
+        };
+        let is_word_modifier_down =
+            context.input.is_key_down(KeyCode::Control) || context.input.is_key_down(KeyCode::Alt);
+
+        match character {
+            // Word-wise deletion is handled as a text control event.
+            '\u{8}' | '\u{7f}' if is_word_modifier_down => {}
+            '\u{8}' | '\u{7f}' if !selection.is_caret() => {
+                // Backspace or delete with multiple characters selected
+                self.user_replace_text(
+                    selection.start(),
+                    selection.end(),
+                    WStr::empty(),
+                    true,
+                    context,
+                );
+            }
+            '\u{8}' => {
+                // Backspace with caret
+                if selection.start() > 0 {
+                    // Delete previous character
+                    let text = self.text();
+                    let start = string_utils::prev_char_boundary(&text, selection.start());
+                    self.user_replace_text(start, selection.start(), WStr::empty(), true, context);
                 }
-                127 => {
-                    // Delete with caret
-                    if selection.end() < self.text_length() {
-                        // Delete next character
-                        let text = self.text();
-                        let end = string_utils::next_char_boundary(&text, selection.start());
-                        self.replace_text(selection.start(), end, WStr::empty(), context);
-                        // No need to change selection
-                        changed = true;
-                    }
+            }
+            '\u{7f}' => {
+                // Delete with caret
+                if selection.end() < self.text_length() {
+                    // Delete next character
+                    let text = self.text();
+                    let end = string_utils::next_char_boundary(&text, selection.start());
+                    self.user_replace_text(selection.start(), end, WStr::empty(), true, context);
                 }
-                code if !(code as char).is_control() => {
-                    self.replace_text(
+            }
+            character if !character.is_control() => {
+                let text = self.filter_user_text(
+                    &WString::from_char(character),
+                    selection.end() - selection.start(),
+                );
+                if !text.is_empty() {
+                    self.user_replace_text(
                         selection.start(),
                         selection.end(),
-                        &WString::from_char(character),
+                        &text,
+                        true,
                         context,
                     );
-                    let new_start = selection.start() + character.len_utf8();
-                    self.set_selection(
-                        Some(TextSelection::for_position(new_start)),
-                        context.gc_context,
-                    );
-                    changed = true;
                 }
-                _ => {}
             }
+            _ => {}
+        }
+    }
 
-            if changed {
-                let mut activation = Avm1Activation::from_nothing(
-                    context.reborrow(),
-                    ActivationIdentifier::root("[Propagate Text Binding]"),
-                    self.into(),
-                );
-                self.propagate_text_binding(&mut activation);
-                self.on_changed(&mut activation);
+    /// Filter text entered by the user through `restrict` and `maxChars`,
+    /// given the number of characters it replaces.
+    fn filter_user_text(self, text: &WStr, replaced: usize) -> WString {
+        let read = self.0.read();
+        let mut room = if read.max_chars > 0 {
+            (read.max_chars as usize).saturating_sub(read.text_spans.text().len() - replaced)
+        } else {
+            usize::MAX
+        };
+
+        let mut filtered = WString::new();
+        for character in text.chars().filter_map(Result::ok) {
+            let character = match &read.restrict {
+                Some(restrict) => restrict.to_allowed(character),
+                None => Some(character),
+            };
+            if let Some(character) = character {
+                if character.len_utf16() > room {
+                    break;
+                }
+                room -= character.len_utf16();
+                filtered.push_char(character);
             }
         }
+
+        filtered
     }
 
-    /// Listens for keyboard text control commands.
+    /// Filter text pasted by the user, given the number of characters it replaces.
     ///
-    /// TODO: Add explicit text control events (#4452).
-    pub fn handle_text_control_event(
+    /// Line breaks are normalized to `\r`, and single line text fields only
+    /// take the first line.
+    fn filter_pasted_text(self, content: &str, replaced: usize) -> WString {
+        let content = content.replace("\r\n", "\r").replace('\n', "\r");
+        let content = if self.is_multiline() {
+            &content[..]
+        } else {
+            content.split('\r').next().unwrap_or_default()
+        };
+
+        self.filter_user_text(&WString::from_utf8(content), replaced)
+    }
+
+    /// Replace a range of the text on behalf of the user, leaving the caret
+    /// after the new text.
+    ///
+    /// The edit can be undone; consecutive typing is undone at once.
+    fn user_replace_text(
         self,
+        from: usize,
+        to: usize,
+        text: &WStr,
+        is_typing: bool,
         context: &mut UpdateContext<'_, 'gc, '_>,
-        event: ClipEvent,
-    ) -> ClipEventResult {
-        if let ClipEvent::KeyPress { key_code } = event {
-            let mut edit_text = self.0.write(context.gc_context);
-            let selection = edit_text.selection;
-            if let Some(mut selection) = selection {
-                let text = edit_text.text_spans.text();
-                let length = text.len();
-                match key_code {
-                    ButtonKeyCode::Left => {
-                        if (context.input.is_key_down(KeyCode::Shift) || selection.is_caret())
-                            && selection.to > 0
-                        {
-                            selection.to = string_utils::prev_char_boundary(text, selection.to);
-                            if !context.input.is_key_down(KeyCode::Shift) {
-                                selection.from = selection.to;
-                            }
-                        } else if !context.input.is_key_down(KeyCode::Shift) {
-                            selection.to = selection.start();
-                            selection.from = selection.to;
-                        }
-                        selection.clamp(length);
-                        edit_text.selection = Some(selection);
-                        return ClipEventResult::Handled;
-                    }
-                    ButtonKeyCode::Right => {
-                        if (context.input.is_key_down(KeyCode::Shift) || selection.is_caret())
-                            && selection.to < length
-                        {
-                            selection.to = string_utils::next_char_boundary(text, selection.to);
-                            if !context.input.is_key_down(KeyCode::Shift) {
-                                selection.from = selection.to;
-                            }
-                        } else if !context.input.is_key_down(KeyCode::Shift) {
-                            selection.to = selection.end();
-                            selection.from = selection.to;
-                        }
-                        selection.clamp(length);
-                        edit_text.selection = Some(selection);
-                        return ClipEventResult::Handled;
-                    }
-                    _ => (),
-                }
+    ) {
+        self.record_undo(is_typing, context.gc_context);
+        self.replace_text(from, to, text, context);
+        self.set_selection(
+            Some(TextSelection::for_position(from + text.len())),
+            context.gc_context,
+        );
+        self.on_user_changed(context);
+    }
+
+    /// Notify the bound variable and the listeners of this text field of a
+    /// change made by the user.
+    fn on_user_changed(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let mut activation = Avm1Activation::from_nothing(
+            context.reborrow(),
+            ActivationIdentifier::root("[Propagate Text Binding]"),
+            self.into(),
+        );
+        self.propagate_text_binding(&mut activation);
+        self.on_changed(&mut activation);
+    }
+
+    fn undo_state(self) -> UndoState<'gc> {
+        let read = self.0.read();
+        UndoState {
+            text_spans: read.text_spans.clone(),
+            images: read.images.clone(),
+            selection: read.selection,
+        }
+    }
+
+    /// Record the current state of the text, before the user edits it.
+    fn record_undo(self, is_typing: bool, gc_context: MutationContext<'gc, '_>) {
+        let is_continued_typing = is_typing && self.0.read().is_typing;
+        let state = (!is_continued_typing).then(|| self.undo_state());
+
+        let mut write = self.0.write(gc_context);
+        if let Some(state) = state {
+            if write.undo_stack.len() >= Self::MAX_UNDO_STATES {
+                write.undo_stack.remove(0);
             }
+            write.undo_stack.push(state);
         }
+        write.redo_stack.clear();
+        write.is_typing = is_typing;
+    }
+
+    /// Return the text to a recorded state.
+    fn restore_undo_state(self, state: UndoState<'gc>, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let mut write = self.0.write(context.gc_context);
+        write.text_spans = state.text_spans;
+        write.images = state.images;
+        write.selection = state.selection;
+        write.hovered_link = None;
+        write.is_typing = false;
+        drop(write);
 
-        ClipEventResult::NotHandled
+        self.relayout(context);
+        self.on_user_changed(context);
     }
 
-    fn initialize_as_broadcaster(&self, activation: &mut Avm1Activation<'_, 'gc, '_>) {
-        if let Avm1Value::Object(object) = self.object() {
-            activation.context.avm1.broadcaster_functions().initialize(
-                activation.context.gc_context,
-                object,
-                activation.context.avm1.prototypes().array,
-            );
+    /// Undo the user's latest edit, returning whether there was one.
+    pub fn undo(self, context: &mut UpdateContext<'_, 'gc, '_>) -> bool {
+        let state = self.0.write(context.gc_context).undo_stack.pop();
+        if let Some(state) = state {
+            let current = self.undo_state();
+            self.0.write(context.gc_context).redo_stack.push(current);
+            self.restore_undo_state(state, context);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Redo the user's latest undone edit, returning whether there was one.
+    pub fn redo(self, context: &mut UpdateContext<'_, 'gc, '_>) -> bool {
+        let state = self.0.write(context.gc_context).redo_stack.pop();
+        if let Some(state) = state {
+            let current = self.undo_state();
+            self.0.write(context.gc_context).undo_stack.push(current);
+            self.restore_undo_state(state, context);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The line containing a caret position.
+    ///
+    /// The position at the end of the text belongs to the last line.
+    fn caret_line(self, position: usize) -> Option<usize> {
+        self.line_index_of_char(position)
+            .or_else(|| self.layout_lines().checked_sub(1))
+    }
+
+    /// The caret position at the end of a line, before its line break.
+    fn line_end(self, line: usize) -> usize {
+        let start = self.line_offset(line).unwrap_or_default();
+        let end = start + self.line_length(line).unwrap_or_default();
+        let read = self.0.read();
+        let text = read.text_spans.text();
+
+        if end > start
+            && (text.at(end - 1) == u16::from(b'\r') || text.at(end - 1) == u16::from(b'\n'))
+        {
+            end - 1
+        } else {
+            end
+        }
+    }
+
+    /// The caret position a number of lines above or below another,
+    /// keeping its column where the line is long enough.
+    fn line_move(self, position: usize, lines: isize) -> usize {
+        let line = match self.caret_line(position) {
+            Some(line) => line,
+            None => return position,
+        };
+
+        let target = line as isize + lines;
+        if target < 0 {
+            return 0;
+        } else if target >= self.layout_lines() as isize {
+            return self.text_length();
+        }
+
+        let column = position - self.line_offset(line).unwrap_or_default();
+        let target = target as usize;
+        let start = self.line_offset(target).unwrap_or_default();
+        (start + column).min(self.line_end(target))
+    }
+
+    /// Listens for keyboard text control commands.
+    ///
+    /// TODO: Add explicit text control events (#4452).
+    pub fn handle_text_control_event(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        event: ClipEvent,
+    ) -> ClipEventResult {
+        let key_code = match event {
+            ClipEvent::KeyPress { key_code } => key_code,
+            _ => return ClipEventResult::NotHandled,
+        };
+        let selection = match self.selection() {
+            Some(selection) => selection,
+            None => return ClipEventResult::NotHandled,
+        };
+
+        let is_shift_down = context.input.is_key_down(KeyCode::Shift);
+        // Control (Alt on macOS) moves and deletes by words.
+        let is_word_modifier_down =
+            context.input.is_key_down(KeyCode::Control) || context.input.is_key_down(KeyCode::Alt);
+        let text = self.text();
+        let length = text.len();
+        let page_lines = (self.bottom_scroll() + 1)
+            .saturating_sub(self.scroll())
+            .max(1) as isize;
+
+        let target = match key_code {
+            ButtonKeyCode::Backspace | ButtonKeyCode::Delete if is_word_modifier_down => {
+                if !self.is_editable() {
+                    return ClipEventResult::NotHandled;
+                }
+
+                let (from, to) = if !selection.is_caret() {
+                    (selection.start(), selection.end())
+                } else if key_code == ButtonKeyCode::Backspace {
+                    (
+                        prev_word_boundary(&text, selection.start()),
+                        selection.start(),
+                    )
+                } else {
+                    (
+                        selection.start(),
+                        next_word_boundary(&text, selection.start()),
+                    )
+                };
+                if from < to {
+                    self.user_replace_text(from, to, WStr::empty(), false, context);
+                }
+                return ClipEventResult::Handled;
+            }
+            ButtonKeyCode::Left if is_word_modifier_down => prev_word_boundary(&text, selection.to),
+            ButtonKeyCode::Right if is_word_modifier_down => {
+                next_word_boundary(&text, selection.to)
+            }
+            ButtonKeyCode::Left if is_shift_down || selection.is_caret() => {
+                string_utils::prev_char_boundary(&text, selection.to)
+            }
+            ButtonKeyCode::Left => selection.start(),
+            ButtonKeyCode::Right if is_shift_down || selection.is_caret() => {
+                string_utils::next_char_boundary(&text, selection.to)
+            }
+            ButtonKeyCode::Right => selection.end(),
+            ButtonKeyCode::Home if is_word_modifier_down => 0,
+            ButtonKeyCode::Home => self
+                .caret_line(selection.to)
+                .and_then(|line| self.line_offset(line))
+                .unwrap_or_default(),
+            ButtonKeyCode::End if is_word_modifier_down => length,
+            ButtonKeyCode::End => self
+                .caret_line(selection.to)
+                .map_or(length, |line| self.line_end(line)),
+            ButtonKeyCode::Up => self.line_move(selection.to, -1),
+            ButtonKeyCode::Down => self.line_move(selection.to, 1),
+            ButtonKeyCode::PgUp => self.line_move(selection.to, -page_lines),
+            ButtonKeyCode::PgDown => self.line_move(selection.to, page_lines),
+            _ => return ClipEventResult::NotHandled,
+        };
+
+        let mut new_selection = if is_shift_down {
+            TextSelection::for_range(selection.from, target)
+        } else {
+            TextSelection::for_position(target)
+        };
+        new_selection.clamp(length);
+
+        let mut write = self.0.write(context.gc_context);
+        write.selection = Some(new_selection);
+        write.is_typing = false;
+        ClipEventResult::Handled
+    }
+
+    /// Listens for keyboard shortcuts that edit the text, such as copy and
+    /// paste.
+    pub fn handle_editing_shortcut(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        key_code: KeyCode,
+    ) -> ClipEventResult {
+        if !context.input.is_key_down(KeyCode::Control) {
+            return ClipEventResult::NotHandled;
+        }
+        let selection = match self.selection() {
+            Some(selection) => selection,
+            None => return ClipEventResult::NotHandled,
+        };
+
+        let is_editable = self.is_editable();
+        // Passwords can't be copied out of a text field.
+        let is_copyable = self.is_selectable() && !self.is_password();
+        let copy = |context: &mut UpdateContext<'_, 'gc, '_>| {
+            if !selection.is_caret() {
+                let text = self.text()[selection.start()..selection.end()].to_string();
+                context.ui.set_clipboard_content(text);
+            }
+        };
+
+        match key_code {
+            KeyCode::A if self.is_selectable() => {
+                let length = self.text_length();
+                self.set_selection(
+                    Some(TextSelection::for_range(0, length)),
+                    context.gc_context,
+                );
+            }
+            KeyCode::C if is_copyable => copy(context),
+            KeyCode::X if is_copyable && is_editable => {
+                copy(context);
+                if !selection.is_caret() {
+                    self.user_replace_text(
+                        selection.start(),
+                        selection.end(),
+                        WStr::empty(),
+                        false,
+                        context,
+                    );
+                }
+            }
+            KeyCode::V if is_editable => {
+                let content = context.ui.clipboard_content();
+                let text = self.filter_pasted_text(&content, selection.end() - selection.start());
+                if !text.is_empty() || !selection.is_caret() {
+                    self.user_replace_text(
+                        selection.start(),
+                        selection.end(),
+                        &text,
+                        false,
+                        context,
+                    );
+                }
+            }
+            KeyCode::Z if is_editable && context.input.is_key_down(KeyCode::Shift) => {
+                self.redo(context);
+            }
+            KeyCode::Z if is_editable => {
+                self.undo(context);
+            }
+            KeyCode::Y if is_editable => {
+                self.redo(context);
+            }
+            _ => return ClipEventResult::NotHandled,
+        }
+
+        ClipEventResult::Handled
+    }
+
+    fn initialize_as_broadcaster(&self, activation: &mut Avm1Activation<'_, 'gc, '_>) {
+        if let Avm1Value::Object(object) = self.object() {
+            activation.context.avm1.broadcaster_functions().initialize(
+                activation.context.gc_context,
+                object,
+                activation.context.avm1.prototypes().array,
+            );
 
             if let Ok(Avm1Value::Object(listeners)) = object.get("_listeners", activation) {
                 let length = listeners.length(activation);
@@ -1432,7 +2212,7 @@ impl<'gc> EditText<'gc> {
                         font = Some(box_font);
                         text_format = Some(box_text_format);
                     }
-                    LayoutContent::Drawing { .. } => {}
+                    LayoutContent::Drawing { .. } | LayoutContent::Image(..) => {}
                 }
             }
         }
@@ -1453,6 +2233,176 @@ impl<'gc> EditText<'gc> {
             x: union_bounds.offset_x() + Twips::from_pixels(EditText::INTERNAL_PADDING),
         })
     }
+
+    /// The position of the layout's origin in the local space of this text
+    /// field, taking padding and scrolling into account.
+    fn layout_origin(self) -> (Twips, Twips) {
+        let read = self.0.read();
+        let padding = Twips::from_pixels(Self::INTERNAL_PADDING);
+        let scroll_offset = read
+            .line_data
+            .get(read.scroll.saturating_sub(1))
+            .map_or(Twips::ZERO, |line| line.offset);
+
+        (
+            read.bounds.x_min + padding - Twips::from_pixels(read.hscroll),
+            read.bounds.y_min + padding - scroll_offset,
+        )
+    }
+
+    /// Find the first laid out character matching a predicate on its index,
+    /// layout box bounds, horizontal offset and advance.
+    ///
+    /// Returns those same values for the matching character.
+    fn find_glyph<F>(self, mut predicate: F) -> Option<(usize, BoxBounds<Twips>, Twips, Twips)>
+    where
+        F: FnMut(usize, &BoxBounds<Twips>, Twips, Twips) -> bool,
+    {
+        let read = self.0.read();
+
+        for layout_box in read.layout.iter() {
+            let start = match layout_box.content() {
+                LayoutContent::Text { start, .. } => *start,
+                _ => continue,
+            };
+
+            if let Some((text, _tf, font, params, color)) =
+                layout_box.as_renderable_text(read.text_spans.displayed_text())
+            {
+                let bounds = layout_box.bounds();
+                let mut result = None;
+                let baseline_adjustment =
+                    font.get_baseline_for_height(params.height()) - params.height();
+                font.evaluate(
+                    text,
+                    self.text_transform(color, baseline_adjustment),
+                    params,
+                    |pos, _transform, _glyph: &Glyph, advance, x| {
+                        if result.is_none() && predicate(start + pos, &bounds, x, advance) {
+                            result = Some((start + pos, bounds, x, advance));
+                        }
+                    },
+                );
+
+                if result.is_some() {
+                    return result;
+                }
+            }
+        }
+
+        None
+    }
+
+    /// The bounds of the character at the given index, in the local space of
+    /// this text field.
+    ///
+    /// Returns `None` for characters that aren't laid out, such as line
+    /// breaks.
+    pub fn char_bounds(self, index: usize) -> Option<BoxBounds<Twips>> {
+        let (_, bounds, x, advance) =
+            self.find_glyph(|position, _bounds, _x, _advance| position == index)?;
+        let (origin_x, origin_y) = self.layout_origin();
+
+        Some(BoxBounds::from_position_and_size(
+            (
+                origin_x + bounds.offset_x() + x,
+                origin_y + bounds.offset_y(),
+            )
+                .into(),
+            (advance, bounds.height()).into(),
+        ))
+    }
+
+    /// The index of the character under a point in the local space of this
+    /// text field.
+    pub fn char_index_at_point(self, position: (Twips, Twips)) -> Option<usize> {
+        let (origin_x, origin_y) = self.layout_origin();
+        let (x, y) = (position.0 - origin_x, position.1 - origin_y);
+
+        self.find_glyph(|_position, bounds, glyph_x, advance| {
+            let glyph_x = bounds.offset_x() + glyph_x;
+            x >= glyph_x && x < glyph_x + advance && y >= bounds.offset_y() && y < bounds.extent_y()
+        })
+        .map(|(index, ..)| index)
+    }
+
+    /// The index of the line under a point in the local space of this text
+    /// field.
+    pub fn line_index_at_point(self, position: (Twips, Twips)) -> Option<usize> {
+        let (_, origin_y) = self.layout_origin();
+        let y = position.1 - origin_y;
+
+        self.0
+            .read()
+            .line_data
+            .iter()
+            .position(|line| y >= line.offset && y < line.extent)
+    }
+
+    /// The index of the line containing the character at the given index.
+    pub fn line_index_of_char(self, index: usize) -> Option<usize> {
+        let read = self.0.read();
+        if index >= read.text_spans.text().len() {
+            return None;
+        }
+
+        read.line_data.iter().rposition(|line| line.start <= index)
+    }
+
+    /// The index of the first character of the given line.
+    pub fn line_offset(self, line: usize) -> Option<usize> {
+        self.0.read().line_data.get(line).map(|line| line.start)
+    }
+
+    /// The number of characters in the given line, including its line
+    /// break.
+    pub fn line_length(self, line: usize) -> Option<usize> {
+        let read = self.0.read();
+        let start = read.line_data.get(line)?.start;
+        let end = read
+            .line_data
+            .get(line + 1)
+            .map_or(read.text_spans.text().len(), |next| next.start);
+
+        Some(end.saturating_sub(start))
+    }
+
+    /// The text of the given line, including its line break.
+    pub fn line_text(self, line: usize) -> Option<WString> {
+        let start = self.line_offset(line)?;
+        let length = self.line_length(line)?;
+
+        Some(self.0.read().text_spans.text()[start..start + length].into())
+    }
+
+    /// The index of the first character of the paragraph containing the
+    /// character at the given index.
+    pub fn paragraph_start(self, index: usize) -> Option<usize> {
+        let read = self.0.read();
+        let text = read.text_spans.text();
+        if index >= text.len() {
+            return None;
+        }
+
+        Some(
+            text[..index]
+                .rfind(&[b'\r', b'\n'][..])
+                .map_or(0, |newline| newline + 1),
+        )
+    }
+
+    /// The number of characters in the paragraph containing the character at
+    /// the given index, including its line break.
+    pub fn paragraph_length(self, index: usize) -> Option<usize> {
+        let start = self.paragraph_start(index)?;
+        let read = self.0.read();
+        let text = read.text_spans.text();
+        let end = text[index..]
+            .find(&[b'\r', b'\n'][..])
+            .map_or(text.len(), |newline| index + newline + 1);
+
+        Some(end - start)
+    }
 }
 
 impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
@@ -1487,8 +2437,12 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         }
     }
 
-    fn run_frame(&self, _context: &mut UpdateContext) {
-        // Noop
+    fn run_frame(&self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        // Embedded images are animated along with the text field.
+        let images = self.0.read().images.clone();
+        for image in images {
+            image.run_frame(context);
+        }
     }
 
     fn as_edit_text(&self) -> Option<EditText<'gc>> {
@@ -1508,6 +2462,13 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
     ) {
         self.set_default_instance_name(context);
 
+        // Text fields instantiated from the library create their own images.
+        let has_images = !self.0.read().text_spans.images().is_empty();
+        if has_images {
+            self.load_images(context);
+            self.relayout(context);
+        }
+
         if !context.is_action_script_3() {
             context
                 .avm1
@@ -1626,6 +2587,17 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         }
 
         let edit_text = self.0.read();
+        if let Some(dependencies) = &mut context.bitmap_cache_dependencies {
+            // The caret blinks, so a cached bitmap containing it can't be reused.
+            if edit_text
+                .selection
+                .map_or(false, |selection| selection.is_caret())
+                && !edit_text.flags.contains(EditTextFlag::READ_ONLY)
+                && edit_text.style_sheet.is_none()
+            {
+                dependencies.push(BitmapCacheDependency::Animated);
+            }
+        }
         context.transform_stack.push(&Transform {
             matrix: Matrix::translate(edit_text.bounds.x_min, edit_text.bounds.y_min),
             ..Default::default()
@@ -1668,7 +2640,10 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
             ..Default::default()
         });
 
-        if edit_text.layout.is_empty() && !edit_text.flags.contains(EditTextFlag::READ_ONLY) {
+        if edit_text.layout.is_empty()
+            && !edit_text.flags.contains(EditTextFlag::READ_ONLY)
+            && edit_text.style_sheet.is_none()
+        {
             let selection = edit_text.selection;
             if let Some(selection) = selection {
                 if selection.is_caret()
@@ -1751,6 +2726,8 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         if !focused {
             text.selection = None;
         }
+        drop(text);
+        self.invalidate_cached_bitmap(gc_context);
     }
 
     fn is_focusable(&self) -> bool {
@@ -1776,11 +2753,13 @@ impl<'gc> TInteractiveObject<'gc> for EditText<'gc> {
     }
 
     fn filter_clip_event(self, event: ClipEvent) -> ClipEventResult {
-        if event != ClipEvent::Press {
-            return ClipEventResult::NotHandled;
+        match event {
+            ClipEvent::Press => ClipEventResult::Handled,
+            ClipEvent::MouseMoveInside | ClipEvent::RollOut { .. } if self.has_hover_style() => {
+                ClipEventResult::Handled
+            }
+            _ => ClipEventResult::NotHandled,
         }
-
-        ClipEventResult::Handled
     }
 
     fn event_dispatch(
@@ -1788,17 +2767,48 @@ impl<'gc> TInteractiveObject<'gc> for EditText<'gc> {
         context: &mut UpdateContext<'_, 'gc, '_>,
         event: ClipEvent<'gc>,
     ) -> ClipEventResult {
+        match event {
+            ClipEvent::MouseMoveInside => {
+                let position = self.global_to_local(*context.mouse_position);
+                let link = self
+                    .char_index_at_point(position)
+                    .and_then(|index| self.link_range_at(index));
+                self.set_hovered_link(link, context);
+                return ClipEventResult::Handled;
+            }
+            ClipEvent::RollOut { .. } => {
+                self.set_hovered_link(None, context);
+                return ClipEventResult::Handled;
+            }
+            _ => {}
+        }
+
         let tracker = context.focus_tracker;
         tracker.set(Some(self.into()), context);
-        if let Some(position) = self
+
+        let index = self
             .screen_position_to_index(*context.mouse_position)
-            .map(TextSelection::for_position)
-        {
-            self.0.write(context.gc_context).selection = Some(position);
-        } else {
-            self.0.write(context.gc_context).selection =
-                Some(TextSelection::for_position(self.text_length()));
-        }
+            .unwrap_or_else(|| self.text_length());
+        let is_shift_down = context.input.is_key_down(KeyCode::Shift);
+        let now = Instant::now();
+
+        let mut write = self.0.write(context.gc_context);
+        let is_double_click = write.last_click.map_or(false, |(time, position)| {
+            position == index && now - time < Self::DOUBLE_CLICK_INTERVAL
+        });
+        write.last_click = (!is_double_click).then_some((now, index));
+        write.is_typing = false;
+        write.selection = Some(match write.selection {
+            // A double-click selects the word under the mouse.
+            _ if is_double_click => {
+                let (start, end) = word_range_at(write.text_spans.text(), index);
+                TextSelection::for_range(start, end)
+            }
+            // A shift-click extends the selection to the mouse.
+            Some(selection) if is_shift_down => TextSelection::for_range(selection.from, index),
+            _ => TextSelection::for_position(index),
+        });
+        drop(write);
 
         self.event_dispatch_to_avm2(context, event);
 
@@ -1846,6 +2856,10 @@ bitflags::bitflags! {
         const WAS_STATIC = 1 << 10;
         const BORDER = 1 << 11;
         const NO_SELECT = 1 << 12;
+
+        // The following bits are only set by ActionScript.
+        const CONDENSE_WHITE = 1 << 13;
+
         const SWF_FLAGS = Self::READ_ONLY.bits | Self::PASSWORD.bits | Self::MULTILINE.bits | Self::WORD_WRAP.bits | Self::USE_OUTLINES.bits |
                           Self::HTML.bits | Self::WAS_STATIC.bits | Self::BORDER.bits | Self::NO_SELECT.bits;
     }
@@ -1878,6 +2892,8 @@ pub struct LineData {
     offset: Twips,
     /// How many twips down the lowest point of the line is
     extent: Twips,
+    /// The index of the first character of the line
+    start: usize,
 }
 
 impl TextSelection {
@@ -1942,3 +2958,378 @@ impl TextSelection {
         self.to == self.from
     }
 }
+
+/// Whether a text unit is part of a word, for word-wise navigation.
+fn is_word_unit(unit: u16) -> bool {
+    char::from_u32(unit.into()).map_or(false, |c| c.is_alphanumeric() || c == '_')
+}
+
+/// The start of the word before a position, skipping any non-word
+/// characters in between.
+fn prev_word_boundary(text: &WStr, mut position: usize) -> usize {
+    while position > 0 && !is_word_unit(text.at(position - 1)) {
+        position -= 1;
+    }
+    while position > 0 && is_word_unit(text.at(position - 1)) {
+        position -= 1;
+    }
+    position
+}
+
+/// The start of the word after a position, skipping the rest of the word
+/// at the position and any non-word characters.
+fn next_word_boundary(text: &WStr, mut position: usize) -> usize {
+    while position < text.len() && is_word_unit(text.at(position)) {
+        position += 1;
+    }
+    while position < text.len() && !is_word_unit(text.at(position)) {
+        position += 1;
+    }
+    position
+}
+
+/// The range of the word, or the run of non-word characters, at a position.
+fn word_range_at(text: &WStr, position: usize) -> (usize, usize) {
+    if text.is_empty() {
+        return (0, 0);
+    }
+
+    let position = position.min(text.len() - 1);
+    let is_word = is_word_unit(text.at(position));
+    let mut start = position;
+    while start > 0 && is_word_unit(text.at(start - 1)) == is_word {
+        start -= 1;
+    }
+    let mut end = position;
+    while end < text.len() && is_word_unit(text.at(end)) == is_word {
+        end += 1;
+    }
+    (start, end)
+}
+
+/// The characters that the user may type into a text field, as set by
+/// `TextField.restrict`.
+///
+/// The restriction is a list of characters and `a-z` style ranges. A `^`
+/// switches between allowing and disallowing the characters that follow it,
+/// and a `\\` escapes the next character. Later entries take precedence.
+#[derive(Clone, Debug, Collect)]
+#[collect(require_static)]
+pub struct TextRestrict {
+    /// The restriction as it was set.
+    source: WString,
+
+    /// Whether characters that don't match any range are allowed.
+    allow_by_default: bool,
+
+    /// Character ranges, and whether the characters in them are allowed.
+    ranges: Vec<(bool, char, char)>,
+}
+
+impl TextRestrict {
+    pub fn from_wstr(source: &WStr) -> Self {
+        let chars: Vec<char> = source
+            .chars()
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect();
+
+        // Read a character at the given position, unescaping it if needed.
+        let read = |i: usize| match chars.get(i) {
+            Some('\\') => chars.get(i + 1).map(|c| (*c, i + 2)),
+            Some(c) => Some((*c, i + 1)),
+            None => None,
+        };
+
+        let allow_by_default = chars.first() == Some(&'^');
+        let mut allow = true;
+        let mut ranges = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            if chars[i] == '^' {
+                allow = !allow;
+                i += 1;
+                continue;
+            }
+
+            let (start, next) = match read(i) {
+                Some(read) => read,
+                None => break,
+            };
+            i = next;
+
+            let mut end = start;
+            if chars.get(i) == Some(&'-') {
+                if let Some((range_end, next)) = read(i + 1) {
+                    end = range_end;
+                    i = next;
+                }
+            }
+
+            ranges.push((allow, start, end));
+        }
+
+        Self {
+            source: source.into(),
+            allow_by_default,
+            ranges,
+        }
+    }
+
+    pub fn source(&self) -> &WStr {
+        &self.source
+    }
+
+    /// Whether the given character may be typed.
+    pub fn is_allowed(&self, c: char) -> bool {
+        let mut allowed = self.allow_by_default;
+        for (allow, start, end) in &self.ranges {
+            if (*start..=*end).contains(&c) {
+                allowed = *allow;
+            }
+        }
+        allowed
+    }
+
+    /// The character to enter when the given character is typed, if any.
+    ///
+    /// Like in Flash Player, a letter that isn't allowed is entered in the
+    /// other case if that is allowed.
+    pub fn to_allowed(&self, c: char) -> Option<char> {
+        if self.is_allowed(c) {
+            return Some(c);
+        }
+
+        let other_case: Vec<char> = if c.is_lowercase() {
+            c.to_uppercase().collect()
+        } else {
+            c.to_lowercase().collect()
+        };
+        match other_case[..] {
+            [other] if other != c && self.is_allowed(other) => Some(other),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        next_word_boundary, prev_word_boundary, word_range_at, EditText, EditTextStyleSheet,
+        TextRestrict, TextSelection,
+    };
+    use crate::avm1::ScriptObject;
+    use crate::context::UpdateContext;
+    use crate::html::StyleSheet;
+    use crate::player::PlayerBuilder;
+    use crate::string::{WStr, WString};
+    use crate::tag_utils::SwfMovie;
+    use crate::vminterface::AvmObject;
+    use std::sync::Arc;
+
+    /// Run a test against a new, empty input text field.
+    fn with_input_text<F>(test: F)
+    where
+        F: for<'gc> FnOnce(&mut UpdateContext<'_, 'gc, '_>, EditText<'gc>),
+    {
+        let player = PlayerBuilder::new().with_movie(SwfMovie::empty(10)).build();
+        let mut player = player.lock().unwrap();
+        player.mutate_with_update_context(|context| {
+            let movie = Arc::new(SwfMovie::empty(10));
+            let edit_text = EditText::new(context, movie, 0.0, 0.0, 200.0, 100.0);
+            edit_text.set_editable(true, context);
+            edit_text.set_selection(Some(TextSelection::for_position(0)), context.gc_context);
+            test(context, edit_text);
+        });
+    }
+
+    fn allowed(restrict: &str, chars: &str) -> String {
+        let restrict = TextRestrict::from_wstr(&crate::string::WString::from_utf8(restrict));
+        chars
+            .chars()
+            .filter_map(|c| restrict.to_allowed(c))
+            .collect()
+    }
+
+    #[test]
+    fn restrict_characters_and_ranges() {
+        assert_eq!(allowed("abc", "abcdef"), "abc");
+        assert_eq!(allowed("0-9", "a1b2c3"), "123");
+        assert_eq!(allowed("A-Z 0-9", "Ab 9?"), "AB 9");
+        assert_eq!(allowed("a-z", "ABC"), "abc");
+        assert_eq!(allowed("", "abc"), "");
+    }
+
+    #[test]
+    fn restrict_negation() {
+        assert_eq!(allowed("^0-9", "a1b2"), "ab");
+        assert_eq!(allowed("A-Z^Q", "AQZ"), "AZ");
+        assert_eq!(allowed("^0-9^5", "a15"), "a5");
+    }
+
+    #[test]
+    fn restrict_escapes() {
+        assert_eq!(allowed("\\-\\^", "-^a"), "-^");
+        assert_eq!(allowed("\\\\", "\\a"), "\\");
+        assert_eq!(allowed("0-9-", "1-a"), "1-");
+        assert_eq!(
+            TextRestrict::from_wstr(WStr::from_units(b"a-c")).source(),
+            WStr::from_units(b"a-c")
+        );
+    }
+
+    #[test]
+    fn word_boundaries() {
+        let text = WStr::from_units(b"one, two_2  three");
+        assert_eq!(next_word_boundary(text, 0), 5);
+        assert_eq!(next_word_boundary(text, 6), 12);
+        assert_eq!(next_word_boundary(text, 12), 17);
+        assert_eq!(prev_word_boundary(text, 17), 12);
+        assert_eq!(prev_word_boundary(text, 12), 5);
+        assert_eq!(prev_word_boundary(text, 3), 0);
+        assert_eq!(prev_word_boundary(text, 0), 0);
+    }
+
+    #[test]
+    fn word_ranges() {
+        let text = WStr::from_units(b"one, two_2  three");
+        assert_eq!(word_range_at(text, 1), (0, 3));
+        assert_eq!(word_range_at(text, 3), (3, 5));
+        assert_eq!(word_range_at(text, 7), (5, 10));
+        assert_eq!(word_range_at(text, 17), (12, 17));
+        assert_eq!(word_range_at(WStr::empty(), 0), (0, 0));
+    }
+
+    #[test]
+    fn style_sheet_prevents_editing() {
+        with_input_text(|context, edit_text| {
+            let style_sheet = EditTextStyleSheet {
+                object: AvmObject::Avm1(ScriptObject::new(context.gc_context, None).into()),
+                styles: StyleSheet::default(),
+            };
+            edit_text.set_style_sheet(Some(style_sheet), context);
+            edit_text.text_input('a', context);
+            assert_eq!(edit_text.text(), WStr::empty());
+            assert!(edit_text.is_input());
+            assert!(!edit_text.is_editable());
+
+            edit_text.set_style_sheet(None, context);
+            edit_text.text_input('a', context);
+            assert_eq!(edit_text.text(), WString::from_utf8("a"));
+            assert!(edit_text.is_input());
+            assert!(edit_text.is_editable());
+        });
+    }
+
+    fn type_text<'gc>(
+        edit_text: EditText<'gc>,
+        text: &str,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) {
+        for character in text.chars() {
+            edit_text.text_input(character, context);
+        }
+    }
+
+    #[test]
+    fn undo_merges_typing() {
+        with_input_text(|context, edit_text| {
+            type_text(edit_text, "abc", context);
+            edit_text.user_replace_text(3, 3, WStr::from_units(b"def"), false, context);
+            type_text(edit_text, "gh", context);
+            assert_eq!(edit_text.text(), WString::from_utf8("abcdefgh"));
+
+            assert!(edit_text.undo(context));
+            assert_eq!(edit_text.text(), WString::from_utf8("abcdef"));
+            assert!(edit_text.undo(context));
+            assert_eq!(edit_text.text(), WString::from_utf8("abc"));
+            assert!(edit_text.undo(context));
+            assert_eq!(edit_text.text(), WStr::empty());
+            assert!(!edit_text.undo(context));
+
+            assert!(edit_text.redo(context));
+            assert_eq!(edit_text.text(), WString::from_utf8("abc"));
+            let selection = edit_text.selection().unwrap();
+            assert_eq!((selection.start(), selection.end()), (3, 3));
+        });
+    }
+
+    #[test]
+    fn editing_clears_redo() {
+        with_input_text(|context, edit_text| {
+            type_text(edit_text, "a", context);
+            assert!(edit_text.undo(context));
+            type_text(edit_text, "b", context);
+            assert!(!edit_text.redo(context));
+            assert_eq!(edit_text.text(), WString::from_utf8("b"));
+
+            assert!(edit_text.undo(context));
+            assert_eq!(edit_text.text(), WStr::empty());
+            assert!(!edit_text.undo(context));
+        });
+    }
+
+    #[test]
+    fn undo_evicts_oldest_state() {
+        with_input_text(|context, edit_text| {
+            for i in 0..=EditText::MAX_UNDO_STATES {
+                edit_text.user_replace_text(i, i, WStr::from_units(b"a"), false, context);
+            }
+            assert_eq!(
+                edit_text.0.read().undo_stack.len(),
+                EditText::MAX_UNDO_STATES
+            );
+
+            for _ in 0..EditText::MAX_UNDO_STATES {
+                assert!(edit_text.undo(context));
+            }
+            // The state before the first edit was evicted.
+            assert!(!edit_text.undo(context));
+            assert_eq!(edit_text.text(), WString::from_utf8("a"));
+        });
+    }
+
+    #[test]
+    fn filter_user_text_restrict_and_max_chars() {
+        with_input_text(|context, edit_text| {
+            edit_text.set_text(WStr::from_units(b"abc"), context);
+            edit_text.set_max_chars(5, context);
+            let filter = |text: &str, replaced| {
+                edit_text.filter_user_text(&WString::from_utf8(text), replaced)
+            };
+            assert_eq!(filter("defgh", 0), WString::from_utf8("de"));
+            assert_eq!(filter("defgh", 1), WString::from_utf8("def"));
+            assert_eq!(filter("defgh", 3), WString::from_utf8("defgh"));
+
+            // Characters that `restrict` removes don't take up room.
+            edit_text.set_restrict(Some(WStr::from_units(b"0-9")), context);
+            assert_eq!(filter("a1b2c3", 0), WString::from_utf8("12"));
+
+            edit_text.set_max_chars(0, context);
+            assert_eq!(filter("a1b2c3", 0), WString::from_utf8("123"));
+        });
+    }
+
+    #[test]
+    fn filter_pasted_text_line_breaks() {
+        with_input_text(|context, edit_text| {
+            let content = "one\r\n2two\nthree";
+            assert_eq!(
+                edit_text.filter_pasted_text(content, 0),
+                WString::from_utf8("one")
+            );
+
+            edit_text.set_multiline(true, context);
+            assert_eq!(
+                edit_text.filter_pasted_text(content, 0),
+                WString::from_utf8("one\r2two\rthree")
+            );
+
+            edit_text.set_max_chars(6, context);
+            edit_text.set_restrict(Some(WStr::from_units(b"^0-9")), context);
+            assert_eq!(
+                edit_text.filter_pasted_text(content, 0),
+                WString::from_utf8("one\rtw")
+            );
+        });
+    }
+}
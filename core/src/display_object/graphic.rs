@@ -95,6 +95,7 @@ impl<'gc> Graphic<'gc> {
     }
 
     pub fn drawing(&self, gc_context: MutationContext<'gc, '_>) -> RefMut<'_, Drawing> {
+        self.invalidate_cached_bitmap(gc_context);
         RefMut::map(self.0.write(gc_context), |w| {
             w.drawing.get_or_insert_with(Drawing::new)
         })
@@ -157,6 +158,7 @@ impl<'gc> TDisplayObject<'gc> for Graphic<'gc> {
             .get_graphic(id)
         {
             self.0.write(context.gc_context).static_data = new_graphic.0.read().static_data;
+            self.invalidate_cached_bitmap(context.gc_context);
         } else {
             log::warn!("PlaceObject: expected Graphic at character ID {}", id);
         }
@@ -45,6 +45,7 @@ impl<'gc> MorphShape<'gc> {
 
     pub fn set_ratio(&mut self, gc_context: MutationContext<'gc, '_>, ratio: u16) {
         self.0.write(gc_context).ratio = ratio;
+        self.invalidate_cached_bitmap(gc_context);
     }
 }
 
@@ -80,6 +81,7 @@ impl<'gc> TDisplayObject<'gc> for MorphShape<'gc> {
             .get_morph_shape(id)
         {
             self.0.write(context.gc_context).static_data = new_morph_shape.0.read().static_data;
+            self.invalidate_cached_bitmap(context.gc_context);
         } else {
             log::warn!("PlaceObject: expected morph shape at character ID {}", id);
         }
@@ -25,8 +25,8 @@ use crate::display_object::interactive::{
     InteractiveObject, InteractiveObjectBase, TInteractiveObject,
 };
 use crate::display_object::{
-    Avm1Button, Avm2Button, Bitmap, DisplayObjectBase, DisplayObjectPtr, EditText, Graphic,
-    MorphShape, TDisplayObject, Text, Video,
+    render_with_scaling_grid, Avm1Button, Avm2Button, Bitmap, DisplayObjectBase, DisplayObjectPtr,
+    EditText, Graphic, MorphShape, TDisplayObject, Text, Video,
 };
 use crate::drawing::Drawing;
 use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult};
@@ -345,6 +345,7 @@ impl<'gc> MovieClip<'gc> {
         mc.audio_stream = None;
         mc.container = ChildContainer::new();
         drop(mc);
+        self.invalidate_cached_bitmap(context.gc_context);
     }
 
     /// Preload a chunk of the movie.
@@ -535,6 +536,10 @@ impl<'gc> MovieClip<'gc> {
                     .0
                     .write(context.gc_context)
                     .define_shape(context, reader, 4),
+                TagCode::DefineScalingGrid => self
+                    .0
+                    .write(context.gc_context)
+                    .define_scaling_grid(context, reader),
                 TagCode::DefineSound => self
                     .0
                     .write(context.gc_context)
@@ -1489,7 +1494,7 @@ impl<'gc> MovieClip<'gc> {
                                 .collect(),
                         );
                     }
-                    // TODO: Missing PlaceObject properties: amf_data, filters
+                    // TODO: Missing PlaceObject properties: amf_data
 
                     // Run first frame.
                     catchup_display_object_to_frame(context, child);
@@ -2042,23 +2047,19 @@ impl<'gc> MovieClip<'gc> {
             .unwrap_or_else(|| context.avm2.classes().movieclip);
 
         if let Avm2Value::Object(object) = self.object2() {
-            let mut constr_thing = || {
-                let mut activation = Avm2Activation::from_nothing(context.reborrow());
-                class_object.call_native_init(Some(object), &[], &mut activation)?;
-
-                Ok(())
-            };
-            let result: Result<(), Avm2Error> = constr_thing();
-
-            if let Err(e) = result {
-                log::error!(
-                    "Got {} when constructing AVM2 side of movie clip of type {}",
-                    e,
+            let mut activation = Avm2Activation::from_nothing(context.reborrow());
+            if let Err(e) = class_object.call_native_init(Some(object), &[], &mut activation) {
+                let description = format!(
+                    "Error when constructing AVM2 side of movie clip of type {}",
                     class_object
                         .try_inner_class_definition()
-                        .map(|c| c.read().name().to_qualified_name(context.gc_context))
+                        .map(|c| c
+                            .read()
+                            .name()
+                            .to_qualified_name(activation.context.gc_context))
                         .unwrap_or_else(|_| "[BorrowError!]".into())
                 );
+                Avm2::uncaught_error(&mut activation, Some(self.into()), e, &description);
             }
         }
     }
@@ -2166,6 +2167,7 @@ impl<'gc> MovieClip<'gc> {
     }
 
     pub fn drawing(&self, gc_context: MutationContext<'gc, '_>) -> RefMut<'_, Drawing> {
+        self.invalidate_cached_bitmap(gc_context);
         RefMut::map(self.0.write(gc_context), |s| &mut s.drawing)
     }
 
@@ -2386,15 +2388,16 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
                             if fs.frame_id == frame_id {
                                 let callable = fs.callable;
                                 drop(write);
-                                if let Err(e) = Avm2::run_stack_frame_for_callable(
-                                    callable,
-                                    Some(avm2_object),
-                                    &[],
-                                    context,
-                                ) {
-                                    log::error!(
-                                        "Error occured when running AVM2 frame script: {}",
-                                        e
+                                let mut activation =
+                                    Avm2Activation::from_nothing(context.reborrow());
+                                if let Err(e) =
+                                    callable.call(Some(avm2_object), &[], &mut activation)
+                                {
+                                    Avm2::uncaught_error(
+                                        &mut activation,
+                                        Some(self.into()),
+                                        e,
+                                        "Error occured when running AVM2 frame script",
                                     );
                                 }
                                 write = self.0.write(context.gc_context);
@@ -2446,8 +2449,10 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
     }
 
     fn render_self(&self, context: &mut RenderContext<'_, 'gc, '_>) {
-        self.0.read().drawing.render(context);
-        self.render_children(context);
+        render_with_scaling_grid((*self).into(), context, |context| {
+            self.0.read().drawing.render(context);
+            self.render_children(context);
+        });
     }
 
     fn self_bounds(&self) -> BoundingBox {
@@ -3272,6 +3277,32 @@ impl<'gc, 'a> MovieClipData<'gc> {
         Ok(())
     }
 
+    #[inline]
+    fn define_scaling_grid(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<'a>,
+    ) -> Result<(), Error> {
+        let id = reader.read_character_id()?;
+        let splitter_rect = reader.read_rectangle()?;
+        match context
+            .library
+            .library_for_movie_mut(self.movie())
+            .character_by_id(id)
+        {
+            Some(Character::MovieClip(clip)) => {
+                clip.set_scaling_grid(context.gc_context, Some(splitter_rect));
+            }
+            Some(_) => {
+                log::warn!("DefineScalingGrid: Tried to apply on non-sprite ID {}", id);
+            }
+            None => {
+                log::warn!("DefineScalingGrid: Character ID {} doesn't exist", id);
+            }
+        }
+        Ok(())
+    }
+
     #[inline]
     fn define_button_sound(
         &mut self,
@@ -4046,13 +4077,14 @@ impl<'a> GotoPlaceObject<'a> {
                 if place_object.background_color.is_none() {
                     place_object.background_color = Some(Color::from_rgba(0));
                 }
+                if place_object.filters.is_none() {
+                    place_object.filters = Some(Default::default());
+                }
                 // Purposely omitted properties:
                 // name, clip_depth, clip_actions, amf_data
                 // These properties are only set on initial placement in `MovieClip::instantiate_child`
                 // and can not be modified by subsequent PlaceObject tags.
                 // Also, is_visible flag persists during rewind unlike all other properties.
-                // TODO: Filters need to be applied here. Rewinding will erase filters if initial
-                // PlaceObject tag has none.
             }
         }
 
@@ -4104,11 +4136,13 @@ impl<'a> GotoPlaceObject<'a> {
         if next_place.background_color.is_some() {
             cur_place.background_color = next_place.background_color.take();
         }
+        if next_place.filters.is_some() {
+            cur_place.filters = next_place.filters.take();
+        }
         // Purposely omitted properties:
         // name, clip_depth, clip_actions, amf_data
         // These properties are only set on initial placement in `MovieClip::instantiate_child`
         // and can not be modified by subsequent PlaceObject tags.
-        // TODO: Filters need to be applied here. New filters will overwrite old filters.
     }
 }
 
@@ -93,6 +93,7 @@ impl<'gc> TDisplayObject<'gc> for Text<'gc> {
             .get_text(id)
         {
             self.0.write(context.gc_context).static_data = new_text.0.read().static_data;
+            self.invalidate_cached_bitmap(context.gc_context);
         } else {
             log::warn!("PlaceObject: expected text at character ID {}", id);
         }
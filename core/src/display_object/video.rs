@@ -5,8 +5,11 @@ use crate::avm2::{
     Activation as Avm2Activation, Object as Avm2Object, StageObject as Avm2StageObject,
 };
 use crate::context::{RenderContext, UpdateContext};
-use crate::display_object::{DisplayObjectBase, DisplayObjectPtr, TDisplayObject};
+use crate::display_object::{
+    BitmapCacheDependency, DisplayObjectBase, DisplayObjectPtr, TDisplayObject,
+};
 use crate::prelude::*;
+use crate::streams::NetStream;
 use crate::tag_utils::{SwfMovie, SwfSlice};
 use crate::vminterface::{AvmObject, Instantiator};
 use gc_arena::{Collect, GcCell, MutationContext};
@@ -40,7 +43,7 @@ pub struct VideoData<'gc> {
     base: DisplayObjectBase<'gc>,
 
     /// The source of the video data (e.g. an external file, a SWF bitstream)
-    source: GcCell<'gc, VideoSource>,
+    source: GcCell<'gc, VideoSource<'gc>>,
 
     /// The decoder stream that this video source is associated to.
     stream: VideoStream,
@@ -76,22 +79,35 @@ pub enum VideoStream {
 }
 
 #[derive(Clone, Debug, Collect)]
-#[collect(require_static)]
-pub enum VideoSource {
+#[collect(no_drop)]
+pub enum VideoSource<'gc> {
     /// A video bitstream embedded inside of a SWF movie.
     Swf {
         /// The movie that defined this video stream.
+        #[collect(require_static)]
         movie: Arc<SwfMovie>,
 
         /// The video stream definition.
+        #[collect(require_static)]
         streamdef: DefineVideoStream,
 
         /// The locations of each embedded sub-bitstream for each video frame.
         ///
         /// Each frame consists of a start and end parameter which can be used
         /// to reconstruct a reference to the embedded bitstream.
+        #[collect(require_static)]
         frames: BTreeMap<u32, (usize, usize)>,
     },
+
+    /// Video played by a `NetStream`, such as an external FLV file.
+    NetStream {
+        /// The stream attached to this video player, if any.
+        stream: Option<NetStream<'gc>>,
+
+        /// The size the video is displayed at, in pixels.
+        width: i32,
+        height: i32,
+    },
 }
 
 impl<'gc> Video<'gc> {
@@ -123,6 +139,97 @@ impl<'gc> Video<'gc> {
         ))
     }
 
+    /// Construct a Video object with no video attached to it.
+    ///
+    /// Videos constructed this way display whatever `NetStream` is attached
+    /// to them.
+    pub fn new(
+        mc: MutationContext<'gc, '_>,
+        width: i32,
+        height: i32,
+        object: Option<AvmObject<'gc>>,
+    ) -> Self {
+        let source = GcCell::allocate(
+            mc,
+            VideoSource::NetStream {
+                stream: None,
+                width,
+                height,
+            },
+        );
+
+        Video(GcCell::allocate(
+            mc,
+            VideoData {
+                base: Default::default(),
+                source,
+                stream: VideoStream::Uninstantiated(0),
+                decoded_frame: None,
+                object,
+                keyframes: BTreeSet::new(),
+            },
+        ))
+    }
+
+    /// Display the video of a `NetStream`, or nothing if `stream` is `None`.
+    ///
+    /// The video player keeps its current size, and stops playing any video
+    /// embedded in the SWF.
+    pub fn attach_net_stream(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        stream: Option<NetStream<'gc>>,
+    ) {
+        let (width, height) = match &*self.0.read().source.read() {
+            VideoSource::Swf { streamdef, .. } => (streamdef.width.into(), streamdef.height.into()),
+            VideoSource::NetStream { width, height, .. } => (*width, *height),
+        };
+
+        // The source is shared with the other instances of the video's
+        // character, so a new one is needed.
+        let source = GcCell::allocate(
+            context.gc_context,
+            VideoSource::NetStream {
+                stream,
+                width,
+                height,
+            },
+        );
+
+        let mut write = self.0.write(context.gc_context);
+        write.source = source;
+        write.decoded_frame = None;
+        drop(write);
+
+        self.invalidate_cached_bitmap(context.gc_context);
+    }
+
+    /// The last frame of video which was decoded.
+    fn current_frame(&self) -> Option<BitmapInfo> {
+        let read = self.0.read();
+        let source = read.source.read();
+        match &*source {
+            VideoSource::Swf { .. } => read
+                .decoded_frame
+                .as_ref()
+                .map(|(_, bitmap)| bitmap.clone()),
+            VideoSource::NetStream { stream, .. } => {
+                stream.and_then(|stream| stream.decoded_frame())
+            }
+        }
+    }
+
+    /// The width of the decoded video, in pixels.
+    pub fn video_width(&self) -> u32 {
+        self.current_frame().map_or(0, |bitmap| bitmap.width.into())
+    }
+
+    /// The height of the decoded video, in pixels.
+    pub fn video_height(&self) -> u32 {
+        self.current_frame()
+            .map_or(0, |bitmap| bitmap.height.into())
+    }
+
     /// Preload frame data from an SWF.
     ///
     /// This function yields an error if this video player is not playing an
@@ -148,6 +255,9 @@ impl<'gc> Video<'gc> {
 
                 frames.insert(tag.frame_num.into(), (subslice.start, subslice.end));
             }
+            VideoSource::NetStream { .. } => {
+                log::warn!("Attempted to preload SWF frame into a NetStream video");
+            }
         }
     }
 
@@ -171,6 +281,8 @@ impl<'gc> Video<'gc> {
 
         let num_frames = match &*read.source.read() {
             VideoSource::Swf { streamdef, .. } => Some(streamdef.num_frames),
+            // Video played by a `NetStream` is advanced by the stream itself.
+            VideoSource::NetStream { .. } => return,
         };
 
         if let Some(num_frames) = num_frames {
@@ -264,6 +376,7 @@ impl<'gc> Video<'gc> {
                     }
                 }
             },
+            VideoSource::NetStream { .. } => return,
         };
 
         drop(read);
@@ -271,6 +384,7 @@ impl<'gc> Video<'gc> {
         match res {
             Ok(bitmap) => {
                 self.0.write(context.gc_context).decoded_frame = Some((frame_id, bitmap));
+                self.invalidate_cached_bitmap(context.gc_context);
             }
             Err(e) => log::error!("Got error when seeking to video frame {}: {}", frame_id, e),
         }
@@ -313,7 +427,7 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
 
         let mut write = self.0.write(context.gc_context);
 
-        let (stream, movie, keyframes) = match &*write.source.read() {
+        let instantiated = match &*write.source.read() {
             VideoSource::Swf {
                 streamdef,
                 movie,
@@ -357,7 +471,21 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
                     }
                 }
 
-                (stream, movie.clone(), keyframes)
+                Some((stream, movie.clone(), keyframes))
+            }
+            // Video played by a `NetStream` is decoded by the stream itself.
+            VideoSource::NetStream { .. } => None,
+        };
+
+        let (stream, movie, keyframes) = match instantiated {
+            Some(instantiated) => instantiated,
+            None => {
+                drop(write);
+
+                if run_frame {
+                    self.run_frame(context);
+                }
+                return;
             }
         };
 
@@ -412,6 +540,7 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
     fn id(&self) -> CharacterId {
         match (*self.0.read().source.read()).borrow() {
             VideoSource::Swf { streamdef, .. } => streamdef.id,
+            VideoSource::NetStream { .. } => 0,
         }
     }
 
@@ -423,12 +552,16 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
                 bounding_box.set_width(Twips::from_pixels(streamdef.width as f64));
                 bounding_box.set_height(Twips::from_pixels(streamdef.height as f64));
             }
+            VideoSource::NetStream { width, height, .. } => {
+                bounding_box.set_width(Twips::from_pixels(*width as f64));
+                bounding_box.set_height(Twips::from_pixels(*height as f64));
+            }
         }
 
         bounding_box
     }
 
-    fn render(&self, context: &mut RenderContext) {
+    fn render(&self, context: &mut RenderContext<'_, 'gc, '_>) {
         if !context.is_offscreen && !self.world_bounds().intersects(&context.stage.view_bounds()) {
             // Off-screen; culled
             return;
@@ -438,7 +571,20 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
 
         let read = self.0.read();
 
-        if let Some((_frame_id, ref bitmap)) = read.decoded_frame {
+        if let Some(dependencies) = &mut context.bitmap_cache_dependencies {
+            if let VideoSource::NetStream {
+                stream: Some(stream),
+                ..
+            } = &*read.source.read()
+            {
+                dependencies.push(BitmapCacheDependency::NetStream(
+                    *stream,
+                    stream.frames_decoded(),
+                ));
+            }
+        }
+
+        if let Some(ref bitmap) = self.current_frame() {
             let mut transform = context.transform_stack.transform().clone();
             let bounds = self.self_bounds();
 
@@ -449,26 +595,27 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
                 bounds.height().to_pixels() as f32 / bitmap.height as f32,
             );
 
-            let (smoothed_flag, num_frames, version) = match &*read.source.read() {
+            let smoothing = match &*read.source.read() {
                 VideoSource::Swf {
                     streamdef,
                     frames,
                     movie,
-                } => (streamdef.is_smoothed, frames.len(), movie.version()),
-            };
-
-            let smoothing = match (context.stage.quality(), version) {
-                (StageQuality::Low, _) => false,
-                (_, 8..) => smoothed_flag,
-                (StageQuality::Medium, _) => false,
-                (StageQuality::High, _) => num_frames == 1,
-                (_, _) => true,
+                } => match (context.stage.quality(), movie.version()) {
+                    (StageQuality::Low, _) => false,
+                    (_, 8..) => streamdef.is_smoothed,
+                    (StageQuality::Medium, _) => false,
+                    (StageQuality::High, _) => frames.len() == 1,
+                    (_, _) => true,
+                },
+                VideoSource::NetStream { .. } => false,
             };
 
             context
                 .commands
                 .render_bitmap(&bitmap.handle, &transform, smoothing);
-        } else {
+        } else if matches!(&*read.source.read(), VideoSource::Swf { .. }) {
+            // Video played by a `NetStream` has nothing to show until the
+            // stream decodes its first frame.
             log::warn!("Video has no decoded frame to render.");
         }
 
@@ -4,7 +4,7 @@ use ruffle_render::backend::{RenderBackend, ShapeHandle};
 use ruffle_render::bitmap::{BitmapHandle, BitmapInfo, BitmapSize, BitmapSource};
 use ruffle_render::bounding_box::BoundingBox;
 use ruffle_render::commands::CommandHandler;
-use ruffle_render::shape_utils::{DistilledShape, DrawCommand, DrawPath};
+use ruffle_render::shape_utils::{DistilledShape, DrawCommand, DrawPath, FillRule};
 use std::cell::Cell;
 use swf::{FillStyle, LineStyle, Twips};
 
@@ -78,7 +78,9 @@ impl Drawing {
 
                     this.set_line_style(None);
                 }
-                DrawPath::Fill { style, commands } => {
+                DrawPath::Fill {
+                    style, commands, ..
+                } => {
                     this.set_fill_style(Some(style.clone()));
 
                     for command in commands {
@@ -120,12 +122,49 @@ impl Drawing {
                     x: self.cursor.0,
                     y: self.cursor.1,
                 }],
+                winding_rule: FillRule::EvenOdd,
             });
         }
         self.fill_start = self.cursor;
         self.dirty.set(true);
     }
 
+    /// The style of the current fill, if there is one.
+    pub fn fill_style(&self) -> Option<&FillStyle> {
+        self.current_fill.as_ref().map(|fill| &fill.style)
+    }
+
+    /// Runs `draw` with `winding_rule` applied to the commands it adds to the current fill.
+    /// The rest of the fill keeps its own winding rule.
+    pub fn draw_with_winding_rule(&mut self, winding_rule: FillRule, draw: impl FnOnce(&mut Self)) {
+        let previous_rule = match &self.current_fill {
+            Some(fill) if fill.winding_rule != winding_rule => fill.winding_rule,
+            _ => return draw(self),
+        };
+
+        self.split_fill(winding_rule);
+        draw(self);
+        self.split_fill(previous_rule);
+    }
+
+    /// Replaces the fill of the current line style with a gradient or bitmap fill.
+    /// This has no effect if there is no current line style.
+    pub fn set_line_fill_style(&mut self, fill_style: FillStyle) {
+        if let Some(line) = &self.current_line {
+            let style = line.style.clone().with_fill_style(fill_style);
+            self.set_line_style(Some(style));
+        }
+    }
+
+    /// Replaces the contents of this drawing with a copy of `other`.
+    pub fn copy_from(&mut self, other: &Drawing) {
+        // Our render handle can't be shared with `other`, so keep our own.
+        let render_handle = self.render_handle.get();
+        *self = other.clone();
+        self.render_handle.set(render_handle);
+        self.dirty.set(true);
+    }
+
     pub fn clear(&mut self) {
         self.current_fill = None;
         self.current_line = None;
@@ -203,77 +242,112 @@ impl Drawing {
         self.dirty.set(true);
     }
 
+    /// Draws a cubic Bezier curve from the cursor. This is approximated with quadratic curves,
+    /// as these are the only kind of curve supported by shapes.
+    pub fn draw_cubic_curve(
+        &mut self,
+        control_a: (Twips, Twips),
+        control_b: (Twips, Twips),
+        anchor: (Twips, Twips),
+    ) {
+        let to_f64 = |(x, y): (Twips, Twips)| (x.get() as f64, y.get() as f64);
+        let mut commands = Vec::new();
+        cubic_to_quadratics(
+            [
+                to_f64(self.cursor),
+                to_f64(control_a),
+                to_f64(control_b),
+                to_f64(anchor),
+            ],
+            0,
+            &mut commands,
+        );
+        for command in commands {
+            self.draw_command(command);
+        }
+    }
+
     pub fn add_bitmap(&mut self, bitmap: BitmapInfo) -> u16 {
         let id = self.bitmaps.len() as u16;
         self.bitmaps.push(bitmap);
         id
     }
 
-    pub fn render(&self, context: &mut RenderContext) {
-        if self.dirty.get() {
-            self.dirty.set(false);
-            let mut paths = Vec::with_capacity(self.paths.len());
-
-            for path in &self.paths {
-                match path {
-                    DrawingPath::Fill(fill) => {
-                        paths.push(DrawPath::Fill {
-                            style: &fill.style,
-                            commands: fill.commands.to_owned(),
-                        });
-                    }
-                    DrawingPath::Line(line) => {
-                        paths.push(DrawPath::Stroke {
-                            style: &line.style,
-                            commands: line.commands.to_owned(),
-                            is_closed: line.is_closed,
-                        });
-                    }
+    /// Returns all of the fills and strokes of this drawing, in drawing order.
+    /// Any pending fill is closed.
+    pub fn draw_paths(&self) -> Vec<DrawPath<'_>> {
+        let mut paths = Vec::with_capacity(self.paths.len());
+
+        for path in &self.paths {
+            match path {
+                DrawingPath::Fill(fill) => {
+                    paths.push(DrawPath::Fill {
+                        style: &fill.style,
+                        commands: fill.commands.to_owned(),
+                        winding_rule: fill.winding_rule,
+                    });
+                }
+                DrawingPath::Line(line) => {
+                    paths.push(DrawPath::Stroke {
+                        style: &line.style,
+                        commands: line.commands.to_owned(),
+                        is_closed: line.is_closed,
+                    });
                 }
             }
+        }
 
-            if let Some(fill) = &self.current_fill {
-                paths.push(DrawPath::Fill {
-                    style: &fill.style,
-                    commands: fill.commands.to_owned(),
-                })
-            }
+        if let Some(fill) = &self.current_fill {
+            paths.push(DrawPath::Fill {
+                style: &fill.style,
+                commands: fill.commands.to_owned(),
+                winding_rule: fill.winding_rule,
+            })
+        }
 
-            for line in &self.pending_lines {
-                let mut commands = line.commands.to_owned();
-                let is_closed = if self.current_fill.is_some() {
-                    commands.push(DrawCommand::LineTo {
-                        x: self.fill_start.0,
-                        y: self.fill_start.1,
-                    });
-                    true
-                } else {
-                    self.cursor == self.fill_start
-                };
-                paths.push(DrawPath::Stroke {
-                    style: &line.style,
-                    commands,
-                    is_closed,
-                })
-            }
+        for line in &self.pending_lines {
+            let mut commands = line.commands.to_owned();
+            let is_closed = if self.current_fill.is_some() {
+                commands.push(DrawCommand::LineTo {
+                    x: self.fill_start.0,
+                    y: self.fill_start.1,
+                });
+                true
+            } else {
+                self.cursor == self.fill_start
+            };
+            paths.push(DrawPath::Stroke {
+                style: &line.style,
+                commands,
+                is_closed,
+            })
+        }
 
-            if let Some(line) = &self.current_line {
-                let mut commands = line.commands.to_owned();
-                let is_closed = if self.current_fill.is_some() {
-                    commands.push(DrawCommand::LineTo {
-                        x: self.fill_start.0,
-                        y: self.fill_start.1,
-                    });
-                    true
-                } else {
-                    self.cursor == self.fill_start
-                };
-                paths.push(DrawPath::Stroke {
-                    style: &line.style,
-                    commands,
-                    is_closed,
-                })
-            }
+        if let Some(line) = &self.current_line {
+            let mut commands = line.commands.to_owned();
+            let is_closed = if self.current_fill.is_some() {
+                commands.push(DrawCommand::LineTo {
+                    x: self.fill_start.0,
+                    y: self.fill_start.1,
+                });
+                true
+            } else {
+                self.cursor == self.fill_start
+            };
+            paths.push(DrawPath::Stroke {
+                style: &line.style,
+                commands,
+                is_closed,
+            })
+        }
+
+        paths
+    }
+
+    pub fn render(&self, context: &mut RenderContext) {
+        if self.dirty.get() {
+            self.dirty.set(false);
+            let paths = self.draw_paths();
 
             let shape = DistilledShape {
                 paths,
@@ -309,7 +383,11 @@ impl Drawing {
         for path in &self.paths {
             match path {
                 DrawingPath::Fill(fill) => {
-                    if shape_utils::draw_command_fill_hit_test(&fill.commands, point) {
+                    if shape_utils::draw_command_fill_hit_test(
+                        &fill.commands,
+                        fill.winding_rule,
+                        point,
+                    ) {
                         return true;
                     }
                 }
@@ -328,7 +406,7 @@ impl Drawing {
 
         // The pending fill will auto-close.
         if let Some(fill) = &self.current_fill {
-            if shape_utils::draw_command_fill_hit_test(&fill.commands, point) {
+            if shape_utils::draw_command_fill_hit_test(&fill.commands, fill.winding_rule, point) {
                 return true;
             }
         }
@@ -380,6 +458,31 @@ impl Drawing {
         false
     }
 
+    // Closes the current fill and continues it from the cursor with a different winding rule.
+    fn split_fill(&mut self, winding_rule: FillRule) {
+        self.close_path();
+        if let Some(fill) = self.current_fill.take() {
+            let style = fill.style.clone();
+            let is_empty = fill
+                .commands
+                .iter()
+                .all(|command| matches!(command, DrawCommand::MoveTo { .. }));
+            if !is_empty {
+                self.paths.push(DrawingPath::Fill(fill));
+            }
+            self.current_fill = Some(DrawingFill {
+                style,
+                commands: vec![DrawCommand::MoveTo {
+                    x: self.cursor.0,
+                    y: self.cursor.1,
+                }],
+                winding_rule,
+            });
+        }
+        self.fill_start = self.cursor;
+        self.dirty.set(true);
+    }
+
     // Ensures that the path is closed for a pending fill.
     fn close_path(&mut self) {
         if let Some(fill) = &mut self.current_fill {
@@ -417,6 +520,7 @@ impl BitmapSource for Drawing {
 struct DrawingFill {
     style: FillStyle,
     commands: Vec<DrawCommand>,
+    winding_rule: FillRule,
 }
 
 #[derive(Debug, Clone)]
@@ -455,3 +559,43 @@ fn stretch_bounding_box(
         }
     }
 }
+
+/// Approximates the cubic Bezier curve with the given points (in twips) with quadratic curves,
+/// by recursively splitting it in half until each half is close enough to a quadratic curve.
+fn cubic_to_quadratics(points: [(f64, f64); 4], depth: u32, commands: &mut Vec<DrawCommand>) {
+    /// The maximum distance between the cubic curve and its approximation, in twips.
+    const TOLERANCE: f64 = 2.0;
+    const MAX_DEPTH: u32 = 8;
+
+    let [p0, p1, p2, p3] = points;
+    // The distance between a cubic curve and the quadratic curve with the control point
+    // `(3 * (p1 + p2) - p0 - p3) / 4` is at most `sqrt(3) / 36 * |p3 - 3 * p2 + 3 * p1 - p0|`.
+    let dx = p3.0 - 3.0 * p2.0 + 3.0 * p1.0 - p0.0;
+    let dy = p3.1 - 3.0 * p2.1 + 3.0 * p1.1 - p0.1;
+    let error = 3f64.sqrt() / 36.0 * dx.hypot(dy);
+
+    if error <= TOLERANCE || depth >= MAX_DEPTH {
+        let control = (
+            (3.0 * (p1.0 + p2.0) - p0.0 - p3.0) / 4.0,
+            (3.0 * (p1.1 + p2.1) - p0.1 - p3.1) / 4.0,
+        );
+        commands.push(DrawCommand::CurveTo {
+            x1: Twips::new(control.0.round() as i32),
+            y1: Twips::new(control.1.round() as i32),
+            x2: Twips::new(p3.0.round() as i32),
+            y2: Twips::new(p3.1.round() as i32),
+        });
+        return;
+    }
+
+    // Split the curve in half with de Casteljau's algorithm.
+    let mid = |a: (f64, f64), b: (f64, f64)| ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+    let p01 = mid(p0, p1);
+    let p12 = mid(p1, p2);
+    let p23 = mid(p2, p3);
+    let p012 = mid(p01, p12);
+    let p123 = mid(p12, p23);
+    let p0123 = mid(p012, p123);
+    cubic_to_quadratics([p0, p01, p012, p0123], depth + 1, commands);
+    cubic_to_quadratics([p0123, p123, p23, p3], depth + 1, commands);
+}
@@ -3,13 +3,15 @@
 mod dimensions;
 mod iterators;
 mod layout;
+mod style_sheet;
 mod text_format;
 
 pub use dimensions::BoxBounds;
 pub use dimensions::Position;
 pub use dimensions::Size;
 pub use layout::{LayoutBox, LayoutContent, LayoutMetrics};
-pub use text_format::{FormatSpans, TextFormat, TextSpan};
+pub use style_sheet::{parse_css, CssStyle, StyleSheet};
+pub use text_format::{FormatSpans, ImageAlign, TextFormat, TextImage, TextSpan};
 
 #[cfg(test)]
 mod test;
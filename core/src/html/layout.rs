@@ -4,7 +4,7 @@ use crate::context::UpdateContext;
 use crate::drawing::Drawing;
 use crate::font::{EvalParameters, Font};
 use crate::html::dimensions::{BoxBounds, Position, Size};
-use crate::html::text_format::{FormatSpans, TextFormat, TextSpan};
+use crate::html::text_format::{FormatSpans, ImageAlign, TextFormat, TextImage, TextSpan};
 use crate::string::{utils as string_utils, WStr};
 use crate::tag_utils::SwfMovie;
 use gc_arena::Collect;
@@ -87,10 +87,44 @@ pub struct LayoutContext<'a, 'gc> {
 
     /// The total width of the text field being laid out.
     max_bounds: Twips,
+
+    /// The images embedded in the text being laid out.
+    images: &'a [TextImage],
+
+    /// The index of the next image to be placed.
+    next_image: usize,
+
+    /// The boxes of the images placed so far.
+    ///
+    /// These are kept apart from `boxes` so that line fixup only sees text.
+    image_boxes: Vec<LayoutBox<'gc>>,
+
+    /// The space taken up by the images placed so far, which text flows
+    /// around.
+    floats: Vec<ImageFloat>,
+}
+
+/// The space taken up by an image at the side of the text.
+struct ImageFloat {
+    align: ImageAlign,
+
+    /// The top of the image.
+    top: Twips,
+
+    /// The bottom of the image, including its vertical space.
+    bottom: Twips,
+
+    /// The width of the image, including its horizontal space.
+    width: Twips,
 }
 
 impl<'a, 'gc> LayoutContext<'a, 'gc> {
-    fn new(movie: Arc<SwfMovie>, max_bounds: Twips, text: &'a WStr) -> Self {
+    fn new(
+        movie: Arc<SwfMovie>,
+        max_bounds: Twips,
+        text: &'a WStr,
+        images: &'a [TextImage],
+    ) -> Self {
         Self {
             movie,
             cursor: Default::default(),
@@ -104,6 +138,153 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
             current_line: 0,
             current_line_span: Default::default(),
             max_bounds,
+            images,
+            next_image: 0,
+            image_boxes: Vec::new(),
+            floats: Vec::new(),
+        }
+    }
+
+    /// The widths taken up by images at the left and right of a line that
+    /// starts at the given height.
+    fn float_insets(&self, y: Twips) -> (Twips, Twips) {
+        let mut insets = (Twips::ZERO, Twips::ZERO);
+        for float in &self.floats {
+            if float.top <= y && y < float.bottom {
+                match float.align {
+                    ImageAlign::Left => insets.0 += float.width,
+                    ImageAlign::Right => insets.1 += float.width,
+                }
+            }
+        }
+        insets
+    }
+
+    /// The text position of the next image to be placed, if any.
+    fn next_image_position(&self) -> Option<usize> {
+        self.images.get(self.next_image).map(|image| image.position)
+    }
+
+    /// Place the next image at the side of the text.
+    ///
+    /// An image embedded at the start of a line is placed next to that line;
+    /// otherwise it is placed next to the line below. Images at the same side
+    /// are placed next to each other.
+    fn append_image(&mut self) {
+        let image = match self.images.get(self.next_image) {
+            Some(image) => image,
+            None => return,
+        };
+
+        let width = Twips::from_pixels(image.width.unwrap_or_default());
+        let height = Twips::from_pixels(image.height.unwrap_or_default());
+        let hspace = Twips::from_pixels(image.hspace);
+        let vspace = Twips::from_pixels(image.vspace);
+
+        let top = if self.is_start_of_line() {
+            self.cursor.y()
+        } else {
+            self.cursor.y() + self.max_font_size + self.line_leading_adjustment()
+        };
+        let (left_inset, right_inset) = self.float_insets(top);
+        let x = match image.align {
+            ImageAlign::Left => left_inset,
+            ImageAlign::Right => self.max_bounds - right_inset - width,
+        };
+
+        let mut image_box = LayoutBox::from_image(self.next_image);
+        image_box.bounds =
+            BoxBounds::from_position_and_size((x, top).into(), (width, height).into());
+        self.image_boxes.push(image_box);
+        self.floats.push(ImageFloat {
+            align: image.align,
+            top,
+            bottom: top + height + vspace,
+            width: width + hspace,
+        });
+        self.next_image += 1;
+    }
+
+    /// Lay out a piece of text without line breaks, wrapping it if needed.
+    #[allow(clippy::too_many_arguments)]
+    fn layout_text(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        font: Font<'gc>,
+        params: EvalParameters,
+        text: &'a WStr,
+        start: usize,
+        span: &TextSpan,
+        is_word_wrap: bool,
+    ) {
+        let mut last_breakpoint = 0;
+
+        if is_word_wrap {
+            let (mut width, mut offset) = self.wrap_dimensions(span);
+
+            while let Some(breakpoint) = font.wrap_line(
+                &text[last_breakpoint..],
+                params,
+                width,
+                offset,
+                self.is_start_of_line(),
+            ) {
+                // This ensures that the space causing the line break
+                // is included in the line it broke.
+                let next_breakpoint =
+                    string_utils::next_char_boundary(text, last_breakpoint + breakpoint);
+
+                // If text doesn't fit at the start of a line, it
+                // won't fit on the next either, abort and put the
+                // whole text on the line (will be cut-off). This
+                // can happen for small text fields with single
+                // characters.
+                if breakpoint == 0 && self.is_start_of_line() {
+                    break;
+                } else if breakpoint == 0 {
+                    self.newline(context, text, next_breakpoint, span);
+
+                    let next_dim = self.wrap_dimensions(span);
+
+                    width = next_dim.0;
+                    offset = next_dim.1;
+
+                    if last_breakpoint >= text.len() {
+                        break;
+                    } else {
+                        continue;
+                    }
+                }
+
+                self.append_text(
+                    &text[last_breakpoint..next_breakpoint],
+                    start + last_breakpoint,
+                    start + next_breakpoint,
+                    span,
+                );
+
+                last_breakpoint = next_breakpoint;
+                if last_breakpoint >= text.len() {
+                    break;
+                }
+
+                self.newline(context, text, next_breakpoint, span);
+                let next_dim = self.wrap_dimensions(span);
+
+                width = next_dim.0;
+                offset = next_dim.1;
+            }
+        }
+
+        let span_end = text.len();
+
+        if last_breakpoint < span_end {
+            self.append_text(
+                &text[last_breakpoint..span_end],
+                start + last_breakpoint,
+                start + span_end,
+                span,
+            );
         }
     }
 
@@ -257,9 +438,11 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
 
         let mut line_bounds = line_bounds.unwrap_or_default();
 
+        let (left_inset, right_inset) = self.float_insets(self.cursor.y());
         let left_adjustment =
-            Self::left_alignment_offset(&self.current_line_span, self.is_first_line);
-        let right_adjustment = Twips::from_pixels(self.current_line_span.right_margin);
+            Self::left_alignment_offset(&self.current_line_span, self.is_first_line) + left_inset;
+        let right_adjustment =
+            Twips::from_pixels(self.current_line_span.right_margin) + right_inset;
 
         let misalignment =
             self.max_bounds - left_adjustment - right_adjustment - line_bounds.width();
@@ -559,8 +742,10 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
     ///
     /// Offsets returned by this function should not be considered final;
     fn wrap_dimensions(&self, current_span: &TextSpan) -> (Twips, Twips) {
-        let width = self.max_bounds - Twips::from_pixels(self.current_line_span.right_margin);
-        let offset = Self::left_alignment_offset(current_span, self.is_first_line);
+        let (left_inset, right_inset) = self.float_insets(self.cursor.y());
+        let width =
+            self.max_bounds - Twips::from_pixels(self.current_line_span.right_margin) - right_inset;
+        let offset = Self::left_alignment_offset(current_span, self.is_first_line) + left_inset;
 
         (width, offset + self.cursor.x())
     }
@@ -579,7 +764,16 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
                 .map(|ls| (fs.displayed_text(), fs.displayed_text().len(), ls)),
         );
 
-        (self.boxes, self.exterior_bounds.unwrap_or_default())
+        let mut bounds = self.exterior_bounds;
+        for image_box in &self.image_boxes {
+            bounds = Some(match bounds {
+                Some(bounds) => bounds + image_box.bounds,
+                None => image_box.bounds,
+            });
+        }
+
+        self.boxes.append(&mut self.image_boxes);
+        (self.boxes, bounds.unwrap_or_default())
     }
 
     fn is_start_of_line(&self) -> bool {
@@ -603,7 +797,8 @@ pub struct LayoutBox<'gc> {
 
 /// Represents different content modes of a given `LayoutBox`.
 ///
-/// Currently, a `LayoutBox` can contain `Text`, `Bullet`s, or a `Drawing`.
+/// Currently, a `LayoutBox` can contain `Text`, `Bullet`s, a `Drawing`, or an
+/// `Image`.
 #[derive(Clone, Debug, Collect)]
 #[collect(no_drop)]
 pub enum LayoutContent<'gc> {
@@ -659,6 +854,12 @@ pub enum LayoutContent<'gc> {
     /// layout box's bounds. The size of those bounds do not affect the
     /// rendering of the drawing.
     Drawing(Drawing),
+
+    /// A layout box containing an image embedded in the text.
+    ///
+    /// The image is the one at the given index of the `FormatSpans` that
+    /// generated this layout box. It is rendered by its text field.
+    Image(usize),
 }
 
 impl<'gc> LayoutBox<'gc> {
@@ -702,6 +903,14 @@ impl<'gc> LayoutBox<'gc> {
         }
     }
 
+    /// Construct an image box.
+    pub fn from_image(index: usize) -> Self {
+        Self {
+            bounds: Default::default(),
+            content: LayoutContent::Image(index),
+        }
+    }
+
     /// Construct a new layout hierarchy from text spans.
     ///
     /// The returned bounds will include both the text bounds itself, as well
@@ -714,7 +923,8 @@ impl<'gc> LayoutBox<'gc> {
         is_word_wrap: bool,
         is_device_font: bool,
     ) -> (Vec<LayoutBox<'gc>>, BoxBounds<Twips>) {
-        let mut layout_context = LayoutContext::new(movie, bounds, fs.displayed_text());
+        let mut layout_context =
+            LayoutContext::new(movie, bounds, fs.displayed_text(), fs.images());
 
         for (span_start, _end, span_text, span) in fs.iter_spans() {
             if let Some(font) = layout_context.resolve_font(context, span, is_device_font) {
@@ -742,81 +952,43 @@ impl<'gc> LayoutBox<'gc> {
 
                     let start = span_start + slice_start;
 
-                    let mut last_breakpoint = 0;
-
-                    if is_word_wrap {
-                        let (mut width, mut offset) = layout_context.wrap_dimensions(span);
-
-                        while let Some(breakpoint) = font.wrap_line(
-                            &text[last_breakpoint..],
+                    // Images are placed between the text before and after them.
+                    let mut piece_start = 0;
+                    while let Some(position) = layout_context
+                        .next_image_position()
+                        .filter(|position| *position <= start + text.len())
+                    {
+                        let piece_end = position.saturating_sub(start).max(piece_start);
+                        layout_context.layout_text(
+                            context,
+                            font,
                             params,
-                            width,
-                            offset,
-                            layout_context.is_start_of_line(),
-                        ) {
-                            // This ensures that the space causing the line break
-                            // is included in the line it broke.
-                            let next_breakpoint = string_utils::next_char_boundary(
-                                text,
-                                last_breakpoint + breakpoint,
-                            );
-
-                            // If text doesn't fit at the start of a line, it
-                            // won't fit on the next either, abort and put the
-                            // whole text on the line (will be cut-off). This
-                            // can happen for small text fields with single
-                            // characters.
-                            if breakpoint == 0 && layout_context.is_start_of_line() {
-                                break;
-                            } else if breakpoint == 0 {
-                                layout_context.newline(context, text, next_breakpoint, span);
-
-                                let next_dim = layout_context.wrap_dimensions(span);
-
-                                width = next_dim.0;
-                                offset = next_dim.1;
-
-                                if last_breakpoint >= text.len() {
-                                    break;
-                                } else {
-                                    continue;
-                                }
-                            }
-
-                            layout_context.append_text(
-                                &text[last_breakpoint..next_breakpoint],
-                                start + last_breakpoint,
-                                start + next_breakpoint,
-                                span,
-                            );
-
-                            last_breakpoint = next_breakpoint;
-                            if last_breakpoint >= text.len() {
-                                break;
-                            }
-
-                            layout_context.newline(context, text, next_breakpoint, span);
-                            let next_dim = layout_context.wrap_dimensions(span);
-
-                            width = next_dim.0;
-                            offset = next_dim.1;
-                        }
-                    }
-
-                    let span_end = text.len();
-
-                    if last_breakpoint < span_end {
-                        layout_context.append_text(
-                            &text[last_breakpoint..span_end],
-                            start + last_breakpoint,
-                            start + span_end,
+                            &text[piece_start..piece_end],
+                            start + piece_start,
                             span,
+                            is_word_wrap,
                         );
+                        layout_context.append_image();
+                        piece_start = piece_end;
                     }
+
+                    layout_context.layout_text(
+                        context,
+                        font,
+                        params,
+                        &text[piece_start..],
+                        start + piece_start,
+                        span,
+                        is_word_wrap,
+                    );
                 }
             }
         }
 
+        while layout_context.next_image_position().is_some() {
+            layout_context.append_image();
+        }
+
         layout_context.end_layout(context, fs)
     }
 
@@ -861,7 +1033,7 @@ impl<'gc> LayoutBox<'gc> {
                 *params,
                 swf::Color::from_rgb(color.to_rgb(), 0xFF),
             )),
-            LayoutContent::Drawing(..) => None,
+            LayoutContent::Drawing(..) | LayoutContent::Image(..) => None,
         }
     }
 
@@ -871,6 +1043,15 @@ impl<'gc> LayoutBox<'gc> {
             LayoutContent::Text { .. } => None,
             LayoutContent::Bullet { .. } => None,
             LayoutContent::Drawing(drawing) => Some(drawing),
+            LayoutContent::Image(..) => None,
+        }
+    }
+
+    /// Returns the index of the image this box contains, if it has one.
+    pub fn as_image(&self) -> Option<usize> {
+        match &self.content {
+            LayoutContent::Image(index) => Some(*index),
+            _ => None,
         }
     }
 
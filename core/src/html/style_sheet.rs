@@ -0,0 +1,252 @@
+//! CSS style sheets applied to HTML text
+
+use crate::html::TextFormat;
+use crate::string::{WStr, WString};
+use gc_arena::Collect;
+use std::collections::HashMap;
+
+/// A rule of a style sheet, as parsed by `parse_css`.
+///
+/// Property names are converted to the camel case names that `StyleSheet`
+/// objects use, e.g. `font-size` becomes `fontSize`.
+pub type CssRule = (WString, Vec<(WString, WString)>);
+
+/// Parse the text of a CSS style sheet into its rules.
+///
+/// A rule with several selectors is returned once for each of them. Returns
+/// `None` if the text is not valid CSS.
+pub fn parse_css(css: &WStr) -> Option<Vec<CssRule>> {
+    let css = css.to_utf8_lossy();
+    let css = strip_comments(&css)?;
+    let mut rules = Vec::new();
+    let mut rest = css.as_str();
+
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return Some(rules);
+        }
+
+        let open = rest.find('{')?;
+        let close = open + rest[open..].find('}')?;
+        let selectors = &rest[..open];
+        let body = &rest[open + 1..close];
+        rest = &rest[close + 1..];
+
+        if selectors.contains('}') || body.contains('{') {
+            return None;
+        }
+
+        let declarations: Vec<_> = body
+            .split(';')
+            .filter_map(|declaration| {
+                let (name, value) = declaration.split_once(':')?;
+                let name = name.trim();
+                if name.is_empty() {
+                    return None;
+                }
+
+                Some((
+                    WString::from_utf8(&camel_case(name)),
+                    WString::from_utf8(value.trim()),
+                ))
+            })
+            .collect();
+
+        for selector in selectors.split(',') {
+            let selector = selector.trim();
+            if selector.is_empty() {
+                return None;
+            }
+
+            rules.push((WString::from_utf8(selector), declarations.clone()));
+        }
+    }
+}
+
+fn strip_comments(css: &str) -> Option<String> {
+    let mut result = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        result.push_str(&rest[..start]);
+        let end = rest[start + 2..].find("*/")?;
+        rest = &rest[start + 2 + end + 2..];
+    }
+    result.push_str(rest);
+    Some(result)
+}
+
+/// Convert a CSS property name such as `font-size` to `fontSize`.
+fn camel_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '-' {
+            upper = true;
+        } else if upper {
+            result.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// How an element is laid out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CssDisplay {
+    /// The element's text continues the current line.
+    Inline,
+
+    /// The element's text is followed by a line break.
+    Block,
+
+    /// The element's text is not displayed.
+    None,
+}
+
+/// The formatting of an element with a style.
+#[derive(Clone, Debug, Default)]
+pub struct CssStyle {
+    pub format: TextFormat,
+    pub display: Option<CssDisplay>,
+}
+
+impl CssStyle {
+    /// Build a style from the camel case properties of a style object.
+    ///
+    /// Properties with values that Flash Player doesn't understand are
+    /// ignored.
+    pub fn from_properties(mut property: impl FnMut(&'static str) -> Option<WString>) -> Self {
+        let mut property = |name| property(name).map(|value| value.to_utf8_lossy().into_owned());
+        let mut format = TextFormat::default();
+
+        if let Some(color) = property("color") {
+            format.color = color
+                .strip_prefix('#')
+                .filter(|hex| hex.len() == 6)
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .map(|rgb| swf::Color::from_rgb(rgb, 0));
+        }
+
+        if let Some(font_family) = property("fontFamily") {
+            format.font = Some(WString::from_utf8(match font_family.trim() {
+                "sans-serif" => "_sans",
+                "serif" => "_serif",
+                "mono" => "_typewriter",
+                font_family => font_family,
+            }));
+        }
+
+        format.size = property("fontSize").and_then(|size| parse_length(&size));
+
+        format.italic = property("fontStyle").and_then(|style| match style.as_str() {
+            "italic" => Some(true),
+            "normal" => Some(false),
+            _ => None,
+        });
+
+        format.bold = property("fontWeight").and_then(|weight| match weight.as_str() {
+            "bold" => Some(true),
+            "normal" => Some(false),
+            _ => None,
+        });
+
+        format.kerning = property("kerning").and_then(|kerning| match kerning.as_str() {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        });
+
+        format.leading = property("leading").and_then(|leading| parse_length(&leading));
+        format.letter_spacing =
+            property("letterSpacing").and_then(|spacing| parse_length(&spacing));
+        format.left_margin = property("marginLeft").and_then(|margin| parse_length(&margin));
+        format.right_margin = property("marginRight").and_then(|margin| parse_length(&margin));
+        format.indent = property("textIndent").and_then(|indent| parse_length(&indent));
+
+        format.align = property("textAlign").and_then(|align| match align.as_str() {
+            "left" => Some(swf::TextAlign::Left),
+            "center" => Some(swf::TextAlign::Center),
+            "right" => Some(swf::TextAlign::Right),
+            "justify" => Some(swf::TextAlign::Justify),
+            _ => None,
+        });
+
+        format.underline =
+            property("textDecoration").and_then(|decoration| match decoration.as_str() {
+                "underline" => Some(true),
+                "none" => Some(false),
+                _ => None,
+            });
+
+        let display = property("display").and_then(|display| match display.as_str() {
+            "inline" => Some(CssDisplay::Inline),
+            "block" => Some(CssDisplay::Block),
+            "none" => Some(CssDisplay::None),
+            _ => None,
+        });
+
+        Self { format, display }
+    }
+
+    /// Apply this style on top of another.
+    pub fn apply_to(&self, format: TextFormat) -> TextFormat {
+        self.format.clone().mix_with(format)
+    }
+}
+
+/// Parse a length such as `12`, `12px` or `12pt`.
+fn parse_length(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let value = value
+        .strip_suffix("px")
+        .or_else(|| value.strip_suffix("pt"))
+        .unwrap_or(value);
+    value.trim().parse().ok()
+}
+
+/// The styles of a `StyleSheet` object, by lowercase name.
+///
+/// Names are either tag names such as `p`, class names such as `.heading`,
+/// or the `a:link`, `a:hover` and `a:active` link states.
+#[derive(Clone, Debug, Default, Collect)]
+#[collect(require_static)]
+pub struct StyleSheet {
+    styles: HashMap<WString, CssStyle>,
+}
+
+impl StyleSheet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, name: &WStr, style: CssStyle) {
+        self.styles.insert(name.to_ascii_lowercase(), style);
+    }
+
+    pub fn get(&self, name: &WStr) -> Option<&CssStyle> {
+        self.styles.get(&name.to_ascii_lowercase())
+    }
+
+    /// The style of the elements of a tag, combined with the style of its
+    /// class.
+    pub fn element_style(&self, tag: &WStr, class: Option<&WStr>) -> Option<CssStyle> {
+        let tag_style = self.get(tag);
+        let class_style = class.and_then(|class| {
+            let mut name = WString::from_utf8(".");
+            name.push_str(class);
+            self.get(&name)
+        });
+
+        match (tag_style, class_style) {
+            (None, None) => None,
+            (Some(style), None) | (None, Some(style)) => Some(style.clone()),
+            (Some(tag_style), Some(class_style)) => Some(CssStyle {
+                format: class_style.apply_to(tag_style.format.clone()),
+                display: class_style.display.or(tag_style.display),
+            }),
+        }
+    }
+}
@@ -1,7 +1,8 @@
 //! Tests for HTML module
 
 use crate::html::dimensions::{BoxBounds, Position, Size};
-use crate::html::text_format::{FormatSpans, TextFormat, TextSpan};
+use crate::html::style_sheet::{parse_css, CssDisplay, CssStyle, StyleSheet};
+use crate::html::text_format::{FormatSpans, ImageAlign, TextFormat, TextImage, TextSpan};
 use crate::string::{WStr, WString};
 use swf::{Rectangle, Twips};
 
@@ -864,3 +865,165 @@ fn formatspans_replace_text_degenerate() {
     assert_eq!((0, 1), fs.get_span_boundaries(0, 5));
     assert_eq!((1, 2), fs.get_span_boundaries(5, 9));
 }
+
+fn css_rules(css: &str) -> Option<Vec<(String, Vec<(String, String)>)>> {
+    parse_css(&WString::from_utf8(css)).map(|rules| {
+        rules
+            .into_iter()
+            .map(|(selector, declarations)| {
+                (
+                    selector.to_string(),
+                    declarations
+                        .into_iter()
+                        .map(|(name, value)| (name.to_string(), value.to_string()))
+                        .collect(),
+                )
+            })
+            .collect()
+    })
+}
+
+fn css_declarations(list: &[(&str, &str)]) -> Vec<(String, String)> {
+    list.iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn parse_css_rules() {
+    assert_eq!(
+        css_rules(
+            "p { font-size: 12px; color: #FF0000 } /* headings */ .title, h1 {font-weight:bold;}"
+        ),
+        Some(vec![
+            (
+                "p".to_string(),
+                css_declarations(&[("fontSize", "12px"), ("color", "#FF0000")])
+            ),
+            (
+                ".title".to_string(),
+                css_declarations(&[("fontWeight", "bold")])
+            ),
+            (
+                "h1".to_string(),
+                css_declarations(&[("fontWeight", "bold")])
+            ),
+        ])
+    );
+    assert_eq!(css_rules(""), Some(vec![]));
+}
+
+#[test]
+fn parse_css_invalid() {
+    assert_eq!(css_rules("p { color: #FF0000"), None);
+    assert_eq!(css_rules("p color: #FF0000 }"), None);
+    assert_eq!(css_rules("p { color: #FF0000 } /* unterminated"), None);
+    assert_eq!(css_rules(", { color: #FF0000 }"), None);
+}
+
+#[test]
+fn css_style_properties() {
+    let style = CssStyle::from_properties(|name| {
+        let value = match name {
+            "color" => "#336699",
+            "fontFamily" => "sans-serif",
+            "fontSize" => "14px",
+            "fontWeight" => "bold",
+            "textAlign" => "center",
+            "textDecoration" => "underline",
+            "marginLeft" => "4",
+            "display" => "block",
+            "fontStyle" => "oblique",
+            _ => return None,
+        };
+        Some(WString::from_utf8(value))
+    });
+
+    assert_eq!(style.format.color, Some(swf::Color::from_rgb(0x336699, 0)));
+    assert_eq!(style.format.font, Some(WString::from_utf8("_sans")));
+    assert_eq!(style.format.size, Some(14.0));
+    assert_eq!(style.format.bold, Some(true));
+    assert_eq!(style.format.italic, None);
+    assert_eq!(style.format.align, Some(swf::TextAlign::Center));
+    assert_eq!(style.format.underline, Some(true));
+    assert_eq!(style.format.left_margin, Some(4.0));
+    assert_eq!(style.display, Some(CssDisplay::Block));
+}
+
+#[test]
+fn style_sheet_element_style() {
+    let mut style_sheet = StyleSheet::new();
+    let mut tag_style = CssStyle::default();
+    tag_style.format.size = Some(10.0);
+    tag_style.format.bold = Some(true);
+    style_sheet.insert(WStr::from_units(b"P"), tag_style);
+    let mut class_style = CssStyle::default();
+    class_style.format.size = Some(20.0);
+    style_sheet.insert(WStr::from_units(b".Big"), class_style);
+
+    let style = style_sheet
+        .element_style(WStr::from_units(b"p"), Some(WStr::from_units(b"big")))
+        .unwrap();
+    assert_eq!(style.format.size, Some(20.0));
+    assert_eq!(style.format.bold, Some(true));
+    assert!(style_sheet
+        .element_style(WStr::from_units(b"span"), None)
+        .is_none());
+}
+
+#[test]
+fn from_html_condense_white() {
+    let html = WStr::from_units(b"<p>  Hello \n\t world  </p><p> again</p>");
+
+    let condensed = FormatSpans::from_html(html, TextFormat::default(), None, true, true);
+    assert_eq!(condensed.text(), WStr::from_units(b"Hello world \nagain\n"));
+
+    let spans = FormatSpans::from_html(html, TextFormat::default(), None, true, false);
+    assert_eq!(
+        spans.text(),
+        WStr::from_units(b"  Hello \n\t world  \n again\n")
+    );
+}
+
+#[test]
+fn from_html_img() {
+    let html = WStr::from_units(
+        b"Hi <img src=\"smile\" width=\"16\" height=\"12\" align=\"right\" hspace=\"2\" id=\"s1\">there",
+    );
+    let spans = FormatSpans::from_html(html, TextFormat::default(), None, false, false);
+
+    assert_eq!(spans.text(), WStr::from_units(b"Hi there"));
+    assert_eq!(
+        spans.images(),
+        &[TextImage {
+            position: 3,
+            src: WString::from_utf8("smile"),
+            id: WString::from_utf8("s1"),
+            width: Some(16.0),
+            height: Some(12.0),
+            align: ImageAlign::Right,
+            hspace: 2.0,
+            vspace: 8.0,
+        }][..]
+    );
+
+    let roundtrip =
+        FormatSpans::from_html(&spans.to_html(), TextFormat::default(), None, false, false);
+    assert_eq!(roundtrip.text(), spans.text());
+    assert_eq!(roundtrip.images(), spans.images());
+}
+
+#[test]
+fn replace_text_moves_images() {
+    let html = WStr::from_units(b"ab<img src=\"x\">cd<img src=\"y\">ef");
+    let mut spans = FormatSpans::from_html(html, TextFormat::default(), None, false, false);
+
+    spans.replace_text(0, 1, WStr::from_units(b"123"), None);
+    let positions: Vec<usize> = spans.images().iter().map(|i| i.position).collect();
+    assert_eq!(positions, vec![4, 6]);
+
+    spans.replace_text(3, 6, WStr::empty(), None);
+    let sources: Vec<WString> = spans.images().iter().map(|i| i.src.clone()).collect();
+    assert_eq!(sources, vec![WString::from_utf8("y")]);
+    assert_eq!(spans.images()[0].position, 3);
+}
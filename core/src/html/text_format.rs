@@ -2,6 +2,7 @@
 
 use crate::context::UpdateContext;
 use crate::html::iterators::TextSpanIter;
+use crate::html::style_sheet::{CssDisplay, StyleSheet};
 use crate::string::{Integer, Units, WStr, WString};
 use crate::tag_utils::SwfMovie;
 use gc_arena::Collect;
@@ -12,6 +13,26 @@ use std::collections::VecDeque;
 use std::fmt::Write;
 use std::sync::Arc;
 
+/// Collapse each run of whitespace in HTML text into a single space.
+///
+/// Whitespace at the start of the text is removed entirely if the text
+/// follows a space or starts a line, as for `TextField.condenseWhite`.
+fn condense_whitespace(src: &WStr, at_line_start: bool) -> WString {
+    let is_white = |ch: u16| matches!(ch, 0x20 | 0x09 | 0x0A | 0x0D);
+    let mut result = WString::with_capacity(src.len(), src.is_wide());
+    let mut after_white = at_line_start;
+    for ch in src.iter() {
+        if !is_white(ch) {
+            result.push(ch);
+            after_white = false;
+        } else if !after_white {
+            result.push_byte(b' ');
+            after_white = true;
+        }
+    }
+    result
+}
+
 /// Replace HTML entities with their equivalent characters.
 ///
 /// Unknown entities will be ignored.
@@ -515,6 +536,76 @@ impl TextSpan {
     }
 }
 
+/// How an image embedded in text is aligned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageAlign {
+    /// The image is placed at the left edge of the text, with text flowing
+    /// around its right side.
+    Left,
+
+    /// The image is placed at the right edge of the text, with text flowing
+    /// around its left side.
+    Right,
+}
+
+/// An image embedded in text with an `<img>` tag.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextImage {
+    /// The position in the text where the image was embedded.
+    pub position: usize,
+
+    /// The export name of a library symbol, or the URL of an image or SWF to
+    /// load.
+    pub src: WString,
+
+    /// The name used to get the image's display object from ActionScript.
+    pub id: WString,
+
+    /// The width of the image in pixels, or `None` to use its natural width.
+    pub width: Option<f64>,
+
+    /// The height of the image in pixels, or `None` to use its natural
+    /// height.
+    pub height: Option<f64>,
+
+    /// The side of the text that the image is placed at.
+    pub align: ImageAlign,
+
+    /// The horizontal space in pixels between the image and the text.
+    pub hspace: f64,
+
+    /// The vertical space in pixels between the image and the text.
+    pub vspace: f64,
+}
+
+impl TextImage {
+    /// Build an image from the attributes of an `<img>` tag.
+    ///
+    /// Returns `None` if the tag has no source.
+    fn from_attributes(
+        position: usize,
+        attribute: impl Fn(&'static [u8]) -> Option<WString>,
+    ) -> Option<Self> {
+        let src = attribute(b"src").filter(|src| !src.is_empty())?;
+        let number = |name| attribute(name).and_then(|value| value.parse::<f64>().ok());
+        let align = match attribute(b"align") {
+            Some(align) if align.eq_ignore_case(WStr::from_units(b"right")) => ImageAlign::Right,
+            _ => ImageAlign::Left,
+        };
+
+        Some(Self {
+            position,
+            src,
+            id: attribute(b"id").unwrap_or_default(),
+            width: number(b"width"),
+            height: number(b"height"),
+            align,
+            hspace: number(b"hspace").unwrap_or(8.0),
+            vspace: number(b"vspace").unwrap_or(8.0),
+        })
+    }
+}
+
 /// Struct which contains text formatted by `TextSpan`s.
 #[derive(Clone, Debug, Collect)]
 #[collect(require_static)]
@@ -523,6 +614,9 @@ pub struct FormatSpans {
     displayed_text: WString,
     spans: Vec<TextSpan>,
     default_format: TextFormat,
+
+    /// The images embedded in the text, in text order.
+    images: Vec<TextImage>,
 }
 
 impl Default for FormatSpans {
@@ -538,6 +632,7 @@ impl FormatSpans {
             displayed_text: WString::new(),
             spans: vec![TextSpan::default()],
             default_format: TextFormat::default(),
+            images: Vec::new(),
         }
     }
 
@@ -549,6 +644,7 @@ impl FormatSpans {
             displayed_text: WString::new(),
             spans: spans.to_vec(),
             default_format: Default::default(),
+            images: Vec::new(),
         }
     }
 
@@ -559,19 +655,32 @@ impl FormatSpans {
             displayed_text: WString::new(),
             spans: vec![TextSpan::with_length_and_format(len, format.clone())],
             default_format: format,
+            images: Vec::new(),
         }
     }
 
     /// Lower an HTML tree into text-span representation.
     ///
-    /// This is the "legacy" implementation of this process: it only looks for
-    /// a handful of presentational attributes in the HTML tree to generate
-    /// styling. There's also a `lower_from_css` that respects both
-    /// presentational markup and CSS stylesheets.
-    pub fn from_html(html: &WStr, default_format: TextFormat, is_multiline: bool) -> Self {
+    /// Styling comes from a handful of presentational tags and attributes in
+    /// the HTML tree, and from the tag, class and `a:link` styles of a
+    /// stylesheet if one is given. Styles apply on top of presentational
+    /// markup.
+    ///
+    /// With `condense_white`, runs of whitespace in the text (including line
+    /// breaks) are displayed as a single space.
+    pub fn from_html(
+        html: &WStr,
+        default_format: TextFormat,
+        style_sheet: Option<&StyleSheet>,
+        is_multiline: bool,
+        condense_white: bool,
+    ) -> Self {
         let mut format_stack = vec![default_format.clone()];
+        // The display of each open element, if its style sets one.
+        let mut display_stack: Vec<Option<CssDisplay>> = Vec::new();
         let mut text = WString::new();
         let mut spans: Vec<TextSpan> = Vec::new();
+        let mut images = Vec::new();
 
         // quick_xml::Reader requires a [u8] slice, but doesn't actually care about Unicode;
         // this means we can pass the raw buffer in the Latin1 case.
@@ -631,7 +740,8 @@ impl FormatSpans {
                         })
                     };
                     let mut format = format_stack.last().unwrap().clone();
-                    match &e.name().to_ascii_lowercase()[..] {
+                    let tag_name = e.name().to_ascii_lowercase();
+                    match &tag_name[..] {
                         b"br" => {
                             if is_multiline {
                                 text.push_byte(b'\n');
@@ -643,6 +753,17 @@ impl FormatSpans {
                             // Skip push to `format_stack`.
                             continue;
                         }
+                        b"img" => {
+                            // `<img>` tags are never closed.
+                            if let Some(start) = opened_starts.pop() {
+                                opened_buffer.truncate(start);
+                            }
+
+                            images.extend(TextImage::from_attributes(text.len(), &attribute));
+
+                            // Skip push to `format_stack`.
+                            continue;
+                        }
                         b"sbr" => {
                             // TODO: <sbr> tags do not add a newline, but rather only break
                             // the format span.
@@ -759,10 +880,40 @@ impl FormatSpans {
                         }
                         _ => {}
                     }
+
+                    let mut display = None;
+                    if let Some(style_sheet) = style_sheet {
+                        let tag_name = decode_to_wstr(&tag_name);
+                        if let Some(style) =
+                            style_sheet.element_style(&tag_name, attribute(b"class").as_deref())
+                        {
+                            format = style.apply_to(format);
+                            display = style.display;
+                        }
+
+                        if tag_name == WStr::from_units(b"a") {
+                            if let Some(style) = style_sheet.get(WStr::from_units(b"a:link")) {
+                                format = style.apply_to(format);
+                            }
+                        }
+                    }
+
                     format_stack.push(format);
+                    display_stack.push(display);
                 }
+                Ok(Event::Text(_)) if display_stack.contains(&Some(CssDisplay::None)) => {}
                 Ok(Event::Text(e)) if !e.is_empty() => {
-                    let e = decode_to_wstr(e.escaped());
+                    let mut e = decode_to_wstr(e.escaped());
+                    if condense_white {
+                        let at_line_start = text.is_empty()
+                            || text.ends_with(b' ')
+                            || text.ends_with(b'\n')
+                            || text.ends_with(b'\r');
+                        e = condense_whitespace(&e, at_line_start);
+                        if e.is_empty() {
+                            continue;
+                        }
+                    }
                     let e = process_html_entity(&e).unwrap_or(e);
                     let format = format_stack.last().unwrap().clone();
                     text.push_str(&e);
@@ -782,18 +933,20 @@ impl FormatSpans {
                         None => continue,
                     }
 
-                    match &e.name().to_ascii_lowercase()[..] {
-                        b"br" | b"sbr" => {
+                    let display = match &e.name().to_ascii_lowercase()[..] {
+                        b"br" | b"sbr" | b"img" => {
                             // Skip pop from `format_stack`.
                             continue;
                         }
-                        b"p" | b"li" if is_multiline => {
-                            text.push_byte(b'\n');
-                            if let Some(span) = spans.last_mut() {
-                                span.span_length += 1;
-                            }
+                        b"p" | b"li" => display_stack.pop().flatten().or(Some(CssDisplay::Block)),
+                        _ => display_stack.pop().flatten(),
+                    };
+
+                    if is_multiline && display == Some(CssDisplay::Block) {
+                        text.push_byte(b'\n');
+                        if let Some(span) = spans.last_mut() {
+                            span.span_length += 1;
                         }
-                        _ => {}
                     }
                     format_stack.pop();
                 }
@@ -811,6 +964,7 @@ impl FormatSpans {
             displayed_text: WString::new(),
             spans,
             default_format,
+            images,
         }
     }
 
@@ -818,6 +972,15 @@ impl FormatSpans {
         &self.default_format
     }
 
+    /// The images embedded in the text, in text order.
+    pub fn images(&self) -> &[TextImage] {
+        &self.images
+    }
+
+    pub fn images_mut(&mut self) -> &mut [TextImage] {
+        &mut self.images
+    }
+
     pub fn set_default_format(&mut self, tf: TextFormat) {
         self.default_format = tf.mix_with(self.default_format.clone());
     }
@@ -1139,6 +1302,16 @@ impl FormatSpans {
 
         self.text = new_string;
 
+        // Images in the replaced text are removed, and images after it move
+        // with the text.
+        self.images
+            .retain(|image| image.position <= from || image.position >= to);
+        for image in &mut self.images {
+            if image.position > from {
+                image.position = image.position - (to - from) + with.len();
+            }
+        }
+
         self.normalize();
     }
 
@@ -1157,23 +1330,39 @@ impl FormatSpans {
     }
 
     pub fn to_html(&self) -> WString {
+        let mut images = self.images.iter().peekable();
+        let mut push_span_text = |state: &mut FormatState, start: usize, text: &WStr| {
+            let mut pos = 0;
+            while let Some(image) = images.next_if(|image| image.position < start + text.len()) {
+                let image_pos = image.position.saturating_sub(start).max(pos);
+                state.push_text(&text[pos..image_pos]);
+                state.push_image(image);
+                pos = image_pos;
+            }
+            state.push_text(&text[pos..]);
+        };
+
         let mut spans = self.iter_spans();
-        let mut state = if let Some((_start, _end, text, span)) = spans.next() {
+        let mut state = if let Some((start, _end, text, span)) = spans.next() {
             let mut state = FormatState {
                 result: WString::new(),
                 font_stack: VecDeque::new(),
                 span,
                 is_open: false,
             };
-            state.push_text(text);
+            push_span_text(&mut state, start, text);
             state
         } else {
             return WString::new();
         };
 
-        for (_start, _end, text, span) in spans {
+        for (start, _end, text, span) in spans {
             state.set_span(span);
-            state.push_text(text);
+            push_span_text(&mut state, start, text);
+        }
+
+        for image in images {
+            state.push_image(image);
         }
 
         state.close_tags();
@@ -1415,6 +1604,31 @@ impl<'a> FormatState<'a> {
         self.span = span;
     }
 
+    fn push_image(&mut self, image: &TextImage) {
+        self.open_tags();
+        let _ = write!(self.result, "<IMG SRC=\"{}\"", image.src);
+        if let Some(width) = image.width {
+            let _ = write!(self.result, " WIDTH=\"{width}\"");
+        }
+        if let Some(height) = image.height {
+            let _ = write!(self.result, " HEIGHT=\"{height}\"");
+        }
+        let _ = write!(
+            self.result,
+            " ALIGN=\"{}\" HSPACE=\"{}\" VSPACE=\"{}\"",
+            match image.align {
+                ImageAlign::Left => "left",
+                ImageAlign::Right => "right",
+            },
+            image.hspace,
+            image.vspace
+        );
+        if !image.id.is_empty() {
+            let _ = write!(self.result, " ID=\"{}\"", image.id);
+        }
+        self.result.push_byte(b'>');
+    }
+
     fn push_text(&mut self, text: &WStr) {
         for (i, text) in text.split(&[b'\n', b'\r'][..]).enumerate() {
             self.open_tags();
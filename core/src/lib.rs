@@ -35,7 +35,11 @@ pub mod loader;
 mod locale;
 mod player;
 mod prelude;
+pub mod profiler;
+pub mod socket;
+pub mod streams;
 pub mod string;
+pub mod system_properties;
 pub mod tag_utils;
 pub mod timer;
 mod types;
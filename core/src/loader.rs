@@ -16,17 +16,18 @@ use crate::avm2::{
 use crate::backend::navigator::{OwnedFuture, Request};
 use crate::context::{ActionQueue, ActionType, UpdateContext};
 use crate::display_object::{
-    Bitmap, DisplayObject, TDisplayObject, TDisplayObjectContainer, TInteractiveObject,
+    Bitmap, DisplayObject, EditText, TDisplayObject, TDisplayObjectContainer, TInteractiveObject,
 };
 use crate::events::ClipEvent;
 use crate::frame_lifecycle::catchup_display_object_to_frame;
 use crate::limits::ExecutionLimit;
 use crate::player::Player;
+use crate::streams::NetStream;
 use crate::string::AvmString;
 use crate::tag_utils::SwfMovie;
 use crate::vminterface::Instantiator;
 use encoding_rs::UTF_8;
-use gc_arena::{Collect, CollectionContext};
+use gc_arena::{Collect, CollectionContext, MutationContext};
 use generational_arena::{Arena, Index};
 use ruffle_render::utils::{determine_jpeg_tag_format, JpegTagFormat};
 use std::fmt;
@@ -164,6 +165,9 @@ pub enum Error {
     #[error("Non-sound loader spawned as sound loader")]
     NotSoundLoader,
 
+    #[error("Non-NetStream loader spawned as NetStream loader")]
+    NotNetStreamLoader,
+
     #[error("Could not fetch: {0}")]
     FetchError(String),
 
@@ -227,7 +231,8 @@ impl<'gc> LoadManager<'gc> {
             | Loader::LoadVars { self_handle, .. }
             | Loader::LoadURLLoader { self_handle, .. }
             | Loader::SoundAvm1 { self_handle, .. }
-            | Loader::SoundAvm2 { self_handle, .. } => *self_handle = Some(handle),
+            | Loader::SoundAvm2 { self_handle, .. }
+            | Loader::NetStream { self_handle, .. } => *self_handle = Some(handle),
         }
         handle
     }
@@ -416,6 +421,26 @@ impl<'gc> LoadManager<'gc> {
         loader.sound_loader_avm2(player, request)
     }
 
+    /// Kick off a load of a file to be played by a `NetStream`.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_netstream(
+        &mut self,
+        gc_context: MutationContext<'gc, '_>,
+        player: Weak<Mutex<Player>>,
+        target_stream: NetStream<'gc>,
+        request: Request,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::NetStream {
+            self_handle: None,
+            target_stream,
+        };
+        let handle = self.add_loader(loader);
+        target_stream.set_pending_load(gc_context, handle);
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.netstream_loader(player, request)
+    }
+
     /// Process tags on all loaders in the Parsing phase.
     ///
     /// Returns true if *all* loaders finished preloading.
@@ -469,6 +494,7 @@ pub enum LoaderStatus {
 pub enum MovieLoaderEventHandler<'gc> {
     Avm1Broadcast(Object<'gc>),
     Avm2LoaderInfo(Avm2Object<'gc>),
+    EditTextImage(EditText<'gc>),
 }
 
 /// A struct that holds garbage-collected pointers for asynchronous code.
@@ -563,6 +589,16 @@ pub enum Loader<'gc> {
         /// The target AVM1 object to load the audio into.
         target_object: Avm2Object<'gc>,
     },
+
+    /// Loader that is loading a file to be played by a `NetStream`.
+    NetStream {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<Handle>,
+
+        /// The stream that will play the file.
+        target_stream: NetStream<'gc>,
+    },
 }
 
 impl<'gc> Loader<'gc> {
@@ -1276,6 +1312,41 @@ impl<'gc> Loader<'gc> {
         })
     }
 
+    /// Creates a future for a NetStream load call.
+    fn netstream_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        request: Request,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::NetStream { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
+            }
+            _ => return Box::pin(async { Err(Error::NotNetStreamLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let fetch = player.lock().unwrap().navigator().fetch(request);
+            let response = fetch.await;
+
+            player.lock().unwrap().update(|uc| {
+                let loader = uc.load_manager.get_loader(handle);
+                let stream = match loader {
+                    Some(&Loader::NetStream { target_stream, .. }) => target_stream,
+                    None => return Err(Error::Cancelled),
+                    _ => return Err(Error::NotNetStreamLoader),
+                };
+
+                stream.load_complete(uc, handle, response.map(|response| response.body));
+                Ok(())
+            })
+        })
+    }
+
     /// Report a movie loader start event to script code.
     fn movie_loader_start(handle: Index, uc: &mut UpdateContext<'_, 'gc, '_>) -> Result<(), Error> {
         let me = uc.load_manager.get_loader_mut(handle);
@@ -1315,7 +1386,7 @@ impl<'gc> Loader<'gc> {
                     );
                 }
             }
-            None => {}
+            Some(MovieLoaderEventHandler::EditTextImage(_)) | None => {}
         }
 
         Ok(())
@@ -1513,7 +1584,7 @@ impl<'gc> Loader<'gc> {
                     );
                 }
             }
-            None => {}
+            Some(MovieLoaderEventHandler::EditTextImage(_)) | None => {}
         }
 
         Ok(())
@@ -1556,6 +1627,9 @@ impl<'gc> Loader<'gc> {
                         uc.gc_context,
                     );
             }
+            Some(MovieLoaderEventHandler::EditTextImage(edit_text)) => {
+                edit_text.image_loaded(uc, clip);
+            }
             None => {}
         }
 
@@ -1626,7 +1700,7 @@ impl<'gc> Loader<'gc> {
                     );
                 }
             }
-            None => {}
+            Some(MovieLoaderEventHandler::EditTextImage(_)) | None => {}
         }
 
         if let Loader::Movie { loader_status, .. } = uc.load_manager.get_loader_mut(handle).unwrap()
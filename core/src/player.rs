@@ -1,7 +1,6 @@
 use crate::avm1::Attribute;
 use crate::avm1::Avm1;
 use crate::avm1::Object;
-use crate::avm1::SystemProperties;
 use crate::avm1::VariableDumper;
 use crate::avm1::{Activation, ActivationIdentifier};
 use crate::avm1::{ScriptObject, TObject, Value};
@@ -36,7 +35,11 @@ use crate::limits::ExecutionLimit;
 use crate::loader::{LoadBehavior, LoadManager};
 use crate::locale::get_current_date_time;
 use crate::prelude::*;
+use crate::profiler::Profiler;
+use crate::socket::Sockets;
+use crate::streams::StreamManager;
 use crate::string::AvmString;
+use crate::system_properties::SystemProperties;
 use crate::tag_utils::SwfMovie;
 use crate::timer::Timers;
 use crate::vminterface::Instantiator;
@@ -135,6 +138,12 @@ struct GcRootData<'gc> {
     /// Timed callbacks created with `setInterval`/`setTimeout`.
     timers: Timers<'gc>,
 
+    /// Connections opened by `XMLSocket` and `flash.net.Socket`.
+    sockets: Sockets<'gc>,
+
+    /// Media streams played by `NetStream`.
+    stream_manager: StreamManager<'gc>,
+
     current_context_menu: Option<ContextMenuState<'gc>>,
 
     /// External interface for (for example) JavaScript <-> ActionScript interaction
@@ -165,6 +174,8 @@ impl<'gc> GcRootData<'gc> {
         &mut HashMap<String, Avm2Object<'gc>>,
         &mut Vec<EditText<'gc>>,
         &mut Timers<'gc>,
+        &mut Sockets<'gc>,
+        &mut StreamManager<'gc>,
         &mut Option<ContextMenuState<'gc>>,
         &mut ExternalInterface<'gc>,
         &mut AudioManager<'gc>,
@@ -181,6 +192,8 @@ impl<'gc> GcRootData<'gc> {
             &mut self.avm2_shared_objects,
             &mut self.unbound_text_fields,
             &mut self.timers,
+            &mut self.sockets,
+            &mut self.stream_manager,
             &mut self.current_context_menu,
             &mut self.external_interface,
             &mut self.audio_manager,
@@ -287,6 +300,9 @@ pub struct Player {
     /// The root SWF URL provided to ActionScript. If None,
     /// the actual loaded url will be used
     spoofed_url: Option<String>,
+
+    /// The script profiler, while script execution is being profiled.
+    profiler: Option<Profiler>,
 }
 
 impl Player {
@@ -399,7 +415,7 @@ impl Player {
             let version_string = activation
                 .context
                 .system
-                .get_version_string(activation.context.avm1);
+                .get_version_string(activation.context.avm1.player_version());
             object.define_value(
                 activation.context.gc_context,
                 "$version",
@@ -509,6 +525,8 @@ impl Player {
             });
 
             self.update_timers(dt);
+            self.update_sockets();
+            self.update_streams(dt);
             self.audio.tick();
         }
     }
@@ -941,6 +959,16 @@ impl Player {
                 }
             }
 
+            // Text fields listen for editing shortcuts, such as copy and paste.
+            if !key_press_handled {
+                if let PlayerEvent::KeyDown { key_code, .. } = event {
+                    if let Some(text) = context.focus_tracker.get().and_then(|o| o.as_edit_text()) {
+                        key_press_handled = text.handle_editing_shortcut(context, key_code)
+                            == ClipEventResult::Handled;
+                    }
+                }
+            }
+
             if context.is_action_script_3() {
                 if let PlayerEvent::KeyDown { key_code, key_char }
                 | PlayerEvent::KeyUp { key_code, key_char } = event
@@ -1468,6 +1496,7 @@ impl Player {
                 stage,
                 clip_depth_stack: vec![],
                 allow_mask: true,
+                bitmap_cache_dependencies: None,
             };
 
             stage.render(&mut render_context);
@@ -1666,6 +1695,8 @@ impl Player {
                 avm2_shared_objects,
                 unbound_text_fields,
                 timers,
+                sockets,
+                stream_manager,
                 current_context_menu,
                 external_interface,
                 audio_manager,
@@ -1699,6 +1730,8 @@ impl Player {
                 avm2_shared_objects,
                 unbound_text_fields,
                 timers,
+                sockets,
+                stream_manager,
                 current_context_menu,
                 needs_render: &mut self.needs_render,
                 avm1,
@@ -1714,6 +1747,7 @@ impl Player {
                 frame_rate: &mut self.frame_rate,
                 actions_since_timeout_check: &mut self.actions_since_timeout_check,
                 frame_phase: &mut self.frame_phase,
+                profiler: &mut self.profiler,
             };
 
             let old_frame_rate = *update_context.frame_rate;
@@ -1823,6 +1857,19 @@ impl Player {
             self.mutate_with_update_context(|context| Timers::update_timers(context, dt));
     }
 
+    /// Dispatch the events of all socket connections which have changed since
+    /// the last update.
+    pub fn update_sockets(&mut self) {
+        // The closure is needed for `'gc` to be inferred for each call.
+        #[allow(clippy::redundant_closure)]
+        self.mutate_with_update_context(|context| Sockets::update_sockets(context));
+    }
+
+    /// Advance the playback of all `NetStream`s by `dt` milliseconds.
+    pub fn update_streams(&mut self, dt: f64) {
+        self.mutate_with_update_context(|context| StreamManager::tick(context, dt));
+    }
+
     /// Returns whether this player consumes mouse wheel events.
     /// Used by web to prevent scrolling.
     pub fn should_prevent_scrolling(&mut self) -> bool {
@@ -1870,6 +1917,21 @@ impl Player {
             arena: Rc::downgrade(&self.gc_arena),
         }
     }
+
+    /// Start profiling script execution, discarding any previous profile.
+    pub fn start_profiling(&mut self) {
+        self.profiler = Some(Profiler::new());
+    }
+
+    /// Stop profiling script execution, returning the recorded profile.
+    pub fn stop_profiling(&mut self) -> Option<Profiler> {
+        self.profiler.take()
+    }
+
+    /// The profile recorded so far, if script execution is being profiled.
+    pub fn profiler(&self) -> Option<&Profiler> {
+        self.profiler.as_ref()
+    }
 }
 
 /// Player factory, which can be used to configure the aspects of a Ruffle player.
@@ -1896,6 +1958,8 @@ pub struct PlayerBuilder {
     warn_on_unsupported_content: bool,
     load_behavior: LoadBehavior,
     spoofed_url: Option<String>,
+    system_properties: SystemProperties,
+    profiling: bool,
 }
 
 impl PlayerBuilder {
@@ -1931,6 +1995,8 @@ impl PlayerBuilder {
             warn_on_unsupported_content: true,
             load_behavior: LoadBehavior::Streaming,
             spoofed_url: None,
+            system_properties: SystemProperties::default(),
+            profiling: false,
         }
     }
 
@@ -2050,6 +2116,19 @@ impl PlayerBuilder {
         self
     }
 
+    /// Sets the system properties reported to ActionScript through
+    /// `System.capabilities` and `flash.system.Capabilities`.
+    pub fn with_system_properties(mut self, system_properties: SystemProperties) -> Self {
+        self.system_properties = system_properties;
+        self
+    }
+
+    /// Sets whether script execution is profiled from the start.
+    pub fn with_profiling(mut self, profiling: bool) -> Self {
+        self.profiling = profiling;
+        self
+    }
+
     /// Builds the player, wiring up the backends and configuring the specified settings.
     pub fn build(self) -> Arc<Mutex<Player>> {
         use crate::backend::*;
@@ -2117,7 +2196,7 @@ impl PlayerBuilder {
 
                 // Misc. state
                 rng: SmallRng::seed_from_u64(get_current_date_time().timestamp_millis() as u64),
-                system: SystemProperties::default(),
+                system: self.system_properties,
                 transform_stack: TransformStack::new(),
                 instance_counter: 0,
                 player_version: NEWEST_PLAYER_VERSION,
@@ -2127,6 +2206,7 @@ impl PlayerBuilder {
                 self_reference: self_ref.clone(),
                 load_behavior: self.load_behavior,
                 spoofed_url: self.spoofed_url.clone(),
+                profiler: self.profiling.then(Profiler::new),
 
                 // GC data
                 gc_arena: Rc::new(RefCell::new(GcArena::new(
@@ -2152,6 +2232,8 @@ impl PlayerBuilder {
                                 avm2_shared_objects: HashMap::new(),
                                 stage: Stage::empty(gc_context, self.fullscreen),
                                 timers: Timers::new(),
+                                sockets: Sockets::new(),
+                                stream_manager: StreamManager::new(),
                                 unbound_text_fields: Vec::new(),
                             },
                         ),
@@ -0,0 +1,433 @@
+//! Script execution profiling
+//!
+//! When enabled, the profiler is told about every AVM1 and AVM2 function that
+//! is entered and exited, including native methods and top-level AVM1 action
+//! blocks. From that it keeps per-function call counts and timings, the total
+//! time spent in each distinct call stack, and a trace of every individual
+//! call.
+
+use crate::tag_utils::SwfSlice;
+use instant::Instant;
+use serde_json::json;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+use swf::avm2::types::AbcFile;
+
+/// The maximum number of calls recorded for the Chrome trace export.
+///
+/// Per-function statistics and folded stacks keep being updated once this is
+/// reached; only the call-by-call trace is truncated.
+const MAX_TRACE_EVENTS: usize = 1_000_000;
+
+/// The virtual machine a profiled function belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScriptVm {
+    Avm1,
+    Avm2,
+}
+
+impl ScriptVm {
+    fn name(self) -> &'static str {
+        match self {
+            ScriptVm::Avm1 => "avm1",
+            ScriptVm::Avm2 => "avm2",
+        }
+    }
+}
+
+/// What identifies a function between calls, for functions which aren't
+/// identified by their name.
+///
+/// Keys hold on to the movie or ABC file their function is defined in, so
+/// the addresses they are compared by can't be reused for other code while
+/// the profiler runs.
+#[derive(Clone)]
+pub enum FunctionKey {
+    /// A native function, by the address of its code.
+    Native(usize),
+
+    /// An AVM1 function, by the actions it runs.
+    Actions(SwfSlice),
+
+    /// An AVM2 method, by its index in the ABC file that defines it.
+    AbcMethod(Rc<AbcFile>, u32),
+}
+
+impl PartialEq for FunctionKey {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Native(a), Self::Native(b)) => a == b,
+            (Self::Actions(a), Self::Actions(b)) => {
+                Arc::ptr_eq(&a.movie, &b.movie) && a.start == b.start && a.end == b.end
+            }
+            (Self::AbcMethod(a, a_index), Self::AbcMethod(b, b_index)) => {
+                Rc::ptr_eq(a, b) && a_index == b_index
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for FunctionKey {}
+
+impl Hash for FunctionKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Self::Native(address) => address.hash(state),
+            Self::Actions(slice) => {
+                Arc::as_ptr(&slice.movie).hash(state);
+                slice.start.hash(state);
+                slice.end.hash(state);
+            }
+            Self::AbcMethod(abc, index) => {
+                Rc::as_ptr(abc).hash(state);
+                index.hash(state);
+            }
+        }
+    }
+}
+
+/// Timing statistics for a single function.
+#[derive(Clone, Debug)]
+pub struct FunctionProfile {
+    /// The name of the function.
+    pub name: String,
+
+    /// The virtual machine the function was run by.
+    pub vm: ScriptVm,
+
+    /// How many times the function was called.
+    pub calls: u64,
+
+    /// The time spent in the function, including the functions it called.
+    ///
+    /// Time spent in recursive calls is only counted once.
+    pub inclusive: Duration,
+
+    /// The time spent in the function itself, excluding the functions it
+    /// called.
+    pub exclusive: Duration,
+}
+
+/// A distinct call stack, as a node in the tree of all recorded call stacks.
+struct StackNode {
+    function: usize,
+    parent: Option<usize>,
+    children: HashMap<usize, usize>,
+    exclusive: Duration,
+}
+
+/// A function call which has not returned yet.
+struct Frame {
+    stack_node: usize,
+    start: Instant,
+    children: Duration,
+}
+
+/// A single completed call, for the Chrome trace export.
+struct TraceEvent {
+    function: usize,
+    start: Duration,
+    duration: Duration,
+}
+
+/// Records where script execution time is spent.
+pub struct Profiler {
+    /// The instant profiling started.
+    start: Instant,
+
+    functions: Vec<FunctionProfile>,
+
+    /// Functions identified by a `FunctionKey`.
+    keyed_functions: HashMap<(ScriptVm, FunctionKey), usize>,
+
+    /// Functions identified by their name alone, for each `ScriptVm`.
+    named_functions: [HashMap<String, usize>; 2],
+
+    /// How many frames of each function are currently active, so that
+    /// recursive calls are not counted twice in inclusive time.
+    active_calls: Vec<u32>,
+
+    stack_nodes: Vec<StackNode>,
+    root_stacks: HashMap<usize, usize>,
+
+    frames: Vec<Frame>,
+    trace: Vec<TraceEvent>,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            functions: Vec::new(),
+            keyed_functions: HashMap::new(),
+            named_functions: Default::default(),
+            active_calls: Vec::new(),
+            stack_nodes: Vec::new(),
+            root_stacks: HashMap::new(),
+            frames: Vec::new(),
+            trace: Vec::new(),
+        }
+    }
+
+    /// Record entering a function which is identified by a key.
+    ///
+    /// `name` is only called the first time a key is seen, so it may be
+    /// expensive to compute.
+    pub fn enter_keyed(&mut self, vm: ScriptVm, key: FunctionKey, name: impl FnOnce() -> String) {
+        let key = (vm, key);
+        let function = match self.keyed_functions.get(&key) {
+            Some(function) => *function,
+            None => {
+                let function = self.add_function(vm, name());
+                self.keyed_functions.insert(key, function);
+                function
+            }
+        };
+
+        self.enter_function(function);
+    }
+
+    /// Record entering a function which is identified by its name.
+    pub fn enter_named(&mut self, vm: ScriptVm, name: &str) {
+        let function = match self.named_functions[vm as usize].get(name) {
+            Some(function) => *function,
+            None => {
+                let function = self.add_function(vm, name.to_string());
+                self.named_functions[vm as usize].insert(name.to_string(), function);
+                function
+            }
+        };
+
+        self.enter_function(function);
+    }
+
+    /// Record returning from the function entered most recently.
+    pub fn exit(&mut self) {
+        let frame = match self.frames.pop() {
+            Some(frame) => frame,
+            None => return,
+        };
+
+        let now = Instant::now();
+        let elapsed = now.duration_since(frame.start);
+        let exclusive = elapsed.saturating_sub(frame.children);
+        let node = &mut self.stack_nodes[frame.stack_node];
+        node.exclusive += exclusive;
+        let function = node.function;
+
+        self.active_calls[function] -= 1;
+        let profile = &mut self.functions[function];
+        profile.exclusive += exclusive;
+        if self.active_calls[function] == 0 {
+            profile.inclusive += elapsed;
+        }
+
+        if let Some(parent) = self.frames.last_mut() {
+            parent.children += elapsed;
+        }
+
+        if self.trace.len() < MAX_TRACE_EVENTS {
+            self.trace.push(TraceEvent {
+                function,
+                start: frame.start.duration_since(self.start),
+                duration: elapsed,
+            });
+        }
+    }
+
+    /// The statistics of every function called so far, in the order they
+    /// were first called.
+    pub fn functions(&self) -> &[FunctionProfile] {
+        &self.functions
+    }
+
+    /// Export the time spent in each call stack in the "folded stacks"
+    /// format used by flamegraph tools.
+    ///
+    /// Each line holds the names of the functions in a stack, outermost
+    /// first and separated by semicolons, followed by the time spent in that
+    /// stack in microseconds.
+    pub fn to_folded_stacks(&self) -> String {
+        let mut output = String::new();
+        for (index, node) in self.stack_nodes.iter().enumerate() {
+            let micros = node.exclusive.as_micros();
+            if micros == 0 {
+                continue;
+            }
+
+            let mut names = Vec::new();
+            let mut current = Some(index);
+            while let Some(index) = current {
+                let node = &self.stack_nodes[index];
+                // Semicolons separate frames, so they can't be part of a name.
+                names.push(self.functions[node.function].name.replace(';', ":"));
+                current = node.parent;
+            }
+            names.reverse();
+
+            output.push_str(&names.join(";"));
+            output.push(' ');
+            output.push_str(&micros.to_string());
+            output.push('\n');
+        }
+
+        output
+    }
+
+    /// Export every recorded call in the Chrome trace event format, as
+    /// understood by `chrome://tracing` and Perfetto.
+    pub fn to_chrome_trace(&self) -> String {
+        let events: Vec<_> = self
+            .trace
+            .iter()
+            .map(|event| {
+                let function = &self.functions[event.function];
+                json!({
+                    "name": function.name,
+                    "cat": function.vm.name(),
+                    "ph": "X",
+                    "ts": event.start.as_secs_f64() * 1_000_000.0,
+                    "dur": event.duration.as_secs_f64() * 1_000_000.0,
+                    "pid": 1,
+                    "tid": 1,
+                })
+            })
+            .collect();
+
+        json!({
+            "traceEvents": events,
+            "displayTimeUnit": "ms",
+        })
+        .to_string()
+    }
+
+    fn add_function(&mut self, vm: ScriptVm, name: String) -> usize {
+        self.functions.push(FunctionProfile {
+            name,
+            vm,
+            calls: 0,
+            inclusive: Duration::ZERO,
+            exclusive: Duration::ZERO,
+        });
+        self.active_calls.push(0);
+        self.functions.len() - 1
+    }
+
+    fn enter_function(&mut self, function: usize) {
+        let parent = self.frames.last().map(|frame| frame.stack_node);
+        let new_stack_node = self.stack_nodes.len();
+        let children = match parent {
+            Some(parent) => &mut self.stack_nodes[parent].children,
+            None => &mut self.root_stacks,
+        };
+        let stack_node = *children.entry(function).or_insert(new_stack_node);
+        if stack_node == new_stack_node {
+            self.stack_nodes.push(StackNode {
+                function,
+                parent,
+                children: HashMap::new(),
+                exclusive: Duration::ZERO,
+            });
+        }
+
+        self.functions[function].calls += 1;
+        self.active_calls[function] += 1;
+        self.frames.push(Frame {
+            stack_node,
+            start: Instant::now(),
+            children: Duration::ZERO,
+        });
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::profiler::{FunctionKey, Profiler, ScriptVm};
+    use crate::tag_utils::{SwfMovie, SwfSlice};
+    use std::sync::Arc;
+
+    #[test]
+    fn counts_calls_per_function() {
+        let mut profiler = Profiler::new();
+        profiler.enter_named(ScriptVm::Avm2, "main");
+        for _ in 0..3 {
+            profiler.enter_keyed(ScriptVm::Avm2, FunctionKey::Native(1), || {
+                "helper".to_string()
+            });
+            profiler.enter_keyed(ScriptVm::Avm2, FunctionKey::Native(1), || unreachable!());
+            profiler.exit();
+            profiler.exit();
+        }
+        profiler.exit();
+
+        let functions = profiler.functions();
+        assert_eq!(functions.len(), 2);
+        assert_eq!(functions[0].name, "main");
+        assert_eq!(functions[0].calls, 1);
+        assert_eq!(functions[1].name, "helper");
+        assert_eq!(functions[1].calls, 6);
+        assert!(functions[0].inclusive >= functions[1].inclusive);
+        assert!(functions[1].inclusive >= functions[1].exclusive);
+    }
+
+    #[test]
+    fn actions_are_keyed_by_movie() {
+        let movie = Arc::new(SwfMovie::empty(10));
+        let other_movie = Arc::new(SwfMovie::empty(10));
+        let mut profiler = Profiler::new();
+        for movie in [&movie, &movie, &other_movie] {
+            let key = FunctionKey::Actions(SwfSlice::empty(movie.clone()));
+            profiler.enter_keyed(ScriptVm::Avm1, key, || "function".to_string());
+            profiler.exit();
+        }
+
+        let functions = profiler.functions();
+        assert_eq!(functions.len(), 2);
+        assert_eq!(functions[0].calls, 2);
+        assert_eq!(functions[1].calls, 1);
+    }
+
+    #[test]
+    fn same_name_in_different_vms() {
+        let mut profiler = Profiler::new();
+        profiler.enter_named(ScriptVm::Avm1, "[Init]");
+        profiler.exit();
+        profiler.enter_named(ScriptVm::Avm2, "[Init]");
+        profiler.exit();
+        profiler.enter_named(ScriptVm::Avm1, "[Init]");
+        profiler.exit();
+
+        let functions = profiler.functions();
+        assert_eq!(functions.len(), 2);
+        assert_eq!(functions[0].vm, ScriptVm::Avm1);
+        assert_eq!(functions[0].calls, 2);
+        assert_eq!(functions[1].vm, ScriptVm::Avm2);
+    }
+
+    #[test]
+    fn chrome_trace_is_valid_json() {
+        let mut profiler = Profiler::new();
+        profiler.enter_named(ScriptVm::Avm1, "outer");
+        profiler.enter_named(ScriptVm::Avm1, "inner");
+        profiler.exit();
+        profiler.exit();
+
+        let trace: serde_json::Value = serde_json::from_str(&profiler.to_chrome_trace()).unwrap();
+        let events = trace["traceEvents"].as_array().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["name"], "inner");
+        assert_eq!(events[1]["name"], "outer");
+        assert_eq!(events[1]["cat"], "avm1");
+    }
+}
@@ -0,0 +1,779 @@
+//! Socket connections for `XMLSocket` and `flash.net.Socket`
+//!
+//! Connections are opened through the `NavigatorBackend`, which reports back
+//! through a channel. That channel is drained once per tick, so socket events
+//! are always dispatched at the same point of the frame loop.
+//!
+//! Movies loaded over the network must be granted access by a socket policy
+//! file before they may connect anywhere. As in Flash Player, the master
+//! policy file on port 843 is asked for first, and then the destination port
+//! itself.
+
+use crate::avm1::{Avm1, Object as Avm1Object, Value as Avm1Value};
+use crate::avm2::object::{EventObject as Avm2EventObject, TObject as _};
+use crate::avm2::{
+    Activation as Avm2Activation, Avm2, Error as Avm2Error, Multiname as Avm2Multiname,
+    Namespace as Avm2Namespace, Object as Avm2Object,
+};
+use crate::context::UpdateContext;
+use crate::string::AvmString;
+use gc_arena::{Collect, CollectionContext};
+use generational_arena::{Arena, Index};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::time::Duration;
+use url::Url;
+
+pub type SocketHandle = Index;
+
+/// The port Flash Player asks for the master socket policy file on.
+const MASTER_POLICY_PORT: u16 = 843;
+
+/// How long to wait for the master policy file before asking the destination
+/// port instead.
+const MASTER_POLICY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// The request a policy server answers with its policy file.
+const POLICY_FILE_REQUEST: &[u8] = b"<policy-file-request/>\0";
+
+/// The outcome of a connection attempt, as reported by the backend.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// The connection was made.
+    Connected,
+
+    /// The connection was refused, or the host could not be reached.
+    Failed,
+
+    /// The connection could not be made before the timeout elapsed.
+    TimedOut,
+}
+
+/// Something that happened on a connection, as reported by the backend.
+#[derive(Debug)]
+pub enum SocketAction {
+    /// The outcome of the connection attempt.
+    Connect(SocketHandle, ConnectionState),
+
+    /// Data was received from the peer.
+    Data(SocketHandle, Vec<u8>),
+
+    /// The peer closed the connection.
+    Close(SocketHandle),
+}
+
+/// The script object a connection belongs to.
+#[derive(Clone, Copy, Collect)]
+#[collect(no_drop)]
+pub enum SocketTarget<'gc> {
+    /// An AVM1 `XMLSocket`.
+    Avm1XmlSocket(Avm1Object<'gc>),
+
+    /// An AVM2 `flash.net.Socket`.
+    Avm2Socket(Avm2Object<'gc>),
+
+    /// An AVM2 `flash.net.XMLSocket`.
+    Avm2XmlSocket(Avm2Object<'gc>),
+}
+
+impl<'gc> SocketTarget<'gc> {
+    fn ptr_eq(a: Self, b: Self) -> bool {
+        match (a, b) {
+            (Self::Avm1XmlSocket(a), Self::Avm1XmlSocket(b)) => Avm1Object::ptr_eq(a, b),
+            (Self::Avm2Socket(a), Self::Avm2Socket(b))
+            | (Self::Avm2XmlSocket(a), Self::Avm2XmlSocket(b)) => Avm2Object::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+/// Why a connection could not be made.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ConnectError {
+    /// The host refused or could not be reached.
+    Io,
+
+    /// No policy file allowed the connection, or it timed out.
+    Security,
+}
+
+enum Connection<'gc> {
+    /// A connection opened by script.
+    Script(ScriptSocket<'gc>),
+
+    /// A connection made to fetch the policy file for a script connection.
+    Policy(PolicyRequest),
+}
+
+struct ScriptSocket<'gc> {
+    target: SocketTarget<'gc>,
+    host: String,
+    port: u16,
+    timeout: Duration,
+    connected: bool,
+
+    /// Data sent to the backend to be written to the connection. Dropping it
+    /// closes the connection.
+    sender: Option<Sender<Vec<u8>>>,
+
+    /// Data received by an `XMLSocket` which does not form a complete
+    /// message yet.
+    buffer: Vec<u8>,
+}
+
+struct PolicyRequest {
+    /// The script connection waiting for this policy file.
+    socket: SocketHandle,
+
+    /// The port the policy file was asked for on.
+    port: u16,
+
+    /// The domain of the movie that wants to connect.
+    domain: String,
+
+    response: Vec<u8>,
+
+    /// Keeps the connection open until the policy file has been received.
+    _sender: Sender<Vec<u8>>,
+}
+
+/// Holds all socket connections of the player.
+pub struct Sockets<'gc> {
+    connections: Arena<Connection<'gc>>,
+    sender: Sender<SocketAction>,
+    receiver: Receiver<SocketAction>,
+}
+
+unsafe impl<'gc> Collect for Sockets<'gc> {
+    fn trace(&self, cc: CollectionContext) {
+        for (_, connection) in self.connections.iter() {
+            if let Connection::Script(socket) = connection {
+                socket.target.trace(cc);
+            }
+        }
+    }
+}
+
+impl<'gc> Sockets<'gc> {
+    pub fn new() -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            connections: Arena::new(),
+            sender,
+            receiver,
+        }
+    }
+
+    /// The host a socket connects to when script does not name one, which is
+    /// the host the root movie was loaded from.
+    pub fn default_host(context: &UpdateContext<'_, 'gc, '_>) -> String {
+        context
+            .swf
+            .url()
+            .and_then(|url| Url::parse(url).ok())
+            .and_then(|url| url.host_str().map(str::to_owned))
+            .unwrap_or_else(|| "localhost".to_string())
+    }
+
+    /// Start connecting `target` to `host` on `port`, closing any connection
+    /// it already has.
+    ///
+    /// The outcome is reported to `target` once the backend knows it.
+    pub fn connect(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        target: SocketTarget<'gc>,
+        host: String,
+        port: u16,
+        timeout: Duration,
+    ) {
+        context.sockets.close(target);
+
+        let policy_domain = policy_domain(context.swf.url());
+        let handle = context
+            .sockets
+            .connections
+            .insert(Connection::Script(ScriptSocket {
+                target,
+                host,
+                port,
+                timeout,
+                connected: false,
+                sender: None,
+                buffer: Vec::new(),
+            }));
+
+        match policy_domain {
+            Some(domain) => Self::request_policy(context, handle, MASTER_POLICY_PORT, domain),
+            None => Self::open(context, handle),
+        }
+    }
+
+    /// Close the connection of `target`, including one that is still being
+    /// made.
+    ///
+    /// Returns `false` if `target` has no connection.
+    pub fn close(&mut self, target: SocketTarget<'gc>) -> bool {
+        let handle = match self.find(target) {
+            Some(handle) => handle,
+            None => return false,
+        };
+
+        self.connections.remove(handle);
+        self.connections.retain(|_, connection| {
+            !matches!(connection, Connection::Policy(request) if request.socket == handle)
+        });
+        true
+    }
+
+    /// Whether `target` is connected.
+    pub fn is_connected(&self, target: SocketTarget<'gc>) -> bool {
+        matches!(
+            self.find(target)
+                .and_then(|handle| self.connections.get(handle)),
+            Some(Connection::Script(ScriptSocket {
+                connected: true,
+                ..
+            }))
+        )
+    }
+
+    /// Send `data` over the connection of `target`.
+    ///
+    /// Returns `false` if `target` is not connected.
+    pub fn send(&mut self, target: SocketTarget<'gc>, data: Vec<u8>) -> bool {
+        match self
+            .find(target)
+            .and_then(|handle| self.connections.get(handle))
+        {
+            Some(Connection::Script(ScriptSocket {
+                connected: true,
+                sender: Some(sender),
+                ..
+            })) => {
+                let _ = sender.send(data);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Dispatch the events for everything that happened on any connection
+    /// since the last update.
+    pub fn update_sockets(context: &mut UpdateContext<'_, 'gc, '_>) {
+        let actions: Vec<_> = context.sockets.receiver.try_iter().collect();
+        for action in actions {
+            match action {
+                SocketAction::Connect(handle, state) => Self::on_connect(context, handle, state),
+                SocketAction::Data(handle, data) => Self::on_data(context, handle, data),
+                SocketAction::Close(handle) => Self::on_close(context, handle),
+            }
+        }
+    }
+
+    fn find(&self, target: SocketTarget<'gc>) -> Option<SocketHandle> {
+        self.connections
+            .iter()
+            .find_map(|(handle, connection)| match connection {
+                Connection::Script(socket) if SocketTarget::ptr_eq(socket.target, target) => {
+                    Some(handle)
+                }
+                _ => None,
+            })
+    }
+
+    /// Ask the backend to make the connection for a script socket.
+    fn open(context: &mut UpdateContext<'_, 'gc, '_>, handle: SocketHandle) {
+        let socket = match context.sockets.connections.get_mut(handle) {
+            Some(Connection::Script(socket)) => socket,
+            _ => return,
+        };
+
+        let (sender, receiver) = channel();
+        socket.sender = Some(sender);
+        let host = socket.host.clone();
+        let (port, timeout) = (socket.port, socket.timeout);
+        let actions = context.sockets.sender.clone();
+        context
+            .navigator
+            .connect_socket(host, port, timeout, handle, receiver, actions);
+    }
+
+    /// Ask for the policy file on `port` of the host a script socket wants to
+    /// connect to.
+    fn request_policy(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        socket: SocketHandle,
+        port: u16,
+        domain: String,
+    ) {
+        let (host, timeout) = match context.sockets.connections.get(socket) {
+            Some(Connection::Script(script)) if port == MASTER_POLICY_PORT => (
+                script.host.clone(),
+                script.timeout.min(MASTER_POLICY_TIMEOUT),
+            ),
+            Some(Connection::Script(script)) => (script.host.clone(), script.timeout),
+            _ => return,
+        };
+
+        let (sender, receiver) = channel();
+        let _ = sender.send(POLICY_FILE_REQUEST.to_vec());
+        let handle = context
+            .sockets
+            .connections
+            .insert(Connection::Policy(PolicyRequest {
+                socket,
+                port,
+                domain,
+                response: Vec::new(),
+                _sender: sender,
+            }));
+        let actions = context.sockets.sender.clone();
+        context
+            .navigator
+            .connect_socket(host, port, timeout, handle, receiver, actions);
+    }
+
+    /// Act on a policy file once it has been received, or once it is clear
+    /// that there is none.
+    fn finish_policy(context: &mut UpdateContext<'_, 'gc, '_>, handle: SocketHandle) {
+        if !matches!(
+            context.sockets.connections.get(handle),
+            Some(Connection::Policy(_))
+        ) {
+            return;
+        }
+
+        let request = match context.sockets.connections.remove(handle) {
+            Some(Connection::Policy(request)) => request,
+            _ => return,
+        };
+
+        let port = match context.sockets.connections.get(request.socket) {
+            Some(Connection::Script(socket)) => socket.port,
+            _ => return,
+        };
+
+        let policy = match request.response.iter().position(|b| *b == 0) {
+            Some(end) => &request.response[..end],
+            None => &request.response[..],
+        };
+
+        if policy_allows(policy, &request.domain, port) {
+            Self::open(context, request.socket);
+        } else if request.port == MASTER_POLICY_PORT && port != MASTER_POLICY_PORT {
+            Self::request_policy(context, request.socket, port, request.domain);
+        } else {
+            Self::fail(context, request.socket, ConnectError::Security);
+        }
+    }
+
+    fn on_connect(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        handle: SocketHandle,
+        state: ConnectionState,
+    ) {
+        match context.sockets.connections.get_mut(handle) {
+            Some(Connection::Script(socket)) => match state {
+                ConnectionState::Connected => {
+                    socket.connected = true;
+                    let target = socket.target;
+                    dispatch_connect(context, target);
+                }
+                ConnectionState::Failed => Self::fail(context, handle, ConnectError::Io),
+                ConnectionState::TimedOut => Self::fail(context, handle, ConnectError::Security),
+            },
+            Some(Connection::Policy(_)) if state != ConnectionState::Connected => {
+                Self::finish_policy(context, handle)
+            }
+            _ => {}
+        }
+    }
+
+    fn on_data(context: &mut UpdateContext<'_, 'gc, '_>, handle: SocketHandle, data: Vec<u8>) {
+        match context.sockets.connections.get_mut(handle) {
+            Some(Connection::Script(socket)) => match socket.target {
+                SocketTarget::Avm2Socket(target) => receive_avm2_socket_data(context, target, data),
+                target => {
+                    socket.buffer.extend_from_slice(&data);
+                    let mut messages = Vec::new();
+                    while let Some(end) = socket.buffer.iter().position(|b| *b == 0) {
+                        let message: Vec<u8> = socket.buffer.drain(..=end).collect();
+                        messages.push(String::from_utf8_lossy(&message[..end]).into_owned());
+                    }
+
+                    for message in messages {
+                        dispatch_xml_data(context, target, &message);
+                    }
+                }
+            },
+            Some(Connection::Policy(request)) => {
+                request.response.extend_from_slice(&data);
+                if request.response.contains(&0) {
+                    Self::finish_policy(context, handle);
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn on_close(context: &mut UpdateContext<'_, 'gc, '_>, handle: SocketHandle) {
+        match context.sockets.connections.get(handle) {
+            Some(Connection::Script(socket)) => {
+                let target = socket.target;
+                context.sockets.connections.remove(handle);
+                dispatch_close(context, target);
+            }
+            Some(Connection::Policy(_)) => Self::finish_policy(context, handle),
+            None => {}
+        }
+    }
+
+    /// Give up on a script connection, and tell its target why.
+    fn fail(context: &mut UpdateContext<'_, 'gc, '_>, handle: SocketHandle, error: ConnectError) {
+        if let Some(Connection::Script(socket)) = context.sockets.connections.remove(handle) {
+            dispatch_connect_error(context, &socket, error);
+        }
+    }
+}
+
+impl<'gc> Default for Sockets<'gc> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The domain a movie loaded from `url` must be granted socket access for by
+/// a policy file, or `None` if it needs no policy file.
+fn policy_domain(url: Option<&str>) -> Option<String> {
+    let url = Url::parse(url?).ok()?;
+    match url.scheme() {
+        "http" | "https" => Some(url.host_str().unwrap_or_default().to_owned()),
+        _ => None,
+    }
+}
+
+/// Whether a socket policy file lets movies from `domain` connect to `port`.
+fn policy_allows(policy: &[u8], domain: &str, port: u16) -> bool {
+    let mut reader = Reader::from_reader(policy);
+    let mut buf = Vec::new();
+    let mut allowed = false;
+
+    loop {
+        buf.clear();
+        let element = match reader.read_event(&mut buf) {
+            Ok(Event::Start(element)) | Ok(Event::Empty(element)) => element,
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(_) => continue,
+        };
+
+        let attribute = |name: &[u8]| {
+            element
+                .attributes()
+                .filter_map(Result::ok)
+                .find(|attribute| attribute.key == name)
+                .and_then(|attribute| {
+                    attribute
+                        .unescaped_value()
+                        .ok()
+                        .map(|value| String::from_utf8_lossy(&value).into_owned())
+                })
+        };
+
+        match element.name() {
+            b"site-control"
+                if attribute(b"permitted-cross-domain-policies").as_deref() == Some("none") =>
+            {
+                return false;
+            }
+            b"allow-access-from" => {
+                let domain_matches =
+                    attribute(b"domain").map_or(false, |pattern| domain_matches(&pattern, domain));
+                let port_matches =
+                    attribute(b"to-ports").map_or(false, |ports| port_matches(&ports, port));
+                allowed |= domain_matches && port_matches;
+            }
+            _ => {}
+        }
+    }
+
+    allowed
+}
+
+/// Whether `domain` matches a policy file domain pattern, such as `*` or
+/// `*.example.com`.
+fn domain_matches(pattern: &str, domain: &str) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => {
+            domain.eq_ignore_ascii_case(suffix)
+                || domain.len() > suffix.len()
+                    && domain[domain.len() - suffix.len()..].eq_ignore_ascii_case(suffix)
+                    && domain.as_bytes()[domain.len() - suffix.len() - 1] == b'.'
+        }
+        None => domain.eq_ignore_ascii_case(pattern),
+    }
+}
+
+/// Whether `port` is in a policy file port list, such as `*` or
+/// `507,516-523`.
+fn port_matches(ports: &str, port: u16) -> bool {
+    ports.split(',').map(str::trim).any(|range| {
+        if range == "*" {
+            return true;
+        }
+
+        match range.split_once('-') {
+            Some((start, end)) => match (start.trim().parse::<u16>(), end.trim().parse::<u16>()) {
+                (Ok(start), Ok(end)) => (start..=end).contains(&port),
+                _ => false,
+            },
+            None => range.parse() == Ok(port),
+        }
+    })
+}
+
+fn ruffle_multiname<'gc>(name: &'static str) -> Avm2Multiname<'gc> {
+    Avm2Multiname::new(Avm2Namespace::Namespace("__ruffle__".into()), name)
+}
+
+/// Dispatch an AVM2 event built by `make_event` to `target`.
+fn dispatch_avm2_event<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    target: Avm2Object<'gc>,
+    make_event: impl FnOnce(&mut Avm2Activation<'_, 'gc, '_>) -> Result<Avm2Object<'gc>, Avm2Error<'gc>>,
+) {
+    let mut activation = Avm2Activation::from_nothing(context.reborrow());
+    let result = make_event(&mut activation)
+        .and_then(|event| Avm2::dispatch_event(&mut activation.context, event, target));
+    if let Err(e) = result {
+        log::error!(
+            "Encountered AVM2 error when dispatching socket event: {}",
+            e
+        );
+    }
+}
+
+fn dispatch_connect<'gc>(context: &mut UpdateContext<'_, 'gc, '_>, target: SocketTarget<'gc>) {
+    match target {
+        SocketTarget::Avm1XmlSocket(object) => {
+            let root_clip = context.stage.root_clip();
+            Avm1::run_stack_frame_for_method(
+                root_clip,
+                object,
+                context,
+                "onConnect".into(),
+                &[true.into()],
+            );
+        }
+        SocketTarget::Avm2Socket(object) | SocketTarget::Avm2XmlSocket(object) => {
+            dispatch_avm2_event(context, object, |activation| {
+                Ok(Avm2EventObject::bare_default_event(
+                    &mut activation.context,
+                    "connect",
+                ))
+            });
+        }
+    }
+}
+
+fn dispatch_connect_error<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    socket: &ScriptSocket<'gc>,
+    error: ConnectError,
+) {
+    let object = match socket.target {
+        SocketTarget::Avm1XmlSocket(object) => {
+            let root_clip = context.stage.root_clip();
+            Avm1::run_stack_frame_for_method(
+                root_clip,
+                object,
+                context,
+                "onConnect".into(),
+                &[false.into()],
+            );
+            return;
+        }
+        SocketTarget::Avm2Socket(object) | SocketTarget::Avm2XmlSocket(object) => object,
+    };
+
+    let (event_type, text, id) = match error {
+        ConnectError::Io => (
+            "ioError",
+            format!("Error #2031: Socket Error. URL: {}", socket.host),
+            2031,
+        ),
+        ConnectError::Security => (
+            "securityError",
+            format!(
+                "Error #2048: Security sandbox violation: {} cannot load data from {}:{}.",
+                context.swf.url().unwrap_or_default(),
+                socket.host,
+                socket.port
+            ),
+            2048,
+        ),
+    };
+
+    dispatch_avm2_event(context, object, |activation| {
+        let class = match error {
+            ConnectError::Io => activation.avm2().classes().ioerrorevent,
+            ConnectError::Security => activation.avm2().classes().securityerrorevent,
+        };
+        let text = AvmString::new_utf8(activation.context.gc_context, text);
+        class.construct(
+            activation,
+            &[
+                event_type.into(),
+                false.into(),
+                false.into(),
+                text.into(),
+                id.into(),
+            ],
+        )
+    });
+}
+
+fn dispatch_close<'gc>(context: &mut UpdateContext<'_, 'gc, '_>, target: SocketTarget<'gc>) {
+    match target {
+        SocketTarget::Avm1XmlSocket(object) => {
+            let root_clip = context.stage.root_clip();
+            Avm1::run_stack_frame_for_method(root_clip, object, context, "onClose".into(), &[]);
+        }
+        SocketTarget::Avm2Socket(object) | SocketTarget::Avm2XmlSocket(object) => {
+            dispatch_avm2_event(context, object, |activation| {
+                Ok(Avm2EventObject::bare_default_event(
+                    &mut activation.context,
+                    "close",
+                ))
+            });
+        }
+    }
+}
+
+/// Deliver a complete message received by an `XMLSocket`.
+fn dispatch_xml_data<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    target: SocketTarget<'gc>,
+    message: &str,
+) {
+    let message = AvmString::new_utf8(context.gc_context, message);
+    match target {
+        SocketTarget::Avm1XmlSocket(object) => {
+            let root_clip = context.stage.root_clip();
+            Avm1::run_stack_frame_for_method(
+                root_clip,
+                object,
+                context,
+                "onData".into(),
+                &[Avm1Value::String(message)],
+            );
+        }
+        SocketTarget::Avm2XmlSocket(object) => {
+            dispatch_avm2_event(context, object, |activation| {
+                let class = activation.avm2().classes().dataevent;
+                class.construct(
+                    activation,
+                    &["data".into(), false.into(), false.into(), message.into()],
+                )
+            });
+        }
+        SocketTarget::Avm2Socket(_) => {}
+    }
+}
+
+/// Append data received by a `flash.net.Socket` to its input buffer, and let
+/// it know about it.
+fn receive_avm2_socket_data<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    target: Avm2Object<'gc>,
+    data: Vec<u8>,
+) {
+    let len = data.len();
+    dispatch_avm2_event(context, target, |activation| {
+        let input = target.get_property(&ruffle_multiname("_input"), activation)?;
+        if let Some(input) = input.as_object() {
+            if let Some(mut storage) = input.as_bytearray_mut(activation.context.gc_context) {
+                // Drop what has been read already, so that the buffer doesn't
+                // keep growing over the lifetime of the connection.
+                let unread = storage.position().min(storage.len());
+                let mut bytes = storage.bytes()[unread..].to_vec();
+                bytes.extend_from_slice(&data);
+                storage.clear();
+                storage.write_bytes(&bytes)?;
+                storage.set_position(0);
+            }
+        }
+
+        activation.avm2().classes().progressevent.construct(
+            activation,
+            &[
+                "socketData".into(),
+                false.into(),
+                false.into(),
+                (len as u32).into(),
+                0.into(),
+            ],
+        )
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::socket::{domain_matches, policy_allows, policy_domain, port_matches};
+
+    #[test]
+    fn policy_domains() {
+        assert_eq!(policy_domain(Some("file:///games/chat.swf")), None);
+        assert_eq!(policy_domain(None), None);
+        assert_eq!(
+            policy_domain(Some("https://games.example.com/chat.swf")).as_deref(),
+            Some("games.example.com")
+        );
+    }
+
+    #[test]
+    fn domain_patterns() {
+        assert!(domain_matches("*", "example.com"));
+        assert!(domain_matches("example.com", "EXAMPLE.com"));
+        assert!(!domain_matches("example.com", "www.example.com"));
+        assert!(domain_matches("*.example.com", "www.example.com"));
+        assert!(domain_matches("*.example.com", "example.com"));
+        assert!(!domain_matches("*.example.com", "badexample.com"));
+    }
+
+    #[test]
+    fn port_lists() {
+        assert!(port_matches("*", 80));
+        assert!(port_matches("507,516-523", 507));
+        assert!(port_matches("507, 516-523", 520));
+        assert!(!port_matches("507,516-523", 524));
+        assert!(!port_matches("", 507));
+    }
+
+    #[test]
+    fn policy_files() {
+        let policy = br#"<?xml version="1.0"?>
+            <!DOCTYPE cross-domain-policy SYSTEM "/xml/dtds/cross-domain-policy.dtd">
+            <cross-domain-policy>
+                <site-control permitted-cross-domain-policies="master-only"/>
+                <allow-access-from domain="*.example.com" to-ports="5000-5010"/>
+                <allow-access-from domain="games.example.org" to-ports="*"/>
+            </cross-domain-policy>"#;
+
+        assert!(policy_allows(policy, "www.example.com", 5005));
+        assert!(!policy_allows(policy, "www.example.com", 6000));
+        assert!(policy_allows(policy, "games.example.org", 6000));
+        assert!(!policy_allows(policy, "example.net", 5005));
+        assert!(!policy_allows(b"", "example.com", 5005));
+
+        let none = br#"<cross-domain-policy>
+                <site-control permitted-cross-domain-policies="none"/>
+                <allow-access-from domain="*" to-ports="*"/>
+            </cross-domain-policy>"#;
+        assert!(!policy_allows(none, "example.com", 5005));
+    }
+}
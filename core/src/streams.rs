@@ -0,0 +1,959 @@
+//! Media streams played by `NetStream`
+//!
+//! Only progressive playback of FLV files is supported. A file is downloaded
+//! in full before it starts to play, and playback then advances with the
+//! player's clock: video frames are decoded once their time has come, script
+//! data tags call methods of the stream's client, and the audio track is
+//! handed to the mixer as a single sound.
+
+pub mod flv;
+
+use crate::avm1::{
+    Activation as Avm1Activation, ActivationIdentifier, ArrayObject as Avm1ArrayObject,
+    Attribute as Avm1Attribute, Avm1, Object as Avm1Object, ScriptObject as Avm1ScriptObject,
+    TObject as _, Value as Avm1Value,
+};
+use crate::avm2::{
+    Activation as Avm2Activation, ArrayObject as Avm2ArrayObject, ArrayStorage, Avm2,
+    Error as Avm2Error, Multiname as Avm2Multiname, Object as Avm2Object, TObject as _,
+    Value as Avm2Value,
+};
+use crate::backend::audio::{SoundHandle, SoundInstanceHandle};
+use crate::context::UpdateContext;
+use crate::loader::{Error as LoaderError, Handle};
+use crate::string::AvmString;
+use crate::vminterface::AvmObject;
+use flv::{FrameType, Reader, ScriptData, ScriptValue, TagData};
+use gc_arena::{Collect, GcCell, MutationContext};
+use ruffle_render::bitmap::BitmapInfo;
+use ruffle_video::frame::EncodedFrame;
+use ruffle_video::VideoStreamHandle;
+use std::fmt;
+use swf::{AudioCompression, SoundEvent, SoundInfo, VideoCodec, VideoDeblocking};
+
+/// The default `bufferTime` of a stream, in seconds.
+const DEFAULT_BUFFER_TIME: f64 = 0.1;
+
+/// Every `NetStream` created by the movie.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct StreamManager<'gc> {
+    streams: Vec<NetStream<'gc>>,
+}
+
+impl<'gc> StreamManager<'gc> {
+    pub fn new() -> Self {
+        Self {
+            streams: Vec::new(),
+        }
+    }
+
+    /// The stream of a `NetStream` script object, which is created the first
+    /// time it is asked for.
+    pub fn stream_for(
+        &mut self,
+        gc_context: MutationContext<'gc, '_>,
+        object: AvmObject<'gc>,
+    ) -> NetStream<'gc> {
+        if let Some(stream) = self.find(object) {
+            return stream;
+        }
+
+        let stream = NetStream::new(gc_context, object);
+        self.streams.push(stream);
+        stream
+    }
+
+    /// The stream of a `NetStream` script object, if it has been created.
+    pub fn find(&self, object: AvmObject<'gc>) -> Option<NetStream<'gc>> {
+        self.streams
+            .iter()
+            .find(|stream| same_object(stream.0.read().object, object))
+            .copied()
+    }
+
+    /// Advance every playing stream by `dt` milliseconds.
+    pub fn tick(context: &mut UpdateContext<'_, 'gc, '_>, dt: f64) {
+        let streams = context.stream_manager.streams.clone();
+        for stream in streams {
+            stream.tick(context, dt);
+        }
+    }
+}
+
+impl<'gc> Default for StreamManager<'gc> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How far a stream has got with its file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StreamStatus {
+    /// Nothing has been played, or the stream was closed.
+    Idle,
+
+    /// The file is being downloaded.
+    Loading,
+
+    /// The file is being played.
+    Playing,
+
+    /// Playback reached the end of the file.
+    Finished,
+}
+
+/// A tag that takes effect while the stream plays.
+enum StreamTag {
+    Video {
+        timestamp: u32,
+        keyframe: bool,
+        frame_id: u32,
+        start: usize,
+        end: usize,
+    },
+    Script {
+        timestamp: u32,
+        data: ScriptData,
+    },
+}
+
+impl StreamTag {
+    fn timestamp(&self) -> u32 {
+        match self {
+            Self::Video { timestamp, .. } | Self::Script { timestamp, .. } => *timestamp,
+        }
+    }
+}
+
+/// A stream of audio and video played by a `NetStream`.
+#[derive(Clone, Copy, Collect)]
+#[collect(no_drop)]
+pub struct NetStream<'gc>(GcCell<'gc, NetStreamData<'gc>>);
+
+impl fmt::Debug for NetStream<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("NetStream").field(&self.0.as_ptr()).finish()
+    }
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct NetStreamData<'gc> {
+    /// The `NetStream` script object of this stream.
+    object: AvmObject<'gc>,
+
+    #[collect(require_static)]
+    state: StreamState,
+}
+
+struct StreamState {
+    status: StreamStatus,
+
+    /// Whether playback is paused.
+    paused: bool,
+
+    /// The loader downloading the current file, so that the downloads of
+    /// files played before it are ignored.
+    pending_load: Option<Handle>,
+
+    /// The downloaded file.
+    data: Vec<u8>,
+
+    /// The video and script data tags of the file, in order.
+    tags: Vec<StreamTag>,
+
+    /// The index of the first tag in `tags` which hasn't taken effect yet.
+    next_tag: usize,
+
+    /// The current playback time, in milliseconds.
+    time: f64,
+
+    /// The timestamp of the last tag of the file, in milliseconds.
+    duration: f64,
+
+    /// How much of the file should be buffered before playing, in seconds.
+    buffer_time: f64,
+
+    video_codec: Option<VideoCodec>,
+    video_stream: Option<VideoStreamHandle>,
+
+    /// The last decoded video frame.
+    decoded_frame: Option<BitmapInfo>,
+
+    /// The number of video frames decoded so far.
+    frames_decoded: u64,
+
+    /// The audio track of the file, registered as a single sound.
+    sound: Option<SoundHandle>,
+    sound_instance: Option<SoundInstanceHandle>,
+}
+
+impl<'gc> NetStream<'gc> {
+    fn new(gc_context: MutationContext<'gc, '_>, object: AvmObject<'gc>) -> Self {
+        Self(GcCell::allocate(
+            gc_context,
+            NetStreamData {
+                object,
+                state: StreamState {
+                    status: StreamStatus::Idle,
+                    paused: false,
+                    pending_load: None,
+                    data: Vec::new(),
+                    tags: Vec::new(),
+                    next_tag: 0,
+                    time: 0.0,
+                    duration: 0.0,
+                    buffer_time: DEFAULT_BUFFER_TIME,
+                    video_codec: None,
+                    video_stream: None,
+                    decoded_frame: None,
+                    frames_decoded: 0,
+                    sound: None,
+                    sound_instance: None,
+                },
+            },
+        ))
+    }
+
+    /// Start downloading a file, which plays once it has been loaded.
+    pub fn play(self, context: &mut UpdateContext<'_, 'gc, '_>, url: &str) {
+        self.reset(context);
+        self.0.write(context.gc_context).state.status = StreamStatus::Loading;
+
+        let future = context.load_manager.load_netstream(
+            context.gc_context,
+            context.player.clone(),
+            self,
+            crate::backend::navigator::Request::get(url.to_string()),
+        );
+        context.navigator.spawn_future(future);
+    }
+
+    /// Remember which loader is downloading the current file.
+    pub fn set_pending_load(self, gc_context: MutationContext<'gc, '_>, loader: Handle) {
+        self.0.write(gc_context).state.pending_load = Some(loader);
+    }
+
+    /// Start playing a downloaded file.
+    ///
+    /// Downloads which were started before the current one are ignored.
+    pub fn load_complete(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        loader: Handle,
+        result: Result<Vec<u8>, LoaderError>,
+    ) {
+        if self.0.read().state.pending_load != Some(loader) {
+            return;
+        }
+        self.0.write(context.gc_context).state.pending_load = None;
+
+        let data = match result {
+            Ok(data) => data,
+            Err(e) => {
+                log::warn!("NetStream could not load its file: {}", e);
+                self.0.write(context.gc_context).state.status = StreamStatus::Idle;
+                self.dispatch_status(context, "NetStream.Play.StreamNotFound", "error");
+                return;
+            }
+        };
+
+        if let Err(e) = self.load_file(context, data) {
+            log::warn!("NetStream could not parse its file: {}", e);
+            self.reset(context);
+            self.dispatch_status(context, "NetStream.Play.FileStructureInvalid", "error");
+            return;
+        }
+
+        self.dispatch_status(context, "NetStream.Play.Start", "status");
+        self.advance(context);
+        self.dispatch_status(context, "NetStream.Buffer.Full", "status");
+        if self.is_playing() {
+            self.start_audio(context);
+        }
+    }
+
+    /// Index the tags of a downloaded file, and prepare its video and audio
+    /// for playback.
+    fn load_file(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        data: Vec<u8>,
+    ) -> Result<(), flv::Error> {
+        let mut reader = Reader::new(&data)?;
+        let mut tags = Vec::new();
+        let mut num_video_frames = 0;
+        let mut duration = 0;
+        let mut video_codec = None;
+        let mut video_size = (0, 0);
+        let mut audio_format = None;
+        let mut audio_data = Vec::new();
+
+        loop {
+            let tag = match reader.read_tag() {
+                Ok(Some(tag)) => tag,
+                Ok(None) => break,
+                Err(e) => {
+                    log::warn!("Skipping invalid FLV tag: {}", e);
+                    continue;
+                }
+            };
+
+            duration = duration.max(tag.timestamp);
+            match tag.data {
+                TagData::Video(video) if video.frame_type != FrameType::Command => {
+                    let codec = match video.codec {
+                        Some(codec) => codec,
+                        None => {
+                            log::warn!("Unsupported FLV video codec {}", video.codec_id);
+                            continue;
+                        }
+                    };
+
+                    if *video_codec.get_or_insert(codec) != codec {
+                        log::warn!("Ignoring FLV video frame with a different codec");
+                        continue;
+                    }
+
+                    let frame_id = num_video_frames;
+                    num_video_frames += 1;
+                    let start = video.data.as_ptr() as usize - data.as_ptr() as usize;
+                    tags.push(StreamTag::Video {
+                        timestamp: tag.timestamp,
+                        keyframe: frame_id == 0 || video.frame_type.is_keyframe(),
+                        frame_id,
+                        start,
+                        end: start + video.data.len(),
+                    });
+                }
+                TagData::Audio(audio) => {
+                    let format = match audio.sound_format() {
+                        Some(format) => format,
+                        None => {
+                            if audio_format.is_none() {
+                                log::warn!("Unsupported FLV audio format {}", audio.format_id);
+                            }
+                            continue;
+                        }
+                    };
+
+                    if *audio_format.get_or_insert_with(|| format.clone()) == format {
+                        audio_data.extend_from_slice(audio.data);
+                    }
+                }
+                TagData::Script(script) => {
+                    if script.name == "onMetaData" {
+                        let dimension = |name| {
+                            script
+                                .value
+                                .get(name)
+                                .and_then(ScriptValue::as_number)
+                                .unwrap_or_default() as u16
+                        };
+                        video_size = (dimension("width"), dimension("height"));
+                    }
+
+                    tags.push(StreamTag::Script {
+                        timestamp: tag.timestamp,
+                        data: script,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let num_frames = tags
+            .iter()
+            .filter(|tag| matches!(tag, StreamTag::Video { .. }))
+            .count() as u32;
+        let video_stream = video_codec.and_then(|codec| {
+            let stream = context
+                .video
+                .register_video_stream(
+                    num_frames,
+                    video_size,
+                    codec,
+                    VideoDeblocking::UseVideoPacketValue,
+                )
+                .map_err(|e| log::error!("Could not register NetStream video: {}", e))
+                .ok()?;
+
+            for tag in &mut tags {
+                if let StreamTag::Video {
+                    keyframe,
+                    frame_id,
+                    start,
+                    end,
+                    ..
+                } = tag
+                {
+                    let dependency = context.video.preload_video_stream_frame(
+                        stream,
+                        EncodedFrame {
+                            codec,
+                            data: &data[*start..*end],
+                            frame_id: *frame_id,
+                        },
+                    );
+
+                    match dependency {
+                        Ok(dependency) => *keyframe |= dependency.is_keyframe(),
+                        Err(e) => log::error!("Got error when pre-loading video frame: {}", e),
+                    }
+                }
+            }
+
+            Some(stream)
+        });
+
+        let sound = audio_format.and_then(|format| {
+            let result = match format.compression {
+                AudioCompression::Mp3 => context
+                    .audio
+                    .register_mp3(&audio_data)
+                    .map_err(|e| e.to_string()),
+                AudioCompression::Uncompressed | AudioCompression::UncompressedUnknownEndian => {
+                    let bytes_per_sample =
+                        (1 + usize::from(format.is_stereo)) * (1 + usize::from(format.is_16_bit));
+                    let num_samples = (audio_data.len() / bytes_per_sample) as u32;
+                    register_sound(context, &format, num_samples, &audio_data)
+                }
+                AudioCompression::Nellymoser
+                | AudioCompression::Nellymoser16Khz
+                | AudioCompression::Nellymoser8Khz => {
+                    // Every 64 byte block holds 256 samples.
+                    let num_samples = (audio_data.len() / 64 * 256) as u32;
+                    register_sound(context, &format, num_samples, &audio_data)
+                }
+                compression => Err(format!(
+                    "{:?} audio is not supported in FLV files",
+                    compression
+                )),
+            };
+
+            result
+                .map_err(|e| log::error!("Could not register NetStream audio: {}", e))
+                .ok()
+        });
+
+        let mut write = self.0.write(context.gc_context);
+        let state = &mut write.state;
+        state.status = StreamStatus::Playing;
+        state.tags = tags;
+        state.next_tag = 0;
+        state.time = 0.0;
+        state.duration = duration.into();
+        state.video_codec = video_codec;
+        state.video_stream = video_stream;
+        state.sound = sound;
+        state.data = data;
+        Ok(())
+    }
+
+    /// Advance playback by `dt` milliseconds.
+    fn tick(self, context: &mut UpdateContext<'_, 'gc, '_>, dt: f64) {
+        if !self.is_playing() {
+            return;
+        }
+
+        self.0.write(context.gc_context).state.time += dt;
+        self.advance(context);
+
+        let read = self.0.read();
+        let finished = read.state.status == StreamStatus::Playing
+            && read.state.next_tag >= read.state.tags.len()
+            && read.state.time >= read.state.duration;
+        drop(read);
+
+        if finished {
+            self.finish(context);
+        }
+    }
+
+    /// Apply every tag whose time has come.
+    fn advance(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        loop {
+            let read = self.0.read();
+            let state = &read.state;
+            let tag = match state.tags.get(state.next_tag) {
+                Some(tag) if f64::from(tag.timestamp()) <= state.time => tag,
+                _ => return,
+            };
+
+            match tag {
+                StreamTag::Video {
+                    frame_id,
+                    start,
+                    end,
+                    ..
+                } => {
+                    let result = match (state.video_stream, state.video_codec) {
+                        (Some(stream), Some(codec)) => {
+                            Some(context.video.decode_video_stream_frame(
+                                stream,
+                                EncodedFrame {
+                                    codec,
+                                    data: &state.data[*start..*end],
+                                    frame_id: *frame_id,
+                                },
+                                context.renderer,
+                            ))
+                        }
+                        _ => None,
+                    };
+                    drop(read);
+
+                    let mut write = self.0.write(context.gc_context);
+                    write.state.next_tag += 1;
+                    match result {
+                        Some(Ok(bitmap)) => {
+                            write.state.decoded_frame = Some(bitmap);
+                            write.state.frames_decoded += 1;
+                        }
+                        Some(Err(e)) => log::error!("Got error when decoding video frame: {}", e),
+                        None => {}
+                    }
+                }
+                StreamTag::Script { data, .. } => {
+                    let data = data.clone();
+                    drop(read);
+
+                    self.0.write(context.gc_context).state.next_tag += 1;
+                    self.call_client(context, &data);
+                }
+            }
+        }
+    }
+
+    /// Stop playing at the end of the file.
+    fn finish(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.stop_audio(context);
+        self.0.write(context.gc_context).state.status = StreamStatus::Finished;
+        self.dispatch_status(context, "NetStream.Buffer.Flush", "status");
+        self.dispatch_status(context, "NetStream.Play.Stop", "status");
+        self.dispatch_status(context, "NetStream.Buffer.Empty", "status");
+    }
+
+    /// Pause playback.
+    pub fn pause(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let read = self.0.read();
+        if read.state.paused || read.state.status == StreamStatus::Idle {
+            return;
+        }
+        drop(read);
+
+        self.stop_audio(context);
+        self.0.write(context.gc_context).state.paused = true;
+        self.dispatch_status(context, "NetStream.Pause.Notify", "status");
+    }
+
+    /// Resume paused playback.
+    pub fn resume(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let read = self.0.read();
+        if !read.state.paused || read.state.status == StreamStatus::Idle {
+            return;
+        }
+        drop(read);
+
+        self.0.write(context.gc_context).state.paused = false;
+        if self.is_playing() {
+            self.start_audio(context);
+        }
+        self.dispatch_status(context, "NetStream.Unpause.Notify", "status");
+    }
+
+    pub fn toggle_pause(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        if self.0.read().state.paused {
+            self.resume(context);
+        } else {
+            self.pause(context);
+        }
+    }
+
+    /// Seek to the keyframe at or before `offset` seconds.
+    pub fn seek(self, context: &mut UpdateContext<'_, 'gc, '_>, offset: f64) {
+        let read = self.0.read();
+        let state = &read.state;
+        if !matches!(state.status, StreamStatus::Playing | StreamStatus::Finished) {
+            return;
+        }
+
+        let target = if offset.is_finite() {
+            (offset * 1000.0).clamp(0.0, state.duration)
+        } else {
+            0.0
+        };
+
+        let keyframe = state.tags.iter().enumerate().rev().find(|(_, tag)| {
+            matches!(tag, StreamTag::Video { timestamp, keyframe: true, .. } if f64::from(*timestamp) <= target)
+        });
+        let (next_tag, time) = match keyframe {
+            Some((index, tag)) => (index, f64::from(tag.timestamp())),
+            // Without a keyframe to seek to, video restarts from its first
+            // frame, after any script data that comes before it.
+            None => (
+                state
+                    .tags
+                    .iter()
+                    .position(|tag| matches!(tag, StreamTag::Video { .. }))
+                    .unwrap_or(state.tags.len()),
+                0.0,
+            ),
+        };
+        drop(read);
+
+        self.stop_audio(context);
+        let mut write = self.0.write(context.gc_context);
+        write.state.status = StreamStatus::Playing;
+        write.state.next_tag = next_tag;
+        write.state.time = time;
+        drop(write);
+
+        self.dispatch_status(context, "NetStream.Seek.Notify", "status");
+        self.advance(context);
+        if self.is_playing() {
+            self.start_audio(context);
+        }
+    }
+
+    /// Stop playing, and forget the current file.
+    pub fn close(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.reset(context);
+    }
+
+    fn reset(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.stop_audio(context);
+
+        let mut write = self.0.write(context.gc_context);
+        let state = &mut write.state;
+        state.status = StreamStatus::Idle;
+        state.paused = false;
+        state.pending_load = None;
+        state.data = Vec::new();
+        state.tags = Vec::new();
+        state.next_tag = 0;
+        state.time = 0.0;
+        state.duration = 0.0;
+        state.video_codec = None;
+        state.video_stream = None;
+        state.sound = None;
+    }
+
+    fn is_playing(self) -> bool {
+        let state = &self.0.read().state;
+        state.status == StreamStatus::Playing && !state.paused
+    }
+
+    /// Play the audio track from the current time.
+    fn start_audio(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let read = self.0.read();
+        let sound = match read.state.sound {
+            Some(sound) if read.state.sound_instance.is_none() => sound,
+            _ => return,
+        };
+
+        // Sound positions are measured in samples at 44.1 kHz.
+        let in_sample = (read.state.time * 44.1) as u32;
+        drop(read);
+
+        let settings = SoundInfo {
+            event: SoundEvent::Event,
+            in_sample: if in_sample > 0 { Some(in_sample) } else { None },
+            out_sample: None,
+            num_loops: 1,
+            envelope: None,
+        };
+
+        let instance =
+            context
+                .audio_manager
+                .start_sound(context.audio, sound, &settings, None, None);
+        self.0.write(context.gc_context).state.sound_instance = instance;
+    }
+
+    fn stop_audio(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let instance = self.0.write(context.gc_context).state.sound_instance.take();
+        if let Some(instance) = instance {
+            context.audio_manager.stop_sound(context.audio, instance);
+        }
+    }
+
+    /// The current playback time, in seconds.
+    pub fn time(self) -> f64 {
+        self.0.read().state.time / 1000.0
+    }
+
+    pub fn bytes_loaded(self) -> usize {
+        self.0.read().state.data.len()
+    }
+
+    pub fn bytes_total(self) -> usize {
+        self.0.read().state.data.len()
+    }
+
+    /// How much of the file is buffered ahead of the current time, in seconds.
+    pub fn buffer_length(self) -> f64 {
+        let state = &self.0.read().state;
+        match state.status {
+            StreamStatus::Playing => (state.duration - state.time).max(0.0) / 1000.0,
+            _ => 0.0,
+        }
+    }
+
+    pub fn buffer_time(self) -> f64 {
+        self.0.read().state.buffer_time
+    }
+
+    pub fn set_buffer_time(self, gc_context: MutationContext<'gc, '_>, buffer_time: f64) {
+        self.0.write(gc_context).state.buffer_time = buffer_time;
+    }
+
+    /// The last decoded video frame.
+    pub fn decoded_frame(self) -> Option<BitmapInfo> {
+        self.0.read().state.decoded_frame.clone()
+    }
+
+    /// The number of video frames decoded so far, which changes along with `decoded_frame`.
+    pub fn frames_decoded(self) -> u64 {
+        self.0.read().state.frames_decoded
+    }
+
+    /// Tell the script object about a change of status.
+    fn dispatch_status(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        code: &'static str,
+        level: &'static str,
+    ) {
+        let object = self.0.read().object;
+        match object {
+            AvmObject::Avm1(object) => {
+                let info = Avm1ScriptObject::new(
+                    context.gc_context,
+                    Some(context.avm1.prototypes().object),
+                );
+                info.define_value(
+                    context.gc_context,
+                    "code",
+                    code.into(),
+                    Avm1Attribute::empty(),
+                );
+                info.define_value(
+                    context.gc_context,
+                    "level",
+                    level.into(),
+                    Avm1Attribute::empty(),
+                );
+
+                let root_clip = context.stage.root_clip();
+                Avm1::run_stack_frame_for_method(
+                    root_clip,
+                    object,
+                    context,
+                    "onStatus".into(),
+                    &[info.into()],
+                );
+            }
+            AvmObject::Avm2(object) => {
+                let mut activation = Avm2Activation::from_nothing(context.reborrow());
+                if let Err(e) = dispatch_avm2_status(&mut activation, object, code, level) {
+                    log::error!(
+                        "Encountered AVM2 error when dispatching `netStatus` event: {}",
+                        e
+                    );
+                }
+            }
+        }
+    }
+
+    /// Call the method named by a script data tag on the stream's client.
+    fn call_client(self, context: &mut UpdateContext<'_, 'gc, '_>, data: &ScriptData) {
+        let object = self.0.read().object;
+        match object {
+            AvmObject::Avm1(object) => {
+                let root_clip = context.stage.root_clip();
+                let mut activation = Avm1Activation::from_nothing(
+                    context.reborrow(),
+                    ActivationIdentifier::root("[NetStream]"),
+                    root_clip,
+                );
+                let value = script_value_to_avm1(&mut activation, &data.value);
+                let name = AvmString::new_utf8(activation.context.gc_context, &data.name);
+                Avm1::run_stack_frame_for_method(
+                    root_clip,
+                    object,
+                    &mut activation.context,
+                    name,
+                    &[value],
+                );
+            }
+            AvmObject::Avm2(object) => {
+                let mut activation = Avm2Activation::from_nothing(context.reborrow());
+                if let Err(e) = call_avm2_client(&mut activation, object, data) {
+                    log::error!(
+                        "Encountered AVM2 error when calling NetStream client method `{}`: {}",
+                        data.name,
+                        e
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn same_object<'gc>(a: AvmObject<'gc>, b: AvmObject<'gc>) -> bool {
+    match (a, b) {
+        (AvmObject::Avm1(a), AvmObject::Avm1(b)) => Avm1Object::ptr_eq(a, b),
+        (AvmObject::Avm2(a), AvmObject::Avm2(b)) => Avm2Object::ptr_eq(a, b),
+        _ => false,
+    }
+}
+
+fn register_sound(
+    context: &mut UpdateContext<'_, '_, '_>,
+    format: &swf::SoundFormat,
+    num_samples: u32,
+    data: &[u8],
+) -> Result<SoundHandle, String> {
+    context
+        .audio
+        .register_sound(&swf::Sound {
+            id: 0,
+            format: format.clone(),
+            num_samples,
+            data,
+        })
+        .map_err(|e| e.to_string())
+}
+
+fn dispatch_avm2_status<'gc>(
+    activation: &mut Avm2Activation<'_, 'gc, '_>,
+    target: Avm2Object<'gc>,
+    code: &'static str,
+    level: &'static str,
+) -> Result<(), Avm2Error<'gc>> {
+    let mut info = activation
+        .avm2()
+        .classes()
+        .object
+        .construct(activation, &[])?;
+    info.set_property(&Avm2Multiname::public("code"), code.into(), activation)?;
+    info.set_property(&Avm2Multiname::public("level"), level.into(), activation)?;
+
+    let event = activation.avm2().classes().netstatusevent.construct(
+        activation,
+        &["netStatus".into(), false.into(), false.into(), info.into()],
+    )?;
+    Avm2::dispatch_event(&mut activation.context, event, target)?;
+    Ok(())
+}
+
+fn call_avm2_client<'gc>(
+    activation: &mut Avm2Activation<'_, 'gc, '_>,
+    stream: Avm2Object<'gc>,
+    data: &ScriptData,
+) -> Result<(), Avm2Error<'gc>> {
+    let client = match stream.get_property(&Avm2Multiname::public("client"), activation)? {
+        Avm2Value::Object(client) => client,
+        _ => return Ok(()),
+    };
+
+    let name = AvmString::new_utf8(activation.context.gc_context, &data.name);
+    match client.get_property(&Avm2Multiname::public(name), activation)? {
+        Avm2Value::Object(method) => {
+            let value = script_value_to_avm2(activation, &data.value)?;
+            method.call(Some(client), &[value], activation)?;
+        }
+        _ => log::warn!("NetStream client has no `{}` method", data.name),
+    }
+
+    Ok(())
+}
+
+fn script_value_to_avm1<'gc>(
+    activation: &mut Avm1Activation<'_, 'gc, '_>,
+    value: &ScriptValue,
+) -> Avm1Value<'gc> {
+    match value {
+        ScriptValue::Number(n) => (*n).into(),
+        ScriptValue::Boolean(b) => (*b).into(),
+        ScriptValue::String(s) => AvmString::new_utf8(activation.context.gc_context, s).into(),
+        ScriptValue::Object(properties) | ScriptValue::EcmaArray(properties) => {
+            let object = Avm1ScriptObject::new(
+                activation.context.gc_context,
+                Some(activation.context.avm1.prototypes().object),
+            );
+            for (name, value) in properties {
+                let value = script_value_to_avm1(activation, value);
+                let name = AvmString::new_utf8(activation.context.gc_context, name);
+                object.define_value(
+                    activation.context.gc_context,
+                    name,
+                    value,
+                    Avm1Attribute::empty(),
+                );
+            }
+            object.into()
+        }
+        ScriptValue::StrictArray(values) => {
+            let values: Vec<_> = values
+                .iter()
+                .map(|value| script_value_to_avm1(activation, value))
+                .collect();
+            Avm1ArrayObject::new(
+                activation.context.gc_context,
+                activation.context.avm1.prototypes().array,
+                values,
+            )
+            .into()
+        }
+        ScriptValue::Date { time, .. } => {
+            let constructor = activation.context.avm1.prototypes().date_constructor;
+            constructor
+                .construct(activation, &[(*time).into()])
+                .unwrap_or(Avm1Value::Undefined)
+        }
+        ScriptValue::Null => Avm1Value::Null,
+        ScriptValue::Undefined | ScriptValue::Reference(_) => Avm1Value::Undefined,
+    }
+}
+
+fn script_value_to_avm2<'gc>(
+    activation: &mut Avm2Activation<'_, 'gc, '_>,
+    value: &ScriptValue,
+) -> Result<Avm2Value<'gc>, Avm2Error<'gc>> {
+    Ok(match value {
+        ScriptValue::Number(n) => (*n).into(),
+        ScriptValue::Boolean(b) => (*b).into(),
+        ScriptValue::String(s) => AvmString::new_utf8(activation.context.gc_context, s).into(),
+        ScriptValue::Object(properties) | ScriptValue::EcmaArray(properties) => {
+            let mut object = activation
+                .avm2()
+                .classes()
+                .object
+                .construct(activation, &[])?;
+            for (name, value) in properties {
+                let value = script_value_to_avm2(activation, value)?;
+                let name = AvmString::new_utf8(activation.context.gc_context, name);
+                object.set_property(&Avm2Multiname::public(name), value, activation)?;
+            }
+            object.into()
+        }
+        ScriptValue::StrictArray(values) => {
+            let values = values
+                .iter()
+                .map(|value| script_value_to_avm2(activation, value))
+                .collect::<Result<Vec<_>, _>>()?;
+            Avm2ArrayObject::from_storage(activation, ArrayStorage::from_args(&values))?.into()
+        }
+        ScriptValue::Date { time, .. } => activation
+            .avm2()
+            .classes()
+            .date
+            .construct(activation, &[(*time).into()])?
+            .into(),
+        ScriptValue::Null => Avm2Value::Null,
+        ScriptValue::Undefined | ScriptValue::Reference(_) => Avm2Value::Undefined,
+    })
+}
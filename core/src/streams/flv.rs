@@ -0,0 +1,606 @@
+//! FLV container parsing
+//!
+//! Only what progressive playback needs is read: the file header, the tag
+//! headers, the audio and video tag headers, and the AMF0 values carried by
+//! script data tags such as `onMetaData`.
+
+use swf::{AudioCompression, SoundFormat, VideoCodec};
+use thiserror::Error;
+
+/// How deeply script data values may nest before the tag is rejected.
+const MAX_SCRIPT_DATA_DEPTH: usize = 64;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("Not an FLV file")]
+    InvalidSignature,
+
+    #[error("Unexpected end of FLV data")]
+    EndOfData,
+
+    #[error("Invalid video frame type {0}")]
+    InvalidFrameType(u8),
+
+    #[error("Invalid script data value type {0}")]
+    InvalidScriptDataType(u8),
+
+    #[error("Script data is nested too deeply")]
+    ScriptDataTooDeep,
+}
+
+/// The header at the start of every FLV file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    pub version: u8,
+    pub has_audio: bool,
+    pub has_video: bool,
+
+    /// The offset of the first tag's `PreviousTagSize` field.
+    pub data_offset: u32,
+}
+
+/// A single tag of an FLV file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tag<'a> {
+    /// The time at which the tag applies, in milliseconds.
+    pub timestamp: u32,
+    pub data: TagData<'a>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TagData<'a> {
+    Audio(AudioData<'a>),
+    Video(VideoData<'a>),
+    Script(ScriptData),
+
+    /// A tag of an unknown type, or one which is encrypted.
+    Unknown(u8),
+}
+
+/// The contents of an audio tag.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AudioData<'a> {
+    /// The FLV sound format ID of the tag.
+    pub format_id: u8,
+
+    /// The codec of the tag, if it is one that SWF files may contain as well.
+    pub compression: Option<AudioCompression>,
+    pub sample_rate: u16,
+    pub is_stereo: bool,
+    pub is_16_bit: bool,
+
+    /// The encoded audio.
+    pub data: &'a [u8],
+}
+
+impl<'a> AudioData<'a> {
+    /// The format of the audio, if it is one that SWF files may contain as
+    /// well.
+    pub fn sound_format(&self) -> Option<SoundFormat> {
+        Some(SoundFormat {
+            compression: self.compression?,
+            sample_rate: self.sample_rate,
+            is_stereo: self.is_stereo,
+            is_16_bit: self.is_16_bit,
+        })
+    }
+}
+
+/// The kind of frame a video tag holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameType {
+    Keyframe,
+    Interframe,
+    DisposableInterframe,
+    GeneratedKeyframe,
+
+    /// A frame which holds no picture, but information for the decoder.
+    Command,
+}
+
+impl FrameType {
+    fn from_u8(n: u8) -> Option<Self> {
+        match n {
+            1 => Some(Self::Keyframe),
+            2 => Some(Self::Interframe),
+            3 => Some(Self::DisposableInterframe),
+            4 => Some(Self::GeneratedKeyframe),
+            5 => Some(Self::Command),
+            _ => None,
+        }
+    }
+
+    /// Whether a frame of this type can be decoded without any prior frames.
+    pub fn is_keyframe(self) -> bool {
+        matches!(self, Self::Keyframe | Self::GeneratedKeyframe)
+    }
+}
+
+/// The contents of a video tag.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VideoData<'a> {
+    pub frame_type: FrameType,
+
+    /// The FLV codec ID of the tag.
+    pub codec_id: u8,
+
+    /// The codec of the tag, if it is one that SWF files may contain as well.
+    pub codec: Option<VideoCodec>,
+
+    /// The encoded frame, in the same form as the data of a SWF `VideoFrame`
+    /// tag.
+    pub data: &'a [u8],
+}
+
+/// The contents of a script data tag: a call of the method `name` on the
+/// stream's client.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptData {
+    pub name: String,
+    pub value: ScriptValue,
+}
+
+/// An AMF0 value, as found in script data tags.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScriptValue {
+    Number(f64),
+    Boolean(bool),
+    String(String),
+    Object(Vec<(String, ScriptValue)>),
+    Null,
+    Undefined,
+    Reference(u16),
+    EcmaArray(Vec<(String, ScriptValue)>),
+    StrictArray(Vec<ScriptValue>),
+    Date { time: f64, timezone: i16 },
+}
+
+impl ScriptValue {
+    /// Look up a property of an object or ECMA array.
+    pub fn get(&self, name: &str) -> Option<&ScriptValue> {
+        match self {
+            Self::Object(properties) | Self::EcmaArray(properties) => properties
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+/// Reads the tags of an FLV file in order.
+pub struct Reader<'a> {
+    data: &'a [u8],
+    header: Header,
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Start reading an FLV file, which must at least contain its header.
+    pub fn new(data: &'a [u8]) -> Result<Self, Error> {
+        let mut input = data;
+        if read_bytes(&mut input, 3)? != b"FLV" {
+            return Err(Error::InvalidSignature);
+        }
+
+        let version = read_u8(&mut input)?;
+        let flags = read_u8(&mut input)?;
+        let data_offset = read_u32(&mut input)?;
+        let header = Header {
+            version,
+            has_audio: flags & 0b100 != 0,
+            has_video: flags & 0b1 != 0,
+            data_offset,
+        };
+
+        Ok(Self {
+            data,
+            header,
+            // Skip the `PreviousTagSize` field preceding the first tag.
+            position: data_offset as usize + 4,
+        })
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Read the next tag.
+    ///
+    /// Returns `None` once the end of the data is reached, including when
+    /// the last tag is incomplete, as it is while a file is still loading.
+    /// A tag that fails to parse is skipped, so reading may continue after
+    /// an error.
+    pub fn read_tag(&mut self) -> Result<Option<Tag<'a>>, Error> {
+        let mut input = match self.data.get(self.position..) {
+            Some(input) if input.len() >= 11 => input,
+            _ => return Ok(None),
+        };
+
+        let tag_type = read_u8(&mut input)?;
+        let data_size = read_u24(&mut input)? as usize;
+        let timestamp = read_u24(&mut input)? | u32::from(read_u8(&mut input)?) << 24;
+        let _stream_id = read_u24(&mut input)?;
+        if input.len() < data_size {
+            return Ok(None);
+        }
+
+        let body = &input[..data_size];
+        // Skip the tag and the `PreviousTagSize` field following it.
+        self.position += 11 + data_size + 4;
+
+        // Bit 5 flags encrypted tags.
+        let data = match tag_type {
+            8 => TagData::Audio(parse_audio_data(body)?),
+            9 => TagData::Video(parse_video_data(body)?),
+            18 => TagData::Script(parse_script_data(body)?),
+            _ => TagData::Unknown(tag_type),
+        };
+
+        Ok(Some(Tag { timestamp, data }))
+    }
+}
+
+fn parse_audio_data(mut input: &[u8]) -> Result<AudioData<'_>, Error> {
+    let flags = read_u8(&mut input)?;
+    let format_id = flags >> 4;
+    let mut sample_rate = [5512, 11025, 22050, 44100][usize::from((flags >> 2) & 0b11)];
+    let compression = match format_id {
+        0 => Some(AudioCompression::UncompressedUnknownEndian),
+        1 => Some(AudioCompression::Adpcm),
+        2 => Some(AudioCompression::Mp3),
+        3 => Some(AudioCompression::Uncompressed),
+        4 => {
+            sample_rate = 16000;
+            Some(AudioCompression::Nellymoser16Khz)
+        }
+        5 => {
+            sample_rate = 8000;
+            Some(AudioCompression::Nellymoser8Khz)
+        }
+        6 => Some(AudioCompression::Nellymoser),
+        10 => {
+            // Skip the AAC packet type.
+            read_u8(&mut input)?;
+            None
+        }
+        11 => {
+            sample_rate = 16000;
+            Some(AudioCompression::Speex)
+        }
+        14 => {
+            sample_rate = 8000;
+            Some(AudioCompression::Mp3)
+        }
+        _ => None,
+    };
+
+    Ok(AudioData {
+        format_id,
+        compression,
+        sample_rate,
+        is_stereo: flags & 0b1 != 0,
+        is_16_bit: flags & 0b10 != 0,
+        data: input,
+    })
+}
+
+fn parse_video_data(mut input: &[u8]) -> Result<VideoData<'_>, Error> {
+    let flags = read_u8(&mut input)?;
+    let frame_type = FrameType::from_u8(flags >> 4).ok_or(Error::InvalidFrameType(flags >> 4))?;
+    let codec_id = flags & 0b1111;
+    let codec = VideoCodec::from_u8(codec_id);
+    match codec_id {
+        // VP6 frames start with the picture's size adjustment, which SWF
+        // video frames leave out.
+        4 | 5 if frame_type != FrameType::Command => {
+            read_u8(&mut input)?;
+        }
+        // Skip the AVC packet type and composition time.
+        7 => {
+            read_bytes(&mut input, 4)?;
+        }
+        _ => {}
+    }
+
+    Ok(VideoData {
+        frame_type,
+        codec_id,
+        codec,
+        data: input,
+    })
+}
+
+fn parse_script_data(mut input: &[u8]) -> Result<ScriptData, Error> {
+    let name = match read_script_value(&mut input, 0)? {
+        ScriptValue::String(name) => name,
+        _ => String::new(),
+    };
+
+    // Some encoders write a name with no value.
+    let value = if input.is_empty() {
+        ScriptValue::Undefined
+    } else {
+        read_script_value(&mut input, 0)?
+    };
+
+    Ok(ScriptData { name, value })
+}
+
+fn read_script_value(input: &mut &[u8], depth: usize) -> Result<ScriptValue, Error> {
+    if depth > MAX_SCRIPT_DATA_DEPTH {
+        return Err(Error::ScriptDataTooDeep);
+    }
+
+    let value_type = read_u8(input)?;
+    Ok(match value_type {
+        0 => ScriptValue::Number(f64::from_bits(read_u64(input)?)),
+        1 => ScriptValue::Boolean(read_u8(input)? != 0),
+        2 => ScriptValue::String(read_string(input)?),
+        3 => ScriptValue::Object(read_properties(input, depth)?),
+        5 => ScriptValue::Null,
+        6 => ScriptValue::Undefined,
+        7 => ScriptValue::Reference(read_u16(input)?),
+        8 => {
+            // The length is only a hint; the properties end with an end marker
+            // like those of an object.
+            let _length = read_u32(input)?;
+            ScriptValue::EcmaArray(read_properties(input, depth)?)
+        }
+        10 => {
+            let length = read_u32(input)?;
+            let mut values = Vec::new();
+            for _ in 0..length {
+                values.push(read_script_value(input, depth + 1)?);
+            }
+            ScriptValue::StrictArray(values)
+        }
+        11 => ScriptValue::Date {
+            time: f64::from_bits(read_u64(input)?),
+            timezone: read_u16(input)? as i16,
+        },
+        12 => {
+            let length = read_u32(input)? as usize;
+            ScriptValue::String(String::from_utf8_lossy(read_bytes(input, length)?).into_owned())
+        }
+        _ => return Err(Error::InvalidScriptDataType(value_type)),
+    })
+}
+
+/// Read the properties of an object, up to and including its end marker.
+fn read_properties(input: &mut &[u8], depth: usize) -> Result<Vec<(String, ScriptValue)>, Error> {
+    let mut properties = Vec::new();
+    loop {
+        let name = read_string(input)?;
+        if name.is_empty() && input.first() == Some(&9) {
+            *input = &input[1..];
+            return Ok(properties);
+        }
+
+        // Truncated objects are common in the wild, and end with the data.
+        if name.is_empty() && input.is_empty() {
+            return Ok(properties);
+        }
+
+        let value = read_script_value(input, depth + 1)?;
+        properties.push((name, value));
+    }
+}
+
+fn read_string(input: &mut &[u8]) -> Result<String, Error> {
+    let length = read_u16(input)? as usize;
+    Ok(String::from_utf8_lossy(read_bytes(input, length)?).into_owned())
+}
+
+fn read_bytes<'a>(input: &mut &'a [u8], length: usize) -> Result<&'a [u8], Error> {
+    if input.len() < length {
+        return Err(Error::EndOfData);
+    }
+
+    let (bytes, rest) = input.split_at(length);
+    *input = rest;
+    Ok(bytes)
+}
+
+fn read_u8(input: &mut &[u8]) -> Result<u8, Error> {
+    Ok(read_bytes(input, 1)?[0])
+}
+
+fn read_u16(input: &mut &[u8]) -> Result<u16, Error> {
+    let bytes = read_bytes(input, 2)?;
+    Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u24(input: &mut &[u8]) -> Result<u32, Error> {
+    let bytes = read_bytes(input, 3)?;
+    Ok(u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]))
+}
+
+fn read_u32(input: &mut &[u8]) -> Result<u32, Error> {
+    let bytes = read_bytes(input, 4)?;
+    Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn read_u64(input: &mut &[u8]) -> Result<u64, Error> {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(read_bytes(input, 8)?);
+    Ok(u64::from_be_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::streams::flv::{
+        Error, FrameType, Reader, ScriptData, ScriptValue, TagData, VideoData,
+    };
+    use swf::{AudioCompression, VideoCodec};
+
+    fn tag(tag_type: u8, timestamp: u32, body: &[u8]) -> Vec<u8> {
+        let mut tag = vec![tag_type];
+        tag.extend_from_slice(&(body.len() as u32).to_be_bytes()[1..]);
+        tag.extend_from_slice(&timestamp.to_be_bytes()[1..]);
+        tag.push((timestamp >> 24) as u8);
+        tag.extend_from_slice(&[0, 0, 0]);
+        tag.extend_from_slice(body);
+        tag.extend_from_slice(&(body.len() as u32 + 11).to_be_bytes());
+        tag
+    }
+
+    fn flv(tags: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = b"FLV\x01\x05\x00\x00\x00\x09\x00\x00\x00\x00".to_vec();
+        for tag in tags {
+            data.extend_from_slice(tag);
+        }
+        data
+    }
+
+    fn amf_string(s: &str) -> Vec<u8> {
+        let mut data = (s.len() as u16).to_be_bytes().to_vec();
+        data.extend_from_slice(s.as_bytes());
+        data
+    }
+
+    #[test]
+    fn header() {
+        let data = flv(&[]);
+        let mut reader = Reader::new(&data).unwrap();
+        assert!(reader.header().has_audio);
+        assert!(reader.header().has_video);
+        assert_eq!(reader.header().data_offset, 9);
+        assert_eq!(reader.read_tag(), Ok(None));
+
+        assert_eq!(
+            Reader::new(b"FWS\x01\x05\x00\x00\x00\x09").err(),
+            Some(Error::InvalidSignature)
+        );
+        assert_eq!(Reader::new(b"FLV\x01").err(), Some(Error::EndOfData));
+    }
+
+    #[test]
+    fn audio_and_video_tags() {
+        let data = flv(&[
+            tag(8, 0, &[0x2f, 0xff, 0xfb]),
+            tag(9, 40, &[0x14, 0x00, 0xaa, 0xbb]),
+            tag(9, 0x01000000, &[0x23, 0xcc]),
+        ]);
+        let mut reader = Reader::new(&data).unwrap();
+
+        let audio = reader.read_tag().unwrap().unwrap();
+        assert_eq!(audio.timestamp, 0);
+        match audio.data {
+            TagData::Audio(audio) => {
+                assert_eq!(audio.compression, Some(AudioCompression::Mp3));
+                assert_eq!(audio.sample_rate, 44100);
+                assert!(audio.is_stereo);
+                assert!(audio.is_16_bit);
+                assert_eq!(audio.data, &[0xff, 0xfb]);
+            }
+            _ => panic!("Expected an audio tag"),
+        }
+
+        // The VP6 size adjustment is skipped.
+        let vp6 = reader.read_tag().unwrap().unwrap();
+        assert_eq!(vp6.timestamp, 40);
+        assert_eq!(
+            vp6.data,
+            TagData::Video(VideoData {
+                frame_type: FrameType::Keyframe,
+                codec_id: 4,
+                codec: Some(VideoCodec::Vp6),
+                data: &[0xaa, 0xbb],
+            })
+        );
+
+        let screen = reader.read_tag().unwrap().unwrap();
+        assert_eq!(screen.timestamp, 0x01000000);
+        match screen.data {
+            TagData::Video(video) => {
+                assert_eq!(video.frame_type, FrameType::Interframe);
+                assert!(!video.frame_type.is_keyframe());
+                assert_eq!(video.codec, Some(VideoCodec::ScreenVideo));
+                assert_eq!(video.data, &[0xcc]);
+            }
+            _ => panic!("Expected a video tag"),
+        }
+
+        assert_eq!(reader.read_tag(), Ok(None));
+    }
+
+    #[test]
+    fn incomplete_tag() {
+        let mut data = flv(&[tag(9, 0, &[0x13, 0x00]), tag(9, 40, &[0x23, 0x00])]);
+        data.truncate(data.len() - 6);
+        let mut reader = Reader::new(&data).unwrap();
+        assert!(reader.read_tag().unwrap().is_some());
+        assert_eq!(reader.read_tag(), Ok(None));
+    }
+
+    #[test]
+    fn script_data() {
+        let mut body = vec![2];
+        body.extend(amf_string("onMetaData"));
+        body.extend([8, 0, 0, 0, 4]);
+        body.extend(amf_string("duration"));
+        body.push(0);
+        body.extend(2.5f64.to_be_bytes());
+        body.extend(amf_string("stereo"));
+        body.extend([1, 1]);
+        body.extend(amf_string("encoder"));
+        body.push(2);
+        body.extend(amf_string("ruffle"));
+        body.extend(amf_string("times"));
+        body.extend([10, 0, 0, 0, 2, 0]);
+        body.extend(0f64.to_be_bytes());
+        body.push(5);
+        body.extend([0, 0, 9]);
+
+        let data = flv(&[tag(18, 0, &body)]);
+        let mut reader = Reader::new(&data).unwrap();
+        let script = match reader.read_tag().unwrap().unwrap().data {
+            TagData::Script(script) => script,
+            _ => panic!("Expected a script data tag"),
+        };
+
+        assert_eq!(
+            script,
+            ScriptData {
+                name: "onMetaData".to_string(),
+                value: ScriptValue::EcmaArray(vec![
+                    ("duration".to_string(), ScriptValue::Number(2.5)),
+                    ("stereo".to_string(), ScriptValue::Boolean(true)),
+                    (
+                        "encoder".to_string(),
+                        ScriptValue::String("ruffle".to_string())
+                    ),
+                    (
+                        "times".to_string(),
+                        ScriptValue::StrictArray(vec![ScriptValue::Number(0.0), ScriptValue::Null])
+                    ),
+                ]),
+            }
+        );
+        assert_eq!(
+            script
+                .value
+                .get("duration")
+                .and_then(ScriptValue::as_number),
+            Some(2.5)
+        );
+    }
+
+    #[test]
+    fn deeply_nested_script_data() {
+        let mut body = vec![2];
+        body.extend(amf_string("onCuePoint"));
+        body.extend([10, 0, 0, 0, 1].repeat(100));
+        let data = flv(&[tag(18, 0, &body)]);
+        let mut reader = Reader::new(&data).unwrap();
+        assert_eq!(reader.read_tag(), Err(Error::ScriptDataTooDeep));
+    }
+}
@@ -0,0 +1,438 @@
+//! Properties of the host system, as reported to ActionScript
+
+use bitflags::bitflags;
+use core::fmt;
+
+/// Available cpu architectures
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CpuArchitecture {
+    PowerPc,
+    X86,
+    Sparc,
+    Arm,
+}
+
+impl fmt::Display for CpuArchitecture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CpuArchitecture::PowerPc => "PowerPC",
+            CpuArchitecture::X86 => "x86",
+            CpuArchitecture::Sparc => "SPARC",
+            CpuArchitecture::Arm => "ARM",
+        })
+    }
+}
+
+/// Available type of sandbox for a given SWF
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SandboxType {
+    Remote,
+    LocalWithFile,
+    LocalWithNetwork,
+    LocalTrusted,
+}
+
+impl fmt::Display for SandboxType {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str(match self {
+            SandboxType::Remote => "remote",
+            SandboxType::LocalWithFile => "localWithFile",
+            SandboxType::LocalWithNetwork => "localWithNetwork",
+            SandboxType::LocalTrusted => "localTrusted",
+        })
+    }
+}
+
+/// The available host operating systems
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OperatingSystem {
+    WindowsXp,
+    Windows2k,
+    WindowsNt,
+    Windows98,
+    Windows95,
+    WindowsCe,
+    WindowsUnknown,
+    Linux,
+    MacOs,
+    /// A custom OS name, such as `Windows 7` or `Mac OS 10.15.7`.
+    Other(String),
+}
+
+impl fmt::Display for OperatingSystem {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str(match self {
+            OperatingSystem::WindowsXp => "Windows XP",
+            OperatingSystem::Windows2k => "Windows 2000",
+            OperatingSystem::WindowsNt => "Windows NT",
+            OperatingSystem::Windows98 => "Windows 98/ME",
+            OperatingSystem::Windows95 => "Windows 95",
+            OperatingSystem::WindowsCe => "Windows CE",
+            OperatingSystem::WindowsUnknown => "Windows",
+            OperatingSystem::Linux => "Linux",
+            OperatingSystem::MacOs => "MacOS",
+            OperatingSystem::Other(name) => name.as_str(),
+        })
+    }
+}
+
+/// The available player manufacturers
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Manufacturer {
+    Windows,
+    Macintosh,
+    Linux,
+    Other(String),
+}
+
+impl Manufacturer {
+    pub fn get_manufacturer_string(&self, version: u8) -> String {
+        let os_part = match self {
+            Manufacturer::Windows => "Windows",
+            Manufacturer::Macintosh => "Macintosh",
+            Manufacturer::Linux => "Linux",
+            Manufacturer::Other(name) => name.as_str(),
+        };
+
+        if version <= 8 {
+            format!("Macromedia {os_part}")
+        } else {
+            format!("Adobe {os_part}")
+        }
+    }
+
+    pub fn get_platform_name(&self) -> &str {
+        match self {
+            Manufacturer::Windows => "WIN",
+            Manufacturer::Macintosh => "MAC",
+            Manufacturer::Linux => "LNX",
+            _ => "",
+        }
+    }
+}
+
+/// The language of the host os
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Language {
+    Czech,
+    Danish,
+    Dutch,
+    English,
+    Finnish,
+    French,
+    German,
+    Hungarian,
+    Italian,
+    Japanese,
+    Korean,
+    Norwegian,
+    Unknown,
+    Polish,
+    Portuguese,
+    Russian,
+    SimplifiedChinese,
+    Spanish,
+    Swedish,
+    TraditionalChinese,
+    Turkish,
+}
+
+impl Language {
+    pub fn get_language_code(&self, player_version: u8) -> &str {
+        match self {
+            Language::Czech => "cs",
+            Language::Danish => "da",
+            Language::Dutch => "nl",
+            Language::English => {
+                if player_version < 7 {
+                    "en-US"
+                } else {
+                    "en"
+                }
+            }
+            Language::Finnish => "fi",
+            Language::French => "fr",
+            Language::German => "de",
+            Language::Hungarian => "hu",
+            Language::Italian => "it",
+            Language::Japanese => "ja",
+            Language::Korean => "ko",
+            Language::Norwegian => "no",
+            Language::Unknown => "xu",
+            Language::Polish => "pl",
+            Language::Portuguese => "pt",
+            Language::Russian => "ru",
+            Language::SimplifiedChinese => "zh-CN",
+            Language::Spanish => "es",
+            Language::Swedish => "sv",
+            Language::TraditionalChinese => "zh-TW",
+            Language::Turkish => "tr",
+        }
+    }
+}
+
+/// The supported colors of the screen
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScreenColor {
+    Color,
+    Gray,
+    BlackWhite,
+}
+
+impl fmt::Display for ScreenColor {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str(match self {
+            ScreenColor::Color => "color",
+            ScreenColor::Gray => "gray",
+            ScreenColor::BlackWhite => "bw",
+        })
+    }
+}
+/// The type of the player
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PlayerType {
+    StandAlone,
+    External,
+    PlugIn,
+    ActiveX,
+}
+
+impl fmt::Display for PlayerType {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str(match self {
+            PlayerType::StandAlone => "StandAlone",
+            PlayerType::External => "External",
+            PlayerType::PlugIn => "PlugIn",
+            PlayerType::ActiveX => "ActiveX",
+        })
+    }
+}
+
+bitflags! {
+    #[derive(Default)]
+    pub struct SystemCapabilities: u32 {
+        const AV_HARDWARE      = 1 << 0;
+        const ACCESSIBILITY    = 1 << 1;
+        const AUDIO            = 1 << 2;
+        const AUDIO_ENCODER    = 1 << 3;
+        const EMBEDDED_VIDEO   = 1 << 4;
+        const IME              = 1 << 5;
+        const MP3              = 1 << 6;
+        const PRINTING         = 1 << 7;
+        const SCREEN_BROADCAST = 1 << 8;
+        const SCREEN_PLAYBACK  = 1 << 9;
+        const STREAMING_AUDIO  = 1 << 10;
+        const STREAMING_VIDEO  = 1 << 11;
+        const VIDEO_ENCODER    = 1 << 12;
+        const DEBUGGER         = 1 << 13;
+        const LOCAL_FILE_READ  = 1 << 14;
+        const PROCESS_64_BIT   = 1 << 15;
+        const PROCESS_32_BIT   = 1 << 16;
+        const ACROBAT_EMBEDDED = 1 << 17;
+        const TLS              = 1 << 18;
+        const WINDOW_LESS      = 1 << 19;
+    }
+}
+
+/// The host environment reported to content through `System.capabilities`
+/// (AVM1) and `flash.system.Capabilities` (AVM2).
+///
+/// A custom set of properties can be given to `PlayerBuilder` in order to
+/// spoof a specific player, for content that checks versions or platforms.
+#[derive(Clone, Debug)]
+pub struct SystemProperties {
+    /// If true then settings should be saved and read from the exact same domain of the player
+    /// If false then they should be saved to the super domain
+    pub exact_settings: bool,
+    /// If true then the system codepage should be used instead of unicode for text files
+    /// If false then unicode should be used
+    pub use_codepage: bool,
+    /// The capabilities of the player
+    pub capabilities: SystemCapabilities,
+    /// The type of the player
+    pub player_type: PlayerType,
+    /// The type of screen available to the player
+    pub screen_color: ScreenColor,
+    /// The language of the host os
+    pub language: Language,
+    /// The resolution of the available screen
+    pub screen_resolution: (u32, u32),
+    /// The aspect ratio of the screens pixels
+    pub aspect_ratio: f32,
+    /// The dpi of the screen
+    pub dpi: f32,
+    /// The manufacturer of the player
+    pub manufacturer: Manufacturer,
+    /// The os of the host
+    pub os: OperatingSystem,
+    /// The type of the player sandbox
+    pub sandbox_type: SandboxType,
+    /// The cpu architecture of the platform
+    pub cpu_architecture: CpuArchitecture,
+    /// The highest supported h264 decoder level
+    pub idc_level: String,
+    /// The full version string of the player, such as `WIN 11,2,202,235`.
+    /// If `None`, it is derived from the manufacturer and the player version.
+    pub version: Option<String>,
+}
+
+impl SystemProperties {
+    pub fn get_version_string(&self, player_version: u8) -> String {
+        match &self.version {
+            Some(version) => version.clone(),
+            None => format!(
+                "{} {},0,0,0",
+                self.manufacturer.get_platform_name(),
+                player_version
+            ),
+        }
+    }
+
+    pub fn has_capability(&self, cap: SystemCapabilities) -> bool {
+        self.capabilities.contains(cap)
+    }
+
+    fn encode_capability(&self, cap: SystemCapabilities) -> &str {
+        if self.has_capability(cap) {
+            "t"
+        } else {
+            "f"
+        }
+    }
+
+    fn encode_not_capability(&self, cap: SystemCapabilities) -> &str {
+        if self.has_capability(cap) {
+            "f"
+        } else {
+            "t"
+        }
+    }
+
+    fn encode_string(&self, s: &str) -> String {
+        percent_encoding::utf8_percent_encode(s, percent_encoding::NON_ALPHANUMERIC).to_string()
+    }
+
+    pub fn get_server_string(&self, player_version: u8) -> String {
+        let manufacturer = self.manufacturer.get_manufacturer_string(player_version);
+        let pairs = [
+            (
+                "A",
+                self.encode_capability(SystemCapabilities::AUDIO).into(),
+            ),
+            (
+                "SA",
+                self.encode_capability(SystemCapabilities::STREAMING_AUDIO)
+                    .into(),
+            ),
+            (
+                "SV",
+                self.encode_capability(SystemCapabilities::STREAMING_VIDEO)
+                    .into(),
+            ),
+            (
+                "EV",
+                self.encode_capability(SystemCapabilities::EMBEDDED_VIDEO)
+                    .into(),
+            ),
+            (
+                "MP3",
+                self.encode_capability(SystemCapabilities::MP3).into(),
+            ),
+            (
+                "AE",
+                self.encode_capability(SystemCapabilities::AUDIO_ENCODER)
+                    .into(),
+            ),
+            (
+                "VE",
+                self.encode_capability(SystemCapabilities::VIDEO_ENCODER)
+                    .into(),
+            ),
+            (
+                "ACC",
+                self.encode_not_capability(SystemCapabilities::ACCESSIBILITY)
+                    .into(),
+            ),
+            (
+                "PR",
+                self.encode_capability(SystemCapabilities::PRINTING).into(),
+            ),
+            (
+                "SP",
+                self.encode_capability(SystemCapabilities::SCREEN_PLAYBACK)
+                    .into(),
+            ),
+            (
+                "SB",
+                self.encode_capability(SystemCapabilities::SCREEN_BROADCAST)
+                    .into(),
+            ),
+            (
+                "DEB",
+                self.encode_capability(SystemCapabilities::DEBUGGER).into(),
+            ),
+            (
+                "V",
+                self.encode_string(&self.get_version_string(player_version)),
+            ),
+            ("M", self.encode_string(&manufacturer)),
+            (
+                "R",
+                format!("{}x{}", self.screen_resolution.0, self.screen_resolution.1),
+            ),
+            ("COL", self.screen_color.to_string()),
+            ("AR", self.aspect_ratio.to_string()),
+            ("OS", self.encode_string(&self.os.to_string())),
+            ("L", self.language.get_language_code(player_version).into()),
+            (
+                "IME",
+                self.encode_capability(SystemCapabilities::IME).into(),
+            ),
+            ("PT", self.player_type.to_string()),
+            (
+                "AVD",
+                self.encode_not_capability(SystemCapabilities::AV_HARDWARE)
+                    .into(),
+            ),
+            (
+                "LFD",
+                self.encode_not_capability(SystemCapabilities::LOCAL_FILE_READ)
+                    .into(),
+            ),
+            ("DP", self.dpi.to_string()),
+        ];
+
+        // Values are percent-encoded individually, as Flash Player encodes
+        // spaces as `%20` rather than `+`.
+        pairs
+            .iter()
+            .map(|(key, value): &(&str, String)| format!("{key}={value}"))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+impl Default for SystemProperties {
+    fn default() -> Self {
+        SystemProperties {
+            //TODO: default to true on fp>=7, false <= 6
+            exact_settings: true,
+            //TODO: default to false on fp>=7, true <= 6
+            use_codepage: false,
+            capabilities: SystemCapabilities::empty(),
+            player_type: PlayerType::StandAlone,
+            screen_color: ScreenColor::Color,
+            // TODO: note for fp <7 this should be the locale and the ui lang for >= 7, on windows
+            language: Language::English,
+            screen_resolution: (0, 0),
+            aspect_ratio: 1_f32,
+            dpi: 1_f32,
+            manufacturer: Manufacturer::Linux,
+            os: OperatingSystem::Linux,
+            sandbox_type: SandboxType::LocalTrusted,
+            cpu_architecture: CpuArchitecture::X86,
+            idc_level: "5.1".into(),
+            version: None,
+        }
+    }
+}
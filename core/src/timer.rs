@@ -9,7 +9,7 @@ use crate::avm1::{
     Activation, ActivationIdentifier, Object as Avm1Object, TObject as _, Value as Avm1Value,
 };
 use crate::avm2::object::TObject;
-use crate::avm2::{Activation as Avm2Activation, Object as Avm2Object, Value as Avm2Value};
+use crate::avm2::{Activation as Avm2Activation, Avm2, Object as Avm2Object, Value as Avm2Value};
 use crate::context::UpdateContext;
 use crate::string::AvmString;
 use gc_arena::Collect;
@@ -121,7 +121,12 @@ impl<'gc> Timers<'gc> {
                     match closure.call(None, &params, &mut avm2_activation) {
                         Ok(v) => v.coerce_to_boolean(),
                         Err(e) => {
-                            log::error!("Unhandled AVM2 error in timer callback: {}", e);
+                            Avm2::uncaught_error(
+                                &mut avm2_activation,
+                                None,
+                                e,
+                                "Unhandled AVM2 error in timer callback",
+                            );
                             false
                         }
                     }
@@ -0,0 +1,39 @@
+use rfd::{MessageButtons, MessageDialog, MessageLevel};
+use ruffle_core::backend::log::LogBackend;
+use std::cell::Cell;
+
+pub struct DesktopLogBackend {
+    show_uncaught_errors: bool,
+    shown_uncaught_error: Cell<bool>,
+}
+
+impl DesktopLogBackend {
+    pub fn new(show_uncaught_errors: bool) -> Self {
+        Self {
+            show_uncaught_errors,
+            shown_uncaught_error: Cell::new(false),
+        }
+    }
+}
+
+impl LogBackend for DesktopLogBackend {
+    fn avm_trace(&self, message: &str) {
+        log::info!(target: "avm_trace", "{}", message);
+    }
+
+    fn avm_uncaught_error(&self, message: &str, stack_trace: &str) {
+        // Like the debug Flash Player, report the error in a dialog. Only the first
+        // error is shown, as a broken movie may throw one on every frame; core logs
+        // all of them.
+        if !self.show_uncaught_errors || self.shown_uncaught_error.replace(true) {
+            return;
+        }
+
+        let dialog = MessageDialog::new()
+            .set_level(MessageLevel::Error)
+            .set_title("Ruffle - Uncaught error")
+            .set_description(&format!("{message}\n{stack_trace}"))
+            .set_buttons(MessageButtons::Ok);
+        dialog.show();
+    }
+}
@@ -8,6 +8,7 @@
 mod audio;
 mod custom_event;
 mod executor;
+mod logging;
 mod navigator;
 mod storage;
 mod task;
@@ -101,12 +102,22 @@ struct Opt {
     #[clap(long, action)]
     dont_warn_on_unsupported_content: bool,
 
+    /// Show the first ActionScript error that isn't caught by the movie in a dialog.
+    #[clap(long, action)]
+    show_uncaught_errors: bool,
+
     #[clap(long, default_value = "streaming")]
     load_behavior: LoadBehavior,
 
     /// Spoofs the root SWF URL provided to ActionScript.
     #[clap(long, value_parser)]
     spoof_url: Option<Url>,
+
+    /// Profile script execution and write the profile to this path on exit.
+    /// Paths ending in ".json" are written in the Chrome trace format, anything
+    /// else as folded stacks for flamegraph tools.
+    #[clap(long)]
+    profile: Option<PathBuf>,
 }
 
 #[cfg(feature = "render_trace")]
@@ -267,6 +278,7 @@ impl App {
         }
 
         builder = builder
+            .with_log(logging::DesktopLogBackend::new(opt.show_uncaught_errors))
             .with_navigator(navigator)
             .with_renderer(renderer)
             .with_storage(storage::DiskStorageBackend::new())
@@ -276,7 +288,8 @@ impl App {
             .with_warn_on_unsupported_content(!opt.dont_warn_on_unsupported_content)
             .with_fullscreen(opt.fullscreen)
             .with_load_behavior(opt.load_behavior)
-            .with_spoofed_url(opt.spoof_url.clone().map(|url| url.to_string()));
+            .with_spoofed_url(opt.spoof_url.clone().map(|url| url.to_string()))
+            .with_profiling(opt.profile.is_some());
 
         let player = builder.build();
 
@@ -361,6 +374,9 @@ impl App {
                 match event {
                     winit::event::Event::LoopDestroyed => {
                         self.player.lock().unwrap().flush_shared_objects();
+                        if let Some(path) = &self.opt.profile {
+                            write_profile(&self.player.lock().unwrap(), path);
+                        }
                         shutdown();
                         return;
                     }
@@ -851,6 +867,23 @@ fn panic_hook() {
     });
 }
 
+fn write_profile(player: &Player, path: &Path) {
+    let profiler = match player.profiler() {
+        Some(profiler) => profiler,
+        None => return,
+    };
+
+    let output = if path.extension().map_or(false, |ext| ext == "json") {
+        profiler.to_chrome_trace()
+    } else {
+        profiler.to_folded_stacks()
+    };
+
+    if let Err(e) = std::fs::write(path, output) {
+        log::error!("Unable to write profile to {}: {}", path.display(), e);
+    }
+}
+
 fn shutdown() {
     // Without explicitly detaching the console cmd won't redraw it's prompt.
     #[cfg(windows)]
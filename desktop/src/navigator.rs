@@ -9,8 +9,13 @@ use ruffle_core::backend::navigator::{
 };
 use ruffle_core::indexmap::IndexMap;
 use ruffle_core::loader::Error;
+use ruffle_core::socket::{ConnectionState, SocketAction, SocketHandle};
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
 use std::rc::Rc;
-use std::sync::mpsc::Sender;
+use std::sync::mpsc::{Receiver, Sender};
+use std::thread;
+use std::time::Duration;
 use url::Url;
 use winit::event_loop::EventLoopProxy;
 
@@ -138,7 +143,6 @@ impl NavigatorBackend for ExternalNavigatorBackend {
                 let body = std::fs::read(&path).or_else(|e| {
                     if cfg!(feature = "sandbox") {
                         use rfd::{FileDialog, MessageButtons, MessageDialog, MessageLevel};
-                        use std::io::ErrorKind;
 
                         if e.kind() == ErrorKind::PermissionDenied {
                             let attempt_sandbox_open = MessageDialog::new()
@@ -219,4 +223,84 @@ impl NavigatorBackend for ExternalNavigatorBackend {
         }
         url
     }
+
+    fn connect_socket(
+        &mut self,
+        host: String,
+        port: u16,
+        timeout: Duration,
+        handle: SocketHandle,
+        receiver: Receiver<Vec<u8>>,
+        sender: Sender<SocketAction>,
+    ) {
+        thread::spawn(move || {
+            let stream = match connect_tcp(&host, port, timeout) {
+                Ok(stream) => stream,
+                Err(e) => {
+                    log::warn!("Could not connect to {}:{}: {}", host, port, e);
+                    let state = if e.kind() == ErrorKind::TimedOut {
+                        ConnectionState::TimedOut
+                    } else {
+                        ConnectionState::Failed
+                    };
+                    let _ = sender.send(SocketAction::Connect(handle, state));
+                    return;
+                }
+            };
+
+            let mut reader = match stream.try_clone() {
+                Ok(reader) => reader,
+                Err(e) => {
+                    log::warn!("Could not use connection to {}:{}: {}", host, port, e);
+                    let _ = sender.send(SocketAction::Connect(handle, ConnectionState::Failed));
+                    return;
+                }
+            };
+
+            if sender
+                .send(SocketAction::Connect(handle, ConnectionState::Connected))
+                .is_err()
+            {
+                return;
+            }
+
+            thread::spawn(move || {
+                let mut buffer = [0; 4096];
+                loop {
+                    let action = match reader.read(&mut buffer) {
+                        Ok(0) | Err(_) => SocketAction::Close(handle),
+                        Ok(read) => SocketAction::Data(handle, buffer[..read].to_vec()),
+                    };
+                    let closed = matches!(action, SocketAction::Close(_));
+                    if sender.send(action).is_err() || closed {
+                        break;
+                    }
+                }
+            });
+
+            // Write everything the movie sends, until it closes the connection.
+            let mut writer = stream;
+            for data in receiver {
+                if let Err(e) = writer.write_all(&data) {
+                    log::warn!("Could not send data to {}:{}: {}", host, port, e);
+                    break;
+                }
+            }
+
+            let _ = writer.shutdown(Shutdown::Both);
+        });
+    }
+}
+
+/// Connect to the first address of `host` that accepts a connection.
+fn connect_tcp(host: &str, port: u16, timeout: Duration) -> std::io::Result<TcpStream> {
+    let mut last_error = None;
+    for address in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&address, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| ErrorKind::NotFound.into()))
 }
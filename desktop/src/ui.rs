@@ -55,6 +55,10 @@ impl UiBackend for DesktopUiBackend {
         self.clipboard.set_text(content).unwrap();
     }
 
+    fn clipboard_content(&mut self) -> String {
+        self.clipboard.get_text().unwrap_or_default()
+    }
+
     fn set_fullscreen(&mut self, is_full: bool) -> Result<(), FullscreenError> {
         self.window.set_fullscreen(if is_full {
             Some(Fullscreen::Borderless(None))
@@ -90,6 +90,7 @@ enum CanvasDrawCommand {
     Fill {
         path: Path2d,
         fill_style: CanvasFillStyle,
+        winding_rule: CanvasWindingRule,
     },
 }
 
@@ -510,7 +511,11 @@ impl<'a> CommandHandler<'a> for WebCanvasRenderBackend {
                 if let Some(shape) = self.shapes.get(shape.0) {
                     for command in shape.0.iter() {
                         match command {
-                            CanvasDrawCommand::Fill { path, fill_style } => {
+                            CanvasDrawCommand::Fill {
+                                path,
+                                fill_style,
+                                winding_rule,
+                            } => {
                                 if transform_dirty {
                                     let _ = self.context.set_transform(
                                         transform.matrix.a.into(),
@@ -527,10 +532,8 @@ impl<'a> CommandHandler<'a> for WebCanvasRenderBackend {
                                         let color =
                                             color.color_transform(&transform.color_transform);
                                         self.context.set_fill_style(&color.0.into());
-                                        self.context.fill_with_path_2d_and_winding(
-                                            path,
-                                            CanvasWindingRule::Evenodd,
-                                        );
+                                        self.context
+                                            .fill_with_path_2d_and_winding(path, *winding_rule);
                                     }
                                     CanvasFillStyle::Gradient(gradient) => {
                                         self.set_color_filter(transform);
@@ -553,13 +556,11 @@ impl<'a> CommandHandler<'a> for WebCanvasRenderBackend {
                                             );
                                             self.context.fill_with_path_2d_and_winding(
                                                 &untransformed_path,
-                                                CanvasWindingRule::Evenodd,
+                                                *winding_rule,
                                             );
                                         } else {
-                                            self.context.fill_with_path_2d_and_winding(
-                                                path,
-                                                CanvasWindingRule::Evenodd,
-                                            );
+                                            self.context
+                                                .fill_with_path_2d_and_winding(path, *winding_rule);
                                         }
 
                                         self.clear_color_filter();
@@ -568,10 +569,8 @@ impl<'a> CommandHandler<'a> for WebCanvasRenderBackend {
                                         self.set_color_filter(transform);
                                         self.context.set_image_smoothing_enabled(bitmap.smoothed);
                                         self.context.set_fill_style(&bitmap.pattern);
-                                        self.context.fill_with_path_2d_and_winding(
-                                            path,
-                                            CanvasWindingRule::Evenodd,
-                                        );
+                                        self.context
+                                            .fill_with_path_2d_and_winding(path, *winding_rule);
                                         self.clear_color_filter();
                                     }
                                 }
@@ -800,7 +799,7 @@ fn swf_shape_to_canvas_commands(
     bitmap_source: &dyn BitmapSource,
     backend: &mut WebCanvasRenderBackend,
 ) -> ShapeData {
-    use ruffle_render::shape_utils::DrawPath;
+    use ruffle_render::shape_utils::{DrawPath, FillRule};
     use swf::{FillStyle, LineCapStyle, LineJoinStyle};
 
     // Some browsers will vomit if you try to load/draw an image with 0 width/height.
@@ -816,7 +815,9 @@ fn swf_shape_to_canvas_commands(
     for path in &shape.paths {
         match path {
             DrawPath::Fill {
-                commands, style, ..
+                commands,
+                style,
+                winding_rule,
             } => {
                 let canvas_path = Path2d::new().unwrap();
                 canvas_path.add_path_with_transformation(
@@ -869,6 +870,10 @@ fn swf_shape_to_canvas_commands(
                 canvas_data.0.push(CanvasDrawCommand::Fill {
                     path: canvas_path,
                     fill_style,
+                    winding_rule: match winding_rule {
+                        FillRule::EvenOdd => CanvasWindingRule::Evenodd,
+                        FillRule::NonZero => CanvasWindingRule::Nonzero,
+                    },
                 });
             }
             DrawPath::Stroke {
@@ -1,8 +1,9 @@
 use std::io::Read;
 
 use naga::{
-    ArraySize, BuiltIn, Constant, ConstantInner, EntryPoint, FunctionArgument, FunctionResult,
-    GlobalVariable, Interpolation, ScalarValue, ShaderStage, StructMember, SwizzleComponent,
+    ArraySize, Block, BuiltIn, Constant, ConstantInner, DerivativeAxis, EntryPoint,
+    FunctionArgument, FunctionResult, GlobalVariable, ImageClass, ImageDimension, Interpolation,
+    SampleLevel, ScalarValue, ShaderStage, StructMember, SwizzleComponent, UnaryOperator,
 };
 use naga::{BinaryOperator, MathFunction};
 use naga::{
@@ -12,7 +13,9 @@ use naga::{
 use num_traits::FromPrimitive;
 
 use crate::{
-    types::*, Error, ShaderType, VertexAttributeFormat, ENTRY_POINT, MAX_VERTEX_ATTRIBUTES,
+    sampler_binding, types::*, Error, SamplerOverride, ShaderType, VertexAttributeFormat,
+    ENTRY_POINT, MAX_TEXTURES, MAX_VERTEX_ATTRIBUTES, NUM_SAMPLERS, SAMPLER_START_BIND_INDEX,
+    TEXTURE_START_BIND_INDEX,
 };
 
 const VERTEX_PROGRAM_CONTANTS: u64 = 128;
@@ -38,10 +41,31 @@ pub(crate) struct NagaBuilder<'a> {
 
     varying_pointers: Vec<Option<Handle<Expression>>>,
 
+    // An `Expression::LocalVariable` for each temporary register used by the shader.
+    temporaries: Vec<Option<Handle<Expression>>>,
+
+    // The `ife`/`ine`/`ifg`/`ifl` blocks that we're currently inside of.
+    // Rather than using a `Statement::If` (which would put derivatives and texture
+    // sampling into non-uniform control flow), every register write inside of a
+    // conditional block only takes effect when the block's condition is true.
+    conditionals: Vec<Conditional>,
+
+    // An `Expression::LocalVariable` for the bool set by `kil`. The fragment
+    // is discarded at the end of the shader, for the same reason as above.
+    killed: Option<Handle<Expression>>,
+
     // An `Expression::GlobalVariables` for the uniform buffer
     // that stores all of the program constants.
     constant_registers: Handle<Expression>,
 
+    // An `Expression::GlobalVariable` for each texture used by the shader,
+    // along with the dimension it was declared with.
+    texture_bindings: [Option<(Dimension, Handle<Expression>)>; MAX_TEXTURES],
+
+    // An `Expression::GlobalVariable` for each sampler used by the shader,
+    // indexed by binding id (starting from `SAMPLER_START_BIND_INDEX`).
+    sampler_bindings: [Option<Handle<Expression>>; NUM_SAMPLERS as usize],
+
     // The function return type being built up. Each time a vertex
     // shader writes to a varying register, we add a new member to this
     return_type: Type,
@@ -52,6 +76,16 @@ pub(crate) struct NagaBuilder<'a> {
     matrix4x4f: Handle<Type>,
 }
 
+struct Conditional {
+    // The condition of the `if` instruction
+    condition: Handle<Expression>,
+    // The condition under which the enclosing block is executed, if any
+    parent: Option<Handle<Expression>>,
+    // The condition under which the current (`if` or `else`) part of this block is executed
+    active: Handle<Expression>,
+    in_else: bool,
+}
+
 impl VertexAttributeFormat {
     fn to_naga_type(self, module: &mut Module) -> Handle<Type> {
         if let VertexAttributeFormat::Float1 = self {
@@ -71,7 +105,8 @@ impl VertexAttributeFormat {
             VertexAttributeFormat::Float2 => (VectorSize::Bi, 4, ScalarKind::Float),
             VertexAttributeFormat::Float3 => (VectorSize::Tri, 4, ScalarKind::Float),
             VertexAttributeFormat::Float4 => (VectorSize::Quad, 4, ScalarKind::Float),
-            VertexAttributeFormat::Bytes4 => (VectorSize::Quad, 1, ScalarKind::Uint),
+            // These are normalized to floats in the [0, 1] range by the vertex fetch.
+            VertexAttributeFormat::Bytes4 => (VectorSize::Quad, 4, ScalarKind::Float),
         };
 
         module.types.insert(
@@ -88,43 +123,33 @@ impl VertexAttributeFormat {
         base_expr: Handle<Expression>,
         builder: &mut NagaBuilder,
     ) -> Result<Handle<Expression>> {
-        Ok(match self {
-            // This does 'vec4f(my_vec3, 1.0)'
-            VertexAttributeFormat::Float3 => {
-                let expr = base_expr;
-                let mut components = vec![];
-                for i in 0..3 {
-                    components.push(builder.evaluate_expr(Expression::AccessIndex {
-                        base: expr,
-                        index: i,
-                    }));
-                }
-                components.push(builder.func.expressions.append(
-                    Expression::Constant(builder.module.constants.append(
-                        Constant {
-                            name: None,
-                            specialization: None,
-                            inner: ConstantInner::Scalar {
-                                width: 4,
-                                value: ScalarValue::Float(1.0),
-                            },
-                        },
-                        Span::UNDEFINED,
-                    )),
-                    Span::UNDEFINED,
-                ));
-                builder.evaluate_expr(Expression::Compose {
-                    ty: builder.vec4f,
-                    components,
-                })
-            }
-            VertexAttributeFormat::Float4 => base_expr,
-            _ => {
-                return Err(Error::Unimplemented(format!(
-                    "Unsupported conversion from {self:?} to float4",
-                )))
+        // Missing components are filled in from 'vec4f(0.0, 0.0, 0.0, 1.0)'
+        let num_components = match self {
+            VertexAttributeFormat::Float1 => 1,
+            VertexAttributeFormat::Float2 => 2,
+            VertexAttributeFormat::Float3 => 3,
+            VertexAttributeFormat::Float4 | VertexAttributeFormat::Bytes4 => return Ok(base_expr),
+        };
+
+        let mut components = vec![];
+        if num_components == 1 {
+            components.push(base_expr);
+        } else {
+            for i in 0..num_components {
+                components.push(builder.evaluate_expr(Expression::AccessIndex {
+                    base: base_expr,
+                    index: i,
+                }));
             }
-        })
+        }
+        for i in num_components..4 {
+            components.push(builder.float_constant(if i == 3 { 1.0 } else { 0.0 }));
+        }
+
+        Ok(builder.evaluate_expr(Expression::Compose {
+            ty: builder.vec4f,
+            components,
+        }))
     }
 }
 
@@ -132,12 +157,14 @@ impl VertexAttributeFormat {
 pub struct ShaderConfig<'a> {
     pub shader_type: ShaderType,
     pub vertex_attributes: &'a [Option<VertexAttributeFormat>; 8],
+    pub sampler_overrides: &'a [Option<SamplerOverride>; MAX_TEXTURES],
 }
 
 impl<'a> NagaBuilder<'a> {
     pub fn process_agal(
         mut agal: &[u8],
         vertex_attributes: &[Option<VertexAttributeFormat>; MAX_VERTEX_ATTRIBUTES],
+        sampler_overrides: &[Option<SamplerOverride>; MAX_TEXTURES],
     ) -> Result<Module> {
         let data = &mut agal;
 
@@ -157,6 +184,7 @@ impl<'a> NagaBuilder<'a> {
         let mut builder = NagaBuilder::new(ShaderConfig {
             shader_type,
             vertex_attributes,
+            sampler_overrides,
         });
 
         while !data.is_empty() {
@@ -327,17 +355,108 @@ impl<'a> NagaBuilder<'a> {
             shader_config,
             argument_expressions: vec![],
             varying_pointers: vec![],
+            temporaries: vec![],
+            conditionals: vec![],
+            killed: None,
             return_type,
             matrix4x4f,
             vec4f,
             constant_registers,
+            texture_bindings: [None; MAX_TEXTURES],
+            sampler_bindings: [None; NUM_SAMPLERS as usize],
+        }
+    }
+
+    fn get_texture(&mut self, sampler: &SamplerField) -> Result<Handle<Expression>> {
+        let index = sampler.reg_num as usize;
+        if let Some((dimension, expr)) = self.texture_bindings[index] {
+            // A sampler register has a single texture bound to it,
+            // so it must be used with the same dimension everywhere.
+            if dimension != sampler.dimension {
+                return Err(Error::MismatchedTextureDimension(sampler.reg_num));
+            }
+            return Ok(expr);
+        }
+
+        let dim = match sampler.dimension {
+            Dimension::TwoD => ImageDimension::D2,
+            Dimension::Cube => ImageDimension::Cube,
+            Dimension::ThreeD => ImageDimension::D3,
+        };
+
+        let global = self.module.global_variables.append(
+            GlobalVariable {
+                name: Some(format!("texture{index}")),
+                space: naga::AddressSpace::Handle,
+                binding: Some(naga::ResourceBinding {
+                    group: 0,
+                    binding: TEXTURE_START_BIND_INDEX + index as u32,
+                }),
+                ty: self.module.types.insert(
+                    Type {
+                        name: None,
+                        inner: TypeInner::Image {
+                            dim,
+                            arrayed: false,
+                            class: ImageClass::Sampled {
+                                kind: ScalarKind::Float,
+                                multi: false,
+                            },
+                        },
+                    },
+                    Span::UNDEFINED,
+                ),
+                init: None,
+            },
+            Span::UNDEFINED,
+        );
+
+        let expr = self
+            .func
+            .expressions
+            .append(Expression::GlobalVariable(global), Span::UNDEFINED);
+        self.texture_bindings[index] = Some((sampler.dimension, expr));
+        Ok(expr)
+    }
+
+    fn get_sampler(&mut self, wrapping: Wrapping, filter: Filter) -> Handle<Expression> {
+        let binding = sampler_binding(wrapping, filter);
+        let index = (binding - SAMPLER_START_BIND_INDEX) as usize;
+        if let Some(expr) = self.sampler_bindings[index] {
+            return expr;
         }
+
+        let global = self.module.global_variables.append(
+            GlobalVariable {
+                name: Some(format!("sampler{index}")),
+                space: naga::AddressSpace::Handle,
+                binding: Some(naga::ResourceBinding { group: 0, binding }),
+                ty: self.module.types.insert(
+                    Type {
+                        name: None,
+                        inner: TypeInner::Sampler { comparison: false },
+                    },
+                    Span::UNDEFINED,
+                ),
+                init: None,
+            },
+            Span::UNDEFINED,
+        );
+
+        let expr = self
+            .func
+            .expressions
+            .append(Expression::GlobalVariable(global), Span::UNDEFINED);
+        self.sampler_bindings[index] = Some(expr);
+        expr
     }
 
     fn get_vertex_input(&mut self, index: usize) -> Result<Handle<Expression>> {
         if index >= self.argument_expressions.len() {
             self.argument_expressions.resize(index + 1, None);
+        }
 
+        if self.argument_expressions[index].is_none() {
             // FIXME - this is a clippy false-positive
             #[allow(clippy::or_fun_call)]
             let ty = self.shader_config.vertex_attributes[index]
@@ -345,6 +464,9 @@ impl<'a> NagaBuilder<'a> {
                 .ok_or(Error::MissingVertexAttributeData(index))?
                 .to_naga_type(&mut self.module);
 
+            // Arguments are added in the order that the vertex attributes are first used,
+            // and are bound to the location of the corresponding vertex attribute.
+            let argument_index = self.func.arguments.len() as u32;
             self.func.arguments.push(FunctionArgument {
                 name: None,
                 ty,
@@ -355,11 +477,10 @@ impl<'a> NagaBuilder<'a> {
                 }),
             });
 
-            // Arguments map one-to-one to vertex attributes.
-            let expr = self
-                .func
-                .expressions
-                .append(Expression::FunctionArgument(index as u32), Span::UNDEFINED);
+            let expr = self.func.expressions.append(
+                Expression::FunctionArgument(argument_index),
+                Span::UNDEFINED,
+            );
             self.argument_expressions[index] = Some(expr);
         }
         Ok(self.argument_expressions[index].unwrap())
@@ -413,6 +534,7 @@ impl<'a> NagaBuilder<'a> {
                     self.varying_pointers[index] = Some(expr);
                 }
                 ShaderType::Fragment => {
+                    let argument_index = self.func.arguments.len() as u32;
                     self.func.arguments.push(FunctionArgument {
                         name: None,
                         ty: self.vec4f,
@@ -423,10 +545,10 @@ impl<'a> NagaBuilder<'a> {
                         }),
                     });
 
-                    let expr = self
-                        .func
-                        .expressions
-                        .append(Expression::FunctionArgument(index as u32), Span::UNDEFINED);
+                    let expr = self.func.expressions.append(
+                        Expression::FunctionArgument(argument_index),
+                        Span::UNDEFINED,
+                    );
                     self.varying_pointers[index] = Some(expr);
                 }
             };
@@ -435,34 +557,40 @@ impl<'a> NagaBuilder<'a> {
         Ok(self.varying_pointers[index].unwrap())
     }
 
-    fn emit_const_register_load(&mut self, index: usize) -> Result<Handle<Expression>> {
-        let index_const = self.module.constants.append(
-            Constant {
-                name: None,
-                specialization: None,
-                inner: ConstantInner::Scalar {
-                    width: 4,
-                    value: ScalarValue::Uint(index as u64),
-                },
+    fn get_temporary_pointer(&mut self, index: usize) -> Handle<Expression> {
+        if index >= self.temporaries.len() {
+            self.temporaries.resize(index + 1, None);
+        }
+
+        if let Some(expr) = self.temporaries[index] {
+            return expr;
+        }
+
+        let local = self.func.local_variables.append(
+            LocalVariable {
+                name: Some(format!("temporary{index}")),
+                ty: self.vec4f,
+                init: None,
             },
             Span::UNDEFINED,
         );
-        let index_expr = self
+        let expr = self
             .func
             .expressions
-            .append(Expression::Constant(index_const), Span::UNDEFINED);
+            .append(Expression::LocalVariable(local), Span::UNDEFINED);
+        self.temporaries[index] = Some(expr);
+        expr
+    }
 
-        let register_pointer = self.func.expressions.append(
-            Expression::Access {
-                base: self.constant_registers,
-                index: index_expr,
-            },
-            Span::UNDEFINED,
-        );
+    fn emit_const_register_load(&mut self, index: Handle<Expression>) -> Handle<Expression> {
+        let register_pointer = self.evaluate_expr(Expression::Access {
+            base: self.constant_registers,
+            index,
+        });
 
-        Ok(self.evaluate_expr(Expression::Load {
+        self.evaluate_expr(Expression::Load {
             pointer: register_pointer,
-        }))
+        })
     }
 
     fn emit_varying_load(&mut self, index: usize) -> Result<Handle<Expression>> {
@@ -476,47 +604,89 @@ impl<'a> NagaBuilder<'a> {
         })
     }
 
-    fn emit_source_field_load(
+    /// Loads the (unswizzled) value of a register, extended to a vec4f.
+    fn emit_register_load(
         &mut self,
-        source: &SourceField,
-        extend_to_vec4: bool,
+        register_type: &RegisterType,
+        reg_num: usize,
     ) -> Result<Handle<Expression>> {
-        let (mut base_expr, source_type) = match source.register_type {
+        Ok(match register_type {
             // We can use a function argument directly - we don't need
             // a separate Expression::Load
-            RegisterType::Attribute => (
-                self.get_vertex_input(source.reg_num as usize)?,
+            RegisterType::Attribute => {
+                let base_expr = self.get_vertex_input(reg_num)?;
                 // FIXME - this is a clippy false-positive
                 #[allow(clippy::or_fun_call)]
-                self.shader_config.vertex_attributes[source.reg_num as usize]
-                    .ok_or(Error::MissingVertexAttributeData(source.reg_num as usize))?,
-            ),
-            RegisterType::Varying => (
-                self.emit_varying_load(source.reg_num as usize)?,
-                VertexAttributeFormat::Float4,
-            ),
-            RegisterType::Constant => (
-                self.emit_const_register_load(source.reg_num as usize)?,
-                // Constants are always a vec4<f32>
-                VertexAttributeFormat::Float4,
-            ),
+                let source_type = self.shader_config.vertex_attributes[reg_num]
+                    .ok_or(Error::MissingVertexAttributeData(reg_num))?;
+                // On the ActionScript side, the user might have specified something *other* than
+                // vec4f, so we need to extend it to a vec4f.
+                source_type.extend_to_float4(base_expr, self)?
+            }
+            RegisterType::Varying => self.emit_varying_load(reg_num)?,
+            // Constants are always a vec4<f32>
+            RegisterType::Constant => {
+                let index = self.uint_constant(reg_num as u64);
+                self.emit_const_register_load(index)
+            }
+            RegisterType::Temporary => {
+                let pointer = self.get_temporary_pointer(reg_num);
+                self.evaluate_expr(Expression::Load { pointer })
+            }
             _ => {
                 return Err(Error::Unimplemented(format!(
-                    "Unimplemented source reg type {:?}",
-                    source.register_type
+                    "Unimplemented source reg type {register_type:?}",
                 )))
             }
-        };
+        })
+    }
 
-        if matches!(source.direct_mode, DirectMode::Indirect) {
-            return Err(Error::Unimplemented(
-                "Indirect addressing not implemented".to_string(),
-            ));
-        }
+    fn emit_source_field_load(&mut self, source: &SourceField) -> Result<Handle<Expression>> {
+        self.emit_source_field_row_load(source, 0)
+    }
 
-        if extend_to_vec4 && source_type != VertexAttributeFormat::Float4 {
-            base_expr = source_type.extend_to_float4(base_expr, self)?;
-        }
+    /// Loads the register `row` registers after the one specified by `source`.
+    /// This is used to read the rows of a matrix, which are stored in consecutive registers.
+    fn emit_source_field_row_load(
+        &mut self,
+        source: &SourceField,
+        row: u16,
+    ) -> Result<Handle<Expression>> {
+        let base_expr = match source.direct_mode {
+            DirectMode::Direct => {
+                self.emit_register_load(&source.register_type, (source.reg_num + row) as usize)?
+            }
+            // With relative addressing, 'reg_num' is the index register, and
+            // one of its components (plus 'indirect_offset') selects the constant register,
+            // like 'vc[va0.x + 5]'.
+            DirectMode::Indirect => {
+                if source.register_type != RegisterType::Constant {
+                    return Err(Error::Unimplemented(format!(
+                        "Indirect addressing of {:?} registers",
+                        source.register_type
+                    )));
+                }
+
+                let index_register =
+                    self.emit_register_load(&source.index_type, source.reg_num as usize)?;
+                let index_component = self.evaluate_expr(Expression::AccessIndex {
+                    base: index_register,
+                    index: source.index_select as u32,
+                });
+                let index = self.evaluate_expr(Expression::As {
+                    expr: index_component,
+                    kind: ScalarKind::Uint,
+                    convert: Some(4),
+                });
+                let offset = self.uint_constant(source.indirect_offset as u64 + row as u64);
+                let index = self.evaluate_expr(Expression::Binary {
+                    op: BinaryOperator::Add,
+                    left: index,
+                    right: offset,
+                });
+                self.emit_const_register_load(index)
+            }
+        };
 
         // Swizzle is 'xyzw', which is a no-op. Just return the base expression.
         if source.swizzle == 0b11100100 {
@@ -543,20 +713,18 @@ impl<'a> NagaBuilder<'a> {
             .try_into()
             .unwrap();
 
-        Ok(self.func.expressions.append(
-            Expression::Swizzle {
-                size: VectorSize::Quad,
-                vector: base_expr,
-                pattern: swizzle_components,
-            },
-            Span::UNDEFINED,
-        ))
+        Ok(self.evaluate_expr(Expression::Swizzle {
+            size: VectorSize::Quad,
+            vector: base_expr,
+            pattern: swizzle_components,
+        }))
     }
 
     fn emit_dest_store(&mut self, dest: &DestField, expr: Handle<Expression>) -> Result<()> {
         let base_expr = match dest.register_type {
             RegisterType::Output => self.dest,
             RegisterType::Varying => self.get_varying_pointer(dest.reg_num as usize)?,
+            RegisterType::Temporary => self.get_temporary_pointer(dest.reg_num as usize),
             _ => {
                 return Err(Error::Unimplemented(format!(
                     "Unimplemented dest reg type: {dest:?}",
@@ -567,33 +735,45 @@ impl<'a> NagaBuilder<'a> {
         // Optimization - use a Store instead of writing individual fields
         // when we're writing to the entire output register.
         if dest.write_mask.is_all() {
-            let store = Statement::Store {
-                pointer: base_expr,
-                value: expr,
-            };
-            self.func.body.push(store, Span::UNDEFINED);
+            self.emit_store(base_expr, expr);
         } else {
             for (i, mask) in [(0, Mask::X), (1, Mask::Y), (2, Mask::Z), (3, Mask::W)] {
                 if dest.write_mask.contains(mask) {
-                    self.func.body.push(
-                        Statement::Store {
-                            pointer: self.func.expressions.append(
-                                Expression::AccessIndex {
-                                    base: base_expr,
-                                    index: i,
-                                },
-                                Span::UNDEFINED,
-                            ),
-                            value: expr,
-                        },
-                        Span::UNDEFINED,
-                    );
+                    let pointer = self.evaluate_expr(Expression::AccessIndex {
+                        base: base_expr,
+                        index: i,
+                    });
+                    let value = self.evaluate_expr(Expression::AccessIndex {
+                        base: expr,
+                        index: i,
+                    });
+                    self.emit_store(pointer, value);
                 }
             }
         }
         Ok(())
     }
 
+    /// Stores `value` to `pointer`. Inside of a conditional block, the old value
+    /// is kept when the block isn't being executed.
+    fn emit_store(&mut self, pointer: Handle<Expression>, value: Handle<Expression>) {
+        let value = match self.conditionals.last() {
+            Some(conditional) => {
+                let condition = conditional.active;
+                let old_value = self.evaluate_expr(Expression::Load { pointer });
+                self.evaluate_expr(Expression::Select {
+                    condition,
+                    accept: value,
+                    reject: old_value,
+                })
+            }
+            None => value,
+        };
+        self.func
+            .body
+            .push(Statement::Store { pointer, value }, Span::UNDEFINED);
+    }
+
     /// Creates a `Statement::Emit` covering `expr`
     fn evaluate_expr(&mut self, expr: Expression) -> Handle<Expression> {
         let prev_len = self.func.expressions.len();
@@ -603,6 +783,129 @@ impl<'a> NagaBuilder<'a> {
         expr
     }
 
+    fn scalar_constant(&mut self, value: ScalarValue) -> Handle<Expression> {
+        let constant = self.module.constants.append(
+            Constant {
+                name: None,
+                specialization: None,
+                inner: ConstantInner::Scalar { width: 4, value },
+            },
+            Span::UNDEFINED,
+        );
+        self.func
+            .expressions
+            .append(Expression::Constant(constant), Span::UNDEFINED)
+    }
+
+    fn float_constant(&mut self, value: f32) -> Handle<Expression> {
+        self.scalar_constant(ScalarValue::Float(value as f64))
+    }
+
+    fn uint_constant(&mut self, value: u64) -> Handle<Expression> {
+        self.scalar_constant(ScalarValue::Uint(value))
+    }
+
+    /// Creates a vec4f with all components set to the scalar `value`
+    fn emit_splat(&mut self, value: Handle<Expression>) -> Handle<Expression> {
+        self.evaluate_expr(Expression::Splat {
+            size: VectorSize::Quad,
+            value,
+        })
+    }
+
+    fn emit_math(
+        &mut self,
+        fun: MathFunction,
+        arg: Handle<Expression>,
+        arg1: Option<Handle<Expression>>,
+    ) -> Handle<Expression> {
+        self.evaluate_expr(Expression::Math {
+            fun,
+            arg,
+            arg1,
+            arg2: None,
+            arg3: None,
+        })
+    }
+
+    fn emit_binary(
+        &mut self,
+        op: BinaryOperator,
+        left: Handle<Expression>,
+        right: Handle<Expression>,
+    ) -> Handle<Expression> {
+        self.evaluate_expr(Expression::Binary { op, left, right })
+    }
+
+    /// Converts a vec4f to a vec3f by dropping the 'w' component.
+    fn emit_xyz(&mut self, vector: Handle<Expression>) -> Handle<Expression> {
+        self.evaluate_expr(Expression::Swizzle {
+            size: VectorSize::Tri,
+            vector,
+            pattern: [
+                SwizzleComponent::X,
+                SwizzleComponent::Y,
+                SwizzleComponent::Z,
+                SwizzleComponent::X,
+            ],
+        })
+    }
+
+    /// Converts a vec3f to a vec4f with a 'w' component of 0.0.
+    /// Opcodes that only produce three components leave 'w' undefined,
+    /// so shaders mask it out when writing the result.
+    fn emit_extend_xyz(&mut self, vector: Handle<Expression>) -> Handle<Expression> {
+        let mut components = vec![];
+        for i in 0..3 {
+            components.push(self.evaluate_expr(Expression::AccessIndex {
+                base: vector,
+                index: i,
+            }));
+        }
+        components.push(self.float_constant(0.0));
+        self.evaluate_expr(Expression::Compose {
+            ty: self.vec4f,
+            components,
+        })
+    }
+
+    /// Computes the product of a matrix stored (as rows) in the registers starting
+    /// at 'matrix' with the column vector 'vector'. Only the first `num_columns`
+    /// components of each row and of `vector` are used.
+    fn emit_matrix_multiply(
+        &mut self,
+        vector: &SourceField,
+        matrix: &SourceField,
+        num_rows: u16,
+        num_columns: u16,
+    ) -> Result<Handle<Expression>> {
+        let vector = self.emit_source_field_load(vector)?;
+        let vector = if num_columns == 3 {
+            self.emit_xyz(vector)
+        } else {
+            vector
+        };
+
+        let mut components = vec![];
+        for row in 0..num_rows {
+            let row = self.emit_source_field_row_load(matrix, row)?;
+            let row = if num_columns == 3 {
+                self.emit_xyz(row)
+            } else {
+                row
+            };
+            components.push(self.emit_math(MathFunction::Dot, row, Some(vector)));
+        }
+        while components.len() < 4 {
+            components.push(self.float_constant(0.0));
+        }
+
+        Ok(self.evaluate_expr(Expression::Compose {
+            ty: self.vec4f,
+            components,
+        }))
+    }
+
     fn process_opcode(
         &mut self,
         opcode: &Opcode,
@@ -610,50 +913,120 @@ impl<'a> NagaBuilder<'a> {
         source1: &SourceField,
         source2: &Source2,
     ) -> Result<()> {
+        let source2_field = || match source2 {
+            Source2::SourceField(source2) => source2,
+            _ => unreachable!(),
+        };
+
         match opcode {
             // Copy the source register to the destination register
             Opcode::Mov => {
-                // On the ActionScript side, the user might have specified something *other* than
-                // vec4f. In that case, we need to extend the source to a vec4f if we're writing to
-                // a vec4f register.
-                // FIXME - do we need to do this extension in other cases?
-                let do_extend = matches!(
-                    dest.register_type,
-                    RegisterType::Output | RegisterType::Varying
-                );
-                let source = self.emit_source_field_load(source1, do_extend)?;
+                let source = self.emit_source_field_load(source1)?;
                 self.emit_dest_store(dest, source)?;
             }
-            // Perform 'M * v', where M is a 4x4 matrix, and 'v' is a column vector.
-            Opcode::M44 => {
-                let source2 = match source2 {
-                    Source2::SourceField(source2) => source2,
+            // Component-wise operations on two vectors
+            Opcode::Add
+            | Opcode::Sub
+            | Opcode::Mul
+            | Opcode::Div
+            | Opcode::Min
+            | Opcode::Max
+            | Opcode::Pow => {
+                let left = self.emit_source_field_load(source1)?;
+                let right = self.emit_source_field_load(source2_field())?;
+                let result = match opcode {
+                    Opcode::Add => self.emit_binary(BinaryOperator::Add, left, right),
+                    Opcode::Sub => self.emit_binary(BinaryOperator::Subtract, left, right),
+                    Opcode::Mul => self.emit_binary(BinaryOperator::Multiply, left, right),
+                    Opcode::Div => self.emit_binary(BinaryOperator::Divide, left, right),
+                    Opcode::Min => self.emit_math(MathFunction::Min, left, Some(right)),
+                    Opcode::Max => self.emit_math(MathFunction::Max, left, Some(right)),
+                    Opcode::Pow => self.emit_math(MathFunction::Pow, left, Some(right)),
+                    _ => unreachable!(),
+                };
+                self.emit_dest_store(dest, result)?;
+            }
+            // Component-wise operations on a single vector
+            Opcode::Frc
+            | Opcode::Sqt
+            | Opcode::Rsq
+            | Opcode::Log
+            | Opcode::Exp
+            | Opcode::Sin
+            | Opcode::Cos
+            | Opcode::Abs
+            | Opcode::Sat => {
+                let source = self.emit_source_field_load(source1)?;
+                let fun = match opcode {
+                    Opcode::Frc => MathFunction::Fract,
+                    Opcode::Sqt => MathFunction::Sqrt,
+                    Opcode::Rsq => MathFunction::InverseSqrt,
+                    // AGAL uses base 2 for both of these
+                    Opcode::Log => MathFunction::Log2,
+                    Opcode::Exp => MathFunction::Exp2,
+                    Opcode::Sin => MathFunction::Sin,
+                    Opcode::Cos => MathFunction::Cos,
+                    Opcode::Abs => MathFunction::Abs,
+                    Opcode::Sat => MathFunction::Saturate,
                     _ => unreachable!(),
                 };
+                let result = self.emit_math(fun, source, None);
+                self.emit_dest_store(dest, result)?;
+            }
+            Opcode::Rcp => {
+                let source = self.emit_source_field_load(source1)?;
+                let one = self.float_constant(1.0);
+                let one = self.emit_splat(one);
+                let result = self.emit_binary(BinaryOperator::Divide, one, source);
+                self.emit_dest_store(dest, result)?;
+            }
+            Opcode::Neg => {
+                let source = self.emit_source_field_load(source1)?;
+                let result = self.evaluate_expr(Expression::Unary {
+                    op: UnaryOperator::Negate,
+                    expr: source,
+                });
+                self.emit_dest_store(dest, result)?;
+            }
+            // Normalizes the 'xyz' components of the source
+            Opcode::Nrm => {
+                let source = self.emit_source_field_load(source1)?;
+                let source = self.emit_xyz(source);
+                let normalized = self.emit_math(MathFunction::Normalize, source, None);
+                let result = self.emit_extend_xyz(normalized);
+                self.emit_dest_store(dest, result)?;
+            }
+            // Cross product of the 'xyz' components of the sources
+            Opcode::Crs => {
+                let left = self.emit_source_field_load(source1)?;
+                let left = self.emit_xyz(left);
+                let right = self.emit_source_field_load(source2_field())?;
+                let right = self.emit_xyz(right);
+                let cross = self.emit_math(MathFunction::Cross, left, Some(right));
+                let result = self.emit_extend_xyz(cross);
+                self.emit_dest_store(dest, result)?;
+            }
+            // Dot products, stored to every component of the destination
+            Opcode::Dp3 | Opcode::Dp4 => {
+                let mut left = self.emit_source_field_load(source1)?;
+                let mut right = self.emit_source_field_load(source2_field())?;
+                if let Opcode::Dp3 = opcode {
+                    left = self.emit_xyz(left);
+                    right = self.emit_xyz(right);
+                }
+                let dot = self.emit_math(MathFunction::Dot, left, Some(right));
+                let result = self.emit_splat(dot);
+                self.emit_dest_store(dest, result)?;
+            }
+            // Perform 'M * v', where M is a 4x4 matrix, and 'v' is a column vector.
+            Opcode::M44 => {
+                let source2 = source2_field();
 
                 // Read each row of the matrix
-                let source2_row0 = self.emit_source_field_load(source2, false)?;
-                let source2_row1 = self.emit_source_field_load(
-                    &SourceField {
-                        reg_num: source2.reg_num + 1,
-                        ..source2.clone()
-                    },
-                    false,
-                )?;
-                let source2_row2 = self.emit_source_field_load(
-                    &SourceField {
-                        reg_num: source2.reg_num + 2,
-                        ..source2.clone()
-                    },
-                    false,
-                )?;
-                let source2_row3 = self.emit_source_field_load(
-                    &SourceField {
-                        reg_num: source2.reg_num + 3,
-                        ..source2.clone()
-                    },
-                    false,
-                )?;
+                let mut rows = vec![];
+                for row in 0..4 {
+                    rows.push(self.emit_source_field_row_load(source2, row)?);
+                }
 
                 // FIXME - The naga spv backend hits an 'unreachable!'
                 // if we don't create a Statement::Emit for each of these,
@@ -661,40 +1034,295 @@ impl<'a> NagaBuilder<'a> {
                 // and report it upstream.
                 let matrix = self.evaluate_expr(Expression::Compose {
                     ty: self.matrix4x4f,
-                    components: vec![source2_row0, source2_row1, source2_row2, source2_row3],
+                    components: rows,
                 });
 
                 // Naga interprets each component of the matrix as a *column*.
                 // However, the matrix is stored in memory as a *row*, so we need
                 // to transpose it.
-                let matrix = self.evaluate_expr(Expression::Math {
-                    fun: MathFunction::Transpose,
-                    arg: matrix,
-                    arg1: None,
-                    arg2: None,
-                    arg3: None,
+                let matrix = self.emit_math(MathFunction::Transpose, matrix, None);
+
+                let vector = self.emit_source_field_load(source1)?;
+
+                let multiply = self.emit_binary(BinaryOperator::Multiply, matrix, vector);
+
+                self.emit_dest_store(dest, multiply)?;
+            }
+            // Like 'M44', but with a 3x3 matrix and a 3-component vector.
+            Opcode::M33 => {
+                let result = self.emit_matrix_multiply(source1, source2_field(), 3, 3)?;
+                self.emit_dest_store(dest, result)?;
+            }
+            // Like 'M44', but with a 3x4 matrix (three rows of four components).
+            Opcode::M34 => {
+                let result = self.emit_matrix_multiply(source1, source2_field(), 3, 4)?;
+                self.emit_dest_store(dest, result)?;
+            }
+            // Component-wise comparisons, producing 1.0 when true and 0.0 when false
+            Opcode::Sge | Opcode::Slt | Opcode::Seq | Opcode::Sne => {
+                let left = self.emit_source_field_load(source1)?;
+                let right = self.emit_source_field_load(source2_field())?;
+                let op = match opcode {
+                    Opcode::Sge => BinaryOperator::GreaterEqual,
+                    Opcode::Slt => BinaryOperator::Less,
+                    Opcode::Seq => BinaryOperator::Equal,
+                    Opcode::Sne => BinaryOperator::NotEqual,
+                    _ => unreachable!(),
+                };
+                let condition = self.emit_binary(op, left, right);
+                let one = self.float_constant(1.0);
+                let accept = self.emit_splat(one);
+                let zero = self.float_constant(0.0);
+                let reject = self.emit_splat(zero);
+                let result = self.evaluate_expr(Expression::Select {
+                    condition,
+                    accept,
+                    reject,
+                });
+                self.emit_dest_store(dest, result)?;
+            }
+            // Discard the fragment if the first component of the source is negative.
+            Opcode::Kil => {
+                if !matches!(self.shader_config.shader_type, ShaderType::Fragment) {
+                    return Err(Error::Unimplemented("'kil' in a vertex shader".to_string()));
+                }
+
+                let source = self.emit_source_field_load(source1)?;
+                let value = self.evaluate_expr(Expression::AccessIndex {
+                    base: source,
+                    index: 0,
                 });
+                let zero = self.float_constant(0.0);
+                let condition = self.emit_binary(BinaryOperator::Less, value, zero);
+
+                let killed = match self.killed {
+                    Some(killed) => killed,
+                    None => {
+                        let init = self.module.constants.append(
+                            Constant {
+                                name: None,
+                                specialization: None,
+                                inner: ConstantInner::Scalar {
+                                    width: 1,
+                                    value: ScalarValue::Bool(false),
+                                },
+                            },
+                            Span::UNDEFINED,
+                        );
+                        let ty = self.module.types.insert(
+                            Type {
+                                name: None,
+                                inner: TypeInner::Scalar {
+                                    kind: ScalarKind::Bool,
+                                    width: 1,
+                                },
+                            },
+                            Span::UNDEFINED,
+                        );
+                        let local = self.func.local_variables.append(
+                            LocalVariable {
+                                name: Some("killed".to_string()),
+                                ty,
+                                init: Some(init),
+                            },
+                            Span::UNDEFINED,
+                        );
+                        let killed = self
+                            .func
+                            .expressions
+                            .append(Expression::LocalVariable(local), Span::UNDEFINED);
+                        self.killed = Some(killed);
+                        killed
+                    }
+                };
 
-                let vector = self.emit_source_field_load(source1, true)?;
+                let was_killed = self.evaluate_expr(Expression::Load { pointer: killed });
+                let killed_value =
+                    self.emit_binary(BinaryOperator::LogicalOr, was_killed, condition);
+                self.emit_store(killed, killed_value);
+            }
+            Opcode::Ddx | Opcode::Ddy => {
+                if !matches!(self.shader_config.shader_type, ShaderType::Fragment) {
+                    return Err(Error::Unimplemented(format!(
+                        "'{opcode:?}' in a vertex shader"
+                    )));
+                }
 
-                let multiply = self.evaluate_expr(Expression::Binary {
-                    op: BinaryOperator::Multiply,
-                    left: matrix,
-                    right: vector,
+                let source = self.emit_source_field_load(source1)?;
+                let axis = match opcode {
+                    Opcode::Ddx => DerivativeAxis::X,
+                    Opcode::Ddy => DerivativeAxis::Y,
+                    _ => unreachable!(),
+                };
+                let result = self.evaluate_expr(Expression::Derivative { axis, expr: source });
+                self.emit_dest_store(dest, result)?;
+            }
+            // Start a conditional block, comparing the first component of each source.
+            Opcode::Ife | Opcode::Ine | Opcode::Ifg | Opcode::Ifl => {
+                let left = self.emit_source_field_load(source1)?;
+                let left = self.evaluate_expr(Expression::AccessIndex {
+                    base: left,
+                    index: 0,
+                });
+                let right = self.emit_source_field_load(source2_field())?;
+                let right = self.evaluate_expr(Expression::AccessIndex {
+                    base: right,
+                    index: 0,
                 });
+                let op = match opcode {
+                    Opcode::Ife => BinaryOperator::Equal,
+                    Opcode::Ine => BinaryOperator::NotEqual,
+                    Opcode::Ifg => BinaryOperator::Greater,
+                    Opcode::Ifl => BinaryOperator::Less,
+                    _ => unreachable!(),
+                };
+                let condition = self.emit_binary(op, left, right);
 
-                self.emit_dest_store(dest, multiply)?;
+                let parent = self.conditionals.last().map(|parent| parent.active);
+                let active = match parent {
+                    Some(parent) => self.emit_binary(BinaryOperator::LogicalAnd, parent, condition),
+                    None => condition,
+                };
+                self.conditionals.push(Conditional {
+                    condition,
+                    parent,
+                    active,
+                    in_else: false,
+                });
             }
-            _ => {
-                return Err(Error::Unimplemented(format!(
-                    "Unimplemented opcode: {opcode:?}",
-                )))
+            Opcode::Els => {
+                let (condition, parent) = match self.conditionals.last() {
+                    Some(conditional) if !conditional.in_else => {
+                        (conditional.condition, conditional.parent)
+                    }
+                    _ => return Err(Error::UnbalancedConditional),
+                };
+
+                let not_condition = self.evaluate_expr(Expression::Unary {
+                    op: UnaryOperator::Not,
+                    expr: condition,
+                });
+                let active = match parent {
+                    Some(parent) => {
+                        self.emit_binary(BinaryOperator::LogicalAnd, parent, not_condition)
+                    }
+                    None => not_condition,
+                };
+
+                let conditional = self.conditionals.last_mut().unwrap();
+                conditional.active = active;
+                conditional.in_else = true;
+            }
+            Opcode::Eif => {
+                self.conditionals
+                    .pop()
+                    .ok_or(Error::UnbalancedConditional)?;
+            }
+            // Sample the texture bound to the sampler register, using 'source1' as the coordinates.
+            Opcode::Tex => {
+                let sampler_field = match source2 {
+                    Source2::Sampler(sampler_field) => sampler_field,
+                    _ => unreachable!(),
+                };
+
+                if sampler_field.reg_num as usize >= MAX_TEXTURES {
+                    return Err(Error::InvalidSamplerRegister(sampler_field.reg_num));
+                }
+
+                if !matches!(self.shader_config.shader_type, ShaderType::Fragment) {
+                    return Err(Error::Unimplemented(
+                        "Texture sampling in a vertex shader".to_string(),
+                    ));
+                }
+
+                // State set with `setSamplerStateAt` takes priority over the
+                // flags in the instruction.
+                let (wrapping, filter, mipmap) =
+                    match self.shader_config.sampler_overrides[sampler_field.reg_num as usize] {
+                        Some(sampler_override) => (
+                            sampler_override.wrapping,
+                            sampler_override.filter,
+                            sampler_override.mipmap,
+                        ),
+                        None => (
+                            sampler_field.wrapping,
+                            sampler_field.filter,
+                            sampler_field.mipmap,
+                        ),
+                    };
+
+                let coordinate_size = match sampler_field.dimension {
+                    Dimension::TwoD => VectorSize::Bi,
+                    Dimension::Cube => VectorSize::Tri,
+                    Dimension::ThreeD => {
+                        return Err(Error::Unimplemented("3D textures".to_string()))
+                    }
+                };
+
+                let image = self.get_texture(sampler_field)?;
+                let sampler = self.get_sampler(wrapping, filter);
+
+                let source = self.emit_source_field_load(source1)?;
+                let coordinate = self.evaluate_expr(Expression::Swizzle {
+                    size: coordinate_size,
+                    vector: source,
+                    pattern: [
+                        SwizzleComponent::X,
+                        SwizzleComponent::Y,
+                        SwizzleComponent::Z,
+                        SwizzleComponent::X,
+                    ],
+                });
+
+                // The level-of-detail bias is stored in units of 1/8th,
+                // and only applies when the texture is mipmapped.
+                // FIXME - the mipmap filter always matches the texture filter,
+                // since we create a single sampler for each filter.
+                let level = match mipmap {
+                    Mipmap::Disable => SampleLevel::Zero,
+                    Mipmap::Nearest | Mipmap::Linear if sampler_field.texture_lod_bias != 0 => {
+                        SampleLevel::Bias(
+                            self.float_constant(sampler_field.texture_lod_bias as f32 / 8.0),
+                        )
+                    }
+                    Mipmap::Nearest | Mipmap::Linear => SampleLevel::Auto,
+                };
+
+                let sample = self.evaluate_expr(Expression::ImageSample {
+                    image,
+                    sampler,
+                    gather: None,
+                    coordinate,
+                    array_index: None,
+                    offset: None,
+                    level,
+                    depth_ref: None,
+                });
+                self.emit_dest_store(dest, sample)?;
             }
         }
         Ok(())
     }
 
     fn finish(mut self) -> Result<Module> {
+        if !self.conditionals.is_empty() {
+            return Err(Error::UnbalancedConditional);
+        }
+
+        if let Some(killed) = self.killed {
+            let condition = self.evaluate_expr(Expression::Load { pointer: killed });
+            let mut accept = Block::new();
+            accept.push(Statement::Kill, Span::UNDEFINED);
+            self.func.body.push(
+                Statement::If {
+                    condition,
+                    accept,
+                    reject: Block::new(),
+                },
+                Span::UNDEFINED,
+            );
+        }
+
         // Load the 'main' output (a position or color) from our temporary location.
         let dest_load = self.evaluate_expr(Expression::Load { pointer: self.dest });
         let mut components = vec![dest_load];
@@ -5,9 +5,44 @@ mod types;
 
 use builder::NagaBuilder;
 
+pub use types::{Filter, Mipmap, Wrapping};
+
 const ENTRY_POINT: &str = "main";
 
 pub const MAX_VERTEX_ATTRIBUTES: usize = 8;
+pub const MAX_TEXTURES: usize = 8;
+
+/// The binding id of the first sampler. There is one sampler for each
+/// combination of `Wrapping` and `Filter` - see `sampler_binding`.
+pub const SAMPLER_START_BIND_INDEX: u32 = 2;
+pub const NUM_SAMPLERS: u32 = 8;
+
+/// The binding id of the texture used by sampler register `fs0`.
+/// Sampler register `fsN` uses binding `TEXTURE_START_BIND_INDEX + N`.
+pub const TEXTURE_START_BIND_INDEX: u32 = SAMPLER_START_BIND_INDEX + NUM_SAMPLERS;
+
+/// Returns the binding id of the sampler with the given wrapping and filter.
+/// Anisotropic filtering is approximated with linear filtering.
+pub fn sampler_binding(wrapping: Wrapping, filter: Filter) -> u32 {
+    let filter = match filter {
+        Filter::Nearest => 0,
+        Filter::Linear
+        | Filter::Anisotropic2x
+        | Filter::Anisotropic4x
+        | Filter::Anisotropic8x
+        | Filter::Anisotropic16x => 1,
+    };
+    SAMPLER_START_BIND_INDEX + wrapping as u32 * 2 + filter
+}
+
+/// Sampler state set through `Context3D.setSamplerStateAt`.
+/// When present, this is used instead of the flags in the `tex` instruction.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SamplerOverride {
+    pub wrapping: Wrapping,
+    pub filter: Filter,
+    pub mipmap: Mipmap,
+}
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum VertexAttributeFormat {
@@ -26,6 +61,9 @@ pub enum Error {
     Unimplemented(String),
     ReadError(std::io::Error),
     InvalidOpcode(u32),
+    InvalidSamplerRegister(u16),
+    MismatchedTextureDimension(u16),
+    UnbalancedConditional,
 }
 
 impl From<std::io::Error> for Error {
@@ -84,10 +122,22 @@ pub enum ShaderType {
  * * Program constants - An AGAL fragment shader has access to 28 program constants.
  *   These are mapped to a single Naga uniform buffer, with a binding id of 1.
  *
+ * * Textures - Each *used* sampler register `fsN` is mapped to a texture
+ *   with a binding id of `TEXTURE_START_BIND_INDEX + N`. The texture is sampled
+ *   with one of the `NUM_SAMPLERS` samplers starting at `SAMPLER_START_BIND_INDEX`,
+ *   chosen by `sampler_binding` from the wrapping and filter of the `tex` instruction
+ *   (or from the corresponding entry in `sampler_overrides`, if present).
+ *
+ * * Conditionals and `kil` - Naga requires texture sampling and derivatives to be
+ *   in uniform control flow, so we don't emit any branches for these. Register writes
+ *   inside of an `ife`..`eif` block are applied with a `select`, and a fragment
+ *   discarded with `kil` is only discarded at the end of the shader.
+ *
  */
 pub fn agal_to_naga(
     agal: &[u8],
     vertex_attributes: &[Option<VertexAttributeFormat>; MAX_VERTEX_ATTRIBUTES],
+    sampler_overrides: &[Option<SamplerOverride>; MAX_TEXTURES],
 ) -> Result<Module, Error> {
-    NagaBuilder::process_agal(agal, vertex_attributes)
+    NagaBuilder::process_agal(agal, vertex_attributes, sampler_overrides)
 }
@@ -126,29 +126,36 @@ impl SourceField {
     }
 }
 
-#[derive(FromPrimitive)]
+#[derive(FromPrimitive, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Filter {
     Nearest = 0,
     Linear = 1,
+    Anisotropic2x = 2,
+    Anisotropic4x = 3,
+    Anisotropic8x = 4,
+    Anisotropic16x = 5,
 }
 
-#[derive(FromPrimitive)]
+#[derive(FromPrimitive, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Mipmap {
     Disable = 0,
     Nearest = 1,
     Linear = 2,
 }
 
-#[derive(FromPrimitive)]
+#[derive(FromPrimitive, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Wrapping {
     Clamp = 0,
     Repeat = 1,
+    ClampURepeatV = 2,
+    RepeatUClampV = 3,
 }
 
-#[derive(FromPrimitive)]
+#[derive(FromPrimitive, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Dimension {
     TwoD = 0,
     Cube = 1,
+    ThreeD = 2,
 }
 
 #[allow(dead_code)]
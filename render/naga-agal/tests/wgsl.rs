@@ -22,7 +22,7 @@ pub fn to_wgsl(module: &Module) -> String {
 // Making this a macro gives us a better span in 'inta'
 macro_rules! test_shader {
     ($shader:expr, $attrs:expr, $shader_type:expr $(,)?) => {
-        let module = agal_to_naga(&$shader, $attrs).unwrap();
+        let module = agal_to_naga(&$shader, $attrs, &[None; 8]).unwrap();
         let output = to_wgsl(&module);
         insta::assert_display_snapshot!(output);
     };
@@ -80,4 +80,140 @@ fn test_shaders() {
         &[None, None, None, None, None, None, None, None],
         ShaderType::Fragment,
     );
+    test_shader!(
+        // tex oc, v0, fs0 <2d, linear, repeat>
+        [
+            160, 1, 0, 0, 0, 161, 1, 40, 0, 0, 0, 0, 0, 15, 3, 0, 0, 0, 228, 4, 0, 0, 0, 0, 0, 0,
+            0, 5, 0, 16, 16
+        ],
+        &[None, None, None, None, None, None, None, None],
+        ShaderType::Fragment,
+    );
+    test_shader!(
+        // mov vt0, va0
+        // add vt0.xy, vt0, vc[va1.x+4]
+        // mul vt1, vt0.yxzw, vc0
+        // m33 vt1.xyz, vt0, vc1
+        // m34 vt2.xyz, vt0, vc4
+        // mov vt2.w, vc0.x
+        // mov op, vt1
+        // mov v0, vt2
+        [
+            160, 1, 0, 0, 0, 161, 0, 0, 0, 0, 0, 0, 0, 15, 2, 0, 0, 0, 228, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 3, 2, 0, 0, 0, 228, 2, 0, 0, 0, 1, 0, 4, 228, 1, 0, 0,
+            128, 3, 0, 0, 0, 1, 0, 15, 2, 0, 0, 0, 225, 2, 0, 0, 0, 0, 0, 0, 228, 1, 0, 0, 0, 23,
+            0, 0, 0, 1, 0, 7, 2, 0, 0, 0, 228, 2, 0, 0, 0, 1, 0, 0, 228, 1, 0, 0, 0, 25, 0, 0, 0,
+            2, 0, 7, 2, 0, 0, 0, 228, 2, 0, 0, 0, 4, 0, 0, 228, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 8, 2,
+            0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 15, 3, 1, 0, 0, 228,
+            2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 15, 4, 2, 0, 0, 228, 2, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0
+        ],
+        &[
+            Some(VertexAttributeFormat::Float2),
+            Some(VertexAttributeFormat::Float1),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ],
+        ShaderType::Vertex,
+    );
+    test_shader!(
+        // sub vt0, va0, vc0
+        // div vt0, vt0, vc1
+        // rcp vt1, vt0
+        // min vt1, vt1, vc2
+        // max vt1, vt1, vc3
+        // frc vt2, vt1
+        // sqt vt2, vt2
+        // rsq vt3, vt2
+        // pow vt3, vt3, vc4
+        // log vt3, vt3
+        // exp vt3, vt3
+        // sin vt2, vt3
+        // cos vt2, vt2
+        // abs vt2, vt2
+        // neg vt2, vt2
+        // sat vt2, vt2
+        // nrm vt1.xyz, vt2
+        // crs vt1.xyz, vt1, vt2
+        // dp3 vt3, vt1, vt2
+        // dp4 vt3.w, vt1, vt2
+        // sge vt0, vt3, vt1
+        // slt vt1, vt3, vt2
+        // seq vt2, vt0, vt1
+        // sne vt3, vt0, vt2
+        // mov op, vt3
+        [
+            160, 1, 0, 0, 0, 161, 0, 2, 0, 0, 0, 0, 0, 15, 2, 0, 0, 0, 228, 0, 0, 0, 0, 0, 0, 0,
+            228, 1, 0, 0, 0, 4, 0, 0, 0, 0, 0, 15, 2, 0, 0, 0, 228, 2, 0, 0, 0, 1, 0, 0, 228, 1, 0,
+            0, 0, 5, 0, 0, 0, 1, 0, 15, 2, 0, 0, 0, 228, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 6, 0,
+            0, 0, 1, 0, 15, 2, 1, 0, 0, 228, 2, 0, 0, 0, 2, 0, 0, 228, 1, 0, 0, 0, 7, 0, 0, 0, 1,
+            0, 15, 2, 1, 0, 0, 228, 2, 0, 0, 0, 3, 0, 0, 228, 1, 0, 0, 0, 8, 0, 0, 0, 2, 0, 15, 2,
+            1, 0, 0, 228, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 9, 0, 0, 0, 2, 0, 15, 2, 2, 0, 0,
+            228, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 10, 0, 0, 0, 3, 0, 15, 2, 2, 0, 0, 228, 2, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 11, 0, 0, 0, 3, 0, 15, 2, 3, 0, 0, 228, 2, 0, 0, 0, 4, 0,
+            0, 228, 1, 0, 0, 0, 12, 0, 0, 0, 3, 0, 15, 2, 3, 0, 0, 228, 2, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 13, 0, 0, 0, 3, 0, 15, 2, 3, 0, 0, 228, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            15, 0, 0, 0, 2, 0, 15, 2, 3, 0, 0, 228, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16, 0, 0,
+            0, 2, 0, 15, 2, 2, 0, 0, 228, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 20, 0, 0, 0, 2, 0,
+            15, 2, 2, 0, 0, 228, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 21, 0, 0, 0, 2, 0, 15, 2, 2,
+            0, 0, 228, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 22, 0, 0, 0, 2, 0, 15, 2, 2, 0, 0, 228,
+            2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 14, 0, 0, 0, 1, 0, 7, 2, 2, 0, 0, 228, 2, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 17, 0, 0, 0, 1, 0, 7, 2, 1, 0, 0, 228, 2, 0, 0, 0, 2, 0, 0,
+            228, 2, 0, 0, 0, 18, 0, 0, 0, 3, 0, 15, 2, 1, 0, 0, 228, 2, 0, 0, 0, 2, 0, 0, 228, 2,
+            0, 0, 0, 19, 0, 0, 0, 3, 0, 8, 2, 1, 0, 0, 228, 2, 0, 0, 0, 2, 0, 0, 228, 2, 0, 0, 0,
+            41, 0, 0, 0, 0, 0, 15, 2, 3, 0, 0, 228, 2, 0, 0, 0, 1, 0, 0, 228, 2, 0, 0, 0, 42, 0, 0,
+            0, 1, 0, 15, 2, 3, 0, 0, 228, 2, 0, 0, 0, 2, 0, 0, 228, 2, 0, 0, 0, 43, 0, 0, 0, 2, 0,
+            15, 2, 0, 0, 0, 228, 2, 0, 0, 0, 1, 0, 0, 228, 2, 0, 0, 0, 45, 0, 0, 0, 3, 0, 15, 2, 0,
+            0, 0, 228, 2, 0, 0, 0, 2, 0, 0, 228, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 15, 3, 3, 0, 0, 228,
+            2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0
+        ],
+        &[
+            Some(VertexAttributeFormat::Bytes4),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ],
+        ShaderType::Vertex,
+    );
+    test_shader!(
+        // kil v0.w
+        // ddx ft0, v0
+        // ddy ft1, v0
+        // ife ft0.x, fc0
+        // mov ft2, ft0
+        // els
+        // ifg ft1.y, fc0.x
+        // mov ft2, ft1
+        // eif
+        // eif
+        // tex ft3, v1, fs1 <cube, nearest, clamp, mipnearest, bias(-1.5)>
+        // tex ft4, v1, fs2 <2d, anisotropic8x, repeat, miplinear>
+        // add ft3, ft3, ft4
+        // add oc, ft2, ft3
+        [
+            160, 1, 0, 0, 0, 161, 1, 39, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 255, 4, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 26, 0, 0, 0, 0, 0, 15, 2, 0, 0, 0, 228, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            27, 0, 0, 0, 1, 0, 15, 2, 0, 0, 0, 228, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 28, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 228, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 15,
+            2, 0, 0, 0, 228, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 30, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 85, 2, 0, 0,
+            0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 15, 2, 1, 0, 0, 228, 2, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 33, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            33, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 40, 0, 0, 0,
+            3, 0, 15, 2, 1, 0, 0, 228, 4, 0, 0, 0, 1, 0, 244, 0, 5, 16, 0, 1, 40, 0, 0, 0, 4, 0,
+            15, 2, 1, 0, 0, 228, 4, 0, 0, 0, 2, 0, 0, 0, 5, 0, 16, 66, 1, 0, 0, 0, 3, 0, 15, 2, 3,
+            0, 0, 228, 2, 0, 0, 0, 4, 0, 0, 228, 2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 15, 3, 2, 0, 0, 228,
+            2, 0, 0, 0, 3, 0, 0, 228, 2, 0, 0, 0
+        ],
+        &[None, None, None, None, None, None, None, None],
+        ShaderType::Fragment,
+    );
 }
@@ -0,0 +1,190 @@
+use crate::bitmap::{as_bitmap, SoftwareBitmap};
+use crate::commands::CommandRenderer;
+use crate::mesh::{Draw, Mesh};
+use crate::surface::Surface;
+use gc_arena::MutationContext;
+use ruffle_render::backend::{Context3D, Context3DCommand};
+use ruffle_render::backend::{RenderBackend, ShapeHandle, ViewportDimensions};
+use ruffle_render::bitmap::{Bitmap, BitmapFormat, BitmapHandle, BitmapSource};
+use ruffle_render::commands::CommandList;
+use ruffle_render::error::Error;
+use ruffle_render::shape_utils::DistilledShape;
+use ruffle_render::tessellator::ShapeTessellator;
+use ruffle_render::utils::unmultiply_alpha_rgba;
+use std::sync::Arc;
+use swf::Color;
+
+/// The largest width or height, in pixels, of a render target or bitmap.
+pub(crate) const MAX_DIMENSION: u32 = 8192;
+
+pub struct SoftwareRenderBackend {
+    surface: Surface,
+    meshes: Vec<Mesh>,
+    shape_tessellator: ShapeTessellator,
+    // This is currently unused - we just store it to report in
+    // `get_viewport_dimensions`
+    viewport_scale_factor: f64,
+}
+
+impl SoftwareRenderBackend {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            surface: Surface::new(
+                width.clamp(1, MAX_DIMENSION),
+                height.clamp(1, MAX_DIMENSION),
+            ),
+            meshes: Vec::new(),
+            shape_tessellator: ShapeTessellator::new(),
+            viewport_scale_factor: 1.0,
+        }
+    }
+
+    /// Returns the contents of the last submitted frame.
+    pub fn capture_frame(&self, premultiplied_alpha: bool) -> Option<image::RgbaImage> {
+        let mut buffer = self.surface.resolve();
+        // The surface uses premultiplied alpha, so
+        // convert to straight alpha if requested by the user.
+        if !premultiplied_alpha {
+            unmultiply_alpha_rgba(&mut buffer);
+        }
+        image::RgbaImage::from_raw(self.surface.width(), self.surface.height(), buffer)
+    }
+
+    fn register_shape_internal(
+        &mut self,
+        shape: DistilledShape,
+        bitmap_source: &dyn BitmapSource,
+    ) -> Mesh {
+        let lyon_mesh = self
+            .shape_tessellator
+            .tessellate_shape(shape, bitmap_source);
+
+        let draws = lyon_mesh
+            .into_iter()
+            .filter_map(|draw| Draw::new(draw, bitmap_source, self))
+            .collect();
+
+        Mesh { draws }
+    }
+}
+
+impl RenderBackend for SoftwareRenderBackend {
+    fn viewport_dimensions(&self) -> ViewportDimensions {
+        ViewportDimensions {
+            width: self.surface.width(),
+            height: self.surface.height(),
+            scale_factor: self.viewport_scale_factor,
+        }
+    }
+
+    fn set_viewport_dimensions(&mut self, dimensions: ViewportDimensions) {
+        // Avoid 0-sized (or absurdly large) framebuffers.
+        let width = dimensions.width.clamp(1, MAX_DIMENSION);
+        let height = dimensions.height.clamp(1, MAX_DIMENSION);
+        self.surface = Surface::new(width, height);
+        self.viewport_scale_factor = dimensions.scale_factor;
+    }
+
+    fn register_shape(
+        &mut self,
+        shape: DistilledShape,
+        bitmap_source: &dyn BitmapSource,
+    ) -> ShapeHandle {
+        let handle = ShapeHandle(self.meshes.len());
+        let mesh = self.register_shape_internal(shape, bitmap_source);
+        self.meshes.push(mesh);
+        handle
+    }
+
+    fn replace_shape(
+        &mut self,
+        shape: DistilledShape,
+        bitmap_source: &dyn BitmapSource,
+        handle: ShapeHandle,
+    ) {
+        let mesh = self.register_shape_internal(shape, bitmap_source);
+        self.meshes[handle.0] = mesh;
+    }
+
+    fn register_glyph_shape(&mut self, glyph: &swf::Glyph) -> ShapeHandle {
+        let shape = ruffle_render::shape_utils::swf_glyph_to_shape(glyph);
+        let handle = ShapeHandle(self.meshes.len());
+        let mesh = self.register_shape_internal(
+            (&shape).into(),
+            &ruffle_render::backend::null::NullBitmapSource,
+        );
+        self.meshes.push(mesh);
+        handle
+    }
+
+    fn render_offscreen(
+        &mut self,
+        handle: BitmapHandle,
+        width: u32,
+        height: u32,
+        commands: CommandList,
+    ) -> Result<Bitmap, Error> {
+        if width > MAX_DIMENSION || height > MAX_DIMENSION {
+            return Err(Error::TooLarge);
+        }
+
+        let bitmap = as_bitmap(&handle);
+        // Draw on top of the existing contents of the bitmap.
+        let mut surface = {
+            let pixels = bitmap.pixels();
+            Surface::from_pixels(width, height, pixels.width, pixels.height, &pixels.data)
+        };
+        commands.execute(&mut CommandRenderer::new(&mut surface, &self.meshes));
+
+        let data = surface.resolve();
+        bitmap.replace_pixels(width, height, data.clone());
+        Ok(Bitmap::new(width, height, BitmapFormat::Rgba, data))
+    }
+
+    fn submit_frame(&mut self, clear: Color, commands: CommandList) {
+        self.surface.clear([
+            f32::from(clear.r) / 255.0,
+            f32::from(clear.g) / 255.0,
+            f32::from(clear.b) / 255.0,
+            f32::from(clear.a) / 255.0,
+        ]);
+        commands.execute(&mut CommandRenderer::new(&mut self.surface, &self.meshes));
+    }
+
+    fn register_bitmap(&mut self, bitmap: Bitmap) -> Result<BitmapHandle, Error> {
+        if bitmap.width() > MAX_DIMENSION || bitmap.height() > MAX_DIMENSION {
+            return Err(Error::TooLarge);
+        }
+
+        let bitmap = bitmap.to_rgba();
+        Ok(BitmapHandle(Arc::new(SoftwareBitmap::new(
+            bitmap.width(),
+            bitmap.height(),
+            bitmap.data().to_vec(),
+        ))))
+    }
+
+    fn update_texture(
+        &mut self,
+        handle: &BitmapHandle,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<(), Error> {
+        as_bitmap(handle).replace_pixels(width, height, rgba);
+        Ok(())
+    }
+
+    fn create_context3d(&mut self) -> Result<Box<dyn Context3D>, Error> {
+        Err(Error::Unimplemented)
+    }
+
+    fn context3d_present<'gc>(
+        &mut self,
+        _context: &mut dyn Context3D,
+        _commands: Vec<Context3DCommand<'gc>>,
+        _mc: MutationContext<'gc, '_>,
+    ) -> Result<(), Error> {
+        Err(Error::Unimplemented)
+    }
+}
@@ -0,0 +1,119 @@
+use crate::Rgba;
+use ruffle_render::bitmap::{BitmapHandle, BitmapHandleImpl};
+use std::cell::{Ref, RefCell};
+
+/// A bitmap living in main memory.
+///
+/// Pixels are stored as 8-bit RGBA with premultiplied alpha, the same layout
+/// used by `ruffle_render::bitmap::BitmapFormat::Rgba`.
+#[derive(Debug)]
+pub struct SoftwareBitmap {
+    pixels: RefCell<Pixels>,
+}
+
+#[derive(Debug)]
+pub struct Pixels {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl BitmapHandleImpl for SoftwareBitmap {}
+
+pub fn as_bitmap(handle: &BitmapHandle) -> &SoftwareBitmap {
+    <dyn BitmapHandleImpl>::downcast_ref(&*handle.0).unwrap()
+}
+
+impl SoftwareBitmap {
+    pub fn new(width: u32, height: u32, data: Vec<u8>) -> Self {
+        Self {
+            pixels: RefCell::new(Pixels {
+                width,
+                height,
+                data,
+            }),
+        }
+    }
+
+    pub fn pixels(&self) -> Ref<'_, Pixels> {
+        self.pixels.borrow()
+    }
+
+    pub fn replace_pixels(&self, width: u32, height: u32, data: Vec<u8>) {
+        *self.pixels.borrow_mut() = Pixels {
+            width,
+            height,
+            data,
+        };
+    }
+}
+
+impl Pixels {
+    #[inline]
+    fn texel(&self, x: i32, y: i32) -> Rgba {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        let p = &self.data[i..i + 4];
+        [
+            f32::from(p[0]) / 255.0,
+            f32::from(p[1]) / 255.0,
+            f32::from(p[2]) / 255.0,
+            f32::from(p[3]) / 255.0,
+        ]
+    }
+
+    /// Maps an (unnormalized) texel coordinate onto the bitmap, according to the wrap mode.
+    #[inline]
+    fn wrap(coord: i32, size: u32, repeating: bool) -> i32 {
+        let size = size as i32;
+        if repeating {
+            coord.rem_euclid(size)
+        } else {
+            coord.clamp(0, size - 1)
+        }
+    }
+
+    /// Samples this bitmap at the given UV coordinate, where `[0.0, 1.0]` spans
+    /// the entire bitmap. The returned color has premultiplied alpha.
+    pub fn sample(&self, u: f32, v: f32, smoothed: bool, repeating: bool) -> Rgba {
+        if self.width == 0 || self.height == 0 || !u.is_finite() || !v.is_finite() {
+            return [0.0; 4];
+        }
+
+        let x = u * self.width as f32;
+        let y = v * self.height as f32;
+        if !smoothed {
+            let x = Self::wrap(x.floor() as i32, self.width, repeating);
+            let y = Self::wrap(y.floor() as i32, self.height, repeating);
+            return self.texel(x, y);
+        }
+
+        // Bilinear filtering, with texel centers at half-integer coordinates.
+        let x = x - 0.5;
+        let y = y - 0.5;
+        let x0 = x.floor();
+        let y0 = y.floor();
+        let fx = x - x0;
+        let fy = y - y0;
+        let (x0, y0) = (x0 as i32, y0 as i32);
+        let (x1, y1) = (
+            Self::wrap(x0 + 1, self.width, repeating),
+            Self::wrap(y0 + 1, self.height, repeating),
+        );
+        let (x0, y0) = (
+            Self::wrap(x0, self.width, repeating),
+            Self::wrap(y0, self.height, repeating),
+        );
+
+        let c00 = self.texel(x0, y0);
+        let c10 = self.texel(x1, y0);
+        let c01 = self.texel(x0, y1);
+        let c11 = self.texel(x1, y1);
+        let mut out = [0.0; 4];
+        for i in 0..4 {
+            let top = c00[i] + (c10[i] - c00[i]) * fx;
+            let bottom = c01[i] + (c11[i] - c01[i]) * fx;
+            out[i] = top + (bottom - top) * fy;
+        }
+        out
+    }
+}
@@ -0,0 +1,160 @@
+use crate::Rgba;
+use swf::BlendMode;
+
+/// Composites `src` over `dst` using normal alpha blending.
+#[inline]
+pub fn over(src: Rgba, dst: Rgba) -> Rgba {
+    let inv_a = 1.0 - src[3];
+    [
+        src[0] + dst[0] * inv_a,
+        src[1] + dst[1] * inv_a,
+        src[2] + dst[2] * inv_a,
+        src[3] + dst[3] * inv_a,
+    ]
+}
+
+/// Composites `src` onto `dst` with the given Flash blend mode.
+///
+/// Both colors use premultiplied alpha. The separable modes follow the usual
+/// premultiplied formulation `src * (1 - da) + dst * (1 - sa) + B(src, dst)`.
+pub fn blend(mode: BlendMode, src: Rgba, dst: Rgba) -> Rgba {
+    let (sa, da) = (src[3], dst[3]);
+    let alpha_over = sa + da - sa * da;
+    let separable = |f: &dyn Fn(f32, f32) -> f32| -> Rgba {
+        let channel = |i: usize| src[i] * (1.0 - da) + dst[i] * (1.0 - sa) + f(src[i], dst[i]);
+        [channel(0), channel(1), channel(2), alpha_over]
+    };
+
+    match mode {
+        BlendMode::Normal | BlendMode::Layer => over(src, dst),
+        BlendMode::Add => [
+            src[0] + dst[0],
+            src[1] + dst[1],
+            src[2] + dst[2],
+            alpha_over,
+        ],
+        BlendMode::Subtract => [
+            dst[0] - src[0],
+            dst[1] - src[1],
+            dst[2] - src[2],
+            alpha_over,
+        ],
+        BlendMode::Multiply => separable(&|s, d| s * d),
+        BlendMode::Screen => [
+            src[0] + dst[0] - src[0] * dst[0],
+            src[1] + dst[1] - src[1] * dst[1],
+            src[2] + dst[2] - src[2] * dst[2],
+            alpha_over,
+        ],
+        BlendMode::Lighten => separable(&|s, d| (s * da).max(d * sa)),
+        BlendMode::Darken => separable(&|s, d| (s * da).min(d * sa)),
+        BlendMode::Difference => [
+            src[0] + dst[0] - 2.0 * (src[0] * da).min(dst[0] * sa),
+            src[1] + dst[1] - 2.0 * (src[1] * da).min(dst[1] * sa),
+            src[2] + dst[2] - 2.0 * (src[2] * da).min(dst[2] * sa),
+            alpha_over,
+        ],
+        BlendMode::Overlay => separable(&|s, d| hard_light(d, da, s, sa)),
+        BlendMode::HardLight => separable(&|s, d| hard_light(s, sa, d, da)),
+        // Inverts the destination wherever the source is drawn.
+        BlendMode::Invert => [
+            (da - dst[0]) * sa + dst[0] * (1.0 - sa),
+            (da - dst[1]) * sa + dst[1] * (1.0 - sa),
+            (da - dst[2]) * sa + dst[2] * (1.0 - sa),
+            da,
+        ],
+        // Applies the alpha of the source to the destination.
+        BlendMode::Alpha => [dst[0] * sa, dst[1] * sa, dst[2] * sa, dst[3] * sa],
+        // Erases the destination wherever the source is drawn.
+        BlendMode::Erase => {
+            let inv_a = 1.0 - sa;
+            [
+                dst[0] * inv_a,
+                dst[1] * inv_a,
+                dst[2] * inv_a,
+                dst[3] * inv_a,
+            ]
+        }
+    }
+}
+
+/// The premultiplied hard light term, where `a` is the "light" color.
+#[inline]
+fn hard_light(a: f32, aa: f32, b: f32, ba: f32) -> f32 {
+    if 2.0 * a <= aa {
+        2.0 * a * b
+    } else {
+        aa * ba - 2.0 * (ba - b) * (aa - a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    const RED: Rgba = [1.0, 0.0, 0.0, 1.0];
+    const HALF_BLUE: Rgba = [0.0, 0.0, 0.5, 0.5];
+    const TRANSPARENT: Rgba = [0.0; 4];
+
+    fn assert_rgba_eq(actual: Rgba, expected: Rgba) {
+        for i in 0..4 {
+            assert_relative_eq!(actual[i], expected[i], epsilon = 0.0001);
+        }
+    }
+
+    #[test]
+    fn over_transparent() {
+        assert_rgba_eq(over(TRANSPARENT, RED), RED);
+        assert_rgba_eq(over(RED, TRANSPARENT), RED);
+        assert_rgba_eq(over(HALF_BLUE, RED), [0.5, 0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn separable_modes_match_normal_over_transparent() {
+        // With nothing underneath, every separable blend mode degrades to a normal blend.
+        for mode in [
+            BlendMode::Multiply,
+            BlendMode::Screen,
+            BlendMode::Lighten,
+            BlendMode::Darken,
+            BlendMode::Difference,
+            BlendMode::Overlay,
+            BlendMode::HardLight,
+        ] {
+            assert_rgba_eq(blend(mode, HALF_BLUE, TRANSPARENT), HALF_BLUE);
+        }
+    }
+
+    #[test]
+    fn multiply_and_screen() {
+        let gray = [0.5, 0.5, 0.5, 1.0];
+        assert_rgba_eq(blend(BlendMode::Multiply, gray, RED), [0.5, 0.0, 0.0, 1.0]);
+        assert_rgba_eq(blend(BlendMode::Screen, gray, RED), [1.0, 0.5, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn difference_and_invert() {
+        let white = [1.0, 1.0, 1.0, 1.0];
+        assert_rgba_eq(
+            blend(BlendMode::Difference, white, RED),
+            [0.0, 1.0, 1.0, 1.0],
+        );
+        assert_rgba_eq(blend(BlendMode::Invert, white, RED), [0.0, 1.0, 1.0, 1.0]);
+        assert_rgba_eq(blend(BlendMode::Invert, TRANSPARENT, RED), RED);
+    }
+
+    #[test]
+    fn alpha_and_erase() {
+        assert_rgba_eq(
+            blend(BlendMode::Alpha, HALF_BLUE, RED),
+            [0.5, 0.0, 0.0, 0.5],
+        );
+        assert_rgba_eq(
+            blend(BlendMode::Erase, HALF_BLUE, RED),
+            [0.5, 0.0, 0.0, 0.5],
+        );
+        assert_rgba_eq(blend(BlendMode::Erase, RED, RED), TRANSPARENT);
+        assert_rgba_eq(blend(BlendMode::Alpha, TRANSPARENT, RED), TRANSPARENT);
+    }
+}
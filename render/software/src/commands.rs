@@ -0,0 +1,595 @@
+use crate::backend::MAX_DIMENSION;
+use crate::bitmap::{as_bitmap, Pixels};
+use crate::mesh::{sample_gradient, texture_uv, DrawType, Mesh};
+use crate::raster::{rasterize_triangle, SAMPLE_COUNT};
+use crate::surface::Surface;
+use crate::Rgba;
+use ruffle_render::backend::ShapeHandle;
+use ruffle_render::bitmap::BitmapHandle;
+use ruffle_render::bounding_box::BoundingBox;
+use ruffle_render::color_transform::ColorTransform;
+use ruffle_render::commands::{CommandHandler, CommandList};
+use ruffle_render::filters::cpu::FilterBuffer;
+use ruffle_render::filters::Filter;
+use ruffle_render::matrix::Matrix;
+use ruffle_render::tessellator::Vertex;
+use ruffle_render::transform::Transform;
+use swf::{BlendMode, Color, Twips};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum MaskState {
+    NoMask,
+    DrawMaskStencil,
+    DrawMaskedContent,
+    ClearMaskStencil,
+}
+
+/// The color and alpha adjustments of a `ColorTransform`, in normalized form.
+#[derive(Clone, Copy)]
+struct ColorAdjustments {
+    mult: [f32; 4],
+    add: [f32; 4],
+}
+
+impl From<&ColorTransform> for ColorAdjustments {
+    fn from(transform: &ColorTransform) -> Self {
+        Self {
+            mult: transform.mult_rgba_normalized(),
+            add: transform.add_rgba_normalized(),
+        }
+    }
+}
+
+impl ColorAdjustments {
+    /// Applies these adjustments to a color with straight alpha,
+    /// returning a color with premultiplied alpha.
+    #[inline]
+    fn apply(&self, color: [f32; 4]) -> Rgba {
+        let mut out = [0.0; 4];
+        for i in 0..4 {
+            out[i] = color[i] * self.mult[i] + self.add[i];
+        }
+        let alpha = out[3].clamp(0.0, 1.0);
+        [
+            out[0].clamp(0.0, 1.0) * alpha,
+            out[1].clamp(0.0, 1.0) * alpha,
+            out[2].clamp(0.0, 1.0) * alpha,
+            alpha,
+        ]
+    }
+}
+
+/// How the color of a triangle is determined.
+enum Paint<'p> {
+    /// Interpolate the colors of the vertices.
+    Vertex,
+    Gradient(&'p ruffle_render::tessellator::Gradient),
+    Bitmap {
+        pixels: &'p Pixels,
+        matrix: [[f32; 3]; 3],
+        smoothed: bool,
+        repeating: bool,
+    },
+}
+
+/// A 2D affine transform stored as `[a, b, c, d, tx, ty]`, in pixels.
+type Affine = [f32; 6];
+
+fn to_affine(matrix: &Matrix) -> Affine {
+    [
+        matrix.a,
+        matrix.b,
+        matrix.c,
+        matrix.d,
+        matrix.tx.to_pixels() as f32,
+        matrix.ty.to_pixels() as f32,
+    ]
+}
+
+#[inline]
+fn transform_point(m: &Affine, x: f32, y: f32) -> [f32; 2] {
+    [m[0] * x + m[2] * y + m[4], m[1] * x + m[3] * y + m[5]]
+}
+
+fn invert(m: &Affine) -> Option<Affine> {
+    let det = m[0] * m[3] - m[1] * m[2];
+    if det == 0.0 || !det.is_finite() {
+        return None;
+    }
+    let (a, b, c, d) = (m[3] / det, -m[1] / det, -m[2] / det, m[0] / det);
+    Some([a, b, c, d, -(a * m[4] + c * m[5]), -(b * m[4] + d * m[5])])
+}
+
+pub struct CommandRenderer<'a, 's> {
+    surface: &'s mut Surface,
+    meshes: &'a [Mesh],
+    mask_state: MaskState,
+    num_masks: u8,
+    /// For every pushed blend mode, whether a new layer was started on the surface.
+    blend_layers: Vec<bool>,
+    /// The position of the top-left corner of the surface, in stage pixels.
+    origin: (i32, i32),
+    /// Whether the surface is an offscreen layer, rather than the stage.
+    is_layer: bool,
+}
+
+impl<'a, 's> CommandRenderer<'a, 's> {
+    pub fn new(surface: &'s mut Surface, meshes: &'a [Mesh]) -> Self {
+        Self {
+            surface,
+            meshes,
+            mask_state: MaskState::NoMask,
+            num_masks: 0,
+            blend_layers: vec![],
+            origin: (0, 0),
+            is_layer: false,
+        }
+    }
+
+    /// Whether anything drawn now ends up in a layer, rather than directly on the stage.
+    fn is_in_layer(&self) -> bool {
+        self.is_layer || self.blend_layers.contains(&true)
+    }
+
+    /// Converts a matrix in stage space into one that targets the surface.
+    fn surface_affine(&self, matrix: &Matrix) -> Affine {
+        let mut affine = to_affine(matrix);
+        affine[4] -= self.origin.0 as f32;
+        affine[5] -= self.origin.1 as f32;
+        affine
+    }
+
+    /// Blends a buffer of premultiplied pixels over the surface, with its top-left corner at
+    /// the given position in stage pixels.
+    fn draw_buffer(&mut self, x: i32, y: i32, buffer: &FilterBuffer) {
+        let (width, height) = (self.surface.width() as i32, self.surface.height() as i32);
+        let (min_x, min_y) = (x - self.origin.0, y - self.origin.1);
+        let stencil = if self.mask_state == MaskState::DrawMaskedContent {
+            Some(self.num_masks)
+        } else {
+            None
+        };
+        for buffer_y in 0..buffer.height() {
+            let surface_y = min_y + buffer_y;
+            if surface_y < 0 || surface_y >= height {
+                continue;
+            }
+            for buffer_x in 0..buffer.width() {
+                let surface_x = min_x + buffer_x;
+                if surface_x < 0 || surface_x >= width {
+                    continue;
+                }
+                let color = buffer.get(buffer_x, buffer_y);
+                if color[3] <= 0.0 {
+                    continue;
+                }
+                let first_sample =
+                    (surface_y as usize * width as usize + surface_x as usize) * SAMPLE_COUNT;
+                for sample in first_sample..first_sample + SAMPLE_COUNT {
+                    if stencil.map_or(true, |stencil| self.surface.stencil(sample) == stencil) {
+                        self.surface.blend_sample(sample, color);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Rasterizes a list of indexed triangles, given in object space.
+    fn draw_triangles(
+        &mut self,
+        vertices: &[Vertex],
+        indices: &[u32],
+        matrix: &Affine,
+        color_adjustments: ColorAdjustments,
+        paint: Paint<'_>,
+    ) {
+        let inverse = match invert(matrix) {
+            Some(inverse) => inverse,
+            None => return,
+        };
+        let (width, height) = (self.surface.width(), self.surface.height());
+        let mask_state = self.mask_state;
+        let num_masks = self.num_masks;
+        let surface = &mut *self.surface;
+
+        for triangle in indices.chunks_exact(3) {
+            let corners = match (
+                vertices.get(triangle[0] as usize),
+                vertices.get(triangle[1] as usize),
+                vertices.get(triangle[2] as usize),
+            ) {
+                (Some(v0), Some(v1), Some(v2)) => [v0, v1, v2],
+                _ => continue,
+            };
+            let positions = corners.map(|v| transform_point(matrix, v.x, v.y));
+
+            // Barycentric setup for interpolating vertex colors.
+            let [p0, p1, p2] = positions;
+            let area = (p1[0] - p0[0]) * (p2[1] - p0[1]) - (p2[0] - p0[0]) * (p1[1] - p0[1]);
+            let colors = corners.map(|v| {
+                [
+                    f32::from(v.color.r) / 255.0,
+                    f32::from(v.color.g) / 255.0,
+                    f32::from(v.color.b) / 255.0,
+                    f32::from(v.color.a) / 255.0,
+                ]
+            });
+
+            rasterize_triangle(width, height, positions, |x, y, coverage| {
+                let first_sample = (y as usize * width as usize + x as usize) * SAMPLE_COUNT;
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+
+                let color = if mask_state == MaskState::DrawMaskStencil
+                    || mask_state == MaskState::ClearMaskStencil
+                {
+                    None
+                } else {
+                    let color = match &paint {
+                        Paint::Vertex => {
+                            let w1 = ((px - p0[0]) * (p2[1] - p0[1])
+                                - (p2[0] - p0[0]) * (py - p0[1]))
+                                / area;
+                            let w2 = ((p1[0] - p0[0]) * (py - p0[1])
+                                - (px - p0[0]) * (p1[1] - p0[1]))
+                                / area;
+                            let w0 = 1.0 - w1 - w2;
+                            let mut color = [0.0; 4];
+                            for (i, n) in color.iter_mut().enumerate() {
+                                *n = colors[0][i] * w0 + colors[1][i] * w1 + colors[2][i] * w2;
+                            }
+                            color_adjustments.apply(color)
+                        }
+                        Paint::Gradient(gradient) => {
+                            let [ox, oy] = transform_point(&inverse, px, py);
+                            let (u, v) = texture_uv(&gradient.matrix, ox, oy);
+                            color_adjustments.apply(sample_gradient(gradient, u, v))
+                        }
+                        Paint::Bitmap {
+                            pixels,
+                            matrix,
+                            smoothed,
+                            repeating,
+                        } => {
+                            let [ox, oy] = transform_point(&inverse, px, py);
+                            let (u, v) = texture_uv(matrix, ox, oy);
+                            let color = pixels.sample(u, v, *smoothed, *repeating);
+                            color_adjustments.apply(unmultiply(color))
+                        }
+                    };
+                    Some(color)
+                };
+
+                for i in 0..SAMPLE_COUNT {
+                    if coverage & (1 << i) == 0 {
+                        continue;
+                    }
+                    let sample = first_sample + i;
+                    let stencil = surface.stencil(sample);
+                    match mask_state {
+                        MaskState::NoMask => {}
+                        MaskState::DrawMaskStencil => {
+                            if stencil == num_masks - 1 {
+                                surface.set_stencil(sample, num_masks);
+                            }
+                            continue;
+                        }
+                        MaskState::DrawMaskedContent => {
+                            if stencil != num_masks {
+                                continue;
+                            }
+                        }
+                        MaskState::ClearMaskStencil => {
+                            if stencil == num_masks {
+                                surface.set_stencil(sample, num_masks - 1);
+                            }
+                            continue;
+                        }
+                    }
+                    if let Some(color) = color {
+                        surface.blend_sample(sample, color);
+                    }
+                }
+            });
+        }
+    }
+
+    /// Draws a `width` by `height` rectangle at the origin of object space.
+    fn draw_quad(
+        &mut self,
+        width: f32,
+        height: f32,
+        matrix: &Affine,
+        color_adjustments: ColorAdjustments,
+        paint: Paint<'_>,
+    ) {
+        let white = Color::from_rgba(0xFFFFFFFF);
+        let vertices =
+            [(0.0, 0.0), (width, 0.0), (width, height), (0.0, height)].map(|(x, y)| Vertex {
+                x,
+                y,
+                color: white.clone(),
+            });
+        self.draw_triangles(
+            &vertices,
+            &[0, 1, 2, 0, 2, 3],
+            matrix,
+            color_adjustments,
+            paint,
+        );
+    }
+}
+
+/// Converts an 8-bit RGBA pixel into a color with channels in `0.0..=1.0`.
+#[inline]
+fn from_unorm(pixel: &[u8]) -> Rgba {
+    [
+        f32::from(pixel[0]) / 255.0,
+        f32::from(pixel[1]) / 255.0,
+        f32::from(pixel[2]) / 255.0,
+        f32::from(pixel[3]) / 255.0,
+    ]
+}
+
+/// Converts a premultiplied color into one with straight alpha.
+#[inline]
+fn unmultiply(color: Rgba) -> [f32; 4] {
+    let alpha = color[3];
+    if alpha == 0.0 {
+        [0.0; 4]
+    } else {
+        [color[0] / alpha, color[1] / alpha, color[2] / alpha, alpha]
+    }
+}
+
+impl<'a, 's> CommandHandler<'a> for CommandRenderer<'a, 's> {
+    fn render_bitmap(&mut self, bitmap: &'a BitmapHandle, transform: &Transform, smoothing: bool) {
+        let bitmap = as_bitmap(bitmap);
+        let pixels = bitmap.pixels();
+        let (width, height) = (pixels.width as f32, pixels.height as f32);
+        if width == 0.0 || height == 0.0 {
+            return;
+        }
+        // Maps object space (in pixels) onto the unit square covered by the bitmap.
+        let uv_matrix = [
+            [1.0 / width, 0.0, 0.0],
+            [0.0, 1.0 / height, 0.0],
+            [0.0, 0.0, 1.0],
+        ];
+        self.draw_quad(
+            width,
+            height,
+            &self.surface_affine(&transform.matrix),
+            ColorAdjustments::from(&transform.color_transform),
+            Paint::Bitmap {
+                pixels: &pixels,
+                matrix: uv_matrix,
+                smoothed: smoothing,
+                repeating: false,
+            },
+        );
+    }
+
+    fn render_shape(&mut self, shape: ShapeHandle, transform: &Transform) {
+        let meshes = self.meshes;
+        let mesh = match meshes.get(shape.0) {
+            Some(mesh) => mesh,
+            None => return,
+        };
+        let matrix = self.surface_affine(&transform.matrix);
+        let color_adjustments = ColorAdjustments::from(&transform.color_transform);
+        for draw in &mesh.draws {
+            let num_indices = if self.mask_state != MaskState::DrawMaskStencil
+                && self.mask_state != MaskState::ClearMaskStencil
+            {
+                draw.indices.len()
+            } else {
+                // Omit strokes when drawing a mask stencil.
+                draw.num_mask_indices as usize
+            };
+            let indices = &draw.indices[..num_indices.min(draw.indices.len())];
+            if indices.is_empty() {
+                continue;
+            }
+
+            match &draw.draw_type {
+                DrawType::Color => {
+                    self.draw_triangles(
+                        &draw.vertices,
+                        indices,
+                        &matrix,
+                        color_adjustments,
+                        Paint::Vertex,
+                    );
+                }
+                DrawType::Gradient(gradient) => {
+                    self.draw_triangles(
+                        &draw.vertices,
+                        indices,
+                        &matrix,
+                        color_adjustments,
+                        Paint::Gradient(gradient),
+                    );
+                }
+                DrawType::Bitmap {
+                    handle,
+                    matrix: texture_matrix,
+                    is_smoothed,
+                    is_repeating,
+                } => {
+                    let pixels = as_bitmap(handle).pixels();
+                    self.draw_triangles(
+                        &draw.vertices,
+                        indices,
+                        &matrix,
+                        color_adjustments,
+                        Paint::Bitmap {
+                            pixels: &pixels,
+                            matrix: *texture_matrix,
+                            smoothed: *is_smoothed,
+                            repeating: *is_repeating,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    fn draw_rect(&mut self, color: Color, matrix: &Matrix) {
+        self.draw_quad(
+            1.0,
+            1.0,
+            &self.surface_affine(matrix),
+            ColorAdjustments {
+                mult: [
+                    f32::from(color.r) / 255.0,
+                    f32::from(color.g) / 255.0,
+                    f32::from(color.b) / 255.0,
+                    f32::from(color.a) / 255.0,
+                ],
+                add: [0.0; 4],
+            },
+            Paint::Vertex,
+        );
+    }
+
+    fn push_mask(&mut self) {
+        debug_assert!(
+            self.mask_state == MaskState::NoMask || self.mask_state == MaskState::DrawMaskedContent
+        );
+        self.num_masks += 1;
+        self.mask_state = MaskState::DrawMaskStencil;
+    }
+
+    fn activate_mask(&mut self) {
+        debug_assert!(self.num_masks > 0 && self.mask_state == MaskState::DrawMaskStencil);
+        self.mask_state = MaskState::DrawMaskedContent;
+    }
+
+    fn deactivate_mask(&mut self) {
+        debug_assert!(self.num_masks > 0 && self.mask_state == MaskState::DrawMaskedContent);
+        self.mask_state = MaskState::ClearMaskStencil;
+    }
+
+    fn pop_mask(&mut self) {
+        debug_assert!(self.num_masks > 0 && self.mask_state == MaskState::ClearMaskStencil);
+        self.num_masks -= 1;
+        self.mask_state = if self.num_masks == 0 {
+            MaskState::NoMask
+        } else {
+            MaskState::DrawMaskedContent
+        };
+    }
+
+    fn render_filtered(
+        &mut self,
+        commands: &'a CommandList,
+        filters: &'a [Filter],
+        bounds: &BoundingBox,
+    ) {
+        // Masks only use the shape of the object, so its filters don't matter.
+        if self.mask_state == MaskState::DrawMaskStencil
+            || self.mask_state == MaskState::ClearMaskStencil
+        {
+            commands.execute(self);
+            return;
+        }
+        if !bounds.valid {
+            return;
+        }
+
+        // Content further than the filters reach from the surface can't affect it.
+        let empty = BoundingBox {
+            x_min: Twips::ZERO,
+            y_min: Twips::ZERO,
+            x_max: Twips::ZERO,
+            y_max: Twips::ZERO,
+            valid: true,
+        };
+        let reach = filters
+            .iter()
+            .fold(empty, |reach, filter| filter.calculate_dest_rect(&reach));
+        let (origin_x, origin_y) = self.origin;
+        let (surface_width, surface_height) =
+            (self.surface.width() as i32, self.surface.height() as i32);
+        let x = (bounds.x_min.to_pixels().floor() as i32)
+            .max(origin_x + reach.x_min.to_pixels().floor() as i32);
+        let y = (bounds.y_min.to_pixels().floor() as i32)
+            .max(origin_y + reach.y_min.to_pixels().floor() as i32);
+        let max_x = (bounds.x_max.to_pixels().ceil() as i32)
+            .min(origin_x + surface_width + reach.x_max.to_pixels().ceil() as i32);
+        let max_y = (bounds.y_max.to_pixels().ceil() as i32)
+            .min(origin_y + surface_height + reach.y_max.to_pixels().ceil() as i32);
+        let width = (max_x - x).min(MAX_DIMENSION as i32);
+        let height = (max_y - y).min(MAX_DIMENSION as i32);
+        if width <= 0 || height <= 0 {
+            return;
+        }
+
+        let mut layer = Surface::new(width as u32, height as u32);
+        let mut renderer = CommandRenderer::new(&mut layer, self.meshes);
+        renderer.origin = (x, y);
+        renderer.is_layer = true;
+        commands.execute(&mut renderer);
+
+        let pixels = layer.resolve();
+        let mut buffer = FilterBuffer::new(width, height);
+        for (i, pixel) in pixels.chunks_exact(4).enumerate() {
+            let i = i as i32;
+            buffer.set(i % width, i / width, from_unorm(pixel));
+        }
+        for filter in filters {
+            let map = match filter {
+                Filter::DisplacementMapFilter(filter) => filter.map_bitmap.as_ref().map(|handle| {
+                    let pixels = as_bitmap(handle).pixels();
+                    let mut map = FilterBuffer::new(pixels.width as i32, pixels.height as i32);
+                    for (i, pixel) in pixels.data.chunks_exact(4).enumerate() {
+                        let i = i as i32;
+                        map.set(i % map.width(), i / map.width(), from_unorm(pixel));
+                    }
+                    map
+                }),
+                _ => None,
+            };
+            buffer = buffer.apply(filter, map.as_ref());
+        }
+        self.draw_buffer(x, y, &buffer);
+    }
+
+    fn render_blend(&mut self, commands: &'a CommandList, blend_mode: BlendMode) {
+        if matches!(blend_mode, BlendMode::Alpha | BlendMode::Erase)
+            && self.mask_state != MaskState::DrawMaskStencil
+            && self.mask_state != MaskState::ClearMaskStencil
+            && !self.is_in_layer()
+        {
+            // These only affect the alpha channel of the layer below them,
+            // which is discarded when there isn't one.
+            return;
+        }
+        self.push_blend_mode(blend_mode);
+        commands.execute(self);
+        self.pop_blend_mode();
+    }
+
+    fn push_blend_mode(&mut self, blend: BlendMode) {
+        // Mask shapes only affect the stencil, so they never need a separate layer.
+        let needs_layer = blend != BlendMode::Normal
+            && self.mask_state != MaskState::DrawMaskStencil
+            && self.mask_state != MaskState::ClearMaskStencil;
+        if needs_layer {
+            self.surface.push_layer(blend);
+        }
+        self.blend_layers.push(needs_layer);
+    }
+
+    fn pop_blend_mode(&mut self) {
+        if self.blend_layers.pop() == Some(true) {
+            // Content inside of a mask may only be composited where the mask was drawn.
+            let stencil = if self.mask_state == MaskState::DrawMaskedContent {
+                Some(self.num_masks)
+            } else {
+                None
+            };
+            self.surface.pop_layer(stencil);
+        }
+    }
+}
@@ -0,0 +1,21 @@
+//! A render backend that rasterizes everything on the CPU.
+//!
+//! This backend does not need a GPU (or any graphics API at all), which makes it
+//! useful for headless environments such as CI machines running the exporter or
+//! the image regression tests. Shapes are tessellated with the same
+//! `ShapeTessellator` used by the wgpu backend, and the resulting triangles are
+//! rasterized into an RGBA buffer with 4x multisampling.
+
+mod bitmap;
+mod blend;
+mod commands;
+mod mesh;
+mod raster;
+mod surface;
+
+pub mod backend;
+
+pub use backend::SoftwareRenderBackend;
+
+/// A color with premultiplied alpha, with each component in the range `[0.0, 1.0]`.
+pub(crate) type Rgba = [f32; 4];
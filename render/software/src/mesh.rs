@@ -0,0 +1,143 @@
+use ruffle_render::backend::RenderBackend;
+use ruffle_render::bitmap::{BitmapHandle, BitmapSource};
+use ruffle_render::tessellator::{
+    Draw as LyonDraw, DrawType as TessDrawType, Gradient, GradientType, Vertex,
+};
+
+pub struct Mesh {
+    pub draws: Vec<Draw>,
+}
+
+pub struct Draw {
+    pub draw_type: DrawType,
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    pub num_mask_indices: u32,
+}
+
+pub enum DrawType {
+    Color,
+    Gradient(Box<Gradient>),
+    Bitmap {
+        handle: BitmapHandle,
+        matrix: [[f32; 3]; 3],
+        is_smoothed: bool,
+        is_repeating: bool,
+    },
+}
+
+impl Draw {
+    /// Converts the output of the tessellator into a draw that can be rasterized.
+    /// Returns `None` if the draw references a bitmap that doesn't exist.
+    pub fn new(
+        draw: LyonDraw,
+        source: &dyn BitmapSource,
+        backend: &mut dyn RenderBackend,
+    ) -> Option<Self> {
+        let draw_type = match draw.draw_type {
+            TessDrawType::Color => DrawType::Color,
+            TessDrawType::Gradient(gradient) => DrawType::Gradient(Box::new(gradient)),
+            TessDrawType::Bitmap(bitmap) => DrawType::Bitmap {
+                handle: source.bitmap_handle(bitmap.bitmap_id, backend)?,
+                matrix: bitmap.matrix,
+                is_smoothed: bitmap.is_smoothed,
+                is_repeating: bitmap.is_repeating,
+            },
+        };
+        Some(Self {
+            draw_type,
+            vertices: draw.vertices,
+            indices: draw.indices,
+            num_mask_indices: draw.mask_index_count,
+        })
+    }
+}
+
+/// Applies a texture matrix (as produced by the tessellator) to a point in object space.
+#[inline]
+pub fn texture_uv(matrix: &[[f32; 3]; 3], x: f32, y: f32) -> (f32, f32) {
+    (
+        matrix[0][0] * x + matrix[1][0] * y + matrix[2][0],
+        matrix[0][1] * x + matrix[1][1] * y + matrix[2][1],
+    )
+}
+
+/// Evaluates a gradient at the given UV coordinate.
+/// Returns a color with straight (non-premultiplied) alpha.
+pub fn sample_gradient(gradient: &Gradient, u: f32, v: f32) -> [f32; 4] {
+    if gradient.num_colors == 0 {
+        return [0.0; 4];
+    }
+
+    let mut t = match gradient.gradient_type {
+        GradientType::Linear => u,
+        GradientType::Radial => {
+            let (x, y) = (u * 2.0 - 1.0, v * 2.0 - 1.0);
+            (x * x + y * y).sqrt()
+        }
+        GradientType::Focal => {
+            let focal_point = gradient.focal_point.to_f32();
+            let (x, y) = (u * 2.0 - 1.0, v * 2.0 - 1.0);
+            let (dx, dy) = (focal_point - x, -y);
+            let l = (dx * dx + dy * dy).sqrt();
+            if l == 0.0 {
+                0.0
+            } else {
+                let (dx, dy) = (dx / l, dy / l);
+                l / ((1.0 - focal_point * focal_point * dy * dy).sqrt() + focal_point * dx)
+            }
+        }
+    };
+
+    t = match gradient.repeat_mode {
+        swf::GradientSpread::Pad => t.clamp(0.0, 1.0),
+        swf::GradientSpread::Repeat => t - t.floor(),
+        swf::GradientSpread::Reflect => {
+            let t = t.abs();
+            if (t as i32) & 1 == 0 {
+                t.fract()
+            } else {
+                1.0 - t.fract()
+            }
+        }
+    };
+
+    let last = gradient.num_colors - 1;
+    let ratios = &gradient.ratios[..gradient.num_colors];
+    let t = t.clamp(ratios[0], ratios[last]);
+    let j = ratios
+        .iter()
+        .position(|&ratio| t <= ratio)
+        .unwrap_or(last)
+        .max(1)
+        .min(last);
+    let i = j - 1;
+    let color = if last == 0 || ratios[j] == ratios[i] {
+        gradient.colors[j]
+    } else {
+        let a = (t - ratios[i]) / (ratios[j] - ratios[i]);
+        let (from, to) = (gradient.colors[i], gradient.colors[j]);
+        [
+            from[0] + (to[0] - from[0]) * a,
+            from[1] + (to[1] - from[1]) * a,
+            from[2] + (to[2] - from[2]) * a,
+            from[3] + (to[3] - from[3]) * a,
+        ]
+    };
+
+    match gradient.interpolation {
+        swf::GradientInterpolation::Rgb => color,
+        swf::GradientInterpolation::LinearRgb => linear_to_srgb(color),
+    }
+}
+
+fn linear_to_srgb(mut color: [f32; 4]) -> [f32; 4] {
+    for n in &mut color[..3] {
+        *n = if *n <= 0.0031308 {
+            *n * 12.92
+        } else {
+            1.055 * n.powf(1.0 / 2.4) - 0.055
+        };
+    }
+    color
+}
@@ -0,0 +1,152 @@
+use crate::blend;
+use crate::raster::SAMPLE_COUNT;
+use crate::Rgba;
+use swf::BlendMode;
+
+/// A multisampled render target.
+///
+/// Each pixel stores `SAMPLE_COUNT` premultiplied RGBA samples, plus a stencil
+/// value per sample that is used for masking. Drawing always happens into the
+/// topmost layer; extra layers are pushed for objects with a non-normal blend
+/// mode, and composited into the layer below once the object is finished.
+pub struct Surface {
+    width: u32,
+    height: u32,
+    layers: Vec<Layer>,
+    stencil: Vec<u8>,
+}
+
+struct Layer {
+    blend_mode: BlendMode,
+    samples: Vec<[u8; 4]>,
+}
+
+#[inline]
+fn to_unorm(color: Rgba) -> [u8; 4] {
+    color.map(|n| (n.clamp(0.0, 1.0) * 255.0 + 0.5) as u8)
+}
+
+#[inline]
+fn from_unorm(color: [u8; 4]) -> Rgba {
+    color.map(|n| f32::from(n) / 255.0)
+}
+
+impl Surface {
+    pub fn new(width: u32, height: u32) -> Self {
+        let num_samples = width as usize * height as usize * SAMPLE_COUNT;
+        Self {
+            width,
+            height,
+            layers: vec![Layer {
+                blend_mode: BlendMode::Normal,
+                samples: vec![[0; 4]; num_samples],
+            }],
+            stencil: vec![0; num_samples],
+        }
+    }
+
+    /// Creates a surface whose samples are initialized from the given premultiplied RGBA pixels.
+    /// Pixels outside of `pixels_width` and `pixels_height` are left transparent.
+    pub fn from_pixels(
+        width: u32,
+        height: u32,
+        pixels_width: u32,
+        pixels_height: u32,
+        pixels: &[u8],
+    ) -> Self {
+        let mut surface = Self::new(width, height);
+        let samples = &mut surface.layers[0].samples;
+        for y in 0..height.min(pixels_height) as usize {
+            for x in 0..width.min(pixels_width) as usize {
+                let i = (y * pixels_width as usize + x) * 4;
+                if let Some(pixel) = pixels.get(i..i + 4) {
+                    let first_sample = (y * width as usize + x) * SAMPLE_COUNT;
+                    samples[first_sample..first_sample + SAMPLE_COUNT]
+                        .fill([pixel[0], pixel[1], pixel[2], pixel[3]]);
+                }
+            }
+        }
+        surface
+    }
+
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Fills the entire surface with a color, and resets all masks and layers.
+    pub fn clear(&mut self, color: Rgba) {
+        self.layers.truncate(1);
+        self.layers[0].samples.fill(to_unorm(color));
+        self.stencil.fill(0);
+    }
+
+    #[inline]
+    pub fn stencil(&self, sample: usize) -> u8 {
+        self.stencil[sample]
+    }
+
+    #[inline]
+    pub fn set_stencil(&mut self, sample: usize, value: u8) {
+        self.stencil[sample] = value;
+    }
+
+    /// Blends a color over the given sample of the current layer.
+    #[inline]
+    pub fn blend_sample(&mut self, sample: usize, color: Rgba) {
+        let layer = self.layers.last_mut().unwrap();
+        let dst = from_unorm(layer.samples[sample]);
+        layer.samples[sample] = to_unorm(blend::over(color, dst));
+    }
+
+    /// Starts a new, transparent layer that will be composited using `blend_mode`.
+    pub fn push_layer(&mut self, blend_mode: BlendMode) {
+        self.layers.push(Layer {
+            blend_mode,
+            samples: vec![[0; 4]; self.stencil.len()],
+        });
+    }
+
+    /// Composites the current layer into the layer below it.
+    /// If `stencil` is set, only samples with that stencil value are affected.
+    pub fn pop_layer(&mut self, stencil: Option<u8>) {
+        if self.layers.len() <= 1 {
+            log::warn!("Attempted to pop the base layer of a software surface");
+            return;
+        }
+        let layer = self.layers.pop().unwrap();
+        let parent = self.layers.last_mut().unwrap();
+        let samples = parent.samples.iter_mut().zip(layer.samples);
+        for ((dst, src), value) in samples.zip(&self.stencil) {
+            if stencil.map_or(true, |stencil| stencil == *value) {
+                *dst = to_unorm(blend::blend(
+                    layer.blend_mode,
+                    from_unorm(src),
+                    from_unorm(*dst),
+                ));
+            }
+        }
+    }
+
+    /// Averages the samples of the base layer into premultiplied RGBA pixels.
+    pub fn resolve(&self) -> Vec<u8> {
+        self.layers[0]
+            .samples
+            .chunks_exact(SAMPLE_COUNT)
+            .flat_map(|samples| {
+                let mut sum = [0u32; 4];
+                for sample in samples {
+                    for (sum, n) in sum.iter_mut().zip(sample) {
+                        *sum += u32::from(*n);
+                    }
+                }
+                sum.map(|n| ((n + SAMPLE_COUNT as u32 / 2) / SAMPLE_COUNT as u32) as u8)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,297 @@
+//! Decoding of Adobe Texture Format (ATF) files,
+//! as used by `Texture.uploadCompressedTextureFromByteArray`.
+//!
+//! Only the 'raw compressed' formats are supported. These store
+//! each mip level in several GPU block-compression formats (DXT, PVRTC, ETC),
+//! of which we only decode the DXT data.
+
+use crate::error::Error;
+
+/// A decoded ATF texture.
+pub struct AtfTexture {
+    pub width: u32,
+    pub height: u32,
+    pub cube_map: bool,
+
+    /// The RGBA pixels of each mip level, for each face of the texture.
+    /// A cube map has six faces, while other textures only have one.
+    pub faces: Vec<Vec<Vec<u8>>>,
+}
+
+const FORMAT_RAW_COMPRESSED: u8 = 3;
+const FORMAT_RAW_COMPRESSED_ALPHA: u8 = 5;
+
+pub fn decode_atf(data: &[u8]) -> Result<AtfTexture, Error> {
+    if !data.starts_with(b"ATF") {
+        return Err(Error::InvalidAtf);
+    }
+
+    // Newer files mark their extended header with 0xFF, and store
+    // the version number right after it.
+    let (version, header_size) = if data.get(6) == Some(&0xFF) {
+        (data.get(7).copied().ok_or(Error::InvalidAtf)?, 12)
+    } else {
+        (0, 6)
+    };
+    let mut data = data.get(header_size..).ok_or(Error::InvalidAtf)?;
+
+    let header = read_bytes(&mut data, 4)?;
+    let cube_map = header[0] & 0x80 != 0;
+    let format = header[0] & 0x7F;
+    if header[1] > 12 || header[2] > 12 {
+        return Err(Error::InvalidAtf);
+    }
+    let width = 1 << header[1];
+    let height = 1 << header[2];
+    let num_mip_levels = header[3];
+    // Every level halves the larger dimension, down to 1x1.
+    if num_mip_levels > header[1].max(header[2]) + 1 {
+        return Err(Error::InvalidAtf);
+    }
+
+    let has_alpha = match format {
+        FORMAT_RAW_COMPRESSED => false,
+        FORMAT_RAW_COMPRESSED_ALPHA => true,
+        _ => return Err(Error::UnsupportedAtfFormat(format)),
+    };
+
+    // Block lengths were 24-bit in files without the extended header.
+    let length_size = if version > 0 { 4 } else { 3 };
+
+    let num_faces = if cube_map { 6 } else { 1 };
+    let mut faces = Vec::with_capacity(num_faces);
+    for _ in 0..num_faces {
+        let mut levels = Vec::with_capacity(num_mip_levels as usize);
+        for level in 0..num_mip_levels {
+            let dxt = read_block(&mut data, length_size)?;
+            // PVRTC and ETC1 data, which we don't use.
+            read_block(&mut data, length_size)?;
+            read_block(&mut data, length_size)?;
+            if version >= 3 {
+                // ETC2 data
+                read_block(&mut data, length_size)?;
+            }
+
+            let level_width = (width >> level).max(1);
+            let level_height = (height >> level).max(1);
+            levels.push(decode_dxt(dxt, level_width, level_height, has_alpha)?);
+        }
+        faces.push(levels);
+    }
+
+    Ok(AtfTexture {
+        width,
+        height,
+        cube_map,
+        faces,
+    })
+}
+
+fn read_bytes<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], Error> {
+    if data.len() < len {
+        return Err(Error::InvalidAtf);
+    }
+    let (bytes, rest) = data.split_at(len);
+    *data = rest;
+    Ok(bytes)
+}
+
+fn read_block<'a>(data: &mut &'a [u8], length_size: usize) -> Result<&'a [u8], Error> {
+    let length = read_bytes(data, length_size)?
+        .iter()
+        .fold(0, |length, byte| (length << 8) | *byte as usize);
+    read_bytes(data, length)
+}
+
+/// Decodes DXT1 (or DXT5, if `has_alpha` is set) data to RGBA pixels.
+fn decode_dxt(data: &[u8], width: u32, height: u32, has_alpha: bool) -> Result<Vec<u8>, Error> {
+    let block_size = if has_alpha { 16 } else { 8 };
+    let blocks_x = (width as usize + 3) / 4;
+    let blocks_y = (height as usize + 3) / 4;
+    if data.len() < blocks_x * blocks_y * block_size {
+        return Err(Error::InvalidAtf);
+    }
+
+    let mut rgba = vec![0; width as usize * height as usize * 4];
+    for (i, block) in data
+        .chunks_exact(block_size)
+        .take(blocks_x * blocks_y)
+        .enumerate()
+    {
+        let (alphas, color_block) = if has_alpha {
+            (Some(dxt5_alphas(&block[..8])), &block[8..])
+        } else {
+            (None, block)
+        };
+
+        let c0 = u16::from_le_bytes([color_block[0], color_block[1]]);
+        let c1 = u16::from_le_bytes([color_block[2], color_block[3]]);
+        let color_indices = u32::from_le_bytes(color_block[4..8].try_into().unwrap());
+
+        let rgb0 = rgb565(c0);
+        let rgb1 = rgb565(c1);
+        let mut palette = [[0; 4]; 4];
+        palette[0] = [rgb0[0], rgb0[1], rgb0[2], 255];
+        palette[1] = [rgb1[0], rgb1[1], rgb1[2], 255];
+        if has_alpha || c0 > c1 {
+            for c in 0..3 {
+                palette[2][c] = ((2 * rgb0[c] as u16 + rgb1[c] as u16) / 3) as u8;
+                palette[3][c] = ((rgb0[c] as u16 + 2 * rgb1[c] as u16) / 3) as u8;
+            }
+            palette[2][3] = 255;
+            palette[3][3] = 255;
+        } else {
+            for c in 0..3 {
+                palette[2][c] = ((rgb0[c] as u16 + rgb1[c] as u16) / 2) as u8;
+            }
+            palette[2][3] = 255;
+            // The fourth color is transparent black.
+        }
+
+        let block_x = (i % blocks_x) * 4;
+        let block_y = (i / blocks_x) * 4;
+        for pixel in 0..16 {
+            let x = block_x + pixel % 4;
+            let y = block_y + pixel / 4;
+            if x >= width as usize || y >= height as usize {
+                continue;
+            }
+
+            let mut color = palette[((color_indices >> (pixel * 2)) & 0b11) as usize];
+            if let Some(alphas) = &alphas {
+                color[3] = alphas[pixel];
+            }
+            let offset = (y * width as usize + x) * 4;
+            rgba[offset..offset + 4].copy_from_slice(&color);
+        }
+    }
+    Ok(rgba)
+}
+
+/// Decodes the alpha values of the 16 pixels in a DXT5 alpha block.
+fn dxt5_alphas(block: &[u8]) -> [u8; 16] {
+    let a0 = block[0] as u16;
+    let a1 = block[1] as u16;
+    let mut palette = [0; 8];
+    palette[0] = a0 as u8;
+    palette[1] = a1 as u8;
+    if a0 > a1 {
+        for i in 1..7 {
+            palette[i + 1] = (((7 - i as u16) * a0 + i as u16 * a1) / 7) as u8;
+        }
+    } else {
+        for i in 1..5 {
+            palette[i + 1] = (((5 - i as u16) * a0 + i as u16 * a1) / 5) as u8;
+        }
+        palette[6] = 0;
+        palette[7] = 255;
+    }
+
+    let indices = block[2..8]
+        .iter()
+        .rev()
+        .fold(0u64, |indices, byte| (indices << 8) | *byte as u64);
+    let mut alphas = [0; 16];
+    for (pixel, alpha) in alphas.iter_mut().enumerate() {
+        *alpha = palette[((indices >> (pixel * 3)) & 0b111) as usize];
+    }
+    alphas
+}
+
+/// Expands a 5:6:5 packed color to 8 bits per channel.
+fn rgb565(color: u16) -> [u8; 3] {
+    let r = ((color >> 11) & 0x1F) as u8;
+    let g = ((color >> 5) & 0x3F) as u8;
+    let b = (color & 0x1F) as u8;
+    [
+        (r << 3) | (r >> 2),
+        (g << 2) | (g >> 4),
+        (b << 3) | (b >> 2),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds an ATF file without the extended header, storing `dxt` for every mip level.
+    fn atf(format: u8, log_width: u8, log_height: u8, dxt: &[&[u8]]) -> Vec<u8> {
+        let mut data = b"ATF\0\0\0".to_vec();
+        data.extend_from_slice(&[format, log_width, log_height, dxt.len() as u8]);
+        for level in dxt {
+            data.extend_from_slice(&(level.len() as u32).to_be_bytes()[1..]);
+            data.extend_from_slice(level);
+            // Empty PVRTC and ETC1 blocks.
+            data.extend_from_slice(&[0; 6]);
+        }
+        data
+    }
+
+    fn pixels(rgba: &[u8]) -> Vec<[u8; 4]> {
+        rgba.chunks_exact(4)
+            .map(|pixel| pixel.try_into().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn decode_dxt1_four_colors() {
+        // Red and blue endpoints, with the pixels of the first row using indices 0, 1, 2 and 3.
+        let block = [0x00, 0xF8, 0x1F, 0x00, 0xE4, 0, 0, 0];
+        let rgba = decode_dxt(&block, 4, 4, false).unwrap();
+        let pixels = pixels(&rgba);
+        assert_eq!(pixels[0], [255, 0, 0, 255]);
+        assert_eq!(pixels[1], [0, 0, 255, 255]);
+        assert_eq!(pixels[2], [170, 0, 85, 255]);
+        assert_eq!(pixels[3], [85, 0, 170, 255]);
+        assert_eq!(pixels[4], [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn decode_dxt1_three_colors() {
+        // Swapping the endpoints selects the mode with a transparent fourth color.
+        let block = [0x1F, 0x00, 0x00, 0xF8, 0xE4, 0, 0, 0];
+        let rgba = decode_dxt(&block, 4, 4, false).unwrap();
+        let pixels = pixels(&rgba);
+        assert_eq!(pixels[0], [0, 0, 255, 255]);
+        assert_eq!(pixels[1], [255, 0, 0, 255]);
+        assert_eq!(pixels[2], [127, 0, 127, 255]);
+        assert_eq!(pixels[3], [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn decode_dxt5_alpha() {
+        // Alpha endpoints 255 and 0, with the first pixels using indices 0, 1 and 2.
+        let alpha_block = [255, 0, 0x88, 0, 0, 0, 0, 0];
+        let color_block = [0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0];
+        let block = [alpha_block, color_block].concat();
+        let rgba = decode_dxt(&block, 4, 4, true).unwrap();
+        let pixels = pixels(&rgba);
+        assert_eq!(pixels[0], [255, 255, 255, 255]);
+        assert_eq!(pixels[1], [255, 255, 255, 0]);
+        assert_eq!(pixels[2], [255, 255, 255, 218]);
+        assert_eq!(pixels[3], [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn decode_dxt_too_short() {
+        assert!(decode_dxt(&[0; 8], 8, 4, false).is_err());
+    }
+
+    #[test]
+    fn decode_atf_mip_levels() {
+        let block: &[u8] = &[0x00, 0xF8, 0x1F, 0x00, 0, 0, 0, 0];
+        let texture = decode_atf(&atf(FORMAT_RAW_COMPRESSED, 1, 0, &[block, block])).unwrap();
+        assert_eq!((texture.width, texture.height), (2, 1));
+        assert!(!texture.cube_map);
+        assert_eq!(texture.faces.len(), 1);
+        assert_eq!(texture.faces[0][0], [255, 0, 0, 255, 255, 0, 0, 255]);
+        assert_eq!(texture.faces[0][1], [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn decode_atf_too_many_mip_levels() {
+        let block: &[u8] = &[0; 8];
+        let data = atf(FORMAT_RAW_COMPRESSED, 1, 0, &[block, block, block]);
+        assert!(matches!(decode_atf(&data), Err(Error::InvalidAtf)));
+    }
+}
@@ -73,6 +73,9 @@ impl_downcast!(VertexBuffer);
 pub trait ShaderModule: Downcast + Collect {}
 impl_downcast!(ShaderModule);
 
+pub trait Texture: Downcast + Collect {}
+impl_downcast!(Texture);
+
 #[derive(Collect)]
 #[collect(require_static)]
 pub enum BufferUsage {
@@ -109,6 +112,33 @@ pub trait Context3D: Collect + Downcast {
         num_vertices: u32,
         vertex_size: u32,
     ) -> Rc<dyn VertexBuffer>;
+
+    fn create_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: Context3DTextureFormat,
+        optimize_for_render_to_texture: bool,
+        streaming_levels: u32,
+    ) -> Rc<dyn Texture>;
+
+    // A `RectangleTexture` has no mipmaps, and does not need to have
+    // power-of-two dimensions.
+    fn create_rectangle_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: Context3DTextureFormat,
+        optimize_for_render_to_texture: bool,
+    ) -> Rc<dyn Texture>;
+
+    fn create_cube_texture(
+        &mut self,
+        size: u32,
+        format: Context3DTextureFormat,
+        optimize_for_render_to_texture: bool,
+        streaming_levels: u32,
+    ) -> Rc<dyn Texture>;
 }
 impl_downcast!(Context3D);
 
@@ -122,7 +152,7 @@ pub enum Context3DVertexBufferFormat {
     Bytes4,
 }
 
-#[derive(Collect, Copy, Clone, Debug)]
+#[derive(Collect, Copy, Clone, Debug, PartialEq, Eq)]
 #[collect(require_static)]
 pub enum Context3DTriangleFace {
     None,
@@ -131,6 +161,96 @@ pub enum Context3DTriangleFace {
     FrontAndBack,
 }
 
+#[derive(Collect, Copy, Clone, Debug, PartialEq, Eq)]
+#[collect(require_static)]
+pub enum Context3DTextureFormat {
+    Bgra,
+    BgraPacked,
+    BgrPacked,
+    Compressed,
+    CompressedAlpha,
+    RgbaHalfFloat,
+}
+
+#[derive(Collect, Copy, Clone, Debug, PartialEq, Eq)]
+#[collect(require_static)]
+pub enum Context3DWrapMode {
+    Clamp,
+    ClampURepeatV,
+    Repeat,
+    RepeatUClampV,
+}
+
+#[derive(Collect, Copy, Clone, Debug, PartialEq, Eq)]
+#[collect(require_static)]
+pub enum Context3DTextureFilter {
+    Nearest,
+    Linear,
+    Anisotropic2X,
+    Anisotropic4X,
+    Anisotropic8X,
+    Anisotropic16X,
+}
+
+#[derive(Collect, Copy, Clone, Debug, PartialEq, Eq)]
+#[collect(require_static)]
+pub enum Context3DMipFilter {
+    MipNone,
+    MipNearest,
+    MipLinear,
+}
+
+#[derive(Collect, Copy, Clone, Debug, PartialEq, Eq)]
+#[collect(require_static)]
+pub enum Context3DBlendFactor {
+    DestinationAlpha,
+    DestinationColor,
+    One,
+    OneMinusDestinationAlpha,
+    OneMinusDestinationColor,
+    OneMinusSourceAlpha,
+    OneMinusSourceColor,
+    SourceAlpha,
+    SourceColor,
+    Zero,
+}
+
+#[derive(Collect, Copy, Clone, Debug, PartialEq, Eq)]
+#[collect(require_static)]
+pub enum Context3DCompareMode {
+    Always,
+    Equal,
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+    Never,
+    NotEqual,
+}
+
+#[derive(Collect, Copy, Clone, Debug, PartialEq, Eq)]
+#[collect(require_static)]
+pub enum Context3DStencilAction {
+    DecrementSaturate,
+    DecrementWrap,
+    IncrementSaturate,
+    IncrementWrap,
+    Invert,
+    Keep,
+    Set,
+    Zero,
+}
+
+/// A rectangle in the pixel coordinates of the current render target.
+#[derive(Collect, Copy, Clone, Debug, PartialEq, Eq)]
+#[collect(require_static)]
+pub struct Context3DRectangle {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
 #[derive(Collect)]
 #[collect(no_drop)]
 pub enum Context3DCommand<'gc> {
@@ -197,9 +317,70 @@ pub enum Context3DCommand<'gc> {
     SetCulling {
         face: Context3DTriangleFace,
     },
+
+    // `data` holds the RGBA pixels of a single mip level (and cube face).
+    // Cube faces are numbered in the same order as `CubeTexture.uploadFromBitmapData`
+    // (+X, -X, +Y, -Y, +Z, -Z), and `side` is always 0 for other textures.
+    UploadToTexture {
+        texture: Rc<dyn Texture>,
+        side: u32,
+        mip_level: u32,
+        data: Vec<u8>,
+    },
+    SetTextureAt {
+        sampler: u32,
+        texture: Option<Rc<dyn Texture>>,
+    },
+    SetSamplerStateAt {
+        sampler: u32,
+        wrap: Context3DWrapMode,
+        filter: Context3DTextureFilter,
+        mip_filter: Context3DMipFilter,
+    },
+
+    SetBlendFactors {
+        source_factor: Context3DBlendFactor,
+        destination_factor: Context3DBlendFactor,
+    },
+    SetDepthTest {
+        depth_mask: bool,
+        pass_compare_mode: Context3DCompareMode,
+    },
+    SetStencilActions {
+        triangle_face: Context3DTriangleFace,
+        compare_mode: Context3DCompareMode,
+        action_on_both_pass: Context3DStencilAction,
+        action_on_depth_fail: Context3DStencilAction,
+        action_on_depth_pass_stencil_fail: Context3DStencilAction,
+    },
+    SetStencilReferenceValue {
+        reference_value: u32,
+        read_mask: u32,
+        write_mask: u32,
+    },
+    // `None` disables the scissor rectangle.
+    SetScissorRectangle {
+        rectangle: Option<Context3DRectangle>,
+    },
+    SetColorMask {
+        red: bool,
+        green: bool,
+        blue: bool,
+        alpha: bool,
+    },
+
+    // `surface_selector` is the cube face to render to, and is always 0 for other textures.
+    SetRenderToTexture {
+        texture: Rc<dyn Texture>,
+        enable_depth_and_stencil: bool,
+        anti_alias: u32,
+        surface_selector: u32,
+        color_output_index: u32,
+    },
+    SetRenderToBackBuffer,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct ShapeHandle(pub usize);
 
 #[derive(Copy, Clone, Debug)]
@@ -1,5 +1,7 @@
 use crate::backend::ShapeHandle;
 use crate::bitmap::BitmapHandle;
+use crate::bounding_box::BoundingBox;
+use crate::filters::Filter;
 use crate::matrix::Matrix;
 use crate::transform::Transform;
 use swf::{BlendMode, Color};
@@ -15,9 +17,38 @@ pub trait CommandHandler<'a> {
 
     fn push_blend_mode(&mut self, blend: BlendMode);
     fn pop_blend_mode(&mut self);
+
+    /// Renders `commands` with the given filters applied, in order.
+    /// `bounds` is the area (in stage space) covered by the filtered result.
+    ///
+    /// The commands use the same coordinate space as the rest of the list.
+    /// Backends that don't support filters will simply draw them unfiltered.
+    fn render_filtered(
+        &mut self,
+        commands: &'a CommandList,
+        _filters: &'a [Filter],
+        _bounds: &BoundingBox,
+    ) where
+        Self: Sized,
+    {
+        commands.execute(self);
+    }
+
+    /// Renders `commands` as a single group, which is then blended with the content below it
+    /// using `blend_mode`.
+    ///
+    /// Backends that can't render groups offscreen will blend each draw individually instead.
+    fn render_blend(&mut self, commands: &'a CommandList, blend_mode: BlendMode)
+    where
+        Self: Sized,
+    {
+        self.push_blend_mode(blend_mode);
+        commands.execute(self);
+        self.pop_blend_mode();
+    }
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct CommandList(Vec<Command>);
 
 impl CommandList {
@@ -43,6 +74,15 @@ impl CommandList {
                 Command::PopMask => handler.pop_mask(),
                 Command::PushBlendMode(blend) => handler.push_blend_mode(*blend),
                 Command::PopBlendMode => handler.pop_blend_mode(),
+                Command::RenderFiltered {
+                    commands,
+                    filters,
+                    bounds,
+                } => handler.render_filtered(commands, filters, bounds),
+                Command::RenderBlend {
+                    commands,
+                    blend_mode,
+                } => handler.render_blend(commands, *blend_mode),
             }
         }
     }
@@ -94,9 +134,29 @@ impl<'a> CommandHandler<'a> for CommandList {
     fn pop_blend_mode(&mut self) {
         self.0.push(Command::PopBlendMode);
     }
+
+    fn render_filtered(
+        &mut self,
+        commands: &'a CommandList,
+        filters: &'a [Filter],
+        bounds: &BoundingBox,
+    ) {
+        self.0.push(Command::RenderFiltered {
+            commands: commands.clone(),
+            filters: filters.to_vec(),
+            bounds: bounds.clone(),
+        });
+    }
+
+    fn render_blend(&mut self, commands: &'a CommandList, blend_mode: BlendMode) {
+        self.0.push(Command::RenderBlend {
+            commands: commands.clone(),
+            blend_mode,
+        });
+    }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Command {
     RenderBitmap {
         bitmap: BitmapHandle,
@@ -117,4 +177,13 @@ pub enum Command {
     PopMask,
     PushBlendMode(BlendMode),
     PopBlendMode,
+    RenderFiltered {
+        commands: CommandList,
+        filters: Vec<Filter>,
+        bounds: BoundingBox,
+    },
+    RenderBlend {
+        commands: CommandList,
+        blend_mode: BlendMode,
+    },
 }
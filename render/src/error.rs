@@ -25,6 +25,12 @@ pub enum Error {
     #[error("Empty GIF")]
     EmptyGif,
 
+    #[error("Invalid ATF data")]
+    InvalidAtf,
+
+    #[error("Unsupported ATF format {0}")]
+    UnsupportedAtfFormat(u8),
+
     #[error("Unsupported DefineBitsLossless{0} format {1:?}")]
     UnsupportedLosslessFormat(u8, swf::BitmapFormat),
 
@@ -0,0 +1,482 @@
+use crate::bitmap::BitmapHandle;
+use crate::bounding_box::BoundingBox;
+use swf::{Color, Twips};
+
+pub mod cpu;
+
+/// A bitmap filter that can be applied to a display object (via `DisplayObject.filters`),
+/// or to a `BitmapData` (via `BitmapData.applyFilter`).
+///
+/// All distances are in pixels, and all angles are in radians.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Filter {
+    BlurFilter(BlurFilter),
+    GlowFilter(GlowFilter),
+    DropShadowFilter(DropShadowFilter),
+    BevelFilter(BevelFilter),
+    GradientGlowFilter(GradientFilter),
+    GradientBevelFilter(GradientFilter),
+    ColorMatrixFilter(ColorMatrixFilter),
+    ConvolutionFilter(ConvolutionFilter),
+    DisplacementMapFilter(DisplacementMapFilter),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlurFilter {
+    pub blur_x: f32,
+    pub blur_y: f32,
+    pub quality: u8,
+}
+
+impl Default for BlurFilter {
+    fn default() -> Self {
+        Self {
+            blur_x: 4.0,
+            blur_y: 4.0,
+            quality: 1,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GlowFilter {
+    pub color: Color,
+    pub blur_x: f32,
+    pub blur_y: f32,
+    pub strength: f32,
+    pub quality: u8,
+    pub inner: bool,
+    pub knockout: bool,
+}
+
+impl Default for GlowFilter {
+    fn default() -> Self {
+        Self {
+            color: Color::from_rgb(0xFF0000, 255),
+            blur_x: 6.0,
+            blur_y: 6.0,
+            strength: 2.0,
+            quality: 1,
+            inner: false,
+            knockout: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DropShadowFilter {
+    pub color: Color,
+    pub blur_x: f32,
+    pub blur_y: f32,
+    pub angle: f32,
+    pub distance: f32,
+    pub strength: f32,
+    pub quality: u8,
+    pub inner: bool,
+    pub knockout: bool,
+    pub hide_object: bool,
+}
+
+impl Default for DropShadowFilter {
+    fn default() -> Self {
+        Self {
+            color: Color::BLACK,
+            blur_x: 4.0,
+            blur_y: 4.0,
+            angle: std::f32::consts::FRAC_PI_4,
+            distance: 4.0,
+            strength: 1.0,
+            quality: 1,
+            inner: false,
+            knockout: false,
+            hide_object: false,
+        }
+    }
+}
+
+/// Where the effect of a bevel filter is placed, relative to the object.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BevelFilterType {
+    Inner,
+    Outer,
+    Full,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BevelFilter {
+    pub shadow_color: Color,
+    pub highlight_color: Color,
+    pub blur_x: f32,
+    pub blur_y: f32,
+    pub angle: f32,
+    pub distance: f32,
+    pub strength: f32,
+    pub quality: u8,
+    pub bevel_type: BevelFilterType,
+    pub knockout: bool,
+}
+
+impl Default for BevelFilter {
+    fn default() -> Self {
+        Self {
+            shadow_color: Color::BLACK,
+            highlight_color: Color::WHITE,
+            blur_x: 4.0,
+            blur_y: 4.0,
+            angle: std::f32::consts::FRAC_PI_4,
+            distance: 4.0,
+            strength: 1.0,
+            quality: 1,
+            bevel_type: BevelFilterType::Inner,
+            knockout: false,
+        }
+    }
+}
+
+/// The shared parameters of `GradientGlowFilter` and `GradientBevelFilter`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GradientFilter {
+    /// The gradient used to color the effect, as `(ratio, color)` pairs.
+    pub colors: Vec<(u8, Color)>,
+    pub blur_x: f32,
+    pub blur_y: f32,
+    pub angle: f32,
+    pub distance: f32,
+    pub strength: f32,
+    pub quality: u8,
+    pub bevel_type: BevelFilterType,
+    pub knockout: bool,
+}
+
+impl Default for GradientFilter {
+    fn default() -> Self {
+        Self {
+            colors: vec![],
+            blur_x: 4.0,
+            blur_y: 4.0,
+            angle: std::f32::consts::FRAC_PI_4,
+            distance: 4.0,
+            strength: 1.0,
+            quality: 1,
+            bevel_type: BevelFilterType::Inner,
+            knockout: false,
+        }
+    }
+}
+
+impl GradientFilter {
+    /// Samples the gradient at the given position in `[0.0, 1.0]`.
+    /// The returned color has straight alpha, with each component in `[0.0, 1.0]`.
+    pub fn sample(&self, t: f32) -> [f32; 4] {
+        let to_rgba = |color: &Color| {
+            [
+                f32::from(color.r) / 255.0,
+                f32::from(color.g) / 255.0,
+                f32::from(color.b) / 255.0,
+                f32::from(color.a) / 255.0,
+            ]
+        };
+        let ratio = t.clamp(0.0, 1.0) * 255.0;
+        let (first, last) = match (self.colors.first(), self.colors.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return [0.0; 4],
+        };
+        if ratio <= f32::from(first.0) {
+            return to_rgba(&first.1);
+        }
+        for pair in self.colors.windows(2) {
+            let (start, end) = (&pair[0], &pair[1]);
+            if ratio <= f32::from(end.0) {
+                let range = f32::from(end.0) - f32::from(start.0);
+                let a = if range > 0.0 {
+                    (ratio - f32::from(start.0)) / range
+                } else {
+                    1.0
+                };
+                let (start, end) = (to_rgba(&start.1), to_rgba(&end.1));
+                return [
+                    start[0] + (end[0] - start[0]) * a,
+                    start[1] + (end[1] - start[1]) * a,
+                    start[2] + (end[2] - start[2]) * a,
+                    start[3] + (end[3] - start[3]) * a,
+                ];
+            }
+        }
+        to_rgba(&last.1)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorMatrixFilter {
+    /// A 4x5 matrix in row-major order.
+    /// The last column is the offset, in the range `[0.0, 255.0]`.
+    pub matrix: [f32; 20],
+}
+
+impl Default for ColorMatrixFilter {
+    fn default() -> Self {
+        Self {
+            matrix: [
+                1.0, 0.0, 0.0, 0.0, 0.0, //
+                0.0, 1.0, 0.0, 0.0, 0.0, //
+                0.0, 0.0, 1.0, 0.0, 0.0, //
+                0.0, 0.0, 0.0, 1.0, 0.0, //
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConvolutionFilter {
+    pub matrix_x: u8,
+    pub matrix_y: u8,
+    /// The `matrix_x` by `matrix_y` kernel, in row-major order.
+    pub matrix: Vec<f32>,
+    pub divisor: f32,
+    pub bias: f32,
+    pub preserve_alpha: bool,
+    pub clamp: bool,
+    /// The color used for pixels outside of the source, if `clamp` is false.
+    pub color: Color,
+}
+
+impl Default for ConvolutionFilter {
+    fn default() -> Self {
+        Self {
+            matrix_x: 0,
+            matrix_y: 0,
+            matrix: vec![],
+            divisor: 1.0,
+            bias: 0.0,
+            preserve_alpha: true,
+            clamp: true,
+            color: Color::from_rgba(0),
+        }
+    }
+}
+
+/// How a displacement map filter handles pixels that are displaced from outside of the source.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DisplacementMapFilterMode {
+    Wrap,
+    Clamp,
+    Ignore,
+    Color,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DisplacementMapFilter {
+    pub map_bitmap: Option<BitmapHandle>,
+    pub map_point: (i32, i32),
+    /// The channel of the map used for the x displacement: 1 = red, 2 = green, 4 = blue, 8 = alpha.
+    pub component_x: u8,
+    /// The channel of the map used for the y displacement: 1 = red, 2 = green, 4 = blue, 8 = alpha.
+    pub component_y: u8,
+    pub scale_x: f32,
+    pub scale_y: f32,
+    pub mode: DisplacementMapFilterMode,
+    pub color: Color,
+}
+
+impl Default for DisplacementMapFilter {
+    fn default() -> Self {
+        Self {
+            map_bitmap: None,
+            map_point: (0, 0),
+            component_x: 0,
+            component_y: 0,
+            scale_x: 0.0,
+            scale_y: 0.0,
+            mode: DisplacementMapFilterMode::Wrap,
+            color: Color::from_rgba(0),
+        }
+    }
+}
+
+/// The number of pixels a blur extends past the edges of the source, in each direction.
+fn blur_extent(blur_x: f32, blur_y: f32, quality: u8) -> (f32, f32) {
+    let passes = f32::from(quality.max(1));
+    (
+        (blur_x.max(0.0) * 0.5 * passes).ceil(),
+        (blur_y.max(0.0) * 0.5 * passes).ceil(),
+    )
+}
+
+impl Filter {
+    /// Returns the area affected by this filter, when applied to an object covering `source`.
+    pub fn calculate_dest_rect(&self, source: &BoundingBox) -> BoundingBox {
+        if !source.valid {
+            return source.clone();
+        }
+
+        // (left, top, right, bottom), in pixels.
+        let expand = |blur_x, blur_y, quality, offset_x: f32, offset_y: f32, inner| {
+            if inner {
+                return (0.0, 0.0, 0.0, 0.0);
+            }
+            let (x, y) = blur_extent(blur_x, blur_y, quality);
+            (
+                x + (-offset_x).max(0.0),
+                y + (-offset_y).max(0.0),
+                x + offset_x.max(0.0),
+                y + offset_y.max(0.0),
+            )
+        };
+        let (left, top, right, bottom) = match self {
+            Filter::BlurFilter(filter) => expand(
+                filter.blur_x,
+                filter.blur_y,
+                filter.quality,
+                0.0,
+                0.0,
+                false,
+            ),
+            Filter::GlowFilter(filter) => expand(
+                filter.blur_x,
+                filter.blur_y,
+                filter.quality,
+                0.0,
+                0.0,
+                filter.inner,
+            ),
+            Filter::DropShadowFilter(filter) => expand(
+                filter.blur_x,
+                filter.blur_y,
+                filter.quality,
+                filter.angle.cos() * filter.distance,
+                filter.angle.sin() * filter.distance,
+                filter.inner,
+            ),
+            Filter::BevelFilter(filter) => {
+                // The highlight and shadow are offset in opposite directions.
+                let distance = filter.distance.abs();
+                expand(
+                    filter.blur_x + 2.0 * distance,
+                    filter.blur_y + 2.0 * distance,
+                    filter.quality,
+                    0.0,
+                    0.0,
+                    filter.bevel_type == BevelFilterType::Inner,
+                )
+            }
+            Filter::GradientGlowFilter(filter) | Filter::GradientBevelFilter(filter) => {
+                let distance = filter.distance.abs();
+                expand(
+                    filter.blur_x + 2.0 * distance,
+                    filter.blur_y + 2.0 * distance,
+                    filter.quality,
+                    0.0,
+                    0.0,
+                    filter.bevel_type == BevelFilterType::Inner,
+                )
+            }
+            Filter::ColorMatrixFilter(_)
+            | Filter::ConvolutionFilter(_)
+            | Filter::DisplacementMapFilter(_) => (0.0, 0.0, 0.0, 0.0),
+        };
+
+        BoundingBox {
+            x_min: source.x_min - Twips::from_pixels(left.into()),
+            y_min: source.y_min - Twips::from_pixels(top.into()),
+            x_max: source.x_max + Twips::from_pixels(right.into()),
+            y_max: source.y_max + Twips::from_pixels(bottom.into()),
+            valid: true,
+        }
+    }
+}
+
+impl From<&swf::Filter> for Filter {
+    fn from(filter: &swf::Filter) -> Self {
+        fn bevel_type(is_inner: bool, is_on_top: bool) -> BevelFilterType {
+            match (is_inner, is_on_top) {
+                (_, true) => BevelFilterType::Full,
+                (true, false) => BevelFilterType::Inner,
+                (false, false) => BevelFilterType::Outer,
+            }
+        }
+
+        fn gradient_filter(filter: &swf::GradientFilter) -> GradientFilter {
+            GradientFilter {
+                colors: filter
+                    .colors
+                    .iter()
+                    .map(|record| (record.ratio, record.color.clone()))
+                    .collect(),
+                blur_x: filter.blur_x.to_f32(),
+                blur_y: filter.blur_y.to_f32(),
+                angle: filter.angle.to_f32(),
+                distance: filter.distance.to_f32(),
+                strength: filter.strength.to_f32(),
+                quality: filter.num_passes,
+                bevel_type: bevel_type(filter.is_inner, filter.is_on_top),
+                knockout: filter.is_knockout,
+            }
+        }
+
+        match filter {
+            swf::Filter::BlurFilter(filter) => Filter::BlurFilter(BlurFilter {
+                blur_x: filter.blur_x.to_f32(),
+                blur_y: filter.blur_y.to_f32(),
+                quality: filter.num_passes,
+            }),
+            swf::Filter::GlowFilter(filter) => Filter::GlowFilter(GlowFilter {
+                color: filter.color.clone(),
+                blur_x: filter.blur_x.to_f32(),
+                blur_y: filter.blur_y.to_f32(),
+                strength: filter.strength.to_f32(),
+                quality: filter.num_passes,
+                inner: filter.is_inner,
+                knockout: filter.is_knockout,
+            }),
+            swf::Filter::DropShadowFilter(filter) => Filter::DropShadowFilter(DropShadowFilter {
+                color: filter.color.clone(),
+                blur_x: filter.blur_x.to_f32(),
+                blur_y: filter.blur_y.to_f32(),
+                angle: filter.angle.to_f32(),
+                distance: filter.distance.to_f32(),
+                strength: filter.strength.to_f32(),
+                quality: filter.num_passes,
+                inner: filter.is_inner,
+                knockout: filter.is_knockout,
+                hide_object: false,
+            }),
+            swf::Filter::BevelFilter(filter) => Filter::BevelFilter(BevelFilter {
+                shadow_color: filter.shadow_color.clone(),
+                highlight_color: filter.highlight_color.clone(),
+                blur_x: filter.blur_x.to_f32(),
+                blur_y: filter.blur_y.to_f32(),
+                angle: filter.angle.to_f32(),
+                distance: filter.distance.to_f32(),
+                strength: filter.strength.to_f32(),
+                quality: filter.num_passes,
+                bevel_type: bevel_type(filter.is_inner, filter.is_on_top),
+                knockout: filter.is_knockout,
+            }),
+            swf::Filter::GradientGlowFilter(filter) => {
+                Filter::GradientGlowFilter(gradient_filter(filter))
+            }
+            swf::Filter::GradientBevelFilter(filter) => {
+                Filter::GradientBevelFilter(gradient_filter(filter))
+            }
+            swf::Filter::ColorMatrixFilter(filter) => {
+                let mut matrix = [0.0; 20];
+                for (dst, src) in matrix.iter_mut().zip(filter.matrix.iter()) {
+                    *dst = src.to_f32();
+                }
+                Filter::ColorMatrixFilter(ColorMatrixFilter { matrix })
+            }
+            swf::Filter::ConvolutionFilter(filter) => {
+                Filter::ConvolutionFilter(ConvolutionFilter {
+                    matrix_x: filter.num_matrix_cols,
+                    matrix_y: filter.num_matrix_rows,
+                    matrix: filter.matrix.iter().map(|n| n.to_f32()).collect(),
+                    divisor: filter.divisor.to_f32(),
+                    bias: filter.bias.to_f32(),
+                    preserve_alpha: filter.is_preserve_alpha,
+                    clamp: filter.is_clamped,
+                    color: filter.default_color.clone(),
+                })
+            }
+        }
+    }
+}
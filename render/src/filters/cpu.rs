@@ -0,0 +1,492 @@
+//! Bitmap filters applied on the CPU.
+//!
+//! This is used by `BitmapData.applyFilter`, and by render backends that can't run filters on
+//! the GPU.
+
+use crate::filters::{
+    BevelFilterType, BlurFilter, ColorMatrixFilter, ConvolutionFilter, DisplacementMapFilter,
+    DisplacementMapFilterMode, Filter, GradientFilter,
+};
+
+/// A straight-alpha color with channels in `0.0..=1.0`.
+fn color_to_rgba(color: &swf::Color) -> [f32; 4] {
+    [
+        f32::from(color.r) / 255.0,
+        f32::from(color.g) / 255.0,
+        f32::from(color.b) / 255.0,
+        f32::from(color.a) / 255.0,
+    ]
+}
+
+fn premultiply([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    let a = a.clamp(0.0, 1.0);
+    [
+        r.clamp(0.0, 1.0) * a,
+        g.clamp(0.0, 1.0) * a,
+        b.clamp(0.0, 1.0) * a,
+        a,
+    ]
+}
+
+fn unmultiply([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    if a <= 0.0 {
+        [0.0; 4]
+    } else {
+        [r / a, g / a, b / a, a]
+    }
+}
+
+/// Returns the offset of a shadow or bevel, rounded to whole pixels.
+fn shadow_offset(angle: f32, distance: f32) -> (i32, i32) {
+    (
+        (angle.cos() * distance).round() as i32,
+        (angle.sin() * distance).round() as i32,
+    )
+}
+
+/// How the effect of a shadow-like filter is colored.
+enum ShadowEffect {
+    /// A single color, used by glows and drop shadows.
+    Color([f32; 4]),
+
+    /// A shadow and a highlight, offset in opposite directions.
+    Bevel {
+        shadow: [f32; 4],
+        highlight: [f32; 4],
+    },
+
+    /// A gradient sampled from the strength of the glow.
+    GradientGlow([[f32; 4]; 256]),
+
+    /// A gradient sampled from the difference between the shadow and the highlight.
+    GradientBevel([[f32; 4]; 256]),
+}
+
+/// The parameters shared by glow, drop shadow, bevel and gradient filters.
+struct Shadow {
+    effect: ShadowEffect,
+    offset: (i32, i32),
+    strength: f32,
+    /// Where the effect is drawn, relative to the source.
+    composite: BevelFilterType,
+    /// Whether the blurred alpha should be inverted, for inner glows and shadows.
+    invert: bool,
+    knockout: bool,
+    hide_object: bool,
+}
+
+/// A rectangle of premultiplied pixels with channels in `0.0..=1.0`,
+/// used as scratch space when applying filters on the CPU.
+#[derive(Clone)]
+pub struct FilterBuffer {
+    width: i32,
+    height: i32,
+    pixels: Vec<[f32; 4]>,
+}
+
+impl FilterBuffer {
+    pub fn new(width: i32, height: i32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0.0; 4]; width.max(0) as usize * height.max(0) as usize],
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= 0 && x < self.width && y >= 0 && y < self.height
+    }
+
+    /// Returns the pixel at the given coordinates, or transparent if they are out of bounds.
+    pub fn get(&self, x: i32, y: i32) -> [f32; 4] {
+        if self.contains(x, y) {
+            self.pixels[(x + y * self.width) as usize]
+        } else {
+            [0.0; 4]
+        }
+    }
+
+    fn get_clamped(&self, x: i32, y: i32) -> [f32; 4] {
+        self.get(x.clamp(0, self.width - 1), y.clamp(0, self.height - 1))
+    }
+
+    pub fn set(&mut self, x: i32, y: i32, color: [f32; 4]) {
+        self.pixels[(x + y * self.width) as usize] = color;
+    }
+
+    /// Applies `filter` to this buffer. Pixels outside of the buffer are treated as transparent.
+    ///
+    /// The map of a displacement map filter is read from `map`, rather than from the filter's
+    /// bitmap; without one, the buffer is left unchanged.
+    pub fn apply(self, filter: &Filter, map: Option<&FilterBuffer>) -> Self {
+        match filter {
+            Filter::BlurFilter(filter) => self.blur(filter),
+            Filter::GlowFilter(filter) => {
+                let blur = BlurFilter {
+                    blur_x: filter.blur_x,
+                    blur_y: filter.blur_y,
+                    quality: filter.quality,
+                };
+                self.shadow(
+                    &blur,
+                    &Shadow {
+                        effect: ShadowEffect::Color(color_to_rgba(&filter.color)),
+                        offset: (0, 0),
+                        strength: filter.strength,
+                        composite: if filter.inner {
+                            BevelFilterType::Inner
+                        } else {
+                            BevelFilterType::Outer
+                        },
+                        invert: filter.inner,
+                        knockout: filter.knockout,
+                        hide_object: false,
+                    },
+                )
+            }
+            Filter::DropShadowFilter(filter) => {
+                let blur = BlurFilter {
+                    blur_x: filter.blur_x,
+                    blur_y: filter.blur_y,
+                    quality: filter.quality,
+                };
+                self.shadow(
+                    &blur,
+                    &Shadow {
+                        effect: ShadowEffect::Color(color_to_rgba(&filter.color)),
+                        offset: shadow_offset(filter.angle, filter.distance),
+                        strength: filter.strength,
+                        composite: if filter.inner {
+                            BevelFilterType::Inner
+                        } else {
+                            BevelFilterType::Outer
+                        },
+                        invert: filter.inner,
+                        knockout: filter.knockout,
+                        hide_object: filter.hide_object,
+                    },
+                )
+            }
+            Filter::BevelFilter(filter) => {
+                let blur = BlurFilter {
+                    blur_x: filter.blur_x,
+                    blur_y: filter.blur_y,
+                    quality: filter.quality,
+                };
+                self.shadow(
+                    &blur,
+                    &Shadow {
+                        effect: ShadowEffect::Bevel {
+                            shadow: color_to_rgba(&filter.shadow_color),
+                            highlight: color_to_rgba(&filter.highlight_color),
+                        },
+                        offset: shadow_offset(filter.angle, filter.distance),
+                        strength: filter.strength,
+                        composite: filter.bevel_type,
+                        invert: false,
+                        knockout: filter.knockout,
+                        hide_object: false,
+                    },
+                )
+            }
+            Filter::GradientGlowFilter(filter) => self.gradient_shadow(filter, false),
+            Filter::GradientBevelFilter(filter) => self.gradient_shadow(filter, true),
+            Filter::ColorMatrixFilter(filter) => self.color_matrix(filter),
+            Filter::ConvolutionFilter(filter) => self.convolution(filter),
+            Filter::DisplacementMapFilter(filter) => match map {
+                Some(map) => self.displacement_map(filter, map),
+                None => self,
+            },
+        }
+    }
+
+    /// Applies a single pass of a box blur of the given (possibly fractional) size.
+    fn box_blur(&self, size: f32, horizontal: bool) -> Self {
+        let (length, lines) = if horizontal {
+            (self.width, self.height)
+        } else {
+            (self.height, self.width)
+        };
+        let half_size = (size - 1.0) / 2.0;
+        let whole = half_size.floor() as i32;
+        let fract = half_size.fract();
+
+        let mut result = Self::new(self.width, self.height);
+        let mut sums = vec![[0.0f32; 4]; length as usize + 1];
+        for line in 0..lines {
+            let coords = |i: i32| if horizontal { (i, line) } else { (line, i) };
+            let get = |i: i32| {
+                let (x, y) = coords(i);
+                self.get(x, y)
+            };
+
+            // Prefix sums of the line, so that each box can be summed in constant time.
+            for i in 0..length {
+                let color = get(i);
+                let prev = sums[i as usize];
+                sums[i as usize + 1] = std::array::from_fn(|c| prev[c] + color[c]);
+            }
+
+            for i in 0..length {
+                let start = (i - whole).clamp(0, length) as usize;
+                let end = (i + whole + 1).clamp(0, length) as usize;
+                let before = get(i - whole - 1);
+                let after = get(i + whole + 1);
+                let total = std::array::from_fn(|c| {
+                    (sums[end][c] - sums[start][c] + (before[c] + after[c]) * fract) / size
+                });
+                let (x, y) = coords(i);
+                result.set(x, y, total);
+            }
+        }
+        result
+    }
+
+    fn blur(self, filter: &BlurFilter) -> Self {
+        let mut result = self;
+        for _ in 0..filter.quality {
+            for (size, horizontal) in [(filter.blur_x, true), (filter.blur_y, false)] {
+                // A box of a single pixel (or less) leaves the source unchanged.
+                if size > 1.0 {
+                    result = result.box_blur(size, horizontal);
+                }
+            }
+        }
+        result
+    }
+
+    /// Combines this buffer with a colored version of its blurred alpha channel.
+    fn shadow(self, blur: &BlurFilter, shadow: &Shadow) -> Self {
+        let blurred = self.clone().blur(blur);
+        let blurred_alpha = |x: i32, y: i32| blurred.get(x, y)[3];
+        let (offset_x, offset_y) = shadow.offset;
+        let sample_gradient = |gradient: &[[f32; 4]; 256], ratio: f32| {
+            premultiply(gradient[(ratio.clamp(0.0, 1.0) * 255.0).round() as usize])
+        };
+
+        let mut result = Self::new(self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let src = self.get(x, y);
+                let effect = match &shadow.effect {
+                    ShadowEffect::Color(_) | ShadowEffect::GradientGlow(_) => {
+                        let mut alpha = blurred_alpha(x - offset_x, y - offset_y);
+                        if shadow.invert {
+                            alpha = 1.0 - alpha;
+                        }
+                        let alpha = (alpha * shadow.strength).clamp(0.0, 1.0);
+                        match &shadow.effect {
+                            ShadowEffect::Color(color) => {
+                                let a = alpha * color[3];
+                                [color[0] * a, color[1] * a, color[2] * a, a]
+                            }
+                            ShadowEffect::GradientGlow(gradient) => {
+                                sample_gradient(gradient, alpha)
+                            }
+                            _ => unreachable!(),
+                        }
+                    }
+                    ShadowEffect::Bevel { .. } | ShadowEffect::GradientBevel(_) => {
+                        // Positive values are in shadow, negative values are highlighted.
+                        let value = ((blurred_alpha(x - offset_x, y - offset_y)
+                            - blurred_alpha(x + offset_x, y + offset_y))
+                            * shadow.strength)
+                            .clamp(-1.0, 1.0);
+                        match &shadow.effect {
+                            ShadowEffect::Bevel {
+                                shadow: shadow_color,
+                                highlight,
+                            } => {
+                                let s = value.max(0.0) * shadow_color[3];
+                                let h = (-value).max(0.0) * highlight[3];
+                                [
+                                    shadow_color[0] * s + highlight[0] * h,
+                                    shadow_color[1] * s + highlight[1] * h,
+                                    shadow_color[2] * s + highlight[2] * h,
+                                    s + h,
+                                ]
+                            }
+                            ShadowEffect::GradientBevel(gradient) => {
+                                sample_gradient(gradient, value * 0.5 + 0.5)
+                            }
+                            _ => unreachable!(),
+                        }
+                    }
+                };
+
+                let mix = |effect_weight: f32, src_weight: f32| {
+                    std::array::from_fn(|c| effect[c] * effect_weight + src[c] * src_weight)
+                };
+                let hidden = shadow.knockout || shadow.hide_object;
+                let color = match shadow.composite {
+                    // Drawn on top of the source, within its shape.
+                    BevelFilterType::Inner if hidden => mix(src[3], 0.0),
+                    BevelFilterType::Inner => mix(src[3], 1.0 - effect[3]),
+                    // Drawn behind the source, outside of its shape.
+                    BevelFilterType::Outer if shadow.knockout => mix(1.0 - src[3], 0.0),
+                    BevelFilterType::Outer if shadow.hide_object => effect,
+                    BevelFilterType::Outer => mix(1.0 - src[3], 1.0),
+                    // Drawn on top of the source, everywhere.
+                    BevelFilterType::Full if hidden => effect,
+                    BevelFilterType::Full => mix(1.0, 1.0 - effect[3]),
+                };
+                result.set(x, y, color);
+            }
+        }
+        result
+    }
+
+    fn gradient_shadow(self, filter: &GradientFilter, is_bevel: bool) -> Self {
+        let blur = BlurFilter {
+            blur_x: filter.blur_x,
+            blur_y: filter.blur_y,
+            quality: filter.quality,
+        };
+        let mut gradient = [[0.0; 4]; 256];
+        for (i, color) in gradient.iter_mut().enumerate() {
+            *color = filter.sample(i as f32 / 255.0);
+        }
+        self.shadow(
+            &blur,
+            &Shadow {
+                effect: if is_bevel {
+                    ShadowEffect::GradientBevel(gradient)
+                } else {
+                    ShadowEffect::GradientGlow(gradient)
+                },
+                offset: shadow_offset(filter.angle, filter.distance),
+                strength: filter.strength,
+                composite: filter.bevel_type,
+                invert: !is_bevel && filter.bevel_type == BevelFilterType::Inner,
+                knockout: filter.knockout,
+                hide_object: false,
+            },
+        )
+    }
+
+    fn color_matrix(mut self, filter: &ColorMatrixFilter) -> Self {
+        let m = &filter.matrix;
+        for pixel in &mut self.pixels {
+            let color = unmultiply(*pixel);
+            let mut result = [0.0; 4];
+            for (c, row) in m.chunks_exact(5).enumerate() {
+                result[c] = row[0] * color[0]
+                    + row[1] * color[1]
+                    + row[2] * color[2]
+                    + row[3] * color[3]
+                    + row[4] / 255.0;
+            }
+            *pixel = premultiply(result);
+        }
+        self
+    }
+
+    fn convolution(self, filter: &ConvolutionFilter) -> Self {
+        let matrix_x = i32::from(filter.matrix_x);
+        let matrix_y = i32::from(filter.matrix_y);
+        if matrix_x == 0 || matrix_y == 0 || filter.matrix.len() < (matrix_x * matrix_y) as usize {
+            return self;
+        }
+        let divisor = if filter.divisor == 0.0 {
+            1.0
+        } else {
+            filter.divisor
+        };
+        let bias = filter.bias / 255.0;
+        let default_color = color_to_rgba(&filter.color);
+        let load = |x: i32, y: i32| {
+            if filter.clamp {
+                unmultiply(self.get_clamped(x, y))
+            } else if self.contains(x, y) {
+                unmultiply(self.get(x, y))
+            } else {
+                default_color
+            }
+        };
+
+        let mut result = Self::new(self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let mut total = [0.0; 4];
+                for j in 0..matrix_y {
+                    for i in 0..matrix_x {
+                        let weight = filter.matrix[(j * matrix_x + i) as usize];
+                        let color = load(x + i - matrix_x / 2, y + j - matrix_y / 2);
+                        for (total, value) in total.iter_mut().zip(color) {
+                            *total += value * weight;
+                        }
+                    }
+                }
+                for value in &mut total {
+                    *value = *value / divisor + bias;
+                }
+                if filter.preserve_alpha {
+                    total[3] = self.get(x, y)[3];
+                }
+                result.set(x, y, premultiply(total));
+            }
+        }
+        result
+    }
+
+    fn displacement_map(self, filter: &DisplacementMapFilter, map: &FilterBuffer) -> Self {
+        let displacement = |component: u8, color: [f32; 4]| {
+            let value = match component {
+                1 => color[0],
+                2 => color[1],
+                4 => color[2],
+                8 => color[3],
+                _ => return 0.0,
+            };
+            ((value * 255.0).round() - 128.0) / 256.0
+        };
+        let (map_x, map_y) = filter.map_point;
+
+        let mut result = Self::new(self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !map.contains(x - map_x, y - map_y) {
+                    result.set(x, y, self.get(x, y));
+                    continue;
+                }
+                let map_color = unmultiply(map.get(x - map_x, y - map_y));
+                let offset_x = displacement(filter.component_x, map_color) * filter.scale_x;
+                let offset_y = displacement(filter.component_y, map_color) * filter.scale_y;
+                let mut displaced_x = x + offset_x.floor() as i32;
+                let mut displaced_y = y + offset_y.floor() as i32;
+                if !self.contains(displaced_x, displaced_y) {
+                    match filter.mode {
+                        DisplacementMapFilterMode::Wrap => {
+                            displaced_x = displaced_x.rem_euclid(self.width);
+                            displaced_y = displaced_y.rem_euclid(self.height);
+                        }
+                        DisplacementMapFilterMode::Clamp => {
+                            displaced_x = displaced_x.clamp(0, self.width - 1);
+                            displaced_y = displaced_y.clamp(0, self.height - 1);
+                        }
+                        DisplacementMapFilterMode::Ignore => {
+                            displaced_x = x;
+                            displaced_y = y;
+                        }
+                        DisplacementMapFilterMode::Color => {
+                            result.set(x, y, premultiply(color_to_rgba(&filter.color)));
+                            continue;
+                        }
+                    }
+                }
+                result.set(x, y, self.get(displaced_x, displaced_y));
+            }
+        }
+        result
+    }
+}
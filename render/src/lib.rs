@@ -1,8 +1,10 @@
+pub mod atf;
 pub mod backend;
 pub mod bitmap;
 pub mod bounding_box;
 pub mod color_transform;
 pub mod error;
+pub mod filters;
 pub mod matrix;
 pub mod shape_utils;
 pub mod transform;
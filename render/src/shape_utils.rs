@@ -59,6 +59,17 @@ pub fn calculate_shape_bounds(shape_records: &[swf::ShapeRecord]) -> swf::Rectan
     bounds
 }
 
+/// The rule used to determine which areas enclosed by a fill's path are filled.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FillRule {
+    /// Areas enclosed an odd number of times are filled. This is always used by SWF shapes.
+    EvenOdd,
+
+    /// Areas with a non-zero winding number are filled. This can be chosen with
+    /// `Graphics.drawPath` in AVM2.
+    NonZero,
+}
+
 /// `DrawPath` represents a solid fill or a stroke.
 /// Fills are always closed paths, while strokes may be open or closed.
 /// Closed paths will have the first point equal to the last point.
@@ -72,6 +83,7 @@ pub enum DrawPath<'a> {
     Fill {
         style: &'a FillStyle,
         commands: Vec<DrawCommand>,
+        winding_rule: FillRule,
     },
 }
 
@@ -513,6 +525,7 @@ impl<'a> ShapeConverter<'a> {
             self.commands.push(DrawPath::Fill {
                 style,
                 commands: path.to_draw_commands().collect(),
+                winding_rule: FillRule::EvenOdd,
             });
             path.segments.clear();
         }
@@ -599,6 +612,7 @@ mod tests {
         let commands = ShapeConverter::from_shape(&shape).into_commands();
         let expected = vec![DrawPath::Fill {
             style: &FILL_STYLES[0],
+            winding_rule: FillRule::EvenOdd,
             commands: vec![
                 DrawCommand::MoveTo {
                     x: Twips::from_pixels(100.0),
@@ -663,6 +677,7 @@ mod tests {
         let commands = ShapeConverter::from_shape(&shape).into_commands();
         let expected = vec![DrawPath::Fill {
             style: &FILL_STYLES[0],
+            winding_rule: FillRule::EvenOdd,
             commands: vec![
                 DrawCommand::MoveTo {
                     x: Twips::from_pixels(100.0),
@@ -829,7 +844,11 @@ pub fn shape_hit_test(
 }
 
 /// Test whether the given point is contained within the paths specified by the draw commands.
-pub fn draw_command_fill_hit_test(commands: &[DrawCommand], test_point: (Twips, Twips)) -> bool {
+pub fn draw_command_fill_hit_test(
+    commands: &[DrawCommand],
+    winding_rule: FillRule,
+    test_point: (Twips, Twips),
+) -> bool {
     let mut cursor = (Twips::ZERO, Twips::ZERO);
     let mut fill_start = (Twips::ZERO, Twips::ZERO);
     let mut winding = 0;
@@ -856,7 +875,10 @@ pub fn draw_command_fill_hit_test(commands: &[DrawCommand], test_point: (Twips,
         winding += winding_number_line(test_point, cursor, fill_start);
     }
 
-    winding & 0b1 != 0
+    match winding_rule {
+        FillRule::EvenOdd => winding & 0b1 != 0,
+        FillRule::NonZero => winding != 0,
+    }
 }
 
 /// Test whether the given point is contained within the strokes specified by the draw commands.
@@ -0,0 +1,81 @@
+/// Shader used for compositing a layer with a blend mode that needs to read the content below it.
+/// Matches the blend modes of the software renderer.
+
+struct BlendUniforms {
+    /// The `ComplexBlend` to apply.
+    mode: i32,
+    _padding1: i32,
+    _padding2: i32,
+    _padding3: i32,
+};
+
+@group(2) @binding(0) var<uniform> blend_params: BlendUniforms;
+
+/// The layer being blended. Colors are premultiplied by alpha.
+@group(2) @binding(1) var source: texture_2d<f32>;
+
+/// A copy of the render target before the layer was drawn. Colors are premultiplied by alpha.
+@group(2) @binding(2) var destination: texture_2d<f32>;
+
+@vertex
+fn main_vertex(in: VertexInput) -> @builtin(position) vec4<f32> {
+    return globals.view_matrix * transforms.world_matrix * vec4<f32>(in.position.x, in.position.y, 0.0, 1.0);
+}
+
+/// The premultiplied hard light term, where `a` is the "light" color.
+fn hard_light(a: vec3<f32>, aa: f32, b: vec3<f32>, ba: f32) -> vec3<f32> {
+    let low = 2.0 * a * b;
+    let high = aa * ba - 2.0 * (ba - b) * (aa - a);
+    return select(high, low, 2.0 * a <= vec3<f32>(aa));
+}
+
+@fragment
+fn main_fragment(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let coords = vec2<i32>(floor(position.xy));
+    let src = textureLoad(source, coords, 0);
+    if (src.a <= 0.0) {
+        // Every blend mode leaves the destination unchanged here.
+        discard;
+    }
+    let dst = textureLoad(destination, coords, 0);
+    let sa = src.a;
+    let da = dst.a;
+    let alpha_over = sa + da - sa * da;
+    let s = src.rgb;
+    let d = dst.rgb;
+    // The parts of each color that aren't covered by the other.
+    let uncovered = s * (1.0 - da) + d * (1.0 - sa);
+
+    var result: vec4<f32>;
+    switch (blend_params.mode) {
+        // Multiply
+        case 0: {
+            result = vec4<f32>(uncovered + s * d, alpha_over);
+        }
+        // Lighten
+        case 1: {
+            result = vec4<f32>(uncovered + max(s * da, d * sa), alpha_over);
+        }
+        // Darken
+        case 2: {
+            result = vec4<f32>(uncovered + min(s * da, d * sa), alpha_over);
+        }
+        // Difference
+        case 3: {
+            result = vec4<f32>(s + d - 2.0 * min(s * da, d * sa), alpha_over);
+        }
+        // Invert
+        case 4: {
+            result = vec4<f32>((da - d) * sa + d * (1.0 - sa), da);
+        }
+        // Overlay
+        case 5: {
+            result = vec4<f32>(uncovered + hard_light(d, da, s, sa), alpha_over);
+        }
+        // HardLight
+        default: {
+            result = vec4<f32>(uncovered + hard_light(s, sa, d, da), alpha_over);
+        }
+    }
+    return clamp(result, vec4<f32>(0.0, 0.0, 0.0, 0.0), vec4<f32>(1.0, 1.0, 1.0, 1.0));
+}
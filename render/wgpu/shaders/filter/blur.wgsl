@@ -0,0 +1,33 @@
+/// Shader used for a single pass of a box blur, in one direction.
+
+struct BlurUniforms {
+    /// The direction of this pass: (1, 0) for horizontal, or (0, 1) for vertical.
+    direction: vec2<f32>,
+
+    /// The width of the box, in pixels. This may be fractional.
+    size: f32,
+
+    _padding: f32,
+};
+
+@group(0) @binding(0) var<uniform> blur: BlurUniforms;
+
+@fragment
+fn main_fragment(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let coords = texel_coords(position);
+    let direction = vec2<i32>(blur.direction);
+    let half_size = (blur.size - 1.0) / 2.0;
+    let whole = i32(floor(half_size));
+
+    var total = load_source(coords);
+    for (var i = 1; i <= whole; i = i + 1) {
+        total = total + load_source(coords + direction * i);
+        total = total + load_source(coords - direction * i);
+    }
+
+    // The edges of a fractional box only partially cover their texels.
+    let edge = direction * (whole + 1);
+    total = total + (load_source(coords + edge) + load_source(coords - edge)) * fract(half_size);
+
+    return total / blur.size;
+}
@@ -0,0 +1,26 @@
+/// Shader used for applying a color matrix filter.
+
+struct ColorMatrixUniforms {
+    /// The rows of the color matrix, without the offsets.
+    r: vec4<f32>,
+    g: vec4<f32>,
+    b: vec4<f32>,
+    a: vec4<f32>,
+
+    /// The last column of the color matrix, normalized to [0.0, 1.0].
+    offset: vec4<f32>,
+};
+
+@group(0) @binding(0) var<uniform> filter_params: ColorMatrixUniforms;
+
+@fragment
+fn main_fragment(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let color = unmultiply_alpha(load_source(texel_coords(position)));
+    let result = vec4<f32>(
+        dot(filter_params.r, color),
+        dot(filter_params.g, color),
+        dot(filter_params.b, color),
+        dot(filter_params.a, color),
+    ) + filter_params.offset;
+    return premultiply_alpha(result);
+}
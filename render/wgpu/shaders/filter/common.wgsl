@@ -0,0 +1,46 @@
+/// Common WGSL shared among all filter shaders.
+/// Ruffle prepends this file onto every filter shader at runtime.
+/// Filters don't use `common.wgsl`, as they don't need the global or per-object transforms.
+
+/// The vertex format shared among all shaders (only the position is used).
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+/// The texture being filtered. Colors are premultiplied by alpha.
+@group(0) @binding(1) var source: texture_2d<f32>;
+
+/// Stretches the unit quad to cover the entire render target.
+@vertex
+fn main_vertex(in: VertexInput) -> @builtin(position) vec4<f32> {
+    return vec4<f32>(in.position.x * 2.0 - 1.0, 1.0 - in.position.y * 2.0, 0.0, 1.0);
+}
+
+/// Returns the texel coordinates of the given fragment position.
+fn texel_coords(position: vec4<f32>) -> vec2<i32> {
+    return vec2<i32>(floor(position.xy));
+}
+
+/// Loads a texel from the source, or transparent black if it's out of bounds.
+fn load_source(coords: vec2<i32>) -> vec4<f32> {
+    let size = vec2<i32>(textureDimensions(source));
+    if (any(coords < vec2<i32>(0, 0)) || any(coords >= size)) {
+        return vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    }
+    return textureLoad(source, coords, 0);
+}
+
+/// Converts a color from premultiplied to straight alpha.
+fn unmultiply_alpha(color: vec4<f32>) -> vec4<f32> {
+    if (color.a > 0.0) {
+        return vec4<f32>(color.rgb / color.a, color.a);
+    }
+    return vec4<f32>(0.0, 0.0, 0.0, 0.0);
+}
+
+/// Converts a color from straight to premultiplied alpha, clamping it to the valid range.
+fn premultiply_alpha(color: vec4<f32>) -> vec4<f32> {
+    let clamped = clamp(color, vec4<f32>(0.0, 0.0, 0.0, 0.0), vec4<f32>(1.0, 1.0, 1.0, 1.0));
+    return vec4<f32>(clamped.rgb * clamped.a, clamped.a);
+}
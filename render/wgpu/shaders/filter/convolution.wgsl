@@ -0,0 +1,61 @@
+/// Shader used for applying a convolution filter.
+
+struct ConvolutionUniforms {
+    /// The kernel, in row-major order, packed four values at a time.
+    matrix_: array<vec4<f32>, 64>,
+
+    /// The color (with straight alpha) used for texels outside of the source when not clamping.
+    default_color: vec4<f32>,
+
+    /// The amount added to each channel, normalized to [0.0, 1.0].
+    bias: f32,
+
+    divisor: f32,
+    matrix_x: f32,
+    matrix_y: f32,
+
+    /// 1.0 if the alpha channel should be left untouched.
+    preserve_alpha: f32,
+
+    /// 1.0 if texels outside of the source should be clamped to its edges.
+    clamp: f32,
+
+    _padding1: f32,
+    _padding2: f32,
+};
+
+@group(0) @binding(0) var<uniform> filter_params: ConvolutionUniforms;
+
+fn load_clamped(coords: vec2<i32>) -> vec4<f32> {
+    let size = vec2<i32>(textureDimensions(source));
+    if (filter_params.clamp > 0.5) {
+        return unmultiply_alpha(textureLoad(source, clamp(coords, vec2<i32>(0, 0), size - vec2<i32>(1, 1)), 0));
+    }
+    if (any(coords < vec2<i32>(0, 0)) || any(coords >= size)) {
+        return filter_params.default_color;
+    }
+    return unmultiply_alpha(textureLoad(source, coords, 0));
+}
+
+@fragment
+fn main_fragment(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let coords = texel_coords(position);
+    let matrix_x = i32(filter_params.matrix_x);
+    let matrix_y = i32(filter_params.matrix_y);
+    let center = vec2<i32>(matrix_x / 2, matrix_y / 2);
+
+    var total = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    for (var y = 0; y < matrix_y; y = y + 1) {
+        for (var x = 0; x < matrix_x; x = x + 1) {
+            let index = y * matrix_x + x;
+            let weight = filter_params.matrix_[index / 4][index % 4];
+            total = total + load_clamped(coords + vec2<i32>(x, y) - center) * weight;
+        }
+    }
+
+    var result = total / filter_params.divisor + vec4<f32>(filter_params.bias);
+    if (filter_params.preserve_alpha > 0.5) {
+        result.a = unmultiply_alpha(load_source(coords)).a;
+    }
+    return premultiply_alpha(result);
+}
@@ -0,0 +1,67 @@
+/// Shader used for applying a displacement map filter.
+
+struct DisplacementMapUniforms {
+    /// The color (with straight alpha) used for out of bounds texels in "color" mode.
+    color: vec4<f32>,
+
+    /// The offset of the map from the top-left of the source, in pixels.
+    map_point: vec2<f32>,
+
+    /// The scale applied to the displacement along each axis.
+    scale: vec2<f32>,
+
+    /// The channel (0 = red, 1 = green, 2 = blue, 3 = alpha) used for the x displacement,
+    /// or -1.0 if there is no displacement along the x axis.
+    component_x: f32,
+
+    /// The channel used for the y displacement, using the same values as `component_x`.
+    component_y: f32,
+
+    /// 0 = wrap, 1 = clamp, 2 = ignore, 3 = color.
+    mode: f32,
+
+    _padding: f32,
+};
+
+@group(0) @binding(0) var<uniform> filter_params: DisplacementMapUniforms;
+@group(0) @binding(2) var map: texture_2d<f32>;
+
+/// Returns the displacement for the given channel of the map, in the range [-0.5, 0.5).
+fn displacement(component: f32, map_color: vec4<f32>) -> f32 {
+    if (component < 0.0) {
+        return 0.0;
+    }
+    return (round(map_color[i32(component)] * 255.0) - 128.0) / 256.0;
+}
+
+@fragment
+fn main_fragment(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let coords = texel_coords(position);
+    let size = vec2<i32>(textureDimensions(source));
+
+    let map_coords = coords - vec2<i32>(filter_params.map_point);
+    let map_size = vec2<i32>(textureDimensions(map));
+    if (any(map_coords < vec2<i32>(0, 0)) || any(map_coords >= map_size)) {
+        return load_source(coords);
+    }
+    let map_color = unmultiply_alpha(textureLoad(map, map_coords, 0));
+    let offset = vec2<f32>(
+        displacement(filter_params.component_x, map_color),
+        displacement(filter_params.component_y, map_color),
+    ) * filter_params.scale;
+
+    var displaced = coords + vec2<i32>(floor(offset));
+    if (any(displaced < vec2<i32>(0, 0)) || any(displaced >= size)) {
+        let mode = i32(filter_params.mode);
+        if (mode == 0) {
+            displaced = ((displaced % size) + size) % size;
+        } else if (mode == 1) {
+            displaced = clamp(displaced, vec2<i32>(0, 0), size - vec2<i32>(1, 1));
+        } else if (mode == 2) {
+            displaced = coords;
+        } else {
+            return premultiply_alpha(filter_params.color);
+        }
+    }
+    return textureLoad(source, displaced, 0);
+}
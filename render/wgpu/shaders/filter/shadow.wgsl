@@ -0,0 +1,115 @@
+/// Shader used for the final pass of the glow, drop shadow, bevel and gradient filters.
+/// These combine the source with a colored version of its blurred alpha channel.
+
+struct ShadowUniforms {
+    /// The color (with straight alpha) of the effect. For bevels, this is the shadow color.
+    color: vec4<f32>,
+
+    /// The color (with straight alpha) of the highlight of a bevel.
+    highlight_color: vec4<f32>,
+
+    /// The offset of the shadow from the source, in pixels.
+    offset: vec2<f32>,
+
+    strength: f32,
+
+    /// 0 = glow or drop shadow, 1 = bevel, 2 = gradient glow, 3 = gradient bevel.
+    mode: f32,
+
+    /// Where the effect is drawn: 0 = inner, 1 = outer, 2 = full.
+    composite: f32,
+
+    /// 1.0 if the blurred alpha should be inverted (for inner glows and shadows).
+    invert: f32,
+
+    /// 1.0 if the source should be removed from the result.
+    knockout: f32,
+
+    /// 1.0 if only the effect should be drawn (for drop shadows).
+    hide_object: f32,
+
+    /// The gradient of gradient filters, sampled at each ratio, with straight alpha.
+    gradient: array<vec4<f32>, 256>,
+};
+
+@group(0) @binding(0) var<uniform> filter_params: ShadowUniforms;
+@group(0) @binding(2) var blurred: texture_2d<f32>;
+
+fn load_blurred(coords: vec2<i32>) -> f32 {
+    let size = vec2<i32>(textureDimensions(blurred));
+    if (any(coords < vec2<i32>(0, 0)) || any(coords >= size)) {
+        return 0.0;
+    }
+    return textureLoad(blurred, coords, 0).a;
+}
+
+fn sample_gradient(ratio: f32) -> vec4<f32> {
+    let color = filter_params.gradient[i32(round(clamp(ratio, 0.0, 1.0) * 255.0))];
+    return vec4<f32>(color.rgb * color.a, color.a);
+}
+
+@fragment
+fn main_fragment(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let coords = texel_coords(position);
+    let offset = vec2<i32>(round(filter_params.offset));
+    let src = load_source(coords);
+    let mode = i32(filter_params.mode);
+
+    // The effect, with premultiplied alpha.
+    var effect: vec4<f32>;
+    if (mode == 0 || mode == 2) {
+        var alpha = load_blurred(coords - offset);
+        if (filter_params.invert > 0.5) {
+            alpha = 1.0 - alpha;
+        }
+        alpha = clamp(alpha * filter_params.strength, 0.0, 1.0);
+        if (mode == 0) {
+            let a = alpha * filter_params.color.a;
+            effect = vec4<f32>(filter_params.color.rgb * a, a);
+        } else {
+            effect = sample_gradient(alpha);
+        }
+    } else {
+        // Positive values are in shadow, negative values are highlighted.
+        let value = clamp(
+            (load_blurred(coords - offset) - load_blurred(coords + offset)) * filter_params.strength,
+            -1.0,
+            1.0,
+        );
+        if (mode == 1) {
+            let shadow = max(value, 0.0) * filter_params.color.a;
+            let highlight = max(-value, 0.0) * filter_params.highlight_color.a;
+            effect = vec4<f32>(
+                filter_params.color.rgb * shadow + filter_params.highlight_color.rgb * highlight,
+                shadow + highlight,
+            );
+        } else {
+            effect = sample_gradient(value * 0.5 + 0.5);
+        }
+    }
+
+    let knockout = filter_params.knockout > 0.5;
+    let hide_object = filter_params.hide_object > 0.5;
+    let composite = i32(filter_params.composite);
+    if (composite == 0) {
+        // Drawn on top of the source, within its shape.
+        if (knockout || hide_object) {
+            return effect * src.a;
+        }
+        return effect * src.a + src * (1.0 - effect.a);
+    } else if (composite == 1) {
+        // Drawn behind the source, outside of its shape.
+        if (knockout) {
+            return effect * (1.0 - src.a);
+        }
+        if (hide_object) {
+            return effect;
+        }
+        return src + effect * (1.0 - src.a);
+    }
+    // Drawn on top of the source, everywhere.
+    if (knockout || hide_object) {
+        return effect;
+    }
+    return effect + src * (1.0 - effect.a);
+}
@@ -1,4 +1,7 @@
+use crate::blend::{create_blend_bind_group, BlendType, ComplexBlend};
+use crate::commands::{Layer, LayerCommand, LayerCommands};
 use crate::context3d::WgpuContext3D;
+use crate::filters::{FilterTexture, FILTER_TEXTURE_FORMAT};
 use crate::mesh::{Draw, Mesh};
 use crate::surface::Surface;
 use crate::target::RenderTargetFrame;
@@ -13,14 +16,16 @@ use raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle};
 use ruffle_render::backend::{Context3D, Context3DCommand};
 use ruffle_render::backend::{RenderBackend, ShapeHandle, ViewportDimensions};
 use ruffle_render::bitmap::{Bitmap, BitmapHandle, BitmapSource};
-use ruffle_render::commands::CommandList;
+use ruffle_render::bounding_box::BoundingBox;
+use ruffle_render::commands::{CommandHandler, CommandList};
 use ruffle_render::error::Error as BitmapError;
+use ruffle_render::filters::Filter;
 use ruffle_render::shape_utils::DistilledShape;
 use ruffle_render::tessellator::ShapeTessellator;
 use std::num::NonZeroU32;
 use std::path::Path;
 use std::sync::Arc;
-use swf::Color;
+use swf::{BlendMode, Color};
 use wgpu::Extent3d;
 
 const DEFAULT_SAMPLE_COUNT: u32 = 4;
@@ -204,6 +209,258 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
         Mesh { draws }
     }
 
+    /// Renders every filtered or blended object in `commands` into its own texture,
+    /// and applies its filters.
+    ///
+    /// The returned layers are in the order that the objects appear in `commands`,
+    /// and are drawn in their place by `CommandRenderer`.
+    fn render_layers(&mut self, commands: &CommandList) -> Vec<Option<Layer>> {
+        let mut layers = LayerCommands::default();
+        commands.execute(&mut layers);
+        layers
+            .layers
+            .into_iter()
+            .map(|layer| match layer {
+                LayerCommand::Filtered {
+                    commands,
+                    filters,
+                    bounds,
+                } => self.render_filter_layer(commands, filters, &bounds),
+                LayerCommand::Blend {
+                    commands,
+                    blend_mode,
+                } => Some(self.render_blend_layer(commands, blend_mode)),
+            })
+            .collect()
+    }
+
+    fn render_filter_layer(
+        &mut self,
+        commands: &CommandList,
+        filters: &[Filter],
+        bounds: &BoundingBox,
+    ) -> Option<Layer> {
+        if !bounds.valid {
+            return None;
+        }
+        let x = bounds.x_min.to_pixels().floor() as i32;
+        let y = bounds.y_min.to_pixels().floor() as i32;
+        let max_size = self.descriptors.limits.max_texture_dimension_2d as i32;
+        let width = (bounds.x_max.to_pixels().ceil() as i32 - x).min(max_size);
+        let height = (bounds.y_max.to_pixels().ceil() as i32 - y).min(max_size);
+        if width <= 0 || height <= 0 {
+            return None;
+        }
+        let (width, height) = (width as u32, height as u32);
+
+        let (old_width, old_height) = self.globals.resolution();
+        let (old_x, old_y) = self.globals.origin();
+        self.globals.set_resolution(width, height);
+        self.globals.set_origin(x, y);
+
+        // Any layers inside of this one must be ready before we can draw it.
+        let inner_layers = self.render_layers(commands);
+
+        let surface = Surface::new(
+            &self.descriptors,
+            DEFAULT_SAMPLE_COUNT,
+            width,
+            height,
+            FILTER_TEXTURE_FORMAT,
+        );
+        let mut texture = FilterTexture::new(&self.descriptors.device, width, height);
+        let mut command_buffers = self.draw_layered(
+            &surface,
+            &texture.texture,
+            &texture.view,
+            Some(wgpu::Color::TRANSPARENT),
+            commands,
+            &inner_layers,
+            true,
+        );
+
+        let label = create_debug_label!("Filter encoder");
+        let mut encoder =
+            self.descriptors
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: label.as_deref(),
+                });
+        for filter in filters {
+            texture =
+                self.descriptors
+                    .filters
+                    .apply(&self.descriptors, &mut encoder, filter, texture);
+        }
+        command_buffers.push(encoder.finish());
+        // Each layer is submitted separately, as the uniform buffers are reused for every draw.
+        self.descriptors.queue.submit(command_buffers);
+
+        self.globals.set_resolution(old_width, old_height);
+        self.globals.set_origin(old_x, old_y);
+
+        Some(Layer {
+            texture: texture_handle(texture.texture, width, height),
+            x,
+            y,
+            complex_blend: None,
+        })
+    }
+
+    /// Renders a blended object into a texture covering the current viewport.
+    fn render_blend_layer(&mut self, commands: &CommandList, blend_mode: BlendMode) -> Layer {
+        let (width, height) = self.globals.resolution();
+        let (x, y) = self.globals.origin();
+        let inner_layers = self.render_layers(commands);
+
+        let surface = Surface::new(
+            &self.descriptors,
+            DEFAULT_SAMPLE_COUNT,
+            width,
+            height,
+            FILTER_TEXTURE_FORMAT,
+        );
+        let texture = FilterTexture::new(&self.descriptors.device, width, height);
+        let command_buffers = self.draw_layered(
+            &surface,
+            &texture.texture,
+            &texture.view,
+            Some(wgpu::Color::TRANSPARENT),
+            commands,
+            &inner_layers,
+            true,
+        );
+        self.descriptors.queue.submit(command_buffers);
+
+        Layer {
+            texture: texture_handle(texture.texture, width, height),
+            x,
+            y,
+            complex_blend: match BlendType::from(blend_mode) {
+                BlendType::Complex(blend) => Some(blend),
+                BlendType::Trivial(_) => None,
+            },
+        }
+    }
+
+    /// Renders the stage into a texture, so that complex blends can read from it.
+    fn render_stage_layer(
+        &mut self,
+        clear_color: wgpu::Color,
+        commands: &CommandList,
+        layers: &[Option<Layer>],
+    ) -> BitmapHandle {
+        let (width, height) = self.globals.resolution();
+        let surface = Surface::new(
+            &self.descriptors,
+            DEFAULT_SAMPLE_COUNT,
+            width,
+            height,
+            FILTER_TEXTURE_FORMAT,
+        );
+        let texture = FilterTexture::new(&self.descriptors.device, width, height);
+        let command_buffers = self.draw_layered(
+            &surface,
+            &texture.texture,
+            &texture.view,
+            Some(clear_color),
+            commands,
+            layers,
+            false,
+        );
+        self.descriptors.queue.submit(command_buffers);
+        texture_handle(texture.texture, width, height)
+    }
+
+    /// Draws `commands` into `texture`, which must be the size of the current viewport.
+    ///
+    /// Complex blends need to read the content below them, so the commands are drawn in
+    /// segments that are split at each complex blend. After each segment, the target is
+    /// copied into a separate texture for the blend shader to read from.
+    ///
+    /// Every segment but the last is submitted immediately, and the command buffers
+    /// of the last one are returned.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_layered(
+        &mut self,
+        surface: &Surface,
+        texture: &wgpu::Texture,
+        view: &wgpu::TextureView,
+        clear_color: Option<wgpu::Color>,
+        commands: &CommandList,
+        layers: &[Option<Layer>],
+        is_layer: bool,
+    ) -> Vec<wgpu::CommandBuffer> {
+        let complex_blends: Vec<(ComplexBlend, &Layer)> = layers
+            .iter()
+            .flatten()
+            .filter_map(|layer| Some((layer.complex_blend?, layer)))
+            .collect();
+        let (width, height) = self.globals.resolution();
+        let mut blend_bind_groups = Vec::with_capacity(complex_blends.len());
+        let mut segment = 0;
+        loop {
+            let mut command_buffers = surface.draw_commands(
+                view,
+                clear_color,
+                &self.descriptors,
+                &mut self.globals,
+                &mut self.uniform_buffers_storage,
+                &self.meshes,
+                commands,
+                layers,
+                &blend_bind_groups,
+                segment,
+                is_layer,
+            );
+            let (blend, layer) = match complex_blends.get(segment) {
+                Some(blend) => *blend,
+                None => return command_buffers,
+            };
+
+            let destination = FilterTexture::new(&self.descriptors.device, width, height);
+            let label = create_debug_label!("Blend copy encoder");
+            let mut encoder =
+                self.descriptors
+                    .device
+                    .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                        label: label.as_deref(),
+                    });
+            encoder.copy_texture_to_texture(
+                wgpu::ImageCopyTexture {
+                    texture,
+                    mip_level: 0,
+                    origin: Default::default(),
+                    aspect: wgpu::TextureAspect::All,
+                },
+                wgpu::ImageCopyTexture {
+                    texture: &destination.texture,
+                    mip_level: 0,
+                    origin: Default::default(),
+                    aspect: wgpu::TextureAspect::All,
+                },
+                Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+            );
+            command_buffers.push(encoder.finish());
+            self.descriptors.queue.submit(command_buffers);
+
+            let source = as_texture(&layer.texture)
+                .texture
+                .create_view(&Default::default());
+            blend_bind_groups.push(create_blend_bind_group(
+                &self.descriptors,
+                blend,
+                &source,
+                &destination.view,
+            ));
+            segment += 1;
+        }
+    }
+
     pub fn descriptors(&self) -> &Arc<Descriptors> {
         &self.descriptors
     }
@@ -347,20 +604,51 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
             }
         };
 
-        let command_buffers = self.surface.draw_commands(
-            frame_output.view(),
-            Some(wgpu::Color {
-                r: f64::from(clear.r) / 255.0,
-                g: f64::from(clear.g) / 255.0,
-                b: f64::from(clear.b) / 255.0,
-                a: f64::from(clear.a) / 255.0,
-            }),
-            &self.descriptors,
-            &mut self.globals,
-            &mut self.uniform_buffers_storage,
-            &self.meshes,
-            commands,
-        );
+        let clear_color = wgpu::Color {
+            r: f64::from(clear.r) / 255.0,
+            g: f64::from(clear.g) / 255.0,
+            b: f64::from(clear.b) / 255.0,
+            a: f64::from(clear.a) / 255.0,
+        };
+        let layers = self.render_layers(&commands);
+        let command_buffers = if layers
+            .iter()
+            .flatten()
+            .any(|layer| layer.complex_blend.is_some())
+        {
+            // Complex blends need to copy the render target, which we can't do with the frame.
+            // Instead, the stage is drawn into a texture first.
+            let stage = self.render_stage_layer(clear_color, &commands, &layers);
+            let mut stage_commands = CommandList::new();
+            stage_commands.render_bitmap(&stage, &Default::default(), false);
+            self.surface.draw_commands(
+                frame_output.view(),
+                Some(clear_color),
+                &self.descriptors,
+                &mut self.globals,
+                &mut self.uniform_buffers_storage,
+                &self.meshes,
+                &stage_commands,
+                &[],
+                &[],
+                0,
+                false,
+            )
+        } else {
+            self.surface.draw_commands(
+                frame_output.view(),
+                Some(clear_color),
+                &self.descriptors,
+                &mut self.globals,
+                &mut self.uniform_buffers_storage,
+                &self.meshes,
+                &commands,
+                &layers,
+                &[],
+                0,
+                false,
+            )
+        };
 
         self.target.submit(
             &self.descriptors.device,
@@ -524,18 +812,20 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
         let (old_width, old_height) = self.globals.resolution();
         self.globals.set_resolution(width, height);
 
+        let layers = self.render_layers(&commands);
+
         let frame_output = target
             .get_next_texture()
             .expect("TextureTargetFrame.get_next_texture is infallible");
 
-        let command_buffers = texture_offscreen.surface.draw_commands(
+        let command_buffers = self.draw_layered(
+            &texture_offscreen.surface,
+            &texture.texture,
             frame_output.view(),
             None,
-            &self.descriptors,
-            &mut self.globals,
-            &mut self.uniform_buffers_storage,
-            &self.meshes,
-            commands,
+            &commands,
+            &layers,
+            true,
         );
         target.submit(
             &self.descriptors.device,
@@ -562,6 +852,18 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
     }
 }
 
+/// Wraps a texture that was rendered by us into a `BitmapHandle`.
+fn texture_handle(texture: Arc<wgpu::Texture>, width: u32, height: u32) -> BitmapHandle {
+    BitmapHandle(Arc::new(Texture {
+        texture,
+        bind_linear: Default::default(),
+        bind_nearest: Default::default(),
+        texture_offscreen: Default::default(),
+        width,
+        height,
+    }))
+}
+
 // We try to request the highest limits we can get away with
 async fn request_device(
     adapter: &wgpu::Adapter,
@@ -0,0 +1,96 @@
+use crate::descriptors::Descriptors;
+use crate::pipelines::BlendMode as TrivialBlend;
+use crate::utils::create_buffer_with_data;
+use bytemuck::{Pod, Zeroable};
+use enum_map::Enum;
+
+/// How a blended layer is composited onto the content below it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BlendType {
+    /// The layer can be drawn using fixed-function GPU blending.
+    Trivial(TrivialBlend),
+
+    /// The layer must be composited by a shader that reads the content below it.
+    Complex(ComplexBlend),
+}
+
+impl From<swf::BlendMode> for BlendType {
+    fn from(mode: swf::BlendMode) -> Self {
+        match mode {
+            swf::BlendMode::Normal | swf::BlendMode::Layer => {
+                BlendType::Trivial(TrivialBlend::Normal)
+            }
+            swf::BlendMode::Add => BlendType::Trivial(TrivialBlend::Add),
+            swf::BlendMode::Subtract => BlendType::Trivial(TrivialBlend::Subtract),
+            swf::BlendMode::Screen => BlendType::Trivial(TrivialBlend::Screen),
+            swf::BlendMode::Alpha => BlendType::Trivial(TrivialBlend::Alpha),
+            swf::BlendMode::Erase => BlendType::Trivial(TrivialBlend::Erase),
+            swf::BlendMode::Multiply => BlendType::Complex(ComplexBlend::Multiply),
+            swf::BlendMode::Lighten => BlendType::Complex(ComplexBlend::Lighten),
+            swf::BlendMode::Darken => BlendType::Complex(ComplexBlend::Darken),
+            swf::BlendMode::Difference => BlendType::Complex(ComplexBlend::Difference),
+            swf::BlendMode::Invert => BlendType::Complex(ComplexBlend::Invert),
+            swf::BlendMode::Overlay => BlendType::Complex(ComplexBlend::Overlay),
+            swf::BlendMode::HardLight => BlendType::Complex(ComplexBlend::HardLight),
+        }
+    }
+}
+
+/// A blend mode that is implemented by `blend.wgsl`.
+/// The discriminants must match the cases in the shader.
+#[derive(Debug, Enum, Copy, Clone, PartialEq, Eq)]
+pub enum ComplexBlend {
+    Multiply = 0,
+    Lighten = 1,
+    Darken = 2,
+    Difference = 3,
+    Invert = 4,
+    Overlay = 5,
+    HardLight = 6,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct BlendUniforms {
+    mode: i32,
+    _padding: [i32; 3],
+}
+
+/// Creates the bind group used to composite `source` onto a copy of the content below it.
+pub fn create_blend_bind_group(
+    descriptors: &Descriptors,
+    blend: ComplexBlend,
+    source: &wgpu::TextureView,
+    destination: &wgpu::TextureView,
+) -> wgpu::BindGroup {
+    let buffer = create_buffer_with_data(
+        &descriptors.device,
+        bytemuck::cast_slice(&[BlendUniforms {
+            mode: blend as i32,
+            _padding: [0; 3],
+        }]),
+        wgpu::BufferUsages::UNIFORM,
+        create_debug_label!("Blend uniforms"),
+    );
+    let label = create_debug_label!("Blend bind group");
+    descriptors
+        .device
+        .create_bind_group(&wgpu::BindGroupDescriptor {
+            label: label.as_deref(),
+            layout: &descriptors.bind_layouts.blend,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(source),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(destination),
+                },
+            ],
+        })
+}
@@ -1,12 +1,17 @@
+use crate::blend::{BlendType, ComplexBlend};
 use crate::frame::Frame;
 use crate::mesh::{DrawType, Mesh};
-use crate::pipelines::BlendMode as ActualBlendMode;
+use crate::pipelines::BlendMode as TrivialBlend;
 use crate::{as_texture, ColorAdjustments, MaskState};
 use ruffle_render::backend::ShapeHandle;
 use ruffle_render::bitmap::BitmapHandle;
-use ruffle_render::commands::CommandHandler;
+use ruffle_render::bounding_box::BoundingBox;
+use ruffle_render::color_transform::ColorTransform;
+use ruffle_render::commands::{CommandHandler, CommandList};
+use ruffle_render::filters::Filter;
+use ruffle_render::matrix::Matrix;
 use ruffle_render::transform::Transform;
-use swf::{BlendMode, Color};
+use swf::{BlendMode, Color, Twips};
 
 pub struct CommandRenderer<'a, 'b> {
     frame: &'b mut Frame<'a>,
@@ -15,14 +20,44 @@ pub struct CommandRenderer<'a, 'b> {
     quad_indices: wgpu::BufferSlice<'a>,
     blend_modes: Vec<BlendMode>,
     num_masks: u32,
+    layers: std::slice::Iter<'a, Option<Layer>>,
+    complex_blends: &'a [wgpu::BindGroup],
+    segment: usize,
+    num_complex_blends: usize,
+    is_layer: bool,
+}
+
+/// The filtered or blended contents of a display object, rendered ahead of time.
+#[derive(Debug)]
+pub struct Layer {
+    pub texture: BitmapHandle,
+
+    /// The position of the top-left of the texture, in pixels.
+    pub x: i32,
+    pub y: i32,
+
+    /// The blend mode used to composite this layer, if it needs to read the content below it.
+    pub complex_blend: Option<ComplexBlend>,
 }
 
 impl<'a, 'b> CommandRenderer<'a, 'b> {
+    /// Creates a renderer that draws a single segment of a command list.
+    ///
+    /// The segments are separated by complex blends, as the target must be copied before
+    /// each of them can be drawn. Segment `n` starts by compositing the `n`th complex blend
+    /// (using the bind group at `complex_blends[n - 1]`), and ends before the next one.
+    ///
+    /// `is_layer` should be false when drawing to the stage, whose alpha channel is ignored.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         frame: &'b mut Frame<'a>,
         meshes: &'a Vec<Mesh>,
         quad_vertices: wgpu::BufferSlice<'a>,
         quad_indices: wgpu::BufferSlice<'a>,
+        layers: &'a [Option<Layer>],
+        complex_blends: &'a [wgpu::BindGroup],
+        segment: usize,
+        is_layer: bool,
     ) -> Self {
         Self {
             frame,
@@ -31,12 +66,71 @@ impl<'a, 'b> CommandRenderer<'a, 'b> {
             quad_indices,
             blend_modes: vec![BlendMode::Normal],
             num_masks: 0,
+            layers: layers.iter(),
+            complex_blends,
+            segment,
+            num_complex_blends: 0,
+            is_layer,
         }
     }
+
+    /// Whether the commands that we're currently visiting are part of the segment being drawn.
+    fn is_drawing(&self) -> bool {
+        self.num_complex_blends == self.segment
+    }
+
+    /// Whether we're currently drawing a mask into the stencil buffer.
+    /// Layers aren't needed for this, as only the shape of the content matters.
+    fn is_drawing_stencil(&self) -> bool {
+        matches!(
+            self.frame.mask_state(),
+            MaskState::DrawMaskStencil | MaskState::ClearMaskStencil
+        )
+    }
+
+    fn current_blend_mode(&self) -> TrivialBlend {
+        match self.blend_modes.last().map(|b| BlendType::from(*b)) {
+            Some(BlendType::Trivial(blend)) => blend,
+            // Individual draws can't read the content below them.
+            Some(BlendType::Complex(_)) | None => TrivialBlend::Normal,
+        }
+    }
+
+    fn draw_layer(&mut self, layer: &'a Layer, blend: TrivialBlend) {
+        self.frame.set_blend_mode(blend);
+        self.render_bitmap(
+            &layer.texture,
+            &Transform {
+                matrix: Matrix::translate(
+                    Twips::from_pixels(layer.x.into()),
+                    Twips::from_pixels(layer.y.into()),
+                ),
+                color_transform: Default::default(),
+            },
+            false,
+        );
+        self.frame.set_blend_mode(self.current_blend_mode());
+    }
+
+    fn draw_complex_blend(&mut self, layer: &'a Layer, bind_group: &'a wgpu::BindGroup) {
+        let texture = as_texture(&layer.texture);
+        self.frame.apply_transform(
+            &(Matrix::translate(
+                Twips::from_pixels(layer.x.into()),
+                Twips::from_pixels(layer.y.into()),
+            ) * Matrix::scale(texture.width as f32, texture.height as f32)),
+            ColorAdjustments::from(ColorTransform::default()),
+        );
+        self.frame.prep_complex_blend(bind_group);
+        self.frame.draw(self.quad_vertices, self.quad_indices, 6);
+    }
 }
 
 impl<'a, 'b> CommandHandler<'a> for CommandRenderer<'a, 'b> {
     fn render_bitmap(&mut self, bitmap: &'a BitmapHandle, transform: &Transform, smoothing: bool) {
+        if !self.is_drawing() {
+            return;
+        }
         let texture = as_texture(bitmap);
 
         self.frame.apply_transform(
@@ -64,6 +158,9 @@ impl<'a, 'b> CommandHandler<'a> for CommandRenderer<'a, 'b> {
     }
 
     fn render_shape(&mut self, shape: ShapeHandle, transform: &Transform) {
+        if !self.is_drawing() {
+            return;
+        }
         self.frame.apply_transform(
             &transform.matrix,
             ColorAdjustments::from(transform.color_transform),
@@ -105,6 +202,9 @@ impl<'a, 'b> CommandHandler<'a> for CommandRenderer<'a, 'b> {
     }
 
     fn draw_rect(&mut self, color: Color, matrix: &ruffle_render::matrix::Matrix) {
+        if !self.is_drawing() {
+            return;
+        }
         self.frame.apply_transform(
             &matrix,
             ColorAdjustments {
@@ -159,16 +259,133 @@ impl<'a, 'b> CommandHandler<'a> for CommandRenderer<'a, 'b> {
 
     fn push_blend_mode(&mut self, blend: BlendMode) {
         self.blend_modes.push(blend);
-        self.frame.set_blend_mode(blend.into());
+        self.frame.set_blend_mode(self.current_blend_mode());
     }
 
     fn pop_blend_mode(&mut self) {
         self.blend_modes.pop();
-        self.frame.set_blend_mode(
-            self.blend_modes
-                .last()
-                .map(|b| ActualBlendMode::from(*b))
-                .unwrap_or(ActualBlendMode::Normal),
-        );
+        self.frame.set_blend_mode(self.current_blend_mode());
+    }
+
+    fn render_filtered(
+        &mut self,
+        commands: &'a CommandList,
+        _filters: &'a [Filter],
+        _bounds: &BoundingBox,
+    ) {
+        if self.is_drawing_stencil() {
+            commands.execute(self);
+            return;
+        }
+
+        // The layers were rendered in the same order that we encounter them.
+        if let Some(Some(layer)) = self.layers.next() {
+            self.draw_layer(layer, TrivialBlend::Normal);
+        }
+    }
+
+    fn render_blend(&mut self, commands: &'a CommandList, blend_mode: BlendMode) {
+        if self.is_drawing_stencil() || blend_mode == BlendMode::Normal {
+            commands.execute(self);
+            return;
+        }
+
+        let layer = match self.layers.next() {
+            Some(Some(layer)) => layer,
+            _ => return,
+        };
+        match BlendType::from(blend_mode) {
+            BlendType::Trivial(TrivialBlend::Alpha | TrivialBlend::Erase) if !self.is_layer => {
+                // These only affect the alpha channel of the layer below them,
+                // which is discarded when there isn't one.
+            }
+            BlendType::Trivial(blend) => self.draw_layer(layer, blend),
+            BlendType::Complex(_) => {
+                self.num_complex_blends += 1;
+                if self.is_drawing() {
+                    let complex_blends = self.complex_blends;
+                    self.draw_complex_blend(layer, &complex_blends[self.segment - 1]);
+                }
+            }
+        }
+    }
+}
+
+/// A filtered or blended object that must be rendered into its own layer.
+pub enum LayerCommand<'a> {
+    Filtered {
+        commands: &'a CommandList,
+        filters: &'a [Filter],
+        bounds: BoundingBox,
+    },
+    Blend {
+        commands: &'a CommandList,
+        blend_mode: BlendMode,
+    },
+}
+
+/// Collects every object in a command list that needs its own layer, without descending into them.
+/// This follows the same rules as `CommandRenderer`, so that the layers are encountered in the same order.
+#[derive(Default)]
+pub struct LayerCommands<'a> {
+    pub layers: Vec<LayerCommand<'a>>,
+    drawing_stencil: bool,
+}
+
+impl<'a> CommandHandler<'a> for LayerCommands<'a> {
+    fn render_bitmap(
+        &mut self,
+        _bitmap: &'a BitmapHandle,
+        _transform: &Transform,
+        _smoothing: bool,
+    ) {
+    }
+    fn render_shape(&mut self, _shape: ShapeHandle, _transform: &Transform) {}
+    fn draw_rect(&mut self, _color: Color, _matrix: &Matrix) {}
+    fn push_blend_mode(&mut self, _blend: BlendMode) {}
+    fn pop_blend_mode(&mut self) {}
+
+    fn push_mask(&mut self) {
+        self.drawing_stencil = true;
+    }
+
+    fn activate_mask(&mut self) {
+        self.drawing_stencil = false;
+    }
+
+    fn deactivate_mask(&mut self) {
+        self.drawing_stencil = true;
+    }
+
+    fn pop_mask(&mut self) {
+        self.drawing_stencil = false;
+    }
+
+    fn render_filtered(
+        &mut self,
+        commands: &'a CommandList,
+        filters: &'a [Filter],
+        bounds: &BoundingBox,
+    ) {
+        if self.drawing_stencil {
+            commands.execute(self);
+        } else {
+            self.layers.push(LayerCommand::Filtered {
+                commands,
+                filters,
+                bounds: bounds.clone(),
+            });
+        }
+    }
+
+    fn render_blend(&mut self, commands: &'a CommandList, blend_mode: BlendMode) {
+        if self.drawing_stencil || blend_mode == BlendMode::Normal {
+            commands.execute(self);
+        } else {
+            self.layers.push(LayerCommand::Blend {
+                commands,
+                blend_mode,
+            });
+        }
     }
 }
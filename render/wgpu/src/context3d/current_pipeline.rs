@@ -1,3 +1,4 @@
+use naga_agal::{SamplerOverride, MAX_TEXTURES};
 use ruffle_render::backend::{Context3DTriangleFace, Context3DVertexBufferFormat};
 
 use wgpu::FrontFace;
@@ -7,7 +8,8 @@ use wgpu::{
 };
 
 use std::borrow::Cow;
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::rc::Rc;
 
 use super::{ShaderModuleAgal, VertexAttributeInfo, MAX_VERTEX_ATTRIBUTES};
@@ -24,33 +26,80 @@ use super::{ShaderModuleAgal, VertexAttributeInfo, MAX_VERTEX_ATTRIBUTES};
 // as dirty. When a `wgpu::RenderPipeline` is actually needed by `drawTriangles`,
 // we build a new `wgpu::RenderPipeline` from the `CurrentPipeline` state (if it's dirty).
 //
-// The `CurrentPipeline` state (including the compiled `wgpu::RenderPipeline`) is stored
-// in `WgpuContext3D`, and is re-used across calls to `present`. Due to lifetime issues,
-// we don't actually store the `wgpu::RenderPipeline` in `CurrentPipeline` - it's
-// instead stored in `WgpuContext3D`.
+// The `CurrentPipeline` state is stored in `WgpuContext3D`, and is re-used across calls
+// to `present`. Each distinct combination of render state (blending, depth and stencil
+// testing, etc.) needs its own `wgpu::RenderPipeline`, so we cache one pipeline for each
+// `PipelineState` we've seen. Switching back and forth between (for example) two blend modes
+// doesn't need to recompile anything. The cache is cleared whenever something that affects
+// every pipeline changes (the shaders, the vertex attributes, or the bind group layout).
 pub struct CurrentPipeline {
     vertex_shader: Option<Rc<ShaderModuleAgal>>,
     fragment_shader: Option<Rc<ShaderModuleAgal>>,
 
-    culling: Context3DTriangleFace,
+    sampler_overrides: [Option<SamplerOverride>; MAX_TEXTURES],
+
+    state: PipelineState,
+
+    compiled_shaders: RefCell<Option<CompiledShaders>>,
+    pipelines: RefCell<HashMap<PipelineState, Rc<wgpu::RenderPipeline>>>,
 
     dirty: Cell<bool>,
 }
 
+/// The render state that is baked into a `wgpu::RenderPipeline`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct PipelineState {
+    cull_mode: Option<wgpu::Face>,
+    blend: wgpu::BlendState,
+    color_mask: ColorWrites,
+    depth_write_enabled: bool,
+    depth_compare: wgpu::CompareFunction,
+    stencil: wgpu::StencilState,
+    // Whether the current render target has a depth and stencil buffer.
+    has_depth_stencil: bool,
+}
+
+struct CompiledShaders {
+    vertex_module: wgpu::ShaderModule,
+    fragment_module: wgpu::ShaderModule,
+    attributes: Vec<wgpu::VertexAttribute>,
+    stride: u64,
+}
+
+pub const DEPTH_STENCIL_FORMAT: TextureFormat = TextureFormat::Depth24PlusStencil8;
+
 impl CurrentPipeline {
     pub fn new() -> Self {
         CurrentPipeline {
             vertex_shader: None,
             fragment_shader: None,
             dirty: Cell::new(true),
-            culling: Context3DTriangleFace::None,
+            sampler_overrides: [None; MAX_TEXTURES],
+            // These match the Flash Player defaults - no blending, and a depth test
+            // of 'less' that writes to the depth buffer.
+            state: PipelineState {
+                cull_mode: None,
+                blend: wgpu::BlendState::REPLACE,
+                color_mask: ColorWrites::all(),
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState {
+                    front: wgpu::StencilFaceState::IGNORE,
+                    back: wgpu::StencilFaceState::IGNORE,
+                    read_mask: 0xFF,
+                    write_mask: 0xFF,
+                },
+                has_depth_stencil: false,
+            },
+            compiled_shaders: RefCell::new(None),
+            pipelines: RefCell::new(HashMap::new()),
         }
     }
     pub fn set_vertex_shader(&mut self, shader: Rc<ShaderModuleAgal>) {
         if let Some(old_shader) = &self.vertex_shader {
             // If we change the shader, we need to recompile the pipeline.
             if !Rc::ptr_eq(old_shader, &shader) {
-                self.dirty.set(true);
+                self.mark_dirty();
             }
         }
         self.vertex_shader = Some(shader);
@@ -60,7 +109,7 @@ impl CurrentPipeline {
         if let Some(old_shader) = &self.fragment_shader {
             // If we change the shader, we need to recompile the pipeline.
             if !Rc::ptr_eq(old_shader, &shader) {
-                self.dirty.set(true);
+                self.mark_dirty();
             }
         }
         self.fragment_shader = Some(shader);
@@ -68,23 +117,125 @@ impl CurrentPipeline {
 
     pub fn update_vertex_buffer_at(&mut self, _index: usize) {
         // FIXME - check if it's the same, so we can skip rebuilding the pipeline
+        self.mark_dirty();
+    }
+
+    pub fn set_sampler_override(&mut self, index: usize, sampler_override: SamplerOverride) {
+        // The sampler is chosen when compiling the fragment shader.
+        if self.sampler_overrides[index] != Some(sampler_override) {
+            self.sampler_overrides[index] = Some(sampler_override);
+            self.mark_dirty();
+        }
+    }
+
+    /// Throws away all of the compiled shaders and pipelines.
+    pub fn mark_dirty(&mut self) {
+        self.compiled_shaders.get_mut().take();
+        self.pipelines.get_mut().clear();
         self.dirty.set(true);
     }
 
-    /// If the pipeline is dirty, recompiles it and returns `Some(freshly_compiled_pipeline`)
+    fn update_state(&mut self, f: impl FnOnce(&mut PipelineState)) {
+        let old_state = self.state.clone();
+        f(&mut self.state);
+        if self.state != old_state {
+            self.dirty.set(true);
+        }
+    }
+
+    pub fn set_culling(&mut self, face: Context3DTriangleFace) {
+        let cull_mode = match face {
+            Context3DTriangleFace::Back => Some(wgpu::Face::Back),
+            Context3DTriangleFace::Front => Some(wgpu::Face::Front),
+            Context3DTriangleFace::FrontAndBack => {
+                log::error!("FrontAndBack culling not supported!");
+                None
+            }
+            Context3DTriangleFace::None => None,
+        };
+        self.update_state(|state| state.cull_mode = cull_mode);
+    }
+
+    pub fn set_blend_factors(&mut self, source: wgpu::BlendFactor, destination: wgpu::BlendFactor) {
+        let component = wgpu::BlendComponent {
+            src_factor: source,
+            dst_factor: destination,
+            operation: wgpu::BlendOperation::Add,
+        };
+        self.update_state(|state| {
+            state.blend = wgpu::BlendState {
+                color: component,
+                alpha: component,
+            }
+        });
+    }
+
+    pub fn set_color_mask(&mut self, color_mask: ColorWrites) {
+        self.update_state(|state| state.color_mask = color_mask);
+    }
+
+    pub fn set_depth_test(&mut self, depth_write_enabled: bool, compare: wgpu::CompareFunction) {
+        self.update_state(|state| {
+            state.depth_write_enabled = depth_write_enabled;
+            state.depth_compare = compare;
+        });
+    }
+
+    pub fn set_stencil_actions(
+        &mut self,
+        face: Context3DTriangleFace,
+        face_state: wgpu::StencilFaceState,
+    ) {
+        self.update_state(|state| {
+            if matches!(
+                face,
+                Context3DTriangleFace::Front | Context3DTriangleFace::FrontAndBack
+            ) {
+                state.stencil.front = face_state;
+            }
+            if matches!(
+                face,
+                Context3DTriangleFace::Back | Context3DTriangleFace::FrontAndBack
+            ) {
+                state.stencil.back = face_state;
+            }
+        });
+    }
+
+    pub fn set_stencil_masks(&mut self, read_mask: u32, write_mask: u32) {
+        self.update_state(|state| {
+            state.stencil.read_mask = read_mask;
+            state.stencil.write_mask = write_mask;
+        });
+    }
+
+    pub fn set_has_depth_stencil(&mut self, has_depth_stencil: bool) {
+        self.update_state(|state| state.has_depth_stencil = has_depth_stencil);
+    }
+
+    /// If the pipeline is dirty, returns `Some(pipeline)` with a pipeline for the current
+    /// state (which is compiled if we haven't seen this state before).
     /// Otherwise, returns `None`.
     pub fn rebuild_pipeline(
         &self,
         device: &wgpu::Device,
         bind_group_layout: &BindGroupLayout,
         vertex_attributes: &[Option<VertexAttributeInfo>; MAX_VERTEX_ATTRIBUTES],
-    ) -> Option<wgpu::RenderPipeline> {
+    ) -> Option<Rc<wgpu::RenderPipeline>> {
         if !self.dirty.get() {
             return None;
         }
 
         self.dirty.set(false);
 
+        if let Some(pipeline) = self.pipelines.borrow().get(&self.state) {
+            return Some(pipeline.clone());
+        }
+
+        let mut compiled_shaders = self.compiled_shaders.borrow_mut();
+        let shaders =
+            compiled_shaders.get_or_insert_with(|| self.compile_shaders(device, vertex_attributes));
+
         let pipeline_layout_label = create_debug_label!("Pipeline layout");
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: pipeline_layout_label.as_deref(),
@@ -92,6 +243,61 @@ impl CurrentPipeline {
             push_constant_ranges: &[],
         });
 
+        let compiled = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: create_debug_label!("RenderPipeline").as_deref(),
+            layout: Some(&pipeline_layout),
+            vertex: VertexState {
+                module: &shaders.vertex_module,
+                entry_point: "main",
+                buffers: &[VertexBufferLayout {
+                    array_stride: shaders.stride,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &shaders.attributes,
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shaders.fragment_module,
+                entry_point: "main",
+                targets: &[Some(ColorTargetState {
+                    format: TextureFormat::Rgba8Unorm,
+                    blend: Some(self.state.blend),
+                    write_mask: self.state.color_mask,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                // Stage3d appears to use clockwise winding:
+                // https://stackoverflow.com/questions/8677498/stage3d-culling-confusion
+                front_face: FrontFace::Cw,
+                cull_mode: self.state.cull_mode,
+                ..Default::default()
+            },
+            // Flash only performs depth and stencil tests when the render target
+            // has a depth and stencil buffer.
+            depth_stencil: self
+                .state
+                .has_depth_stencil
+                .then(|| wgpu::DepthStencilState {
+                    format: DEPTH_STENCIL_FORMAT,
+                    depth_write_enabled: self.state.depth_write_enabled,
+                    depth_compare: self.state.depth_compare,
+                    stencil: self.state.stencil.clone(),
+                    bias: Default::default(),
+                }),
+            multisample: Default::default(),
+            multiview: Default::default(),
+        });
+        let compiled = Rc::new(compiled);
+        self.pipelines
+            .borrow_mut()
+            .insert(self.state.clone(), compiled.clone());
+        Some(compiled)
+    }
+
+    fn compile_shaders(
+        &self,
+        device: &wgpu::Device,
+        vertex_attributes: &[Option<VertexAttributeInfo>; MAX_VERTEX_ATTRIBUTES],
+    ) -> CompiledShaders {
         let agal_attributes = vertex_attributes.clone().map(|attr| {
             attr.map(|attr| match attr.format {
                 Context3DVertexBufferFormat::Float4 => naga_agal::VertexAttributeFormat::Float4,
@@ -109,6 +315,7 @@ impl CurrentPipeline {
                 .expect("Missing vertex shader!")
                 .0,
             &agal_attributes,
+            &self.sampler_overrides,
         )
         .expect("Vertex shader failed to compile");
 
@@ -119,6 +326,7 @@ impl CurrentPipeline {
                 .expect("Missing fragment shader")
                 .0,
             &[None; 8],
+            &self.sampler_overrides,
         )
         .expect("Fragment shader failed to compile");
 
@@ -155,7 +363,7 @@ impl CurrentPipeline {
                         Context3DVertexBufferFormat::Float1 => {
                             (wgpu::VertexFormat::Float32, std::mem::size_of::<f32>())
                         }
-                        Context3DVertexBufferFormat::Bytes4 => (wgpu::VertexFormat::Uint8x4, 4),
+                        Context3DVertexBufferFormat::Bytes4 => (wgpu::VertexFormat::Unorm8x4, 4),
                     };
                     // FIXME - assert that this matches up with the AS3-supplied offset
                     stride += entry_size_bytes;
@@ -170,54 +378,11 @@ impl CurrentPipeline {
             })
             .collect::<Vec<_>>();
 
-        let cull_mode = match self.culling {
-            Context3DTriangleFace::Back => Some(wgpu::Face::Back),
-            Context3DTriangleFace::Front => Some(wgpu::Face::Front),
-            Context3DTriangleFace::FrontAndBack => {
-                log::error!("FrontAndBack culling not supported!");
-                None
-            }
-            Context3DTriangleFace::None => None,
-        };
-
-        let compiled = device.create_render_pipeline(&RenderPipelineDescriptor {
-            label: create_debug_label!("RenderPipeline").as_deref(),
-            layout: Some(&pipeline_layout),
-            vertex: VertexState {
-                module: &vertex_module,
-                entry_point: "main",
-                buffers: &[VertexBufferLayout {
-                    array_stride: stride as u64,
-                    step_mode: wgpu::VertexStepMode::Vertex,
-                    attributes: &wgpu_attributes,
-                }],
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &fragment_module,
-                entry_point: "main",
-                targets: &[Some(ColorTargetState {
-                    format: TextureFormat::Rgba8Unorm,
-                    blend: None,
-                    write_mask: ColorWrites::all(),
-                })],
-            }),
-            primitive: wgpu::PrimitiveState {
-                // Stage3d appears to use clockwise winding:
-                // https://stackoverflow.com/questions/8677498/stage3d-culling-confusion
-                front_face: FrontFace::Cw,
-                cull_mode,
-                ..Default::default()
-            },
-            // FIXME - get this from AS3
-            depth_stencil: None,
-            multisample: Default::default(),
-            multiview: Default::default(),
-        });
-        Some(compiled)
-    }
-
-    pub fn set_culling(&mut self, face: Context3DTriangleFace) {
-        self.culling = face;
-        self.dirty.set(true);
+        CompiledShaders {
+            vertex_module,
+            fragment_module,
+            attributes: wgpu_attributes,
+            stride: stride as u64,
+        }
     }
 }
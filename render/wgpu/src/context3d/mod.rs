@@ -1,6 +1,9 @@
+use naga_agal::{SamplerOverride, MAX_TEXTURES};
 use ruffle_render::backend::{
-    Context3D, Context3DCommand, Context3DVertexBufferFormat, IndexBuffer, ProgramType,
-    ShaderModule, VertexBuffer,
+    Context3D, Context3DBlendFactor, Context3DCommand, Context3DCompareMode, Context3DMipFilter,
+    Context3DRectangle, Context3DStencilAction, Context3DTextureFilter, Context3DTextureFormat,
+    Context3DVertexBufferFormat, Context3DWrapMode, IndexBuffer, ProgramType, ShaderModule,
+    VertexBuffer,
 };
 use ruffle_render::bitmap::BitmapHandle;
 
@@ -14,7 +17,7 @@ use crate::descriptors::Descriptors;
 use crate::Texture;
 use gc_arena::{Collect, MutationContext};
 
-use std::num::NonZeroU64;
+use std::num::{NonZeroU32, NonZeroU64};
 use std::rc::Rc;
 use std::sync::Arc;
 
@@ -22,7 +25,7 @@ mod current_pipeline;
 mod render_pass_wrapper;
 use render_pass_wrapper::{finish_render_pass, RenderPassWrapper};
 
-use current_pipeline::CurrentPipeline;
+use current_pipeline::{CurrentPipeline, DEPTH_STENCIL_FORMAT};
 
 const COLOR_MASK: u32 = 1 << 0;
 const DEPTH_MASK: u32 = 1 << 1;
@@ -49,15 +52,37 @@ pub struct WgpuContext3D {
     // we need in individual `Arc`s.
     descriptors: Arc<Descriptors>,
 
-    // Currently, the only resources we bind are the 'program constants' uniform buffers
-    // (one for the vertex shader, and one for the fragment shader).
-    // These never change, so we can just create them once and reuse them.
+    // We bind the 'program constants' uniform buffers (one for the vertex shader,
+    // and one for the fragment shader), a fixed set of samplers, and one texture
+    // for each sampler register. The bind group is re-created whenever
+    // `setTextureAt` changes a texture, while the layout is only re-created
+    // when the dimension (2D or cube) of a bound texture changes.
     bind_group: BindGroup,
     bind_group_layout: BindGroupLayout,
+    texture_view_dimensions: [wgpu::TextureViewDimension; MAX_TEXTURES],
+
+    samplers: Vec<wgpu::Sampler>,
+    bound_textures: [Option<Rc<TextureWrapper>>; MAX_TEXTURES],
+    // Bound in place of any texture that hasn't been set with `setTextureAt`.
+    dummy_texture: wgpu::TextureView,
 
     buffer_staging_belt: StagingBelt,
 
     texture_view: Option<wgpu::TextureView>,
+    // Created by `configureBackBuffer` when `enableDepthAndStencil` is set.
+    depth_stencil_view: Option<wgpu::TextureView>,
+    back_buffer_size: (u32, u32),
+
+    // The texture set by `setRenderToTexture`. When this is `None`,
+    // we render to the back buffer.
+    texture_target: Option<TextureTarget>,
+    // The depth and stencil buffer used when rendering to a texture. This is
+    // shared by all textures, and only re-created when the texture size changes.
+    texture_depth_stencil: Option<Rc<DepthStencilBuffer>>,
+
+    // Dynamic state of the render pass, which doesn't need a new pipeline.
+    scissor_rectangle: Option<Context3DRectangle>,
+    stencil_reference: u32,
 
     // Note - the Context3D docs state that rendering should be double-buffered.
     // However, our Context3DCommand list already acts like a second buffer -
@@ -69,7 +94,7 @@ pub struct WgpuContext3D {
     fragment_shader_uniforms: wgpu::Buffer,
 
     current_pipeline: CurrentPipeline,
-    compiled_pipeline: Option<wgpu::RenderPipeline>,
+    compiled_pipeline: Option<Rc<wgpu::RenderPipeline>>,
 
     vertex_attributes: [Option<VertexAttributeInfo>; MAX_VERTEX_ATTRIBUTES],
 }
@@ -85,35 +110,9 @@ const FRAGMENT_SHADER_UNIFORMS_BUFFER_SIZE: u64 =
 
 impl WgpuContext3D {
     pub fn new(descriptors: Arc<Descriptors>, raw_texture_handle: BitmapHandle) -> Self {
-        let globals_layout_label = create_debug_label!("Globals bind group layout");
+        let texture_view_dimensions = [wgpu::TextureViewDimension::D2; MAX_TEXTURES];
         let bind_group_layout =
-            descriptors
-                .device
-                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                    label: globals_layout_label.as_deref(),
-                    entries: &[
-                        wgpu::BindGroupLayoutEntry {
-                            binding: 0,
-                            visibility: wgpu::ShaderStages::VERTEX,
-                            ty: wgpu::BindingType::Buffer {
-                                ty: wgpu::BufferBindingType::Uniform,
-                                has_dynamic_offset: false,
-                                min_binding_size: None,
-                            },
-                            count: None,
-                        },
-                        wgpu::BindGroupLayoutEntry {
-                            binding: 1,
-                            visibility: wgpu::ShaderStages::FRAGMENT,
-                            ty: wgpu::BindingType::Buffer {
-                                ty: wgpu::BufferBindingType::Uniform,
-                                has_dynamic_offset: false,
-                                min_binding_size: None,
-                            },
-                            count: None,
-                        },
-                    ],
-                });
+            create_bind_group_layout(&descriptors.device, &texture_view_dimensions);
 
         // FIXME - determine the best chunk size for this
         let buffer_staging_belt = StagingBelt::new(1024);
@@ -132,42 +131,35 @@ impl WgpuContext3D {
             mapped_at_creation: false,
         });
 
-        let bind_group_label = create_debug_label!("Bind group");
-        let bind_group = descriptors
-            .device
-            .create_bind_group(&wgpu::BindGroupDescriptor {
-                label: bind_group_label.as_deref(),
-                layout: &bind_group_layout,
-                entries: &[
-                    BindGroupEntry {
-                        binding: 0,
-                        resource: BindingResource::Buffer(wgpu::BufferBinding {
-                            buffer: &vertex_shader_uniforms,
-                            offset: 0,
-                            size: Some(
-                                NonZeroU64::new(VERTEX_SHADER_UNIFORMS_BUFFER_SIZE).unwrap(),
-                            ),
-                        }),
-                    },
-                    BindGroupEntry {
-                        binding: 1,
-                        resource: BindingResource::Buffer(wgpu::BufferBinding {
-                            buffer: &fragment_shader_uniforms,
-                            offset: 0,
-                            size: Some(
-                                NonZeroU64::new(FRAGMENT_SHADER_UNIFORMS_BUFFER_SIZE).unwrap(),
-                            ),
-                        }),
-                    },
-                ],
-            });
+        let samplers = create_samplers(&descriptors.device);
+
+        let dummy_texture = create_dummy_texture(&descriptors.device);
+
+        let bind_group = create_bind_group(
+            &descriptors.device,
+            &bind_group_layout,
+            &vertex_shader_uniforms,
+            &fragment_shader_uniforms,
+            &samplers,
+            [&dummy_texture; MAX_TEXTURES],
+        );
 
         Self {
             descriptors,
             bind_group_layout,
             bind_group,
+            texture_view_dimensions,
+            samplers,
+            bound_textures: Default::default(),
+            dummy_texture,
             buffer_staging_belt,
             texture_view: None,
+            depth_stencil_view: None,
+            back_buffer_size: (0, 0),
+            texture_target: None,
+            texture_depth_stencil: None,
+            scissor_rectangle: None,
+            stencil_reference: 0,
             raw_texture_handle,
             vertex_shader_uniforms,
             fragment_shader_uniforms,
@@ -176,6 +168,140 @@ impl WgpuContext3D {
             vertex_attributes: std::array::from_fn(|_| None),
         }
     }
+
+    fn create_texture_wrapper(
+        &self,
+        width: u32,
+        height: u32,
+        format: Context3DTextureFormat,
+        mip_level_count: u32,
+        cube: bool,
+    ) -> TextureWrapper {
+        // All texture data is converted to RGBA before it is uploaded.
+        if !matches!(
+            format,
+            Context3DTextureFormat::Bgra
+                | Context3DTextureFormat::Compressed
+                | Context3DTextureFormat::CompressedAlpha
+        ) {
+            log::warn!("Context3D: Texture format {format:?} will be stored as RGBA");
+        }
+
+        let width = width.max(1);
+        let height = height.max(1);
+        let texture_label = create_debug_label!("Context3D texture");
+        let texture = self
+            .descriptors
+            .device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: texture_label.as_deref(),
+                size: Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: if cube { 6 } else { 1 },
+                },
+                mip_level_count,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                // Any texture can be used with `setRenderToTexture`.
+                usage: wgpu::TextureUsages::TEXTURE_BINDING
+                    | wgpu::TextureUsages::COPY_DST
+                    | wgpu::TextureUsages::RENDER_ATTACHMENT,
+            });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(if cube {
+                wgpu::TextureViewDimension::Cube
+            } else {
+                wgpu::TextureViewDimension::D2
+            }),
+            ..Default::default()
+        });
+        TextureWrapper {
+            texture,
+            view,
+            width,
+            height,
+            mip_level_count,
+            cube,
+        }
+    }
+
+    // Re-creates the bind group (and, if necessary, the bind group layout)
+    // after the bound textures have changed.
+    fn rebuild_bind_group(&mut self) {
+        let dimensions = self.bound_textures.clone().map(|texture| match texture {
+            Some(texture) if texture.cube => wgpu::TextureViewDimension::Cube,
+            _ => wgpu::TextureViewDimension::D2,
+        });
+
+        if dimensions != self.texture_view_dimensions {
+            self.texture_view_dimensions = dimensions;
+            self.bind_group_layout =
+                create_bind_group_layout(&self.descriptors.device, &dimensions);
+            // The pipeline layout is created from our bind group layout.
+            self.current_pipeline.mark_dirty();
+        }
+
+        let texture_views = std::array::from_fn(|i| match &self.bound_textures[i] {
+            Some(texture) => &texture.view,
+            None => &self.dummy_texture,
+        });
+        self.bind_group = create_bind_group(
+            &self.descriptors.device,
+            &self.bind_group_layout,
+            &self.vertex_shader_uniforms,
+            &self.fragment_shader_uniforms,
+            &self.samplers,
+            texture_views,
+        );
+    }
+
+    // The size of the current render target.
+    fn target_size(&self) -> (u32, u32) {
+        match &self.texture_target {
+            Some(target) => (target.width, target.height),
+            None => self.back_buffer_size,
+        }
+    }
+
+    fn apply_dynamic_state(&self, render_pass: &mut RenderPass) {
+        let (width, height) = self.target_size();
+        // wgpu requires the scissor rectangle to be inside of the render target.
+        let (x, y, scissor_width, scissor_height) = match self.scissor_rectangle {
+            Some(rect) => {
+                let x = rect.x.min(width);
+                let y = rect.y.min(height);
+                (x, y, rect.width.min(width - x), rect.height.min(height - y))
+            }
+            None => (0, 0, width, height),
+        };
+        render_pass.set_scissor_rect(x, y, scissor_width, scissor_height);
+        render_pass.set_stencil_reference(self.stencil_reference);
+    }
+
+    // Performs a `clear()` that hasn't been used by a render pass yet.
+    fn flush_pending_clear(
+        &self,
+        command_encoder: &mut CommandEncoder,
+        pending_clear: PendingClear,
+    ) {
+        if pending_clear.is_empty() || self.texture_view.is_none() {
+            return;
+        }
+        let (color_view, depth_stencil_view) = target_views(
+            &self.texture_view,
+            &self.depth_stencil_view,
+            &self.texture_target,
+        );
+        begin_render_pass(
+            color_view,
+            depth_stencil_view,
+            command_encoder,
+            pending_clear,
+        );
+    }
+
     // Executes all of the given `commands` in response to a `Context3D.present` call.
     pub(crate) fn present<'gc>(
         &mut self,
@@ -188,7 +314,7 @@ impl WgpuContext3D {
                 .create_command_encoder(&wgpu::CommandEncoderDescriptor {
                     label: Some("Context3D command encoder"),
                 });
-        let mut compiled_pipeline: Option<wgpu::RenderPipeline> = self.compiled_pipeline.take();
+        let mut compiled_pipeline: Option<Rc<wgpu::RenderPipeline>> = self.compiled_pipeline.take();
         let mut render_pass = RenderPassWrapper::new(None);
 
         let mut buffer_command_encoder =
@@ -203,9 +329,9 @@ impl WgpuContext3D {
         // was an explicit `Clear` command. Otherwise, we want to preserve the
         // contents of the previous render pass.
         //
-        // This variable gets set to `Some` when we encounter a `Clear` command,
-        // and then gets set to `None` when we create a new render pass.
-        let mut clear_color = None;
+        // This variable gets filled in when we encounter a `Clear` command,
+        // and then gets reset when we create a new render pass.
+        let mut pending_clear = PendingClear::default();
 
         // After a call to 'present()', the Context3D API requires a call to 'clear'
         // before any new calls to 'drawTriangles'. This tracks whether we've
@@ -220,28 +346,28 @@ impl WgpuContext3D {
                     green,
                     blue,
                     alpha,
-                    depth: _,
-                    stencil: _,
+                    depth,
+                    stencil,
                     mask,
                 } => {
-                    if *mask != COLOR_MASK | DEPTH_MASK | STENCIL_MASK {
-                        log::warn!(
-                            "Context3D::present: Clear command with mask {:x} not implemeneted",
-                            mask
-                        );
+                    if mask & COLOR_MASK != 0 {
+                        pending_clear.color = Some(wgpu::Color {
+                            r: *red,
+                            g: *green,
+                            b: *blue,
+                            a: *alpha,
+                        });
+                    }
+                    if mask & DEPTH_MASK != 0 {
+                        pending_clear.depth = Some(*depth as f32);
+                    }
+                    if mask & STENCIL_MASK != 0 {
+                        pending_clear.stencil = Some(*stencil);
                     }
-
-                    clear_color = Some(wgpu::Color {
-                        r: *red,
-                        g: *green,
-                        b: *blue,
-                        a: *alpha,
-                    });
                     seen_clear_command = true;
-                    // FIXME - clear depth and stencil buffers once we implement them
 
                     // Finish the current render pass - our next DrawTriangles command will create
-                    // a new RenderPass using our `clear_color`.
+                    // a new RenderPass using our `pending_clear`.
                     finish_render_pass!(render_pass);
                 }
                 Context3DCommand::ConfigureBackBuffer {
@@ -257,10 +383,6 @@ impl WgpuContext3D {
                             "configureBackBuffer: anti_alias={anti_alias} is not yet implemented"
                         );
                     }
-                    if *depth_and_stencil {
-                        log::warn!("configureBackBuffer: depth_and_stencil is not yet implemented");
-                    }
-
                     let texture_label = create_debug_label!("Render target texture");
                     let format = wgpu::TextureFormat::Rgba8Unorm;
 
@@ -285,6 +407,14 @@ impl WgpuContext3D {
 
                     finish_render_pass!(render_pass);
                     self.texture_view = Some(wgpu_texture.create_view(&Default::default()));
+                    self.depth_stencil_view = depth_and_stencil.then(|| {
+                        create_depth_stencil_view(&self.descriptors.device, *width, *height)
+                    });
+                    self.back_buffer_size = (*width, *height);
+                    if self.texture_target.is_none() {
+                        self.current_pipeline
+                            .set_has_depth_stencil(*depth_and_stencil);
+                    }
 
                     self.raw_texture_handle = BitmapHandle(Arc::new(Texture {
                         texture: Arc::new(wgpu_texture),
@@ -364,16 +494,24 @@ impl WgpuContext3D {
                     if new_pipeline.is_some() || render_pass.is_none() {
                         finish_render_pass!(render_pass);
 
-                        *render_pass = Some(make_render_pass(
-                            self.texture_view.as_ref().unwrap(),
+                        let (color_view, depth_stencil_view) = target_views(
+                            &self.texture_view,
+                            &self.depth_stencil_view,
+                            &self.texture_target,
+                        );
+                        let mut new_render_pass = make_render_pass(
+                            color_view,
+                            depth_stencil_view,
                             &mut render_command_encoder,
                             &self.bind_group,
                             &self.vertex_attributes,
                             // Subsequent draw calls (without an intermediate 'clear()' call)
-                            // will use a clear color of None. This ensures that by itself,
+                            // will not clear anything. This ensures that by itself,
                             // re-creating the render pass has no effect on the output
-                            clear_color.take(),
-                        ));
+                            std::mem::take(&mut pending_clear),
+                        );
+                        self.apply_dynamic_state(&mut new_render_pass);
+                        *render_pass = Some(new_render_pass);
 
                         if let Some(new_pipeline) = new_pipeline {
                             compiled_pipeline = Some(new_pipeline);
@@ -491,10 +629,255 @@ impl WgpuContext3D {
                 Context3DCommand::SetCulling { face } => {
                     self.current_pipeline.set_culling(*face);
                 }
+                Context3DCommand::UploadToTexture {
+                    texture,
+                    side,
+                    mip_level,
+                    data,
+                } => {
+                    let texture: &TextureWrapper =
+                        texture.as_any().downcast_ref::<TextureWrapper>().unwrap();
+
+                    if *mip_level >= texture.mip_level_count {
+                        log::warn!(
+                            "Context3D::present: Ignoring upload to mip level {mip_level} of a texture with {} levels",
+                            texture.mip_level_count
+                        );
+                        continue;
+                    }
+
+                    let width = (texture.width >> mip_level).max(1);
+                    let height = (texture.height >> mip_level).max(1);
+                    if data.len() < (width * height * 4) as usize {
+                        log::warn!(
+                            "Context3D::present: Not enough data to upload a {width}x{height} texture"
+                        );
+                        continue;
+                    }
+
+                    self.descriptors.queue.write_texture(
+                        wgpu::ImageCopyTexture {
+                            texture: &texture.texture,
+                            mip_level: *mip_level,
+                            origin: wgpu::Origin3d {
+                                x: 0,
+                                y: 0,
+                                z: *side,
+                            },
+                            aspect: wgpu::TextureAspect::All,
+                        },
+                        data,
+                        wgpu::ImageDataLayout {
+                            offset: 0,
+                            bytes_per_row: NonZeroU32::new(width * 4),
+                            rows_per_image: None,
+                        },
+                        Extent3d {
+                            width,
+                            height,
+                            depth_or_array_layers: 1,
+                        },
+                    );
+                }
+                Context3DCommand::SetTextureAt { sampler, texture } => {
+                    let texture = texture
+                        .clone()
+                        .map(|texture| texture.into_any_rc().downcast::<TextureWrapper>().unwrap());
+
+                    finish_render_pass!(render_pass);
+
+                    self.bound_textures[*sampler as usize] = texture;
+                    self.rebuild_bind_group();
+                }
+                Context3DCommand::SetSamplerStateAt {
+                    sampler,
+                    wrap,
+                    filter,
+                    mip_filter,
+                } => {
+                    let wrapping = match wrap {
+                        Context3DWrapMode::Clamp => naga_agal::Wrapping::Clamp,
+                        Context3DWrapMode::ClampURepeatV => naga_agal::Wrapping::ClampURepeatV,
+                        Context3DWrapMode::Repeat => naga_agal::Wrapping::Repeat,
+                        Context3DWrapMode::RepeatUClampV => naga_agal::Wrapping::RepeatUClampV,
+                    };
+                    let filter = match filter {
+                        Context3DTextureFilter::Nearest => naga_agal::Filter::Nearest,
+                        Context3DTextureFilter::Linear => naga_agal::Filter::Linear,
+                        Context3DTextureFilter::Anisotropic2X => naga_agal::Filter::Anisotropic2x,
+                        Context3DTextureFilter::Anisotropic4X => naga_agal::Filter::Anisotropic4x,
+                        Context3DTextureFilter::Anisotropic8X => naga_agal::Filter::Anisotropic8x,
+                        Context3DTextureFilter::Anisotropic16X => naga_agal::Filter::Anisotropic16x,
+                    };
+                    let mipmap = match mip_filter {
+                        Context3DMipFilter::MipNone => naga_agal::Mipmap::Disable,
+                        Context3DMipFilter::MipNearest => naga_agal::Mipmap::Nearest,
+                        Context3DMipFilter::MipLinear => naga_agal::Mipmap::Linear,
+                    };
+
+                    finish_render_pass!(render_pass);
+
+                    self.current_pipeline.set_sampler_override(
+                        *sampler as usize,
+                        SamplerOverride {
+                            wrapping,
+                            filter,
+                            mipmap,
+                        },
+                    );
+                }
+                Context3DCommand::SetBlendFactors {
+                    source_factor,
+                    destination_factor,
+                } => {
+                    self.current_pipeline.set_blend_factors(
+                        convert_blend_factor(*source_factor),
+                        convert_blend_factor(*destination_factor),
+                    );
+                }
+                Context3DCommand::SetDepthTest {
+                    depth_mask,
+                    pass_compare_mode,
+                } => {
+                    self.current_pipeline
+                        .set_depth_test(*depth_mask, convert_compare_mode(*pass_compare_mode));
+                }
+                Context3DCommand::SetStencilActions {
+                    triangle_face,
+                    compare_mode,
+                    action_on_both_pass,
+                    action_on_depth_fail,
+                    action_on_depth_pass_stencil_fail,
+                } => {
+                    self.current_pipeline.set_stencil_actions(
+                        *triangle_face,
+                        wgpu::StencilFaceState {
+                            compare: convert_compare_mode(*compare_mode),
+                            fail_op: convert_stencil_action(*action_on_depth_pass_stencil_fail),
+                            depth_fail_op: convert_stencil_action(*action_on_depth_fail),
+                            pass_op: convert_stencil_action(*action_on_both_pass),
+                        },
+                    );
+                }
+                Context3DCommand::SetStencilReferenceValue {
+                    reference_value,
+                    read_mask,
+                    write_mask,
+                } => {
+                    self.current_pipeline
+                        .set_stencil_masks(*read_mask, *write_mask);
+                    self.stencil_reference = *reference_value;
+                    if let Some(render_pass) = render_pass.as_mut() {
+                        render_pass.set_stencil_reference(*reference_value);
+                    }
+                }
+                Context3DCommand::SetScissorRectangle { rectangle } => {
+                    self.scissor_rectangle = *rectangle;
+                    if let Some(render_pass) = render_pass.as_mut() {
+                        self.apply_dynamic_state(render_pass);
+                    }
+                }
+                Context3DCommand::SetColorMask {
+                    red,
+                    green,
+                    blue,
+                    alpha,
+                } => {
+                    let mut color_mask = wgpu::ColorWrites::empty();
+                    color_mask.set(wgpu::ColorWrites::RED, *red);
+                    color_mask.set(wgpu::ColorWrites::GREEN, *green);
+                    color_mask.set(wgpu::ColorWrites::BLUE, *blue);
+                    color_mask.set(wgpu::ColorWrites::ALPHA, *alpha);
+                    self.current_pipeline.set_color_mask(color_mask);
+                }
+                Context3DCommand::SetRenderToTexture {
+                    texture,
+                    enable_depth_and_stencil,
+                    anti_alias,
+                    surface_selector,
+                    color_output_index,
+                } => {
+                    let texture: &TextureWrapper =
+                        texture.as_any().downcast_ref::<TextureWrapper>().unwrap();
+
+                    if *anti_alias != 0 {
+                        log::warn!(
+                            "setRenderToTexture: anti_alias={anti_alias} is not yet implemented"
+                        );
+                    }
+                    if *color_output_index != 0 {
+                        log::warn!(
+                            "setRenderToTexture: color_output_index={color_output_index} is not yet implemented"
+                        );
+                    }
+
+                    finish_render_pass!(render_pass);
+                    // A `clear()` applies to the render target that was current when it was called.
+                    self.flush_pending_clear(
+                        &mut render_command_encoder,
+                        std::mem::take(&mut pending_clear),
+                    );
+
+                    let depth_stencil = if *enable_depth_and_stencil {
+                        let depth_stencil = match &self.texture_depth_stencil {
+                            Some(depth_stencil)
+                                if depth_stencil.width == texture.width
+                                    && depth_stencil.height == texture.height =>
+                            {
+                                depth_stencil.clone()
+                            }
+                            _ => Rc::new(DepthStencilBuffer {
+                                view: create_depth_stencil_view(
+                                    &self.descriptors.device,
+                                    texture.width,
+                                    texture.height,
+                                ),
+                                width: texture.width,
+                                height: texture.height,
+                            }),
+                        };
+                        self.texture_depth_stencil = Some(depth_stencil.clone());
+                        Some(depth_stencil)
+                    } else {
+                        None
+                    };
+
+                    // We always render to the first mip level of the texture.
+                    let view = texture.texture.create_view(&wgpu::TextureViewDescriptor {
+                        dimension: Some(wgpu::TextureViewDimension::D2),
+                        base_mip_level: 0,
+                        mip_level_count: NonZeroU32::new(1),
+                        base_array_layer: if texture.cube { *surface_selector } else { 0 },
+                        array_layer_count: NonZeroU32::new(1),
+                        ..Default::default()
+                    });
+
+                    self.texture_target = Some(TextureTarget {
+                        view,
+                        depth_stencil,
+                        width: texture.width,
+                        height: texture.height,
+                    });
+                    self.current_pipeline
+                        .set_has_depth_stencil(*enable_depth_and_stencil);
+                }
+                Context3DCommand::SetRenderToBackBuffer => {
+                    finish_render_pass!(render_pass);
+                    self.flush_pending_clear(
+                        &mut render_command_encoder,
+                        std::mem::take(&mut pending_clear),
+                    );
+
+                    self.texture_target = None;
+                    self.current_pipeline
+                        .set_has_depth_stencil(self.depth_stencil_view.is_some());
+                }
             }
         }
 
         finish_render_pass!(render_pass);
+        // Perform a `clear()` that wasn't followed by any draw calls.
+        self.flush_pending_clear(&mut render_command_encoder, pending_clear);
 
         self.buffer_staging_belt.finish();
 
@@ -522,9 +905,48 @@ pub struct VertexBufferWrapper(wgpu::Buffer);
 #[collect(require_static)]
 pub struct ShaderModuleAgal(Vec<u8>);
 
+#[derive(Collect)]
+#[collect(require_static)]
+pub struct TextureWrapper {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    width: u32,
+    height: u32,
+    mip_level_count: u32,
+    cube: bool,
+}
+
+pub struct TextureTarget {
+    view: wgpu::TextureView,
+    depth_stencil: Option<Rc<DepthStencilBuffer>>,
+    width: u32,
+    height: u32,
+}
+
+pub struct DepthStencilBuffer {
+    view: wgpu::TextureView,
+    width: u32,
+    height: u32,
+}
+
+/// The buffers to clear when the next render pass begins.
+#[derive(Default)]
+struct PendingClear {
+    color: Option<wgpu::Color>,
+    depth: Option<f32>,
+    stencil: Option<u32>,
+}
+
+impl PendingClear {
+    fn is_empty(&self) -> bool {
+        self.color.is_none() && self.depth.is_none() && self.stencil.is_none()
+    }
+}
+
 impl IndexBuffer for IndexBufferWrapper {}
 impl VertexBuffer for VertexBufferWrapper {}
 impl ShaderModule for ShaderModuleAgal {}
+impl ruffle_render::backend::Texture for TextureWrapper {}
 
 // Context3D.setVertexBufferAt supports up to 8 vertex buffer attributes
 const MAX_VERTEX_ATTRIBUTES: usize = 8;
@@ -576,6 +998,39 @@ impl Context3D for WgpuContext3D {
         Rc::new(VertexBufferWrapper(buffer))
     }
 
+    fn create_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: Context3DTextureFormat,
+        _optimize_for_render_to_texture: bool,
+        _streaming_levels: u32,
+    ) -> Rc<dyn ruffle_render::backend::Texture> {
+        let mip_level_count = u32::BITS - width.max(height).max(1).leading_zeros();
+        Rc::new(self.create_texture_wrapper(width, height, format, mip_level_count, false))
+    }
+
+    fn create_rectangle_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: Context3DTextureFormat,
+        _optimize_for_render_to_texture: bool,
+    ) -> Rc<dyn ruffle_render::backend::Texture> {
+        Rc::new(self.create_texture_wrapper(width, height, format, 1, false))
+    }
+
+    fn create_cube_texture(
+        &mut self,
+        size: u32,
+        format: Context3DTextureFormat,
+        _optimize_for_render_to_texture: bool,
+        _streaming_levels: u32,
+    ) -> Rc<dyn ruffle_render::backend::Texture> {
+        let mip_level_count = u32::BITS - size.max(1).leading_zeros();
+        Rc::new(self.create_texture_wrapper(size, size, format, mip_level_count, true))
+    }
+
     fn disposed_index_buffer_handle(&self) -> Rc<dyn IndexBuffer> {
         todo!()
     }
@@ -585,29 +1040,78 @@ impl Context3D for WgpuContext3D {
     }
 }
 
-// This cannot be a method on `self`, because we need to only borrow certain fields
-// with the long lifetime 'a
-fn make_render_pass<'a>(
-    texture_view: &'a wgpu::TextureView,
+// Returns the color and depth/stencil views of the current render target.
+fn target_views<'a>(
+    back_buffer: &'a Option<wgpu::TextureView>,
+    back_buffer_depth_stencil: &'a Option<wgpu::TextureView>,
+    texture_target: &'a Option<TextureTarget>,
+) -> (&'a wgpu::TextureView, Option<&'a wgpu::TextureView>) {
+    match texture_target {
+        Some(target) => (
+            &target.view,
+            target
+                .depth_stencil
+                .as_ref()
+                .map(|depth_stencil| &depth_stencil.view),
+        ),
+        None => (
+            back_buffer.as_ref().unwrap(),
+            back_buffer_depth_stencil.as_ref(),
+        ),
+    }
+}
+
+fn begin_render_pass<'a>(
+    color_view: &'a wgpu::TextureView,
+    depth_stencil_view: Option<&'a wgpu::TextureView>,
     command_encoder: &'a mut CommandEncoder,
-    bind_group: &'a BindGroup,
-    vertex_attributes: &'a [Option<VertexAttributeInfo>; MAX_VERTEX_ATTRIBUTES],
-    clear_color: Option<wgpu::Color>,
+    clear: PendingClear,
 ) -> RenderPass<'a> {
-    let load = match clear_color {
-        Some(_) => wgpu::LoadOp::Clear(clear_color.unwrap()),
+    let load = match clear.color {
+        Some(color) => wgpu::LoadOp::Clear(color),
         None => wgpu::LoadOp::Load,
     };
 
-    let mut pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+    command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
         label: Some("Context3D render pass"),
         color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-            view: texture_view,
+            view: color_view,
             resolve_target: None,
             ops: wgpu::Operations { load, store: true },
         })],
-        depth_stencil_attachment: None,
-    });
+        depth_stencil_attachment: depth_stencil_view.map(|view| {
+            wgpu::RenderPassDepthStencilAttachment {
+                view,
+                depth_ops: Some(wgpu::Operations {
+                    load: match clear.depth {
+                        Some(depth) => wgpu::LoadOp::Clear(depth),
+                        None => wgpu::LoadOp::Load,
+                    },
+                    store: true,
+                }),
+                stencil_ops: Some(wgpu::Operations {
+                    load: match clear.stencil {
+                        Some(stencil) => wgpu::LoadOp::Clear(stencil),
+                        None => wgpu::LoadOp::Load,
+                    },
+                    store: true,
+                }),
+            }
+        }),
+    })
+}
+
+// This cannot be a method on `self`, because we need to only borrow certain fields
+// with the long lifetime 'a
+fn make_render_pass<'a>(
+    color_view: &'a wgpu::TextureView,
+    depth_stencil_view: Option<&'a wgpu::TextureView>,
+    command_encoder: &'a mut CommandEncoder,
+    bind_group: &'a BindGroup,
+    vertex_attributes: &'a [Option<VertexAttributeInfo>; MAX_VERTEX_ATTRIBUTES],
+    clear: PendingClear,
+) -> RenderPass<'a> {
+    let mut pass = begin_render_pass(color_view, depth_stencil_view, command_encoder, clear);
     pass.set_bind_group(0, bind_group, &[]);
     for (i, attr) in vertex_attributes.iter().enumerate() {
         if let Some(attr) = attr {
@@ -616,3 +1120,240 @@ fn make_render_pass<'a>(
     }
     pass
 }
+
+fn create_depth_stencil_view(device: &wgpu::Device, width: u32, height: u32) -> wgpu::TextureView {
+    let label = create_debug_label!("Context3D depth and stencil buffer");
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: label.as_deref(),
+        size: Extent3d {
+            width: width.max(1),
+            height: height.max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: DEPTH_STENCIL_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+    });
+    texture.create_view(&Default::default())
+}
+
+fn convert_blend_factor(factor: Context3DBlendFactor) -> wgpu::BlendFactor {
+    match factor {
+        Context3DBlendFactor::DestinationAlpha => wgpu::BlendFactor::DstAlpha,
+        Context3DBlendFactor::DestinationColor => wgpu::BlendFactor::Dst,
+        Context3DBlendFactor::One => wgpu::BlendFactor::One,
+        Context3DBlendFactor::OneMinusDestinationAlpha => wgpu::BlendFactor::OneMinusDstAlpha,
+        Context3DBlendFactor::OneMinusDestinationColor => wgpu::BlendFactor::OneMinusDst,
+        Context3DBlendFactor::OneMinusSourceAlpha => wgpu::BlendFactor::OneMinusSrcAlpha,
+        Context3DBlendFactor::OneMinusSourceColor => wgpu::BlendFactor::OneMinusSrc,
+        Context3DBlendFactor::SourceAlpha => wgpu::BlendFactor::SrcAlpha,
+        Context3DBlendFactor::SourceColor => wgpu::BlendFactor::Src,
+        Context3DBlendFactor::Zero => wgpu::BlendFactor::Zero,
+    }
+}
+
+fn convert_compare_mode(mode: Context3DCompareMode) -> wgpu::CompareFunction {
+    match mode {
+        Context3DCompareMode::Always => wgpu::CompareFunction::Always,
+        Context3DCompareMode::Equal => wgpu::CompareFunction::Equal,
+        Context3DCompareMode::Greater => wgpu::CompareFunction::Greater,
+        Context3DCompareMode::GreaterEqual => wgpu::CompareFunction::GreaterEqual,
+        Context3DCompareMode::Less => wgpu::CompareFunction::Less,
+        Context3DCompareMode::LessEqual => wgpu::CompareFunction::LessEqual,
+        Context3DCompareMode::Never => wgpu::CompareFunction::Never,
+        Context3DCompareMode::NotEqual => wgpu::CompareFunction::NotEqual,
+    }
+}
+
+fn convert_stencil_action(action: Context3DStencilAction) -> wgpu::StencilOperation {
+    match action {
+        Context3DStencilAction::DecrementSaturate => wgpu::StencilOperation::DecrementClamp,
+        Context3DStencilAction::DecrementWrap => wgpu::StencilOperation::DecrementWrap,
+        Context3DStencilAction::IncrementSaturate => wgpu::StencilOperation::IncrementClamp,
+        Context3DStencilAction::IncrementWrap => wgpu::StencilOperation::IncrementWrap,
+        Context3DStencilAction::Invert => wgpu::StencilOperation::Invert,
+        Context3DStencilAction::Keep => wgpu::StencilOperation::Keep,
+        Context3DStencilAction::Set => wgpu::StencilOperation::Replace,
+        Context3DStencilAction::Zero => wgpu::StencilOperation::Zero,
+    }
+}
+
+fn create_bind_group_layout(
+    device: &wgpu::Device,
+    texture_view_dimensions: &[wgpu::TextureViewDimension; MAX_TEXTURES],
+) -> BindGroupLayout {
+    let mut entries = vec![
+        wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        },
+        wgpu::BindGroupLayoutEntry {
+            binding: 1,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        },
+    ];
+
+    for i in 0..naga_agal::NUM_SAMPLERS {
+        entries.push(wgpu::BindGroupLayoutEntry {
+            binding: naga_agal::SAMPLER_START_BIND_INDEX + i,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+            count: None,
+        });
+    }
+
+    for (i, view_dimension) in texture_view_dimensions.iter().enumerate() {
+        entries.push(wgpu::BindGroupLayoutEntry {
+            binding: naga_agal::TEXTURE_START_BIND_INDEX + i as u32,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: *view_dimension,
+                multisampled: false,
+            },
+            count: None,
+        });
+    }
+
+    let globals_layout_label = create_debug_label!("Globals bind group layout");
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: globals_layout_label.as_deref(),
+        entries: &entries,
+    })
+}
+
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &BindGroupLayout,
+    vertex_shader_uniforms: &wgpu::Buffer,
+    fragment_shader_uniforms: &wgpu::Buffer,
+    samplers: &[wgpu::Sampler],
+    texture_views: [&wgpu::TextureView; MAX_TEXTURES],
+) -> BindGroup {
+    let mut entries = vec![
+        BindGroupEntry {
+            binding: 0,
+            resource: BindingResource::Buffer(wgpu::BufferBinding {
+                buffer: vertex_shader_uniforms,
+                offset: 0,
+                size: Some(NonZeroU64::new(VERTEX_SHADER_UNIFORMS_BUFFER_SIZE).unwrap()),
+            }),
+        },
+        BindGroupEntry {
+            binding: 1,
+            resource: BindingResource::Buffer(wgpu::BufferBinding {
+                buffer: fragment_shader_uniforms,
+                offset: 0,
+                size: Some(NonZeroU64::new(FRAGMENT_SHADER_UNIFORMS_BUFFER_SIZE).unwrap()),
+            }),
+        },
+    ];
+
+    for (i, sampler) in samplers.iter().enumerate() {
+        entries.push(BindGroupEntry {
+            binding: naga_agal::SAMPLER_START_BIND_INDEX + i as u32,
+            resource: BindingResource::Sampler(sampler),
+        });
+    }
+
+    for (i, view) in texture_views.into_iter().enumerate() {
+        entries.push(BindGroupEntry {
+            binding: naga_agal::TEXTURE_START_BIND_INDEX + i as u32,
+            resource: BindingResource::TextureView(view),
+        });
+    }
+
+    let bind_group_label = create_debug_label!("Bind group");
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: bind_group_label.as_deref(),
+        layout,
+        entries: &entries,
+    })
+}
+
+// Creates one sampler for each combination of wrapping and filter,
+// ordered by their binding id (see `naga_agal::sampler_binding`).
+fn create_samplers(device: &wgpu::Device) -> Vec<wgpu::Sampler> {
+    let mut samplers = Vec::with_capacity(naga_agal::NUM_SAMPLERS as usize);
+    for wrapping in [
+        naga_agal::Wrapping::Clamp,
+        naga_agal::Wrapping::Repeat,
+        naga_agal::Wrapping::ClampURepeatV,
+        naga_agal::Wrapping::RepeatUClampV,
+    ] {
+        for filter in [naga_agal::Filter::Nearest, naga_agal::Filter::Linear] {
+            debug_assert_eq!(
+                naga_agal::sampler_binding(wrapping, filter),
+                naga_agal::SAMPLER_START_BIND_INDEX + samplers.len() as u32
+            );
+
+            let (address_mode_u, address_mode_v) = match wrapping {
+                naga_agal::Wrapping::Clamp => (
+                    wgpu::AddressMode::ClampToEdge,
+                    wgpu::AddressMode::ClampToEdge,
+                ),
+                naga_agal::Wrapping::Repeat => {
+                    (wgpu::AddressMode::Repeat, wgpu::AddressMode::Repeat)
+                }
+                naga_agal::Wrapping::ClampURepeatV => {
+                    (wgpu::AddressMode::ClampToEdge, wgpu::AddressMode::Repeat)
+                }
+                naga_agal::Wrapping::RepeatUClampV => {
+                    (wgpu::AddressMode::Repeat, wgpu::AddressMode::ClampToEdge)
+                }
+            };
+            // The shader samples level 0 directly when mipmapping is disabled,
+            // so the mipmap filter only matters for `mipnearest` and `miplinear`.
+            let filter_mode = match filter {
+                naga_agal::Filter::Nearest => wgpu::FilterMode::Nearest,
+                // FIXME - implement anisotropic filtering
+                _ => wgpu::FilterMode::Linear,
+            };
+
+            let label = create_debug_label!("Context3D sampler {:?} {:?}", wrapping, filter);
+            samplers.push(device.create_sampler(&wgpu::SamplerDescriptor {
+                label: label.as_deref(),
+                address_mode_u,
+                address_mode_v,
+                address_mode_w: wgpu::AddressMode::ClampToEdge,
+                mag_filter: filter_mode,
+                min_filter: filter_mode,
+                mipmap_filter: filter_mode,
+                ..Default::default()
+            }));
+        }
+    }
+    samplers
+}
+
+fn create_dummy_texture(device: &wgpu::Device) -> wgpu::TextureView {
+    let texture_label = create_debug_label!("Context3D dummy texture");
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: texture_label.as_deref(),
+        size: Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING,
+    });
+    texture.create_view(&Default::default())
+}
@@ -1,3 +1,4 @@
+use crate::filters::Filters;
 use crate::layouts::BindLayouts;
 use crate::pipelines::VERTEX_BUFFERS_DESCRIPTION;
 use crate::shaders::Shaders;
@@ -13,6 +14,7 @@ pub struct Descriptors {
     pub bitmap_samplers: BitmapSamplers,
     pub bind_layouts: BindLayouts,
     pub quad: Quad,
+    pub filters: Filters,
     copy_srgb_pipeline: Mutex<FnvHashMap<wgpu::TextureFormat, Arc<wgpu::RenderPipeline>>>,
     shaders: Shaders,
     pipelines: Mutex<FnvHashMap<(u32, wgpu::TextureFormat), Arc<Pipelines>>>,
@@ -25,6 +27,7 @@ impl Descriptors {
         let bitmap_samplers = BitmapSamplers::new(&device);
         let shaders = Shaders::new(&device);
         let quad = Quad::new(&device);
+        let filters = Filters::new(&device, &shaders);
 
         Self {
             adapter,
@@ -34,6 +37,7 @@ impl Descriptors {
             bitmap_samplers,
             bind_layouts,
             quad,
+            filters,
             copy_srgb_pipeline: Default::default(),
             shaders,
             pipelines: Default::default(),
@@ -0,0 +1,643 @@
+use crate::as_texture;
+use crate::descriptors::Descriptors;
+use crate::pipelines::VERTEX_BUFFERS_DESCRIPTION;
+use crate::shaders::Shaders;
+use crate::utils::create_buffer_with_data;
+use bytemuck::{Pod, Zeroable};
+use ruffle_render::filters::{
+    BevelFilterType, BlurFilter, ColorMatrixFilter, ConvolutionFilter, DisplacementMapFilter,
+    DisplacementMapFilterMode, Filter, GradientFilter,
+};
+use std::sync::Arc;
+use swf::Color;
+
+/// The format of every texture that filters read from and render into.
+pub const FILTER_TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+/// The largest kernel supported by the convolution filter.
+const MAX_CONVOLUTION_SIZE: usize = 256;
+
+/// A texture that is being filtered. Colors are premultiplied by alpha.
+pub struct FilterTexture {
+    pub texture: Arc<wgpu::Texture>,
+    pub view: wgpu::TextureView,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl FilterTexture {
+    pub fn new(device: &wgpu::Device, width: u32, height: u32) -> Self {
+        let label = create_debug_label!("Filter texture");
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: label.as_deref(),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: FILTER_TEXTURE_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+        });
+        let view = texture.create_view(&Default::default());
+        Self {
+            texture: Arc::new(texture),
+            view,
+            width,
+            height,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct BlurUniforms {
+    direction: [f32; 2],
+    size: f32,
+    _padding: f32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct ColorMatrixUniforms {
+    rows: [[f32; 4]; 4],
+    offset: [f32; 4],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct ConvolutionUniforms {
+    matrix: [[f32; 4]; MAX_CONVOLUTION_SIZE / 4],
+    default_color: [f32; 4],
+    bias: f32,
+    divisor: f32,
+    matrix_x: f32,
+    matrix_y: f32,
+    preserve_alpha: f32,
+    clamp: f32,
+    _padding: [f32; 2],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct DisplacementMapUniforms {
+    color: [f32; 4],
+    map_point: [f32; 2],
+    scale: [f32; 2],
+    component_x: f32,
+    component_y: f32,
+    mode: f32,
+    _padding: f32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct ShadowUniforms {
+    color: [f32; 4],
+    highlight_color: [f32; 4],
+    offset: [f32; 2],
+    strength: f32,
+    mode: f32,
+    composite: f32,
+    invert: f32,
+    knockout: f32,
+    hide_object: f32,
+    gradient: [[f32; 4]; 256],
+}
+
+impl Default for ShadowUniforms {
+    fn default() -> Self {
+        Self::zeroed()
+    }
+}
+
+/// The kind of effect drawn by the shadow shader.
+#[derive(Clone, Copy)]
+enum ShadowMode {
+    Glow = 0,
+    Bevel = 1,
+    GradientGlow = 2,
+    GradientBevel = 3,
+}
+
+/// Where the shadow shader draws its effect, relative to the source.
+#[derive(Clone, Copy)]
+enum Composite {
+    Inner = 0,
+    Outer = 1,
+    Full = 2,
+}
+
+impl From<BevelFilterType> for Composite {
+    fn from(bevel_type: BevelFilterType) -> Self {
+        match bevel_type {
+            BevelFilterType::Inner => Composite::Inner,
+            BevelFilterType::Outer => Composite::Outer,
+            BevelFilterType::Full => Composite::Full,
+        }
+    }
+}
+
+fn color_to_rgba(color: &Color) -> [f32; 4] {
+    [
+        f32::from(color.r) / 255.0,
+        f32::from(color.g) / 255.0,
+        f32::from(color.b) / 255.0,
+        f32::from(color.a) / 255.0,
+    ]
+}
+
+fn bool_to_f32(value: bool) -> f32 {
+    if value {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Returns the offset of a shadow or bevel, in pixels.
+fn shadow_offset(angle: f32, distance: f32) -> [f32; 2] {
+    [angle.cos() * distance, angle.sin() * distance]
+}
+
+fn gradient_lut(gradient: &GradientFilter) -> [[f32; 4]; 256] {
+    let mut lut = [[0.0; 4]; 256];
+    for (i, color) in lut.iter_mut().enumerate() {
+        *color = gradient.sample(i as f32 / 255.0);
+    }
+    lut
+}
+
+/// The pipelines used to apply bitmap filters on the GPU.
+///
+/// Each filter is made of one or more passes, which each draw a quad covering
+/// the entire target texture, reading from the previous pass.
+#[derive(Debug)]
+pub struct Filters {
+    bind_layout: wgpu::BindGroupLayout,
+    blur: wgpu::RenderPipeline,
+    color_matrix: wgpu::RenderPipeline,
+    convolution: wgpu::RenderPipeline,
+    displacement_map: wgpu::RenderPipeline,
+    shadow: wgpu::RenderPipeline,
+}
+
+impl Filters {
+    pub fn new(device: &wgpu::Device, shaders: &Shaders) -> Self {
+        let texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                multisampled: false,
+                sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                view_dimension: wgpu::TextureViewDimension::D2,
+            },
+            count: None,
+        };
+        let bind_layout_label = create_debug_label!("Filter bind group layout");
+        let bind_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: bind_layout_label.as_deref(),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                texture_entry(1),
+                texture_entry(2),
+            ],
+        });
+
+        let pipeline_layout_label = create_debug_label!("Filter pipeline layout");
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: pipeline_layout_label.as_deref(),
+            bind_group_layouts: &[&bind_layout],
+            push_constant_ranges: &[],
+        });
+
+        let create_pipeline = |name: &str, shader: &wgpu::ShaderModule| {
+            let label = create_debug_label!("{} filter pipeline", name);
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: label.as_deref(),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: shader,
+                    entry_point: "main_vertex",
+                    buffers: &VERTEX_BUFFERS_DESCRIPTION,
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: shader,
+                    entry_point: "main_fragment",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: FILTER_TEXTURE_FORMAT,
+                        blend: Some(wgpu::BlendState::REPLACE),
+                        write_mask: Default::default(),
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::default(),
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState {
+                    count: 1,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+            })
+        };
+
+        Self {
+            blur: create_pipeline("Blur", &shaders.blur_filter_shader),
+            color_matrix: create_pipeline("Color matrix", &shaders.color_matrix_filter_shader),
+            convolution: create_pipeline("Convolution", &shaders.convolution_filter_shader),
+            displacement_map: create_pipeline(
+                "Displacement map",
+                &shaders.displacement_map_filter_shader,
+            ),
+            shadow: create_pipeline("Shadow", &shaders.shadow_filter_shader),
+            bind_layout,
+        }
+    }
+
+    /// Applies `filter` to `source`, recording the passes into `encoder`.
+    /// The result has the same dimensions as the source.
+    pub fn apply(
+        &self,
+        descriptors: &Descriptors,
+        encoder: &mut wgpu::CommandEncoder,
+        filter: &Filter,
+        source: FilterTexture,
+    ) -> FilterTexture {
+        match filter {
+            Filter::BlurFilter(filter) => self
+                .blur(descriptors, encoder, filter, &source)
+                .unwrap_or(source),
+            Filter::ColorMatrixFilter(filter) => {
+                self.color_matrix(descriptors, encoder, filter, &source)
+            }
+            Filter::ConvolutionFilter(filter) => self
+                .convolution(descriptors, encoder, filter, &source)
+                .unwrap_or(source),
+            Filter::DisplacementMapFilter(filter) => self
+                .displacement_map(descriptors, encoder, filter, &source)
+                .unwrap_or(source),
+            Filter::GlowFilter(filter) => {
+                let blur = BlurFilter {
+                    blur_x: filter.blur_x,
+                    blur_y: filter.blur_y,
+                    quality: filter.quality,
+                };
+                let uniforms = ShadowUniforms {
+                    color: color_to_rgba(&filter.color),
+                    strength: filter.strength,
+                    mode: ShadowMode::Glow as i32 as f32,
+                    composite: if filter.inner {
+                        Composite::Inner
+                    } else {
+                        Composite::Outer
+                    } as i32 as f32,
+                    invert: bool_to_f32(filter.inner),
+                    knockout: bool_to_f32(filter.knockout),
+                    ..Default::default()
+                };
+                self.shadow(descriptors, encoder, &blur, &uniforms, &source)
+            }
+            Filter::DropShadowFilter(filter) => {
+                let blur = BlurFilter {
+                    blur_x: filter.blur_x,
+                    blur_y: filter.blur_y,
+                    quality: filter.quality,
+                };
+                let uniforms = ShadowUniforms {
+                    color: color_to_rgba(&filter.color),
+                    offset: shadow_offset(filter.angle, filter.distance),
+                    strength: filter.strength,
+                    mode: ShadowMode::Glow as i32 as f32,
+                    composite: if filter.inner {
+                        Composite::Inner
+                    } else {
+                        Composite::Outer
+                    } as i32 as f32,
+                    invert: bool_to_f32(filter.inner),
+                    knockout: bool_to_f32(filter.knockout),
+                    hide_object: bool_to_f32(filter.hide_object),
+                    ..Default::default()
+                };
+                self.shadow(descriptors, encoder, &blur, &uniforms, &source)
+            }
+            Filter::BevelFilter(filter) => {
+                let blur = BlurFilter {
+                    blur_x: filter.blur_x,
+                    blur_y: filter.blur_y,
+                    quality: filter.quality,
+                };
+                let uniforms = ShadowUniforms {
+                    color: color_to_rgba(&filter.shadow_color),
+                    highlight_color: color_to_rgba(&filter.highlight_color),
+                    offset: shadow_offset(filter.angle, filter.distance),
+                    strength: filter.strength,
+                    mode: ShadowMode::Bevel as i32 as f32,
+                    composite: Composite::from(filter.bevel_type) as i32 as f32,
+                    knockout: bool_to_f32(filter.knockout),
+                    ..Default::default()
+                };
+                self.shadow(descriptors, encoder, &blur, &uniforms, &source)
+            }
+            Filter::GradientGlowFilter(filter) => self.gradient_shadow(
+                descriptors,
+                encoder,
+                filter,
+                ShadowMode::GradientGlow,
+                &source,
+            ),
+            Filter::GradientBevelFilter(filter) => self.gradient_shadow(
+                descriptors,
+                encoder,
+                filter,
+                ShadowMode::GradientBevel,
+                &source,
+            ),
+        }
+    }
+
+    /// Draws a single pass of a filter into a new texture.
+    #[allow(clippy::too_many_arguments)]
+    fn pass<T: Pod>(
+        &self,
+        descriptors: &Descriptors,
+        encoder: &mut wgpu::CommandEncoder,
+        pipeline: &wgpu::RenderPipeline,
+        uniforms: &T,
+        source: &FilterTexture,
+        secondary: &wgpu::TextureView,
+    ) -> FilterTexture {
+        let target = FilterTexture::new(&descriptors.device, source.width, source.height);
+        let buffer = create_buffer_with_data(
+            &descriptors.device,
+            bytemuck::cast_slice(std::slice::from_ref(uniforms)),
+            wgpu::BufferUsages::UNIFORM,
+            create_debug_label!("Filter uniforms"),
+        );
+        let bind_group_label = create_debug_label!("Filter bind group");
+        let bind_group = descriptors
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: bind_group_label.as_deref(),
+                layout: &self.bind_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: buffer.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(&source.view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: wgpu::BindingResource::TextureView(secondary),
+                    },
+                ],
+            });
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: create_debug_label!("Filter pass").as_deref(),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &target.view,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: true,
+                },
+                resolve_target: None,
+            })],
+            depth_stencil_attachment: None,
+        });
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.set_vertex_buffer(0, descriptors.quad.vertices.slice(..));
+        render_pass.set_index_buffer(
+            descriptors.quad.indices.slice(..),
+            wgpu::IndexFormat::Uint32,
+        );
+        render_pass.draw_indexed(0..6, 0, 0..1);
+        drop(render_pass);
+
+        target
+    }
+
+    /// Blurs the source, returning `None` if the blur has no effect.
+    fn blur(
+        &self,
+        descriptors: &Descriptors,
+        encoder: &mut wgpu::CommandEncoder,
+        filter: &BlurFilter,
+        source: &FilterTexture,
+    ) -> Option<FilterTexture> {
+        let mut result: Option<FilterTexture> = None;
+        for _ in 0..filter.quality {
+            for (direction, size) in [([1.0, 0.0], filter.blur_x), ([0.0, 1.0], filter.blur_y)] {
+                // A box of a single pixel (or less) leaves the source unchanged.
+                if size <= 1.0 {
+                    continue;
+                }
+                let uniforms = BlurUniforms {
+                    direction,
+                    size,
+                    _padding: 0.0,
+                };
+                let input = result.as_ref().unwrap_or(source);
+                result = Some(self.pass(
+                    descriptors,
+                    encoder,
+                    &self.blur,
+                    &uniforms,
+                    input,
+                    &input.view,
+                ));
+            }
+        }
+        result
+    }
+
+    fn color_matrix(
+        &self,
+        descriptors: &Descriptors,
+        encoder: &mut wgpu::CommandEncoder,
+        filter: &ColorMatrixFilter,
+        source: &FilterTexture,
+    ) -> FilterTexture {
+        let m = &filter.matrix;
+        let uniforms = ColorMatrixUniforms {
+            rows: [
+                [m[0], m[1], m[2], m[3]],
+                [m[5], m[6], m[7], m[8]],
+                [m[10], m[11], m[12], m[13]],
+                [m[15], m[16], m[17], m[18]],
+            ],
+            offset: [m[4] / 255.0, m[9] / 255.0, m[14] / 255.0, m[19] / 255.0],
+        };
+        self.pass(
+            descriptors,
+            encoder,
+            &self.color_matrix,
+            &uniforms,
+            source,
+            &source.view,
+        )
+    }
+
+    /// Convolves the source, returning `None` if the kernel is empty or too large.
+    fn convolution(
+        &self,
+        descriptors: &Descriptors,
+        encoder: &mut wgpu::CommandEncoder,
+        filter: &ConvolutionFilter,
+        source: &FilterTexture,
+    ) -> Option<FilterTexture> {
+        let size = usize::from(filter.matrix_x) * usize::from(filter.matrix_y);
+        if size == 0 || filter.matrix.len() < size {
+            return None;
+        }
+        if size > MAX_CONVOLUTION_SIZE {
+            log::warn!(
+                "Convolution filter of {}x{} is too large",
+                filter.matrix_x,
+                filter.matrix_y
+            );
+            return None;
+        }
+
+        let mut matrix = [[0.0; 4]; MAX_CONVOLUTION_SIZE / 4];
+        for (i, value) in filter.matrix[..size].iter().enumerate() {
+            matrix[i / 4][i % 4] = *value;
+        }
+        let uniforms = ConvolutionUniforms {
+            matrix,
+            default_color: color_to_rgba(&filter.color),
+            bias: filter.bias / 255.0,
+            divisor: if filter.divisor == 0.0 {
+                1.0
+            } else {
+                filter.divisor
+            },
+            matrix_x: f32::from(filter.matrix_x),
+            matrix_y: f32::from(filter.matrix_y),
+            preserve_alpha: bool_to_f32(filter.preserve_alpha),
+            clamp: bool_to_f32(filter.clamp),
+            _padding: [0.0; 2],
+        };
+        Some(self.pass(
+            descriptors,
+            encoder,
+            &self.convolution,
+            &uniforms,
+            source,
+            &source.view,
+        ))
+    }
+
+    /// Displaces the source, returning `None` if there is no map.
+    fn displacement_map(
+        &self,
+        descriptors: &Descriptors,
+        encoder: &mut wgpu::CommandEncoder,
+        filter: &DisplacementMapFilter,
+        source: &FilterTexture,
+    ) -> Option<FilterTexture> {
+        let map = as_texture(filter.map_bitmap.as_ref()?);
+        let map_view = map.texture.create_view(&Default::default());
+        let component = |component: u8| match component {
+            1 => 0.0,
+            2 => 1.0,
+            4 => 2.0,
+            8 => 3.0,
+            _ => -1.0,
+        };
+        let uniforms = DisplacementMapUniforms {
+            color: color_to_rgba(&filter.color),
+            map_point: [filter.map_point.0 as f32, filter.map_point.1 as f32],
+            scale: [filter.scale_x, filter.scale_y],
+            component_x: component(filter.component_x),
+            component_y: component(filter.component_y),
+            mode: match filter.mode {
+                DisplacementMapFilterMode::Wrap => 0.0,
+                DisplacementMapFilterMode::Clamp => 1.0,
+                DisplacementMapFilterMode::Ignore => 2.0,
+                DisplacementMapFilterMode::Color => 3.0,
+            },
+            _padding: 0.0,
+        };
+        Some(self.pass(
+            descriptors,
+            encoder,
+            &self.displacement_map,
+            &uniforms,
+            source,
+            &map_view,
+        ))
+    }
+
+    /// Blurs the source, and then combines it with the colored blur.
+    fn shadow(
+        &self,
+        descriptors: &Descriptors,
+        encoder: &mut wgpu::CommandEncoder,
+        blur: &BlurFilter,
+        uniforms: &ShadowUniforms,
+        source: &FilterTexture,
+    ) -> FilterTexture {
+        let blurred = self.blur(descriptors, encoder, blur, source);
+        let blurred_view = &blurred.as_ref().unwrap_or(source).view;
+        self.pass(
+            descriptors,
+            encoder,
+            &self.shadow,
+            uniforms,
+            source,
+            blurred_view,
+        )
+    }
+
+    fn gradient_shadow(
+        &self,
+        descriptors: &Descriptors,
+        encoder: &mut wgpu::CommandEncoder,
+        filter: &GradientFilter,
+        mode: ShadowMode,
+        source: &FilterTexture,
+    ) -> FilterTexture {
+        let blur = BlurFilter {
+            blur_x: filter.blur_x,
+            blur_y: filter.blur_y,
+            quality: filter.quality,
+        };
+        let is_glow = matches!(mode, ShadowMode::GradientGlow);
+        let uniforms = ShadowUniforms {
+            offset: shadow_offset(filter.angle, filter.distance),
+            strength: filter.strength,
+            mode: mode as i32 as f32,
+            composite: Composite::from(filter.bevel_type) as i32 as f32,
+            invert: bool_to_f32(is_glow && filter.bevel_type == BevelFilterType::Inner),
+            knockout: bool_to_f32(filter.knockout),
+            gradient: gradient_lut(filter),
+            ..Default::default()
+        };
+        self.shadow(descriptors, encoder, &blur, &uniforms, source)
+    }
+}
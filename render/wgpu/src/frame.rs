@@ -73,6 +73,13 @@ impl<'a> Frame<'a> {
         self.render_pass.set_bind_group(2, bind_group, &[]);
     }
 
+    pub fn prep_complex_blend(&mut self, bind_group: &'a wgpu::BindGroup) {
+        self.render_pass
+            .set_pipeline(&self.pipelines.complex_blend[self.mask_state]);
+
+        self.render_pass.set_bind_group(2, bind_group, &[]);
+    }
+
     pub fn draw(
         &mut self,
         vertices: wgpu::BufferSlice<'a>,
@@ -8,6 +8,8 @@ pub struct Globals {
     buffer: wgpu::Buffer,
     viewport_width: u32,
     viewport_height: u32,
+    origin_x: i32,
+    origin_y: i32,
     dirty: bool,
 }
 
@@ -48,6 +50,8 @@ impl Globals {
             buffer,
             viewport_width: 0,
             viewport_height: 0,
+            origin_x: 0,
+            origin_y: 0,
             dirty: true,
         }
     }
@@ -64,20 +68,40 @@ impl Globals {
         }
     }
 
+    pub fn origin(&self) -> (i32, i32) {
+        (self.origin_x, self.origin_y)
+    }
+
+    /// Sets the point in stage space (in pixels) that is drawn at the top-left of the viewport.
+    pub fn set_origin(&mut self, origin_x: i32, origin_y: i32) {
+        if origin_x != self.origin_x || origin_y != self.origin_y {
+            self.origin_x = origin_x;
+            self.origin_y = origin_y;
+            self.dirty = true;
+        }
+    }
+
     pub fn update_uniform(&mut self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder) {
         if !self.dirty {
             return;
         }
         self.dirty = false;
+        let width = self.viewport_width as f32;
+        let height = self.viewport_height as f32;
         let temp_label = create_debug_label!("Temporary globals buffer");
         let temp_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: temp_label.as_deref(),
             contents: bytemuck::cast_slice(&[GlobalsUniform {
                 view_matrix: [
-                    [1.0 / (self.viewport_width as f32 / 2.0), 0.0, 0.0, 0.0],
-                    [0.0, -1.0 / (self.viewport_height as f32 / 2.0), 0.0, 0.0],
+                    [1.0 / (width / 2.0), 0.0, 0.0, 0.0],
+                    [0.0, -1.0 / (height / 2.0), 0.0, 0.0],
                     [0.0, 0.0, 1.0, 0.0],
-                    [-1.0, 1.0, 0.0, 1.0],
+                    [
+                        -1.0 - self.origin_x as f32 / (width / 2.0),
+                        1.0 + self.origin_y as f32 / (height / 2.0),
+                        0.0,
+                        1.0,
+                    ],
                 ],
             }]),
             usage: wgpu::BufferUsages::COPY_SRC,
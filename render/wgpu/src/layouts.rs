@@ -5,6 +5,7 @@ pub struct BindLayouts {
     pub bitmap: wgpu::BindGroupLayout,
     pub gradient: wgpu::BindGroupLayout,
     pub copy_srgb: wgpu::BindGroupLayout,
+    pub blend: wgpu::BindGroupLayout,
 }
 
 impl BindLayouts {
@@ -135,12 +136,41 @@ impl BindLayouts {
             label: create_debug_label!("Copy sRGB bind group layout").as_deref(),
         });
 
+        let blend_texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                multisampled: false,
+                sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                view_dimension: wgpu::TextureViewDimension::D2,
+            },
+            count: None,
+        };
+        let blend = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                blend_texture_entry(1),
+                blend_texture_entry(2),
+            ],
+            label: create_debug_label!("Blend bind group layout").as_deref(),
+        });
+
         Self {
             globals,
             transforms,
             bitmap,
             gradient,
             copy_srgb,
+            blend,
         }
     }
 }
@@ -30,10 +30,12 @@ pub mod target;
 mod uniform_buffer;
 
 pub mod backend;
+mod blend;
 #[cfg(feature = "clap")]
 pub mod clap;
 mod commands;
 pub mod descriptors;
+mod filters;
 mod frame;
 mod layouts;
 mod mesh;
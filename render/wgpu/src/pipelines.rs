@@ -13,31 +13,18 @@ pub const VERTEX_BUFFERS_DESCRIPTION: [wgpu::VertexBufferLayout; 1] = [wgpu::Ver
     ],
 }];
 
-#[derive(Debug, Enum, Copy, Clone)]
+/// A blend mode that can be drawn with fixed-function GPU blending.
+///
+/// The remaining Flash blend modes need to read the content below them,
+/// and are composited by `blend.wgsl` instead.
+#[derive(Debug, Enum, Copy, Clone, PartialEq, Eq)]
 pub enum BlendMode {
     Normal,
     Add,
     Subtract,
-}
-
-// Use the GPU blend modes to roughly approximate Flash's blend modes.
-// This should look reasonable for the most common cases, but full support requires
-// rendering to an intermediate texture and custom shaders for the complex blend modes.
-impl From<swf::BlendMode> for BlendMode {
-    fn from(blend: swf::BlendMode) -> Self {
-        match blend {
-            swf::BlendMode::Normal => BlendMode::Normal,
-
-            // dst + src
-            swf::BlendMode::Add => BlendMode::Add,
-
-            // dst - src
-            swf::BlendMode::Subtract => BlendMode::Subtract,
-
-            // Unsupported blend mode. Default to normal for now.
-            _ => BlendMode::Normal,
-        }
-    }
+    Screen,
+    Alpha,
+    Erase,
 }
 
 impl BlendMode {
@@ -60,6 +47,39 @@ impl BlendMode {
                 },
                 alpha: wgpu::BlendComponent::OVER,
             },
+            // src + dst - src * dst
+            BlendMode::Screen => wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::One,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrc,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: wgpu::BlendComponent::OVER,
+            },
+            // dst * src_alpha
+            BlendMode::Alpha => {
+                let component = wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::Zero,
+                    dst_factor: wgpu::BlendFactor::SrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                };
+                wgpu::BlendState {
+                    color: component,
+                    alpha: component,
+                }
+            }
+            // dst * (1 - src_alpha)
+            BlendMode::Erase => {
+                let component = wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::Zero,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                };
+                wgpu::BlendState {
+                    color: component,
+                    alpha: component,
+                }
+            }
         }
     }
 }
@@ -74,6 +94,7 @@ pub struct Pipelines {
     pub color: ShapePipeline,
     pub bitmap: ShapePipeline,
     pub gradient: ShapePipeline,
+    pub complex_blend: EnumMap<MaskState, wgpu::RenderPipeline>,
 }
 
 impl ShapePipeline {
@@ -158,10 +179,42 @@ impl Pipelines {
             ],
         );
 
+        let complex_blend_pipeline_layout_label =
+            create_debug_label!("Complex blend pipeline layout");
+        let complex_blend_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: complex_blend_pipeline_layout_label.as_deref(),
+                bind_group_layouts: &[
+                    &bind_layouts.globals,
+                    &bind_layouts.transforms,
+                    &bind_layouts.blend,
+                ],
+                push_constant_ranges: &[],
+            });
+        let complex_blend_array: [wgpu::RenderPipeline; MaskState::LENGTH] = (0..MaskState::LENGTH)
+            .map(|mask_enum| {
+                create_mask_pipeline(
+                    "Complex blend",
+                    device,
+                    format,
+                    &shaders.blend_shader,
+                    msaa_sample_count,
+                    &VERTEX_BUFFERS_DESCRIPTION,
+                    &complex_blend_pipeline_layout,
+                    MaskState::from_usize(mask_enum),
+                    // The shader computes the blended result itself.
+                    wgpu::BlendState::REPLACE,
+                )
+            })
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+
         Self {
             color: color_pipelines,
             bitmap: bitmap_pipelines,
             gradient: gradient_pipelines,
+            complex_blend: EnumMap::from_array(complex_blend_array),
         }
     }
 }
@@ -225,12 +278,40 @@ fn create_shape_pipeline(
         push_constant_ranges: &[],
     });
 
-    let mask_render_state = |mask_name, stencil_state, write_mask, blend| {
+    ShapePipeline::build(|blend_mode, mask_state| {
+        create_mask_pipeline(
+            name,
+            device,
+            format,
+            shader,
+            msaa_sample_count,
+            vertex_buffers_layout,
+            &pipeline_layout,
+            mask_state,
+            blend_mode.blend_state(),
+        )
+    })
+}
+
+/// Creates a pipeline that draws with the stencil operations required by `mask_state`.
+#[allow(clippy::too_many_arguments)]
+fn create_mask_pipeline(
+    name: &'static str,
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    shader: &wgpu::ShaderModule,
+    msaa_sample_count: u32,
+    vertex_buffers_layout: &[wgpu::VertexBufferLayout<'_>],
+    pipeline_layout: &wgpu::PipelineLayout,
+    mask_state: MaskState,
+    blend: wgpu::BlendState,
+) -> wgpu::RenderPipeline {
+    let mask_render_state = |mask_name, stencil_state, write_mask| {
         device.create_render_pipeline(&create_pipeline_descriptor(
             create_debug_label!("{} pipeline {}", name, mask_name).as_deref(),
             shader,
             shader,
-            &pipeline_layout,
+            pipeline_layout,
             Some(wgpu::DepthStencilState {
                 format: wgpu::TextureFormat::Depth24PlusStencil8,
                 depth_write_enabled: false,
@@ -253,53 +334,46 @@ fn create_shape_pipeline(
         ))
     };
 
-    ShapePipeline::build(|blend_mode, mask_state| {
-        let blend = blend_mode.blend_state();
-        match mask_state {
-            MaskState::NoMask => mask_render_state(
-                "no mask",
-                wgpu::StencilFaceState {
-                    compare: wgpu::CompareFunction::Always,
-                    fail_op: wgpu::StencilOperation::Keep,
-                    depth_fail_op: wgpu::StencilOperation::Keep,
-                    pass_op: wgpu::StencilOperation::Keep,
-                },
-                wgpu::ColorWrites::ALL,
-                blend,
-            ),
-            MaskState::DrawMaskStencil => mask_render_state(
-                "draw mask stencil",
-                wgpu::StencilFaceState {
-                    compare: wgpu::CompareFunction::Equal,
-                    fail_op: wgpu::StencilOperation::Keep,
-                    depth_fail_op: wgpu::StencilOperation::Keep,
-                    pass_op: wgpu::StencilOperation::IncrementClamp,
-                },
-                wgpu::ColorWrites::empty(),
-                blend,
-            ),
-            MaskState::DrawMaskedContent => mask_render_state(
-                "draw masked content",
-                wgpu::StencilFaceState {
-                    compare: wgpu::CompareFunction::Equal,
-                    fail_op: wgpu::StencilOperation::Keep,
-                    depth_fail_op: wgpu::StencilOperation::Keep,
-                    pass_op: wgpu::StencilOperation::Keep,
-                },
-                wgpu::ColorWrites::ALL,
-                blend,
-            ),
-            MaskState::ClearMaskStencil => mask_render_state(
-                "clear mask stencil",
-                wgpu::StencilFaceState {
-                    compare: wgpu::CompareFunction::Equal,
-                    fail_op: wgpu::StencilOperation::Keep,
-                    depth_fail_op: wgpu::StencilOperation::Keep,
-                    pass_op: wgpu::StencilOperation::DecrementClamp,
-                },
-                wgpu::ColorWrites::empty(),
-                blend,
-            ),
-        }
-    })
+    match mask_state {
+        MaskState::NoMask => mask_render_state(
+            "no mask",
+            wgpu::StencilFaceState {
+                compare: wgpu::CompareFunction::Always,
+                fail_op: wgpu::StencilOperation::Keep,
+                depth_fail_op: wgpu::StencilOperation::Keep,
+                pass_op: wgpu::StencilOperation::Keep,
+            },
+            wgpu::ColorWrites::ALL,
+        ),
+        MaskState::DrawMaskStencil => mask_render_state(
+            "draw mask stencil",
+            wgpu::StencilFaceState {
+                compare: wgpu::CompareFunction::Equal,
+                fail_op: wgpu::StencilOperation::Keep,
+                depth_fail_op: wgpu::StencilOperation::Keep,
+                pass_op: wgpu::StencilOperation::IncrementClamp,
+            },
+            wgpu::ColorWrites::empty(),
+        ),
+        MaskState::DrawMaskedContent => mask_render_state(
+            "draw masked content",
+            wgpu::StencilFaceState {
+                compare: wgpu::CompareFunction::Equal,
+                fail_op: wgpu::StencilOperation::Keep,
+                depth_fail_op: wgpu::StencilOperation::Keep,
+                pass_op: wgpu::StencilOperation::Keep,
+            },
+            wgpu::ColorWrites::ALL,
+        ),
+        MaskState::ClearMaskStencil => mask_render_state(
+            "clear mask stencil",
+            wgpu::StencilFaceState {
+                compare: wgpu::CompareFunction::Equal,
+                fail_op: wgpu::StencilOperation::Keep,
+                depth_fail_op: wgpu::StencilOperation::Keep,
+                pass_op: wgpu::StencilOperation::DecrementClamp,
+            },
+            wgpu::ColorWrites::empty(),
+        ),
+    }
 }
@@ -4,6 +4,12 @@ pub struct Shaders {
     pub bitmap_shader: wgpu::ShaderModule,
     pub gradient_shader: wgpu::ShaderModule,
     pub copy_srgb_shader: wgpu::ShaderModule,
+    pub blend_shader: wgpu::ShaderModule,
+    pub blur_filter_shader: wgpu::ShaderModule,
+    pub color_matrix_filter_shader: wgpu::ShaderModule,
+    pub convolution_filter_shader: wgpu::ShaderModule,
+    pub displacement_map_filter_shader: wgpu::ShaderModule,
+    pub shadow_filter_shader: wgpu::ShaderModule,
 }
 
 impl Shaders {
@@ -21,12 +27,44 @@ impl Shaders {
             "copy sRGB",
             include_str!("../shaders/copy_srgb.wgsl"),
         );
+        let blend_shader = create_shader(device, "blend", include_str!("../shaders/blend.wgsl"));
+        let blur_filter_shader = create_filter_shader(
+            device,
+            "blur filter",
+            include_str!("../shaders/filter/blur.wgsl"),
+        );
+        let color_matrix_filter_shader = create_filter_shader(
+            device,
+            "color matrix filter",
+            include_str!("../shaders/filter/color_matrix.wgsl"),
+        );
+        let convolution_filter_shader = create_filter_shader(
+            device,
+            "convolution filter",
+            include_str!("../shaders/filter/convolution.wgsl"),
+        );
+        let displacement_map_filter_shader = create_filter_shader(
+            device,
+            "displacement map filter",
+            include_str!("../shaders/filter/displacement_map.wgsl"),
+        );
+        let shadow_filter_shader = create_filter_shader(
+            device,
+            "shadow filter",
+            include_str!("../shaders/filter/shadow.wgsl"),
+        );
 
         Self {
             color_shader,
             bitmap_shader,
             gradient_shader,
             copy_srgb_shader,
+            blend_shader,
+            blur_filter_shader,
+            color_matrix_filter_shader,
+            convolution_filter_shader,
+            displacement_map_filter_shader,
+            shadow_filter_shader,
         }
     }
 }
@@ -49,3 +87,21 @@ fn create_shader(
     };
     device.create_shader_module(desc)
 }
+
+/// Builds a `wgpu::ShaderModule` for a filter pass from the given WGSL source in `src`.
+///
+/// Filters are prepended with `filter/common.wgsl` instead of `common.wgsl`.
+fn create_filter_shader(
+    device: &wgpu::Device,
+    name: &'static str,
+    src: &'static str,
+) -> wgpu::ShaderModule {
+    const COMMON_SRC: &str = include_str!("../shaders/filter/common.wgsl");
+    let src = [COMMON_SRC, src].concat();
+    let label = create_debug_label!("Shader {}", name);
+    let desc = wgpu::ShaderModuleDescriptor {
+        label: label.as_deref(),
+        source: wgpu::ShaderSource::Wgsl(src.into()),
+    };
+    device.create_shader_module(desc)
+}
@@ -1,4 +1,4 @@
-use crate::commands::CommandRenderer;
+use crate::commands::{CommandRenderer, Layer};
 use crate::descriptors::Quad;
 use crate::frame::Frame;
 use crate::layouts::BindLayouts;
@@ -377,6 +377,8 @@ impl Surface {
         }
     }
 
+    /// Draws a single segment of `commands` (see `CommandRenderer::new`).
+    /// Every segment after the first continues from the contents of the previous one.
     #[allow(clippy::too_many_arguments)]
     pub fn draw_commands(
         &self,
@@ -386,7 +388,11 @@ impl Surface {
         globals: &mut Globals,
         uniform_buffers_storage: &mut BufferStorage<Transforms>,
         meshes: &Vec<Mesh>,
-        commands: CommandList,
+        commands: &CommandList,
+        layers: &[Option<Layer>],
+        complex_blends: &[wgpu::BindGroup],
+        segment: usize,
+        is_layer: bool,
     ) -> Vec<wgpu::CommandBuffer> {
         let label = create_debug_label!("Draw encoder");
         let mut draw_encoder =
@@ -406,9 +412,10 @@ impl Surface {
 
         globals.update_uniform(&descriptors.device, &mut draw_encoder);
 
-        let load = match clear_color {
-            Some(color) => wgpu::LoadOp::Clear(color),
-            None => wgpu::LoadOp::Load,
+        let (load, stencil_load) = match (clear_color, segment) {
+            (Some(color), 0) => (wgpu::LoadOp::Clear(color), wgpu::LoadOp::Clear(0)),
+            (None, 0) => (wgpu::LoadOp::Load, wgpu::LoadOp::Clear(0)),
+            _ => (wgpu::LoadOp::Load, wgpu::LoadOp::Load),
         };
 
         let mut render_pass = draw_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
@@ -424,7 +431,7 @@ impl Surface {
                     store: false,
                 }),
                 stencil_ops: Some(wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(0),
+                    load: stencil_load,
                     store: true,
                 }),
             }),
@@ -445,6 +452,10 @@ impl Surface {
             meshes,
             descriptors.quad.vertices.slice(..),
             descriptors.quad.indices.slice(..),
+            layers,
+            complex_blends,
+            segment,
+            is_layer,
         ));
         frame.finish();
 
@@ -336,10 +336,15 @@ impl<'a> Reader<'a> {
     fn read_metadata(&mut self) -> Result<Metadata> {
         let name = self.read_index()?;
         let num_items = self.read_u30()?;
-        let mut items = Vec::with_capacity(num_items as usize);
+        // Contrary to the AVM2 specification, all keys are stored before all values.
+        let mut keys = Vec::with_capacity(num_items as usize);
         for _ in 0..num_items {
+            keys.push(self.read_index()?);
+        }
+        let mut items = Vec::with_capacity(num_items as usize);
+        for key in keys {
             items.push(MetadataItem {
-                key: self.read_index()?,
+                key,
                 value: self.read_index()?,
             })
         }
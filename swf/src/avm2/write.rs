@@ -422,6 +422,8 @@ impl<W: Write> Writer<W> {
         self.write_u30(metadata.items.len() as u32)?;
         for item in &metadata.items {
             self.write_index(&item.key)?;
+        }
+        for item in &metadata.items {
             self.write_index(&item.value)?;
         }
         Ok(())
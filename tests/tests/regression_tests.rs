@@ -6,30 +6,41 @@ use approx::assert_relative_eq;
 use regex::Regex;
 use ruffle_core::backend::{
     log::LogBackend,
-    navigator::{NullExecutor, NullNavigatorBackend},
+    navigator::{
+        NavigationMethod, NavigatorBackend, NullExecutor, NullNavigatorBackend, OwnedFuture,
+        Request, Response,
+    },
     storage::{MemoryStorageBackend, StorageBackend},
 };
 use ruffle_core::context::UpdateContext;
 use ruffle_core::events::MouseButton as RuffleMouseButton;
 use ruffle_core::external::Value as ExternalValue;
 use ruffle_core::external::{ExternalInterfaceMethod, ExternalInterfaceProvider};
+use ruffle_core::indexmap::IndexMap;
 use ruffle_core::limits::ExecutionLimit;
+use ruffle_core::loader::Error as LoaderError;
+use ruffle_core::socket::{ConnectionState, SocketAction, SocketHandle};
+use ruffle_core::system_properties::{Manufacturer, OperatingSystem, PlayerType, SystemProperties};
 use ruffle_core::tag_utils::SwfMovie;
 use ruffle_core::{Player, PlayerBuilder, PlayerEvent, ViewportDimensions};
 use ruffle_input_format::{AutomatedEvent, InputInjector, MouseButton as InputMouseButton};
 
-#[cfg(feature = "imgtests")]
+#[cfg(feature = "software_imgtests")]
+use ruffle_render_software::SoftwareRenderBackend;
+#[cfg(all(feature = "imgtests", not(feature = "software_imgtests")))]
 use ruffle_render_wgpu::backend::WgpuRenderBackend;
-#[cfg(feature = "imgtests")]
+#[cfg(all(feature = "imgtests", not(feature = "software_imgtests")))]
 use ruffle_render_wgpu::{target::TextureTarget, wgpu};
 use std::cell::RefCell;
 use std::collections::BTreeMap;
 use std::path::Path;
 use std::rc::Rc;
+use std::sync::mpsc::{Receiver, Sender, TryRecvError};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use url::Url;
 
-const RUN_IMG_TESTS: bool = cfg!(feature = "imgtests");
+const RUN_IMG_TESTS: bool = cfg!(any(feature = "imgtests", feature = "software_imgtests"));
 
 fn set_logger() {
     let _ = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
@@ -191,20 +202,22 @@ swf_tests! {
     (as3_bitmapdata_copypixels, "avm2/bitmapdata_copypixels", 2, img = true),
     #[ignore] (as3_bitmap_properties, "avm2/bitmap_properties", 1),
     (as3_bitmap_subclass, "avm2/bitmap_subclass", 1),
-    #[cfg_attr(not(feature = "imgtests"), ignore)] (as3_bitmap_subclass_properties, "avm2/bitmap_subclass_properties", 1, img = true),
+    #[cfg_attr(not(any(feature = "imgtests", feature = "software_imgtests")), ignore)] (as3_bitmap_subclass_properties, "avm2/bitmap_subclass_properties", 1, img = true),
     (as3_bitmap_timeline, "avm2/bitmap_timeline", 1),
-    #[cfg_attr(not(feature = "imgtests"), ignore)] (as3_bitmapdata_clone, "avm2/bitmapdata_clone", 1, img = true),
+    #[cfg_attr(not(any(feature = "imgtests", feature = "software_imgtests")), ignore)] (as3_bitmapdata_clone, "avm2/bitmapdata_clone", 1, img = true),
+    (as3_bitmapdata_applyfilter, "avm2/bitmapdata_applyfilter", 1),
     (as3_bitmapdata_constr, "avm2/bitmapdata_constr", 1),
     (as3_bitmapdata_dispose, "avm2/bitmapdata_dispose", 1),
     // We need a render backend in order to call `BitmapData.draw`
-    #[cfg_attr(not(feature = "imgtests"), ignore)] (as3_bitmapdata_draw, "avm2/bitmapdata_draw", 1, img = true),
-    #[cfg_attr(not(feature = "imgtests"), ignore)] (as3_bitmapdata_opaque, "avm2/bitmapdata_opaque", 1, img = true),
+    #[cfg_attr(not(any(feature = "imgtests", feature = "software_imgtests")), ignore)] (as3_bitmapdata_draw, "avm2/bitmapdata_draw", 1, img = true),
+    #[cfg_attr(not(any(feature = "imgtests", feature = "software_imgtests")), ignore)] (as3_bitmapdata_opaque, "avm2/bitmapdata_opaque", 1, img = true),
     (as3_bitmapdata_zero_size, "avm2/bitmapdata_zero_size", 1),
-    #[cfg_attr(not(feature = "imgtests"), ignore)] (as3_bitmapdata_embedded, "avm2/bitmapdata_embedded", 1, img = true),
+    #[cfg_attr(not(any(feature = "imgtests", feature = "software_imgtests")), ignore)] (as3_bitmapdata_embedded, "avm2/bitmapdata_embedded", 1, img = true),
     (as3_bitmapdata_fillrect, "avm2/bitmapdata_fillrect", 1),
     (as3_bitnot, "avm2/bitnot", 1),
     (as3_bitor, "avm2/bitor", 1),
     (as3_bitxor, "avm2/bitxor", 1),
+    #[cfg_attr(not(feature = "software_imgtests"), ignore)] (as3_blendmodes_render, "avm2/blendmodes_render", 1, img = true),
     (as3_boolean_constr, "avm2/boolean_constr", 1),
     (as3_boolean_negation, "avm2/boolean_negation", 1),
     (as3_boolean_tostring, "avm2/boolean_tostring", 1),
@@ -212,7 +225,10 @@ swf_tests! {
     (as3_bytearray_readobject_amf0, "avm2/bytearray_readobject_amf0", 1),
     (as3_bytearray_readobject_amf3, "avm2/bytearray_readobject_amf3", 1),
     (as3_bytearray_writeobject, "avm2/bytearray_writeobject", 1),
+    (as3_bytearray_amf_types, "avm2/bytearray_amf_types", 1),
     (as3_bytearray, "avm2/bytearray", 1),
+    #[cfg_attr(all(feature = "imgtests", not(feature = "software_imgtests")), ignore)] (as3_cacheasbitmap_render, "avm2/cacheasbitmap_render", 4, img = true),
+    (as3_capabilities, "avm2/capabilities", 1),
     (as3_checkfilter, "avm2/checkfilter", 1),
     (as3_class_call, "avm2/class_call", 1),
     (as3_class_cast_call, "avm2/class_cast_call", 1),
@@ -229,6 +245,8 @@ swf_tests! {
     (as3_coerce_property, "avm2/coerce_property", 1),
     (as3_coerce_string, "avm2/coerce_string", 1),
     (as3_constructor_call, "avm2/constructor_call", 1),
+    #[cfg_attr(not(feature = "imgtests"), ignore)] (as3_context3d_drawtobitmapdata, "avm2/context3d_drawtobitmapdata", 2, img = true),
+    #[cfg_attr(not(feature = "imgtests"), ignore)] (as3_context3d_texture, "avm2/context3d_texture", 2, img = true),
     (as3_control_flow_bool, "avm2/control_flow_bool", 1),
     (as3_control_flow_stricteq, "avm2/control_flow_stricteq", 1),
     (as3_convert_boolean, "avm2/convert_boolean", 1),
@@ -242,6 +260,7 @@ swf_tests! {
     (as3_decrement_i, "avm2/decrement_i", 1),
     (as3_decrement, "avm2/decrement", 1),
     (as3_default_values, "avm2/default_values", 1),
+    (as3_describe_type, "avm2/describe_type", 1),
     (as3_dictionary_access, "avm2/dictionary_access", 1),
     (as3_dictionary_delete, "avm2/dictionary_delete", 1),
     (as3_dictionary_foreach, "avm2/dictionary_foreach", 1),
@@ -286,8 +305,11 @@ swf_tests! {
     (as3_drag_drop, "avm2/drag_drop", 14),
     (as3_edittext_antialiastype, "avm2/edittext_antialiastype", 1),
     (as3_edittext_default_format, "avm2/edittext_default_format", 1),
+    (as3_edittext_geometry, "avm2/edittext_geometry", 1),
     (as3_edittext_html_entity, "avm2/edittext_html_entity", 1),
+    (as3_edittext_html_img, "avm2/edittext_html_img", 4),
     (as3_edittext_html_roundtrip, "avm2/edittext_html_roundtrip", 1),
+    (as3_edittext_input_restrictions, "avm2/edittext_input_restrictions", 1),
     (as3_edittext_mouseenabled, "avm2/edittext_mouseenabled", 1),
     (as3_edittext_newline_stripping, "avm2/edittext_newline_stripping", 1),
     (as3_edittext_width_height, "avm2/edittext_width_height", 1),
@@ -319,6 +341,7 @@ swf_tests! {
     (as3_eventdispatcher_willtrigger, "avm2/eventdispatcher_willtrigger", 1),
     (as3_falsiness, "avm2/falsiness", 1),
     (as3_filefilter_properties, "avm2/filefilter_properties", 1),
+    #[cfg_attr(not(feature = "software_imgtests"), ignore)] (as3_filters_render, "avm2/filters_render", 1, img = true),
     (as3_font_embedded, "avm2/font_embedded", 1),
     (as3_font_hasglyphs, "avm2/font_hasglyphs", 1),
     (as3_framelabel_constr, "avm2/framelabel_constr", 5),
@@ -343,6 +366,7 @@ swf_tests! {
     (as3_get_qualified_super_class_name, "avm2/get_qualified_super_class_name", 1),
     (as3_get_timer, "avm2/get_timer", 1),
     (as3_getouterscope, "avm2/getouterscope", 1),
+    (as3_graphics_readgraphicsdata, "avm2/graphics_readgraphicsdata", 1),
     (as3_greaterequals, "avm2/greaterequals", 1),
     (as3_greaterthan, "avm2/greaterthan", 1),
     (as3_has_own_property, "avm2/has_own_property", 1),
@@ -361,6 +385,7 @@ swf_tests! {
     (as3_inclocal, "avm2/inclocal", 1),
     (as3_increment_i, "avm2/increment_i", 1),
     (as3_increment, "avm2/increment", 1),
+    (as3_inline_cache, "avm2/inline_cache", 1),
     (as3_instanceof, "avm2/instanceof", 1),
     (as3_int_constr, "avm2/int_constr", 1),
     (as3_int_edge_cases, "avm2/int_edge_cases", 1),
@@ -448,6 +473,7 @@ swf_tests! {
     (as3_multiply, "avm2/multiply", 1),
     (as3_nan_scale, "avm2/nan_scale", 1),
     (as3_negate, "avm2/negate", 1),
+    (as3_netstream, "avm2/netstream", 40),
     (as3_nonconflicting_declarations, "avm2/nonconflicting_declarations", 1),
     (as3_number_constr, "avm2/number_constr", 1),
     #[ignore] (as3_number_tostring, "avm2/number_tostring", 1), //Ignored because Flash Player adds extra x, W, and/or ° symbols randomly
@@ -485,7 +511,9 @@ swf_tests! {
     (as3_regexp_exec, "avm2/regexp_exec", 1),
     (as3_regexp_test, "avm2/regexp_test", 1),
     (as3_rshift, "avm2/rshift", 1),
+    #[cfg_attr(all(feature = "imgtests", not(feature = "software_imgtests")), ignore)] (as3_scale9grid, "avm2/scale9grid", 1, img = true),
     (as3_scene_constr, "avm2/scene_constr", 5),
+    (as3_scope_lookup_resolution, "avm2/scope_lookup_resolution", 1),
     (as3_set_property_is_enumerable, "avm2/set_property_is_enumerable", 1),
     (as3_shape_drawrect, "avm2/shape_drawrect", 1),
     (as3_simplebutton_childevents_nested, "avm2/simplebutton_childevents_nested", 2),
@@ -499,6 +527,7 @@ swf_tests! {
     (as3_simplebutton_soundtransform, "avm2/simplebutton_soundtransform", 49),
     (as3_simplebutton_structure, "avm2/simplebutton_structure", 2),
     (as3_simplebutton_symbolclass, "avm2/simplebutton_symbolclass", 3),
+    (as3_socket, "avm2/socket", 10),
     (as3_sound_embeddedprops, "avm2/sound_embeddedprops", 1),
     (as3_sound_play, "avm2/sound_play", 1),
     (as3_sound_valueof, "avm2/sound_valueof", 1),
@@ -516,8 +545,8 @@ swf_tests! {
     (as3_stage_loaderinfo_properties, "avm2/stage_loaderinfo_properties", 2),
     (as3_stage_mouseenabled, "avm2/stage_mouseenabled", 1),
     (as3_stage_properties, "avm2/stage_properties", 1),
-    (as3_stage3d_rotating_cube, "avm2/stage3d_rotating_cube", 40, img = true),
-    (as3_stage3d_triangle, "avm2/stage3d_triangle", 1, img = true),
+    #[cfg_attr(feature = "software_imgtests", ignore)] (as3_stage3d_rotating_cube, "avm2/stage3d_rotating_cube", 40, img = true),
+    #[cfg_attr(feature = "software_imgtests", ignore)] (as3_stage3d_triangle, "avm2/stage3d_triangle", 1, img = true),
     (as3_static_text, "avm2/static_text", 1),
     (as3_stored_properties, "avm2/stored_properties", 1),
     (as3_strict_equality, "avm2/strict_equality", 1),
@@ -537,6 +566,7 @@ swf_tests! {
     (as3_try_catch_typed, "avm2/try_catch_typed", 1),
     (as3_string_slice_substr_substring, "avm2/string_slice_substr_substring", 1),
     (as3_string_split, "avm2/string_split", 1),
+    (as3_stylesheet, "avm2/stylesheet", 1),
     (as3_subtract, "avm2/subtract", 1),
     (as3_symbol_class_binary_data, "avm2/symbol_class_binary_data", 1),
     (as3_textformat, "avm2/textformat", 1),
@@ -551,6 +581,7 @@ swf_tests! {
     #[ignore] (as3_uint_toprecision, "avm2/uint_toprecision", 1), //Ignored because Flash Player has a print routine that adds extraneous zeros to things
     (as3_uint_tostring, "avm2/uint_tostring", 1),
     (as3_unchecked_function, "avm2/unchecked_function", 1),
+    (as3_uncaught_error_event, "avm2/uncaught_error_event", 4),
     (as3_url_loader, "avm2/url_loader", 1),
     (as3_url_vars, "avm2/url_vars", 1),
     (as3_urshift, "avm2/urshift", 1),
@@ -577,8 +608,10 @@ swf_tests! {
     (as3_vector_sort, "avm2/vector_sort", 1),
     (as3_vector_splice, "avm2/vector_splice", 1),
     (as3_vector_tostring, "avm2/vector_tostring", 1),
+    (as3_verify_error, "avm2/verify_error", 1),
     (as3_virtual_properties, "avm2/virtual_properties", 1),
     (as3_with, "avm2/with", 1),
+    (as3_xml_e4x, "avm2/xml_e4x", 1),
     (as3_escape, "avm2/escape", 1),
     (as3_escape_multi_byte, "avm2/escape_multi_byte", 1),
     (attach_movie, "avm1/attach_movie", 1),
@@ -869,6 +902,7 @@ swf_tests! {
     (xml_remove_node, "avm1/xml_remove_node", 1),
     (xml_reparenting, "avm1/xml_reparenting", 1),
     (xml_siblings, "avm1/xml_siblings", 1),
+    (xml_socket, "avm1/xml_socket", 10),
     (xml_to_string_comment, "avm1/xml_to_string_comment", 1),
     (xml_to_string, "avm1/xml_to_string", 1),
     (xml_unescaping, "avm1/xml_unescaping", 1),
@@ -1133,6 +1167,41 @@ fn shared_object_avm2() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn shared_object_cross_vm() -> Result<(), Error> {
+    set_logger();
+    // Test that a shared object saved by one VM reads back the same in both.
+    let saved = std::fs::read("tests/swfs/avm1/shared_object/RuffleTest.sol")?;
+
+    for (swf_path, expected_output_path) in [
+        (
+            "tests/swfs/avm1/shared_object/test.swf",
+            "tests/swfs/avm1/shared_object/output2.txt",
+        ),
+        (
+            "tests/swfs/avm2/shared_object_avm1_data/test.swf",
+            "tests/swfs/avm2/shared_object_avm1_data/output.txt",
+        ),
+    ] {
+        test_swf_with_hooks(
+            swf_path,
+            1,
+            "tests/swfs/avm2/shared_object_avm1_data/input.json",
+            expected_output_path,
+            |player| {
+                let mut player = player.lock().unwrap();
+                player.storage_mut().put("localhost//RuffleTest", &saved);
+                Ok(())
+            },
+            |_player| Ok(()),
+            false,
+            false,
+        )?;
+    }
+
+    Ok(())
+}
+
 #[test]
 fn timeout_avm1() -> Result<(), Error> {
     set_logger();
@@ -1180,6 +1249,49 @@ fn stage_scale_mode() -> Result<(), Error> {
     )
 }
 
+/// System properties of a player that differs from the default one in every reported way.
+fn custom_system_properties() -> SystemProperties {
+    SystemProperties {
+        player_type: PlayerType::PlugIn,
+        manufacturer: Manufacturer::Macintosh,
+        os: OperatingSystem::Other("Mac OS 10.15.7".to_string()),
+        version: Some("MAC 32,0,0,465".to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn system_properties_avm1() -> Result<(), Error> {
+    set_logger();
+    test_swf_with_builder(
+        "tests/swfs/avm1/system_properties/test.swf",
+        1,
+        "tests/swfs/avm1/system_properties/input.json",
+        "tests/swfs/avm1/system_properties/output.txt",
+        |builder| builder.with_system_properties(custom_system_properties()),
+        |_| Ok(()),
+        |_| Ok(()),
+        false,
+        false,
+    )
+}
+
+#[test]
+fn system_properties_avm2() -> Result<(), Error> {
+    set_logger();
+    test_swf_with_builder(
+        "tests/swfs/avm2/system_properties/test.swf",
+        1,
+        "tests/swfs/avm2/system_properties/input.json",
+        "tests/swfs/avm2/system_properties/output.txt",
+        |builder| builder.with_system_properties(custom_system_properties()),
+        |_| Ok(()),
+        |_| Ok(()),
+        false,
+        false,
+    )
+}
+
 /// Wrapper around string slice that makes debug output `{:?}` to print string same way as `{}`.
 /// Used in different `assert*!` macros in combination with `pretty_assertions` crate to make
 /// test failures to show nice diffs.
@@ -1242,6 +1354,34 @@ fn test_swf_with_hooks(
     before_end: impl FnOnce(Arc<Mutex<Player>>) -> Result<(), Error>,
     check_img: bool,
     frame_time_sleep: bool,
+) -> Result<(), Error> {
+    test_swf_with_builder(
+        swf_path,
+        num_frames,
+        simulated_input_path,
+        expected_output_path,
+        |builder| builder,
+        before_start,
+        before_end,
+        check_img,
+        frame_time_sleep,
+    )
+}
+
+/// Loads an SWF and runs it through the Ruffle core for a number of frames,
+/// in a player with extra options set on its builder.
+/// Tests that the trace output matches the given expected output.
+#[allow(clippy::too_many_arguments)]
+fn test_swf_with_builder(
+    swf_path: &str,
+    num_frames: u32,
+    simulated_input_path: &str,
+    expected_output_path: &str,
+    configure_builder: impl FnOnce(PlayerBuilder) -> PlayerBuilder,
+    before_start: impl FnOnce(Arc<Mutex<Player>>) -> Result<(), Error>,
+    before_end: impl FnOnce(Arc<Mutex<Player>>) -> Result<(), Error>,
+    check_img: bool,
+    frame_time_sleep: bool,
 ) -> Result<(), Error> {
     let injector =
         InputInjector::from_file(simulated_input_path).unwrap_or_else(|_| InputInjector::empty());
@@ -1255,6 +1395,7 @@ fn test_swf_with_hooks(
     let trace_log = run_swf(
         swf_path,
         num_frames,
+        configure_builder,
         before_start,
         injector,
         before_end,
@@ -1286,6 +1427,7 @@ fn test_swf_approx(
     let trace_log = run_swf(
         swf_path,
         num_frames,
+        |builder| builder,
         |_| Ok(()),
         injector,
         |_| Ok(()),
@@ -1372,11 +1514,47 @@ fn test_swf_approx(
     Ok(())
 }
 
+/// Captures the last rendered frame, along with the suffix used for its expected image.
+/// The software renderer produces identical output everywhere, so its images aren't per-OS.
+#[cfg(feature = "software_imgtests")]
+fn capture_image(player: &mut Player) -> (image::RgbaImage, String) {
+    let renderer = player
+        .renderer_mut()
+        .downcast_mut::<SoftwareRenderBackend>()
+        .unwrap();
+
+    // Use straight alpha, since we want to save this as a PNG
+    let actual_image = renderer
+        .capture_frame(false)
+        .expect("Failed to capture image");
+    (actual_image, "software".to_string())
+}
+
+/// Captures the last rendered frame, along with the suffix used for its expected image.
+#[cfg(all(feature = "imgtests", not(feature = "software_imgtests")))]
+fn capture_image(player: &mut Player) -> (image::RgbaImage, String) {
+    let renderer = player
+        .renderer_mut()
+        .downcast_mut::<WgpuRenderBackend<TextureTarget>>()
+        .unwrap();
+
+    // Use straight alpha, since we want to save this as a PNG
+    let actual_image = renderer
+        .capture_frame(false)
+        .expect("Failed to capture image");
+
+    let info = renderer.descriptors().adapter.get_info();
+    let suffix = format!("{}-{:?}", std::env::consts::OS, info.backend);
+    (actual_image, suffix)
+}
+
 /// Loads an SWF and runs it through the Ruffle core for a number of frames.
 /// Tests that the trace output matches the given expected output.
+#[allow(clippy::too_many_arguments)]
 fn run_swf(
     swf_path: &str,
     num_frames: u32,
+    configure_builder: impl FnOnce(PlayerBuilder) -> PlayerBuilder,
     before_start: impl FnOnce(Arc<Mutex<Player>>) -> Result<(), Error>,
     mut injector: InputInjector,
     before_end: impl FnOnce(Arc<Mutex<Player>>) -> Result<(), Error>,
@@ -1394,11 +1572,22 @@ fn run_swf(
     let frame_time = 1000.0 / movie.frame_rate().to_f64();
     let frame_time_duration = Duration::from_millis(frame_time as u64);
     let trace_output = Rc::new(RefCell::new(Vec::new()));
+    let socket_server = TestSocketServer::default();
 
     #[allow(unused_mut)]
     let mut builder = PlayerBuilder::new();
 
-    #[cfg(feature = "imgtests")]
+    #[cfg(feature = "software_imgtests")]
+    if check_img {
+        let width = movie.width().to_pixels() as u32;
+        let height = movie.height().to_pixels() as u32;
+
+        builder = builder
+            .with_renderer(SoftwareRenderBackend::new(width, height))
+            .with_viewport_dimensions(width, height, 1.0);
+    };
+
+    #[cfg(all(feature = "imgtests", not(feature = "software_imgtests")))]
     if check_img {
         const BACKEND: wgpu::Backends = wgpu::Backends::PRIMARY;
 
@@ -1423,9 +1612,12 @@ fn run_swf(
             .with_viewport_dimensions(width, height, 1.0);
     };
 
-    let player = builder
+    let player = configure_builder(builder)
         .with_log(TestLogBackend::new(trace_output.clone()))
-        .with_navigator(NullNavigatorBackend::with_base_path(base_path, &executor))
+        .with_navigator(TestNavigatorBackend::new(
+            NullNavigatorBackend::with_base_path(base_path, &executor),
+            socket_server.clone(),
+        ))
         .with_max_execution_duration(Duration::from_secs(300))
         .with_viewport_dimensions(
             movie.width().to_pixels() as u32,
@@ -1464,6 +1656,9 @@ fn run_swf(
         player.lock().unwrap().run_frame();
         player.lock().unwrap().update_timers(frame_time);
         executor.run();
+        socket_server.pump();
+        player.lock().unwrap().update_sockets();
+        player.lock().unwrap().update_streams(frame_time);
 
         injector.next(|evt, _btns_down| {
             player.lock().unwrap().handle_event(match evt {
@@ -1495,22 +1690,11 @@ fn run_swf(
 
     // Render the image to disk
     // FIXME: Determine how we want to compare against on on-disk image
-    #[cfg(feature = "imgtests")]
+    #[cfg(any(feature = "imgtests", feature = "software_imgtests"))]
     if check_img {
         let mut player_lock = player.lock().unwrap();
         player_lock.render();
-        let renderer = player_lock
-            .renderer_mut()
-            .downcast_mut::<WgpuRenderBackend<TextureTarget>>()
-            .unwrap();
-
-        // Use straight alpha, since we want to save this as a PNG
-        let actual_image = renderer
-            .capture_frame(false)
-            .expect("Failed to capture image");
-
-        let info = renderer.descriptors().adapter.get_info();
-        let suffix = format!("{}-{:?}", std::env::consts::OS, info.backend);
+        let (actual_image, suffix) = capture_image(&mut player_lock);
 
         let expected_image_path = base_path.join(format!("expected-{}.png", &suffix));
         let expected_image = image::open(&expected_image_path);
@@ -1559,6 +1743,115 @@ impl LogBackend for TestLogBackend {
     }
 }
 
+/// The port on which `TestSocketServer` accepts connections.
+const TEST_SOCKET_PORT: u16 = 8000;
+
+/// A connection accepted by `TestSocketServer`.
+struct TestSocket {
+    handle: SocketHandle,
+    receiver: Receiver<Vec<u8>>,
+    sender: Sender<SocketAction>,
+}
+
+/// An in-process server for socket tests, which echoes everything it receives.
+///
+/// Only connections to `TEST_SOCKET_PORT` succeed, and the server closes a
+/// connection after echoing any data containing `<close/>`.
+#[derive(Clone, Default)]
+struct TestSocketServer {
+    sockets: Rc<RefCell<Vec<TestSocket>>>,
+}
+
+impl TestSocketServer {
+    fn connect(
+        &self,
+        port: u16,
+        handle: SocketHandle,
+        receiver: Receiver<Vec<u8>>,
+        sender: Sender<SocketAction>,
+    ) {
+        if port != TEST_SOCKET_PORT {
+            let _ = sender.send(SocketAction::Connect(handle, ConnectionState::Failed));
+            return;
+        }
+
+        let _ = sender.send(SocketAction::Connect(handle, ConnectionState::Connected));
+        self.sockets.borrow_mut().push(TestSocket {
+            handle,
+            receiver,
+            sender,
+        });
+    }
+
+    /// Echo back everything sent since the last call.
+    fn pump(&self) {
+        self.sockets.borrow_mut().retain(|socket| loop {
+            match socket.receiver.try_recv() {
+                Ok(data) => {
+                    let close = data.windows(8).any(|window| window == b"<close/>");
+                    let _ = socket.sender.send(SocketAction::Data(socket.handle, data));
+                    if close {
+                        let _ = socket.sender.send(SocketAction::Close(socket.handle));
+                        return false;
+                    }
+                }
+                Err(TryRecvError::Empty) => return true,
+                Err(TryRecvError::Disconnected) => return false,
+            }
+        });
+    }
+}
+
+/// A `NullNavigatorBackend` whose socket connections go to a `TestSocketServer`.
+struct TestNavigatorBackend {
+    inner: NullNavigatorBackend,
+    socket_server: TestSocketServer,
+}
+
+impl TestNavigatorBackend {
+    fn new(inner: NullNavigatorBackend, socket_server: TestSocketServer) -> Self {
+        Self {
+            inner,
+            socket_server,
+        }
+    }
+}
+
+impl NavigatorBackend for TestNavigatorBackend {
+    fn navigate_to_url(
+        &self,
+        url: String,
+        target: String,
+        vars_method: Option<(NavigationMethod, IndexMap<String, String>)>,
+    ) {
+        self.inner.navigate_to_url(url, target, vars_method)
+    }
+
+    fn fetch(&self, request: Request) -> OwnedFuture<Response, LoaderError> {
+        self.inner.fetch(request)
+    }
+
+    fn spawn_future(&mut self, future: OwnedFuture<(), LoaderError>) {
+        self.inner.spawn_future(future)
+    }
+
+    fn pre_process_url(&self, url: Url) -> Url {
+        self.inner.pre_process_url(url)
+    }
+
+    fn connect_socket(
+        &mut self,
+        _host: String,
+        port: u16,
+        _timeout: Duration,
+        handle: SocketHandle,
+        receiver: Receiver<Vec<u8>>,
+        sender: Sender<SocketAction>,
+    ) {
+        self.socket_server.connect(port, handle, receiver, sender);
+    }
+}
+
 #[derive(Default)]
 pub struct ExternalInterfaceTestProvider {}
 
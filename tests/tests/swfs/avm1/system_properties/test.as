@@ -0,0 +1,5 @@
+trace("version: " + System.capabilities.version);
+trace("os: " + System.capabilities.os);
+trace("playerType: " + System.capabilities.playerType);
+trace("manufacturer: " + System.capabilities.manufacturer);
+trace("serverString: " + System.capabilities.serverString);
@@ -0,0 +1,32 @@
+// The test harness echoes everything sent to port 8000, and closes the
+// connection after echoing "<close/>". Connections to any other port fail.
+
+function onFailedConnect(success) {
+	trace("onConnect: " + success);
+	this.onConnect = onOpenConnect;
+	trace("connect to port 8000: " + this.connect("localhost", 8000));
+}
+
+function onOpenConnect(success) {
+	trace("onConnect: " + success);
+	this.send("hello");
+	this.send(new XML("<message><id>1</id></message>"));
+	this.send("<close/>");
+}
+
+function onSocketXML(xml) {
+	trace("onXML: " + xml);
+}
+
+function onSocketClose() {
+	trace("onClose");
+	trace("///Done");
+}
+
+var socket = new XMLSocket();
+trace("connect to port 0: " + socket.connect("localhost", 0));
+trace("connect without a port: " + socket.connect("localhost"));
+socket.onConnect = onFailedConnect;
+socket.onXML = onSocketXML;
+socket.onClose = onSocketClose;
+trace("connect to port 8001: " + socket.connect("localhost", 8001));
@@ -0,0 +1,66 @@
+package {
+	import flash.display.BitmapData;
+	import flash.display.MovieClip;
+	import flash.filters.BevelFilter;
+	import flash.filters.BitmapFilter;
+	import flash.filters.BlurFilter;
+	import flash.filters.ColorMatrixFilter;
+	import flash.filters.ConvolutionFilter;
+	import flash.filters.DisplacementMapFilter;
+	import flash.filters.DropShadowFilter;
+	import flash.filters.GlowFilter;
+	import flash.filters.GradientGlowFilter;
+	import flash.geom.Point;
+	import flash.geom.Rectangle;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			var map:BitmapData = new BitmapData(20, 20, false, 0xFF8040);
+			var filters:Array = [
+				["BlurFilter", new BlurFilter(4, 4, 1)],
+				["GlowFilter", new GlowFilter(0x00FF00, 1, 4, 4, 2, 1)],
+				["DropShadowFilter", new DropShadowFilter(4, 45, 0x0000FF, 1, 4, 4, 1, 1)],
+				["BevelFilter", new BevelFilter(4, 45, 0xFFFFFF, 1, 0x000000, 1, 4, 4, 1, 1)],
+				["GradientGlowFilter", new GradientGlowFilter(0, 45, [0xFF0000, 0x0000FF], [0, 1], [0, 255], 4, 4, 1, 1)],
+				["ColorMatrixFilter", new ColorMatrixFilter([0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0])],
+				["ConvolutionFilter", new ConvolutionFilter(3, 3, [0, 1, 0, 1, 1, 1, 0, 1, 0], 5)],
+				["DisplacementMapFilter", new DisplacementMapFilter(map, new Point(0, 0), 1, 2, 4, 4)]
+			];
+			var rects:Array = [
+				new Rectangle(0, 0, 20, 20),
+				new Rectangle(5, 5, 10, 10),
+				new Rectangle(-10, -10, 20, 20),
+				new Rectangle(10, 10, 100000, 100000),
+				new Rectangle(-2147483648, -2147483648, 2147483647, 2147483647)
+			];
+
+			for each (var entry:Array in filters) {
+				var filter:BitmapFilter = entry[1];
+				trace("// " + entry[0]);
+				for each (var rect:Rectangle in rects) {
+					var source:BitmapData = createSource();
+					var dest:BitmapData = new BitmapData(20, 20, true, 0);
+					dest.applyFilter(source, rect, new Point(0, 0), filter);
+					trace("applyFilter(" + rect + "): " + pixels(dest));
+					trace("generateFilterRect(" + rect + "): " + source.generateFilterRect(rect, filter));
+				}
+			}
+		}
+
+		private function createSource():BitmapData {
+			var source:BitmapData = new BitmapData(20, 20, true, 0);
+			source.fillRect(new Rectangle(5, 5, 10, 10), 0xFFFF0000);
+			source.fillRect(new Rectangle(8, 8, 4, 4), 0x800000FF);
+			return source;
+		}
+
+		private function pixels(bitmap:BitmapData):String {
+			var result:Array = [];
+			var points:Array = [[0, 0], [3, 3], [5, 5], [9, 9], [10, 10], [14, 14], [16, 16], [19, 19]];
+			for each (var point:Array in points) {
+				result.push(bitmap.getPixel32(point[0], point[1]).toString(16));
+			}
+			return result.join(",");
+		}
+	}
+}
@@ -0,0 +1,81 @@
+package {
+	import flash.display.BlendMode;
+	import flash.display.MovieClip;
+	import flash.display.Shape;
+	import flash.display.Sprite;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			// A background with different colors and alphas for the blend modes to read from.
+			var background:Shape = new Shape();
+			var colors:Array = [0xFF0000, 0x00FF00, 0x0000FF, 0xFFFFFF, 0x000000, 0x808080];
+			for (var i:int = 0; i < colors.length; i++) {
+				background.graphics.beginFill(colors[i], i % 2 == 0 ? 1 : 0.5);
+				background.graphics.drawRect(0, i * 60, 550, 30);
+				background.graphics.endFill();
+			}
+			addChild(background);
+
+			var modes:Array = [
+				BlendMode.ADD,
+				BlendMode.SUBTRACT,
+				BlendMode.MULTIPLY,
+				BlendMode.SCREEN,
+				BlendMode.LIGHTEN,
+				BlendMode.DARKEN,
+				BlendMode.DIFFERENCE,
+				BlendMode.INVERT,
+				BlendMode.OVERLAY,
+				BlendMode.HARDLIGHT
+			];
+			for (var j:int = 0; j < modes.length; j++) {
+				var shape:Shape = createShape();
+				shape.x = 10 + (j % 5) * 108;
+				shape.y = 10 + int(j / 5) * 180;
+				shape.blendMode = modes[j];
+				addChild(shape);
+				trace(shape.blendMode);
+			}
+
+			// Alpha and Erase only affect the layer of their parent.
+			addLayered(BlendMode.ALPHA, 10, true);
+			addLayered(BlendMode.ERASE, 150, true);
+			addLayered(BlendMode.ALPHA, 290, false);
+			addLayered(BlendMode.ERASE, 430, false);
+		}
+
+		private function createShape():Shape {
+			var shape:Shape = new Shape();
+			shape.graphics.beginFill(0xFF8000);
+			shape.graphics.drawRect(0, 0, 45, 150);
+			shape.graphics.endFill();
+			shape.graphics.beginFill(0x4080FF, 0.6);
+			shape.graphics.drawRect(45, 0, 45, 150);
+			shape.graphics.endFill();
+			return shape;
+		}
+
+		private function addLayered(mode:String, x:Number, layer:Boolean):void {
+			var container:Sprite = new Sprite();
+			container.graphics.beginFill(0x00C000);
+			container.graphics.drawRect(0, 0, 100, 40);
+			container.graphics.endFill();
+			if (layer) {
+				container.blendMode = BlendMode.LAYER;
+			}
+			var child:Shape = new Shape();
+			child.graphics.beginFill(0x000000, 0.25);
+			child.graphics.drawCircle(25, 20, 15);
+			child.graphics.endFill();
+			child.graphics.beginFill(0x000000, 1);
+			child.graphics.drawCircle(75, 20, 15);
+			child.graphics.endFill();
+			child.blendMode = mode;
+			container.addChild(child);
+			container.x = x;
+			container.y = 350;
+			addChild(container);
+			trace(container.blendMode + " > " + child.blendMode);
+		}
+	}
+}
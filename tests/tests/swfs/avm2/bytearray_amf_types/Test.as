@@ -0,0 +1,185 @@
+package {
+	import flash.display.MovieClip;
+	import flash.net.registerClassAlias;
+	import flash.net.getClassByAlias;
+	import flash.utils.ByteArray;
+	import flash.utils.Dictionary;
+	import flash.utils.IDataInput;
+	import flash.utils.IDataOutput;
+	import flash.utils.IExternalizable;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			registerClassAlias("test.Item", Item);
+			registerClassAlias("test.Score", Score);
+			trace("getClassByAlias(\"test.Item\") == Item: " + (getClassByAlias("test.Item") == Item));
+			try {
+				getClassByAlias("test.Missing");
+			} catch (e:ReferenceError) {
+				trace("getClassByAlias(\"test.Missing\") threw " + e.errorID);
+			}
+
+			trace("AMF3");
+			runTests(3);
+			trace("AMF0");
+			runTests(0);
+
+			trace("Bytes");
+			var ba:ByteArray = new ByteArray();
+			var single:Single = new Single();
+			single.value = 5;
+			registerClassAlias("S", Single);
+			ba.writeObject(single);
+			ba.writeObject(Vector.<int>([1, -1]));
+			ba.writeObject("repeated");
+			dump(ba);
+		}
+
+		private function roundTrip(value:*, encoding:uint):* {
+			var ba:ByteArray = new ByteArray();
+			ba.objectEncoding = encoding;
+			ba.writeObject(value);
+			ba.position = 0;
+			var result:* = ba.readObject();
+			if (ba.bytesAvailable != 0) {
+				trace("  " + ba.bytesAvailable + " bytes left over");
+			}
+			return result;
+		}
+
+		private function runTests(encoding:uint):void {
+			var item:Item = new Item();
+			item.name = "sword";
+			item.count = 3;
+			item.level = 7;
+			item.setSecret("hidden");
+			item.extra = "dynamic";
+			var result:* = roundTrip(item, encoding);
+			trace("Item: " + (result is Item) + " " + result.name + " " + result.count + " " + result.level + " " + result.secret + " " + result.extra);
+
+			var unregistered:Unregistered = new Unregistered();
+			unregistered.value = 12;
+			result = roundTrip(unregistered, encoding);
+			trace("Unregistered: " + (result is Unregistered) + " " + result.value);
+
+			var score:Score = new Score("alice", 42);
+			result = roundTrip(score, encoding);
+			trace("Score: " + (result is Score) + " " + result.player + " " + result.points);
+
+			var ints:Vector.<int> = Vector.<int>([1, -2, 3]);
+			result = roundTrip(ints, encoding);
+			trace("Vector.<int>: " + (result is Vector.<int>) + " " + result);
+
+			var uints:Vector.<uint> = Vector.<uint>([1, 4000000000]);
+			uints.fixed = true;
+			result = roundTrip(uints, encoding);
+			trace("Vector.<uint>: " + (result is Vector.<uint>) + " " + result + " fixed " + result.fixed);
+
+			var numbers:Vector.<Number> = Vector.<Number>([1.5, -0.25]);
+			result = roundTrip(numbers, encoding);
+			trace("Vector.<Number>: " + (result is Vector.<Number>) + " " + result);
+
+			var items:Vector.<Item> = new Vector.<Item>();
+			items.push(item, item);
+			result = roundTrip(items, encoding);
+			trace("Vector.<Item>: " + (result is Vector.<Item>) + " " + result.length + " " + result[0].name + " same " + (result[0] === result[1]));
+
+			var key:Object = {};
+			var dict:Dictionary = new Dictionary();
+			dict[key] = "object key";
+			dict["name"] = "string key";
+			result = roundTrip(dict, encoding);
+			trace("Dictionary: " + (result is Dictionary));
+			for (var k:* in result) {
+				if (k is String) {
+					trace("  " + k + " = " + result[k]);
+				} else {
+					trace("  [object] = " + result[k]);
+				}
+			}
+
+			var bytes:ByteArray = new ByteArray();
+			bytes.writeUTFBytes("abc");
+			result = roundTrip(bytes, encoding);
+			trace("ByteArray: " + (result is ByteArray) + " " + result.length + " " + result.toString());
+
+			var xml:XML = <root><child attr="1">text</child></root>;
+			result = roundTrip(xml, encoding);
+			trace("XML: " + (result is XML) + " " + result.child.@attr + " " + result.child);
+
+			var shared:Object = {value: 1};
+			var container:Object = {first: shared, second: shared, list: [shared]};
+			container.self = container;
+			result = roundTrip(container, encoding);
+			trace("References: " + (result.first === result.second) + " " + (result.list[0] === result.first) + " " + (result.self === result));
+
+			var date:Date = new Date(2000, 0, 1);
+			result = roundTrip([date, date], encoding);
+			trace("Dates: " + (result[0].time == date.time) + " " + (result[0] === result[1]));
+		}
+
+		private function dump(ba:ByteArray):void {
+			var out:String = "";
+			for (var i:int = 0; i < ba.length; i++) {
+				var hex:String = ba[i].toString(16);
+				out += (hex.length == 1 ? "0" : "") + hex + " ";
+			}
+			trace(out);
+		}
+	}
+}
+
+dynamic class Item {
+	public var name:String;
+	public var count:int;
+	private var _level:int;
+	private var _secret:String;
+
+	public function get level():int {
+		return _level;
+	}
+
+	public function set level(value:int):void {
+		_level = value;
+	}
+
+	public function get secret():String {
+		return _secret;
+	}
+
+	public function setSecret(value:String):void {
+		_secret = value;
+	}
+}
+
+class Unregistered {
+	public var value:int;
+}
+
+class Single {
+	public var value:int;
+}
+
+import flash.utils.IDataInput;
+import flash.utils.IDataOutput;
+import flash.utils.IExternalizable;
+
+class Score implements IExternalizable {
+	public var player:String;
+	public var points:int;
+
+	public function Score(player:String = null, points:int = 0) {
+		this.player = player;
+		this.points = points;
+	}
+
+	public function writeExternal(output:IDataOutput):void {
+		output.writeUTF(player);
+		output.writeInt(points);
+	}
+
+	public function readExternal(input:IDataInput):void {
+		player = input.readUTF();
+		points = input.readInt();
+	}
+}
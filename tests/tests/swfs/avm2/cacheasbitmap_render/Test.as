@@ -0,0 +1,106 @@
+package {
+	import flash.display.Bitmap;
+	import flash.display.BitmapData;
+	import flash.display.MovieClip;
+	import flash.display.Shape;
+	import flash.display.Sprite;
+	import flash.events.Event;
+	import flash.geom.Rectangle;
+
+	public class Test extends MovieClip {
+		private var frame:int = 0;
+		private var moved:Sprite;
+		private var mover:Shape;
+		private var drawn:Sprite;
+		private var bitmapData:BitmapData;
+		private var scaled:Sprite;
+		private var background:Sprite;
+
+		public function Test() {
+			// A cached sprite whose child moves after it was cached.
+			moved = new Sprite();
+			moved.graphics.beginFill(0xFF0000);
+			moved.graphics.drawRect(0, 0, 100, 60);
+			moved.graphics.endFill();
+			mover = new Shape();
+			mover.graphics.beginFill(0x0000FF);
+			mover.graphics.drawCircle(0, 0, 20);
+			mover.graphics.endFill();
+			mover.x = 30;
+			mover.y = 30;
+			moved.addChild(mover);
+			moved.x = 20.5;
+			moved.y = 20.5;
+			moved.cacheAsBitmap = true;
+			addChild(moved);
+
+			// A sprite with an opaque background, which is drawn into after it was cached.
+			drawn = new Sprite();
+			drawn.graphics.beginFill(0x0000FF);
+			drawn.graphics.drawCircle(40, 40, 40);
+			drawn.graphics.endFill();
+			drawn.x = 200;
+			drawn.y = 20;
+			drawn.opaqueBackground = 0x00FF00;
+			addChild(drawn);
+
+			// A cached sprite containing a bitmap whose pixels change after it was cached.
+			bitmapData = new BitmapData(60, 60, false, 0xFF0000);
+			var holder:Sprite = new Sprite();
+			holder.addChild(new Bitmap(bitmapData));
+			holder.x = 350;
+			holder.y = 20;
+			holder.cacheAsBitmap = true;
+			addChild(holder);
+
+			// A cached sprite that is scaled and rotated after it was cached.
+			scaled = new Sprite();
+			scaled.graphics.beginFill(0xFF00FF);
+			scaled.graphics.drawRect(0, 0, 40, 40);
+			scaled.graphics.endFill();
+			scaled.graphics.beginFill(0x000000);
+			scaled.graphics.drawRect(10, 10, 20, 20);
+			scaled.graphics.endFill();
+			scaled.x = 60;
+			scaled.y = 160;
+			scaled.cacheAsBitmap = true;
+			addChild(scaled);
+
+			// An opaque background that is changed, and a cached sprite that is removed.
+			background = new Sprite();
+			background.graphics.beginFill(0x000000);
+			background.graphics.drawCircle(40, 40, 20);
+			background.graphics.endFill();
+			background.x = 250;
+			background.y = 180;
+			background.opaqueBackground = 0xFFFF00;
+			addChild(background);
+
+			trace("moved.cacheAsBitmap: " + moved.cacheAsBitmap);
+			trace("drawn.cacheAsBitmap: " + drawn.cacheAsBitmap);
+			trace("drawn.opaqueBackground: " + drawn.opaqueBackground);
+			trace("background.opaqueBackground: " + background.opaqueBackground);
+
+			addEventListener(Event.ENTER_FRAME, onEnterFrame);
+		}
+
+		private function onEnterFrame(event:Event):void {
+			frame++;
+			if (frame == 2) {
+				mover.x = 70;
+				moved.x = 30.5;
+				drawn.graphics.beginFill(0xFF0000);
+				drawn.graphics.drawRect(60, 60, 40, 40);
+				drawn.graphics.endFill();
+				bitmapData.fillRect(new Rectangle(20, 20, 20, 20), 0x0000FF);
+				scaled.scaleX = 2;
+				scaled.rotation = 20;
+				background.opaqueBackground = 0x00FFFF;
+				trace("background.opaqueBackground: " + background.opaqueBackground);
+			} else if (frame == 3) {
+				trace("Updated cached objects");
+				removeEventListener(Event.ENTER_FRAME, onEnterFrame);
+			}
+		}
+	}
+}
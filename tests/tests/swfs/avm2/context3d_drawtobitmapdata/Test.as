@@ -0,0 +1,108 @@
+package {
+	import flash.display.Bitmap;
+	import flash.display.BitmapData;
+	import flash.display.MovieClip;
+	import flash.display.Stage3D;
+	import flash.display3D.Context3D;
+	import flash.display3D.Context3DProgramType;
+	import flash.display3D.Context3DRenderMode;
+	import flash.display3D.Context3DVertexBufferFormat;
+	import flash.display3D.IndexBuffer3D;
+	import flash.display3D.Program3D;
+	import flash.display3D.VertexBuffer3D;
+	import flash.events.Event;
+	import flash.utils.ByteArray;
+	import flash.utils.Endian;
+
+	public class Test extends MovieClip {
+		private var stage3D:Stage3D;
+
+		public function Test() {
+			stage3D = stage.stage3Ds[0];
+			stage3D.addEventListener(Event.CONTEXT3D_CREATE, contextCreated);
+			stage3D.requestContext3D(Context3DRenderMode.AUTO);
+		}
+
+		// Assembles a shader made of a single `mov` instruction.
+		private function movShader(type:int, destType:int, sourceType:int):ByteArray {
+			var agal:ByteArray = new ByteArray();
+			agal.endian = Endian.LITTLE_ENDIAN;
+			agal.writeByte(0xa0);
+			agal.writeUnsignedInt(1);
+			agal.writeByte(0xa1);
+			agal.writeByte(type);
+			// mov
+			agal.writeUnsignedInt(0);
+			// Destination register 0, with the mask xyzw.
+			agal.writeShort(0);
+			agal.writeByte(0xf);
+			agal.writeByte(destType);
+			// Source register 0, with the swizzle xyzw.
+			agal.writeShort(0);
+			agal.writeByte(0);
+			agal.writeByte(0xe4);
+			agal.writeByte(sourceType);
+			agal.writeByte(0);
+			agal.writeShort(0);
+			// Unused second source.
+			agal.writeUnsignedInt(0);
+			agal.writeUnsignedInt(0);
+			return agal;
+		}
+
+		private function hex(value:uint):String {
+			return "0x" + value.toString(16).toUpperCase();
+		}
+
+		private function contextCreated(event:Event):void {
+			var context:Context3D = stage3D.context3D;
+			context.configureBackBuffer(64, 64, 0, true);
+
+			// A quad covering the left half of the back buffer.
+			var vertices:VertexBuffer3D = context.createVertexBuffer(4, 4);
+			vertices.uploadFromVector(Vector.<Number>([
+				-1, -1, 0, 1,
+				0, -1, 0, 1,
+				0, 1, 0, 1,
+				-1, 1, 0, 1
+			]), 0, 4);
+			var indices:IndexBuffer3D = context.createIndexBuffer(6);
+			indices.uploadFromVector(Vector.<uint>([0, 1, 2, 0, 2, 3]), 0, 6);
+
+			// `mov op, va0` and `mov oc, fc0`
+			var program:Program3D = context.createProgram();
+			program.upload(movShader(0, 3, 0), movShader(1, 3, 1));
+			context.setProgram(program);
+			context.setVertexBufferAt(0, vertices, 0, Context3DVertexBufferFormat.FLOAT_4);
+			context.setProgramConstantsFromVector(Context3DProgramType.FRAGMENT, 0, Vector.<Number>([0, 0, 1, 1]));
+
+			context.clear(1, 0, 0, 1);
+			context.drawTriangles(indices);
+
+			var full:BitmapData = new BitmapData(64, 64, true, 0);
+			context.drawToBitmapData(full);
+			trace("full.getPixel32(0, 0): " + hex(full.getPixel32(0, 0)));
+			trace("full.getPixel32(31, 32): " + hex(full.getPixel32(31, 32)));
+			trace("full.getPixel32(32, 32): " + hex(full.getPixel32(32, 32)));
+			trace("full.getPixel32(63, 63): " + hex(full.getPixel32(63, 63)));
+
+			// A smaller BitmapData only receives the top left of the back buffer.
+			var small:BitmapData = new BitmapData(16, 16, true, 0);
+			context.drawToBitmapData(small);
+			trace("small.getPixel32(15, 15): " + hex(small.getPixel32(15, 15)));
+
+			// A larger BitmapData keeps its pixels outside of the back buffer.
+			var large:BitmapData = new BitmapData(80, 80, true, 0xFF00FF00);
+			context.drawToBitmapData(large);
+			trace("large.getPixel32(63, 0): " + hex(large.getPixel32(63, 0)));
+			trace("large.getPixel32(64, 0): " + hex(large.getPixel32(64, 0)));
+			trace("large.getPixel32(0, 79): " + hex(large.getPixel32(0, 79)));
+
+			context.present();
+
+			var bitmap:Bitmap = new Bitmap(full);
+			bitmap.x = 100;
+			addChild(bitmap);
+		}
+	}
+}
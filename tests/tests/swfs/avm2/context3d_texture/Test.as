@@ -0,0 +1,99 @@
+package {
+	import flash.display.Bitmap;
+	import flash.display.BitmapData;
+	import flash.display.MovieClip;
+	import flash.display.Stage3D;
+	import flash.display3D.Context3D;
+	import flash.display3D.Context3DRenderMode;
+	import flash.display3D.Context3DTextureFormat;
+	import flash.display3D.Context3DVertexBufferFormat;
+	import flash.display3D.IndexBuffer3D;
+	import flash.display3D.Program3D;
+	import flash.display3D.VertexBuffer3D;
+	import flash.display3D.textures.Texture;
+	import flash.events.Event;
+	import flash.utils.ByteArray;
+
+	public class Test extends MovieClip {
+		private var stage3D:Stage3D;
+
+		public function Test() {
+			stage3D = stage.stage3Ds[0];
+			stage3D.addEventListener(Event.CONTEXT3D_CREATE, contextCreated);
+			stage3D.requestContext3D(Context3DRenderMode.AUTO);
+		}
+
+		private function agal(bytes:Array):ByteArray {
+			var data:ByteArray = new ByteArray();
+			for each (var byte:int in bytes) {
+				data.writeByte(byte);
+			}
+			return data;
+		}
+
+		private function hex(value:uint):String {
+			return "0x" + value.toString(16).toUpperCase();
+		}
+
+		private function contextCreated(event:Event):void {
+			var context:Context3D = stage3D.context3D;
+			context.configureBackBuffer(64, 64, 0, false);
+
+			// A quad covering the back buffer, with the top left corner of the texture at the top left.
+			var vertices:VertexBuffer3D = context.createVertexBuffer(4, 6);
+			vertices.uploadFromVector(Vector.<Number>([
+				-1, 1, 0, 1, 0, 0,
+				1, 1, 0, 1, 1, 0,
+				1, -1, 0, 1, 1, 1,
+				-1, -1, 0, 1, 0, 1
+			]), 0, 4);
+			var indices:IndexBuffer3D = context.createIndexBuffer(6);
+			indices.uploadFromVector(Vector.<uint>([0, 1, 2, 0, 2, 3]), 0, 6);
+
+			// `mov op, va0` and `mov v0, va1`
+			var vertexShader:ByteArray = agal([
+				0xa0, 1, 0, 0, 0, 0xa1, 0,
+				0, 0, 0, 0, 0, 0, 15, 3, 0, 0, 0, 228, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+				0, 0, 0, 0, 0, 0, 15, 4, 1, 0, 0, 228, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0
+			]);
+			// `tex oc, v0, fs0 <2d, clamp, nearest, mipnone>`
+			var fragmentShader:ByteArray = agal([
+				0xa0, 1, 0, 0, 0, 0xa1, 1,
+				40, 0, 0, 0, 0, 0, 15, 3, 0, 0, 0, 228, 4, 0, 0, 0, 0, 0, 0, 0, 5, 0, 0, 0
+			]);
+			var program:Program3D = context.createProgram();
+			program.upload(vertexShader, fragmentShader);
+			context.setProgram(program);
+			context.setVertexBufferAt(0, vertices, 0, Context3DVertexBufferFormat.FLOAT_4);
+			context.setVertexBufferAt(1, vertices, 4, Context3DVertexBufferFormat.FLOAT_2);
+
+			// A different color in each corner, to check the orientation and channel order.
+			var source:BitmapData = new BitmapData(2, 2, true, 0);
+			source.setPixel32(0, 0, 0xFFFF0000);
+			source.setPixel32(1, 0, 0xFF00FF00);
+			source.setPixel32(0, 1, 0xFF0000FF);
+			source.setPixel32(1, 1, 0xFFFFFF00);
+
+			var texture:Texture = context.createTexture(2, 2, Context3DTextureFormat.BGRA, false);
+			trace("texture: " + texture);
+			texture.uploadFromBitmapData(source);
+			context.setTextureAt(0, texture);
+
+			context.clear(0, 0, 0, 1);
+			context.drawTriangles(indices);
+
+			var result:BitmapData = new BitmapData(64, 64, true, 0);
+			context.drawToBitmapData(result);
+			trace("result.getPixel32(16, 16): " + hex(result.getPixel32(16, 16)));
+			trace("result.getPixel32(48, 16): " + hex(result.getPixel32(48, 16)));
+			trace("result.getPixel32(16, 48): " + hex(result.getPixel32(16, 48)));
+			trace("result.getPixel32(48, 48): " + hex(result.getPixel32(48, 48)));
+
+			context.present();
+
+			var bitmap:Bitmap = new Bitmap(result);
+			bitmap.x = 100;
+			addChild(bitmap);
+		}
+	}
+}
@@ -0,0 +1,117 @@
+package {
+	import flash.display.MovieClip;
+	import flash.utils.describeType;
+	import avmplus.describeTypeJSON;
+	import avmplus.INCLUDE_BASES;
+	import avmplus.INCLUDE_METHODS;
+	import avmplus.INCLUDE_TRAITS;
+	import avmplus.INCLUDE_VARIABLES;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			var instance:XML = describeType(new Example());
+			trace("// describeType(new Example())");
+			trace(instance.@name);
+			trace(instance.@base);
+			trace(instance.@isDynamic);
+			trace(instance.@isFinal);
+			trace(instance.@isStatic);
+			trace(instance.extendsClass.@type.toXMLString());
+			trace(instance.implementsInterface.@type.toXMLString());
+			trace(instance.constructor.parameter.toXMLString());
+			trace(instance.variable.(@name == "injected").toXMLString());
+			trace(instance.variable.(@name == "baseVar").toXMLString());
+			trace(instance.constant.(@name == "answer").toXMLString());
+			trace(instance.variable.(@name == "secret").length());
+			trace(instance.accessor.(@name == "readWrite").toXMLString());
+			trace(instance.accessor.(@name == "onlyGet").toXMLString());
+			trace(instance.accessor.(@name == "onlySet").toXMLString());
+			trace(instance.method.(@name == "run").toXMLString());
+			trace(instance.method.(@name == "other").toXMLString());
+			trace(instance.metadata.toXMLString());
+			trace(instance.factory.length());
+
+			var statics:XML = describeType(Example);
+			trace("// describeType(Example)");
+			trace(statics.@name);
+			trace(statics.@base);
+			trace(statics.@isDynamic);
+			trace(statics.@isFinal);
+			trace(statics.@isStatic);
+			trace(statics.constant.(@name == "CONST").toXMLString());
+			trace(statics.method.(@name == "make").toXMLString());
+			trace(statics.accessor.(@name == "prototype").toXMLString());
+			trace(statics.factory.@type);
+			trace(statics.factory.method.(@name == "run").@declaredBy);
+
+			trace("// describeType(null)");
+			trace(describeType(null).toXMLString());
+
+			var json:Object = describeTypeJSON(new Example(), INCLUDE_TRAITS | INCLUDE_BASES | INCLUDE_METHODS);
+			trace("// describeTypeJSON(new Example(), INCLUDE_TRAITS | INCLUDE_BASES | INCLUDE_METHODS)");
+			trace(json.name);
+			trace(json.isDynamic);
+			trace(json.traits.bases);
+			trace(json.traits.variables);
+			trace(json.traits.accessors);
+			for each (var method:Object in json.traits.methods) {
+				if (method.name == "other") {
+					trace(method.returnType);
+					trace(method.declaredBy);
+					trace(method.parameters.length);
+					trace(method.parameters[0].type);
+					trace(method.parameters[0].optional);
+				}
+			}
+
+			json = describeTypeJSON(new Example(), INCLUDE_VARIABLES);
+			trace("// describeTypeJSON(new Example(), INCLUDE_VARIABLES)");
+			trace(json.traits);
+		}
+	}
+}
+
+interface IExample {
+	function run(a:int):void;
+}
+
+[Event(name="change", type="flash.events.Event")]
+class Base {
+	public var baseVar:String;
+
+	public function get onlyGet():int {
+		return 1;
+	}
+
+	public function set readWrite(value:Number):void {}
+
+	public function set onlySet(value:String):void {}
+}
+
+[Bindable]
+dynamic class Example extends Base implements IExample {
+	public static const CONST:String = "c";
+
+	public static function make():Example {
+		return new Example();
+	}
+
+	[Inject(name="a", "positional")]
+	public var injected:Object;
+
+	public const answer:int = 42;
+
+	private var secret:int;
+
+	public function Example(x:int = 1) {}
+
+	public function get readWrite():Number {
+		return 0;
+	}
+
+	public function run(a:int):void {}
+
+	public function other(s:String, n:Number = 0, ...rest):Example {
+		return this;
+	}
+}
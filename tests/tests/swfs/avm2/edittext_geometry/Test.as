@@ -0,0 +1,64 @@
+package {
+	import flash.display.MovieClip;
+	import flash.text.TextField;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			var field:TextField = new TextField();
+			field.width = 300;
+			field.height = 100;
+			field.multiline = true;
+			field.text = "Hello world\rSecond line\rThird";
+
+			trace("// lines");
+			trace(field.numLines);
+			for (var i:int = 0; i < field.numLines; i++) {
+				trace(i + ": " + field.getLineOffset(i) + " " + field.getLineLength(i) + " " + escape(field.getLineText(i)));
+			}
+			try {
+				field.getLineText(10);
+			} catch (e:RangeError) {
+				trace(e.errorID);
+			}
+
+			trace("// getLineIndexOfChar");
+			trace(field.getLineIndexOfChar(0));
+			trace(field.getLineIndexOfChar(11));
+			trace(field.getLineIndexOfChar(12));
+			trace(field.getLineIndexOfChar(28));
+			trace(field.getLineIndexOfChar(100));
+			trace(field.getLineIndexOfChar(-1));
+
+			trace("// paragraphs");
+			trace(field.getFirstCharInParagraph(5));
+			trace(field.getFirstCharInParagraph(15));
+			trace(field.getParagraphLength(15));
+			trace(field.getParagraphLength(26));
+			trace(field.getFirstCharInParagraph(100));
+
+			trace("// getCharBoundaries");
+			var first:Object = field.getCharBoundaries(0);
+			var second:Object = field.getCharBoundaries(1);
+			var below:Object = field.getCharBoundaries(12);
+			trace(first.x);
+			trace(first.width > 0);
+			trace(second.x == first.x + first.width);
+			trace(below.y > first.y);
+			trace(field.getCharBoundaries(100));
+
+			trace("// points");
+			trace(field.getCharIndexAtPoint(first.x + 1, first.y + 1));
+			trace(field.getCharIndexAtPoint(second.x + 1, second.y + 1));
+			trace(field.getCharIndexAtPoint(below.x + 1, below.y + 1));
+			trace(field.getCharIndexAtPoint(290, 90));
+			trace(field.getLineIndexAtPoint(5, first.y + 1));
+			trace(field.getLineIndexAtPoint(5, below.y + 1));
+			trace(field.getLineIndexAtPoint(5, 95));
+
+			trace("// selection");
+			trace(field.selectionBeginIndex, field.selectionEndIndex, field.caretIndex);
+			field.setSelection(8, 3);
+			trace(field.selectionBeginIndex, field.selectionEndIndex, field.caretIndex);
+		}
+	}
+}
@@ -0,0 +1,7 @@
+package {
+	import flash.display.MovieClip;
+
+	// Linked to a library symbol containing a 30x20 red rectangle.
+	public class RedBox extends MovieClip {
+	}
+}
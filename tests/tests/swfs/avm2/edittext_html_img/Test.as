@@ -0,0 +1,80 @@
+package {
+	import flash.display.DisplayObject;
+	import flash.display.MovieClip;
+	import flash.events.Event;
+	import flash.geom.Rectangle;
+	import flash.text.TextField;
+
+	public class Test extends MovieClip {
+		private var urlField:TextField;
+		private var frames:int = 0;
+
+		public function Test() {
+			var field:TextField = makeField();
+
+			trace("// text with images");
+			field.htmlText = "Hello <img src=\"missing_image\" id=\"pic\" width=\"20\" height=\"10\" align=\"right\"> world";
+			trace(field.text);
+			trace(field.htmlText);
+
+			trace("// getImageReference");
+			var pic:DisplayObject = field.getImageReference("pic");
+			trace(pic != null);
+			trace(pic is MovieClip);
+			trace(field.getImageReference("other"));
+
+			trace("// replaced text");
+			field.htmlText = "No images";
+			trace(field.getImageReference("pic"));
+
+			trace("// library symbol without a size");
+			var plain:TextField = makeField();
+			plain.htmlText = "Beside";
+			var left:TextField = makeField();
+			left.htmlText = "<img src=\"RedBox\" id=\"box\" align=\"left\">Beside";
+			var box:DisplayObject = left.getImageReference("box");
+			trace(box is MovieClip);
+			trace(box.width, box.height);
+
+			trace("// left-aligned image");
+			var leftChar:Rectangle = left.getCharBoundaries(0);
+			var plainChar:Rectangle = plain.getCharBoundaries(0);
+			trace(leftChar.x - plainChar.x);
+			trace(leftChar.y == plainChar.y);
+			trace(Math.round(left.textWidth - plain.textWidth));
+
+			trace("// right-aligned image");
+			var right:TextField = makeField();
+			right.htmlText = "Beside<img src=\"RedBox\" align=\"right\">";
+			var rightChar:Rectangle = right.getCharBoundaries(0);
+			trace(rightChar.x == plainChar.x);
+			trace(rightChar.y == plainChar.y);
+			trace(right.textWidth > plain.textWidth + box.width);
+
+			trace("// image loaded from a URL");
+			urlField = makeField();
+			urlField.htmlText = "<img src=\"blue_box.png\" id=\"url\" align=\"left\">Beside";
+			trace(urlField.getCharBoundaries(0).x);
+			addEventListener(Event.ENTER_FRAME, onEnterFrame);
+		}
+
+		private function makeField():TextField {
+			var field:TextField = new TextField();
+			field.width = 200;
+			field.multiline = true;
+			field.wordWrap = true;
+			return field;
+		}
+
+		private function onEnterFrame(event:Event):void {
+			frames++;
+			if (frames == 3) {
+				trace("// after the image loaded");
+				var url:DisplayObject = urlField.getImageReference("url");
+				trace(url.width, url.height);
+				trace(urlField.getCharBoundaries(0).x);
+				removeEventListener(Event.ENTER_FRAME, onEnterFrame);
+			}
+		}
+	}
+}
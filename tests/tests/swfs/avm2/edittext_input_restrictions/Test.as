@@ -0,0 +1,39 @@
+package {
+	import flash.display.MovieClip;
+	import flash.text.TextField;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			var field:TextField = new TextField();
+
+			trace("// restrict");
+			trace(field.restrict);
+			field.restrict = "A-Z 0-9";
+			trace(field.restrict);
+			field.restrict = "";
+			trace(field.restrict == "");
+			field.restrict = null;
+			trace(field.restrict);
+
+			trace("// maxChars");
+			trace(field.maxChars);
+			field.maxChars = 8;
+			trace(field.maxChars);
+			field.text = "longer than eight characters";
+			trace(field.text);
+			field.maxChars = 0;
+			trace(field.maxChars);
+
+			trace("// condenseWhite");
+			field.multiline = true;
+			trace(field.condenseWhite);
+			field.htmlText = "<p>  Hello \n   world  </p>";
+			trace(escape(field.text));
+			field.condenseWhite = true;
+			trace(field.condenseWhite);
+			trace(escape(field.text));
+			field.htmlText = "<p>  Hello \n   world  </p>";
+			trace(escape(field.text));
+		}
+	}
+}
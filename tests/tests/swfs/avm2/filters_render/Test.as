@@ -0,0 +1,59 @@
+package {
+	import flash.display.MovieClip;
+	import flash.display.Shape;
+	import flash.filters.BevelFilter;
+	import flash.filters.BitmapFilter;
+	import flash.filters.BlurFilter;
+	import flash.filters.DropShadowFilter;
+	import flash.filters.GlowFilter;
+
+	// Each row renders one filter type, in three variations.
+	public class Test extends MovieClip {
+		public function Test() {
+			addRow(20, "BlurFilter", [
+				new BlurFilter(8, 8, 1),
+				new BlurFilter(16, 4, 2),
+				new BlurFilter(10, 10, 3)
+			]);
+			addRow(115, "GlowFilter", [
+				new GlowFilter(0x00FF00, 1, 12, 12, 2, 1),
+				new GlowFilter(0x00FF00, 1, 12, 12, 2, 1, true),
+				new GlowFilter(0x00FF00, 1, 12, 12, 2, 1, false, true)
+			]);
+			addRow(210, "DropShadowFilter", [
+				new DropShadowFilter(8, 45, 0x000000, 1, 6, 6, 1, 1),
+				new DropShadowFilter(8, 45, 0x000000, 1, 6, 6, 1, 1, true),
+				new DropShadowFilter(8, 45, 0x000000, 1, 6, 6, 1, 1, false, false, true)
+			]);
+			addRow(305, "BevelFilter", [
+				new BevelFilter(6, 45, 0xFFFFFF, 1, 0x000000, 1, 6, 6, 1, 1),
+				new BevelFilter(6, 45, 0xFFFFFF, 1, 0x000000, 1, 6, 6, 1, 1, "outer"),
+				new BevelFilter(6, 45, 0xFFFFFF, 1, 0x000000, 1, 6, 6, 1, 1, "full", true)
+			]);
+		}
+
+		private function addRow(y:Number, name:String, filters:Array):void {
+			for (var i:int = 0; i < filters.length; i++) {
+				addShape(60 + i * 160, y, i > 0, filters[i]);
+			}
+			trace("Rendered " + name);
+		}
+
+		private function addShape(x:Number, y:Number, rotated:Boolean, filter:BitmapFilter):void {
+			var shape:Shape = new Shape();
+			shape.graphics.beginFill(0xFF0000);
+			shape.graphics.drawRect(0, 0, 80, 40);
+			shape.graphics.endFill();
+			shape.graphics.beginFill(0x0000FF, 0.5);
+			shape.graphics.drawCircle(40, 55, 20);
+			shape.graphics.endFill();
+			shape.x = x;
+			shape.y = y;
+			if (rotated) {
+				shape.rotation = 10;
+			}
+			shape.filters = [filter];
+			addChild(shape);
+		}
+	}
+}
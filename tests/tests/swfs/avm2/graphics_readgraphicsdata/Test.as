@@ -0,0 +1,94 @@
+package {
+	import flash.display.BitmapData;
+	import flash.display.GraphicsBitmapFill;
+	import flash.display.GraphicsEndFill;
+	import flash.display.GraphicsPath;
+	import flash.display.GraphicsPathCommand;
+	import flash.display.GraphicsPathWinding;
+	import flash.display.GraphicsSolidFill;
+	import flash.display.GraphicsStroke;
+	import flash.display.IGraphicsData;
+	import flash.display.Shape;
+	import flash.display.Sprite;
+	import flash.geom.Matrix;
+	import flash.utils.getQualifiedClassName;
+
+	public class Test extends Sprite {
+		public function Test() {
+			trace("// drawPath with wide commands and nonZero winding");
+			var path:Shape = new Shape();
+			path.graphics.beginFill(0xFF0000);
+			path.graphics.drawRect(0, 0, 10, 10);
+			path.graphics.drawPath(
+				Vector.<int>([GraphicsPathCommand.WIDE_MOVE_TO, GraphicsPathCommand.WIDE_LINE_TO, GraphicsPathCommand.WIDE_LINE_TO]),
+				Vector.<Number>([-1, -1, 20, 0, -1, -1, 30, 0, -1, -1, 30, 10]),
+				GraphicsPathWinding.NON_ZERO);
+			path.graphics.drawRect(40, 0, 10, 10);
+			path.graphics.endFill();
+			dump(path.graphics.readGraphicsData());
+
+			trace("// drawTriangles with culling");
+			var vertices:Vector.<Number> = Vector.<Number>([0, 0, 10, 0, 0, 10, 20, 0, 20, 10, 30, 0]);
+			for each (var culling:String in ["none", "positive", "negative"]) {
+				trace("culling = " + culling);
+				var triangles:Shape = new Shape();
+				triangles.graphics.beginFill(0x00FF00, 0.5);
+				triangles.graphics.drawTriangles(vertices, null, null, culling);
+				triangles.graphics.endFill();
+				dump(triangles.graphics.readGraphicsData());
+			}
+
+			trace("// drawTriangles with UVs");
+			var uvs:Shape = new Shape();
+			uvs.graphics.beginBitmapFill(new BitmapData(4, 4, false, 0x0000FF));
+			uvs.graphics.drawTriangles(Vector.<Number>([0, 0, 20, 0, 0, 40]), null, Vector.<Number>([0, 0, 1, 0, 0, 1]));
+			uvs.graphics.endFill();
+			dump(uvs.graphics.readGraphicsData());
+
+			trace("// drawGraphicsData round trip");
+			var source:Shape = new Shape();
+			source.graphics.drawGraphicsData(Vector.<IGraphicsData>([
+				new GraphicsStroke(2, false, "normal", "none", "miter", 3, new GraphicsSolidFill(0x0000FF, 0.25)),
+				new GraphicsSolidFill(0x123456),
+				new GraphicsPath(Vector.<int>([1, 2, 3, 2]), Vector.<Number>([0, 0, 10, 0, 15, 5, 10, 10, 0, 10]), GraphicsPathWinding.NON_ZERO),
+				new GraphicsEndFill(),
+				new GraphicsStroke()
+			]));
+			var data:Vector.<IGraphicsData> = source.graphics.readGraphicsData();
+			dump(data);
+			var copy:Shape = new Shape();
+			copy.graphics.drawGraphicsData(data);
+			trace("round trip matches: " + (describe(copy.graphics.readGraphicsData()) == describe(data)));
+		}
+
+		private function dump(data:Vector.<IGraphicsData>):void {
+			trace(describe(data));
+		}
+
+		private function describe(data:Vector.<IGraphicsData>):String {
+			var lines:Array = [];
+			for each (var item:IGraphicsData in data) {
+				var line:String = getQualifiedClassName(item).replace("flash.display::", "");
+				if (item is GraphicsSolidFill) {
+					var fill:GraphicsSolidFill = GraphicsSolidFill(item);
+					line += " color=" + fill.color.toString(16) + " alpha=" + fill.alpha;
+				} else if (item is GraphicsBitmapFill) {
+					var bitmapFill:GraphicsBitmapFill = GraphicsBitmapFill(item);
+					var m:Matrix = bitmapFill.matrix;
+					line += " matrix=(a=" + m.a + ", b=" + m.b + ", c=" + m.c + ", d=" + m.d + ", tx=" + m.tx + ", ty=" + m.ty + ")";
+				} else if (item is GraphicsPath) {
+					var path:GraphicsPath = GraphicsPath(item);
+					line += " commands=" + path.commands + " data=" + path.data + " winding=" + path.winding;
+				} else if (item is GraphicsStroke) {
+					var stroke:GraphicsStroke = GraphicsStroke(item);
+					line += " thickness=" + stroke.thickness;
+					if (stroke.fill is GraphicsSolidFill) {
+						line += " color=" + GraphicsSolidFill(stroke.fill).color.toString(16) + " alpha=" + GraphicsSolidFill(stroke.fill).alpha;
+					}
+				}
+				lines.push(line);
+			}
+			return lines.join("\n");
+		}
+	}
+}
@@ -0,0 +1,103 @@
+package {
+	import flash.display.MovieClip;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			var receivers:Array = [new Base(), new Derived(), new Other(), new Base(), {value: "dynamic", describe: function():String { return "dynamic describe"; }}];
+
+			// Each of these property accesses is a single call site that
+			// sees receivers of several different classes.
+			trace("///get");
+			for each (var receiver:Object in receivers) {
+				trace(receiver.value);
+			}
+
+			trace("///call");
+			for each (receiver in receivers) {
+				trace(receiver.describe());
+			}
+
+			trace("///set");
+			for each (receiver in receivers) {
+				receiver.value = "changed";
+				trace(receiver.value);
+			}
+
+			trace("///callpropvoid");
+			for each (receiver in receivers) {
+				receiver.describe();
+			}
+
+			trace("///slot after method on same vtable");
+			var d:Derived = new Derived();
+			for (var i:int = 0; i < 3; i++) {
+				d.counter = d.counter + i;
+			}
+			trace(d.counter);
+
+			trace("///dynamic property added after a miss");
+			var dyn:Dynamic = new Dynamic();
+			for (i = 0; i < 3; i++) {
+				trace(dyn.extra);
+				dyn.extra = i;
+			}
+
+			trace("///prototype change after a miss");
+			for (i = 0; i < 2; i++) {
+				trace(dyn.fromProto);
+				Dynamic.prototype.fromProto = "proto " + i;
+			}
+
+			trace("///getter and setter");
+			var g:Getter = new Getter();
+			for (i = 0; i < 3; i++) {
+				g.value = i;
+				trace(g.value);
+			}
+		}
+	}
+}
+
+class Base {
+	public var value:String = "base value";
+
+	public function describe():String {
+		trace("Base.describe called");
+		return "Base";
+	}
+}
+
+class Derived extends Base {
+	public var counter:int = 0;
+
+	public override function describe():String {
+		trace("Derived.describe called");
+		return "Derived";
+	}
+}
+
+class Other {
+	public var padding:int = 5;
+	public var value:String = "other value";
+
+	public function describe():String {
+		trace("Other.describe called");
+		return "Other";
+	}
+}
+
+dynamic class Dynamic {
+}
+
+class Getter {
+	private var _value:int;
+
+	public function get value():String {
+		return "getter " + _value;
+	}
+
+	public function set value(v:String):void {
+		trace("setter " + v);
+		_value = int(v) * 10;
+	}
+}
@@ -0,0 +1,90 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.NetStatusEvent;
+	import flash.media.Video;
+	import flash.net.NetConnection;
+	import flash.net.NetStream;
+
+	public class Test extends MovieClip {
+		private var stream:NetStream;
+		private var stops:int = 0;
+
+		public function Test() {
+			var unconnected:NetConnection = new NetConnection();
+			try {
+				new NetStream(unconnected);
+			} catch (e:ArgumentError) {
+				trace("// new NetStream(unconnected) threw " + e.errorID);
+			}
+
+			var remote:NetConnection = new NetConnection();
+			remote.addEventListener(NetStatusEvent.NET_STATUS, function(e:NetStatusEvent):void {
+				trace("remote: " + e.info.code + " (" + e.info.level + ")");
+			});
+			remote.connect("rtmp://localhost/app");
+			trace("remote.connected: " + remote.connected);
+
+			var connection:NetConnection = new NetConnection();
+			connection.addEventListener(NetStatusEvent.NET_STATUS, function(e:NetStatusEvent):void {
+				trace("connection: " + e.info.code + " (" + e.info.level + ")");
+			});
+			connection.connect(null);
+			trace("connection.connected: " + connection.connected);
+			trace("connection.uri: " + connection.uri);
+
+			var missing:NetStream = new NetStream(connection);
+			missing.addEventListener(NetStatusEvent.NET_STATUS, function(e:NetStatusEvent):void {
+				trace("missing: " + e.info.code + " (" + e.info.level + ")");
+			});
+			missing.play("missing.flv");
+
+			stream = new NetStream(connection);
+			trace("stream.client == stream: " + (stream.client == stream));
+			trace("stream.bufferTime: " + stream.bufferTime);
+			stream.client = {
+				onMetaData: function(info:Object):void {
+					trace("onMetaData: duration=" + info.duration + " width=" + info.width + " height=" + info.height + " title=" + info.title);
+					trace("onMetaData: keyframes.times=" + info.keyframes.times);
+				},
+				onCuePoint: function(info:Object):void {
+					trace("onCuePoint: " + info.name + " at " + info.time);
+				}
+			};
+			stream.addEventListener(NetStatusEvent.NET_STATUS, onStatus);
+
+			var video:Video = new Video(64, 48);
+			trace("video.width: " + video.width + ", video.height: " + video.height);
+			video.attachNetStream(stream);
+			addChild(video);
+
+			stream.play("intro.flv");
+		}
+
+		private function onStatus(e:NetStatusEvent):void {
+			trace("stream: " + e.info.code + " (" + e.info.level + ")");
+
+			switch (e.info.code) {
+				case "NetStream.Play.Start":
+					trace("bytesLoaded == bytesTotal: " + (stream.bytesLoaded == stream.bytesTotal));
+					trace("bytesTotal: " + stream.bytesTotal);
+					trace("time: " + stream.time);
+					break;
+				case "NetStream.Play.Stop":
+					stops++;
+					trace("time >= 0.5: " + (stream.time >= 0.5));
+					if (stops == 1) {
+						stream.seek(0.35);
+					} else {
+						stream.pause();
+						stream.togglePause();
+						stream.close();
+						trace("time after close: " + stream.time);
+					}
+					break;
+				case "NetStream.Seek.Notify":
+					trace("time after seek: " + stream.time);
+					break;
+			}
+		}
+	}
+}
@@ -0,0 +1,77 @@
+package {
+	import flash.display.MovieClip;
+	import flash.display.Sprite;
+	import flash.geom.Rectangle;
+
+	public class Test extends MovieClip {
+		// Placed on the timeline, with a DefineScalingGrid tag.
+		public var grid:MovieClip;
+
+		public function Test() {
+			trace("// timeline clip");
+			trace("scale9Grid: " + grid.scale9Grid);
+			grid.scaleX = 3;
+			grid.scaleY = 1.5;
+			trace("width, height: " + grid.width + ", " + grid.height);
+
+			trace("// new Sprite");
+			var sprite:Sprite = createSprite();
+			trace("scale9Grid: " + sprite.scale9Grid);
+			sprite.scale9Grid = new Rectangle(20, 20, 60, 60);
+			trace("scale9Grid after set: " + sprite.scale9Grid);
+			var rect:Rectangle = sprite.scale9Grid;
+			rect.x = 5;
+			trace("scale9Grid after modifying the returned rectangle: " + sprite.scale9Grid);
+			trace("returns the same object: " + (sprite.scale9Grid == sprite.scale9Grid));
+			sprite.x = 10;
+			sprite.y = 190;
+			sprite.scaleX = 2;
+			sprite.scaleY = 2;
+			addChild(sprite);
+			trace("width, height: " + sprite.width + ", " + sprite.height);
+
+			// Corners that don't fit are shrunk, and the center disappears.
+			var small:Sprite = createSprite();
+			small.scale9Grid = new Rectangle(20, 20, 60, 60);
+			small.x = 230;
+			small.y = 190;
+			small.scaleX = 0.3;
+			small.scaleY = 0.3;
+			addChild(small);
+
+			// The same sprite without a grid, for comparison.
+			var unsliced:Sprite = createSprite();
+			unsliced.scale9Grid = new Rectangle(20, 20, 60, 60);
+			unsliced.scale9Grid = null;
+			trace("scale9Grid after setting null: " + unsliced.scale9Grid);
+			unsliced.x = 330;
+			unsliced.y = 190;
+			unsliced.scaleX = 2;
+			unsliced.scaleY = 2;
+			addChild(unsliced);
+		}
+
+		private function createSprite():Sprite {
+			var sprite:Sprite = new Sprite();
+			var colors:Array = [
+				0xFF0000, 0x808080, 0x00FF00,
+				0x808080, 0xFFFF80, 0x808080,
+				0x0000FF, 0x808080, 0xFF00FF
+			];
+			var edges:Array = [0, 20, 80, 100];
+			for (var row:int = 0; row < 3; row++) {
+				for (var column:int = 0; column < 3; column++) {
+					sprite.graphics.beginFill(colors[row * 3 + column]);
+					sprite.graphics.drawRect(
+						edges[column],
+						edges[row],
+						edges[column + 1] - edges[column],
+						edges[row + 1] - edges[row]
+					);
+					sprite.graphics.endFill();
+				}
+			}
+			return sprite;
+		}
+	}
+}
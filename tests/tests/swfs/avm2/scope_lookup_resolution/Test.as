@@ -0,0 +1,78 @@
+package {
+	import flash.display.MovieClip;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			trace("// Helper.describe()");
+			trace(Helper.describe());
+			counter = 5;
+			trace("// Helper.describe() after counter = 5");
+			trace(Helper.describe());
+
+			var shadow:Shadow = new Shadow();
+			trace("// shadow.read()");
+			trace(shadow.read());
+			trace("// shadow.readGlobal()");
+			trace(shadow.readGlobal());
+			trace("// shadow.readFunction()");
+			trace(shadow.readFunction());
+			trace("// Helper.labelFunction()()");
+			trace(Helper.labelFunction()());
+
+			var base:Base = new Base();
+			var derived:Derived = new Derived();
+			trace("// base.read()");
+			trace(base.read());
+			trace("// derived.read()");
+			trace(derived.read());
+		}
+	}
+}
+
+var counter:int = 1;
+var label:String = "global label";
+
+function globalLabel():String {
+	return label;
+}
+
+class Helper {
+	static const NAME:String = "Helper";
+
+	static function describe():String {
+		return NAME + " " + counter + " " + label + " " + Shadow.KIND;
+	}
+
+	static function labelFunction():Function {
+		return globalLabel;
+	}
+}
+
+final class Shadow {
+	static const KIND:String = "final";
+
+	var label:String = "shadow label";
+
+	function read():String {
+		return label + " " + counter + " " + Helper.NAME;
+	}
+
+	function readGlobal():String {
+		return globalLabel();
+	}
+
+	function readFunction():String {
+		var f:Function = globalLabel;
+		return f();
+	}
+}
+
+class Base {
+	function read():String {
+		return label;
+	}
+}
+
+class Derived extends Base {
+	var label:String = "derived label";
+}
@@ -0,0 +1,31 @@
+package {
+	import flash.display.Sprite;
+	import flash.net.SharedObject;
+
+	// Reads the shared object saved by the `avm1/shared_object` test.
+	public class Test extends Sprite {
+		public function Test() {
+			var obj:SharedObject = SharedObject.getLocal("RuffleTest", "/");
+
+			trace("saved: " + obj.data.saved);
+			trace("num: " + obj.data.num);
+			trace("str: " + obj.data.str);
+
+			trace("array: " + obj.data.array);
+			trace("array.length: " + obj.data.array.length);
+			trace("array.hasOwnProperty('0'): " + obj.data.array.hasOwnProperty('0'));
+			trace("array.hasOwnProperty('1'): " + obj.data.array.hasOwnProperty('1'));
+			trace("array['prop']: " + obj.data.array['prop']);
+			trace("array[-1]: " + obj.data.array[-1]);
+
+			trace("array.denseArray: " + obj.data.denseArray);
+			trace("array.textxml: " + obj.data.testxml);
+			trace("typeof(array.textxml): " + typeof(obj.data.testxml));
+			trace("array.date: " + obj.data.date.getTime());
+			trace("typeof(array.date): " + typeof(obj.data.date));
+
+			trace("o.a: " + obj.data.o.a);
+			trace("o.b: " + obj.data.o.b);
+		}
+	}
+}
@@ -0,0 +1,131 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.DataEvent;
+	import flash.events.Event;
+	import flash.events.IOErrorEvent;
+	import flash.events.ProgressEvent;
+	import flash.events.SecurityErrorEvent;
+	import flash.net.Socket;
+	import flash.net.XMLSocket;
+
+	// The test harness echoes everything sent to port 8000, and closes the
+	// connection after echoing "<close/>". Connections to any other port fail.
+	public class Test extends MovieClip {
+		private var xmlSocket:XMLSocket;
+		private var socket:Socket;
+		private var socketDataEvents:int = 0;
+
+		public function Test() {
+			trace("///Unconnected sockets");
+			var unconnected:Socket = new Socket();
+			trace("connected: " + unconnected.connected);
+			trace("timeout: " + unconnected.timeout);
+			attempt("Socket.writeUTFBytes", function():void { unconnected.writeUTFBytes("hello"); });
+			attempt("Socket.flush", function():void { unconnected.flush(); });
+			attempt("Socket.close", function():void { unconnected.close(); });
+			attempt("Socket.connect to port 0", function():void { unconnected.connect("localhost", 0); });
+			attempt("Socket.connect to port 65536", function():void { unconnected.connect("localhost", 65536); });
+
+			var unconnectedXml:XMLSocket = new XMLSocket();
+			trace("connected: " + unconnectedXml.connected);
+			trace("timeout: " + unconnectedXml.timeout);
+			attempt("XMLSocket.send", function():void { unconnectedXml.send("hello"); });
+			attempt("XMLSocket.close", function():void { unconnectedXml.close(); });
+			attempt("XMLSocket.connect to port 0", function():void { unconnectedXml.connect("localhost", 0); });
+
+			trace("///XMLSocket to a closed port");
+			xmlSocket = new XMLSocket();
+			xmlSocket.addEventListener(IOErrorEvent.IO_ERROR, onXmlFailed);
+			xmlSocket.addEventListener(SecurityErrorEvent.SECURITY_ERROR, onEvent);
+			xmlSocket.connect("localhost", 8001);
+			trace("connected after connect(): " + xmlSocket.connected);
+		}
+
+		private function attempt(name:String, fn:Function):void {
+			try {
+				fn();
+				trace(name + ": no error");
+			} catch (e:Error) {
+				trace(name + ": " + e);
+			}
+		}
+
+		private function onEvent(event:Event):void {
+			trace("event: " + event);
+		}
+
+		private function onXmlFailed(event:IOErrorEvent):void {
+			trace("event: " + event);
+			trace("connected: " + xmlSocket.connected);
+			xmlSocket.removeEventListener(IOErrorEvent.IO_ERROR, onXmlFailed);
+			xmlSocket.addEventListener(IOErrorEvent.IO_ERROR, onEvent);
+
+			trace("///XMLSocket to an open port");
+			xmlSocket.addEventListener(Event.CONNECT, onXmlConnect);
+			xmlSocket.addEventListener(DataEvent.DATA, onXmlData);
+			xmlSocket.addEventListener(Event.CLOSE, onXmlClose);
+			xmlSocket.connect("localhost", 8000);
+		}
+
+		private function onXmlConnect(event:Event):void {
+			trace("event: " + event);
+			trace("connected: " + xmlSocket.connected);
+			xmlSocket.send("hello");
+			xmlSocket.send(<message><id>1</id></message>);
+			xmlSocket.send("<close/>");
+		}
+
+		private function onXmlData(event:DataEvent):void {
+			trace("event: " + event.type + ", data: " + event.data);
+		}
+
+		private function onXmlClose(event:Event):void {
+			trace("event: " + event);
+			trace("connected: " + xmlSocket.connected);
+			attempt("XMLSocket.send after close", function():void { xmlSocket.send("hello"); });
+
+			trace("///Socket to an open port");
+			socket = new Socket();
+			socket.addEventListener(Event.CONNECT, onSocketConnect);
+			socket.addEventListener(ProgressEvent.SOCKET_DATA, onSocketData);
+			socket.addEventListener(Event.CLOSE, onSocketClose);
+			socket.addEventListener(IOErrorEvent.IO_ERROR, onEvent);
+			socket.addEventListener(SecurityErrorEvent.SECURITY_ERROR, onEvent);
+			socket.connect("localhost", 8000);
+		}
+
+		private function onSocketConnect(event:Event):void {
+			trace("event: " + event);
+			trace("connected: " + socket.connected);
+			socket.writeUTF("hello");
+			socket.writeInt(-42);
+			socket.writeByte(7);
+			trace("bytesPending: " + socket.bytesPending);
+			socket.flush();
+			trace("bytesPending after flush: " + socket.bytesPending);
+		}
+
+		private function onSocketData(event:ProgressEvent):void {
+			trace("event: " + event);
+			trace("bytesAvailable: " + socket.bytesAvailable);
+			socketDataEvents++;
+			if (socketDataEvents == 1) {
+				trace("readUTF: " + socket.readUTF());
+				trace("readInt: " + socket.readInt());
+				trace("bytesAvailable: " + socket.bytesAvailable);
+				socket.writeUTFBytes("<close/>");
+				socket.flush();
+			} else {
+				trace("readByte: " + socket.readByte());
+				trace("readUTFBytes: " + socket.readUTFBytes(socket.bytesAvailable));
+			}
+		}
+
+		private function onSocketClose(event:Event):void {
+			trace("event: " + event);
+			trace("connected: " + socket.connected);
+			attempt("Socket.close after close", function():void { socket.close(); });
+			trace("///Done");
+		}
+	}
+}
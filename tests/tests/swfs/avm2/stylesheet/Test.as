@@ -0,0 +1,80 @@
+package {
+	import flash.display.MovieClip;
+	import flash.text.StyleSheet;
+	import flash.text.TextField;
+	import flash.text.TextFormat;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			var css:StyleSheet = new StyleSheet();
+			css.parseCSS("p { font-size: 18px; color: #FF0000; }\n" +
+				"/* Headings */ .Heading { font-weight: bold; font-family: serif; }\n" +
+				"a:hover { text-decoration: underline; }\n" +
+				"h1, h2 { display: block; }");
+
+			trace("// styleNames");
+			trace(css.styleNames.sort());
+
+			trace("// getStyle");
+			trace(css.getStyle("p").fontSize);
+			trace(css.getStyle("p").color);
+			trace(css.getStyle(".HEADING").fontWeight);
+			trace(css.getStyle(".heading").fontFamily);
+			trace(css.getStyle("missing"));
+
+			trace("// getStyle returns a copy");
+			css.getStyle("p").fontSize = "30px";
+			trace(css.getStyle("p").fontSize);
+
+			trace("// setStyle");
+			css.setStyle("em", {fontStyle: "italic"});
+			css.setStyle("h2", null);
+			trace(css.styleNames.sort());
+
+			trace("// parseCSS with invalid CSS");
+			css.parseCSS("li { color: #00FF00");
+			trace(css.styleNames.sort());
+
+			trace("// transform");
+			var format:TextFormat = css.transform(css.getStyle("p")) as TextFormat;
+			trace(format.size);
+			trace(format.color);
+			trace(format.bold);
+			format = css.transform(css.getStyle(".heading")) as TextFormat;
+			trace(format.bold);
+			trace(format.font);
+
+			trace("// TextField with a style sheet");
+			var field:TextField = new TextField();
+			field.type = "input";
+			field.styleSheet = css;
+			trace(field.styleSheet == css);
+			trace(field.type);
+			field.htmlText = "<p>Hi <span class='heading'>there</span></p><h1>hidden?</h1>";
+			trace(field.text);
+			trace(field.htmlText);
+			trace(field.getTextFormat(0, 1).size);
+			trace(field.getTextFormat(0, 1).color);
+			trace(field.getTextFormat(0, 1).bold);
+			trace(field.getTextFormat(3, 4).bold);
+			trace(field.getTextFormat(3, 4).font);
+
+			trace("// setTextFormat with a style sheet");
+			try {
+				field.setTextFormat(new TextFormat());
+			} catch (e:Error) {
+				trace(e.message);
+			}
+
+			trace("// Changing the style sheet applies to new text");
+			css.setStyle("p", {fontSize: "24"});
+			field.htmlText = "<p>Hi</p>";
+			trace(field.getTextFormat(0, 1).size);
+
+			trace("// Removing the style sheet");
+			field.styleSheet = null;
+			trace(field.styleSheet);
+			trace(field.type);
+		}
+	}
+}
@@ -0,0 +1,14 @@
+package {
+	import flash.display.MovieClip;
+	import flash.system.Capabilities;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			trace("version: " + Capabilities.version);
+			trace("os: " + Capabilities.os);
+			trace("playerType: " + Capabilities.playerType);
+			trace("manufacturer: " + Capabilities.manufacturer);
+			trace("serverString: " + Capabilities.serverString);
+		}
+	}
+}
@@ -0,0 +1,60 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.Event;
+	import flash.events.UncaughtErrorEvent;
+	import flash.utils.setTimeout;
+
+	public class Test extends MovieClip {
+		private var frames:int = 0;
+
+		public function Test() {
+			loaderInfo.uncaughtErrorEvents.addEventListener(UncaughtErrorEvent.UNCAUGHT_ERROR, onUncaughtError);
+
+			addEventListener("custom", function(e:Event):void {
+				throw new Error("Thrown from an event handler");
+			});
+			addEventListener("custom", function(e:Event):void {
+				trace("Second handler still runs");
+			});
+			trace("dispatchEvent returned " + dispatchEvent(new Event("custom")));
+
+			addEventListener("primitive", function(e:Event):void {
+				throw "A thrown string";
+			});
+			dispatchEvent(new Event("primitive"));
+
+			addEventListener("rethrow", function(e:Event):void {
+				throw new ArgumentError("Rethrown by listener");
+			});
+			dispatchEvent(new Event("rethrow"));
+
+			addEventListener(Event.ENTER_FRAME, onEnterFrame);
+			addFrameScript(0, function():void {
+				trace("Frame script running");
+				throw new RangeError("Thrown from a frame script");
+			});
+			setTimeout(function():void {
+				throw new TypeError("Thrown from a timer callback");
+			}, 1);
+		}
+
+		private function onEnterFrame(e:Event):void {
+			frames++;
+			if (frames == 2) {
+				removeEventListener(Event.ENTER_FRAME, onEnterFrame);
+				throw new Error("Thrown from enterFrame");
+			}
+		}
+
+		private function onUncaughtError(e:UncaughtErrorEvent):void {
+			trace("uncaughtError: " + e.error);
+			trace("  type: " + e.type + ", bubbles: " + e.bubbles + ", cancelable: " + e.cancelable);
+			trace("  error is Error: " + (e.error is Error));
+			trace("  target is UncaughtErrorEvents: " + (e.target == loaderInfo.uncaughtErrorEvents));
+			e.preventDefault();
+			if (e.error is ArgumentError) {
+				throw new Error("Thrown from the uncaughtError listener");
+			}
+		}
+	}
+}
@@ -0,0 +1,86 @@
+package {
+	import flash.display.MovieClip;
+
+	// The method bodies of `BadCode` are patched after compilation, so that
+	// each of them fails verification in a different way:
+	//
+	//  * `underflow` returns without pushing anything
+	//  * `fallOff` runs past the end of the method body
+	//  * `badBranch` jumps past the end of the method body
+	//  * `scopeUnderflow` pops more scopes than it pushed
+	//  * `badRegister` reads register 5 of a method with one register
+	//  * `illegalOpcode` contains the undefined opcode 0xFF
+	public class Test extends MovieClip {
+		public function Test() {
+			run("underflow", BadCode.underflow);
+			run("fallOff", BadCode.fallOff);
+			run("badBranch", BadCode.badBranch);
+			run("scopeUnderflow", BadCode.scopeUnderflow);
+			run("badRegister", BadCode.badRegister);
+			run("illegalOpcode", BadCode.illegalOpcode, false);
+
+			// Failing methods keep failing; nothing is cached for them.
+			run("underflow (again)", BadCode.underflow);
+
+			trace("///verified code still runs");
+			trace(BadCode.valid(5));
+			trace(BadCode.valid(-3));
+		}
+
+		private function run(name:String, fn:Function, printMessage:Boolean = true):void {
+			trace("///" + name);
+			try {
+				trace(fn());
+			} catch (e:VerifyError) {
+				trace("VerifyError " + e.errorID);
+				if (printMessage) {
+					trace(e.message);
+				}
+			} catch (e:Error) {
+				trace("Unexpected error: " + e);
+			}
+		}
+	}
+}
+
+class BadCode {
+	public static function underflow():* {
+		return 12345;
+	}
+
+	public static function fallOff():* {
+		return 23456;
+	}
+
+	public static function badBranch():* {
+		return 30000;
+	}
+
+	public static function scopeUnderflow():* {
+		return 20000;
+	}
+
+	public static function badRegister():* {
+		return 25000;
+	}
+
+	public static function illegalOpcode():* {
+		return 14321;
+	}
+
+	public static function valid(x:int):String {
+		var total:Number = 0;
+		for (var i:int = 0; i < 10; i++) {
+			total += i * x;
+		}
+
+		switch ((int(total) + x) % 3) {
+			case 0:
+				return "zero " + total;
+			case 1:
+				return "one " + total;
+			default:
+				return "two " + total;
+		}
+	}
+}
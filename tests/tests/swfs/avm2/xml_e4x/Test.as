@@ -0,0 +1,56 @@
+package {
+	import flash.display.Sprite;
+
+	public class Test extends Sprite {
+		public function Test() {
+			trace("// parsing");
+			var xml:XML = <root a="1"><item id="1">one</item><item id="2">two</item><other><item id="3">three</item></other></root>;
+			trace(xml.name());
+			trace(xml.@a);
+			trace(xml.item.length());
+			trace(xml.item[1]);
+			trace(XML("<parsed><child/></parsed>").child.length());
+
+			trace("// descendants");
+			trace(xml..item.length());
+			trace(xml..@id);
+
+			trace("// filter predicates");
+			trace(xml..item.(@id == "2"));
+			trace(xml..item.(@id != "1").length());
+
+			trace("// toXMLString");
+			trace(xml.other.toXMLString());
+			trace(xml.item[0].toXMLString());
+
+			trace("// appendChild");
+			xml.other.appendChild(<item id="4">four</item>);
+			trace(xml..item.length());
+			trace(xml.other.toXMLString());
+
+			trace("// namespaces");
+			var ns:Namespace = new Namespace("http://example.com/ns");
+			var nsXml:XML = <root xmlns:ex="http://example.com/ns"><ex:item>qualified</ex:item><item>unqualified</item></root>;
+			trace(nsXml.ns::item);
+			trace(nsXml.item);
+			trace(nsXml.ns::item.name().uri);
+
+			trace("// default xml namespace");
+			var dxnsXml:XML = <root xmlns="http://example.com/default"><item>default</item></root>;
+			trace(dxnsXml.item.length());
+			useDefaultNamespace(dxnsXml);
+			trace(dxnsXml.item.length());
+		}
+
+		private function useDefaultNamespace(xml:XML):void {
+			default xml namespace = "http://example.com/default";
+			trace(xml.item);
+			trace(xml.child("item"));
+			trace(readItems(xml));
+		}
+
+		private function readItems(xml:XML):int {
+			return xml.item.length();
+		}
+	}
+}
@@ -5,7 +5,10 @@ use ruffle_core::backend::navigator::{
 };
 use ruffle_core::indexmap::IndexMap;
 use ruffle_core::loader::Error;
+use ruffle_core::socket::{ConnectionState, SocketAction, SocketHandle};
 use std::borrow::Cow;
+use std::sync::mpsc::{Receiver, Sender};
+use std::time::Duration;
 use url::Url;
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::{spawn_local, JsFuture};
@@ -230,4 +233,22 @@ impl NavigatorBackend for WebNavigatorBackend {
         }
         url
     }
+
+    fn connect_socket(
+        &mut self,
+        host: String,
+        port: u16,
+        _timeout: Duration,
+        handle: SocketHandle,
+        _receiver: Receiver<Vec<u8>>,
+        sender: Sender<SocketAction>,
+    ) {
+        // Browsers can't open raw TCP connections.
+        log::warn!(
+            "Sockets are not supported on web: cannot connect to {}:{}",
+            host,
+            port
+        );
+        let _ = sender.send(SocketAction::Connect(handle, ConnectionState::Failed));
+    }
 }
@@ -61,6 +61,13 @@ impl UiBackend for WebUiBackend {
         log::warn!("set clipboard not implemented");
     }
 
+    fn clipboard_content(&mut self) -> String {
+        // TODO: The browser only allows reading the clipboard asynchronously,
+        // from within a paste event.
+        log::warn!("get clipboard not implemented");
+        String::new()
+    }
+
     fn set_fullscreen(&mut self, is_full: bool) -> Result<(), FullscreenError> {
         match self.js_player.set_fullscreen(is_full) {
             Ok(_) => Ok(()),